@@ -27,9 +27,9 @@ use arrow::{downcast_dictionary_array, downcast_primitive_array};
 use arrow_buffer::i256;
 
 use crate::cast::{
-    as_boolean_array, as_fixed_size_list_array, as_generic_binary_array,
-    as_large_list_array, as_list_array, as_primitive_array, as_string_array,
-    as_struct_array,
+    as_binary_view_array, as_boolean_array, as_fixed_size_list_array,
+    as_generic_binary_array, as_large_list_array, as_list_array, as_primitive_array,
+    as_string_array, as_string_view_array, as_struct_array,
 };
 use crate::error::{Result, _internal_err};
 
@@ -371,6 +371,9 @@ pub fn create_hashes<'a>(
             DataType::LargeUtf8 => hash_array(as_largestring_array(array), random_state, hashes_buffer, rehash),
             DataType::Binary => hash_array(as_generic_binary_array::<i32>(array)?, random_state, hashes_buffer, rehash),
             DataType::LargeBinary => hash_array(as_generic_binary_array::<i64>(array)?, random_state, hashes_buffer, rehash),
+            // Hashes the values the views point to in place, without gathering them
+            DataType::Utf8View => hash_array(as_string_view_array(array)?, random_state, hashes_buffer, rehash),
+            DataType::BinaryView => hash_array(as_binary_view_array(array)?, random_state, hashes_buffer, rehash),
             DataType::FixedSizeBinary(_) => {
                 let array: &FixedSizeBinaryArray = array.as_any().downcast_ref().unwrap();
                 hash_array(array, random_state, hashes_buffer, rehash)
@@ -561,6 +564,44 @@ mod tests {
         assert_ne!(dict_hashes[0], dict_hashes[2]);
     }
 
+    #[test]
+    fn create_hashes_for_view_arrays() -> Result<()> {
+        // Inlined as well as out-of-line values
+        let strings = [
+            Some("foo"),
+            None,
+            Some("a string longer than twelve bytes"),
+            Some("foo"),
+            Some("a string longer than twelve bytes, too"),
+        ];
+        let string_array = Arc::new(strings.iter().cloned().collect::<StringArray>());
+        let view_array = Arc::new(strings.iter().cloned().collect::<StringViewArray>());
+        let binary_array = Arc::new(
+            strings
+                .iter()
+                .map(|s| s.map(str::as_bytes))
+                .collect::<BinaryArray>(),
+        );
+        let binary_view_array = Arc::new(
+            strings
+                .iter()
+                .map(|s| s.map(str::as_bytes))
+                .collect::<BinaryViewArray>(),
+        );
+
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+        let hashes = |array: ArrayRef| {
+            let mut hashes = vec![0; strings.len()];
+            create_hashes(&[array], &random_state, &mut hashes).map(|h| h.clone())
+        };
+
+        // Views hash like the values they point to
+        assert_eq!(hashes(string_array)?, hashes(view_array)?);
+        assert_eq!(hashes(binary_array)?, hashes(binary_view_array)?);
+
+        Ok(())
+    }
+
     #[test]
     // Tests actual values of hashes, which are different if forcing collisions
     #[cfg(not(feature = "force_hash_collisions"))]
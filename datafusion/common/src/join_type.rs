@@ -44,6 +44,16 @@ pub enum JoinType {
     LeftAnti,
     /// Right Anti Join
     RightAnti,
+    /// Left Mark Join: every left row, with an additional boolean `mark`
+    /// column telling whether it has a match in the right side. The mark
+    /// follows the three-valued logic of `IN`: unless nulls are compared equal,
+    /// it is null for an unmatched row if its join key is null, or if the
+    /// right side has a null join key, provided the right side is not empty.
+    LeftMark,
+    /// Right Mark Join: every right row, with an additional boolean `mark`
+    /// column telling whether it has a match in the left side, see
+    /// [`JoinType::LeftMark`]
+    RightMark,
 }
 
 impl JoinType {
@@ -63,6 +73,8 @@ impl Display for JoinType {
             JoinType::RightSemi => "RightSemi",
             JoinType::LeftAnti => "LeftAnti",
             JoinType::RightAnti => "RightAnti",
+            JoinType::LeftMark => "LeftMark",
+            JoinType::RightMark => "RightMark",
         };
         write!(f, "{join_type}")
     }
@@ -82,6 +94,8 @@ impl FromStr for JoinType {
             "RIGHTSEMI" => Ok(JoinType::RightSemi),
             "LEFTANTI" => Ok(JoinType::LeftAnti),
             "RIGHTANTI" => Ok(JoinType::RightAnti),
+            "LEFTMARK" => Ok(JoinType::LeftMark),
+            "RIGHTMARK" => Ok(JoinType::RightMark),
             _ => _not_impl_err!("The join type {s} does not exist or is not implemented"),
         }
     }
@@ -2948,6 +2948,9 @@ mod tests {
                         out_partitioning,
                     &Partitioning::UnknownPartitioning(partition_count) if partition_count == default_partition_count));
                 }
+                JoinType::LeftMark | JoinType::RightMark => {
+                    unreachable!("not in all_join_types")
+                }
             }
         }
 
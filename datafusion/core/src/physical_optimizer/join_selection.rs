@@ -117,6 +117,8 @@ fn supports_swap(join_type: JoinType) -> bool {
             | JoinType::RightSemi
             | JoinType::LeftAnti
             | JoinType::RightAnti
+            | JoinType::LeftMark
+            | JoinType::RightMark
     )
 }
 
@@ -132,6 +134,8 @@ fn swap_join_type(join_type: JoinType) -> JoinType {
         JoinType::RightSemi => JoinType::LeftSemi,
         JoinType::LeftAnti => JoinType::RightAnti,
         JoinType::RightAnti => JoinType::LeftAnti,
+        JoinType::LeftMark => JoinType::RightMark,
+        JoinType::RightMark => JoinType::LeftMark,
     }
 }
 
@@ -156,6 +160,15 @@ fn swap_join_projection(
     })
 }
 
+/// Returns whether the given join is a [`JoinType::LeftMark`] or
+/// [`JoinType::RightMark`] join.
+fn is_mark_join(hash_join: &HashJoinExec) -> bool {
+    matches!(
+        hash_join.join_type(),
+        JoinType::LeftMark | JoinType::RightMark
+    )
+}
+
 /// This function swaps the inputs of the given join operator.
 fn swap_hash_join(
     hash_join: &HashJoinExec,
@@ -163,6 +176,17 @@ fn swap_hash_join(
 ) -> Result<Arc<dyn ExecutionPlan>> {
     let left = hash_join.left();
     let right = hash_join.right();
+    // The output of a swapped mark join has the same columns, the marked side
+    // followed by the mark column
+    let projection = if is_mark_join(hash_join) {
+        hash_join.projection.clone()
+    } else {
+        swap_join_projection(
+            left.schema().fields().len(),
+            right.schema().fields().len(),
+            hash_join.projection.as_ref(),
+        )
+    };
     let new_join = HashJoinExec::try_new(
         Arc::clone(right),
         Arc::clone(left),
@@ -173,11 +197,7 @@ fn swap_hash_join(
             .collect(),
         swap_join_filter(hash_join.filter()),
         &swap_join_type(*hash_join.join_type()),
-        swap_join_projection(
-            left.schema().fields().len(),
-            right.schema().fields().len(),
-            hash_join.projection.as_ref(),
-        ),
+        projection,
         partition_mode,
         hash_join.null_equals_null(),
     )?;
@@ -187,6 +207,8 @@ fn swap_hash_join(
             | JoinType::RightSemi
             | JoinType::LeftAnti
             | JoinType::RightAnti
+            | JoinType::LeftMark
+            | JoinType::RightMark
     ) {
         Ok(Arc::new(new_join))
     } else {
@@ -326,7 +348,9 @@ fn try_collect_left(
         | JoinType::LeftSemi
         | JoinType::Right
         | JoinType::RightSemi
-        | JoinType::RightAnti => {
+        | JoinType::RightAnti
+        | JoinType::LeftMark
+        | JoinType::RightMark => {
             ignore_threshold
                 || supports_collect_by_thresholds(
                     &**left,
@@ -341,7 +365,9 @@ fn try_collect_left(
         | JoinType::RightSemi
         | JoinType::Left
         | JoinType::LeftSemi
-        | JoinType::LeftAnti => {
+        | JoinType::LeftAnti
+        | JoinType::LeftMark
+        | JoinType::RightMark => {
             ignore_threshold
                 || supports_collect_by_thresholds(
                     &**right,
@@ -420,9 +446,11 @@ fn statistical_join_selection_subrule(
     let transformed =
         if let Some(hash_join) = plan.as_any().downcast_ref::<HashJoinExec>() {
             match hash_join.partition_mode() {
+                // Unless nulls are equal, the marks of a mark join depend on
+                // the whole build side
                 PartitionMode::Auto => try_collect_left(
                     hash_join,
-                    false,
+                    is_mark_join(hash_join) && !hash_join.null_equals_null(),
                     collect_threshold_byte_size,
                     collect_threshold_num_rows,
                 )?
@@ -499,8 +527,9 @@ fn hash_join_convert_symmetric_subrule(
     if let Some(hash_join) = input.as_any().downcast_ref::<HashJoinExec>() {
         let left_unbounded = hash_join.left.execution_mode().is_unbounded();
         let right_unbounded = hash_join.right.execution_mode().is_unbounded();
-        // Process only if both left and right sides are unbounded.
-        if left_unbounded && right_unbounded {
+        // Process only if both left and right sides are unbounded. Symmetric
+        // hash joins do not support mark joins.
+        if left_unbounded && right_unbounded && !is_mark_join(hash_join) {
             // Determine the partition mode based on configuration.
             let mode = if config_options.optimizer.repartition_joins {
                 StreamJoinPartitionMode::Partitioned
@@ -635,6 +664,7 @@ fn hash_join_swap_subrule(
                     | JoinType::Left
                     | JoinType::LeftSemi
                     | JoinType::LeftAnti
+                    | JoinType::LeftMark
             )
         {
             input = swap_join_according_to_unboundedness(hash_join)?;
@@ -645,9 +675,10 @@ fn hash_join_swap_subrule(
 
 /// This function swaps sides of a hash join to make it runnable even if one of
 /// its inputs are infinite. Note that this is not always possible; i.e.
-/// [`JoinType::Full`], [`JoinType::Right`], [`JoinType::RightAnti`] and
-/// [`JoinType::RightSemi`] can not run with an unbounded left side, even if
-/// we swap join sides. Therefore, we do not consider them here.
+/// [`JoinType::Full`], [`JoinType::Right`], [`JoinType::RightAnti`],
+/// [`JoinType::RightSemi`] and [`JoinType::RightMark`] can not run with an
+/// unbounded left side, even if we swap join sides. Therefore, we do not
+/// consider them here.
 fn swap_join_according_to_unboundedness(
     hash_join: &HashJoinExec,
 ) -> Result<Arc<dyn ExecutionPlan>> {
@@ -656,7 +687,11 @@ fn swap_join_according_to_unboundedness(
     match (*partition_mode, *join_type) {
         (
             _,
-            JoinType::Right | JoinType::RightSemi | JoinType::RightAnti | JoinType::Full,
+            JoinType::Right
+            | JoinType::RightSemi
+            | JoinType::RightAnti
+            | JoinType::RightMark
+            | JoinType::Full,
         ) => internal_err!("{join_type} join cannot be swapped for unbounded input."),
         (PartitionMode::Partitioned, _) => {
             swap_hash_join(hash_join, PartitionMode::Partitioned)
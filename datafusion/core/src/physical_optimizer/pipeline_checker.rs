@@ -71,7 +71,9 @@ pub fn check_finiteness_requirements(
 ) -> Result<Transformed<Arc<dyn ExecutionPlan>>> {
     if let Some(exec) = input.as_any().downcast_ref::<SymmetricHashJoinExec>() {
         if !(optimizer_options.allow_symmetric_joins_without_pruning
-            || (exec.check_if_order_information_available()? && is_prunable(exec)))
+            || (exec.check_if_order_information_available()?
+                && is_prunable(exec)
+                && exec.check_if_order_directions_allow_pruning()?))
         {
             return plan_err!("Join operation cannot operate on a non-prunable stream without enabling \
                               the 'allow_symmetric_joins_without_pruning' configuration flag");
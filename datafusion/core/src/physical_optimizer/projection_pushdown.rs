@@ -438,6 +438,13 @@ fn try_swapping_with_repartition(
             }
             Partitioning::Hash(new_partitions, *size)
         }
+        Partitioning::Bucketed(partition, size) => {
+            let Some(new_partition) = update_expr(partition, projection.expr(), false)?
+            else {
+                return Ok(None);
+            };
+            Partitioning::Bucketed(new_partition, *size)
+        }
         others => others.clone(),
     };
 
@@ -537,6 +544,15 @@ fn try_embed_to_hash_join(
     projection: &ProjectionExec,
     hash_join: &HashJoinExec,
 ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    // The row provenance columns, the unmatched row marker and the match flag
+    // can not be projected away by the hash join.
+    if hash_join.row_provenance()
+        || hash_join.unmatched_marker()
+        || hash_join.match_flag()
+    {
+        return Ok(None);
+    }
+
     // Collect all column indices from the given projection expressions.
     let projection_index = collect_column_indices(projection.expr());
 
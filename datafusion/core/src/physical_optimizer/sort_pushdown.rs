@@ -326,6 +326,8 @@ fn expr_source_side(
             .iter()
             .all(|e| e.expr.as_any().downcast_ref::<Column>().is_some())
             .then_some(JoinSide::Right),
+        // The mark column is not taken from either side
+        JoinType::LeftMark | JoinType::RightMark => None,
     }
 }
 
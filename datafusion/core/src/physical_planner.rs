@@ -39,8 +39,8 @@ use crate::logical_expr::{
     Window,
 };
 use crate::logical_expr::{
-    CrossJoin, Expr, LogicalPlan, Partitioning as LogicalPartitioning, PlanType,
-    Repartition, Union, UserDefinedLogicalNode,
+    CrossJoin, Expr, JoinType, LogicalPlan, Partitioning as LogicalPartitioning,
+    PlanType, Repartition, Union, UserDefinedLogicalNode,
 };
 use crate::logical_expr::{Limit, Values};
 use crate::physical_expr::{create_physical_expr, create_physical_exprs};
@@ -1069,6 +1069,25 @@ impl DefaultPhysicalPlanner {
                     };
 
                     let prefer_hash_join = session_state.config_options().optimizer.prefer_hash_join;
+                    // Only hash joins support mark joins
+                    let mark_join = matches!(join_type, JoinType::LeftMark | JoinType::RightMark);
+
+                    // Inner joins already have their null keys filtered by the
+                    // `FilterNullJoinKeys` logical optimizer rule
+                    let (physical_left, physical_right) = if session_state.config_options().optimizer.filter_null_join_keys
+                        && !join_on.is_empty()
+                        && *join_type != JoinType::Inner
+                    {
+                        join_utils::filter_null_join_keys(
+                            physical_left,
+                            physical_right,
+                            &join_on,
+                            join_type,
+                            null_equals_null,
+                        )?
+                    } else {
+                        (physical_left, physical_right)
+                    };
 
                     if join_on.is_empty() {
                         // there is no equal join condition, use the nested loop join
@@ -1082,6 +1101,7 @@ impl DefaultPhysicalPlanner {
                     } else if session_state.config().target_partitions() > 1
                         && session_state.config().repartition_joins()
                         && !prefer_hash_join
+                        && !mark_join
                     {
                         // Use SortMergeJoin if hash join is not preferred
                         // Sort-Merge join support currently is experimental
@@ -1098,9 +1118,13 @@ impl DefaultPhysicalPlanner {
                         )?))
                     } else if session_state.config().target_partitions() > 1
                         && session_state.config().repartition_joins()
-                        && prefer_hash_join {
+                        && (prefer_hash_join || mark_join) {
                          let partition_mode = {
-                            if session_state.config().collect_statistics() {
+                            // Unless nulls are equal, the marks depend on the
+                            // whole build side
+                            if mark_join && !null_equals_null {
+                                PartitionMode::CollectLeft
+                            } else if session_state.config().collect_statistics() {
                                 PartitionMode::Auto
                             } else {
                                 PartitionMode::Partitioned
@@ -2048,6 +2072,33 @@ mod tests {
             .await
     }
 
+    #[tokio::test]
+    async fn filter_null_join_keys_of_non_preserved_side() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        let right = scan_empty(Some("r"), &schema, None)?.build()?;
+        let logical_plan = scan_empty(Some("l"), &schema, None)?
+            .join(right, JoinType::Left, (vec!["a"], vec!["a"]), None)?
+            .build()?;
+
+        let config = SessionConfig::new()
+            .with_target_partitions(1)
+            .set_bool("datafusion.optimizer.filter_null_join_keys", true);
+        let session_state =
+            SessionState::new_with_config_rt(config, Arc::new(RuntimeEnv::default()));
+        let plan = DefaultPhysicalPlanner::default()
+            .create_physical_plan(&logical_plan, &session_state)
+            .await?;
+
+        let join = plan.as_any().downcast_ref::<HashJoinExec>().unwrap();
+        assert_eq!(join.left().name(), "EmptyExec");
+        let filter = join.right().as_any().downcast_ref::<FilterExec>().unwrap();
+        assert_eq!(filter.predicate().to_string(), "a@0 IS NOT NULL");
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_all_operators() -> Result<()> {
         let logical_plan = test_csv_scan()
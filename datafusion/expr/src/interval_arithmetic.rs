@@ -43,6 +43,8 @@ macro_rules! get_extreme_value {
             DataType::Int64 => ScalarValue::Int64(Some(i64::$extreme)),
             DataType::Float32 => ScalarValue::Float32(Some(f32::$extreme)),
             DataType::Float64 => ScalarValue::Float64(Some(f64::$extreme)),
+            DataType::Date32 => ScalarValue::Date32(Some(i32::$extreme)),
+            DataType::Date64 => ScalarValue::Date64(Some(i64::$extreme)),
             DataType::Duration(TimeUnit::Second) => {
                 ScalarValue::DurationSecond(Some(i64::$extreme))
             }
@@ -94,6 +96,18 @@ macro_rules! value_transition {
             Int64(Some(value)) if value == i64::$bound => Int64(None),
             Float32(Some(value)) if value == f32::$bound => Float32(None),
             Float64(Some(value)) if value == f64::$bound => Float64(None),
+            Date32(Some(value)) if value == i32::$bound => Date32(None),
+            Date64(Some(value)) if value == i64::$bound => Date64(None),
+            Time32Second(Some(value)) if value == i32::$bound => Time32Second(None),
+            Time32Millisecond(Some(value)) if value == i32::$bound => {
+                Time32Millisecond(None)
+            }
+            Time64Microsecond(Some(value)) if value == i64::$bound => {
+                Time64Microsecond(None)
+            }
+            Time64Nanosecond(Some(value)) if value == i64::$bound => {
+                Time64Nanosecond(None)
+            }
             DurationSecond(Some(value)) if value == i64::$bound => DurationSecond(None),
             DurationMillisecond(Some(value)) if value == i64::$bound => {
                 DurationMillisecond(None)
@@ -992,6 +1006,20 @@ fn next_value_helper<const INC: bool>(value: ScalarValue) -> ScalarValue {
         UInt16(Some(val)) => UInt16(Some(increment_decrement::<INC, u16>(val))),
         UInt32(Some(val)) => UInt32(Some(increment_decrement::<INC, u32>(val))),
         UInt64(Some(val)) => UInt64(Some(increment_decrement::<INC, u64>(val))),
+        Date32(Some(val)) => Date32(Some(increment_decrement::<INC, i32>(val))),
+        Date64(Some(val)) => Date64(Some(increment_decrement::<INC, i64>(val))),
+        Time32Second(Some(val)) => {
+            Time32Second(Some(increment_decrement::<INC, i32>(val)))
+        }
+        Time32Millisecond(Some(val)) => {
+            Time32Millisecond(Some(increment_decrement::<INC, i32>(val)))
+        }
+        Time64Microsecond(Some(val)) => {
+            Time64Microsecond(Some(increment_decrement::<INC, i64>(val)))
+        }
+        Time64Nanosecond(Some(val)) => {
+            Time64Nanosecond(Some(increment_decrement::<INC, i64>(val)))
+        }
         DurationSecond(Some(val)) => {
             DurationSecond(Some(increment_decrement::<INC, i64>(val)))
         }
@@ -1107,9 +1135,19 @@ pub fn satisfy_greater(
 
     // Only the lower bound of left hand side and the upper bound of the right
     // hand side can change after propagating the greater-than operation.
+    // Strict comparisons exclude the bound itself, which is expressed in closed
+    // intervals by moving to the next (or previous) value of the type. There
+    // is no such value beyond the limits of integer and temporal types, in
+    // which case the comparison is infeasible (rather than unbounded). Floats
+    // are unbounded beyond their limits, as infinities lie there:
+    let bounded = !left.data_type().is_floating();
     let new_left_lower = if left.lower.is_null() || left.lower <= right.lower {
         if strict {
-            next_value(right.lower.clone())
+            let lower = next_value(right.lower.clone());
+            if bounded && lower.is_null() && !right.lower.is_null() {
+                return Ok(None);
+            }
+            lower
         } else {
             right.lower.clone()
         }
@@ -1122,7 +1160,11 @@ pub fn satisfy_greater(
         || (!left.upper.is_null() && left.upper <= right.upper)
     {
         if strict {
-            prev_value(left.upper.clone())
+            let upper = prev_value(left.upper.clone());
+            if bounded && upper.is_null() && !left.upper.is_null() {
+                return Ok(None);
+            }
+            upper
         } else {
             left.upper.clone()
         }
@@ -3186,6 +3228,22 @@ mod tests {
                 Interval::make(Some(-500.0_f64), Some(1000.0_f64))?,
                 Interval::make(Some(-500.0_f64), Some(500.0_f64))?,
             ),
+            // Infinities are greater than the maximum of a float type, or less
+            // than its minimum:
+            (
+                Interval::make::<f64>(None, None)?,
+                Interval::make(Some(f64::MAX), None)?,
+                false,
+                Interval::make::<f64>(None, None)?,
+                Interval::make(Some(f64::MAX), None)?,
+            ),
+            (
+                Interval::make(None, Some(f32::MIN))?,
+                Interval::make::<f32>(None, None)?,
+                false,
+                Interval::make(None, Some(f32::MIN))?,
+                Interval::make::<f32>(None, None)?,
+            ),
         ];
         for (first, second, includes_endpoints, left_modified, right_modified) in cases {
             assert_eq!(
@@ -3200,6 +3258,18 @@ mod tests {
                 Interval::make(Some(1000_i64), None)?,
                 false,
             ),
+            // Nothing is greater than the maximum of a type, or less than its
+            // minimum:
+            (
+                Interval::make(None::<i32>, None)?,
+                Interval::make(Some(i32::MAX), None)?,
+                false,
+            ),
+            (
+                Interval::make(None, Some(u8::MIN))?,
+                Interval::make(None::<u8>, None)?,
+                false,
+            ),
             (
                 Interval::make(Some(-1000.0_f32), Some(1000.0_f32))?,
                 Interval::make(Some(1500.0_f32), Some(2000.0_f32))?,
@@ -3213,6 +3283,63 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_satisfy_comparison_temporal() -> Result<()> {
+        // Strict comparisons exclude the bound by one unit of the type, while
+        // non-strict comparisons include it:
+        let cases = vec![
+            (
+                ScalarValue::TimestampMillisecond(Some(1_000), None),
+                ScalarValue::TimestampMillisecond(Some(1_001), None),
+                ScalarValue::TimestampMillisecond(None, None),
+            ),
+            (
+                ScalarValue::Date32(Some(100)),
+                ScalarValue::Date32(Some(101)),
+                ScalarValue::Date32(None),
+            ),
+            (
+                ScalarValue::Time32Second(Some(60)),
+                ScalarValue::Time32Second(Some(61)),
+                ScalarValue::Time32Second(None),
+            ),
+            (
+                ScalarValue::Time32Millisecond(Some(60)),
+                ScalarValue::Time32Millisecond(Some(61)),
+                ScalarValue::Time32Millisecond(None),
+            ),
+            (
+                ScalarValue::Time64Microsecond(Some(60)),
+                ScalarValue::Time64Microsecond(Some(61)),
+                ScalarValue::Time64Microsecond(None),
+            ),
+            (
+                ScalarValue::Time64Nanosecond(Some(60)),
+                ScalarValue::Time64Nanosecond(Some(61)),
+                ScalarValue::Time64Nanosecond(None),
+            ),
+        ];
+        for (bound, next, unbounded) in cases {
+            assert_eq!(next_value(bound.clone()), next);
+            assert_eq!(prev_value(next.clone()), bound);
+            let left = Interval::try_new(unbounded.clone(), unbounded.clone())?;
+            let right = Interval::try_new(bound.clone(), unbounded.clone())?;
+            // left >= right:
+            let (left_modified, _) = satisfy_greater(&left, &right, false)?.unwrap();
+            assert_eq!(left_modified.lower(), &bound);
+            // left > right:
+            let (left_modified, _) = satisfy_greater(&left, &right, true)?.unwrap();
+            assert_eq!(left_modified.lower(), &next);
+            // right < left, i.e. the upper bound of the right side moves down:
+            let left = Interval::try_new(unbounded.clone(), next.clone())?;
+            let right = Interval::try_new(unbounded.clone(), unbounded)?;
+            let (_, right_modified) = satisfy_greater(&left, &right, true)?.unwrap();
+            assert_eq!(right_modified.upper(), &bound);
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_interval_display() {
         let interval = Interval::make(Some(0.25_f32), Some(0.50_f32)).unwrap();
@@ -1212,6 +1212,20 @@ pub fn build_join_schema(
             // Only use the right side for the schema
             right_fields.map(|(q, f)| (q.cloned(), f.clone())).collect()
         }
+        JoinType::LeftMark => {
+            // The left side, followed by the mark of the right side
+            left_fields
+                .map(|(q, f)| (q.cloned(), f.clone()))
+                .chain(std::iter::once(mark_field(right)))
+                .collect()
+        }
+        JoinType::RightMark => {
+            // The right side, followed by the mark of the left side
+            right_fields
+                .map(|(q, f)| (q.cloned(), f.clone()))
+                .chain(std::iter::once(mark_field(left)))
+                .collect()
+        }
     };
     let func_dependencies = left.functional_dependencies().join(
         right.functional_dependencies(),
@@ -1224,6 +1238,28 @@ pub fn build_join_schema(
     dfschema.with_functional_dependencies(func_dependencies)
 }
 
+/// The `mark` column of a [`JoinType::LeftMark`] or [`JoinType::RightMark`]
+/// join telling whether a row has a match in `schema`, the other side of the
+/// join. It is qualified by the relation of `schema` if there is a single one,
+/// so that it does not clash with a `mark` column of the joined side, and
+/// nullable, as it follows the three-valued logic of `IN`.
+fn mark_field(schema: &DFSchema) -> (Option<TableReference>, Arc<Field>) {
+    let mut qualifiers = schema
+        .iter()
+        .filter_map(|(qualifier, _)| qualifier.cloned())
+        .collect::<Vec<_>>();
+    qualifiers.dedup();
+    let qualifier = if qualifiers.len() == 1 {
+        qualifiers.pop()
+    } else {
+        None
+    };
+    (
+        qualifier,
+        Arc::new(Field::new("mark", DataType::Boolean, true)),
+    )
+}
+
 /// Add additional "synthetic" group by expressions based on functional
 /// dependencies.
 ///
@@ -1711,6 +1747,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn plan_mark_join_schema() -> Result<()> {
+        for (join_type, marked, other) in [
+            (JoinType::LeftMark, "t1", "t2"),
+            (JoinType::RightMark, "t2", "t1"),
+        ] {
+            let t2 = test_table_scan_with_name("t2")?;
+            let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+                .join(t2, join_type, (vec!["a"], vec!["a"]), None)?
+                .project(vec![Expr::Wildcard { qualifier: None }])?
+                .build()?;
+
+            // The marked side's columns, followed by the mark qualified by
+            // the other side
+            let expected = format!(
+                "Projection: {marked}.a, {marked}.b, {marked}.c, {other}.mark\
+                \n  {join_type} Join: t1.a = t2.a\
+                \n    TableScan: t1\
+                \n    TableScan: t2"
+            );
+            assert_eq!(expected, format!("{plan:?}"));
+            let mark = plan.schema().field(3);
+            assert_eq!(mark.data_type(), &DataType::Boolean);
+            assert!(mark.is_nullable());
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn plan_builder_union() -> Result<()> {
         let plan =
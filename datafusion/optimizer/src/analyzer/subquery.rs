@@ -212,11 +212,17 @@ fn check_inner_plan(
                 })?;
                 Ok(())
             }
-            JoinType::Left | JoinType::LeftSemi | JoinType::LeftAnti => {
+            JoinType::Left
+            | JoinType::LeftSemi
+            | JoinType::LeftAnti
+            | JoinType::LeftMark => {
                 check_inner_plan(left, is_scalar, is_aggregate, can_contain_outer_ref)?;
                 check_inner_plan(right, is_scalar, is_aggregate, false)
             }
-            JoinType::Right | JoinType::RightSemi | JoinType::RightAnti => {
+            JoinType::Right
+            | JoinType::RightSemi
+            | JoinType::RightAnti
+            | JoinType::RightMark => {
                 check_inner_plan(left, is_scalar, is_aggregate, false)?;
                 check_inner_plan(right, is_scalar, is_aggregate, can_contain_outer_ref)
             }
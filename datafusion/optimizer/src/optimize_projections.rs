@@ -337,8 +337,21 @@ fn optimize_projections(
         }
         LogicalPlan::Join(join) => {
             let left_len = join.left.schema().fields().len();
+            // The mark column of mark joins, which follows the columns of the
+            // marked side, is not taken from either child:
+            let indices = match join.join_type {
+                JoinType::LeftMark | JoinType::RightMark => {
+                    let mark_idx = join.schema.fields().len() - 1;
+                    indices
+                        .iter()
+                        .copied()
+                        .filter(|&idx| idx != mark_idx)
+                        .collect::<Vec<_>>()
+                }
+                _ => indices.to_vec(),
+            };
             let (left_req_indices, right_req_indices) =
-                split_join_requirements(left_len, indices, &join.join_type);
+                split_join_requirements(left_len, &indices, &join.join_type);
             let exprs = plan.expressions();
             let left_indices =
                 get_all_required_indices(&left_req_indices, &join.left, exprs.iter())?;
@@ -826,10 +839,14 @@ fn split_join_requirements(
             (left_reqs, right_reqs)
         }
         // All requirements can be re-routed to left child directly.
-        JoinType::LeftAnti | JoinType::LeftSemi => (indices.to_vec(), vec![]),
+        JoinType::LeftAnti | JoinType::LeftSemi | JoinType::LeftMark => {
+            (indices.to_vec(), vec![])
+        }
         // All requirements can be re-routed to right side directly.
         // No need to change index, join schema is right child schema.
-        JoinType::RightSemi | JoinType::RightAnti => (vec![], indices.to_vec()),
+        JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => {
+            (vec![], indices.to_vec())
+        }
     }
 }
 
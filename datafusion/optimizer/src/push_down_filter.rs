@@ -158,11 +158,15 @@ fn lr_is_preserved(plan: &LogicalPlan) -> Result<(bool, bool)> {
             JoinType::Right => Ok((false, true)),
             JoinType::Full => Ok((false, false)),
             // No columns from the right side of the join can be referenced in output
-            // predicates for semi/anti joins, so whether we specify t/f doesn't matter.
-            JoinType::LeftSemi | JoinType::LeftAnti => Ok((true, false)),
+            // predicates for semi/anti/mark joins, so whether we specify t/f doesn't matter.
+            JoinType::LeftSemi | JoinType::LeftAnti | JoinType::LeftMark => {
+                Ok((true, false))
+            }
             // No columns from the left side of the join can be referenced in output
-            // predicates for semi/anti joins, so whether we specify t/f doesn't matter.
-            JoinType::RightSemi | JoinType::RightAnti => Ok((false, true)),
+            // predicates for semi/anti/mark joins, so whether we specify t/f doesn't matter.
+            JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => {
+                Ok((false, true))
+            }
         },
         LogicalPlan::CrossJoin(_) => Ok((true, true)),
         _ => internal_err!("lr_is_preserved only valid for JOIN nodes"),
@@ -182,6 +186,10 @@ fn on_lr_is_preserved(plan: &LogicalPlan) -> Result<(bool, bool)> {
             JoinType::LeftSemi | JoinType::RightSemi => Ok((true, true)),
             JoinType::LeftAnti => Ok((false, true)),
             JoinType::RightAnti => Ok((true, false)),
+            // The join filter decides the marks of the rows of the marked side,
+            // but only drops rows of the other side
+            JoinType::LeftMark => Ok((false, true)),
+            JoinType::RightMark => Ok((true, false)),
         },
         LogicalPlan::CrossJoin(_) => {
             internal_err!("on_lr_is_preserved cannot be applied to CROSSJOIN nodes")
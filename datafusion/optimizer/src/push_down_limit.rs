@@ -227,14 +227,15 @@ fn push_down_join(join: &Join, limit: usize) -> Option<Join> {
     let (left_limit, right_limit) = if is_no_join_condition(join) {
         match join.join_type {
             Left | Right | Full => (Some(limit), Some(limit)),
-            LeftAnti | LeftSemi => (Some(limit), None),
-            RightAnti | RightSemi => (None, Some(limit)),
+            LeftAnti | LeftSemi | LeftMark => (Some(limit), None),
+            RightAnti | RightSemi | RightMark => (None, Some(limit)),
             Inner => (None, None),
         }
     } else {
+        // Every row of the outer or marked side produces at least one row
         match join.join_type {
-            Left => (Some(limit), None),
-            Right => (None, Some(limit)),
+            Left | LeftMark => (Some(limit), None),
+            Right | RightMark => (None, Some(limit)),
             _ => (None, None),
         }
     };
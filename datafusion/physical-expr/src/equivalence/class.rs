@@ -473,8 +473,10 @@ impl EquivalenceGroup {
                 }
                 result
             }
-            JoinType::LeftSemi | JoinType::LeftAnti => self.clone(),
-            JoinType::RightSemi | JoinType::RightAnti => right_equivalences.clone(),
+            JoinType::LeftSemi | JoinType::LeftAnti | JoinType::LeftMark => self.clone(),
+            JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => {
+                right_equivalences.clone()
+            }
         }
     }
 }
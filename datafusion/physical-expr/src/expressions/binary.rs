@@ -333,7 +333,12 @@ impl PhysicalExpr for BinaryExpr {
         let left_interval = children[0];
         let right_interval = children[1];
         // Calculate current node's interval:
-        apply_operator(&self.op, left_interval, right_interval)
+        let interval = apply_operator(&self.op, left_interval, right_interval)?;
+        if may_wrap_around(&self.op, left_interval, right_interval) {
+            // The result may take any value after wrapping around:
+            return Interval::make_unbounded(&interval.data_type());
+        }
+        Ok(interval)
     }
 
     fn propagate_constraints(
@@ -430,6 +435,10 @@ impl PhysicalExpr for BinaryExpr {
                 propagate_comparison(&self.op, interval, left_interval, right_interval)?
                     .map(|(left, right)| vec![left, right]),
             )
+        } else if may_wrap_around(&self.op, left_interval, right_interval) {
+            // Children values whose result wraps around would be excluded by
+            // inverting the operation, so the children can not be refined.
+            Ok(Some(vec![]))
         } else {
             Ok(
                 propagate_arithmetic(&self.op, interval, left_interval, right_interval)?
@@ -467,6 +476,20 @@ impl PartialEq<dyn Any> for BinaryExpr {
     }
 }
 
+/// Returns whether evaluating `op` on unsigned integer values within the
+/// `left` and `right` intervals may wrap around at zero, i.e. whether the
+/// subtraction may underflow. Interval arithmetic saturates at the bounds of
+/// the data type, whereas the evaluation of the expression wraps around, so
+/// interval bounds do not hold for such operands.
+fn may_wrap_around(op: &Operator, left: &Interval, right: &Interval) -> bool {
+    // Lower bounds of unsigned intervals are null when they are zero:
+    op == &Operator::Minus
+        && left.data_type().is_unsigned_integer()
+        && (left.lower().is_null()
+            || right.upper().is_null()
+            || left.lower() < right.upper())
+}
+
 /// Casts dictionary array to result type for binary numerical operators. Such operators
 /// between array and scalar produce a dictionary array other than primitive array of the
 /// same operators between array and array. This leads to inconsistent result types causing
@@ -3965,4 +3988,40 @@ mod tests {
                 .unwrap();
         assert_eq!(&casted, &dictionary);
     }
+
+    #[test]
+    fn test_unsigned_subtraction_bounds() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::UInt64, false)]);
+        // a - 10
+        let expr = BinaryExpr::new(
+            col("a", &schema)?,
+            Operator::Minus,
+            lit(ScalarValue::UInt64(Some(10))),
+        );
+        let ten = Interval::make(Some(10_u64), Some(10_u64))?;
+
+        // Values of `a` below 10 wrap around, so the result is unbounded:
+        let left = Interval::make(Some(3_u64), Some(20_u64))?;
+        assert_eq!(
+            expr.evaluate_bounds(&[&left, &ten])?,
+            Interval::make_unbounded(&DataType::UInt64)?
+        );
+        let parent = Interval::make(Some(5_u64), None)?;
+        assert_eq!(
+            expr.propagate_constraints(&parent, &[&left, &ten])?,
+            Some(vec![])
+        );
+
+        // Without wrapping around, the bounds are refined as usual:
+        let left = Interval::make(Some(12_u64), Some(20_u64))?;
+        assert_eq!(
+            expr.evaluate_bounds(&[&left, &ten])?,
+            Interval::make(Some(2_u64), Some(10_u64))?
+        );
+        assert_eq!(
+            expr.propagate_constraints(&parent, &[&left, &ten])?,
+            Some(vec![Interval::make(Some(15_u64), Some(20_u64))?, ten])
+        );
+        Ok(())
+    }
 }
@@ -245,6 +245,17 @@ pub fn propagate_arithmetic(
                 &inverse_op,
             )
         }
+        // Dates can only be shifted by time intervals (which are not convertible
+        // to a `Duration` in general), so we only propagate to the date child.
+        (DataType::Date32 | DataType::Date64, DataType::Interval(_)) => {
+            Ok(apply_operator(&inverse_op, parent, right_child)?
+                .intersect(left_child)?
+                .map(|value| (value, right_child.clone())))
+        }
+        (DataType::Interval(_), DataType::Date32 | DataType::Date64) => Ok(
+            propagate_right(left_child, parent, right_child, op, &inverse_op)?
+                .map(|right| (left_child.clone(), right)),
+        ),
         _ => {
             // First, propagate to the left:
             match apply_operator(&inverse_op, parent, right_child)?
@@ -720,7 +731,9 @@ fn reverse_tuple<T, U>((first, second): (T, U)) -> (U, T) {
 mod tests {
     use super::*;
     use crate::expressions::{BinaryExpr, Column};
-    use crate::intervals::test_utils::gen_conjunctive_numerical_expr;
+    use crate::intervals::test_utils::{
+        gen_between_expr, gen_conjunctive_numerical_expr,
+    };
 
     use arrow::datatypes::TimeUnit;
     use arrow_schema::{DataType, Field};
@@ -862,6 +875,51 @@ mod tests {
     generate_cases!(generate_case_f32, f32, Float32);
     generate_cases!(generate_case_f64, f64, Float64);
 
+    #[rstest]
+    #[test]
+    fn test_between_shape_bounds(
+        #[values(Operator::Gt, Operator::GtEq)] greater_op: Operator,
+        #[values(Operator::Lt, Operator::LtEq)] less_op: Operator,
+    ) -> Result<()> {
+        let build_col = Arc::new(Column::new("build", 0)) as Arc<dyn PhysicalExpr>;
+        let probe_col = Arc::new(Column::new("probe", 1)) as Arc<dyn PhysicalExpr>;
+        let schema = Schema::new(vec![
+            Field::new("build", DataType::Int32, true),
+            Field::new("probe", DataType::Int32, true),
+        ]);
+        // probe >/>= build - 5 AND probe </<= build + 10
+        let expr = gen_between_expr(
+            probe_col.clone(),
+            build_col.clone(),
+            ScalarValue::Int32(Some(5)),
+            ScalarValue::Int32(Some(10)),
+            (greater_op, less_op),
+        );
+        let mut graph = ExprIntervalGraph::try_new(expr, &schema)?;
+        let node_indices = graph.gather_node_indices(&[build_col, probe_col]);
+        let mut intervals = vec![
+            (
+                node_indices[0].1,
+                Interval::make(Some(0_i32), Some(100_i32))?,
+            ),
+            (
+                node_indices[1].1,
+                Interval::make(Some(50_i32), Some(60_i32))?,
+            ),
+        ];
+        let result = graph.update_ranges(&mut intervals, Interval::CERTAINLY_TRUE)?;
+        assert_eq!(result, PropagationResult::Success);
+
+        // Both bounds on the build side are derived in a single pass, and they
+        // are exclusive exactly when the corresponding operator is strict:
+        // build > probe - 10 >= 40 and build < probe + 5 <= 65.
+        let lower = if less_op == Operator::Lt { 41 } else { 40 };
+        let upper = if greater_op == Operator::Gt { 64 } else { 65 };
+        assert_eq!(intervals[0].1, Interval::make(Some(lower), Some(upper))?);
+        assert_eq!(intervals[1].1, Interval::make(Some(50_i32), Some(60_i32))?);
+        Ok(())
+    }
+
     #[test]
     fn testing_not_possible() -> Result<()> {
         let left_col = Arc::new(Column::new("left_watermark", 0));
@@ -1477,6 +1535,48 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_propagate_constraints_date_column_across_epoch() -> Result<()> {
+        let expression = BinaryExpr::new(
+            Arc::new(Column::new("date_column", 0)),
+            Operator::Minus,
+            Arc::new(Column::new("interval_column", 1)),
+        );
+        // 29.12.1969 - 02.01.1970
+        let parent = Interval::try_new(
+            ScalarValue::Date32(Some(-3)),
+            ScalarValue::Date32(Some(1)),
+        )?;
+        // 22.12.1969 - 11.01.1970
+        let left_child = Interval::try_new(
+            ScalarValue::Date32(Some(-10)),
+            ScalarValue::Date32(Some(10)),
+        )?;
+        // 2 days
+        let right_child = Interval::try_new(
+            ScalarValue::new_interval_dt(2, 0),
+            ScalarValue::new_interval_dt(2, 0),
+        )?;
+        let children = vec![&left_child, &right_child];
+        let result = expression
+            .propagate_constraints(&parent, &children)?
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                // 31.12.1969 - 04.01.1970
+                Interval::try_new(
+                    ScalarValue::Date32(Some(-1)),
+                    ScalarValue::Date32(Some(3)),
+                )?,
+                right_child.clone(),
+            ],
+            result
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_propagate_comparison() -> Result<()> {
         // In the examples below:
@@ -90,3 +90,29 @@ pub fn gen_conjunctive_temporal_expr(
         right_expr,
     )))
 }
+
+/// This test function generates a double-bounded (`BETWEEN`-style) statement
+/// on a single column with the following form:
+/// probe_col >/>= build_col - a AND probe_col </<= build_col + b
+pub fn gen_between_expr(
+    probe_col: Arc<dyn PhysicalExpr>,
+    build_col: Arc<dyn PhysicalExpr>,
+    a: ScalarValue,
+    b: ScalarValue,
+    bounds: (Operator, Operator),
+) -> Arc<dyn PhysicalExpr> {
+    let (greater_op, less_op) = bounds;
+    let lower_bound = Arc::new(BinaryExpr::new(
+        build_col.clone(),
+        Operator::Minus,
+        Arc::new(Literal::new(a)),
+    ));
+    let upper_bound = Arc::new(BinaryExpr::new(
+        build_col,
+        Operator::Plus,
+        Arc::new(Literal::new(b)),
+    ));
+    let left_expr = Arc::new(BinaryExpr::new(probe_col.clone(), greater_op, lower_bound));
+    let right_expr = Arc::new(BinaryExpr::new(probe_col, less_op, upper_bound));
+    Arc::new(BinaryExpr::new(left_expr, Operator::And, right_expr))
+}
@@ -111,6 +111,13 @@ pub enum Partitioning {
     /// Allocate rows based on a hash of one of more expressions and the specified number of
     /// partitions
     Hash(Vec<Arc<dyn PhysicalExpr>>, usize),
+    /// Allocate rows based on an integer bucket computed by an expression (e.g.
+    /// a coarse time bucket such as the hour of a timestamp) and the specified
+    /// number of partitions. Bucket `b` is allocated to partition
+    /// `b mod partition_count`, so that rows of the same bucket end up in the
+    /// same partition and adjacent buckets end up in adjacent partitions. See
+    /// [`Partitioning::bucket_partition`].
+    Bucketed(Arc<dyn PhysicalExpr>, usize),
     /// Unknown partitioning scheme with a known number of partitions
     UnknownPartitioning(usize),
 }
@@ -127,6 +134,9 @@ impl fmt::Display for Partitioning {
                     .join(", ");
                 write!(f, "Hash([{phy_exprs_str}], {size})")
             }
+            Partitioning::Bucketed(phy_expr, size) => {
+                write!(f, "Bucketed({phy_expr}, {size})")
+            }
             Partitioning::UnknownPartitioning(size) => {
                 write!(f, "UnknownPartitioning({size})")
             }
@@ -138,10 +148,20 @@ impl Partitioning {
     pub fn partition_count(&self) -> usize {
         use Partitioning::*;
         match self {
-            RoundRobinBatch(n) | Hash(_, n) | UnknownPartitioning(n) => *n,
+            RoundRobinBatch(n) | Hash(_, n) | Bucketed(_, n) | UnknownPartitioning(n) => {
+                *n
+            }
         }
     }
 
+    /// Returns the partition that rows of the given `bucket` are allocated to by
+    /// [`Partitioning::Bucketed`] with `partition_count` partitions. Negative
+    /// buckets wrap around, so that consecutive buckets are always allocated to
+    /// consecutive partitions (modulo `partition_count`).
+    pub fn bucket_partition(bucket: i64, partition_count: usize) -> usize {
+        bucket.rem_euclid(partition_count as i64) as usize
+    }
+
     /// Returns true when the guarantees made by this [`Partitioning`] are sufficient to
     /// satisfy the partitioning scheme mandated by the `required` [`Distribution`].
     pub fn satisfy(
@@ -201,6 +221,10 @@ impl PartialEq for Partitioning {
             {
                 true
             }
+            (
+                Partitioning::Bucketed(expr1, count1),
+                Partitioning::Bucketed(expr2, count2),
+            ) if expr1.eq(expr2) && (count1 == count2) => true,
             _ => false,
         }
     }
@@ -270,6 +294,7 @@ mod tests {
         let single_partition = Partitioning::UnknownPartitioning(1);
         let unspecified_partition = Partitioning::UnknownPartitioning(10);
         let round_robin_partition = Partitioning::RoundRobinBatch(10);
+        let bucketed_partition = Partitioning::Bucketed(partition_exprs1[0].clone(), 10);
         let hash_partition1 = Partitioning::Hash(partition_exprs1, 10);
         let hash_partition2 = Partitioning::Hash(partition_exprs2, 10);
         let eq_properties = EquivalenceProperties::new(schema);
@@ -281,21 +306,31 @@ mod tests {
                 round_robin_partition.satisfy(&distribution, &eq_properties),
                 hash_partition1.satisfy(&distribution, &eq_properties),
                 hash_partition2.satisfy(&distribution, &eq_properties),
+                bucketed_partition.satisfy(&distribution, &eq_properties),
             );
 
             match distribution {
                 Distribution::UnspecifiedDistribution => {
-                    assert_eq!(result, (true, true, true, true, true))
+                    assert_eq!(result, (true, true, true, true, true, true))
                 }
                 Distribution::SinglePartition => {
-                    assert_eq!(result, (true, false, false, false, false))
+                    assert_eq!(result, (true, false, false, false, false, false))
                 }
                 Distribution::HashPartitioned(_) => {
-                    assert_eq!(result, (false, false, false, true, false))
+                    assert_eq!(result, (false, false, false, true, false, false))
                 }
             }
         }
 
         Ok(())
     }
+
+    #[test]
+    fn bucket_partition() {
+        assert_eq!(Partitioning::bucket_partition(0, 4), 0);
+        assert_eq!(Partitioning::bucket_partition(6, 4), 2);
+        assert_eq!(Partitioning::bucket_partition(-1, 4), 3);
+        assert_eq!(Partitioning::bucket_partition(-5, 4), 3);
+        assert_eq!(Partitioning::bucket_partition(i64::MIN, 3), 1);
+    }
 }
@@ -526,18 +526,21 @@ impl AggregateExec {
             // but needs to respect aliases (e.g. mapping in the GROUP BY
             // expression).
             let input_eq_properties = input.equivalence_properties();
-            if let Partitioning::Hash(exprs, part) = output_partitioning {
-                let normalized_exprs = exprs
-                    .iter()
-                    .map(|expr| {
-                        input_eq_properties
-                            .project_expr(expr, projection_mapping)
-                            .unwrap_or_else(|| {
-                                Arc::new(UnKnownColumn::new(&expr.to_string()))
-                            })
-                    })
-                    .collect();
-                output_partitioning = Partitioning::Hash(normalized_exprs, part);
+            let project_expr = |expr: &Arc<dyn PhysicalExpr>| {
+                input_eq_properties
+                    .project_expr(expr, projection_mapping)
+                    .unwrap_or_else(|| Arc::new(UnKnownColumn::new(&expr.to_string())))
+            };
+            match output_partitioning {
+                Partitioning::Hash(exprs, part) => {
+                    let normalized_exprs = exprs.iter().map(project_expr).collect();
+                    output_partitioning = Partitioning::Hash(normalized_exprs, part);
+                }
+                Partitioning::Bucketed(expr, part) => {
+                    output_partitioning =
+                        Partitioning::Bucketed(project_expr(&expr), part);
+                }
+                _ => {}
             }
         }
 
@@ -21,8 +21,8 @@
 use std::{any::Any, sync::Arc, task::Poll};
 
 use super::utils::{
-    adjust_right_output_partitioning, BuildProbeJoinMetrics, OnceAsync, OnceFut,
-    StatefulStreamResult,
+    adjust_right_output_partitioning, limit_output_rows, skip_empty_batches,
+    BuildProbeJoinMetrics, OnceAsync, OnceFut, OutputRowLimit, StatefulStreamResult,
 };
 use crate::coalesce_batches::concat_batches;
 use crate::coalesce_partitions::CoalescePartitionsExec;
@@ -63,6 +63,12 @@ pub struct CrossJoinExec {
     left_fut: OnceAsync<JoinLeftData>,
     /// Execution plan metrics
     metrics: ExecutionPlanMetricsSet,
+    /// Limit on the number of output rows across all partitions, see
+    /// [`Self::with_max_output_rows`]
+    output_row_limit: Option<OutputRowLimit>,
+    /// Whether zero-row batches are dropped from the output, see
+    /// [`Self::with_skip_empty_output_batches`]
+    skip_empty_output_batches: bool,
     cache: PlanProperties,
 }
 
@@ -86,10 +92,33 @@ impl CrossJoinExec {
             schema,
             left_fut: Default::default(),
             metrics: ExecutionPlanMetricsSet::default(),
+            output_row_limit: None,
+            skip_empty_output_batches: false,
             cache,
         }
     }
 
+    /// Fails the execution with `ResourcesExhausted` once the output rows of
+    /// all partitions together exceed `max_output_rows`, see
+    /// [`HashJoinExec::with_max_output_rows`]. By default, the output is
+    /// unlimited.
+    ///
+    /// [`HashJoinExec::with_max_output_rows`]: crate::joins::HashJoinExec::with_max_output_rows
+    pub fn with_max_output_rows(mut self, max_output_rows: usize) -> Self {
+        self.output_row_limit = Some(OutputRowLimit::new(max_output_rows));
+        self
+    }
+
+    /// Drops zero-row batches from the output instead of emitting them, see
+    /// [`HashJoinExec::with_skip_empty_output_batches`]. By default, batches
+    /// are emitted as produced, including empty ones.
+    ///
+    /// [`HashJoinExec::with_skip_empty_output_batches`]: crate::joins::HashJoinExec::with_skip_empty_output_batches
+    pub fn with_skip_empty_output_batches(mut self, skip: bool) -> Self {
+        self.skip_empty_output_batches = skip;
+        self
+    }
+
     /// left (build) side which gets loaded in memory
     pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
         &self.left
@@ -100,6 +129,16 @@ impl CrossJoinExec {
         &self.right
     }
 
+    /// Maximum number of output rows across all partitions, if limited
+    pub fn max_output_rows(&self) -> Option<usize> {
+        self.output_row_limit.as_ref().map(|limit| limit.max_rows())
+    }
+
+    /// Whether zero-row batches are dropped from the output
+    pub fn skip_empty_output_batches(&self) -> bool {
+        self.skip_empty_output_batches
+    }
+
     /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
     fn compute_properties(
         left: &Arc<dyn ExecutionPlan>,
@@ -219,10 +258,13 @@ impl ExecutionPlan for CrossJoinExec {
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(CrossJoinExec::new(
-            children[0].clone(),
-            children[1].clone(),
-        )))
+        let mut join = CrossJoinExec::new(children[0].clone(), children[1].clone());
+        join.output_row_limit = self
+            .output_row_limit
+            .as_ref()
+            .map(|limit| OutputRowLimit::new(limit.max_rows()));
+        join.skip_empty_output_batches = self.skip_empty_output_batches;
+        Ok(Arc::new(join))
     }
 
     fn required_input_distribution(&self) -> Vec<Distribution> {
@@ -254,7 +296,7 @@ impl ExecutionPlan for CrossJoinExec {
             )
         });
 
-        Ok(Box::pin(CrossJoinStream {
+        let stream = Box::pin(CrossJoinStream {
             schema: self.schema.clone(),
             left_fut,
             right: stream,
@@ -262,7 +304,12 @@ impl ExecutionPlan for CrossJoinExec {
             join_metrics,
             state: CrossJoinStreamState::WaitBuildSide,
             left_data: RecordBatch::new_empty(self.left().schema()),
-        }))
+        });
+        Ok(limit_output_rows(
+            skip_empty_batches(stream, self.skip_empty_output_batches),
+            self.output_row_limit.as_ref(),
+            partition,
+        ))
     }
 
     fn statistics(&self) -> Result<Statistics> {
@@ -469,9 +516,12 @@ impl CrossJoinStream {
     fn build_batches(&mut self) -> Result<StatefulStreamResult<Option<RecordBatch>>> {
         let right_batch = self.state.try_as_record_batch()?;
         if self.left_index < self.left_data.num_rows() {
+            // Every pair of rows matches, so joining only assembles the output
             let join_timer = self.join_metrics.join_time.timer();
+            let output_timer = self.join_metrics.output_time.timer();
             let result =
                 build_batch(self.left_index, right_batch, &self.left_data, &self.schema);
+            output_timer.done();
             join_timer.done();
 
             if let Ok(ref batch) = result {
@@ -491,9 +541,10 @@ impl CrossJoinStream {
 mod tests {
     use super::*;
     use crate::common;
-    use crate::test::build_table_scan_i32;
+    use crate::memory::MemoryExec;
+    use crate::test::{build_table_i32, build_table_scan_i32};
 
-    use datafusion_common::{assert_batches_sorted_eq, assert_contains};
+    use datafusion_common::{assert_batches_sorted_eq, assert_contains, DataFusionError};
     use datafusion_execution::runtime_env::{RuntimeConfig, RuntimeEnv};
 
     async fn join_collect(
@@ -706,6 +757,50 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_max_output_rows() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let left = build_table_scan_i32(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 6]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        // Two right partitions with one row each, each producing 3 output rows
+        let right_batch = build_table_i32(
+            ("a2", &vec![10, 11]),
+            ("b2", &vec![12, 13]),
+            ("c2", &vec![14, 15]),
+        );
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![right_batch.slice(0, 1)], vec![right_batch.slice(1, 1)]],
+            right_batch.schema(),
+            None,
+        )?);
+
+        let join =
+            CrossJoinExec::new(left.clone(), right.clone()).with_max_output_rows(6);
+        assert_eq!(join.max_output_rows(), Some(6));
+        for partition in 0..2 {
+            let batches =
+                common::collect(join.execute(partition, task_ctx.clone())?).await?;
+            assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 3);
+        }
+
+        // The limit is shared by all partitions:
+        let join = CrossJoinExec::new(left, right).with_max_output_rows(5);
+        common::collect(join.execute(0, task_ctx.clone())?).await?;
+        let err = common::collect(join.execute(1, task_ctx)?)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DataFusionError::ResourcesExhausted(_)));
+        assert_contains!(
+            err.to_string(),
+            "Join produced more than the maximum of 5 output rows"
+        );
+
+        Ok(())
+    }
+
     /// Returns the column names on the schema
     fn columns(schema: &Schema) -> Vec<String> {
         schema.fields().iter().map(|f| f.name().clone()).collect()
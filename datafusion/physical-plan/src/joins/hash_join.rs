@@ -17,16 +17,26 @@
 
 //! [`HashJoinExec`] Partitioned Hash Join Operator
 
+use std::collections::{HashSet, VecDeque};
 use std::fmt;
+use std::future::Future;
 use std::mem::size_of;
+use std::ops::Range;
+use std::pin::Pin;
 use std::sync::Arc;
 use std::task::Poll;
+use std::time::Duration;
 use std::{any::Any, usize, vec};
 
 use super::{
+    output_shaping::{split_serialized_rows, OutputChunk, OutputShaping, ProbeKeyGroups},
+    runtime_filter::{
+        supports_runtime_filter, RuntimeFilter, RuntimeFilterKey, RuntimeFilterRegistry,
+    },
     utils::{OnceAsync, OnceFut},
-    PartitionMode,
+    Collation, FloatKeyMode, PartitionMode,
 };
+use crate::streaming::{PartitionStream, StreamingTableExec};
 use crate::ExecutionPlanProperties;
 use crate::{
     coalesce_partitions::CoalescePartitionsExec,
@@ -35,43 +45,68 @@ use crate::{
     hash_utils::create_hashes,
     joins::utils::{
         adjust_indices_by_join_type, adjust_right_output_partitioning,
-        apply_join_filter_to_indices, build_batch_from_indices, build_join_schema,
-        check_join_is_valid, estimate_join_statistics, get_final_indices_from_bit_map,
-        need_produce_result_in_final, partitioned_join_output_partitioning,
-        BuildProbeJoinMetrics, ColumnIndex, JoinFilter, JoinHashMap, JoinHashMapOffset,
-        JoinHashMapType, JoinOn, JoinOnRef, StatefulStreamResult,
+        append_hidden_columns, apply_join_filter_to_indices, build_batch_from_indices,
+        build_join_schema, canonicalize_join_on, check_join_is_valid,
+        estimate_join_statistics, estimated_ipc_size, evaluate_output_exprs,
+        get_final_indices_from_bit_map, is_join_filter_satisfied, join_metrics_json,
+        limit_concurrency, limit_output_rows, match_flag_indices,
+        need_produce_result_in_final, new_empty_join_batch, new_null_join_array,
+        output_exprs_schema, output_order_indices, partitioned_join_output_partitioning,
+        skip_empty_batches, sort_output_rows, split_indices_by_byte_budget,
+        take_join_column, with_match_flag_field, with_row_provenance_fields,
+        with_unmatched_marker_field, BuildProbeJoinMetrics, CollatedKeyExpr, ColumnIndex,
+        ConcurrencyLimit, FloatKeyExpr, JoinFilter, JoinHashMap, JoinHashMapOffset,
+        JoinHashMapStats, JoinHashMapType, JoinOn, JoinOnRef, MatchBitmap,
+        MatchedKeysTrace, NullPadding, OutputRowLimit, StatefulStreamResult,
     },
-    metrics::{ExecutionPlanMetricsSet, MetricsSet},
-    DisplayAs, DisplayFormatType, Distribution, ExecutionMode, ExecutionPlan,
-    Partitioning, PlanProperties, RecordBatchStream, SendableRecordBatchStream,
-    Statistics,
+    metrics::{self, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet},
+    repartition::RepartitionExec,
+    stream::{RecordBatchReceiverStream, RecordBatchStreamAdapter},
+    ColumnStatistics, DisplayAs, DisplayFormatType, Distribution, ExecutionMode,
+    ExecutionPlan, Partitioning, PlanProperties, RecordBatchStream,
+    SendableRecordBatchStream, Statistics,
 };
 
 use arrow::array::{
-    Array, ArrayRef, BooleanArray, BooleanBufferBuilder, PrimitiveArray, UInt32Array,
-    UInt64Array,
+    new_null_array, Array, ArrayRef, AsArray, BinaryArray, BinaryViewArray, BooleanArray,
+    GenericByteViewArray, PrimitiveArray, StringArray, StringViewArray, StructArray,
+    UInt32Array, UInt32BufferBuilder, UInt64Array, UInt64BufferBuilder,
 };
+use arrow::buffer::NullBuffer;
 use arrow::compute::kernels::cmp::{eq, not_distinct};
-use arrow::compute::{and, concat_batches, take, FilterBuilder};
-use arrow::datatypes::{Schema, SchemaRef};
-use arrow::record_batch::RecordBatch;
-use arrow::util::bit_util;
+use arrow::compute::{
+    concat_batches, filter, filter_record_batch, not, take, FilterBuilder,
+};
+use arrow::datatypes::{ByteViewType, DataType, Schema, SchemaRef};
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use arrow::row::{OwnedRow, RowConverter, SortField};
 use arrow_array::cast::downcast_array;
 use arrow_schema::ArrowError;
+use datafusion_common::cast::{
+    as_binary_view_array, as_boolean_array, as_string_view_array, as_uint64_array,
+};
+use datafusion_common::stats::Precision;
 use datafusion_common::{
-    internal_datafusion_err, internal_err, plan_err, project_schema, DataFusionError,
-    JoinSide, JoinType, Result,
+    exec_err, internal_datafusion_err, internal_err, not_impl_err, plan_err,
+    project_schema, DataFusionError, JoinSide, JoinType, Result,
 };
+use datafusion_common_runtime::SpawnedTask;
 use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr::equivalence::{
     join_equivalence_properties, ProjectionMapping,
 };
-use datafusion_physical_expr::expressions::UnKnownColumn;
-use datafusion_physical_expr::{PhysicalExpr, PhysicalExprRef};
+use datafusion_physical_expr::expressions::{cast, Column, UnKnownColumn};
+use datafusion_physical_expr::{
+    physical_exprs_equal, LexOrdering, PhysicalExpr, PhysicalExprRef, PhysicalSortExpr,
+};
 
 use ahash::RandomState;
-use futures::{ready, Stream, StreamExt, TryStreamExt};
+use futures::future::{poll_fn, BoxFuture};
+use futures::{ready, FutureExt, Stream, StreamExt, TryStreamExt};
+use log::warn;
+use parking_lot::Mutex;
+use tokio::time::{sleep_until, Instant, Sleep};
 
 /// HashTable and input data for the left (build side) of a join
 struct JoinLeftData {
@@ -79,6 +114,9 @@ struct JoinLeftData {
     hash_map: JoinHashMap,
     /// The input rows for the build side
     batch: RecordBatch,
+    /// Bloom filter over the hashes of the join keys of `batch`, present if
+    /// the probe side is reduced by it
+    key_filter: Option<JoinKeyFilter>,
     /// Memory reservation that tracks memory used by `hash_map` hash table
     /// `batch`. Cleared on drop.
     #[allow(dead_code)]
@@ -90,11 +128,13 @@ impl JoinLeftData {
     fn new(
         hash_map: JoinHashMap,
         batch: RecordBatch,
+        key_filter: Option<JoinKeyFilter>,
         reservation: MemoryReservation,
     ) -> Self {
         Self {
             hash_map,
             batch,
+            key_filter,
             reservation,
         }
     }
@@ -113,6 +153,97 @@ impl JoinLeftData {
     fn batch(&self) -> &RecordBatch {
         &self.batch
     }
+
+    /// returns the filter over the build side join keys, if any
+    fn key_filter(&self) -> Option<&JoinKeyFilter> {
+        self.key_filter.as_ref()
+    }
+}
+
+/// Number of bits of a [`JoinKeyFilter`] per inserted key
+const JOIN_KEY_FILTER_BITS_PER_KEY: usize = 10;
+
+/// Bloom filter over the join key hashes of one side of a hash join, used to
+/// drop the rows of the other side that can not have a match before they
+/// enter the join, see [`HashJoinExec::with_semijoin_reduction`]. Every hash
+/// sets two bits, derived from its lower and upper halves.
+pub(super) struct JoinKeyFilter {
+    /// The bits of the filter, a power of two of them
+    bits: Vec<u64>,
+    /// Mask selecting a bit index from a hash
+    mask: u64,
+}
+
+impl JoinKeyFilter {
+    /// Creates an empty filter sized for `num_keys` keys
+    fn new(num_keys: usize) -> Self {
+        let num_bits = (num_keys.max(1) * JOIN_KEY_FILTER_BITS_PER_KEY)
+            .next_power_of_two()
+            .max(64);
+        Self {
+            bits: vec![0; num_bits / 64],
+            mask: num_bits as u64 - 1,
+        }
+    }
+
+    /// Creates a filter containing all `hashes`
+    pub(super) fn from_hashes<'a>(hashes: impl IntoIterator<Item = &'a [u64]>) -> Self {
+        let hashes = hashes.into_iter().collect::<Vec<_>>();
+        let mut filter = Self::new(hashes.iter().map(|h| h.len()).sum());
+        for hash in hashes.into_iter().flatten() {
+            filter.insert(*hash);
+        }
+        filter
+    }
+
+    fn bit_indices(&self, hash: u64) -> [usize; 2] {
+        [
+            (hash & self.mask) as usize,
+            (hash.rotate_left(32) & self.mask) as usize,
+        ]
+    }
+
+    /// Adds `hash` to the filter
+    fn insert(&mut self, hash: u64) {
+        for index in self.bit_indices(hash) {
+            self.bits[index / 64] |= 1 << (index % 64);
+        }
+    }
+
+    /// Returns false if `hash` was certainly not inserted into the filter
+    fn contains(&self, hash: u64) -> bool {
+        self.bit_indices(hash)
+            .into_iter()
+            .all(|index| self.bits[index / 64] & (1 << (index % 64)) != 0)
+    }
+
+    /// Returns a mask selecting the rows of `hashes` that may be contained in
+    /// the filter, along with the number of rows that are not
+    pub(super) fn mask(&self, hashes: &[u64]) -> (BooleanArray, usize) {
+        let mask = hashes
+            .iter()
+            .map(|hash| Some(self.contains(*hash)))
+            .collect::<BooleanArray>();
+        let rejected = mask.len() - mask.true_count();
+        (mask, rejected)
+    }
+
+    /// Size of the filter in bytes
+    fn size(&self) -> usize {
+        self.bits.len() * size_of::<u64>()
+    }
+}
+
+/// The probe side input of a partition, buffered to reduce the build side
+/// before the hash table is built, see [`HashJoinExec::with_semijoin_reduction`]
+struct BufferedProbeSide {
+    /// The probe side batches, replayed once the hash table is built
+    batches: Vec<RecordBatch>,
+    /// Bloom filter over the hashes of the join keys of `batches`
+    key_filter: JoinKeyFilter,
+    /// Memory reservation tracking `batches` and `key_filter`. Cleared on drop.
+    #[allow(dead_code)]
+    reservation: MemoryReservation,
 }
 
 /// Join execution plan: Evaluates eqijoin predicates in parallel on multiple
@@ -289,6 +420,9 @@ pub struct HashJoinExec {
     join_schema: SchemaRef,
     /// Future that consumes left input and builds the hash table
     left_fut: OnceAsync<JoinLeftData>,
+    /// Future that reads the left input until the strategy is decided on, if
+    /// the partitioning is adaptive, see [`Self::with_adaptive_partitioning`]
+    build_sample: OnceAsync<BuildSample>,
     /// Shared the `RandomState` for the hashing algorithm
     random_state: RandomState,
     /// Partitioning mode to use
@@ -304,6 +438,79 @@ pub struct HashJoinExec {
     /// Otherwise, rows that have `null`s in the join columns will not be
     /// matched and thus will not appear in the output.
     pub null_equals_null: bool,
+    /// Maximum number of build side batches hashed concurrently, see
+    /// [`Self::with_build_threads`]
+    build_threads: usize,
+    /// Maximum number of build side batches read ahead while the fetched ones
+    /// are hashed, see [`Self::with_build_prefetch`]
+    build_prefetch: usize,
+    /// Whether the output carries the hidden row provenance columns, see
+    /// [`Self::with_row_provenance`]
+    row_provenance: bool,
+    /// Whether the output carries the hidden unmatched row marker column, see
+    /// [`Self::with_unmatched_marker`]
+    unmatched_marker: bool,
+    /// Whether all probe rows are emitted with the hidden match flag column,
+    /// see [`Self::with_match_flag`]
+    match_flag: bool,
+    /// Whether the join keys are compared in the order of their selectivity,
+    /// see [`Self::with_key_reordering`]
+    reorder_keys: bool,
+    /// Options shaping the output batches, see for example
+    /// [`Self::with_batch_size`] and [`Self::with_low_latency`]
+    output_shaping: OutputShaping,
+    /// Whether the null arrays padding unmatched rows are reused across
+    /// output batches, see [`Self::with_null_buffer_reuse`]
+    null_buffer_reuse: bool,
+    /// Length above which a chain of build rows sharing a hash value is
+    /// indexed by its join keys, see [`Self::with_max_chain_length`]
+    max_chain_length: Option<usize>,
+    /// Factor by which the build side may exceed its estimated number of rows
+    /// before the join switches to partitioned execution, see
+    /// [`Self::with_adaptive_partitioning`]
+    adaptive_partitioning: Option<usize>,
+    /// Whether the probe side only conjuncts of the filter are applied before
+    /// the hash lookup, see [`Self::with_probe_filter_first`]
+    probe_filter_first: Option<bool>,
+    /// Whether the build side join keys are known to be unique, see
+    /// [`Self::with_build_key_unique`]
+    build_key_unique: bool,
+    /// Limit on the number of output rows across all partitions, see
+    /// [`Self::with_max_output_rows`]
+    output_row_limit: Option<OutputRowLimit>,
+    /// Collation the string join keys are compared under, see
+    /// [`Self::with_collation`]
+    collation: Collation,
+    /// Semantics the float join keys are compared under, see
+    /// [`Self::with_float_key_mode`]
+    float_key_mode: FloatKeyMode,
+    /// Expressions computing the output columns from the join columns, see
+    /// [`Self::with_output_exprs`]
+    output_exprs: Option<Vec<(PhysicalExprRef, String)>>,
+    /// Whether the inputs are reduced to the rows whose join keys may occur in
+    /// the other input, see [`Self::with_semijoin_reduction`]
+    semijoin_reduction: bool,
+    /// Whether join keys of different types are cast to a common type, see
+    /// [`Self::with_key_cast`]
+    key_cast: bool,
+    /// How long an output partition may wait for its inputs before a warning
+    /// is logged, see [`Self::with_stall_timeout`]
+    stall_timeout: Option<Duration>,
+    /// Column of the left input holding the hashes of its join keys, see
+    /// [`Self::with_build_hash_column`]
+    build_hash_column: Option<Column>,
+    /// Ordering the output rows are sorted in before they are emitted, and the
+    /// number of rows kept, see [`Self::with_output_row_order_by`]
+    output_row_order: Option<(LexOrdering, Option<usize>)>,
+    /// Limit on the number of partitions producing a batch at once, see
+    /// [`Self::with_max_concurrency`]
+    concurrency_limit: Option<ConcurrencyLimit>,
+    /// Registry the build side join keys are published to as runtime filters,
+    /// and the id of this join in it, see [`Self::with_runtime_filter_sink`]
+    runtime_filter_sink: Option<(RuntimeFilterRegistry, usize)>,
+    /// Sample of the matched join key values, see
+    /// [`Self::with_trace_matched_keys`]
+    matched_keys_trace: Option<MatchedKeysTrace>,
     /// Cache holding plan properties like equivalences, output partitioning etc.
     cache: PlanProperties,
 }
@@ -327,10 +534,41 @@ impl HashJoinExec {
         let left_schema = left.schema();
         let right_schema = right.schema();
         if on.is_empty() {
-            return plan_err!("On constraints in HashJoinExec should be non-empty");
+            // Without equijoin keys there is nothing to hash on, every build
+            // side row would match every probe side row.
+            return if filter.is_some() {
+                plan_err!(
+                    "On constraints in HashJoinExec should be non-empty, \
+                     use NestedLoopJoinExec for joins with only a filter"
+                )
+            } else {
+                plan_err!(
+                    "On constraints in HashJoinExec should be non-empty, \
+                     use CrossJoinExec for joins without keys and filter"
+                )
+            };
         }
 
         check_join_is_valid(&left_schema, &right_schema, &on)?;
+        if let Some(filter) = &filter {
+            filter.validate(&left_schema, &right_schema)?;
+        }
+        if matches!(join_type, JoinType::LeftMark | JoinType::RightMark)
+            && !null_equals_null
+        {
+            // A null mark depends on whether the other side has a null key,
+            // which is only known if the whole build side is in one partition
+            if partition_mode == PartitionMode::Partitioned {
+                return plan_err!(
+                    "{join_type} joins are not supported in Partitioned mode unless nulls are equal"
+                );
+            }
+            if on.len() > 1 || filter.is_some() {
+                return not_impl_err!(
+                    "{join_type} joins with more than one key or a filter are only supported if nulls are equal"
+                );
+            }
+        }
 
         let (join_schema, column_indices) =
             build_join_schema(&left_schema, &right_schema, join_type);
@@ -350,6 +588,11 @@ impl HashJoinExec {
             &on,
             partition_mode,
             projection.as_ref(),
+            None,
+            false,
+            false,
+            false,
+            None,
         )?;
 
         Ok(HashJoinExec {
@@ -360,1812 +603,9359 @@ impl HashJoinExec {
             join_type: *join_type,
             join_schema,
             left_fut: Default::default(),
+            build_sample: Default::default(),
             random_state,
             mode: partition_mode,
             metrics: ExecutionPlanMetricsSet::new(),
             projection,
             column_indices,
             null_equals_null,
+            build_threads: 1,
+            build_prefetch: 0,
+            row_provenance: false,
+            unmatched_marker: false,
+            match_flag: false,
+            reorder_keys: false,
+            output_shaping: OutputShaping::default(),
+            null_buffer_reuse: true,
+            max_chain_length: None,
+            adaptive_partitioning: None,
+            probe_filter_first: None,
+            build_key_unique: false,
+            output_row_limit: None,
+            collation: Collation::Binary,
+            float_key_mode: FloatKeyMode::TotalOrder,
+            output_exprs: None,
+            semijoin_reduction: false,
+            key_cast: false,
+            stall_timeout: None,
+            build_hash_column: None,
+            output_row_order: None,
+            concurrency_limit: None,
+            runtime_filter_sink: None,
+            matched_keys_trace: None,
             cache,
         })
     }
 
-    /// left (build) side which gets hashed
-    pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
-        &self.left
+    /// Distributes the probe side over `probe_threads` output partitions which
+    /// share the single build side hash table, so that the probe phase can use
+    /// more cores than the probe input's partitioning allows. The right input
+    /// is replaced by a round-robin [`RepartitionExec`] of it into
+    /// `probe_threads` partitions, which becomes the right child of the join.
+    ///
+    /// This is only supported in [`PartitionMode::CollectLeft`] mode, and for
+    /// join types which do not need to emit unmatched build side rows once the
+    /// probe side is exhausted.
+    pub fn with_probe_threads(mut self, probe_threads: usize) -> Result<Self> {
+        if probe_threads <= 1 {
+            return Ok(self);
+        }
+        if self.mode != PartitionMode::CollectLeft {
+            return plan_err!(
+                "Probe threads are only supported in CollectLeft mode, got {:?}",
+                self.mode
+            );
+        }
+        if need_produce_result_in_final(self.join_type) {
+            return plan_err!(
+                "Probe threads are not supported for {} joins",
+                self.join_type
+            );
+        }
+        self.right = Arc::new(RepartitionExec::try_new(
+            self.right.clone(),
+            Partitioning::RoundRobinBatch(probe_threads),
+        )?);
+        self.cache = Self::compute_properties(
+            &self.left,
+            &self.right,
+            self.join_schema.clone(),
+            self.join_type,
+            self.equivalence_keys(),
+            self.mode,
+            self.projection.as_ref(),
+            self.output_exprs.as_deref(),
+            self.row_provenance,
+            self.unmatched_marker,
+            self.match_flag,
+            self.output_row_ordering(),
+        )?;
+        Ok(self)
     }
 
-    /// right (probe) side which are filtered by the hash table
-    pub fn right(&self) -> &Arc<dyn ExecutionPlan> {
-        &self.right
+    /// Aligns the partitions of inputs with different partition counts in
+    /// [`PartitionMode::Partitioned`] mode by hash repartitioning the input
+    /// with fewer partitions to the partition count of the other one, which
+    /// saves reshuffling both inputs. The other input is repartitioned as well
+    /// if it is not hash partitioned on its join keys, as its partitions would
+    /// not line up with the repartitioned ones otherwise. The repartitionings
+    /// become the children of the join in place of the inputs.
+    ///
+    /// Inputs with the same partition count are kept as they are.
+    pub fn with_aligned_input_partitions(mut self) -> Result<Self> {
+        if self.mode != PartitionMode::Partitioned {
+            return plan_err!(
+                "Aligning input partitions is only supported in Partitioned mode, got {:?}",
+                self.mode
+            );
+        }
+        let left_partitions = self.left.output_partitioning().partition_count();
+        let right_partitions = self.right.output_partitioning().partition_count();
+        if left_partitions == right_partitions {
+            return Ok(self);
+        }
+        let partition_count = left_partitions.max(right_partitions);
+        let (left_keys, right_keys) = self.on.iter().cloned().unzip();
+        self.left = rehash_input(self.left.clone(), left_keys, partition_count)?;
+        self.right = rehash_input(self.right.clone(), right_keys, partition_count)?;
+        self.cache = Self::compute_properties(
+            &self.left,
+            &self.right,
+            self.join_schema.clone(),
+            self.join_type,
+            self.equivalence_keys(),
+            self.mode,
+            self.projection.as_ref(),
+            self.output_exprs.as_deref(),
+            self.row_provenance,
+            self.unmatched_marker,
+            self.match_flag,
+            self.output_row_ordering(),
+        )?;
+        Ok(self)
     }
 
-    /// Set of common columns used to join on
-    pub fn on(&self) -> &[(PhysicalExprRef, PhysicalExprRef)] {
-        &self.on
+    /// Hashes the join keys of up to `build_threads` build side batches
+    /// concurrently on blocking tasks of the runtime while building the hash
+    /// table, instead of hashing all batches on the task executing the join.
+    /// This reduces the build latency for large build sides, in particular for
+    /// the one-time build in [`PartitionMode::CollectLeft`] mode.
+    ///
+    /// Rows are inserted into the hash table in the same order either way, so
+    /// the output is the same as with a serial build.
+    pub fn with_build_threads(mut self, build_threads: usize) -> Self {
+        self.build_threads = build_threads.max(1);
+        self
     }
 
-    /// Filters applied before join output
-    pub fn filter(&self) -> Option<&JoinFilter> {
-        self.filter.as_ref()
+    /// Reads up to `build_prefetch` build side batches ahead on a separate task
+    /// of the runtime, while hashing the already fetched batches, instead of
+    /// hashing the batches only once the whole build side has been read. This
+    /// overlaps IO and compute when the build side comes from a slow source,
+    /// in particular for the one-time build in [`PartitionMode::CollectLeft`]
+    /// mode. `0`, the default, disables prefetching.
+    ///
+    /// The batches are hashed on the task executing the join as they arrive,
+    /// so [`Self::with_build_threads`] has no effect while prefetching. The
+    /// prefetched batches are not accounted for in the memory reservation of
+    /// the build side until they are hashed. The output does not change.
+    pub fn with_build_prefetch(mut self, build_prefetch: usize) -> Self {
+        self.build_prefetch = build_prefetch;
+        self
     }
 
-    /// How the join is performed
-    pub fn join_type(&self) -> &JoinType {
-        &self.join_type
+    /// Appends the hidden [`BUILD_ROW_COLUMN`] and [`PROBE_ROW_COLUMN`] columns
+    /// to the output, identifying the build and probe side rows every output
+    /// row originates from. The columns follow the (projected) join columns,
+    /// see [`with_row_provenance_fields`] for their exact semantics. Note that
+    /// the build side row index refers to the concatenated build side batch,
+    /// not to the batch of the build side input the row was read from.
+    ///
+    /// Other join operators do not support emitting these columns.
+    ///
+    /// [`BUILD_ROW_COLUMN`]: crate::joins::utils::BUILD_ROW_COLUMN
+    /// [`PROBE_ROW_COLUMN`]: crate::joins::utils::PROBE_ROW_COLUMN
+    pub fn with_row_provenance(mut self, row_provenance: bool) -> Result<Self> {
+        if row_provenance && self.output_exprs.is_some() {
+            return plan_err!(
+                "Row provenance can not be emitted by a HashJoinExec with output expressions"
+            );
+        }
+        if row_provenance
+            && matches!(self.join_type, JoinType::LeftMark | JoinType::RightMark)
+        {
+            return plan_err!(
+                "Row provenance can not be emitted for {} joins",
+                self.join_type
+            );
+        }
+        self.cache = Self::compute_properties(
+            &self.left,
+            &self.right,
+            self.join_schema.clone(),
+            self.join_type,
+            self.equivalence_keys(),
+            self.mode,
+            self.projection.as_ref(),
+            self.output_exprs.as_deref(),
+            row_provenance,
+            self.unmatched_marker,
+            self.match_flag,
+            self.output_row_ordering(),
+        )?;
+        self.row_provenance = row_provenance;
+        Ok(self)
     }
 
-    /// The partitioning mode of this hash join
-    pub fn partition_mode(&self) -> &PartitionMode {
-        &self.mode
+    /// Appends the hidden [`UNMATCHED_ROW_COLUMN`] column to the output, which
+    /// flags the rows without a matching row on the other side, i.e. the rows
+    /// padded with nulls, so that matched and unmatched rows can be routed to
+    /// different operators without re-deriving which rows were matched. The
+    /// column follows the (projected) join columns and the row provenance
+    /// columns, if any. Only `Left`, `Right` and `Full` joins emit unmatched
+    /// rows, so only they support the marker.
+    ///
+    /// Unmatched build side rows are emitted once all probe side partitions
+    /// sharing the build side are done, so they are flagged regardless of the
+    /// partition they are emitted in, see [`with_unmatched_marker_field`].
+    ///
+    /// [`UNMATCHED_ROW_COLUMN`]: crate::joins::utils::UNMATCHED_ROW_COLUMN
+    pub fn with_unmatched_marker(mut self, unmatched_marker: bool) -> Result<Self> {
+        if unmatched_marker {
+            if !matches!(
+                self.join_type,
+                JoinType::Left | JoinType::Right | JoinType::Full
+            ) {
+                return plan_err!(
+                    "Unmatched rows can only be marked for outer joins, not for {} joins",
+                    self.join_type
+                );
+            }
+            if self.output_exprs.is_some() {
+                return plan_err!(
+                    "Unmatched rows can not be marked by a HashJoinExec with output expressions"
+                );
+            }
+        }
+        self.cache = Self::compute_properties(
+            &self.left,
+            &self.right,
+            self.join_schema.clone(),
+            self.join_type,
+            self.equivalence_keys(),
+            self.mode,
+            self.projection.as_ref(),
+            self.output_exprs.as_deref(),
+            self.row_provenance,
+            unmatched_marker,
+            self.match_flag,
+            self.output_row_ordering(),
+        )?;
+        self.unmatched_marker = unmatched_marker;
+        Ok(self)
     }
 
-    /// Get null_equals_null
-    pub fn null_equals_null(&self) -> bool {
-        self.null_equals_null
+    /// Emits every probe side row of a `RightSemi` or `RightAnti` join exactly
+    /// once, followed by the hidden [`MATCHED_ROW_COLUMN`] column flagging
+    /// whether it has a matching build side row, instead of only the matched
+    /// or unmatched rows. A single join then serves both `EXISTS` and
+    /// `NOT EXISTS` style filters downstream, and both join types produce the
+    /// same output. Unlike a mark join, the flag is never null, also not for
+    /// rows with null join keys. The column follows the (projected) join
+    /// columns and the row provenance columns, if any.
+    ///
+    /// [`MATCHED_ROW_COLUMN`]: crate::joins::utils::MATCHED_ROW_COLUMN
+    pub fn with_match_flag(mut self, match_flag: bool) -> Result<Self> {
+        if match_flag {
+            if !matches!(self.join_type, JoinType::RightSemi | JoinType::RightAnti) {
+                return plan_err!(
+                    "Probe rows can only be flagged for RightSemi and RightAnti joins, not for {} joins",
+                    self.join_type
+                );
+            }
+            if self.output_exprs.is_some() {
+                return plan_err!(
+                    "Probe rows can not be flagged by a HashJoinExec with output expressions"
+                );
+            }
+            if self.runtime_filter_sink.is_some() {
+                return plan_err!(
+                    "Probe rows can not be flagged by a HashJoinExec publishing runtime filters"
+                );
+            }
+        }
+        self.cache = Self::compute_properties(
+            &self.left,
+            &self.right,
+            self.join_schema.clone(),
+            self.join_type,
+            self.equivalence_keys(),
+            self.mode,
+            self.projection.as_ref(),
+            self.output_exprs.as_deref(),
+            self.row_provenance,
+            self.unmatched_marker,
+            match_flag,
+            self.output_row_ordering(),
+        )?;
+        self.match_flag = match_flag;
+        Ok(self)
     }
 
-    /// Calculate order preservation flags for this hash join.
-    fn maintains_input_order(join_type: JoinType) -> Vec<bool> {
-        vec![
-            false,
-            matches!(
-                join_type,
-                JoinType::Inner | JoinType::RightAnti | JoinType::RightSemi
-            ),
-        ]
+    /// Compares the join keys in the order of their estimated selectivity when
+    /// resolving hash collisions, so that most non-matching candidate rows are
+    /// discarded by the first, most discriminating key. The selectivity of a
+    /// key is estimated from the distinct counts in the statistics of both
+    /// inputs; if these are not available for all keys, the given order is
+    /// kept. This does not affect the join results.
+    pub fn with_key_reordering(mut self, reorder_keys: bool) -> Self {
+        self.reorder_keys = reorder_keys;
+        self
     }
 
-    /// Get probe side information for the hash join.
-    pub fn probe_side() -> JoinSide {
-        // In current implementation right side is always probe side.
-        JoinSide::Right
+    /// Emits the output for a probe batch in chunks of a fraction of the
+    /// configured batch size as soon as their matches are resolved, instead of
+    /// accumulating up to a full batch of matches first. This reduces the
+    /// latency of the first results at the cost of producing more, smaller
+    /// batches; the overall output is the same.
+    pub fn with_low_latency(mut self, low_latency: bool) -> Self {
+        self.output_shaping.low_latency = low_latency;
+        self
     }
 
-    /// Return whether the join contains a projection
-    pub fn contain_projection(&self) -> bool {
-        self.projection.is_some()
+    /// Sets whether the null arrays padding the columns of the side without a
+    /// match are reused across output batches. If so, the padding of an
+    /// output batch is sliced from a null array kept by the stream, which is
+    /// accounted for in the memory pool, instead of being allocated anew.
+    /// The output is the same either way. Defaults to true.
+    pub fn with_null_buffer_reuse(mut self, null_buffer_reuse: bool) -> Self {
+        self.null_buffer_reuse = null_buffer_reuse;
+        self
     }
 
-    /// Return new instance of [HashJoinExec] with the given projection.
-    pub fn with_projection(&self, projection: Option<Vec<usize>>) -> Result<Self> {
-        //  check if the projection is valid
-        can_project(&self.schema(), projection.as_ref())?;
-        let projection = match projection {
-            Some(projection) => match &self.projection {
-                Some(p) => Some(projection.iter().map(|i| p[*i]).collect()),
-                None => Some(projection),
-            },
-            None => None,
-        };
-        Self::try_new(
-            self.left.clone(),
-            self.right.clone(),
-            self.on.clone(),
-            self.filter.clone(),
-            &self.join_type,
-            projection,
-            self.mode,
-            self.null_equals_null,
-        )
+    /// Limits the bytes taken by the values of an output batch to about
+    /// `max_output_batch_bytes`, in addition to the configured batch size.
+    /// Output rows are accounted for with the lengths of their variable-length
+    /// values (e.g. strings, binaries and lists) and the widths of their
+    /// fixed-width values, so that a few very large values don't blow up the
+    /// output batches. A single row exceeding the limit on its own is emitted
+    /// in a batch by itself. By default, only the row count is limited.
+    pub fn with_max_output_batch_bytes(mut self, max_output_batch_bytes: usize) -> Self {
+        self.output_shaping.max_output_batch_bytes = Some(max_output_batch_bytes);
+        self
     }
 
-    /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
-    fn compute_properties(
-        left: &Arc<dyn ExecutionPlan>,
-        right: &Arc<dyn ExecutionPlan>,
-        schema: SchemaRef,
-        join_type: JoinType,
-        on: JoinOnRef,
-        mode: PartitionMode,
-        projection: Option<&Vec<usize>>,
-    ) -> Result<PlanProperties> {
-        // Calculate equivalence properties:
-        let mut eq_properties = join_equivalence_properties(
-            left.equivalence_properties().clone(),
-            right.equivalence_properties().clone(),
-            &join_type,
-            schema.clone(),
-            &Self::maintains_input_order(join_type),
-            Some(Self::probe_side()),
-            on,
-        );
-
-        // Get output partitioning:
-        let left_columns_len = left.schema().fields.len();
-        let mut output_partitioning = match mode {
-            PartitionMode::CollectLeft => match join_type {
-                JoinType::Inner | JoinType::Right => adjust_right_output_partitioning(
-                    right.output_partitioning(),
-                    left_columns_len,
-                ),
-                JoinType::RightSemi | JoinType::RightAnti => {
-                    right.output_partitioning().clone()
-                }
-                JoinType::Left
-                | JoinType::LeftSemi
-                | JoinType::LeftAnti
-                | JoinType::Full => Partitioning::UnknownPartitioning(
-                    right.output_partitioning().partition_count(),
-                ),
-            },
-            PartitionMode::Partitioned => partitioned_join_output_partitioning(
-                join_type,
-                left.output_partitioning(),
-                right.output_partitioning(),
-                left_columns_len,
-            ),
-            PartitionMode::Auto => Partitioning::UnknownPartitioning(
-                right.output_partitioning().partition_count(),
-            ),
-        };
-
-        // Determine execution mode by checking whether this join is pipeline
-        // breaking. This happens when the left side is unbounded, or the right
-        // side is unbounded with `Left`, `Full`, `LeftAnti` or `LeftSemi` join types.
-        let pipeline_breaking = left.execution_mode().is_unbounded()
-            || (right.execution_mode().is_unbounded()
-                && matches!(
-                    join_type,
-                    JoinType::Left
-                        | JoinType::Full
-                        | JoinType::LeftAnti
-                        | JoinType::LeftSemi
-                ));
+    /// Limits the size of every output batch serialized as an Arrow IPC
+    /// message to `max_serialized_batch_bytes`, so that it fits into a frame
+    /// when the output is served over Arrow Flight. Unlike
+    /// [`Self::with_max_output_batch_bytes`], the limit is strict: it covers
+    /// the offsets, validity and padding of all columns and the message
+    /// header, as estimated by [`estimated_ipc_size`], and the join fails with
+    /// `ResourcesExhausted` if a single output row exceeds it.
+    ///
+    /// View columns share the data buffers of their input, which are
+    /// serialized in full, so they should be compacted before the join.
+    pub fn with_max_serialized_batch_bytes(
+        mut self,
+        max_serialized_batch_bytes: usize,
+    ) -> Self {
+        self.output_shaping.max_serialized_batch_bytes = Some(max_serialized_batch_bytes);
+        self
+    }
 
-        let mode = if pipeline_breaking {
-            ExecutionMode::PipelineBreaking
-        } else {
-            execution_mode_from_children([left, right])
-        };
+    /// Emits output batches of up to `batch_size` rows instead of the batch
+    /// size configured for the session, e.g. larger batches for a join whose
+    /// output is much larger than its inputs, while other operators of the
+    /// plan keep the session's batch size. A `batch_size` of `0` is treated
+    /// as `1`.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.output_shaping.batch_size = Some(batch_size.max(1));
+        self
+    }
 
-        // If contains projection, update the PlanProperties.
-        if let Some(projection) = projection {
-            let projection_exprs = project_index_to_exprs(projection, &schema);
-            // construct a map from the input expressions to the output expression of the Projection
-            let projection_mapping =
-                ProjectionMapping::try_new(&projection_exprs, &schema)?;
-            let out_schema = project_schema(&schema, Some(projection))?;
-            if let Partitioning::Hash(exprs, part) = output_partitioning {
-                let normalized_exprs = exprs
-                    .iter()
-                    .map(|expr| {
-                        eq_properties
-                            .project_expr(expr, &projection_mapping)
-                            .unwrap_or_else(|| {
-                                Arc::new(UnKnownColumn::new(&expr.to_string()))
-                            })
-                    })
-                    .collect();
-                output_partitioning = Partitioning::Hash(normalized_exprs, part);
-            }
-            eq_properties = eq_properties.project(&projection_mapping, out_schema);
-        }
-        Ok(PlanProperties::new(
-            eq_properties,
-            output_partitioning,
-            mode,
-        ))
+    /// Bounds the number of key comparisons a probe row makes with the build
+    /// rows sharing its hash value. Chains of more than `max_chain_length`
+    /// such rows, e.g. from adversarial keys or a poor hash function, are
+    /// indexed by their join keys after the hash table is built, so that the
+    /// rows with equal keys are found by a binary search instead of comparing
+    /// the keys of every row of the chain. `0`, the default, disables the
+    /// limit.
+    ///
+    /// The index is accounted for in the memory reservation of the build side,
+    /// and the number of indexed chains is reported by the
+    /// `hash_table_indexed_chains` metric. The output does not change.
+    pub fn with_max_chain_length(mut self, max_chain_length: usize) -> Self {
+        self.max_chain_length = (max_chain_length > 0).then_some(max_chain_length);
+        self
     }
-}
 
-impl DisplayAs for HashJoinExec {
-    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
-        match t {
-            DisplayFormatType::Default | DisplayFormatType::Verbose => {
-                let display_filter = self.filter.as_ref().map_or_else(
-                    || "".to_string(),
-                    |f| format!(", filter={}", f.expression()),
+    /// Switches a join in [`PartitionMode::CollectLeft`] mode to partitioned
+    /// execution at runtime if its build side turns out to have more than
+    /// `factor` times the number of rows estimated by its statistics, which
+    /// are often far off. Rather than collecting the whole build side into a
+    /// single hash table, every partition then builds a hash table over its
+    /// share of the build side, concurrently.
+    ///
+    /// The build side is read until it either ends or exceeds the bound. In
+    /// the latter case, the batches read so far are replayed before the
+    /// remaining ones, and both sides are repartitioned by hashing their join
+    /// keys. Runtime filters are withdrawn then, and the switch is reported by
+    /// the `adaptive_partitioning_switches` metric. Joins without an estimate
+    /// are never switched, and a `factor` of 0, the default, disables the
+    /// switch.
+    ///
+    /// Neither the partitioning nor the ordering of the probe side is kept
+    /// once switched, so the probe side must not be hash partitioned or
+    /// ordered. Mark joins, which need the whole build side to mark their
+    /// rows, are never switched.
+    pub fn with_adaptive_partitioning(mut self, factor: usize) -> Result<Self> {
+        if factor > 0 {
+            if matches!(self.join_type, JoinType::LeftMark | JoinType::RightMark) {
+                return plan_err!(
+                    "Adaptive partitioning is not supported for {} joins",
+                    self.join_type
+                );
+            }
+            if self.mode != PartitionMode::CollectLeft {
+                return plan_err!(
+                    "Adaptive partitioning is only supported in CollectLeft mode, got {:?}",
+                    self.mode
+                );
+            }
+            if matches!(self.right.output_partitioning(), Partitioning::Hash(_, _))
+                || self.right.output_ordering().is_some()
+            {
+                return plan_err!(
+                    "Adaptive partitioning is not supported for a hash partitioned or ordered probe side"
                 );
-                let display_projections = if self.contain_projection() {
-                    format!(
-                        ", projection=[{}]",
-                        self.projection
-                            .as_ref()
-                            .unwrap()
-                            .iter()
-                            .map(|index| format!(
-                                "{}@{}",
-                                self.join_schema.fields().get(*index).unwrap().name(),
-                                index
-                            ))
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    )
-                } else {
-                    "".to_string()
-                };
-                let on = self
-                    .on
-                    .iter()
-                    .map(|(c1, c2)| format!("({}, {})", c1, c2))
-                    .collect::<Vec<String>>()
-                    .join(", ");
-                write!(
-                    f,
-                    "HashJoinExec: mode={:?}, join_type={:?}, on=[{}]{}{}",
-                    self.mode, self.join_type, on, display_filter, display_projections
-                )
             }
         }
-    }
-}
-
-fn project_index_to_exprs(
-    projection_index: &[usize],
-    schema: &SchemaRef,
-) -> Vec<(Arc<dyn PhysicalExpr>, String)> {
-    projection_index
-        .iter()
-        .map(|index| {
-            let field = schema.field(*index);
-            (
-                Arc::new(datafusion_physical_expr::expressions::Column::new(
-                    field.name(),
-                    *index,
-                )) as Arc<dyn PhysicalExpr>,
-                field.name().to_owned(),
-            )
-        })
-        .collect::<Vec<_>>()
-}
-
-impl ExecutionPlan for HashJoinExec {
-    fn name(&self) -> &'static str {
-        "HashJoinExec"
+        self.adaptive_partitioning = (factor > 0).then_some(factor);
+        Ok(self)
     }
 
-    fn as_any(&self) -> &dyn Any {
+    /// Sets whether the conjuncts of the filter referencing only probe side
+    /// columns are applied before the hash lookup. If so, they are evaluated
+    /// once per probe row, and probe rows rejected by them are dropped from
+    /// the looked up matches before the rest of the filter is evaluated.
+    /// Otherwise, the whole filter is evaluated on each match, which is
+    /// cheaper if few probe rows are rejected.
+    ///
+    /// By default, the conjuncts are applied first if they reject at least
+    /// half of the rows of the first non-empty probe batch.
+    pub fn with_probe_filter_first(mut self, probe_filter_first: bool) -> Self {
+        self.probe_filter_first = Some(probe_filter_first);
         self
     }
 
-    fn properties(&self) -> &PlanProperties {
-        &self.cache
+    /// Hints that the join keys of the build side are unique, e.g. because
+    /// they form a primary key. Every hash value of the hash table then maps
+    /// to a single build side row, so the hash table doesn't need to chain
+    /// rows and probe rows match at most one row without traversing chains.
+    ///
+    /// The hint is verified while building the hash table: the execution
+    /// fails if two build side rows have equal join keys.
+    pub fn with_build_key_unique(mut self, build_key_unique: bool) -> Self {
+        self.build_key_unique = build_key_unique;
+        self
     }
 
-    fn required_input_distribution(&self) -> Vec<Distribution> {
-        match self.mode {
-            PartitionMode::CollectLeft => vec![
-                Distribution::SinglePartition,
-                Distribution::UnspecifiedDistribution,
-            ],
-            PartitionMode::Partitioned => {
-                let (left_expr, right_expr) =
-                    self.on.iter().map(|(l, r)| (l.clone(), r.clone())).unzip();
-                vec![
-                    Distribution::HashPartitioned(left_expr),
-                    Distribution::HashPartitioned(right_expr),
-                ]
-            }
-            PartitionMode::Auto => vec![
-                Distribution::UnspecifiedDistribution,
-                Distribution::UnspecifiedDistribution,
-            ],
-        }
+    /// Fails the execution with [`DataFusionError::ResourcesExhausted`] once
+    /// the output rows of all partitions together exceed `max_output_rows`.
+    /// This protects against runaway joins, e.g. due to bad estimates. By
+    /// default, the output is unlimited.
+    pub fn with_max_output_rows(mut self, max_output_rows: usize) -> Self {
+        self.output_row_limit = Some(OutputRowLimit::new(max_output_rows));
+        self
     }
 
-    // For [JoinType::Inner] and [JoinType::RightSemi] in hash joins, the probe phase initiates by
-    // applying the hash function to convert the join key(s) in each row into a hash value from the
-    // probe side table in the order they're arranged. The hash value is used to look up corresponding
-    // entries in the hash table that was constructed from the build side table during the build phase.
-    //
-    // Because of the immediate generation of result rows once a match is found,
-    // the output of the join tends to follow the order in which the rows were read from
-    // the probe side table. This is simply due to the sequence in which the rows were processed.
-    // Hence, it appears that the hash join is preserving the order of the probe side.
-    //
-    // Meanwhile, in the case of a [JoinType::RightAnti] hash join,
-    // the unmatched rows from the probe side are also kept in order.
-    // This is because the **`RightAnti`** join is designed to return rows from the right
-    // (probe side) table that have no match in the left (build side) table. Because the rows
-    // are processed sequentially in the probe phase, and unmatched rows are directly output
-    // as results, these results tend to retain the order of the probe side table.
-    fn maintains_input_order(&self) -> Vec<bool> {
-        Self::maintains_input_order(self.join_type)
+    /// Drops zero-row batches from the output instead of emitting them, so
+    /// that consumers don't need to filter them out. By default, batches are
+    /// emitted as produced, including empty ones.
+    pub fn with_skip_empty_output_batches(mut self, skip: bool) -> Self {
+        self.output_shaping.skip_empty_batches = skip;
+        self
     }
 
-    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
-        vec![self.left.clone(), self.right.clone()]
+    /// Compares string join keys under `collation` instead of by their bytes:
+    /// keys are hashed and compared on their normalized form, so that e.g.
+    /// `'Apple'` matches `'apple'` under [`Collation::CaseInsensitive`]. The
+    /// output contains the original, unnormalized values.
+    ///
+    /// This is only supported in [`PartitionMode::CollectLeft`] mode, as the
+    /// inputs of a partitioned join are hash partitioned on their binary keys.
+    pub fn with_collation(mut self, collation: Collation) -> Result<Self> {
+        if collation != Collation::Binary && self.mode != PartitionMode::CollectLeft {
+            return plan_err!(
+                "Collation {collation:?} is only supported in CollectLeft mode, got {:?}",
+                self.mode
+            );
+        }
+        self.collation = collation;
+        // A hash table built by an earlier execution hashed the keys under the
+        // previous collation:
+        self.left_fut = Default::default();
+        self.cache = Self::compute_properties(
+            &self.left,
+            &self.right,
+            self.join_schema.clone(),
+            self.join_type,
+            self.equivalence_keys(),
+            self.mode,
+            self.projection.as_ref(),
+            self.output_exprs.as_deref(),
+            self.row_provenance,
+            self.unmatched_marker,
+            self.match_flag,
+            self.output_row_ordering(),
+        )?;
+        Ok(self)
     }
 
-    fn with_new_children(
-        self: Arc<Self>,
-        children: Vec<Arc<dyn ExecutionPlan>>,
-    ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(HashJoinExec::try_new(
-            children[0].clone(),
-            children[1].clone(),
-            self.on.clone(),
-            self.filter.clone(),
-            &self.join_type,
-            self.projection.clone(),
+    /// Hashes and compares float join keys under `float_key_mode`. By default,
+    /// keys are compared under [`FloatKeyMode::TotalOrder`], which tells
+    /// `-0.0` from `+0.0` as is needed e.g. for scientific data, while
+    /// [`FloatKeyMode::Ieee`] matches them, and never matches NaN keys, as
+    /// `=` does. The output contains the original values.
+    ///
+    /// [`FloatKeyMode::Ieee`] is only supported in [`PartitionMode::CollectLeft`]
+    /// mode, as the inputs of a partitioned join are hash partitioned on their
+    /// raw keys, and not if nulls are compared equal, as NaN keys are compared
+    /// as nulls under it.
+    pub fn with_float_key_mode(mut self, float_key_mode: FloatKeyMode) -> Result<Self> {
+        if float_key_mode == FloatKeyMode::Ieee {
+            if self.mode != PartitionMode::CollectLeft {
+                return plan_err!(
+                    "Float key mode {float_key_mode:?} is only supported in CollectLeft mode, got {:?}",
+                    self.mode
+                );
+            }
+            if self.null_equals_null {
+                return plan_err!(
+                    "Float key mode {float_key_mode:?} is not supported if nulls are compared equal"
+                );
+            }
+        }
+        self.float_key_mode = float_key_mode;
+        // A hash table built by an earlier execution hashed the keys under the
+        // previous float key mode:
+        self.left_fut = Default::default();
+        self.cache = Self::compute_properties(
+            &self.left,
+            &self.right,
+            self.join_schema.clone(),
+            self.join_type,
+            self.equivalence_keys(),
             self.mode,
-            self.null_equals_null,
-        )?))
+            self.projection.as_ref(),
+            self.output_exprs.as_deref(),
+            self.row_provenance,
+            self.unmatched_marker,
+            self.match_flag,
+            self.output_row_ordering(),
+        )?;
+        Ok(self)
     }
 
-    fn execute(
-        &self,
-        partition: usize,
-        context: Arc<TaskContext>,
-    ) -> Result<SendableRecordBatchStream> {
-        let on_left = self.on.iter().map(|on| on.0.clone()).collect::<Vec<_>>();
-        let on_right = self.on.iter().map(|on| on.1.clone()).collect::<Vec<_>>();
-        let left_partitions = self.left.output_partitioning().partition_count();
-        let right_partitions = self.right.output_partitioning().partition_count();
-
-        if self.mode == PartitionMode::Partitioned && left_partitions != right_partitions
+    /// Computes the output columns with `output_exprs` instead of emitting the
+    /// join columns, e.g. `left.a + right.b`, so that no separate projection
+    /// is needed. The expressions are evaluated directly on the batches of
+    /// join columns, whose schema they must be valid for, see
+    /// [`Self::join_schema`]. They can not be combined with a projection, the
+    /// row provenance columns, the unmatched row marker or the match flag.
+    pub fn with_output_exprs(
+        mut self,
+        output_exprs: Vec<(PhysicalExprRef, String)>,
+    ) -> Result<Self> {
+        if self.projection.is_some()
+            || self.row_provenance
+            || self.unmatched_marker
+            || self.match_flag
         {
-            return internal_err!(
-                "Invalid HashJoinExec, partition count mismatch {left_partitions}!={right_partitions},\
-                 consider using RepartitionExec"
+            return plan_err!(
+                "Output expressions can not be combined with a projection, row provenance, unmatched marker or match flag in HashJoinExec"
+            );
+        }
+        if self.output_row_order.is_some() {
+            return plan_err!(
+                "The output row order of a HashJoinExec must be set after its output expressions"
             );
         }
+        self.output_exprs = Some(output_exprs);
+        // Validates the expressions against the join schema as well:
+        self.cache = Self::compute_properties(
+            &self.left,
+            &self.right,
+            self.join_schema.clone(),
+            self.join_type,
+            self.equivalence_keys(),
+            self.mode,
+            self.projection.as_ref(),
+            self.output_exprs.as_deref(),
+            self.row_provenance,
+            self.unmatched_marker,
+            self.match_flag,
+            self.output_row_ordering(),
+        )?;
+        Ok(self)
+    }
 
-        let join_metrics = BuildProbeJoinMetrics::new(partition, &self.metrics);
-        let left_fut = match self.mode {
-            PartitionMode::CollectLeft => self.left_fut.once(|| {
-                let reservation =
-                    MemoryConsumer::new("HashJoinInput").register(context.memory_pool());
-                collect_left_input(
-                    None,
-                    self.random_state.clone(),
-                    self.left.clone(),
-                    on_left.clone(),
-                    context.clone(),
-                    join_metrics.clone(),
-                    reservation,
-                )
-            }),
-            PartitionMode::Partitioned => {
-                let reservation =
-                    MemoryConsumer::new(format!("HashJoinInput[{partition}]"))
-                        .register(context.memory_pool());
+    /// Drops the rows of one input whose join keys can not occur in the other
+    /// input before they enter the join, using a Bloom filter over the join
+    /// keys of the other input. This cuts the work of joining inputs whose
+    /// keys overlap little, without changing the output:
+    ///
+    /// * Probe side rows are checked against a filter over the build side
+    ///   keys, built along with the hash table, for join types which do not
+    ///   emit unmatched probe side rows.
+    /// * In [`PartitionMode::Partitioned`] mode, build side rows are checked
+    ///   against a filter over the probe side keys of the partition before
+    ///   they are inserted into the hash table, for join types which do not
+    ///   emit unmatched build side rows. This buffers the probe side
+    ///   partition in memory, so it is only done if the statistics of the
+    ///   inputs show that the probe side is not larger than the build side.
+    ///
+    /// Neither is done if the output carries the row provenance columns.
+    pub fn with_semijoin_reduction(mut self, semijoin_reduction: bool) -> Self {
+        self.semijoin_reduction = semijoin_reduction;
+        self
+    }
 
-                OnceFut::new(collect_left_input(
-                    Some(partition),
-                    self.random_state.clone(),
-                    self.left.clone(),
-                    on_left.clone(),
-                    context.clone(),
-                    join_metrics.clone(),
-                    reservation,
-                ))
+    /// Logs a warning whenever an output partition makes no progress for
+    /// `stall_timeout` while waiting for one of its inputs, naming the join
+    /// side and input partition it waits for, and counts these warnings in the
+    /// `stall_warnings` metric. A partition input that never produces, e.g.
+    /// due to a bug upstream, otherwise hangs the query without any
+    /// diagnostics. The warning is repeated for as long as the stall lasts; it
+    /// does not affect the output.
+    ///
+    /// Applies to all modes, but is mainly useful in
+    /// [`PartitionMode::Partitioned`] mode, in which each output partition
+    /// waits for a single partition of both inputs.
+    pub fn with_stall_timeout(mut self, stall_timeout: Option<Duration>) -> Self {
+        self.stall_timeout = stall_timeout;
+        self
+    }
+
+    /// Publishes a [`RuntimeFilter`] over the build side values of every join
+    /// key to `sink` once the build side is complete, keyed by the id this
+    /// join gets in `sink` and the name of the probe side column it is
+    /// compared to, see [`Self::runtime_filter_keys`]. Scans of the probe side
+    /// subscribe to the registry to skip the rows, or row groups, that can not
+    /// have a match, which saves reading and joining most of a large probe
+    /// side that is joined with a selective build side, as a fact table is
+    /// joined with a filtered dimension table.
+    ///
+    /// The filters are declared to `sink` when the join is executed, before
+    /// its build side is read, so that a scan starting before the build side
+    /// is complete can wait for them, see [`RuntimeFilterRegistry`]. Only join
+    /// keys whose probe side expression is a column get a filter, and none are
+    /// published if the string keys are compared under a collation other than
+    /// [`Collation::Binary`], or the float keys under [`FloatKeyMode::Ieee`].
+    ///
+    /// This is only supported in [`PartitionMode::CollectLeft`] mode, in which
+    /// the build side is complete for all probe side partitions at once, and
+    /// for join types which do not emit unmatched probe side rows.
+    pub fn with_runtime_filter_sink(
+        self,
+        sink: Option<RuntimeFilterRegistry>,
+    ) -> Result<Self> {
+        let sink = sink.map(|sink| {
+            let join_id = sink.register_join();
+            (sink, join_id)
+        });
+        self.with_registered_runtime_filter_sink(sink)
+    }
+
+    /// Sets the registry the runtime filters are published to along with the
+    /// id of this join in it, see [`Self::with_runtime_filter_sink`]
+    fn with_registered_runtime_filter_sink(
+        mut self,
+        sink: Option<(RuntimeFilterRegistry, usize)>,
+    ) -> Result<Self> {
+        if sink.is_some() {
+            if self.mode != PartitionMode::CollectLeft {
+                return plan_err!(
+                    "Runtime filters are only supported in CollectLeft mode, got {:?}",
+                    self.mode
+                );
             }
-            PartitionMode::Auto => {
+            if !matches!(
+                self.join_type,
+                JoinType::Inner
+                    | JoinType::Left
+                    | JoinType::LeftSemi
+                    | JoinType::LeftAnti
+                    | JoinType::RightSemi
+            ) {
                 return plan_err!(
-                    "Invalid HashJoinExec, unsupported PartitionMode {:?} in execute()",
-                    PartitionMode::Auto
+                    "Runtime filters are not supported for {} joins",
+                    self.join_type
                 );
             }
-        };
+            // The filters would drop the unmatched probe rows to be flagged:
+            if self.match_flag {
+                return plan_err!(
+                    "Runtime filters are not supported by a HashJoinExec emitting the match flag"
+                );
+            }
+        }
+        self.runtime_filter_sink = sink;
+        Ok(self)
+    }
 
-        let batch_size = context.session_config().batch_size();
+    /// Captures a sample of up to `max_keys` distinct matched join key values,
+    /// and the number of their matches, shared by all output partitions. This
+    /// is meant for diagnosing joins producing more or fewer rows than
+    /// expected: the sample can be inspected through
+    /// [`Self::matched_keys_trace`] once the join ran.
+    ///
+    /// The key values are traced as compared, i.e. after applying the
+    /// collation and float key mode, and a match is counted for every pair of
+    /// rows joined on them that also passes the filter. Tracing is disabled
+    /// for a `max_keys` of 0, the default.
+    pub fn with_trace_matched_keys(mut self, max_keys: usize) -> Self {
+        self.matched_keys_trace = (max_keys > 0).then(|| MatchedKeysTrace::new(max_keys));
+        self
+    }
 
-        let reservation = MemoryConsumer::new(format!("HashJoinStream[{partition}]"))
-            .register(context.memory_pool());
+    /// Maximum number of distinct matched key values traced, 0 if disabled
+    fn trace_max_keys(&self) -> usize {
+        self.matched_keys_trace
+            .as_ref()
+            .map_or(0, MatchedKeysTrace::max_keys)
+    }
 
-        // we have the batches and the hash map with their keys. We can how create a stream
-        // over the right that uses this information to issue new batches.
-        let right_stream = self.right.execute(partition, context)?;
+    /// Returns the join keys a runtime filter is published for, see
+    /// [`Self::with_runtime_filter_sink`], as the build side expression and
+    /// the probe side column
+    fn runtime_filter_columns(&self) -> Vec<(PhysicalExprRef, Column)> {
+        let right_schema = self.right.schema();
+        self.on
+            .iter()
+            .filter_map(|(left_key, right_key)| {
+                let column = right_key.as_any().downcast_ref::<Column>()?;
+                let data_type = right_schema.field(column.index()).data_type();
+                supports_runtime_filter(data_type)
+                    .then(|| (left_key.clone(), column.clone()))
+            })
+            .collect()
+    }
 
-        // update column indices to reflect the projection
-        let column_indices_after_projection = match &self.projection {
-            Some(projection) => projection
-                .iter()
-                .map(|i| self.column_indices[*i].clone())
-                .collect(),
-            None => self.column_indices.clone(),
-        };
+    /// Returns whether the probe side is reduced by the build side join keys,
+    /// see [`Self::with_semijoin_reduction`]
+    fn reduces_probe_side(&self) -> bool {
+        self.semijoin_reduction
+            && !self.row_provenance
+            && !self.match_flag
+            && matches!(
+                self.join_type,
+                JoinType::Inner
+                    | JoinType::Left
+                    | JoinType::LeftSemi
+                    | JoinType::LeftAnti
+                    | JoinType::RightSemi
+            )
+    }
 
-        Ok(Box::pin(HashJoinStream {
-            schema: self.schema(),
-            on_left,
-            on_right,
-            filter: self.filter.clone(),
-            join_type: self.join_type,
-            right: right_stream,
-            column_indices: column_indices_after_projection,
-            random_state: self.random_state.clone(),
-            join_metrics,
-            null_equals_null: self.null_equals_null,
-            reservation,
-            state: HashJoinStreamState::WaitBuildSide,
-            build_side: BuildSide::Initial(BuildSideInitialState { left_fut }),
-            batch_size,
-            hashes_buffer: vec![],
-        }))
+    /// Returns whether only the first build side row of every distinct join key
+    /// is kept while building the hash table. This is the case for joins that
+    /// only check for the existence of a matching build side row, i.e. right
+    /// semi and anti joins without a filter: their output does not include
+    /// build side rows, so duplicates only take up memory. Left semi and anti
+    /// joins output the build side rows themselves, duplicates included.
+    ///
+    /// If the build side keys are hinted to be unique, they are kept as is, so
+    /// that the hint is still verified.
+    fn dedups_build_keys(&self) -> bool {
+        matches!(self.join_type, JoinType::RightSemi | JoinType::RightAnti)
+            && self.filter.is_none()
+            && !self.build_key_unique
     }
 
-    fn metrics(&self) -> Option<MetricsSet> {
-        Some(self.metrics.clone_inner())
+    /// Returns whether the build side is reduced by the probe side join keys,
+    /// see [`Self::with_semijoin_reduction`]
+    fn reduces_build_side(&self) -> bool {
+        if !self.semijoin_reduction
+            || self.row_provenance
+            || self.mode != PartitionMode::Partitioned
+            || need_produce_result_in_final(self.join_type)
+        {
+            return false;
+        }
+        let (Ok(left_stats), Ok(right_stats)) =
+            (self.left.statistics(), self.right.statistics())
+        else {
+            return false;
+        };
+        matches!(
+            (left_stats.num_rows.get_value(), right_stats.num_rows.get_value()),
+            (Some(left_rows), Some(right_rows)) if right_rows <= left_rows
+        )
     }
 
-    fn statistics(&self) -> Result<Statistics> {
-        // TODO stats: it is not possible in general to know the output size of joins
-        // There are some special cases though, for example:
-        // - `A LEFT JOIN B ON A.col=B.col` with `COUNT_DISTINCT(B.col)=COUNT(B.col)`
-        let mut stats = estimate_join_statistics(
-            self.left.clone(),
-            self.right.clone(),
-            self.on.clone(),
-            &self.join_type,
-            &self.join_schema,
+    /// Casts each pair of join keys of different types, e.g. `Int32` and
+    /// `Int64`, to the narrowest type both can be cast to without losing
+    /// information before they are hashed and compared, so that equal values
+    /// match regardless of their types. Fails if a pair of keys has no such
+    /// type, e.g. `Int64` and `Float64`.
+    ///
+    /// Casting keys is only supported in [`PartitionMode::CollectLeft`] mode,
+    /// as the inputs of a partitioned join are hash partitioned on their
+    /// uncast keys.
+    pub fn with_key_cast(mut self, key_cast: bool) -> Result<Self> {
+        if key_cast {
+            let cast_types =
+                join_key_cast_types(&self.on, &self.left.schema(), &self.right.schema())?;
+            if cast_types.iter().any(Option::is_some)
+                && self.mode != PartitionMode::CollectLeft
+            {
+                return plan_err!(
+                    "Casting join keys is only supported in CollectLeft mode, got {:?}",
+                    self.mode
+                );
+            }
+        }
+        self.key_cast = key_cast;
+        Ok(self)
+    }
+
+    /// Builds the hash table from the hashes held by `build_hash_column` of the
+    /// left input instead of hashing its join keys, e.g. as already computed
+    /// by a hash repartitioning on the same keys upstream. This saves a
+    /// hashing pass over the build side.
+    ///
+    /// The column must be of type `UInt64` and hold, for every row, the hash
+    /// of its join keys as computed by [`create_hashes`] with the random state
+    /// of the join, which is the one used by
+    /// [`RepartitionExec`](crate::repartition::RepartitionExec). Only the
+    /// first row of every build batch is checked against its join keys; the
+    /// join fails if it does not match, or if the column holds nulls.
+    pub fn with_build_hash_column(
+        mut self,
+        build_hash_column: Option<Column>,
+    ) -> Result<Self> {
+        if let Some(column) = &build_hash_column {
+            let left_schema = self.left.schema();
+            let Some(field) = left_schema
+                .fields()
+                .get(column.index())
+                .filter(|field| field.name() == column.name())
+            else {
+                return plan_err!(
+                    "Build hash column {column} is not a column of the left input"
+                );
+            };
+            if field.data_type() != &DataType::UInt64 {
+                return plan_err!(
+                    "Build hash column {column} must be of type UInt64, got {}",
+                    field.data_type()
+                );
+            }
+        }
+        self.build_hash_column = build_hash_column;
+        Ok(self)
+    }
+
+    /// Sorts the output rows of every partition by `ordering` before they are
+    /// emitted, keeping only the first `fetch` rows if given. With `fetch`,
+    /// the rows are kept in a bounded heap as the join produces them, fusing
+    /// a top-k sort into the join, which is cheaper than a separate sort and
+    /// limit for small results. Without `fetch`, all output rows of the
+    /// partition are buffered and sorted once the join completes.
+    ///
+    /// `ordering` refers to the output columns, so it has to be set after any
+    /// output expressions, and is dropped by [`Self::with_projection`].
+    pub fn with_output_row_order_by(
+        mut self,
+        ordering: LexOrdering,
+        fetch: Option<usize>,
+    ) -> Result<Self> {
+        if ordering.is_empty() {
+            return plan_err!("The output row order of a HashJoinExec can not be empty");
+        }
+        let schema = self.schema();
+        for sort_expr in &ordering {
+            sort_expr.expr.data_type(&schema)?;
+        }
+        self.output_row_order = Some((ordering, fetch));
+        self.cache = Self::compute_properties(
+            &self.left,
+            &self.right,
+            self.join_schema.clone(),
+            self.join_type,
+            self.equivalence_keys(),
+            self.mode,
+            self.projection.as_ref(),
+            self.output_exprs.as_deref(),
+            self.row_provenance,
+            self.unmatched_marker,
+            self.match_flag,
+            self.output_row_ordering(),
         )?;
-        // Project statistics if there is a projection
-        if let Some(projection) = &self.projection {
-            stats.column_statistics = stats
-                .column_statistics
-                .into_iter()
-                .enumerate()
-                .filter(|(i, _)| projection.contains(i))
-                .map(|(_, s)| s)
-                .collect();
+        Ok(self)
+    }
+
+    /// Emits the output rows of every partition grouped by their probe side
+    /// join keys, i.e. the rows joined for a probe key contiguously, so that a
+    /// downstream operator can process them per key as they stream by without
+    /// sorting them first. The groups are emitted in the order their keys
+    /// first occur, the rows of a group in the order they were joined. The
+    /// unmatched build side rows of outer joins have no probe key and form a
+    /// group of their own.
+    ///
+    /// The output rows are buffered until the probe side is exhausted, bounded
+    /// by the memory reservation of the partition: if it can not grow, the
+    /// rows buffered so far are emitted, and the rows of keys occurring again
+    /// later form another group. As rows are reordered, the probe side must
+    /// neither be ordered nor unbounded. Left semi, anti and mark joins emit
+    /// no probe side rows, and are not supported.
+    pub fn with_group_by_probe_key(mut self, group_by_probe_key: bool) -> Result<Self> {
+        if group_by_probe_key {
+            if matches!(
+                self.join_type,
+                JoinType::LeftSemi | JoinType::LeftAnti | JoinType::LeftMark
+            ) {
+                return plan_err!(
+                    "Grouping the output by probe key is not supported for {} joins",
+                    self.join_type
+                );
+            }
+            if self.right.output_ordering().is_some()
+                || self.right.execution_mode().is_unbounded()
+            {
+                return plan_err!(
+                    "Grouping the output by probe key is not supported for an ordered or unbounded probe side"
+                );
+            }
         }
-        Ok(stats)
+        self.output_shaping.group_by_probe_key = group_by_probe_key;
+        Ok(self)
     }
-}
 
-/// Reads the left (build) side of the input, buffering it in memory, to build a
-/// hash table (`LeftJoinData`)
-async fn collect_left_input(
-    partition: Option<usize>,
-    random_state: RandomState,
-    left: Arc<dyn ExecutionPlan>,
-    on_left: Vec<PhysicalExprRef>,
-    context: Arc<TaskContext>,
-    metrics: BuildProbeJoinMetrics,
-    reservation: MemoryReservation,
-) -> Result<JoinLeftData> {
-    let schema = left.schema();
+    /// Sets all options shaping the output batches at once, validating them
+    /// like their individual builders, e.g. when the join is rebuilt
+    fn with_output_shaping(self, output_shaping: OutputShaping) -> Result<Self> {
+        let mut join = self.with_group_by_probe_key(output_shaping.group_by_probe_key)?;
+        join.output_shaping = output_shaping;
+        Ok(join)
+    }
 
-    let (left_input, left_input_partition) = if let Some(partition) = partition {
-        (left, partition)
-    } else if left.output_partitioning().partition_count() != 1 {
-        (Arc::new(CoalescePartitionsExec::new(left)) as _, 0)
-    } else {
-        (left, 0)
-    };
+    /// Lets at most `max_concurrency` output partitions produce a batch at
+    /// the same time, e.g. to bound the CPU used by a join on a shared
+    /// cluster. The other partitions wait until a running one has produced
+    /// its batch, so the number of output partitions and the results are
+    /// unchanged, while execution may take longer. The highest number of
+    /// partitions that produced a batch at once is reported by the
+    /// `peak_concurrent_partitions` metric. By default, all partitions run
+    /// concurrently.
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Result<Self> {
+        if max_concurrency == 0 {
+            return plan_err!("The maximum concurrency of HashJoinExec must be positive");
+        }
+        self.concurrency_limit = Some(ConcurrencyLimit::new(max_concurrency));
+        Ok(self)
+    }
 
-    // Depending on partition argument load single partition or whole left side in memory
-    let stream = left_input.execute(left_input_partition, context.clone())?;
+    /// The ordering the output rows are sorted in, if any, see
+    /// [`Self::with_output_row_order_by`]
+    fn output_row_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.output_row_order
+            .as_ref()
+            .map(|(ordering, _)| ordering.as_slice())
+    }
 
-    // This operation performs 2 steps at once:
-    // 1. creates a [JoinHashMap] of all batches from the stream
-    // 2. stores the batches in a vector.
-    let initial = (Vec::new(), 0, metrics, reservation);
-    let (batches, num_rows, metrics, mut reservation) = stream
-        .try_fold(initial, |mut acc, batch| async {
-            let batch_size = batch.get_array_memory_size();
-            // Reserve memory for incoming batch
-            acc.3.try_grow(batch_size)?;
-            // Update metrics
-            acc.2.build_mem_used.add(batch_size);
-            acc.2.build_input_batches.add(1);
-            acc.2.build_input_rows.add(batch.num_rows());
-            // Update rowcount
-            acc.1 += batch.num_rows();
-            // Push batch to output
-            acc.0.push(batch);
-            Ok(acc)
-        })
-        .await?;
+    /// The join keys the output equivalences are derived from. Join keys
+    /// compared under a non-binary collation or IEEE float semantics are not
+    /// equal in the output.
+    fn equivalence_keys(&self) -> &[(PhysicalExprRef, PhysicalExprRef)] {
+        match (self.collation, self.float_key_mode) {
+            (Collation::Binary, FloatKeyMode::TotalOrder) => &self.on,
+            _ => &[],
+        }
+    }
 
-    // Estimation of memory size, required for hashtable, prior to allocation.
-    // Final result can be verified using `RawTable.allocation_info()`
-    //
-    // For majority of cases hashbrown overestimates buckets qty to keep ~1/8 of them empty.
-    // This formula leads to overallocation for small tables (< 8 elements) but fine overall.
-    let estimated_buckets = (num_rows.checked_mul(8).ok_or_else(|| {
-        DataFusionError::Execution(
-            "usize overflow while estimating number of hasmap buckets".to_string(),
-        )
-    })? / 7)
-        .next_power_of_two();
-    // 16 bytes per `(u64, u64)`
-    // + 1 byte for each bucket
-    // + fixed size of JoinHashMap (RawTable + Vec)
-    let estimated_hastable_size =
-        16 * estimated_buckets + estimated_buckets + size_of::<JoinHashMap>();
+    /// Returns the future reading the left input until the strategy is decided
+    /// on, shared by all partitions, if the partitioning is adaptive and the
+    /// number of rows of the left input is estimated, see
+    /// [`Self::with_adaptive_partitioning`]. Failing to compute the statistics
+    /// is not an error, since they are only used as a hint here.
+    fn adaptive_build_sample(
+        &self,
+        on_left: &[PhysicalExprRef],
+        on_right: &[PhysicalExprRef],
+        context: &Arc<TaskContext>,
+    ) -> Option<OnceFut<BuildSample>> {
+        let factor = self.adaptive_partitioning?;
+        let estimated_rows = *self.left.statistics().ok()?.num_rows.get_value()?;
+        Some(self.build_sample.once(|| {
+            let reservation = MemoryConsumer::new("HashJoinBuildSample")
+                .register(context.memory_pool());
+            let switches = MetricBuilder::new(&self.metrics)
+                .global_counter("adaptive_partitioning_switches");
+            let runtime_filters = self
+                .runtime_filter_sink
+                .as_ref()
+                .map(|(sink, _)| (sink.clone(), self.runtime_filter_keys()));
+            sample_build_input(
+                self.left.clone(),
+                self.right.clone(),
+                on_left.to_vec(),
+                on_right.to_vec(),
+                estimated_rows.saturating_mul(factor),
+                context.clone(),
+                reservation,
+                switches,
+                runtime_filters,
+            )
+        }))
+    }
 
-    reservation.try_grow(estimated_hastable_size)?;
-    metrics.build_mem_used.add(estimated_hastable_size);
+    /// Whether the join keys are hashed and compared on their raw values
+    fn compares_raw_keys(&self) -> bool {
+        self.collation == Collation::Binary
+            && self.float_key_mode == FloatKeyMode::TotalOrder
+    }
 
-    let mut hashmap = JoinHashMap::with_capacity(num_rows);
-    let mut hashes_buffer = Vec::new();
-    let mut offset = 0;
+    /// left (build) side which gets hashed
+    pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.left
+    }
 
-    // Updating hashmap starting from the last batch
-    let batches_iter = batches.iter().rev();
-    for batch in batches_iter.clone() {
-        hashes_buffer.clear();
-        hashes_buffer.resize(batch.num_rows(), 0);
-        update_hash(
-            &on_left,
-            batch,
-            &mut hashmap,
-            offset,
-            &random_state,
-            &mut hashes_buffer,
-            0,
-            true,
-        )?;
-        offset += batch.num_rows();
+    /// right (probe) side which are filtered by the hash table
+    pub fn right(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.right
     }
-    // Merge all batches into a single batch, so we
-    // can directly index into the arrays
-    let single_batch = concat_batches(&schema, batches_iter)?;
-    let data = JoinLeftData::new(hashmap, single_batch, reservation);
 
-    Ok(data)
-}
+    /// Set of common columns used to join on
+    pub fn on(&self) -> &[(PhysicalExprRef, PhysicalExprRef)] {
+        &self.on
+    }
 
-/// Updates `hash_map` with new entries from `batch` evaluated against the expressions `on`
-/// using `offset` as a start value for `batch` row indices.
-///
-/// `fifo_hashmap` sets the order of iteration over `batch` rows while updating hashmap,
-/// which allows to keep either first (if set to true) or last (if set to false) row index
-/// as a chain head for rows with equal hash values.
-#[allow(clippy::too_many_arguments)]
-pub fn update_hash<T>(
-    on: &[PhysicalExprRef],
-    batch: &RecordBatch,
-    hash_map: &mut T,
-    offset: usize,
-    random_state: &RandomState,
-    hashes_buffer: &mut Vec<u64>,
-    deleted_offset: usize,
-    fifo_hashmap: bool,
-) -> Result<()>
-where
-    T: JoinHashMapType,
-{
-    // evaluate the keys
-    let keys_values = on
-        .iter()
-        .map(|c| c.evaluate(batch)?.into_array(batch.num_rows()))
-        .collect::<Result<Vec<_>>>()?;
+    /// Filters applied before join output
+    pub fn filter(&self) -> Option<&JoinFilter> {
+        self.filter.as_ref()
+    }
 
-    // calculate the hash values
-    let hash_values = create_hashes(&keys_values, random_state, hashes_buffer)?;
+    /// How the join is performed
+    pub fn join_type(&self) -> &JoinType {
+        &self.join_type
+    }
 
-    // For usual JoinHashmap, the implementation is void.
-    hash_map.extend_zero(batch.num_rows());
+    /// The partitioning mode of this hash join
+    pub fn partition_mode(&self) -> &PartitionMode {
+        &self.mode
+    }
 
-    // Updating JoinHashMap from hash values iterator
-    let hash_values_iter = hash_values
-        .iter()
-        .enumerate()
-        .map(|(i, val)| (i + offset, val));
+    /// The metrics of this join as a JSON object suitable for machine
+    /// ingestion, see [`JOIN_METRICS_JSON_VERSION`] for its stability
+    ///
+    /// [`JOIN_METRICS_JSON_VERSION`]: crate::joins::utils::JOIN_METRICS_JSON_VERSION
+    pub fn metrics_json(&self) -> serde_json::Value {
+        join_metrics_json(
+            self.name(),
+            &self.join_type,
+            Some(format!("{:?}", self.mode)),
+            &self.metrics,
+        )
+    }
 
-    if fifo_hashmap {
-        hash_map.update_from_iter(hash_values_iter.rev(), deleted_offset);
-    } else {
-        hash_map.update_from_iter(hash_values_iter, deleted_offset);
+    /// Get null_equals_null
+    pub fn null_equals_null(&self) -> bool {
+        self.null_equals_null
     }
 
-    Ok(())
-}
+    /// Maximum number of build side batches hashed concurrently
+    pub fn build_threads(&self) -> usize {
+        self.build_threads
+    }
 
-/// Represents build-side of hash join.
-enum BuildSide {
-    /// Indicates that build-side not collected yet
-    Initial(BuildSideInitialState),
-    /// Indicates that build-side data has been collected
-    Ready(BuildSideReadyState),
-}
+    /// Maximum number of build side batches read ahead while building the hash
+    /// table
+    pub fn build_prefetch(&self) -> usize {
+        self.build_prefetch
+    }
 
-/// Container for BuildSide::Initial related data
-struct BuildSideInitialState {
-    /// Future for building hash table from build-side input
-    left_fut: OnceFut<JoinLeftData>,
-}
+    /// Length above which a chain of build rows sharing a hash value is
+    /// indexed by its join keys, if limited
+    pub fn max_chain_length(&self) -> Option<usize> {
+        self.max_chain_length
+    }
 
-/// Container for BuildSide::Ready related data
-struct BuildSideReadyState {
-    /// Collected build-side data
-    left_data: Arc<JoinLeftData>,
-    /// Which build-side rows have been matched while creating output.
-    /// For some OUTER joins, we need to know which rows have not been matched
-    /// to produce the correct output.
-    visited_left_side: BooleanBufferBuilder,
-}
+    /// Number of rows of an output batch, if overriding the batch size of the
+    /// session
+    pub fn batch_size(&self) -> Option<usize> {
+        self.output_shaping.batch_size
+    }
 
-impl BuildSide {
-    /// Tries to extract BuildSideInitialState from BuildSide enum.
-    /// Returns an error if state is not Initial.
-    fn try_as_initial_mut(&mut self) -> Result<&mut BuildSideInitialState> {
-        match self {
-            BuildSide::Initial(state) => Ok(state),
-            _ => internal_err!("Expected build side in initial state"),
-        }
+    /// Factor by which the build side may exceed its estimated number of rows
+    /// before the join switches to partitioned execution, if adaptive
+    pub fn adaptive_partitioning(&self) -> Option<usize> {
+        self.adaptive_partitioning
     }
 
-    /// Tries to extract BuildSideReadyState from BuildSide enum.
-    /// Returns an error if state is not Ready.
-    fn try_as_ready(&self) -> Result<&BuildSideReadyState> {
-        match self {
-            BuildSide::Ready(state) => Ok(state),
-            _ => internal_err!("Expected build side in ready state"),
-        }
+    /// Whether the output carries the hidden row provenance columns
+    pub fn row_provenance(&self) -> bool {
+        self.row_provenance
     }
 
-    /// Tries to extract BuildSideReadyState from BuildSide enum.
-    /// Returns an error if state is not Ready.
-    fn try_as_ready_mut(&mut self) -> Result<&mut BuildSideReadyState> {
-        match self {
-            BuildSide::Ready(state) => Ok(state),
-            _ => internal_err!("Expected build side in ready state"),
-        }
+    /// Whether the output carries the hidden unmatched row marker column
+    pub fn unmatched_marker(&self) -> bool {
+        self.unmatched_marker
     }
-}
 
-/// Represents state of HashJoinStream
-///
-/// Expected state transitions performed by HashJoinStream are:
-///
-/// ```text
-///
-///       WaitBuildSide
-///             │
-///             ▼
-///  ┌─► FetchProbeBatch ───► ExhaustedProbeSide ───► Completed
-///  │          │
-///  │          ▼
-///  └─ ProcessProbeBatch
-///
-/// ```
-enum HashJoinStreamState {
-    /// Initial state for HashJoinStream indicating that build-side data not collected yet
-    WaitBuildSide,
-    /// Indicates that build-side has been collected, and stream is ready for fetching probe-side
-    FetchProbeBatch,
-    /// Indicates that non-empty batch has been fetched from probe-side, and is ready to be processed
-    ProcessProbeBatch(ProcessProbeBatchState),
-    /// Indicates that probe-side has been fully processed
-    ExhaustedProbeSide,
-    /// Indicates that HashJoinStream execution is completed
-    Completed,
-}
+    /// Whether all probe rows are emitted with the hidden match flag column
+    pub fn match_flag(&self) -> bool {
+        self.match_flag
+    }
 
-impl HashJoinStreamState {
-    /// Tries to extract ProcessProbeBatchState from HashJoinStreamState enum.
-    /// Returns an error if state is not ProcessProbeBatchState.
-    fn try_as_process_probe_batch_mut(&mut self) -> Result<&mut ProcessProbeBatchState> {
-        match self {
-            HashJoinStreamState::ProcessProbeBatch(state) => Ok(state),
-            _ => internal_err!("Expected hash join stream in ProcessProbeBatch state"),
-        }
+    /// The registry the runtime filters are published to, if any
+    pub fn runtime_filter_sink(&self) -> Option<&RuntimeFilterRegistry> {
+        self.runtime_filter_sink.as_ref().map(|(sink, _)| sink)
     }
-}
-
-/// Container for HashJoinStreamState::ProcessProbeBatch related data
-struct ProcessProbeBatchState {
-    /// Current probe-side batch
-    batch: RecordBatch,
-    /// Starting offset for JoinHashMap lookups
-    offset: JoinHashMapOffset,
-    /// Max joined probe-side index from current batch
-    joined_probe_idx: Option<usize>,
-}
 
-impl ProcessProbeBatchState {
-    fn advance(&mut self, offset: JoinHashMapOffset, joined_probe_idx: Option<usize>) {
-        self.offset = offset;
-        if joined_probe_idx.is_some() {
-            self.joined_probe_idx = joined_probe_idx;
-        }
+    /// The keys of the runtime filters published to
+    /// [`Self::runtime_filter_sink`], which scans of the probe side subscribe
+    /// to, see [`Self::with_runtime_filter_sink`]
+    pub fn runtime_filter_keys(&self) -> Vec<RuntimeFilterKey> {
+        let Some((_, join_id)) = &self.runtime_filter_sink else {
+            return vec![];
+        };
+        self.runtime_filter_columns()
+            .into_iter()
+            .map(|(_, column)| RuntimeFilterKey::new(*join_id, column.name()))
+            .collect()
     }
-}
 
-/// [`Stream`] for [`HashJoinExec`] that does the actual join.
-///
-/// This stream:
-///
-/// 1. Reads the entire left input (build) and constructs a hash table
-///
-/// 2. Streams [RecordBatch]es as they arrive from the right input (probe) and joins
-/// them with the contents of the hash table
-struct HashJoinStream {
-    /// Input schema
-    schema: Arc<Schema>,
-    /// equijoin columns from the left (build side)
-    on_left: Vec<PhysicalExprRef>,
-    /// equijoin columns from the right (probe side)
-    on_right: Vec<PhysicalExprRef>,
-    /// optional join filter
-    filter: Option<JoinFilter>,
-    /// type of the join (left, right, semi, etc)
-    join_type: JoinType,
-    /// right (probe) input
-    right: SendableRecordBatchStream,
-    /// Random state used for hashing initialization
-    random_state: RandomState,
-    /// Metrics
-    join_metrics: BuildProbeJoinMetrics,
-    /// Information of index and left / right placement of columns
-    column_indices: Vec<ColumnIndex>,
-    /// If null_equals_null is true, null == null else null != null
-    null_equals_null: bool,
-    /// Memory reservation
-    reservation: MemoryReservation,
-    /// State of the stream
-    state: HashJoinStreamState,
-    /// Build side
-    build_side: BuildSide,
-    /// Maximum output batch size
-    batch_size: usize,
-    /// Scratch space for computing hashes
-    hashes_buffer: Vec<u64>,
-}
+    /// Sample of the matched join key values, if traced
+    pub fn matched_keys_trace(&self) -> Option<&MatchedKeysTrace> {
+        self.matched_keys_trace.as_ref()
+    }
 
-impl RecordBatchStream for HashJoinStream {
-    fn schema(&self) -> SchemaRef {
-        self.schema.clone()
+    /// Whether the join keys are compared in the order of their selectivity
+    pub fn key_reordering(&self) -> bool {
+        self.reorder_keys
     }
-}
 
-/// Executes lookups by hash against JoinHashMap and resolves potential
-/// hash collisions.
-/// Returns build/probe indices satisfying the equality condition, along with
-/// (optional) starting point for next iteration.
-///
-/// # Example
-///
-/// For `LEFT.b1 = RIGHT.b2`:
-/// LEFT (build) Table:
-/// ```text
-///  a1  b1  c1
-///  1   1   10
-///  3   3   30
-///  5   5   50
-///  7   7   70
-///  9   8   90
-///  11  8   110
-///  13   10  130
-/// ```
-///
-/// RIGHT (probe) Table:
-/// ```text
-///  a2   b2  c2
-///  2    2   20
-///  4    4   40
-///  6    6   60
-///  8    8   80
-/// 10   10  100
-/// 12   10  120
-/// ```
-///
-/// The result is
-/// ```text
-/// "+----+----+-----+----+----+-----+",
-/// "| a1 | b1 | c1  | a2 | b2 | c2  |",
-/// "+----+----+-----+----+----+-----+",
-/// "| 9  | 8  | 90  | 8  | 8  | 80  |",
-/// "| 11 | 8  | 110 | 8  | 8  | 80  |",
-/// "| 13 | 10 | 130 | 10 | 10 | 100 |",
-/// "| 13 | 10 | 130 | 12 | 10 | 120 |",
-/// "+----+----+-----+----+----+-----+"
-/// ```
-///
-/// And the result of build and probe indices are:
-/// ```text
-/// Build indices: 4, 5, 6, 6
-/// Probe indices: 3, 3, 4, 5
-/// ```
-#[allow(clippy::too_many_arguments)]
-fn lookup_join_hashmap(
-    build_hashmap: &JoinHashMap,
-    build_input_buffer: &RecordBatch,
-    probe_batch: &RecordBatch,
-    build_on: &[PhysicalExprRef],
-    probe_on: &[PhysicalExprRef],
-    null_equals_null: bool,
-    hashes_buffer: &[u64],
-    limit: usize,
-    offset: JoinHashMapOffset,
-) -> Result<(UInt64Array, UInt32Array, Option<JoinHashMapOffset>)> {
-    let keys_values = probe_on
-        .iter()
-        .map(|c| c.evaluate(probe_batch)?.into_array(probe_batch.num_rows()))
-        .collect::<Result<Vec<_>>>()?;
-    let build_join_values = build_on
-        .iter()
-        .map(|c| {
-            c.evaluate(build_input_buffer)?
-                .into_array(build_input_buffer.num_rows())
-        })
-        .collect::<Result<Vec<_>>>()?;
+    /// Whether output is emitted in smaller, more frequent batches
+    pub fn low_latency(&self) -> bool {
+        self.output_shaping.low_latency
+    }
 
-    let (mut probe_builder, mut build_builder, next_offset) = build_hashmap
-        .get_matched_indices_with_limit_offset(hashes_buffer, None, limit, offset);
+    /// Whether the null arrays padding unmatched rows are reused across
+    /// output batches
+    pub fn null_buffer_reuse(&self) -> bool {
+        self.null_buffer_reuse
+    }
 
-    let build_indices: UInt64Array =
-        PrimitiveArray::new(build_builder.finish().into(), None);
-    let probe_indices: UInt32Array =
-        PrimitiveArray::new(probe_builder.finish().into(), None);
+    /// Upper bound of the bytes taken by the values of an output batch, if any
+    pub fn max_output_batch_bytes(&self) -> Option<usize> {
+        self.output_shaping.max_output_batch_bytes
+    }
 
-    let (build_indices, probe_indices) = equal_rows_arr(
-        &build_indices,
-        &probe_indices,
-        &build_join_values,
-        &keys_values,
-        null_equals_null,
-    )?;
+    /// Upper bound of the estimated serialized size of an output batch, if any
+    pub fn max_serialized_batch_bytes(&self) -> Option<usize> {
+        self.output_shaping.max_serialized_batch_bytes
+    }
 
-    Ok((build_indices, probe_indices, next_offset))
-}
+    /// Whether the probe side only conjuncts of the filter are applied before
+    /// the hash lookup, `None` if this is decided while probing
+    pub fn probe_filter_first(&self) -> Option<bool> {
+        self.probe_filter_first
+    }
 
-// version of eq_dyn supporting equality on null arrays
-fn eq_dyn_null(
-    left: &dyn Array,
-    right: &dyn Array,
-    null_equals_null: bool,
-) -> Result<BooleanArray, ArrowError> {
-    match (left.data_type(), right.data_type()) {
-        _ if null_equals_null => not_distinct(&left, &right),
-        _ => eq(&left, &right),
+    /// Whether the build side join keys are hinted to be unique
+    pub fn build_key_unique(&self) -> bool {
+        self.build_key_unique
     }
-}
 
-pub fn equal_rows_arr(
-    indices_left: &UInt64Array,
-    indices_right: &UInt32Array,
-    left_arrays: &[ArrayRef],
-    right_arrays: &[ArrayRef],
-    null_equals_null: bool,
-) -> Result<(UInt64Array, UInt32Array)> {
-    let mut iter = left_arrays.iter().zip(right_arrays.iter());
+    /// Maximum number of output rows across all partitions, if limited
+    pub fn max_output_rows(&self) -> Option<usize> {
+        self.output_row_limit.as_ref().map(|limit| limit.max_rows())
+    }
 
-    let (first_left, first_right) = iter.next().ok_or_else(|| {
-        DataFusionError::Internal(
-            "At least one array should be provided for both left and right".to_string(),
-        )
-    })?;
+    /// Whether zero-row batches are dropped from the output
+    pub fn skip_empty_output_batches(&self) -> bool {
+        self.output_shaping.skip_empty_batches
+    }
 
-    let arr_left = take(first_left.as_ref(), indices_left, None)?;
-    let arr_right = take(first_right.as_ref(), indices_right, None)?;
+    /// Collation the string join keys are compared under
+    pub fn collation(&self) -> Collation {
+        self.collation
+    }
 
-    let mut equal: BooleanArray = eq_dyn_null(&arr_left, &arr_right, null_equals_null)?;
+    /// The semantics float join keys are compared under
+    pub fn float_key_mode(&self) -> FloatKeyMode {
+        self.float_key_mode
+    }
 
-    // Use map and try_fold to iterate over the remaining pairs of arrays.
-    // In each iteration, take is used on the pair of arrays and their equality is determined.
-    // The results are then folded (combined) using the and function to get a final equality result.
-    equal = iter
-        .map(|(left, right)| {
-            let arr_left = take(left.as_ref(), indices_left, None)?;
-            let arr_right = take(right.as_ref(), indices_right, None)?;
-            eq_dyn_null(arr_left.as_ref(), arr_right.as_ref(), null_equals_null)
-        })
-        .try_fold(equal, |acc, equal2| and(&acc, &equal2?))?;
+    /// Schema of the join columns, before any projection or output
+    /// expressions are applied
+    pub fn join_schema(&self) -> &SchemaRef {
+        &self.join_schema
+    }
 
-    let filter_builder = FilterBuilder::new(&equal).optimize().build();
+    /// Expressions computing the output columns, if any
+    pub fn output_exprs(&self) -> Option<&[(PhysicalExprRef, String)]> {
+        self.output_exprs.as_deref()
+    }
 
-    let left_filtered = filter_builder.filter(indices_left)?;
-    let right_filtered = filter_builder.filter(indices_right)?;
+    /// Whether the inputs are reduced to the rows whose join keys may occur in
+    /// the other input
+    pub fn semijoin_reduction(&self) -> bool {
+        self.semijoin_reduction
+    }
 
-    Ok((
-        downcast_array(left_filtered.as_ref()),
-        downcast_array(right_filtered.as_ref()),
-    ))
-}
+    /// Whether join keys of different types are cast to a common type
+    pub fn key_cast(&self) -> bool {
+        self.key_cast
+    }
 
-impl HashJoinStream {
-    /// Separate implementation function that unpins the [`HashJoinStream`] so
-    /// that partial borrows work correctly
-    fn poll_next_impl(
-        &mut self,
-        cx: &mut std::task::Context<'_>,
-    ) -> Poll<Option<Result<RecordBatch>>> {
-        loop {
-            return match self.state {
-                HashJoinStreamState::WaitBuildSide => {
-                    handle_state!(ready!(self.collect_build_side(cx)))
-                }
-                HashJoinStreamState::FetchProbeBatch => {
-                    handle_state!(ready!(self.fetch_probe_batch(cx)))
-                }
-                HashJoinStreamState::ProcessProbeBatch(_) => {
-                    handle_state!(self.process_probe_batch())
-                }
-                HashJoinStreamState::ExhaustedProbeSide => {
-                    handle_state!(self.process_unmatched_build_batch())
-                }
-                HashJoinStreamState::Completed => Poll::Ready(None),
-            };
-        }
+    /// How long an output partition may wait for its inputs before a warning
+    /// is logged, if at all
+    pub fn stall_timeout(&self) -> Option<Duration> {
+        self.stall_timeout
     }
 
-    /// Collects build-side data by polling `OnceFut` future from initialized build-side
-    ///
-    /// Updates build-side to `Ready`, and state to `FetchProbeSide`
-    fn collect_build_side(
-        &mut self,
-        cx: &mut std::task::Context<'_>,
-    ) -> Poll<Result<StatefulStreamResult<Option<RecordBatch>>>> {
-        let build_timer = self.join_metrics.build_time.timer();
-        // build hash table from left (build) side, if not yet done
-        let left_data = ready!(self
-            .build_side
-            .try_as_initial_mut()?
-            .left_fut
-            .get_shared(cx))?;
-        build_timer.done();
+    /// Column of the left input holding the hashes of its join keys, if any
+    pub fn build_hash_column(&self) -> Option<&Column> {
+        self.build_hash_column.as_ref()
+    }
 
-        // Reserving memory for visited_left_side bitmap in case it hasn't been initialized yet
-        // and join_type requires to store it
-        if need_produce_result_in_final(self.join_type) {
-            // TODO: Replace `ceil` wrapper with stable `div_cell` after
-            // https://github.com/rust-lang/rust/issues/88581
-            let visited_bitmap_size = bit_util::ceil(left_data.num_rows(), 8);
-            self.reservation.try_grow(visited_bitmap_size)?;
-            self.join_metrics.build_mem_used.add(visited_bitmap_size);
-        }
+    /// The ordering the output rows are sorted in and the number of rows kept,
+    /// if the output is sorted
+    pub fn output_row_order_by(&self) -> Option<(&[PhysicalSortExpr], Option<usize>)> {
+        self.output_row_order
+            .as_ref()
+            .map(|(ordering, fetch)| (ordering.as_slice(), *fetch))
+    }
 
-        let visited_left_side = if need_produce_result_in_final(self.join_type) {
-            let num_rows = left_data.num_rows();
-            // Some join types need to track which row has be matched or unmatched:
-            // `left semi` join:  need to use the bitmap to produce the matched row in the left side
-            // `left` join:       need to use the bitmap to produce the unmatched row in the left side with null
-            // `left anti` join:  need to use the bitmap to produce the unmatched row in the left side
-            // `full` join:       need to use the bitmap to produce the unmatched row in the left side with null
-            let mut buffer = BooleanBufferBuilder::new(num_rows);
-            buffer.append_n(num_rows, false);
-            buffer
-        } else {
-            BooleanBufferBuilder::new(0)
-        };
+    /// Whether the output rows are emitted grouped by their probe side join
+    /// keys
+    pub fn group_by_probe_key(&self) -> bool {
+        self.output_shaping.group_by_probe_key
+    }
 
-        self.state = HashJoinStreamState::FetchProbeBatch;
-        self.build_side = BuildSide::Ready(BuildSideReadyState {
-            left_data,
-            visited_left_side,
-        });
+    /// Maximum number of partitions producing a batch at once, if limited
+    pub fn max_concurrency(&self) -> Option<usize> {
+        self.concurrency_limit
+            .as_ref()
+            .map(|limit| limit.max_concurrency())
+    }
 
-        Poll::Ready(Ok(StatefulStreamResult::Continue))
+    /// Returns the order in which the join keys are compared, as indices into
+    /// [`Self::on`]: by decreasing number of distinct values if key reordering
+    /// is enabled and the input statistics provide it for all keys, and in the
+    /// given order otherwise.
+    fn join_key_order(&self) -> Vec<usize> {
+        let mut key_order = (0..self.on.len()).collect::<Vec<_>>();
+        if !self.reorder_keys {
+            return key_order;
+        }
+        let (Ok(left_stats), Ok(right_stats)) =
+            (self.left.statistics(), self.right.statistics())
+        else {
+            return key_order;
+        };
+        let distinct_counts = self
+            .on
+            .iter()
+            .map(|(left_key, right_key)| {
+                let left_count = key_distinct_count(left_key, &left_stats)?;
+                let right_count = key_distinct_count(right_key, &right_stats)?;
+                Some(left_count.max(right_count))
+            })
+            .collect::<Option<Vec<_>>>();
+        if let Some(distinct_counts) = distinct_counts {
+            // The sort is stable, so keys with equal counts keep their order:
+            key_order.sort_by_key(|i| std::cmp::Reverse(distinct_counts[*i]));
+        }
+        key_order
     }
 
-    /// Fetches next batch from probe-side
-    ///
-    /// If non-empty batch has been fetched, updates state to `ProcessProbeBatchState`,
-    /// otherwise updates state to `ExhaustedProbeSide`
-    fn fetch_probe_batch(
-        &mut self,
-        cx: &mut std::task::Context<'_>,
-    ) -> Poll<Result<StatefulStreamResult<Option<RecordBatch>>>> {
-        match ready!(self.right.poll_next_unpin(cx)) {
-            None => {
-                self.state = HashJoinStreamState::ExhaustedProbeSide;
-            }
-            Some(Ok(batch)) => {
-                // Precalculate hash values for fetched batch
-                let keys_values = self
-                    .on_right
-                    .iter()
-                    .map(|c| c.evaluate(&batch)?.into_array(batch.num_rows()))
-                    .collect::<Result<Vec<_>>>()?;
+    /// Calculate order preservation flags for this hash join.
+    fn maintains_input_order(join_type: JoinType) -> Vec<bool> {
+        vec![
+            false,
+            matches!(
+                join_type,
+                JoinType::Inner
+                    | JoinType::RightAnti
+                    | JoinType::RightSemi
+                    | JoinType::RightMark
+            ),
+        ]
+    }
 
-                self.hashes_buffer.clear();
-                self.hashes_buffer.resize(batch.num_rows(), 0);
-                create_hashes(&keys_values, &self.random_state, &mut self.hashes_buffer)?;
+    /// Get probe side information for the hash join.
+    pub fn probe_side() -> JoinSide {
+        // In current implementation right side is always probe side.
+        JoinSide::Right
+    }
 
-                self.join_metrics.input_batches.add(1);
-                self.join_metrics.input_rows.add(batch.num_rows());
+    /// Return whether the join contains a projection
+    pub fn contain_projection(&self) -> bool {
+        self.projection.is_some()
+    }
 
-                self.state =
-                    HashJoinStreamState::ProcessProbeBatch(ProcessProbeBatchState {
-                        batch,
-                        offset: (0, None),
-                        joined_probe_idx: None,
-                    });
-            }
-            Some(Err(err)) => return Poll::Ready(Err(err)),
+    /// Return new instance of [HashJoinExec] with the given projection.
+    pub fn with_projection(&self, projection: Option<Vec<usize>>) -> Result<Self> {
+        if self.row_provenance && projection.is_some() {
+            return plan_err!(
+                "Projections can not be applied to a HashJoinExec emitting row provenance columns"
+            );
+        }
+        if self.unmatched_marker && projection.is_some() {
+            return plan_err!(
+                "Projections can not be applied to a HashJoinExec emitting the unmatched row marker"
+            );
+        }
+        if self.match_flag && projection.is_some() {
+            return plan_err!(
+                "Projections can not be applied to a HashJoinExec emitting the match flag"
+            );
+        }
+        if self.output_exprs.is_some() && projection.is_some() {
+            return plan_err!(
+                "Projections can not be applied to a HashJoinExec with output expressions"
+            );
+        }
+        //  check if the projection is valid
+        can_project(&self.schema(), projection.as_ref())?;
+        let projection = match projection {
+            Some(projection) => match &self.projection {
+                Some(p) => Some(projection.iter().map(|i| p[*i]).collect()),
+                None => Some(projection),
+            },
+            None => None,
         };
-
-        Poll::Ready(Ok(StatefulStreamResult::Continue))
+        let join = Self::try_new(
+            self.left.clone(),
+            self.right.clone(),
+            self.on.clone(),
+            self.filter.clone(),
+            &self.join_type,
+            projection,
+            self.mode,
+            self.null_equals_null,
+        )?
+        .with_row_provenance(self.row_provenance)?
+        .with_unmatched_marker(self.unmatched_marker)?
+        .with_match_flag(self.match_flag)?
+        .with_collation(self.collation)?
+        .with_float_key_mode(self.float_key_mode)?
+        .with_key_cast(self.key_cast)?
+        .with_build_hash_column(self.build_hash_column.clone())?
+        .with_registered_runtime_filter_sink(self.runtime_filter_sink.clone())?
+        .with_adaptive_partitioning(self.adaptive_partitioning.unwrap_or(0))?
+        .with_output_shaping(self.output_shaping.clone())?;
+        let mut join = match &self.output_exprs {
+            Some(output_exprs) => join.with_output_exprs(output_exprs.clone())?,
+            None => join,
+        };
+        if let Some(max_concurrency) = self.max_concurrency() {
+            join = join.with_max_concurrency(max_concurrency)?;
+        }
+        join.output_row_limit = self
+            .output_row_limit
+            .as_ref()
+            .map(|limit| OutputRowLimit::new(limit.max_rows()));
+        join.max_chain_length = self.max_chain_length;
+        join.probe_filter_first = self.probe_filter_first;
+        Ok(join
+            .with_key_reordering(self.reorder_keys)
+            .with_null_buffer_reuse(self.null_buffer_reuse)
+            .with_build_key_unique(self.build_key_unique)
+            .with_build_threads(self.build_threads)
+            .with_build_prefetch(self.build_prefetch)
+            .with_semijoin_reduction(self.semijoin_reduction)
+            .with_stall_timeout(self.stall_timeout)
+            .with_trace_matched_keys(self.trace_max_keys()))
     }
 
-    /// Joins current probe batch with build-side data and produces batch with matched output
-    ///
-    /// Updates state to `FetchProbeBatch`
-    fn process_probe_batch(
-        &mut self,
-    ) -> Result<StatefulStreamResult<Option<RecordBatch>>> {
-        let state = self.state.try_as_process_probe_batch_mut()?;
-        let build_side = self.build_side.try_as_ready_mut()?;
-
-        let timer = self.join_metrics.join_time.timer();
+    /// Return new instance of [HashJoinExec] emitting the output columns named
+    /// `names`, in that order, instead of the left columns followed by the
+    /// right ones. Every name must refer to exactly one column of the current
+    /// output schema, and can be requested only once.
+    pub fn with_output_order(&self, names: &[&str]) -> Result<Self> {
+        let projection = output_order_indices(&self.schema(), names)?;
+        self.with_projection(Some(projection))
+    }
 
-        // get the matched by join keys indices
-        let (left_indices, right_indices, next_offset) = lookup_join_hashmap(
-            build_side.left_data.hash_map(),
-            build_side.left_data.batch(),
-            &state.batch,
-            &self.on_left,
-            &self.on_right,
-            self.null_equals_null,
-            &self.hashes_buffer,
-            self.batch_size,
-            state.offset,
-        )?;
+    /// Returns whether the statistics of the probe side guarantee that it
+    /// produces no rows. Failing to compute the statistics is not an error,
+    /// since they are only used as a hint here.
+    fn probe_side_is_empty(&self) -> bool {
+        self.right
+            .statistics()
+            .map(|stats| stats.num_rows == Precision::Exact(0))
+            .unwrap_or(false)
+    }
 
-        // apply join filter if exists
-        let (left_indices, right_indices) = if let Some(filter) = &self.filter {
-            apply_join_filter_to_indices(
-                build_side.left_data.batch(),
-                &state.batch,
-                left_indices,
-                right_indices,
-                filter,
-                JoinSide::Left,
-            )?
+    /// Executes the join for `partition` when the probe side is known to be
+    /// empty. The build side input is streamed through, and the probe side
+    /// columns are filled with nulls. The output is split like that of a
+    /// regular execution, see [`Self::with_low_latency`],
+    /// [`Self::with_max_output_batch_bytes`] and
+    /// [`Self::with_max_serialized_batch_bytes`].
+    fn execute_with_empty_probe_side(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let chunk_size = self.output_shaping.chunk_size(&context);
+        let output_byte_budget = self.output_shaping.byte_budget();
+        let max_serialized_batch_bytes = self.output_shaping.max_serialized_batch_bytes;
+        let left_stream = if self.mode == PartitionMode::Partitioned {
+            self.left.execute(partition, context)?
+        } else if self.left.output_partitioning().partition_count() != 1 {
+            CoalescePartitionsExec::new(self.left.clone()).execute(0, context)?
         } else {
-            (left_indices, right_indices)
+            self.left.execute(0, context)?
         };
 
-        // mark joined left-side indices as visited, if required by join type
-        if need_produce_result_in_final(self.join_type) {
-            left_indices.iter().flatten().for_each(|x| {
-                build_side.visited_left_side.set_bit(x as usize, true);
-            });
-        }
+        let column_indices: Vec<ColumnIndex> = match &self.projection {
+            Some(projection) => projection
+                .iter()
+                .map(|i| self.column_indices[*i].clone())
+                .collect(),
+            None => self.column_indices.clone(),
+        };
+        // Null columns for the probe side are built once, and sliced for every
+        // chunk of rows:
+        let right_schema = self.right.schema();
+        let null_columns = column_indices
+            .iter()
+            .map(|column_index| {
+                (column_index.side == JoinSide::Right).then(|| {
+                    new_null_join_array(
+                        right_schema.field(column_index.index).data_type(),
+                        chunk_size,
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        // Build side rows are paired with the missing row of an empty probe
+        // batch when split by the output byte budget:
+        let probe_batch = new_empty_join_batch(right_schema)?;
+
+        // The output expressions, if any, are evaluated on the join columns:
+        let schema = match &self.output_exprs {
+            Some(_) => self.join_schema.clone(),
+            None => self.schema(),
+        };
+        let join_metrics = BuildProbeJoinMetrics::new(partition, &self.metrics);
+        let output_schema = schema.clone();
+        let row_provenance = self.row_provenance;
+        let unmatched_marker = self.unmatched_marker;
+        let mut build_offset = 0;
+        let stream = left_stream
+            .map(move |batch| -> Result<_> {
+                let batch = batch?;
+                let build_output_batch = |rows: Range<usize>| -> Result<RecordBatch> {
+                    let num_rows = rows.len();
+                    // Slices share the buffers of the build side batch, which
+                    // are serialized in full, so the rows are copied if the
+                    // serialized size is limited:
+                    let build_indices = max_serialized_batch_bytes.map(|_| {
+                        UInt64Array::from_iter_values(rows.start as u64..rows.end as u64)
+                    });
+                    let mut columns = column_indices
+                        .iter()
+                        .zip(null_columns.iter())
+                        .map(|(column_index, null_column)| {
+                            Ok(match null_column {
+                                Some(array) if num_rows <= array.len() => {
+                                    array.slice(0, num_rows)
+                                }
+                                Some(array) => {
+                                    new_null_join_array(array.data_type(), num_rows)
+                                }
+                                None => {
+                                    let column = batch.column(column_index.index);
+                                    match &build_indices {
+                                        Some(indices) => take(column, indices, None)?,
+                                        None => column.slice(rows.start, num_rows),
+                                    }
+                                }
+                            })
+                        })
+                        .collect::<Result<Vec<ArrayRef>>>()?;
+                    if row_provenance {
+                        let build_rows = (build_offset + rows.start) as u64
+                            ..(build_offset + rows.end) as u64;
+                        columns.push(Arc::new(UInt64Array::from_iter_values(build_rows)));
+                        columns.push(new_null_array(&DataType::UInt32, num_rows));
+                    }
+                    if unmatched_marker {
+                        // Without probe side rows, no build side row is matched:
+                        columns.push(Arc::new(BooleanArray::from(vec![true; num_rows])));
+                    }
+                    let options =
+                        RecordBatchOptions::new().with_row_count(Some(num_rows));
+                    Ok(RecordBatch::try_new_with_options(
+                        output_schema.clone(),
+                        columns,
+                        &options,
+                    )?)
+                };
 
-        // The goals of index alignment for different join types are:
-        //
-        // 1) Right & FullJoin -- to append all missing probe-side indices between
-        //    previous (excluding) and current joined indices.
-        // 2) SemiJoin -- deduplicate probe indices in range between previous
-        //    (excluding) and current joined indices.
-        // 3) AntiJoin -- return only missing indices in range between
-        //    previous and current joined indices.
-        //    Inclusion/exclusion of the indices themselves don't matter
-        //
-        // As a summary -- alignment range can be produced based only on
-        // joined (matched with filters applied) probe side indices, excluding starting one
-        // (left from previous iteration).
+                let num_rows = batch.num_rows();
+                let mut results = vec![];
+                for start in (0..num_rows).step_by(chunk_size) {
+                    let rows = start..(start + chunk_size).min(num_rows);
+                    let ranges = match output_byte_budget {
+                        Some(byte_budget) => {
+                            let build_indices = UInt64Array::from_iter_values(
+                                rows.start as u64..rows.end as u64,
+                            );
+                            let probe_indices = UInt32Array::new_null(rows.len());
+                            split_indices_by_byte_budget(
+                                &batch,
+                                &probe_batch,
+                                &build_indices,
+                                &probe_indices,
+                                &column_indices,
+                                JoinSide::Left,
+                                byte_budget,
+                            )
+                            .into_iter()
+                            .map(|range| start + range.start..start + range.end)
+                            .collect()
+                        }
+                        None => vec![rows],
+                    };
+                    let mut ranges = VecDeque::from(ranges);
+                    while let Some(rows) = ranges.pop_front() {
+                        let result = build_output_batch(rows.clone())?;
+                        if let Some(max_bytes) = max_serialized_batch_bytes {
+                            let batch_bytes = estimated_ipc_size(&result);
+                            if batch_bytes > max_bytes {
+                                let parts = split_serialized_rows(
+                                    rows.len(),
+                                    batch_bytes,
+                                    max_bytes,
+                                )?;
+                                for part in parts.into_iter().rev() {
+                                    ranges.push_front(
+                                        rows.start + part.start..rows.start + part.end,
+                                    );
+                                }
+                                continue;
+                            }
+                        }
+                        join_metrics.output_batches.add(1);
+                        join_metrics.output_rows.add(result.num_rows());
+                        results.push(Ok(result));
+                    }
+                }
+                build_offset += num_rows;
+                Ok(futures::stream::iter(results))
+            })
+            .try_flatten();
 
-        // if any rows have been joined -- get last joined probe-side (right) row
-        // it's important that index counts as "joined" after hash collisions checks
-        // and join filters applied.
-        let last_joined_right_idx = match right_indices.len() {
-            0 => None,
-            n => Some(right_indices.value(n - 1) as usize),
-        };
+        Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+    }
 
-        // Calculate range and perform alignment.
-        // In case probe batch has been processed -- align all remaining rows.
-        let index_alignment_range_start = state.joined_probe_idx.map_or(0, |v| v + 1);
-        let index_alignment_range_end = if next_offset.is_none() {
-            state.batch.num_rows()
-        } else {
-            last_joined_right_idx.map_or(0, |v| v + 1)
+    /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
+    #[allow(clippy::too_many_arguments)]
+    fn compute_properties(
+        left: &Arc<dyn ExecutionPlan>,
+        right: &Arc<dyn ExecutionPlan>,
+        schema: SchemaRef,
+        join_type: JoinType,
+        on: JoinOnRef,
+        mode: PartitionMode,
+        projection: Option<&Vec<usize>>,
+        output_exprs: Option<&[(PhysicalExprRef, String)]>,
+        row_provenance: bool,
+        unmatched_marker: bool,
+        match_flag: bool,
+        output_ordering: Option<&[PhysicalSortExpr]>,
+    ) -> Result<PlanProperties> {
+        // The row provenance columns, the unmatched row marker and the match
+        // flag follow the join columns, and do not take part in any
+        // equivalences or orderings:
+        let output_schema = |schema: SchemaRef| {
+            let schema = if row_provenance {
+                Arc::new(with_row_provenance_fields(&schema))
+            } else {
+                schema
+            };
+            let schema = if unmatched_marker {
+                Arc::new(with_unmatched_marker_field(&schema))
+            } else {
+                schema
+            };
+            if match_flag {
+                Arc::new(with_match_flag_field(&schema))
+            } else {
+                schema
+            }
         };
 
-        let (left_indices, right_indices) = adjust_indices_by_join_type(
-            left_indices,
-            right_indices,
-            index_alignment_range_start..index_alignment_range_end,
-            self.join_type,
+        // Calculate equivalence properties:
+        let mut eq_properties = join_equivalence_properties(
+            left.equivalence_properties().clone(),
+            right.equivalence_properties().clone(),
+            &join_type,
+            output_schema(schema.clone()),
+            &Self::maintains_input_order(join_type),
+            Some(Self::probe_side()),
+            on,
         );
 
-        let result = build_batch_from_indices(
-            &self.schema,
-            build_side.left_data.batch(),
-            &state.batch,
-            &left_indices,
-            &right_indices,
-            &self.column_indices,
-            JoinSide::Left,
-        )?;
+        // Get output partitioning:
+        let left_columns_len = left.schema().fields.len();
+        let mut output_partitioning = match mode {
+            PartitionMode::CollectLeft => match join_type {
+                JoinType::Inner | JoinType::Right => adjust_right_output_partitioning(
+                    right.output_partitioning(),
+                    left_columns_len,
+                ),
+                JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => {
+                    right.output_partitioning().clone()
+                }
+                JoinType::Left
+                | JoinType::LeftSemi
+                | JoinType::LeftAnti
+                | JoinType::LeftMark
+                | JoinType::Full => Partitioning::UnknownPartitioning(
+                    right.output_partitioning().partition_count(),
+                ),
+            },
+            PartitionMode::Partitioned => partitioned_join_output_partitioning(
+                join_type,
+                left.output_partitioning(),
+                right.output_partitioning(),
+                left_columns_len,
+            ),
+            PartitionMode::Auto => Partitioning::UnknownPartitioning(
+                right.output_partitioning().partition_count(),
+            ),
+        };
 
-        self.join_metrics.output_batches.add(1);
-        self.join_metrics.output_rows.add(result.num_rows());
-        timer.done();
+        // Determine execution mode by checking whether this join is pipeline
+        // breaking. This happens when the left side is unbounded, or the right
+        // side is unbounded with `Left`, `Full`, `LeftAnti`, `LeftSemi` or
+        // `LeftMark` join types.
+        let pipeline_breaking = left.execution_mode().is_unbounded()
+            || (right.execution_mode().is_unbounded()
+                && matches!(
+                    join_type,
+                    JoinType::Left
+                        | JoinType::Full
+                        | JoinType::LeftAnti
+                        | JoinType::LeftSemi
+                        | JoinType::LeftMark
+                ));
 
-        if next_offset.is_none() {
-            self.state = HashJoinStreamState::FetchProbeBatch;
+        let mut mode = if pipeline_breaking {
+            ExecutionMode::PipelineBreaking
         } else {
-            state.advance(
-                next_offset
-                    .ok_or_else(|| internal_datafusion_err!("unexpected None offset"))?,
-                last_joined_right_idx,
-            )
+            execution_mode_from_children([left, right])
+        };
+
+        // If contains projection or output expressions, update the PlanProperties.
+        let projection = match (projection, output_exprs) {
+            (Some(projection), _) => Some((
+                project_index_to_exprs(projection, &schema),
+                project_schema(&schema, Some(projection))?,
+            )),
+            (None, Some(output_exprs)) => Some((
+                output_exprs.to_vec(),
+                Arc::new(output_exprs_schema(output_exprs, &schema)?),
+            )),
+            (None, None) => None,
         };
+        if let Some((projection_exprs, projected_schema)) = projection {
+            // construct a map from the input expressions to the output expression of the Projection
+            let projection_mapping =
+                ProjectionMapping::try_new(&projection_exprs, &schema)?;
+            let out_schema = output_schema(projected_schema);
+            let project_expr = |expr: &Arc<dyn PhysicalExpr>| {
+                eq_properties
+                    .project_expr(expr, &projection_mapping)
+                    .unwrap_or_else(|| Arc::new(UnKnownColumn::new(&expr.to_string())))
+            };
+            match output_partitioning {
+                Partitioning::Hash(exprs, part) => {
+                    let normalized_exprs = exprs.iter().map(project_expr).collect();
+                    output_partitioning = Partitioning::Hash(normalized_exprs, part);
+                }
+                Partitioning::Bucketed(expr, part) => {
+                    output_partitioning =
+                        Partitioning::Bucketed(project_expr(&expr), part);
+                }
+                _ => {}
+            }
+            eq_properties = eq_properties.project(&projection_mapping, out_schema);
+        }
+        // The output rows are sorted once the join completes:
+        if let Some(output_ordering) = output_ordering {
+            eq_properties = eq_properties.with_reorder(output_ordering.to_vec());
+            if mode.is_unbounded() {
+                mode = ExecutionMode::PipelineBreaking;
+            }
+        }
+        Ok(PlanProperties::new(
+            eq_properties,
+            output_partitioning,
+            mode,
+        ))
+    }
+}
+
+impl DisplayAs for HashJoinExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let display_filter = self.filter.as_ref().map_or_else(
+                    || "".to_string(),
+                    |f| format!(", filter={}", f.expression()),
+                );
+                let display_projections = if self.contain_projection() {
+                    format!(
+                        ", projection=[{}]",
+                        self.projection
+                            .as_ref()
+                            .unwrap()
+                            .iter()
+                            .map(|index| format!(
+                                "{}@{}",
+                                self.join_schema.fields().get(*index).unwrap().name(),
+                                index
+                            ))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                } else {
+                    "".to_string()
+                };
+                let on = self
+                    .on
+                    .iter()
+                    .map(|(c1, c2)| format!("({}, {})", c1, c2))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                let display_build_threads = if self.build_threads > 1 {
+                    format!(", build_threads={}", self.build_threads)
+                } else {
+                    "".to_string()
+                };
+                let display_build_prefetch = if self.build_prefetch > 0 {
+                    format!(", build_prefetch={}", self.build_prefetch)
+                } else {
+                    "".to_string()
+                };
+                let display_batch_size = match self.output_shaping.batch_size {
+                    Some(batch_size) => format!(", batch_size={batch_size}"),
+                    None => "".to_string(),
+                };
+                let display_adaptive_partitioning = match self.adaptive_partitioning {
+                    Some(factor) => format!(", adaptive_partitioning={factor}"),
+                    None => "".to_string(),
+                };
+                let display_max_chain_length = match self.max_chain_length {
+                    Some(max_chain_length) => {
+                        format!(", max_chain_length={max_chain_length}")
+                    }
+                    None => "".to_string(),
+                };
+                let display_row_provenance = if self.row_provenance {
+                    ", row_provenance=true"
+                } else {
+                    ""
+                };
+                let display_unmatched_marker = if self.unmatched_marker {
+                    ", unmatched_marker=true"
+                } else {
+                    ""
+                };
+                let display_match_flag = if self.match_flag {
+                    ", match_flag=true"
+                } else {
+                    ""
+                };
+                let display_semijoin_reduction = if self.semijoin_reduction {
+                    ", semijoin_reduction=true"
+                } else {
+                    ""
+                };
+                let display_key_cast = if self.key_cast { ", key_cast=true" } else { "" };
+                let display_build_hash_column = match &self.build_hash_column {
+                    Some(column) => format!(", build_hash_column={column}"),
+                    None => "".to_string(),
+                };
+                let display_runtime_filter_sink = match &self.runtime_filter_sink {
+                    Some(_) => format!(
+                        ", runtime_filter_sink=[{}]",
+                        self.runtime_filter_keys()
+                            .iter()
+                            .map(RuntimeFilterKey::column)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    None => "".to_string(),
+                };
+                let display_output_row_order = match &self.output_row_order {
+                    Some((ordering, fetch)) => {
+                        let ordering = ordering
+                            .iter()
+                            .map(|sort_expr| sort_expr.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        match fetch {
+                            Some(fetch) => {
+                                format!(", output_row_order=[{ordering}], fetch={fetch}")
+                            }
+                            None => format!(", output_row_order=[{ordering}]"),
+                        }
+                    }
+                    None => "".to_string(),
+                };
+                let display_group_by_probe_key = if self.output_shaping.group_by_probe_key
+                {
+                    ", group_by_probe_key=true"
+                } else {
+                    ""
+                };
+                let display_max_concurrency = match self.max_concurrency() {
+                    Some(max_concurrency) => {
+                        format!(", max_concurrency={max_concurrency}")
+                    }
+                    None => "".to_string(),
+                };
+                let display_stall_timeout = match self.stall_timeout {
+                    Some(stall_timeout) => format!(", stall_timeout={stall_timeout:?}"),
+                    None => "".to_string(),
+                };
+                let display_collation = if self.collation != Collation::Binary {
+                    format!(", collation={:?}", self.collation)
+                } else {
+                    "".to_string()
+                };
+                let display_float_key_mode =
+                    if self.float_key_mode != FloatKeyMode::TotalOrder {
+                        format!(", float_key_mode={:?}", self.float_key_mode)
+                    } else {
+                        "".to_string()
+                    };
+                let display_trace_matched_keys = match &self.matched_keys_trace {
+                    Some(trace) => format!(", trace_matched_keys={}", trace.max_keys()),
+                    None => "".to_string(),
+                };
+                let display_output_exprs = match &self.output_exprs {
+                    Some(output_exprs) => format!(
+                        ", output_exprs=[{}]",
+                        output_exprs
+                            .iter()
+                            .map(|(expr, name)| format!("{expr} as {name}"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    None => "".to_string(),
+                };
+                write!(
+                    f,
+                    "HashJoinExec: mode={:?}, join_type={:?}, on=[{}]{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}",
+                    self.mode,
+                    self.join_type,
+                    on,
+                    display_filter,
+                    display_projections,
+                    display_build_threads,
+                    display_build_prefetch,
+                    display_batch_size,
+                    display_max_chain_length,
+                    display_adaptive_partitioning,
+                    display_row_provenance,
+                    display_unmatched_marker,
+                    display_match_flag,
+                    display_semijoin_reduction,
+                    display_key_cast,
+                    display_build_hash_column,
+                    display_runtime_filter_sink,
+                    display_stall_timeout,
+                    display_output_row_order,
+                    display_group_by_probe_key,
+                    display_max_concurrency,
+                    display_collation,
+                    display_float_key_mode,
+                    display_trace_matched_keys,
+                    display_output_exprs
+                )
+            }
+        }
+    }
+}
+
+/// Returns the type each pair of join keys `on` is cast to, see
+/// [`HashJoinExec::with_key_cast`]: `None` if both keys have the same type, and
+/// otherwise the narrowest type both can be cast to without losing information.
+/// Fails if there is no such type for a pair.
+fn join_key_cast_types(
+    on: JoinOnRef,
+    left_schema: &Schema,
+    right_schema: &Schema,
+) -> Result<Vec<Option<DataType>>> {
+    on.iter()
+        .map(|(left_key, right_key)| {
+            let left_type = left_key.data_type(left_schema)?;
+            let right_type = right_key.data_type(right_schema)?;
+            if left_type == right_type {
+                return Ok(None);
+            }
+            match lossless_common_type(&left_type, &right_type) {
+                Some(common_type) => Ok(Some(common_type)),
+                None => plan_err!(
+                    "Join keys {left_key} of type {left_type} and {right_key} of type {right_type} \
+                     can not be cast to a common type without losing information"
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Casts the pairs of join keys `on` of different types to a common type, see
+/// [`join_key_cast_types`]
+fn cast_join_keys(
+    on: JoinOn,
+    left_schema: &Schema,
+    right_schema: &Schema,
+) -> Result<JoinOn> {
+    let cast_types = join_key_cast_types(&on, left_schema, right_schema)?;
+    on.into_iter()
+        .zip(cast_types)
+        .map(|((left_key, right_key), cast_type)| match cast_type {
+            Some(cast_type) => Ok((
+                cast(left_key, left_schema, cast_type.clone())?,
+                cast(right_key, right_schema, cast_type)?,
+            )),
+            None => Ok((left_key, right_key)),
+        })
+        .collect()
+}
+
+/// Join key types ordered by their width, in which [`lossless_common_type`]
+/// looks for a common type
+const JOIN_KEY_CAST_CANDIDATES: [DataType; 15] = [
+    DataType::Int8,
+    DataType::UInt8,
+    DataType::Int16,
+    DataType::UInt16,
+    DataType::Float16,
+    DataType::Int32,
+    DataType::UInt32,
+    DataType::Float32,
+    DataType::Int64,
+    DataType::UInt64,
+    DataType::Float64,
+    DataType::Utf8,
+    DataType::LargeUtf8,
+    DataType::Binary,
+    DataType::LargeBinary,
+];
+
+/// Returns the narrowest type both `left` and `right` can be cast to without
+/// losing information, if any
+fn lossless_common_type(left: &DataType, right: &DataType) -> Option<DataType> {
+    JOIN_KEY_CAST_CANDIDATES
+        .into_iter()
+        .find(|to| casts_losslessly(left, to) && casts_losslessly(right, to))
+}
+
+/// Returns whether every value of type `from` can be cast to `to` and back
+fn casts_losslessly(from: &DataType, to: &DataType) -> bool {
+    use DataType::*;
+    // Integers are described by whether they are signed and their number of
+    // value bits, floats by the number of bits of their significand:
+    let integer = |data_type: &DataType| match data_type {
+        Int8 => Some((true, 7)),
+        Int16 => Some((true, 15)),
+        Int32 => Some((true, 31)),
+        Int64 => Some((true, 63)),
+        UInt8 => Some((false, 8)),
+        UInt16 => Some((false, 16)),
+        UInt32 => Some((false, 32)),
+        UInt64 => Some((false, 64)),
+        _ => None,
+    };
+    let float = |data_type: &DataType| match data_type {
+        Float16 => Some(11),
+        Float32 => Some(24),
+        Float64 => Some(53),
+        _ => None,
+    };
+    if from == to {
+        return true;
+    }
+    match (integer(from), integer(to), float(from), float(to)) {
+        (Some((from_signed, from_bits)), Some((to_signed, to_bits)), _, _) => {
+            (to_signed || !from_signed) && from_bits <= to_bits
+        }
+        (Some((_, from_bits)), _, _, Some(to_bits)) => from_bits <= to_bits,
+        (_, _, Some(from_bits), Some(to_bits)) => from_bits <= to_bits,
+        _ => matches!((from, to), (Utf8, LargeUtf8) | (Binary, LargeBinary)),
+    }
+}
+
+/// Returns the number of distinct values of the join key `expr` according to
+/// `stats`, if `expr` is a column and the number is known.
+fn key_distinct_count(expr: &PhysicalExprRef, stats: &Statistics) -> Option<usize> {
+    let column = expr.as_any().downcast_ref::<Column>()?;
+    stats
+        .column_statistics
+        .get(column.index())?
+        .distinct_count
+        .get_value()
+        .copied()
+}
+
+fn project_index_to_exprs(
+    projection_index: &[usize],
+    schema: &SchemaRef,
+) -> Vec<(Arc<dyn PhysicalExpr>, String)> {
+    projection_index
+        .iter()
+        .map(|index| {
+            let field = schema.field(*index);
+            (
+                Arc::new(datafusion_physical_expr::expressions::Column::new(
+                    field.name(),
+                    *index,
+                )) as Arc<dyn PhysicalExpr>,
+                field.name().to_owned(),
+            )
+        })
+        .collect::<Vec<_>>()
+}
+
+impl ExecutionPlan for HashJoinExec {
+    fn name(&self) -> &'static str {
+        "HashJoinExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.cache
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        match self.mode {
+            // The marks of the build side rows depend on all probe side rows
+            PartitionMode::CollectLeft if self.join_type == JoinType::LeftMark => {
+                vec![Distribution::SinglePartition, Distribution::SinglePartition]
+            }
+            PartitionMode::CollectLeft => vec![
+                Distribution::SinglePartition,
+                Distribution::UnspecifiedDistribution,
+            ],
+            PartitionMode::Partitioned => {
+                let (left_expr, right_expr) =
+                    self.on.iter().map(|(l, r)| (l.clone(), r.clone())).unzip();
+                vec![
+                    Distribution::HashPartitioned(left_expr),
+                    Distribution::HashPartitioned(right_expr),
+                ]
+            }
+            PartitionMode::Auto => vec![
+                Distribution::UnspecifiedDistribution,
+                Distribution::UnspecifiedDistribution,
+            ],
+        }
+    }
+
+    // For [JoinType::Inner] and [JoinType::RightSemi] in hash joins, the probe phase initiates by
+    // applying the hash function to convert the join key(s) in each row into a hash value from the
+    // probe side table in the order they're arranged. The hash value is used to look up corresponding
+    // entries in the hash table that was constructed from the build side table during the build phase.
+    //
+    // Because of the immediate generation of result rows once a match is found,
+    // the output of the join tends to follow the order in which the rows were read from
+    // the probe side table. This is simply due to the sequence in which the rows were processed.
+    // Hence, it appears that the hash join is preserving the order of the probe side.
+    //
+    // Meanwhile, in the case of a [JoinType::RightAnti] hash join,
+    // the unmatched rows from the probe side are also kept in order.
+    // This is because the **`RightAnti`** join is designed to return rows from the right
+    // (probe side) table that have no match in the left (build side) table. Because the rows
+    // are processed sequentially in the probe phase, and unmatched rows are directly output
+    // as results, these results tend to retain the order of the probe side table.
+    fn maintains_input_order(&self) -> Vec<bool> {
+        Self::maintains_input_order(self.join_type)
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let mut join = HashJoinExec::try_new(
+            children[0].clone(),
+            children[1].clone(),
+            self.on.clone(),
+            self.filter.clone(),
+            &self.join_type,
+            self.projection.clone(),
+            self.mode,
+            self.null_equals_null,
+        )?
+        .with_row_provenance(self.row_provenance)?
+        .with_unmatched_marker(self.unmatched_marker)?
+        .with_match_flag(self.match_flag)?
+        .with_collation(self.collation)?
+        .with_float_key_mode(self.float_key_mode)?
+        .with_key_cast(self.key_cast)?
+        .with_build_hash_column(self.build_hash_column.clone())?
+        .with_registered_runtime_filter_sink(self.runtime_filter_sink.clone())?
+        .with_adaptive_partitioning(self.adaptive_partitioning.unwrap_or(0))?
+        .with_output_shaping(self.output_shaping.clone())?
+        .with_key_reordering(self.reorder_keys)
+        .with_null_buffer_reuse(self.null_buffer_reuse)
+        .with_build_key_unique(self.build_key_unique)
+        .with_build_threads(self.build_threads)
+        .with_build_prefetch(self.build_prefetch)
+        .with_semijoin_reduction(self.semijoin_reduction)
+        .with_stall_timeout(self.stall_timeout)
+        .with_trace_matched_keys(self.trace_max_keys());
+        if let Some(output_exprs) = &self.output_exprs {
+            join = join.with_output_exprs(output_exprs.clone())?;
+        }
+        if let Some((ordering, fetch)) = &self.output_row_order {
+            join = join.with_output_row_order_by(ordering.clone(), *fetch)?;
+        }
+        if let Some(max_concurrency) = self.max_concurrency() {
+            join = join.with_max_concurrency(max_concurrency)?;
+        }
+        join.output_row_limit = self
+            .output_row_limit
+            .as_ref()
+            .map(|limit| OutputRowLimit::new(limit.max_rows()));
+        join.max_chain_length = self.max_chain_length;
+        join.probe_filter_first = self.probe_filter_first;
+        Ok(Arc::new(join))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        // Both sides are hashed and compared using the same key order, without
+        // the key pairs that are implied by another one:
+        let on = canonicalize_join_on(
+            self.join_key_order()
+                .into_iter()
+                .map(|i| self.on[i].clone())
+                .collect(),
+            self.left.equivalence_properties(),
+            self.right.equivalence_properties(),
+        );
+        let on = if self.key_cast {
+            cast_join_keys(on, &self.left.schema(), &self.right.schema())?
+        } else {
+            on
+        };
+        let on_left = on
+            .iter()
+            .map(|(left_key, _)| {
+                let left_key = FloatKeyExpr::wrap(left_key.clone(), self.float_key_mode);
+                CollatedKeyExpr::wrap(left_key, self.collation)
+            })
+            .collect::<Vec<_>>();
+        let on_right = on
+            .iter()
+            .map(|(_, right_key)| {
+                let right_key =
+                    FloatKeyExpr::wrap(right_key.clone(), self.float_key_mode);
+                CollatedKeyExpr::wrap(right_key, self.collation)
+            })
+            .collect::<Vec<_>>();
+        let left_partitions = self.left.output_partitioning().partition_count();
+        let right_partitions = self.right.output_partitioning().partition_count();
+
+        if self.mode == PartitionMode::Partitioned && left_partitions != right_partitions
+        {
+            return internal_err!(
+                "Invalid HashJoinExec, partition count mismatch {left_partitions}!={right_partitions},\
+                 consider using RepartitionExec or with_aligned_input_partitions"
+            );
+        }
+        if self.mode == PartitionMode::CollectLeft
+            && self.join_type == JoinType::LeftMark
+            && right_partitions != 1
+        {
+            return internal_err!(
+                "Invalid HashJoinExec, LeftMark joins in CollectLeft mode need a single probe side partition, got {right_partitions}"
+            );
+        }
+
+        // If the probe side is known to be empty, every output row of these join
+        // types is a build side row padded with nulls, so we can skip building the
+        // hash table altogether:
+        if self.mode != PartitionMode::Auto
+            && matches!(
+                self.join_type,
+                JoinType::Left | JoinType::Full | JoinType::LeftAnti
+            )
+            && self.probe_side_is_empty()
+        {
+            let stream =
+                self.execute_with_empty_probe_side(partition, context.clone())?;
+            let stream = evaluate_output_exprs(
+                stream,
+                self.output_exprs.as_deref(),
+                self.schema(),
+            );
+            let stream = sort_output_rows(
+                stream,
+                self.output_row_order.as_ref(),
+                partition,
+                self.output_shaping.batch_size(&context),
+                &context,
+            )?;
+            let stream = limit_output_rows(
+                skip_empty_batches(stream, self.output_shaping.skip_empty_batches),
+                self.output_row_limit.as_ref(),
+                partition,
+            );
+            return Ok(limit_concurrency(
+                stream,
+                self.concurrency_limit.as_ref(),
+                partition,
+                &self.metrics,
+            ));
+        }
+
+        let join_metrics = BuildProbeJoinMetrics::new(partition, &self.metrics);
+        let hash_table_metrics = HashTableMetrics::new(partition, &self.metrics);
+        let reduce_probe_side = self.reduces_probe_side();
+        let dedup_build_keys = self.dedups_build_keys();
+
+        // With adaptive partitioning, the left side is read until the strategy
+        // is decided on, and the right side is only executed afterwards:
+        let build_sample = self.adaptive_build_sample(&on_left, &on_right, &context);
+
+        // Stream over the right (probe) side, whose batches are joined with the
+        // hash table built from the left side
+        let right_stream = match &build_sample {
+            Some(build_sample) => adaptive_probe_input(
+                self.right.clone(),
+                build_sample.clone(),
+                partition,
+                context.clone(),
+            ),
+            None => self.right.execute(partition, context.clone())?,
+        };
+        // To reduce the build side, the probe side partition is buffered before
+        // the hash table is built, and replayed from the buffer afterwards:
+        let (right_stream, probe_side) = if self.reduces_build_side() {
+            let reservation =
+                MemoryConsumer::new(format!("HashJoinProbeBuffer[{partition}]"))
+                    .register(context.memory_pool());
+            let probe_side = OnceFut::new(collect_probe_input(
+                right_stream,
+                on_right.clone(),
+                self.random_state.clone(),
+                reservation,
+            ));
+            (
+                replay_probe_input(self.right.schema(), probe_side.clone()),
+                Some(probe_side),
+            )
+        } else {
+            (right_stream, None)
+        };
+
+        let left_fut = match self.mode {
+            PartitionMode::CollectLeft => {
+                let mut shared_left_fut = self.left_fut.once(|| {
+                    let reservation = MemoryConsumer::new("HashJoinInput")
+                        .register(context.memory_pool());
+                    let left_fut = collect_left_input(
+                        None,
+                        self.random_state.clone(),
+                        self.left.clone(),
+                        on_left.clone(),
+                        context.clone(),
+                        join_metrics.clone(),
+                        hash_table_metrics.clone(),
+                        reservation,
+                        self.build_key_unique,
+                        self.null_equals_null,
+                        self.build_threads,
+                        self.build_prefetch,
+                        reduce_probe_side,
+                        None,
+                        dedup_build_keys,
+                        self.build_hash_column.clone(),
+                        self.max_chain_length,
+                        build_sample.clone(),
+                    );
+                    let runtime_filters =
+                        self.runtime_filter_sink.clone().map(|(sink, _)| {
+                            let keys = self
+                                .runtime_filter_columns()
+                                .into_iter()
+                                .zip(self.runtime_filter_keys())
+                                .collect::<Vec<_>>();
+                            // Resets the filters published by a previous execution,
+                            // before any probe side batch is read:
+                            for (_, key) in &keys {
+                                sink.declare(key);
+                            }
+                            (
+                                sink,
+                                keys,
+                                self.right.schema(),
+                                self.compares_raw_keys(),
+                                self.null_equals_null,
+                            )
+                        });
+                    async move {
+                        let left_data = left_fut.await;
+                        if let Some((
+                            sink,
+                            keys,
+                            right_schema,
+                            raw_keys,
+                            null_equals_null,
+                        )) = runtime_filters
+                        {
+                            // A failed build side withdraws the filters, releasing
+                            // the scans waiting for them:
+                            for ((left_key, column), key) in keys {
+                                let filter = match &left_data {
+                                    Ok(left_data) if raw_keys => build_runtime_filter(
+                                        left_data,
+                                        &left_key,
+                                        right_schema.field(column.index()).data_type(),
+                                        null_equals_null,
+                                    ),
+                                    _ => None,
+                                };
+                                match filter {
+                                    Some(filter) => sink.publish(&key, filter),
+                                    None => sink.withdraw(&key),
+                                }
+                            }
+                        }
+                        left_data
+                    }
+                });
+                match build_sample {
+                    // Once switched, every partition builds a hash table over
+                    // its share of the left side
+                    Some(mut build_sample) => {
+                        let reservation =
+                            MemoryConsumer::new(format!("HashJoinInput[{partition}]"))
+                                .register(context.memory_pool());
+                        let mut partitioned_left_fut = OnceFut::new(collect_left_input(
+                            Some(partition),
+                            self.random_state.clone(),
+                            self.left.clone(),
+                            on_left.clone(),
+                            context.clone(),
+                            join_metrics.clone(),
+                            hash_table_metrics,
+                            reservation,
+                            self.build_key_unique,
+                            self.null_equals_null,
+                            self.build_threads,
+                            self.build_prefetch,
+                            reduce_probe_side,
+                            None,
+                            dedup_build_keys,
+                            self.build_hash_column.clone(),
+                            self.max_chain_length,
+                            Some(build_sample.clone()),
+                        ));
+                        async move {
+                            let switched = poll_fn(|cx| build_sample.get_shared(cx))
+                                .await?
+                                .probe_rehash
+                                .is_some();
+                            if switched {
+                                poll_fn(|cx| partitioned_left_fut.get_shared(cx)).await
+                            } else {
+                                poll_fn(|cx| shared_left_fut.get_shared(cx)).await
+                            }
+                        }
+                        .boxed()
+                    }
+                    None => {
+                        async move { poll_fn(|cx| shared_left_fut.get_shared(cx)).await }
+                            .boxed()
+                    }
+                }
+            }
+            PartitionMode::Partitioned => {
+                let reservation =
+                    MemoryConsumer::new(format!("HashJoinInput[{partition}]"))
+                        .register(context.memory_pool());
+
+                // Failures are reported like those of a shared build side:
+                let mut left_fut = OnceFut::new(collect_left_input(
+                    Some(partition),
+                    self.random_state.clone(),
+                    self.left.clone(),
+                    on_left.clone(),
+                    context.clone(),
+                    join_metrics.clone(),
+                    hash_table_metrics,
+                    reservation,
+                    self.build_key_unique,
+                    self.null_equals_null,
+                    self.build_threads,
+                    self.build_prefetch,
+                    reduce_probe_side,
+                    probe_side,
+                    dedup_build_keys,
+                    self.build_hash_column.clone(),
+                    self.max_chain_length,
+                    None,
+                ));
+                async move { poll_fn(|cx| left_fut.get_shared(cx)).await }.boxed()
+            }
+            PartitionMode::Auto => {
+                return plan_err!(
+                    "Invalid HashJoinExec, unsupported PartitionMode {:?} in execute()",
+                    PartitionMode::Auto
+                );
+            }
+        };
+
+        // The number of matches joined at a time bounds the output batch size:
+        let batch_size = self.output_shaping.chunk_size(&context);
+
+        let reservation = MemoryConsumer::new(format!("HashJoinStream[{partition}]"))
+            .register(context.memory_pool());
+
+        // update column indices to reflect the projection, the mark column of
+        // mark joins has none
+        let column_indices_after_projection = match &self.projection {
+            Some(projection) => projection
+                .iter()
+                .filter_map(|i| self.column_indices.get(*i).cloned())
+                .collect(),
+            None => self.column_indices.clone(),
+        };
+        let mark_positions =
+            matches!(self.join_type, JoinType::LeftMark | JoinType::RightMark).then(
+                || match &self.projection {
+                    Some(projection) => projection
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, i)| **i == self.column_indices.len())
+                        .map(|(position, _)| position)
+                        .collect(),
+                    None => vec![self.column_indices.len()],
+                },
+            );
+
+        // The row provenance columns, the unmatched row marker and the match
+        // flag are appended to the batches built from the join columns:
+        let hidden_columns = 2 * usize::from(self.row_provenance)
+            + usize::from(self.unmatched_marker)
+            + usize::from(self.match_flag);
+        let (schema, hidden_columns_schema) = if hidden_columns > 0 {
+            let schema = self.schema();
+            let join_fields = &schema.fields()[..schema.fields().len() - hidden_columns];
+            let join_schema = Schema::new_with_metadata(
+                join_fields.to_vec(),
+                schema.metadata().clone(),
+            );
+            (Arc::new(join_schema), Some(schema))
+        } else if self.output_exprs.is_some() {
+            // The output expressions are evaluated on the join columns:
+            (self.join_schema.clone(), None)
+        } else {
+            (self.schema(), None)
+        };
+        // The mark column is inserted into the batches built from the other
+        // join columns:
+        let (schema, mark) = match mark_positions {
+            Some(positions) => {
+                let join_fields = schema
+                    .fields()
+                    .iter()
+                    .enumerate()
+                    .filter(|(position, _)| !positions.contains(position))
+                    .map(|(_, field)| field.clone())
+                    .collect::<Vec<_>>();
+                let join_schema =
+                    Schema::new_with_metadata(join_fields, schema.metadata().clone());
+                let mark = MarkColumn {
+                    schema,
+                    positions,
+                    null_aware: !self.null_equals_null,
+                    other_side_has_rows: false,
+                    other_side_has_null_key: false,
+                };
+                (Arc::new(join_schema), Some(mark))
+            }
+            None => (schema, None),
+        };
+
+        let key_comparisons =
+            MetricBuilder::new(&self.metrics).counter("key_comparisons", partition);
+        let filter_evaluations =
+            MetricBuilder::new(&self.metrics).counter("filter_evaluations", partition);
+        let skipped_null_key_rows =
+            MetricBuilder::new(&self.metrics).counter("skipped_null_key_rows", partition);
+        let semijoin_reduced_probe_rows = MetricBuilder::new(&self.metrics)
+            .counter("semijoin_reduced_probe_rows", partition);
+        // Probe rows with a null key never match unless nulls are equal, and
+        // the output of these join types does not include unmatched probe
+        // rows, unless flagged. The row provenance refers to rows of the
+        // unfiltered batch.
+        let skip_null_keys = !self.null_equals_null
+            && !self.row_provenance
+            && !self.match_flag
+            && matches!(
+                self.join_type,
+                JoinType::Inner
+                    | JoinType::Left
+                    | JoinType::LeftSemi
+                    | JoinType::LeftAnti
+                    | JoinType::RightSemi
+            );
+
+        let stall_watchdog = self.stall_timeout.map(|timeout| StallWatchdog {
+            timeout,
+            partition,
+            build_partition: (self.mode == PartitionMode::Partitioned)
+                .then_some(partition),
+            last_progress: Instant::now(),
+            deadline: None,
+            warnings: MetricBuilder::new(&self.metrics)
+                .counter("stall_warnings", partition),
+        });
+
+        let probe_filter = match &self.filter {
+            Some(filter) => match filter.split_side_conjuncts(JoinSide::Right)? {
+                (Some(predicate), remaining) => Some(ProbeFilter {
+                    predicate,
+                    remaining,
+                    first: self.probe_filter_first,
+                }),
+                (None, _) => None,
+            },
+            None => None,
+        };
+
+        let probe_key_groups = self
+            .output_shaping
+            .group_by_probe_key
+            .then(|| {
+                let reservation =
+                    MemoryConsumer::new(format!("HashJoinProbeKeyGroups[{partition}]"))
+                        .register(context.memory_pool());
+                ProbeKeyGroups::try_new(&on_right, &right_stream.schema(), reservation)
+            })
+            .transpose()?;
+
+        let stream = Box::pin(HashJoinStream {
+            schema,
+            on_left,
+            on_right,
+            filter: self.filter.clone(),
+            probe_filter,
+            join_type: self.join_type,
+            right: right_stream,
+            column_indices: column_indices_after_projection,
+            random_state: self.random_state.clone(),
+            join_metrics,
+            null_equals_null: self.null_equals_null,
+            skip_null_keys,
+            reduce_probe_side,
+            reservation,
+            state: HashJoinStreamState::WaitBuildSide,
+            build_side: BuildSide::Initial(BuildSideInitialState { left_fut }),
+            batch_size,
+            low_latency_chunk_size: self.output_shaping.low_latency.then_some(batch_size),
+            output_byte_budget: self.output_shaping.byte_budget(),
+            max_serialized_batch_bytes: self.output_shaping.max_serialized_batch_bytes,
+            pending_output: VecDeque::new(),
+            hashes_buffer: vec![],
+            hidden_columns_schema,
+            row_provenance: self.row_provenance,
+            unmatched_marker: self.unmatched_marker,
+            match_flag: self.match_flag,
+            key_comparisons,
+            filter_evaluations,
+            skipped_null_key_rows,
+            semijoin_reduced_probe_rows,
+            stall_watchdog,
+            matched_keys_trace: self.matched_keys_trace.clone(),
+            probe_key_groups,
+            single_build_row: false,
+            null_padding: if self.null_buffer_reuse {
+                NullPadding::new(
+                    MemoryConsumer::new(format!("HashJoinNullPadding[{partition}]"))
+                        .register(context.memory_pool()),
+                )
+            } else {
+                NullPadding::default()
+            },
+            mark,
+        });
+        let stream =
+            evaluate_output_exprs(stream, self.output_exprs.as_deref(), self.schema());
+        let stream = sort_output_rows(
+            stream,
+            self.output_row_order.as_ref(),
+            partition,
+            self.output_shaping.batch_size(&context),
+            &context,
+        )?;
+        let stream = limit_output_rows(
+            skip_empty_batches(stream, self.output_shaping.skip_empty_batches),
+            self.output_row_limit.as_ref(),
+            partition,
+        );
+        Ok(limit_concurrency(
+            stream,
+            self.concurrency_limit.as_ref(),
+            partition,
+            &self.metrics,
+        ))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        // TODO stats: it is not possible in general to know the output size of joins
+        // There are some special cases though, for example:
+        // - `A LEFT JOIN B ON A.col=B.col` with `COUNT_DISTINCT(B.col)=COUNT(B.col)`
+        let mut stats = estimate_join_statistics(
+            self.left.clone(),
+            self.right.clone(),
+            self.on.clone(),
+            &self.join_type,
+            &self.join_schema,
+        )?;
+        // Project statistics if there is a projection
+        if let Some(projection) = &self.projection {
+            stats.column_statistics = stats
+                .column_statistics
+                .into_iter()
+                .enumerate()
+                .filter(|(i, _)| projection.contains(i))
+                .map(|(_, s)| s)
+                .collect();
+        }
+        // Output expressions keep the statistics of the columns they select:
+        if let Some(output_exprs) = &self.output_exprs {
+            stats.column_statistics = output_exprs
+                .iter()
+                .map(|(expr, _)| {
+                    expr.as_any()
+                        .downcast_ref::<Column>()
+                        .map_or_else(ColumnStatistics::new_unknown, |column| {
+                            stats.column_statistics[column.index()].clone()
+                        })
+                })
+                .collect();
+        }
+        if self.row_provenance {
+            stats.column_statistics.extend([
+                ColumnStatistics::new_unknown(),
+                ColumnStatistics::new_unknown(),
+            ]);
+        }
+        if self.unmatched_marker {
+            stats
+                .column_statistics
+                .push(ColumnStatistics::new_unknown());
+        }
+        if self.match_flag {
+            stats
+                .column_statistics
+                .push(ColumnStatistics::new_unknown());
+        }
+        Ok(stats)
+    }
+}
+
+/// Fill statistics of the build side hash table, recorded once it is built.
+/// A high maximum chain length signals a poor hash or heavily skewed keys.
+#[derive(Clone)]
+struct HashTableMetrics {
+    /// Number of buckets allocated by the hash table
+    buckets: metrics::Gauge,
+    /// Ratio of occupied buckets, as a percentage
+    load_factor_pct: metrics::Gauge,
+    /// Number of distinct hash values of the build side join keys, i.e. of
+    /// hash table entries. Keys colliding on their hash value are counted
+    /// once, so this is a lower bound of the number of distinct join keys.
+    /// Null keys share a single hash value.
+    distinct_hashes: metrics::Gauge,
+    /// Length of the longest chain of build rows sharing a hash value
+    max_chain_length: metrics::Gauge,
+    /// Number of build rows chained behind another row with the same hash value
+    collisions: metrics::Gauge,
+    /// Number of tasks the build side batches were hashed on
+    build_tasks: metrics::Count,
+    /// Wall-clock time from starting to read the build side until the hash
+    /// table is built
+    build_wall_time: metrics::Time,
+    /// Number of build rows dropped by the semijoin reduction
+    semijoin_reduced_rows: metrics::Count,
+    /// Number of build rows dropped for repeating the join keys of an earlier
+    /// row
+    deduplicated_rows: metrics::Count,
+    /// Number of build batches whose join keys were hashed
+    hashed_batches: metrics::Count,
+    /// Number of chains longer than the chain length limit that were indexed
+    /// by their join keys
+    indexed_chains: metrics::Gauge,
+}
+
+impl HashTableMetrics {
+    fn new(partition: usize, metrics: &ExecutionPlanMetricsSet) -> Self {
+        Self {
+            buckets: MetricBuilder::new(metrics).gauge("hash_table_buckets", partition),
+            load_factor_pct: MetricBuilder::new(metrics)
+                .gauge("hash_table_load_factor_pct", partition),
+            distinct_hashes: MetricBuilder::new(metrics)
+                .gauge("build_distinct_hashes", partition),
+            max_chain_length: MetricBuilder::new(metrics)
+                .gauge("hash_table_max_chain_length", partition),
+            collisions: MetricBuilder::new(metrics)
+                .gauge("hash_table_collisions", partition),
+            build_tasks: MetricBuilder::new(metrics)
+                .counter("hash_table_build_tasks", partition),
+            build_wall_time: MetricBuilder::new(metrics)
+                .subset_time("hash_table_build_wall_time", partition),
+            semijoin_reduced_rows: MetricBuilder::new(metrics)
+                .counter("semijoin_reduced_build_rows", partition),
+            deduplicated_rows: MetricBuilder::new(metrics)
+                .counter("hash_table_deduplicated_rows", partition),
+            hashed_batches: MetricBuilder::new(metrics)
+                .counter("hash_table_hashed_batches", partition),
+            indexed_chains: MetricBuilder::new(metrics)
+                .gauge("hash_table_indexed_chains", partition),
+        }
+    }
+
+    fn record(&self, stats: &JoinHashMapStats) {
+        self.buckets.set(stats.buckets);
+        self.load_factor_pct.set(stats.load_factor_pct());
+        self.distinct_hashes.set(stats.entries);
+        self.max_chain_length.set(stats.max_chain_length);
+        self.collisions.set(stats.collisions);
+    }
+}
+
+/// Returns `input` hash partitioned on `keys` into `partition_count`
+/// partitions, see [`HashJoinExec::with_aligned_input_partitions`]. An input
+/// which already is partitioned that way is kept as it is.
+fn rehash_input(
+    input: Arc<dyn ExecutionPlan>,
+    keys: Vec<PhysicalExprRef>,
+    partition_count: usize,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    if let Partitioning::Hash(exprs, count) = input.output_partitioning() {
+        if *count == partition_count && physical_exprs_equal(exprs, &keys) {
+            return Ok(input);
+        }
+    }
+    Ok(Arc::new(RepartitionExec::try_new(
+        input,
+        Partitioning::Hash(keys, partition_count),
+    )?))
+}
+
+/// Builds the runtime filter over the values of `left_key` in the build side,
+/// cast to the `data_type` of the probe side column it is compared to, see
+/// [`HashJoinExec::with_runtime_filter_sink`]. Returns `None` if the filter can
+/// not be built, in which case the probe side is not filtered.
+fn build_runtime_filter(
+    left_data: &JoinLeftData,
+    left_key: &PhysicalExprRef,
+    data_type: &DataType,
+    null_equals_null: bool,
+) -> Option<RuntimeFilter> {
+    let filter = left_key
+        .evaluate(left_data.batch())
+        .and_then(|values| values.into_array(left_data.num_rows()))
+        .and_then(|values| {
+            // Values not representable in the probe side type can not match,
+            // they are cast to nulls, which must not be taken for null keys:
+            let matches_nulls = null_equals_null && values.null_count() > 0;
+            let values = arrow::compute::cast(&values, data_type)?;
+            RuntimeFilter::try_new(&values, matches_nulls)
+        });
+    match filter {
+        Ok(filter) => Some(filter),
+        Err(e) => {
+            warn!("Failed to build runtime filter for join key {left_key}: {e}");
+            None
+        }
+    }
+}
+
+/// Reads the left (build) side of the input, buffering it in memory, to build a
+/// hash table (`LeftJoinData`)
+///
+/// If `build_key_unique` is set, fails if two rows have equal join keys, see
+/// [`HashJoinExec::with_build_key_unique`]. The join keys are hashed on up to
+/// `build_threads` tasks, see [`HashJoinExec::with_build_threads`], unless up
+/// to `build_prefetch` batches are read ahead while hashing the fetched ones,
+/// see [`HashJoinExec::with_build_prefetch`].
+///
+/// If `probe_side` is given, the rows whose join keys are not in its key filter
+/// are dropped, and if `key_filter` is set, a filter over the join keys of the
+/// remaining rows is built, see [`HashJoinExec::with_semijoin_reduction`].
+///
+/// If `dedup_keys` is set, only the first row of every distinct join key is
+/// kept, see [`HashJoinExec::dedups_build_keys`].
+///
+/// If `hash_column` is given, the hashes are read from it instead of computed
+/// from the join keys, see [`HashJoinExec::with_build_hash_column`].
+///
+/// If `build_sample` is given, the left side is read from it rather than
+/// from `left`, see [`HashJoinExec::with_adaptive_partitioning`].
+#[allow(clippy::too_many_arguments)]
+async fn collect_left_input(
+    partition: Option<usize>,
+    random_state: RandomState,
+    left: Arc<dyn ExecutionPlan>,
+    on_left: Vec<PhysicalExprRef>,
+    context: Arc<TaskContext>,
+    metrics: BuildProbeJoinMetrics,
+    hash_table_metrics: HashTableMetrics,
+    reservation: MemoryReservation,
+    build_key_unique: bool,
+    null_equals_null: bool,
+    build_threads: usize,
+    build_prefetch: usize,
+    key_filter: bool,
+    probe_side: Option<OnceFut<BufferedProbeSide>>,
+    dedup_keys: bool,
+    hash_column: Option<Column>,
+    max_chain_length: Option<usize>,
+    build_sample: Option<OnceFut<BuildSample>>,
+) -> Result<JoinLeftData> {
+    let left = match build_sample {
+        Some(mut build_sample) => poll_fn(|cx| build_sample.get_shared(cx))
+            .await?
+            .build_input
+            .clone(),
+        None => left,
+    };
+    let schema = left.schema();
+    let build_timer = hash_table_metrics.build_wall_time.timer();
+
+    let (left_input, left_input_partition) = if let Some(partition) = partition {
+        (left, partition)
+    } else if left.output_partitioning().partition_count() != 1 {
+        (Arc::new(CoalescePartitionsExec::new(left)) as _, 0)
+    } else {
+        (left, 0)
+    };
+
+    // Depending on partition argument load single partition or whole left side in memory
+    let stream = if build_prefetch > 0 {
+        // Read ahead on a separate task, so that the batches are fetched while
+        // the ones already received are hashed below
+        let mut builder =
+            RecordBatchReceiverStream::builder(schema.clone(), build_prefetch);
+        builder.run_input(left_input, left_input_partition, context.clone());
+        builder.build()
+    } else {
+        left_input.execute(left_input_partition, context.clone())?
+    };
+
+    let probe_side = match probe_side {
+        Some(mut probe_side) => Some(poll_fn(|cx| probe_side.get_shared(cx)).await?),
+        None => None,
+    };
+    // The join keys of each batch are hashed on arrival if prefetching, or if
+    // rows are dropped from the batch based on them. Hashes read from the hash
+    // column are cheap enough not to be worth hashing concurrently.
+    let hash_on_arrival =
+        build_prefetch > 0 || probe_side.is_some() || dedup_keys || hash_column.is_some();
+    let seen_keys = if dedup_keys {
+        Some(SeenBuildKeys::try_new(&on_left, &schema)?)
+    } else {
+        None
+    };
+
+    // This operation performs 2 steps at once:
+    // 1. creates a [JoinHashMap] of all batches from the stream
+    // 2. stores the batches in a vector.
+    let (on_left_ref, random_state_ref) = (&on_left, &random_state);
+    let probe_key_filter = probe_side.as_ref().map(|probe_side| &probe_side.key_filter);
+    let semijoin_reduced_rows = &hash_table_metrics.semijoin_reduced_rows;
+    let deduplicated_rows = &hash_table_metrics.deduplicated_rows;
+    let hashed_batches = &hash_table_metrics.hashed_batches;
+    let hash_column_ref = hash_column.as_ref();
+    let initial = (Vec::new(), 0, metrics, reservation, Vec::new(), seen_keys);
+    let (batches, num_rows, metrics, mut reservation, prefetched_hashes, seen_keys) =
+        stream
+            .try_fold(initial, |mut acc, mut batch| async move {
+                acc.2.build_input_batches.add(1);
+                acc.2.build_input_rows.add(batch.num_rows());
+                let mut hashes = Vec::new();
+                if let Some(hash_column) = hash_column_ref {
+                    hashes = read_build_hashes(
+                        &batch,
+                        hash_column,
+                        on_left_ref,
+                        random_state_ref,
+                    )?;
+                } else if hash_on_arrival {
+                    hashed_batches.add(1);
+                    hashes = hash_build_batch(&batch, on_left_ref, random_state_ref)?;
+                }
+                if let Some(probe_key_filter) = probe_key_filter {
+                    let (mask, rejected) = probe_key_filter.mask(&hashes);
+                    if rejected > 0 {
+                        semijoin_reduced_rows.add(rejected);
+                        batch = filter_record_batch(&batch, &mask)?;
+                        hashes.retain(|hash| probe_key_filter.contains(*hash));
+                    }
+                }
+                if let Some(seen_keys) = acc.5.as_mut() {
+                    let (mask, keys_size) =
+                        seen_keys.first_occurrences(&batch, on_left_ref)?;
+                    acc.3.try_grow(keys_size)?;
+                    let duplicates = mask.len() - mask.true_count();
+                    if duplicates > 0 {
+                        deduplicated_rows.add(duplicates);
+                        batch = filter_record_batch(&batch, &mask)?;
+                        hashes = hashes
+                            .into_iter()
+                            .zip(mask.values())
+                            .filter_map(|(hash, keep)| keep.then_some(hash))
+                            .collect();
+                    }
+                }
+                let batch_size = batch.get_array_memory_size();
+                // Reserve memory for incoming batch
+                acc.3.try_grow(batch_size)?;
+                // Update metrics
+                acc.2.build_mem_used.add(batch_size);
+                // Update rowcount
+                acc.1 += batch.num_rows();
+                if hash_on_arrival {
+                    acc.4.push(hashes);
+                }
+                // Push batch to output
+                acc.0.push(batch);
+                Ok(acc)
+            })
+            .await?;
+
+    // The distinct keys are only needed while reading the build side
+    if let Some(seen_keys) = seen_keys {
+        reservation.shrink(seen_keys.size);
+    }
+
+    // Estimation of memory size, required for hashtable, prior to allocation.
+    // Final result can be verified using `RawTable.allocation_info()`
+    //
+    // For majority of cases hashbrown overestimates buckets qty to keep ~1/8 of them empty.
+    // This formula leads to overallocation for small tables (< 8 elements) but fine overall.
+    let estimated_buckets = (num_rows.checked_mul(8).ok_or_else(|| {
+        DataFusionError::Execution(
+            "usize overflow while estimating number of hasmap buckets".to_string(),
+        )
+    })? / 7)
+        .next_power_of_two();
+    // 16 bytes per `(u64, u64)`
+    // + 1 byte for each bucket
+    // + fixed size of JoinHashMap (RawTable + Vec)
+    let estimated_hastable_size =
+        16 * estimated_buckets + estimated_buckets + size_of::<JoinHashMap>();
+
+    reservation.try_grow(estimated_hastable_size)?;
+    metrics.build_mem_used.add(estimated_hastable_size);
+
+    let batch_hashes = if hash_on_arrival {
+        if hash_column.is_none() {
+            hash_table_metrics.build_tasks.add(1);
+        }
+        prefetched_hashes
+    } else {
+        hash_table_metrics.hashed_batches.add(batches.len());
+        hash_build_batches(
+            &batches,
+            &on_left,
+            &random_state,
+            build_threads,
+            &hash_table_metrics.build_tasks,
+        )
+        .await?
+    };
+
+    let mut hashmap = JoinHashMap::with_capacity(num_rows);
+    let mut offset = 0;
+
+    // Updating hashmap starting from the last batch
+    for hashes in batch_hashes.iter().rev() {
+        update_hash_from_values(&mut hashmap, hashes, offset, 0, true);
+        offset += hashes.len();
+    }
+    hash_table_metrics.record(&hashmap.fill_stats());
+    // Merge all batches into a single batch, so we
+    // can directly index into the arrays
+    let single_batch = concat_batches(&schema, batches.iter().rev())?;
+    build_timer.done();
+
+    if build_key_unique {
+        let keys = on_left
+            .iter()
+            .map(|c| {
+                c.evaluate(&single_batch)?
+                    .into_array(single_batch.num_rows())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if let Some((first, second)) =
+            hashmap.find_duplicate_keys(&keys, null_equals_null)?
+        {
+            return exec_err!(
+                "Build side join keys are hinted to be unique, but rows {first} and {second} have equal keys"
+            );
+        }
+        hashmap.release_chains();
+    }
+    if let Some(max_chain_length) = max_chain_length {
+        let keys = on_left
+            .iter()
+            .map(|c| {
+                c.evaluate(&single_batch)?
+                    .into_array(single_batch.num_rows())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let indexed_chains = hashmap.index_long_chains(&keys, max_chain_length)?;
+        hash_table_metrics.indexed_chains.set(indexed_chains);
+        let indices_size = hashmap.chain_indices_size();
+        reservation.try_grow(indices_size)?;
+        metrics.build_mem_used.add(indices_size);
+    }
+    let key_filter = if key_filter {
+        let key_filter =
+            JoinKeyFilter::from_hashes(batch_hashes.iter().map(Vec::as_slice));
+        reservation.try_grow(key_filter.size())?;
+        metrics.build_mem_used.add(key_filter.size());
+        Some(key_filter)
+    } else {
+        None
+    };
+    let data = JoinLeftData::new(hashmap, single_batch, key_filter, reservation);
+
+    Ok(data)
+}
+
+/// Updates `hash_map` with new entries from `batch` evaluated against the expressions `on`
+/// using `offset` as a start value for `batch` row indices.
+///
+/// `fifo_hashmap` sets the order of iteration over `batch` rows while updating hashmap,
+/// which allows to keep either first (if set to true) or last (if set to false) row index
+/// as a chain head for rows with equal hash values.
+#[allow(clippy::too_many_arguments)]
+pub fn update_hash<T>(
+    on: &[PhysicalExprRef],
+    batch: &RecordBatch,
+    hash_map: &mut T,
+    offset: usize,
+    random_state: &RandomState,
+    hashes_buffer: &mut Vec<u64>,
+    deleted_offset: usize,
+    fifo_hashmap: bool,
+) -> Result<()>
+where
+    T: JoinHashMapType,
+{
+    // evaluate the keys
+    let keys_values = on
+        .iter()
+        .map(|c| c.evaluate(batch)?.into_array(batch.num_rows()))
+        .collect::<Result<Vec<_>>>()?;
+
+    // calculate the hash values
+    let hash_values = create_hashes(&keys_values, random_state, hashes_buffer)?;
+
+    update_hash_from_values(hash_map, hash_values, offset, deleted_offset, fifo_hashmap);
+
+    Ok(())
+}
+
+/// Updates `hash_map` with the already computed `hash_values` of a batch, see
+/// [`update_hash`].
+fn update_hash_from_values<T>(
+    hash_map: &mut T,
+    hash_values: &[u64],
+    offset: usize,
+    deleted_offset: usize,
+    fifo_hashmap: bool,
+) where
+    T: JoinHashMapType,
+{
+    // For usual JoinHashmap, the implementation is void.
+    hash_map.extend_zero(hash_values.len());
+
+    // Updating JoinHashMap from hash values iterator
+    let hash_values_iter = hash_values
+        .iter()
+        .enumerate()
+        .map(|(i, val)| (i + offset, val));
+
+    if fifo_hashmap {
+        hash_map.update_from_iter(hash_values_iter.rev(), deleted_offset);
+    } else {
+        hash_map.update_from_iter(hash_values_iter, deleted_offset);
+    }
+}
+
+/// Computes the hashes of the join keys `on` of every batch, in the order of
+/// `batches`. The batches are split into up to `build_threads` contiguous
+/// chunks, which are hashed concurrently on blocking tasks. The number of
+/// tasks used is added to `build_tasks`.
+async fn hash_build_batches(
+    batches: &[RecordBatch],
+    on: &[PhysicalExprRef],
+    random_state: &RandomState,
+    build_threads: usize,
+    build_tasks: &metrics::Count,
+) -> Result<Vec<Vec<u64>>> {
+    if build_threads <= 1 || batches.len() <= 1 {
+        build_tasks.add(1);
+        return batches
+            .iter()
+            .map(|batch| hash_build_batch(batch, on, random_state))
+            .collect();
+    }
+
+    let chunk_size = batches.len().div_ceil(build_threads);
+    let tasks = batches
+        .chunks(chunk_size)
+        .map(|chunk| {
+            let chunk = chunk.to_vec();
+            let on = on.to_vec();
+            let random_state = random_state.clone();
+            SpawnedTask::spawn_blocking(move || {
+                chunk
+                    .iter()
+                    .map(|batch| hash_build_batch(batch, &on, &random_state))
+                    .collect::<Result<Vec<_>>>()
+            })
+        })
+        .collect::<Vec<_>>();
+    build_tasks.add(tasks.len());
+
+    let mut batch_hashes = Vec::with_capacity(batches.len());
+    for task in tasks {
+        match task.join().await {
+            Ok(hashes) => batch_hashes.extend(hashes?),
+            Err(e) => return exec_err!("Error occurred while hashing build side: {e}"),
+        }
+    }
+    Ok(batch_hashes)
+}
+
+/// Returns the hashes of the join keys `on` of the rows of `batch`
+fn hash_build_batch(
+    batch: &RecordBatch,
+    on: &[PhysicalExprRef],
+    random_state: &RandomState,
+) -> Result<Vec<u64>> {
+    let keys_values = on
+        .iter()
+        .map(|c| c.evaluate(batch)?.into_array(batch.num_rows()))
+        .collect::<Result<Vec<_>>>()?;
+    let mut hashes = vec![0; batch.num_rows()];
+    create_hashes(&keys_values, random_state, &mut hashes)?;
+    Ok(hashes)
+}
+
+/// Returns the hashes of the join keys of the rows of `batch` held by its
+/// `hash_column`, see [`HashJoinExec::with_build_hash_column`]. Fails if the
+/// column holds nulls, or if the hash of the first row does not match the one
+/// computed from its join keys `on`.
+fn read_build_hashes(
+    batch: &RecordBatch,
+    hash_column: &Column,
+    on: &[PhysicalExprRef],
+    random_state: &RandomState,
+) -> Result<Vec<u64>> {
+    let hashes = as_uint64_array(batch.column(hash_column.index()))?;
+    if hashes.null_count() > 0 {
+        return exec_err!("Build hash column {hash_column} holds nulls");
+    }
+    if batch.num_rows() > 0 {
+        let expected = hash_build_batch(&batch.slice(0, 1), on, random_state)?[0];
+        if hashes.value(0) != expected {
+            return exec_err!(
+                "Build hash column {hash_column} is inconsistent with the join keys: \
+                 it holds {} instead of {expected}",
+                hashes.value(0)
+            );
+        }
+    }
+    Ok(hashes.values().to_vec())
+}
+
+/// The distinct join keys of the build side rows read so far, to drop the rows
+/// repeating them, see [`HashJoinExec::dedups_build_keys`]
+struct SeenBuildKeys {
+    /// Converts the join keys into comparable rows
+    converter: RowConverter,
+    /// The join keys seen so far
+    keys: HashSet<OwnedRow>,
+    /// Size of `keys` in bytes
+    size: usize,
+}
+
+impl SeenBuildKeys {
+    /// Creates an empty set for the join keys `on` of the build side `schema`
+    fn try_new(on: &[PhysicalExprRef], schema: &Schema) -> Result<Self> {
+        let fields = on
+            .iter()
+            .map(|key| {
+                let data_type = match key.data_type(schema)? {
+                    DataType::Utf8View => DataType::Utf8,
+                    DataType::BinaryView => DataType::Binary,
+                    data_type => data_type,
+                };
+                Ok(SortField::new(data_type))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            converter: RowConverter::new(fields)?,
+            keys: HashSet::new(),
+            size: 0,
+        })
+    }
+
+    /// Returns a mask selecting the rows of `batch` whose join keys `on` have
+    /// not been seen before, in `batch` or any earlier batch, along with the
+    /// number of bytes the keys of the selected rows add to the set
+    fn first_occurrences(
+        &mut self,
+        batch: &RecordBatch,
+        on: &[PhysicalExprRef],
+    ) -> Result<(BooleanArray, usize)> {
+        // The row format doesn't support view arrays yet, whose keys are
+        // converted into the corresponding non-view arrays:
+        let keys_values = on
+            .iter()
+            .map(|c| {
+                let array = c.evaluate(batch)?.into_array(batch.num_rows())?;
+                Ok(match array.data_type() {
+                    DataType::Utf8View => Arc::new(
+                        as_string_view_array(&array)?
+                            .iter()
+                            .collect::<StringArray>(),
+                    ) as ArrayRef,
+                    DataType::BinaryView => Arc::new(
+                        as_binary_view_array(&array)?
+                            .iter()
+                            .collect::<BinaryArray>(),
+                    ),
+                    _ => array,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let rows = self.converter.convert_columns(&keys_values)?;
+        let mut added_size = 0;
+        let mask = rows
+            .iter()
+            .map(|row| {
+                let row_size = row.as_ref().len() + size_of::<OwnedRow>();
+                let is_first = self.keys.insert(row.owned());
+                if is_first {
+                    added_size += row_size;
+                }
+                Some(is_first)
+            })
+            .collect::<BooleanArray>();
+        self.size += added_size;
+        Ok((mask, added_size))
+    }
+}
+
+/// Reads the probe side input `stream` of a partition into memory and builds a
+/// filter over the hashes of its join keys `on_right`, to reduce the build
+/// side by, see [`HashJoinExec::with_semijoin_reduction`]
+async fn collect_probe_input(
+    mut stream: SendableRecordBatchStream,
+    on_right: Vec<PhysicalExprRef>,
+    random_state: RandomState,
+    mut reservation: MemoryReservation,
+) -> Result<BufferedProbeSide> {
+    let mut batches = vec![];
+    let mut batch_hashes = vec![];
+    while let Some(batch) = stream.next().await {
+        let batch = batch?;
+        reservation.try_grow(batch.get_array_memory_size())?;
+        batch_hashes.push(hash_build_batch(&batch, &on_right, &random_state)?);
+        batches.push(batch);
+    }
+    let key_filter = JoinKeyFilter::from_hashes(batch_hashes.iter().map(Vec::as_slice));
+    reservation.try_grow(key_filter.size())?;
+    Ok(BufferedProbeSide {
+        batches,
+        key_filter,
+        reservation,
+    })
+}
+
+/// Returns a stream of the probe side batches buffered by `probe_side`, once
+/// they have been read
+fn replay_probe_input(
+    schema: SchemaRef,
+    mut probe_side: OnceFut<BufferedProbeSide>,
+) -> SendableRecordBatchStream {
+    let stream =
+        futures::stream::once(
+            async move { poll_fn(|cx| probe_side.get_shared(cx)).await },
+        )
+        .map_ok(|probe_side| {
+            futures::stream::iter(probe_side.batches.clone().into_iter().map(Ok))
+        })
+        .try_flatten();
+    Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+}
+
+/// The left side of a [`HashJoinExec`] with adaptive partitioning, read until
+/// the join decided on its strategy, see
+/// [`HashJoinExec::with_adaptive_partitioning`]
+struct BuildSample {
+    /// The left side, replaying the batches read so far before the remaining
+    /// ones, hash repartitioned if the join switched to partitioned execution
+    build_input: Arc<dyn ExecutionPlan>,
+    /// The hash repartitioned right side, present if the join switched to
+    /// partitioned execution
+    probe_rehash: Option<Arc<dyn ExecutionPlan>>,
+}
+
+/// Reads the `left` side of a join with adaptive partitioning until it either
+/// ends or exceeds `max_rows`. In the latter case, the join switches to
+/// partitioned execution: both the `left` side, replaying the batches read so
+/// far, and the `right` side are repartitioned by hashing their join keys
+/// `on_left` and `on_right` into as many partitions as `right` has, and the
+/// declared `runtime_filters` are withdrawn.
+#[allow(clippy::too_many_arguments)]
+async fn sample_build_input(
+    left: Arc<dyn ExecutionPlan>,
+    right: Arc<dyn ExecutionPlan>,
+    on_left: Vec<PhysicalExprRef>,
+    on_right: Vec<PhysicalExprRef>,
+    max_rows: usize,
+    context: Arc<TaskContext>,
+    mut reservation: MemoryReservation,
+    switches: metrics::Count,
+    runtime_filters: Option<(RuntimeFilterRegistry, Vec<RuntimeFilterKey>)>,
+) -> Result<BuildSample> {
+    let schema = left.schema();
+    let mut stream = if left.output_partitioning().partition_count() != 1 {
+        CoalescePartitionsExec::new(left).execute(0, context)?
+    } else {
+        left.execute(0, context)?
+    };
+    let mut batches = vec![];
+    let mut num_rows = 0;
+    while num_rows <= max_rows {
+        let Some(batch) = stream.next().await.transpose()? else {
+            // The whole left side was read without exceeding the bound:
+            return Ok(BuildSample {
+                build_input: replay_build_input(schema, batches, None, reservation)?,
+                probe_rehash: None,
+            });
+        };
+        reservation.try_grow(batch.get_array_memory_size())?;
+        num_rows += batch.num_rows();
+        batches.push(batch);
+    }
+
+    switches.add(1);
+    if let Some((sink, keys)) = runtime_filters {
+        for key in keys {
+            sink.withdraw(&key);
+        }
+    }
+    let partitions = right.output_partitioning().partition_count();
+    let build_input = replay_build_input(schema, batches, Some(stream), reservation)?;
+    Ok(BuildSample {
+        build_input: Arc::new(RepartitionExec::try_new(
+            build_input,
+            Partitioning::Hash(on_left, partitions),
+        )?),
+        probe_rehash: Some(Arc::new(RepartitionExec::try_new(
+            right,
+            Partitioning::Hash(on_right, partitions),
+        )?)),
+    })
+}
+
+/// Returns a single partition plan replaying the buffered `batches` before the
+/// `remaining` ones of the stream they were read from. The memory of every
+/// buffered batch is released from `reservation` once it is replayed. The
+/// plan can only be executed once.
+fn replay_build_input(
+    schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+    remaining: Option<SendableRecordBatchStream>,
+    mut reservation: MemoryReservation,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let stream = futures::stream::iter(batches)
+        .map(move |batch| {
+            reservation.shrink(batch.get_array_memory_size());
+            Ok(batch)
+        })
+        .chain(futures::stream::iter(remaining).flatten());
+    let partition = ReplayedBuildInput {
+        schema: schema.clone(),
+        stream: Mutex::new(Some(Box::pin(RecordBatchStreamAdapter::new(
+            schema.clone(),
+            stream,
+        )))),
+    };
+    Ok(Arc::new(StreamingTableExec::try_new(
+        schema,
+        vec![Arc::new(partition)],
+        None,
+        vec![],
+        false,
+    )?))
+}
+
+/// The partition of the plan returned by [`replay_build_input`]
+struct ReplayedBuildInput {
+    schema: SchemaRef,
+    /// The replaying stream, taken once the partition is executed
+    stream: Mutex<Option<SendableRecordBatchStream>>,
+}
+
+impl PartitionStream for ReplayedBuildInput {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        match self.stream.lock().take() {
+            Some(stream) => stream,
+            None => {
+                let err = internal_datafusion_err!(
+                    "The replayed build side of a HashJoinExec can only be read once"
+                );
+                Box::pin(RecordBatchStreamAdapter::new(
+                    self.schema.clone(),
+                    futures::stream::once(async move { Err(err) }),
+                ))
+            }
+        }
+    }
+}
+
+/// Returns the stream of the `partition` of the `right` side of a join with
+/// adaptive partitioning, which is repartitioned if the join switched to
+/// partitioned execution, see [`BuildSample`]
+fn adaptive_probe_input(
+    right: Arc<dyn ExecutionPlan>,
+    mut build_sample: OnceFut<BuildSample>,
+    partition: usize,
+    context: Arc<TaskContext>,
+) -> SendableRecordBatchStream {
+    let schema = right.schema();
+    let stream = futures::stream::once(async move {
+        let build_sample = poll_fn(|cx| build_sample.get_shared(cx)).await?;
+        let right = build_sample.probe_rehash.clone().unwrap_or(right);
+        right.execute(partition, context)
+    })
+    .try_flatten();
+    Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+}
+
+/// Represents build-side of hash join.
+enum BuildSide {
+    /// Indicates that build-side not collected yet
+    Initial(BuildSideInitialState),
+    /// Indicates that build-side data has been collected
+    Ready(BuildSideReadyState),
+}
+
+/// Container for BuildSide::Initial related data
+struct BuildSideInitialState {
+    /// Future for building hash table from build-side input
+    left_fut: BoxFuture<'static, Result<Arc<JoinLeftData>>>,
+}
+
+/// Container for BuildSide::Ready related data
+struct BuildSideReadyState {
+    /// Collected build-side data
+    left_data: Arc<JoinLeftData>,
+    /// Which build-side rows have been matched while creating output.
+    /// For some OUTER joins, we need to know which rows have not been matched
+    /// to produce the correct output.
+    visited_left_side: MatchBitmap,
+}
+
+impl BuildSide {
+    /// Tries to extract BuildSideInitialState from BuildSide enum.
+    /// Returns an error if state is not Initial.
+    fn try_as_initial_mut(&mut self) -> Result<&mut BuildSideInitialState> {
+        match self {
+            BuildSide::Initial(state) => Ok(state),
+            _ => internal_err!("Expected build side in initial state"),
+        }
+    }
+
+    /// Tries to extract BuildSideReadyState from BuildSide enum.
+    /// Returns an error if state is not Ready.
+    fn try_as_ready(&self) -> Result<&BuildSideReadyState> {
+        match self {
+            BuildSide::Ready(state) => Ok(state),
+            _ => internal_err!("Expected build side in ready state"),
+        }
+    }
+
+    /// Tries to extract BuildSideReadyState from BuildSide enum.
+    /// Returns an error if state is not Ready.
+    fn try_as_ready_mut(&mut self) -> Result<&mut BuildSideReadyState> {
+        match self {
+            BuildSide::Ready(state) => Ok(state),
+            _ => internal_err!("Expected build side in ready state"),
+        }
+    }
+}
+
+/// Represents state of HashJoinStream
+///
+/// Expected state transitions performed by HashJoinStream are:
+///
+/// ```text
+///
+///       WaitBuildSide
+///             │
+///             ▼
+///  ┌─► FetchProbeBatch ───► ExhaustedProbeSide ───► Completed
+///  │          │
+///  │          ▼
+///  └─ ProcessProbeBatch
+///
+/// ```
+enum HashJoinStreamState {
+    /// Initial state for HashJoinStream indicating that build-side data not collected yet
+    WaitBuildSide,
+    /// Indicates that build-side has been collected, and stream is ready for fetching probe-side
+    FetchProbeBatch,
+    /// Indicates that non-empty batch has been fetched from probe-side, and is ready to be processed
+    ProcessProbeBatch(ProcessProbeBatchState),
+    /// Indicates that probe-side has been fully processed
+    ExhaustedProbeSide,
+    /// Indicates that HashJoinStream execution is completed
+    Completed,
+}
+
+impl HashJoinStreamState {
+    /// Tries to extract ProcessProbeBatchState from HashJoinStreamState enum.
+    /// Returns an error if state is not ProcessProbeBatchState.
+    fn try_as_process_probe_batch_mut(&mut self) -> Result<&mut ProcessProbeBatchState> {
+        match self {
+            HashJoinStreamState::ProcessProbeBatch(state) => Ok(state),
+            _ => internal_err!("Expected hash join stream in ProcessProbeBatch state"),
+        }
+    }
+}
+
+/// Container for HashJoinStreamState::ProcessProbeBatch related data
+struct ProcessProbeBatchState {
+    /// Current probe-side batch
+    batch: RecordBatch,
+    /// Starting offset for JoinHashMap lookups
+    offset: JoinHashMapOffset,
+    /// Max joined probe-side index from current batch
+    joined_probe_idx: Option<usize>,
+    /// Result of the probe side only filter conjuncts for the rows of
+    /// `batch`, present if they are applied before the hash lookup
+    probe_filter_mask: Option<BooleanArray>,
+}
+
+impl ProcessProbeBatchState {
+    fn advance(&mut self, offset: JoinHashMapOffset, joined_probe_idx: Option<usize>) {
+        self.offset = offset;
+        if joined_probe_idx.is_some() {
+            self.joined_probe_idx = joined_probe_idx;
+        }
+    }
+}
+
+/// [`Stream`] for [`HashJoinExec`] that does the actual join.
+///
+/// This stream:
+///
+/// 1. Reads the entire left input (build) and constructs a hash table
+///
+/// 2. Streams [RecordBatch]es as they arrive from the right input (probe) and joins
+/// them with the contents of the hash table
+struct HashJoinStream {
+    /// Input schema
+    schema: Arc<Schema>,
+    /// equijoin columns from the left (build side)
+    on_left: Vec<PhysicalExprRef>,
+    /// equijoin columns from the right (probe side)
+    on_right: Vec<PhysicalExprRef>,
+    /// optional join filter
+    filter: Option<JoinFilter>,
+    /// Probe side only conjuncts of `filter`, if any
+    probe_filter: Option<ProbeFilter>,
+    /// type of the join (left, right, semi, etc)
+    join_type: JoinType,
+    /// right (probe) input
+    right: SendableRecordBatchStream,
+    /// Random state used for hashing initialization
+    random_state: RandomState,
+    /// Metrics
+    join_metrics: BuildProbeJoinMetrics,
+    /// Information of index and left / right placement of columns
+    column_indices: Vec<ColumnIndex>,
+    /// If null_equals_null is true, null == null else null != null
+    null_equals_null: bool,
+    /// Whether probe rows with a null join key are dropped before hashing, as
+    /// they can not affect the output
+    skip_null_keys: bool,
+    /// Whether probe rows are dropped before the hash lookup if their join
+    /// keys are not in the filter over the build side keys
+    reduce_probe_side: bool,
+    /// Memory reservation
+    reservation: MemoryReservation,
+    /// State of the stream
+    state: HashJoinStreamState,
+    /// Build side
+    build_side: BuildSide,
+    /// Maximum output batch size
+    batch_size: usize,
+    /// Maximum number of rows of an output batch, if the join emits its
+    /// output in low latency chunks
+    low_latency_chunk_size: Option<usize>,
+    /// Upper bound of the bytes taken by the values of an output batch, if any
+    output_byte_budget: Option<usize>,
+    /// Upper bound of the estimated serialized size of an output batch, if any
+    max_serialized_batch_bytes: Option<usize>,
+    /// Chunks of output rows exceeding the output byte budget of the batch
+    /// they were joined for, emitted before the stream advances
+    pending_output: VecDeque<OutputChunk>,
+    /// Scratch space for computing hashes
+    hashes_buffer: Vec<u64>,
+    /// Output schema including the row provenance columns and the unmatched
+    /// row marker, present if any of them are requested. `schema` only covers
+    /// the join columns in that case.
+    hidden_columns_schema: Option<SchemaRef>,
+    /// Whether the row provenance columns are emitted
+    row_provenance: bool,
+    /// Whether the unmatched row marker is emitted
+    unmatched_marker: bool,
+    /// Whether all probe rows are emitted with the match flag
+    match_flag: bool,
+    /// Number of join key value comparisons made to resolve hash collisions
+    key_comparisons: metrics::Count,
+    /// Number of times the join filter was evaluated against a probe batch
+    filter_evaluations: metrics::Count,
+    /// Number of probe rows dropped before hashing for their null join keys
+    skipped_null_key_rows: metrics::Count,
+    /// Number of probe rows dropped by the semijoin reduction
+    semijoin_reduced_probe_rows: metrics::Count,
+    /// Warns about the stream waiting too long for its inputs, if enabled
+    stall_watchdog: Option<StallWatchdog>,
+    /// Sample the matched join key values are traced into, if enabled
+    matched_keys_trace: Option<MatchedKeysTrace>,
+    /// Buffers the output rows to emit them grouped by probe key, if enabled
+    probe_key_groups: Option<ProbeKeyGroups>,
+    /// Whether the build side of an inner join consists of a single row, which
+    /// the probe rows are then compared to directly instead of being hashed
+    /// and looked up in the hash table
+    single_build_row: bool,
+    /// Null arrays padding the columns of the side without a match
+    null_padding: NullPadding,
+    /// Mark column of a mark join, inserted into the batches built from the
+    /// join columns in `schema`
+    mark: Option<MarkColumn>,
+}
+
+/// Marks the rows of a [`JoinType::LeftMark`] or [`JoinType::RightMark`]
+/// join, see [`MARK_COLUMN`]
+///
+/// [`MARK_COLUMN`]: crate::joins::utils::MARK_COLUMN
+struct MarkColumn {
+    /// Output schema including the mark column
+    schema: SchemaRef,
+    /// Positions of the mark column in the output schema, more than one if
+    /// the projection repeats it
+    positions: Vec<usize>,
+    /// Whether unmatched rows may be marked null, i.e. unless nulls are equal
+    null_aware: bool,
+    /// Whether the side the marked rows are matched against has any rows
+    other_side_has_rows: bool,
+    /// Whether the side the marked rows are matched against has a null key
+    other_side_has_null_key: bool,
+}
+
+impl MarkColumn {
+    /// Records the join `keys` of rows of the side the marked rows are
+    /// matched against
+    fn record_other_side_keys(&mut self, keys: &ArrayRef) {
+        self.other_side_has_rows |= !keys.is_empty();
+        self.other_side_has_null_key |= keys
+            .logical_nulls()
+            .is_some_and(|nulls| nulls.null_count() > 0);
+    }
+}
+
+/// Tracks the progress of a [`HashJoinStream`], see
+/// [`HashJoinExec::with_stall_timeout`]
+struct StallWatchdog {
+    /// How long the stream may wait for its inputs before a warning is logged
+    timeout: Duration,
+    /// Output partition of the stream
+    partition: usize,
+    /// Partition of the build side the stream waits for, `None` if it is
+    /// collected from all partitions
+    build_partition: Option<usize>,
+    /// When the stream last produced an output batch
+    last_progress: Instant,
+    /// Fires `timeout` after the last progress or warning, created once the
+    /// stream first waits for its inputs
+    deadline: Option<Pin<Box<Sleep>>>,
+    /// Number of warnings logged
+    warnings: metrics::Count,
+}
+
+impl StallWatchdog {
+    /// Records that the stream made progress
+    fn progressed(&mut self) {
+        self.last_progress = Instant::now();
+        if let Some(deadline) = &mut self.deadline {
+            deadline.as_mut().reset(self.last_progress + self.timeout);
+        }
+    }
+
+    /// Logs a warning if the stream, waiting for its inputs in `state`, made no
+    /// progress for the timeout since the last progress or warning. Registers
+    /// `cx` to be woken up by the next deadline.
+    fn check(&mut self, state: &HashJoinStreamState, cx: &mut std::task::Context<'_>) {
+        let timeout = self.timeout;
+        let deadline = self
+            .deadline
+            .get_or_insert_with(|| Box::pin(sleep_until(self.last_progress + timeout)));
+        if deadline.as_mut().poll(cx).is_pending() {
+            return;
+        }
+        let (side, input_partition) = match state {
+            HashJoinStreamState::WaitBuildSide => (
+                "left",
+                self.build_partition
+                    .map_or("all".to_string(), |partition| partition.to_string()),
+            ),
+            _ => ("right", self.partition.to_string()),
+        };
+        warn!(
+            "HashJoinExec stalled: partition={}, waiting_for_side={side}, \
+             waiting_for_partition={input_partition}, stalled_for={:?}",
+            self.partition,
+            self.last_progress.elapsed()
+        );
+        self.warnings.add(1);
+        deadline.as_mut().reset(Instant::now() + timeout);
+        // Registers the waker for the next deadline
+        let _ = deadline.as_mut().poll(cx);
+    }
+}
+
+/// Conjuncts of the join filter of a [`HashJoinStream`] referencing only probe
+/// side columns, see [`HashJoinExec::with_probe_filter_first`]
+struct ProbeFilter {
+    /// The conjuncts, evaluated on probe batches
+    predicate: PhysicalExprRef,
+    /// The filter made of the other conjuncts, if any
+    remaining: Option<JoinFilter>,
+    /// Whether the conjuncts are applied before the hash lookup, `None` until
+    /// decided by the first non-empty probe batch
+    first: Option<bool>,
+}
+
+impl RecordBatchStream for HashJoinStream {
+    fn schema(&self) -> SchemaRef {
+        self.hidden_columns_schema
+            .clone()
+            .unwrap_or_else(|| self.schema.clone())
+    }
+}
+
+/// Drops the matches of probe rows rejected by `probe_mask`
+fn filter_by_probe_mask(
+    build_indices: UInt64Array,
+    probe_indices: UInt32Array,
+    probe_mask: &BooleanArray,
+) -> Result<(UInt64Array, UInt32Array)> {
+    let keep = take(probe_mask, &probe_indices, None)?;
+    let keep = FilterBuilder::new(as_boolean_array(&keep)?)
+        .optimize()
+        .build();
+    Ok((
+        downcast_array(keep.filter(&build_indices)?.as_ref()),
+        downcast_array(keep.filter(&probe_indices)?.as_ref()),
+    ))
+}
+
+/// Executes lookups by hash against JoinHashMap and resolves potential
+/// hash collisions.
+/// Returns build/probe indices satisfying the equality condition, along with
+/// (optional) starting point for next iteration.
+///
+/// # Example
+///
+/// For `LEFT.b1 = RIGHT.b2`:
+/// LEFT (build) Table:
+/// ```text
+///  a1  b1  c1
+///  1   1   10
+///  3   3   30
+///  5   5   50
+///  7   7   70
+///  9   8   90
+///  11  8   110
+///  13   10  130
+/// ```
+///
+/// RIGHT (probe) Table:
+/// ```text
+///  a2   b2  c2
+///  2    2   20
+///  4    4   40
+///  6    6   60
+///  8    8   80
+/// 10   10  100
+/// 12   10  120
+/// ```
+///
+/// The result is
+/// ```text
+/// "+----+----+-----+----+----+-----+",
+/// "| a1 | b1 | c1  | a2 | b2 | c2  |",
+/// "+----+----+-----+----+----+-----+",
+/// "| 9  | 8  | 90  | 8  | 8  | 80  |",
+/// "| 11 | 8  | 110 | 8  | 8  | 80  |",
+/// "| 13 | 10 | 130 | 10 | 10 | 100 |",
+/// "| 13 | 10 | 130 | 12 | 10 | 120 |",
+/// "+----+----+-----+----+----+-----+"
+/// ```
+///
+/// And the result of build and probe indices are:
+/// ```text
+/// Build indices: 4, 5, 6, 6
+/// Probe indices: 3, 3, 4, 5
+/// ```
+#[allow(clippy::too_many_arguments)]
+fn lookup_join_hashmap(
+    build_hashmap: &JoinHashMap,
+    build_input_buffer: &RecordBatch,
+    probe_batch: &RecordBatch,
+    build_on: &[PhysicalExprRef],
+    probe_on: &[PhysicalExprRef],
+    null_equals_null: bool,
+    hashes_buffer: &[u64],
+    limit: usize,
+    offset: JoinHashMapOffset,
+) -> Result<(UInt64Array, UInt32Array, Option<JoinHashMapOffset>)> {
+    lookup_join_hashmap_with_key_comparisons(
+        build_hashmap,
+        build_input_buffer,
+        probe_batch,
+        build_on,
+        probe_on,
+        null_equals_null,
+        hashes_buffer,
+        limit,
+        offset,
+        None,
+    )
+}
+
+/// Like [`lookup_join_hashmap`], adding the number of join key comparisons
+/// made to `key_comparisons`, if given.
+#[allow(clippy::too_many_arguments)]
+fn lookup_join_hashmap_with_key_comparisons(
+    build_hashmap: &JoinHashMap,
+    build_input_buffer: &RecordBatch,
+    probe_batch: &RecordBatch,
+    build_on: &[PhysicalExprRef],
+    probe_on: &[PhysicalExprRef],
+    null_equals_null: bool,
+    hashes_buffer: &[u64],
+    limit: usize,
+    offset: JoinHashMapOffset,
+    key_comparisons: Option<&metrics::Count>,
+) -> Result<(UInt64Array, UInt32Array, Option<JoinHashMapOffset>)> {
+    let keys_values = probe_on
+        .iter()
+        .map(|c| c.evaluate(probe_batch)?.into_array(probe_batch.num_rows()))
+        .collect::<Result<Vec<_>>>()?;
+    let build_join_values = build_on
+        .iter()
+        .map(|c| {
+            c.evaluate(build_input_buffer)?
+                .into_array(build_input_buffer.num_rows())
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if let Some(converter) = build_hashmap.chain_key_converter() {
+        let same_types = keys_values
+            .iter()
+            .zip(&build_join_values)
+            .all(|(probe, build)| probe.data_type() == build.data_type());
+        if same_types {
+            return lookup_indexed_join_hashmap(
+                build_hashmap,
+                converter,
+                &build_join_values,
+                &keys_values,
+                null_equals_null,
+                hashes_buffer,
+                limit,
+                offset,
+                key_comparisons,
+            );
+        }
+    }
+
+    let (mut probe_builder, mut build_builder, next_offset) = build_hashmap
+        .get_matched_indices_with_limit_offset(hashes_buffer, None, limit, offset);
+
+    let build_indices: UInt64Array =
+        PrimitiveArray::new(build_builder.finish().into(), None);
+    let probe_indices: UInt32Array =
+        PrimitiveArray::new(probe_builder.finish().into(), None);
+
+    let (build_indices, probe_indices) = equal_rows_arr(
+        &build_indices,
+        &probe_indices,
+        &build_join_values,
+        &keys_values,
+        null_equals_null,
+        key_comparisons,
+    )?;
+
+    Ok((build_indices, probe_indices, next_offset))
+}
+
+/// Like [`lookup_join_hashmap`] for a hash table with indexed chains, see
+/// [`JoinHashMap::index_long_chains`]: the candidates found in a chain index
+/// are known to have equal keys, so only the other candidates are compared.
+#[allow(clippy::too_many_arguments)]
+fn lookup_indexed_join_hashmap(
+    build_hashmap: &JoinHashMap,
+    converter: &RowConverter,
+    build_join_values: &[ArrayRef],
+    keys_values: &[ArrayRef],
+    null_equals_null: bool,
+    hashes_buffer: &[u64],
+    limit: usize,
+    offset: JoinHashMapOffset,
+    key_comparisons: Option<&metrics::Count>,
+) -> Result<(UInt64Array, UInt32Array, Option<JoinHashMapOffset>)> {
+    let probe_rows = converter.convert_columns(keys_values)?;
+    // Probe rows with a null key have no match unless nulls are equal:
+    let probe_nulls = if null_equals_null {
+        None
+    } else {
+        keys_values.iter().fold(None, |nulls, key| {
+            NullBuffer::union(nulls.as_ref(), key.nulls())
+        })
+    };
+    let mut matches = build_hashmap.get_matched_indices_with_chain_index(
+        hashes_buffer,
+        &probe_rows,
+        probe_nulls.as_ref(),
+        limit,
+        offset,
+    );
+    if let Some(key_comparisons) = key_comparisons {
+        key_comparisons.add(matches.key_comparisons);
+    }
+
+    let build_indices: UInt64Array =
+        PrimitiveArray::new(matches.build_indices.finish().into(), None);
+    let probe_indices: UInt32Array =
+        PrimitiveArray::new(matches.probe_indices.finish().into(), None);
+    let verified = BooleanArray::new(matches.verified.finish(), None);
+    let unverified = not(&verified)?;
+
+    let (checked_build, checked_probe) = equal_rows_arr(
+        &downcast_array(filter(&build_indices, &unverified)?.as_ref()),
+        &downcast_array(filter(&probe_indices, &unverified)?.as_ref()),
+        build_join_values,
+        keys_values,
+        null_equals_null,
+        key_comparisons,
+    )?;
+    let verified_build: UInt64Array =
+        downcast_array(filter(&build_indices, &verified)?.as_ref());
+    let verified_probe: UInt32Array =
+        downcast_array(filter(&probe_indices, &verified)?.as_ref());
+
+    // The candidates of a probe row are either all verified or all checked, so
+    // merging both by probe row restores the order of the candidates:
+    let mut build_builder = UInt64BufferBuilder::new(build_indices.len());
+    let mut probe_builder = UInt32BufferBuilder::new(probe_indices.len());
+    let (mut i, mut j) = (0, 0);
+    while i < verified_probe.len() || j < checked_probe.len() {
+        let take_verified = j == checked_probe.len()
+            || (i < verified_probe.len()
+                && verified_probe.value(i) < checked_probe.value(j));
+        if take_verified {
+            build_builder.append(verified_build.value(i));
+            probe_builder.append(verified_probe.value(i));
+            i += 1;
+        } else {
+            build_builder.append(checked_build.value(j));
+            probe_builder.append(checked_probe.value(j));
+            j += 1;
+        }
+    }
+
+    Ok((
+        PrimitiveArray::new(build_builder.finish().into(), None),
+        PrimitiveArray::new(probe_builder.finish().into(), None),
+        matches.next_offset,
+    ))
+}
+
+/// Matches the rows of `probe_batch` to the single row of `build_batch` by
+/// comparing their join keys directly, without looking up a hash table. As
+/// every probe row matches at most once, the whole batch is matched at once,
+/// so no offset to continue from is returned.
+fn match_single_build_row(
+    build_batch: &RecordBatch,
+    probe_batch: &RecordBatch,
+    build_on: &[PhysicalExprRef],
+    probe_on: &[PhysicalExprRef],
+    null_equals_null: bool,
+    key_comparisons: &metrics::Count,
+) -> Result<(UInt64Array, UInt32Array, Option<JoinHashMapOffset>)> {
+    let evaluate = |on: &[PhysicalExprRef], batch: &RecordBatch| {
+        on.iter()
+            .map(|c| c.evaluate(batch)?.into_array(batch.num_rows()))
+            .collect::<Result<Vec<_>>>()
+    };
+    let num_rows = probe_batch.num_rows();
+    let (build_indices, probe_indices) = equal_rows_arr(
+        &UInt64Array::from(vec![0; num_rows]),
+        &UInt32Array::from_iter_values(0..num_rows as u32),
+        &evaluate(build_on, build_batch)?,
+        &evaluate(probe_on, probe_batch)?,
+        null_equals_null,
+        Some(key_comparisons),
+    )?;
+    Ok((build_indices, probe_indices, None))
+}
+
+// version of eq_dyn supporting equality on null arrays
+fn eq_dyn_null(
+    left: &dyn Array,
+    right: &dyn Array,
+    null_equals_null: bool,
+) -> Result<BooleanArray, ArrowError> {
+    match (left.data_type(), right.data_type()) {
+        (DataType::Utf8View, DataType::Utf8View) => Ok(eq_byte_views(
+            left.as_any().downcast_ref::<StringViewArray>().unwrap(),
+            right.as_any().downcast_ref::<StringViewArray>().unwrap(),
+            null_equals_null,
+        )),
+        (DataType::BinaryView, DataType::BinaryView) => Ok(eq_byte_views(
+            left.as_any().downcast_ref::<BinaryViewArray>().unwrap(),
+            right.as_any().downcast_ref::<BinaryViewArray>().unwrap(),
+            null_equals_null,
+        )),
+        (DataType::Struct(_), DataType::Struct(_)) => {
+            eq_structs(left.as_struct(), right.as_struct(), null_equals_null)
+        }
+        _ if null_equals_null => not_distinct(&left, &right),
+        _ => eq(&left, &right),
+    }
+}
+
+/// Compares two struct arrays of the same length row by row, field by field.
+/// Unless `null_equals_null`, structs with a null field never compare equal,
+/// as the comparison of that field is unknown.
+fn eq_structs(
+    left: &StructArray,
+    right: &StructArray,
+    null_equals_null: bool,
+) -> Result<BooleanArray, ArrowError> {
+    if left.num_columns() != right.num_columns() {
+        return Err(ArrowError::InvalidArgumentError(format!(
+            "Cannot compare struct join keys of types {} and {}",
+            left.data_type(),
+            right.data_type()
+        )));
+    }
+    let fields = left
+        .columns()
+        .iter()
+        .zip(right.columns())
+        .map(|(left, right)| eq_dyn_null(left.as_ref(), right.as_ref(), null_equals_null))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((0..left.len())
+        .map(|i| {
+            Some(match (left.is_valid(i), right.is_valid(i)) {
+                (true, true) => fields
+                    .iter()
+                    .all(|equal| equal.is_valid(i) && equal.value(i)),
+                (false, false) => null_equals_null,
+                _ => false,
+            })
+        })
+        .collect())
+}
+
+/// Compares two view arrays of the same length row by row. The lower 64 bits
+/// of a view hold the length and the first four bytes of its value, so most
+/// unequal values are told apart without looking at the data buffers.
+fn eq_byte_views<T: ByteViewType + ?Sized>(
+    left: &GenericByteViewArray<T>,
+    right: &GenericByteViewArray<T>,
+    null_equals_null: bool,
+) -> BooleanArray {
+    let (left_views, right_views) = (left.views(), right.views());
+    (0..left.len())
+        .map(|i| {
+            Some(match (left.is_valid(i), right.is_valid(i)) {
+                (true, true) => {
+                    left_views[i] as u64 == right_views[i] as u64 && {
+                        let left_value: &[u8] = left.value(i).as_ref();
+                        let right_value: &[u8] = right.value(i).as_ref();
+                        left_value == right_value
+                    }
+                }
+                (false, false) => null_equals_null,
+                _ => false,
+            })
+        })
+        .collect()
+}
+
+/// Keeps the pairs of `indices_left` and `indices_right` for which all the
+/// join key values in `left_arrays` and `right_arrays` are equal.
+///
+/// The keys are compared one at a time, each one only for the pairs matched by
+/// all the previous keys, so placing the most selective key first minimizes
+/// the number of comparisons. This number is added to `key_comparisons`.
+pub fn equal_rows_arr(
+    indices_left: &UInt64Array,
+    indices_right: &UInt32Array,
+    left_arrays: &[ArrayRef],
+    right_arrays: &[ArrayRef],
+    null_equals_null: bool,
+    key_comparisons: Option<&metrics::Count>,
+) -> Result<(UInt64Array, UInt32Array)> {
+    if left_arrays.is_empty() || right_arrays.is_empty() {
+        return internal_err!(
+            "At least one array should be provided for both left and right"
+        );
+    }
+
+    let mut indices_left = indices_left.clone();
+    let mut indices_right = indices_right.clone();
+    for (left, right) in left_arrays.iter().zip(right_arrays.iter()) {
+        if indices_left.is_empty() {
+            break;
+        }
+        if let Some(key_comparisons) = key_comparisons {
+            key_comparisons.add(indices_left.len());
+        }
+        let arr_left = take_join_column(left.as_ref(), &indices_left)?;
+        let arr_right = take_join_column(right.as_ref(), &indices_right)?;
+        let equal = eq_dyn_null(&arr_left, &arr_right, null_equals_null)?;
+
+        let filter_builder = FilterBuilder::new(&equal).optimize().build();
+        let left_filtered = filter_builder.filter(&indices_left)?;
+        let right_filtered = filter_builder.filter(&indices_right)?;
+        indices_left = downcast_array(left_filtered.as_ref());
+        indices_right = downcast_array(right_filtered.as_ref());
+    }
+
+    Ok((indices_left, indices_right))
+}
+
+impl HashJoinStream {
+    /// Separate implementation function that unpins the [`HashJoinStream`] so
+    /// that partial borrows work correctly. Buffers the joined batches if the
+    /// output is grouped by probe key, see [`ProbeKeyGroups`].
+    fn poll_next_impl(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Result<RecordBatch>>> {
+        if self.probe_key_groups.is_none() {
+            return self.poll_joined(cx);
+        }
+        loop {
+            let joined = ready!(self.poll_joined(cx));
+            let Some(groups) = self.probe_key_groups.as_mut() else {
+                return Poll::Ready(joined);
+            };
+            match joined {
+                Some(Ok(batch)) => {
+                    if let Some(grouped) = groups.push(batch).transpose() {
+                        return Poll::Ready(Some(grouped));
+                    }
+                }
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => return Poll::Ready(groups.flush().transpose()),
+            }
+        }
+    }
+
+    /// Polls the next batch of joined rows
+    fn poll_joined(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Result<RecordBatch>>> {
+        loop {
+            if let Some(chunk) = self.pending_output.pop_front() {
+                return Poll::Ready(Some(self.process_pending_output(chunk)));
+            }
+            return match self.state {
+                HashJoinStreamState::WaitBuildSide => {
+                    handle_state!(ready!(self.collect_build_side(cx)))
+                }
+                HashJoinStreamState::FetchProbeBatch => {
+                    handle_state!(ready!(self.fetch_probe_batch(cx)))
+                }
+                HashJoinStreamState::ProcessProbeBatch(_) => {
+                    handle_state!(self.process_probe_batch())
+                }
+                HashJoinStreamState::ExhaustedProbeSide => {
+                    handle_state!(self.process_unmatched_build_batch())
+                }
+                HashJoinStreamState::Completed => Poll::Ready(None),
+            };
+        }
+    }
+
+    /// Collects build-side data by polling `OnceFut` future from initialized build-side
+    ///
+    /// Updates build-side to `Ready`, and state to `FetchProbeSide`
+    fn collect_build_side(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Result<StatefulStreamResult<Option<RecordBatch>>>> {
+        let build_timer = self.join_metrics.build_time.timer();
+        // build hash table from left (build) side, if not yet done
+        let left_data = ready!(self
+            .build_side
+            .try_as_initial_mut()?
+            .left_fut
+            .poll_unpin(cx))?;
+        build_timer.done();
+        self.single_build_row =
+            self.join_type == JoinType::Inner && left_data.num_rows() == 1;
+        if let Some(mark) = self.mark.as_mut().filter(|mark| mark.null_aware) {
+            if self.join_type == JoinType::RightMark {
+                let batch = left_data.batch();
+                let keys = self.on_left[0]
+                    .evaluate(batch)?
+                    .into_array(batch.num_rows())?;
+                mark.record_other_side_keys(&keys);
+            }
+        }
+
+        // Reserving memory for visited_left_side bitmap in case it hasn't been initialized yet
+        // and join_type requires to store it
+        if need_produce_result_in_final(self.join_type) {
+            let visited_bitmap_size = MatchBitmap::size(left_data.num_rows());
+            self.reservation.try_grow(visited_bitmap_size)?;
+            self.join_metrics.build_mem_used.add(visited_bitmap_size);
+        }
+
+        let visited_left_side = if need_produce_result_in_final(self.join_type) {
+            let num_rows = left_data.num_rows();
+            // Some join types need to track which row has be matched or unmatched:
+            // `left semi` join:  need to use the bitmap to produce the matched row in the left side
+            // `left` join:       need to use the bitmap to produce the unmatched row in the left side with null
+            // `left anti` join:  need to use the bitmap to produce the unmatched row in the left side
+            // `full` join:       need to use the bitmap to produce the unmatched row in the left side with null
+            MatchBitmap::new(num_rows)
+        } else {
+            MatchBitmap::new(0)
+        };
+
+        self.state = HashJoinStreamState::FetchProbeBatch;
+        self.build_side = BuildSide::Ready(BuildSideReadyState {
+            left_data,
+            visited_left_side,
+        });
+
+        Poll::Ready(Ok(StatefulStreamResult::Continue))
+    }
+
+    /// Fetches next batch from probe-side
+    ///
+    /// If non-empty batch has been fetched, updates state to `ProcessProbeBatchState`,
+    /// otherwise updates state to `ExhaustedProbeSide`
+    fn fetch_probe_batch(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Result<StatefulStreamResult<Option<RecordBatch>>>> {
+        match ready!(self.right.poll_next_unpin(cx)) {
+            None => {
+                self.state = HashJoinStreamState::ExhaustedProbeSide;
+            }
+            Some(Ok(mut batch)) => {
+                self.join_metrics.input_batches.add(1);
+                self.join_metrics.input_rows.add(batch.num_rows());
+
+                // Precalculate hash values for fetched batch
+                let mut keys_values = self
+                    .on_right
+                    .iter()
+                    .map(|c| c.evaluate(&batch)?.into_array(batch.num_rows()))
+                    .collect::<Result<Vec<_>>>()?;
+
+                if let Some(mark) = self.mark.as_mut().filter(|mark| mark.null_aware) {
+                    if self.join_type == JoinType::LeftMark {
+                        mark.record_other_side_keys(&keys_values[0]);
+                    }
+                }
+
+                if self.skip_null_keys {
+                    // A row is kept if none of its keys is null:
+                    let valid = keys_values.iter().fold(None, |valid, keys| {
+                        NullBuffer::union(valid.as_ref(), keys.logical_nulls().as_ref())
+                    });
+                    if let Some(valid) = valid.filter(|v| v.null_count() > 0) {
+                        self.skipped_null_key_rows.add(valid.null_count());
+                        let mask = BooleanArray::new(valid.into_inner(), None);
+                        batch = filter_record_batch(&batch, &mask)?;
+                        keys_values = keys_values
+                            .iter()
+                            .map(|keys| arrow::compute::filter(keys, &mask))
+                            .collect::<Result<Vec<_>, _>>()?;
+                    }
+                }
+
+                // The keys of a single build row are compared to the probe
+                // keys directly, without hashing them:
+                if !self.single_build_row {
+                    self.hashes_buffer.clear();
+                    self.hashes_buffer.resize(batch.num_rows(), 0);
+                    create_hashes(
+                        &keys_values,
+                        &self.random_state,
+                        &mut self.hashes_buffer,
+                    )?;
+                }
+
+                if self.reduce_probe_side && !self.single_build_row {
+                    let build_side = self.build_side.try_as_ready()?;
+                    if let Some(key_filter) = build_side.left_data.key_filter() {
+                        let (mask, rejected) = key_filter.mask(&self.hashes_buffer);
+                        if rejected > 0 {
+                            self.semijoin_reduced_probe_rows.add(rejected);
+                            batch = filter_record_batch(&batch, &mask)?;
+                            self.hashes_buffer.retain(|hash| key_filter.contains(*hash));
+                        }
+                    }
+                }
+
+                let probe_filter_mask = self.probe_filter_mask(&batch)?;
+                self.state =
+                    HashJoinStreamState::ProcessProbeBatch(ProcessProbeBatchState {
+                        batch,
+                        offset: (0, None),
+                        joined_probe_idx: None,
+                        probe_filter_mask,
+                    });
+            }
+            Some(Err(err)) => return Poll::Ready(Err(err)),
+        };
+
+        Poll::Ready(Ok(StatefulStreamResult::Continue))
+    }
+
+    /// Evaluates the probe side only filter conjuncts on `batch` if they are
+    /// to be applied before the hash lookup, deciding whether they are based
+    /// on the first non-empty batch unless configured
+    fn probe_filter_mask(&mut self, batch: &RecordBatch) -> Result<Option<BooleanArray>> {
+        let Some(probe_filter) = self.probe_filter.as_mut() else {
+            return Ok(None);
+        };
+        if probe_filter.first == Some(false)
+            || batch.num_rows() == 0
+            || self
+                .filter
+                .as_ref()
+                .is_some_and(|f| is_join_filter_satisfied(batch, f, JoinSide::Right))
+        {
+            return Ok(None);
+        }
+        self.filter_evaluations.add(1);
+        let mask = probe_filter
+            .predicate
+            .evaluate(batch)?
+            .into_array(batch.num_rows())?;
+        let mask = as_boolean_array(&mask)?.clone();
+        if probe_filter.first.is_none() {
+            let rejected = mask.len() - mask.true_count();
+            probe_filter.first = Some(rejected * 2 >= mask.len());
+        }
+        Ok(Some(mask))
+    }
+
+    /// Joins current probe batch with build-side data and produces batch with matched output
+    ///
+    /// Updates state to `FetchProbeBatch`
+    fn process_probe_batch(
+        &mut self,
+    ) -> Result<StatefulStreamResult<Option<RecordBatch>>> {
+        let state = self.state.try_as_process_probe_batch_mut()?;
+        let build_side = self.build_side.try_as_ready_mut()?;
+
+        // Building the output borrows the stream mutably, so the timers run
+        // on clones of the metrics:
+        let join_time = self.join_metrics.join_time.clone();
+        let timer = join_time.timer();
+        let probe_timer = self.join_metrics.probe_time.timer();
+
+        // get the matched by join keys indices
+        let (left_indices, right_indices, next_offset) = if self.single_build_row {
+            match_single_build_row(
+                build_side.left_data.batch(),
+                &state.batch,
+                &self.on_left,
+                &self.on_right,
+                self.null_equals_null,
+                &self.key_comparisons,
+            )?
+        } else {
+            lookup_join_hashmap_with_key_comparisons(
+                build_side.left_data.hash_map(),
+                build_side.left_data.batch(),
+                &state.batch,
+                &self.on_left,
+                &self.on_right,
+                self.null_equals_null,
+                &self.hashes_buffer,
+                self.batch_size,
+                state.offset,
+                Some(&self.key_comparisons),
+            )?
+        };
+
+        // apply join filter if exists, unless the probe batch is known to satisfy it
+        let (left_indices, right_indices) = match (
+            self.filter
+                .as_ref()
+                .filter(|f| !is_join_filter_satisfied(&state.batch, f, JoinSide::Right)),
+            &state.probe_filter_mask,
+        ) {
+            // The probe side only conjuncts were evaluated before the lookup,
+            // so only the remaining ones need to be evaluated on the matches
+            (Some(_), Some(mask)) => {
+                let (left_indices, right_indices) =
+                    filter_by_probe_mask(left_indices, right_indices, mask)?;
+                match self
+                    .probe_filter
+                    .as_ref()
+                    .and_then(|p| p.remaining.as_ref())
+                {
+                    Some(remaining) => {
+                        self.filter_evaluations.add(1);
+                        apply_join_filter_to_indices(
+                            build_side.left_data.batch(),
+                            &state.batch,
+                            left_indices,
+                            right_indices,
+                            remaining,
+                            JoinSide::Left,
+                        )?
+                    }
+                    None => (left_indices, right_indices),
+                }
+            }
+            (Some(filter), None) => {
+                self.filter_evaluations.add(1);
+                apply_join_filter_to_indices(
+                    build_side.left_data.batch(),
+                    &state.batch,
+                    left_indices,
+                    right_indices,
+                    filter,
+                    JoinSide::Left,
+                )?
+            }
+            (None, _) => (left_indices, right_indices),
+        };
+
+        // mark joined left-side indices as visited, if required by join type
+        if need_produce_result_in_final(self.join_type) {
+            build_side.visited_left_side.mark_matched(&left_indices);
+        }
+
+        if let Some(trace) = &self.matched_keys_trace {
+            let keys = self
+                .on_right
+                .iter()
+                .map(|c| c.evaluate(&state.batch)?.into_array(state.batch.num_rows()))
+                .collect::<Result<Vec<_>>>()?;
+            trace.record(&keys, &right_indices)?;
+        }
+
+        // The goals of index alignment for different join types are:
+        //
+        // 1) Right & FullJoin -- to append all missing probe-side indices between
+        //    previous (excluding) and current joined indices.
+        // 2) SemiJoin -- deduplicate probe indices in range between previous
+        //    (excluding) and current joined indices.
+        // 3) AntiJoin -- return only missing indices in range between
+        //    previous and current joined indices.
+        //    Inclusion/exclusion of the indices themselves don't matter
+        //
+        // As a summary -- alignment range can be produced based only on
+        // joined (matched with filters applied) probe side indices, excluding starting one
+        // (left from previous iteration).
+
+        // if any rows have been joined -- get last joined probe-side (right) row
+        // it's important that index counts as "joined" after hash collisions checks
+        // and join filters applied.
+        let last_joined_right_idx = match right_indices.len() {
+            0 => None,
+            n => Some(right_indices.value(n - 1) as usize),
+        };
+
+        // Calculate range and perform alignment.
+        // In case probe batch has been processed -- align all remaining rows.
+        let index_alignment_range_start = state.joined_probe_idx.map_or(0, |v| v + 1);
+        let index_alignment_range_end = if next_offset.is_none() {
+            state.batch.num_rows()
+        } else {
+            last_joined_right_idx.map_or(0, |v| v + 1)
+        };
+
+        let index_alignment_range =
+            index_alignment_range_start..index_alignment_range_end;
+        let (left_indices, right_indices) = if self.match_flag {
+            match_flag_indices(&left_indices, &right_indices, index_alignment_range)
+        } else {
+            adjust_indices_by_join_type(
+                left_indices,
+                right_indices,
+                index_alignment_range,
+                self.join_type,
+            )
+        };
+
+        let probe_batch = state.batch.clone();
+        if next_offset.is_none() {
+            self.state = HashJoinStreamState::FetchProbeBatch;
+        } else {
+            state.advance(
+                next_offset
+                    .ok_or_else(|| internal_datafusion_err!("unexpected None offset"))?,
+                last_joined_right_idx,
+            )
+        };
+        probe_timer.done();
+
+        let output_time = self.join_metrics.output_time.clone();
+        let output_timer = output_time.timer();
+        let mut chunks = self.split_output(probe_batch, left_indices, right_indices)?;
+        let first_chunk = chunks.remove(0);
+        self.pending_output.extend(chunks);
+        let result = self.build_serializable_output_batch(first_chunk)?;
+        output_timer.done();
+
+        self.join_metrics.output_batches.add(1);
+        self.join_metrics.output_rows.add(result.num_rows());
+        timer.done();
+
+        Ok(StatefulStreamResult::Ready(Some(result)))
+    }
+
+    /// Splits the output rows of the joined `build_indices` and
+    /// `probe_indices` into chunks of at most the low latency chunk size, see
+    /// [`HashJoinExec::with_low_latency`], fitting into the output byte
+    /// budget, see [`HashJoinExec::with_max_output_batch_bytes`]. Returns at
+    /// least one, possibly empty, chunk.
+    fn split_output(
+        &self,
+        probe_batch: RecordBatch,
+        build_indices: UInt64Array,
+        probe_indices: UInt32Array,
+    ) -> Result<Vec<OutputChunk>> {
+        // The output rows of right semi and anti joins are the probe side
+        // rows, and their build side indices are not used:
+        let num_rows = probe_indices.len();
+        let build_indices = if build_indices.len() == num_rows {
+            build_indices
+        } else {
+            UInt64Array::new_null(num_rows)
+        };
+        let mut ranges = match self.low_latency_chunk_size {
+            Some(chunk_size) => (0..num_rows)
+                .step_by(chunk_size)
+                .map(|start| start..(start + chunk_size).min(num_rows))
+                .collect(),
+            None => vec![],
+        };
+        if ranges.is_empty() {
+            ranges.push(0..num_rows);
+        }
+        if let Some(byte_budget) = self.output_byte_budget {
+            let build_side = self.build_side.try_as_ready()?;
+            ranges = ranges
+                .into_iter()
+                .flat_map(|rows| {
+                    split_indices_by_byte_budget(
+                        build_side.left_data.batch(),
+                        &probe_batch,
+                        &build_indices.slice(rows.start, rows.len()),
+                        &probe_indices.slice(rows.start, rows.len()),
+                        &self.column_indices,
+                        JoinSide::Left,
+                        byte_budget,
+                    )
+                    .into_iter()
+                    .map(move |range| rows.start + range.start..rows.start + range.end)
+                })
+                .collect();
+        }
+        Ok(ranges
+            .into_iter()
+            .map(|range| OutputChunk {
+                probe_batch: probe_batch.clone(),
+                build_indices: build_indices.slice(range.start, range.len()),
+                probe_indices: probe_indices.slice(range.start, range.len()),
+            })
+            .collect())
+    }
+
+    /// Builds the output batch of the rows of `chunk`, including the row
+    /// provenance columns, the unmatched row marker and the match flag if
+    /// requested
+    fn build_output_batch(&mut self, chunk: &OutputChunk) -> Result<RecordBatch> {
+        let build_side = self.build_side.try_as_ready()?;
+        let batch = build_batch_from_indices(
+            &self.schema,
+            build_side.left_data.batch(),
+            &chunk.probe_batch,
+            &chunk.build_indices,
+            &chunk.probe_indices,
+            &self.column_indices,
+            JoinSide::Left,
+            &mut self.null_padding,
+        )?;
+        if let Some(mark) = &self.mark {
+            let marks = self.mark_rows(mark, chunk)?;
+            let mut columns = batch.columns().to_vec();
+            for position in &mark.positions {
+                columns.insert(*position, marks.clone());
+            }
+            let options =
+                RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+            return Ok(RecordBatch::try_new_with_options(
+                mark.schema.clone(),
+                columns,
+                &options,
+            )?);
+        }
+        match &self.hidden_columns_schema {
+            Some(schema) => append_hidden_columns(
+                batch,
+                schema,
+                self.join_type,
+                &chunk.build_indices,
+                &chunk.probe_indices,
+                self.row_provenance,
+                self.unmatched_marker,
+                self.match_flag,
+            ),
+            None => Ok(batch),
+        }
+    }
+
+    /// Computes the mark column of the rows of `chunk` of a mark join: rows
+    /// with a match are marked true, and rows without one false. Unless nulls
+    /// are equal, an unmatched row is marked null instead if its key is null
+    /// or the other side has a null key, provided the other side has any rows,
+    /// like the result of `IN` for a null key.
+    fn mark_rows(&self, mark: &MarkColumn, chunk: &OutputChunk) -> Result<ArrayRef> {
+        let num_rows = chunk.probe_indices.len();
+        // The rows of the marked side, and whether they are matched:
+        let (batch, key, rows, matched): (_, _, Vec<usize>, Vec<bool>) =
+            if self.join_type == JoinType::RightMark {
+                (
+                    &chunk.probe_batch,
+                    &self.on_right[0],
+                    chunk
+                        .probe_indices
+                        .values()
+                        .iter()
+                        .map(|i| *i as usize)
+                        .collect(),
+                    (0..num_rows)
+                        .map(|row| chunk.build_indices.is_valid(row))
+                        .collect(),
+                )
+            } else {
+                (
+                    self.build_side.try_as_ready()?.left_data.batch(),
+                    &self.on_left[0],
+                    chunk
+                        .build_indices
+                        .values()
+                        .iter()
+                        .map(|i| *i as usize)
+                        .collect(),
+                    (0..num_rows)
+                        .map(|row| chunk.probe_indices.is_valid(row))
+                        .collect(),
+                )
+            };
+        if !mark.null_aware || !mark.other_side_has_rows {
+            return Ok(Arc::new(BooleanArray::from(matched)));
+        }
+        let key_nulls = if mark.other_side_has_null_key {
+            None
+        } else {
+            key.evaluate(batch)?
+                .into_array(batch.num_rows())?
+                .logical_nulls()
+        };
+        let marks = matched
+            .into_iter()
+            .zip(rows)
+            .map(|(matched, row)| {
+                if matched {
+                    Some(true)
+                } else if mark.other_side_has_null_key
+                    || key_nulls.as_ref().is_some_and(|nulls| nulls.is_null(row))
+                {
+                    None
+                } else {
+                    Some(false)
+                }
+            })
+            .collect::<BooleanArray>();
+        Ok(Arc::new(marks))
+    }
+
+    /// Builds the output batch of the rows of `chunk` like
+    /// [`Self::build_output_batch`]. If its estimated serialized size exceeds
+    /// [`HashJoinExec::with_max_serialized_batch_bytes`], the rows are split
+    /// into smaller chunks, the first of which is built while the others are
+    /// deferred.
+    fn build_serializable_output_batch(
+        &mut self,
+        mut chunk: OutputChunk,
+    ) -> Result<RecordBatch> {
+        let batch = loop {
+            let batch = self.build_output_batch(&chunk)?;
+            let Some(max_bytes) = self.max_serialized_batch_bytes else {
+                break batch;
+            };
+            let batch_bytes = estimated_ipc_size(&batch);
+            if batch_bytes <= max_bytes {
+                break batch;
+            }
+            let num_rows = chunk.build_indices.len();
+            let mut parts = split_serialized_rows(num_rows, batch_bytes, max_bytes)?
+                .into_iter()
+                .map(|rows| OutputChunk {
+                    probe_batch: chunk.probe_batch.clone(),
+                    build_indices: chunk.build_indices.slice(rows.start, rows.len()),
+                    probe_indices: chunk.probe_indices.slice(rows.start, rows.len()),
+                })
+                .collect::<VecDeque<_>>();
+            chunk = parts.pop_front().unwrap();
+            while let Some(part) = parts.pop_back() {
+                self.pending_output.push_front(part);
+            }
+        };
+        if let Some(groups) = &mut self.probe_key_groups {
+            groups.record_keys(&chunk)?;
+        }
+        Ok(batch)
+    }
+
+    /// Produces the output batch of a chunk of rows deferred by the output
+    /// byte budget
+    fn process_pending_output(&mut self, chunk: OutputChunk) -> Result<RecordBatch> {
+        let join_time = self.join_metrics.join_time.clone();
+        let timer = join_time.timer();
+        let output_time = self.join_metrics.output_time.clone();
+        let output_timer = output_time.timer();
+        let result = self.build_serializable_output_batch(chunk)?;
+        output_timer.done();
+        self.join_metrics.output_batches.add(1);
+        self.join_metrics.output_rows.add(result.num_rows());
+        timer.done();
+        Ok(result)
+    }
+
+    /// Processes unmatched build-side rows for certain join types and produces output batch
+    ///
+    /// Updates state to `Completed`
+    fn process_unmatched_build_batch(
+        &mut self,
+    ) -> Result<StatefulStreamResult<Option<RecordBatch>>> {
+        let join_time = self.join_metrics.join_time.clone();
+        let timer = join_time.timer();
+
+        if !need_produce_result_in_final(self.join_type) {
+            self.state = HashJoinStreamState::Completed;
+
+            return Ok(StatefulStreamResult::Continue);
+        }
+
+        let build_side = self.build_side.try_as_ready()?;
+        let output_time = self.join_metrics.output_time.clone();
+        let output_timer = output_time.timer();
+
+        // use the global left bitmap to produce the left indices and right indices
+        let (left_side, right_side) =
+            get_final_indices_from_bit_map(&build_side.visited_left_side, self.join_type);
+        let empty_right_batch = new_empty_join_batch(self.right.schema())?;
+        // use the left and right indices to produce the batch result
+        let mut chunks = self.split_output(empty_right_batch, left_side, right_side)?;
+        let first_chunk = chunks.remove(0);
+        self.pending_output.extend(chunks);
+        let result = self.build_serializable_output_batch(first_chunk);
+        output_timer.done();
+
+        if let Ok(ref batch) = result {
+            self.join_metrics.input_batches.add(1);
+            self.join_metrics.input_rows.add(batch.num_rows());
+
+            self.join_metrics.output_batches.add(1);
+            self.join_metrics.output_rows.add(batch.num_rows());
+        }
+        timer.done();
+
+        self.state = HashJoinStreamState::Completed;
+
+        Ok(StatefulStreamResult::Ready(Some(result?)))
+    }
+}
+
+impl Stream for HashJoinStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let poll = self.poll_next_impl(cx);
+        let this = &mut *self;
+        if let Some(watchdog) = &mut this.stall_watchdog {
+            match poll {
+                Poll::Ready(_) => watchdog.progressed(),
+                Poll::Pending => watchdog.check(&this.state, cx),
+            }
+        }
+        poll
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::joins::output_shaping::LOW_LATENCY_OUTPUT_CHUNKS;
+    use crate::joins::test_utils::{
+        build_sides_record_batches, collect_and_record, compare_batches,
+        complicated_filter, read_recorded_batches, single_key_record_batches,
+        split_record_batches, with_statistics_override, IpcRecordingStream,
+    };
+    use crate::joins::utils::{
+        mark_join_filter_satisfied, BUILD_ROW_COLUMN, MATCHED_ROW_COLUMN,
+        PROBE_ROW_COLUMN, UNMATCHED_ROW_COLUMN,
+    };
+    use crate::{
+        common, displayable, expressions::Column, hash_utils::create_hashes,
+        memory::MemoryExec, repartition::RepartitionExec, sorts::sort::SortExec,
+        test::build_table_i32, test::exec::BlockingExec, test::exec::MockExec,
+        test::exec::StatisticsExec,
+    };
+
+    use arrow::array::{
+        ArrayRef, AsArray, Date32Array, Float64Array, Int32Array, Int64Array,
+        StringArray, StringViewArray, UInt32Builder, UInt64Builder,
+    };
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow_schema::SortOptions;
+    use datafusion_common::cast::as_int32_array;
+    use datafusion_common::{
+        assert_batches_eq, assert_batches_sorted_eq, assert_contains, exec_err,
+        ScalarValue,
+    };
+    use datafusion_execution::config::SessionConfig;
+    use datafusion_execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+    use datafusion_expr::Operator;
+    use datafusion_physical_expr::expressions::{BinaryExpr, Literal};
+    use datafusion_physical_expr::PhysicalExpr;
+
+    use hashbrown::raw::RawTable;
+    use rstest::*;
+    use rstest_reuse::{self, *};
+
+    fn div_ceil(a: usize, b: usize) -> usize {
+        (a + b - 1) / b
+    }
+
+    #[template]
+    #[rstest]
+    fn batch_sizes(#[values(8192, 10, 5, 2, 1)] batch_size: usize) {}
+
+    fn prepare_task_ctx(batch_size: usize) -> Arc<TaskContext> {
+        let session_config = SessionConfig::default().with_batch_size(batch_size);
+        Arc::new(TaskContext::default().with_session_config(session_config))
+    }
+
+    fn build_table(
+        a: (&str, &Vec<i32>),
+        b: (&str, &Vec<i32>),
+        c: (&str, &Vec<i32>),
+    ) -> Arc<dyn ExecutionPlan> {
+        let batch = build_table_i32(a, b, c);
+        let schema = batch.schema();
+        Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+    }
+
+    fn build_table_f64(
+        a: (&str, &Vec<i32>),
+        b: (&str, &Vec<f64>),
+    ) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(a.0, DataType::Int32, false),
+            Field::new(b.0, DataType::Float64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(a.1.clone())),
+                Arc::new(Float64Array::from(b.1.clone())),
+            ],
+        )
+        .unwrap();
+        Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+    }
+
+    fn join(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: JoinOn,
+        join_type: &JoinType,
+        null_equals_null: bool,
+    ) -> Result<HashJoinExec> {
+        HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            join_type,
+            None,
+            PartitionMode::CollectLeft,
+            null_equals_null,
+        )
+    }
+
+    fn join_with_filter(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: JoinOn,
+        filter: JoinFilter,
+        join_type: &JoinType,
+        null_equals_null: bool,
+    ) -> Result<HashJoinExec> {
+        HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            Some(filter),
+            join_type,
+            None,
+            PartitionMode::CollectLeft,
+            null_equals_null,
+        )
+    }
+
+    async fn join_collect(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: JoinOn,
+        join_type: &JoinType,
+        null_equals_null: bool,
+        context: Arc<TaskContext>,
+    ) -> Result<(Vec<String>, Vec<RecordBatch>)> {
+        let join = join(left, right, on, join_type, null_equals_null)?;
+        let columns_header = columns(&join.schema());
+
+        let stream = join.execute(0, context)?;
+        let batches = common::collect(stream).await?;
+
+        Ok((columns_header, batches))
+    }
+
+    async fn partitioned_join_collect(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: JoinOn,
+        join_type: &JoinType,
+        null_equals_null: bool,
+        context: Arc<TaskContext>,
+    ) -> Result<(Vec<String>, Vec<RecordBatch>)> {
+        let partition_count = 4;
+
+        let (left_expr, right_expr) =
+            on.iter().map(|(l, r)| (l.clone(), r.clone())).unzip();
+
+        let join = HashJoinExec::try_new(
+            Arc::new(RepartitionExec::try_new(
+                left,
+                Partitioning::Hash(left_expr, partition_count),
+            )?),
+            Arc::new(RepartitionExec::try_new(
+                right,
+                Partitioning::Hash(right_expr, partition_count),
+            )?),
+            on,
+            None,
+            join_type,
+            None,
+            PartitionMode::Partitioned,
+            null_equals_null,
+        )?;
+
+        let columns = columns(&join.schema());
+
+        let mut batches = vec![];
+        for i in 0..partition_count {
+            let stream = join.execute(i, context.clone())?;
+            let more_batches = common::collect(stream).await?;
+            batches.extend(
+                more_batches
+                    .into_iter()
+                    .filter(|b| b.num_rows() > 0)
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        Ok((columns, batches))
+    }
+
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn join_inner_one(batch_size: usize) -> Result<()> {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 5]), // this has a repetition
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        let (columns, batches) = join_collect(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::Inner,
+            false,
+            task_ctx,
+        )
+        .await?;
+
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 3  | 5  | 9  | 20 | 5  | 80 |",
+            "+----+----+----+----+----+----+",
+        ];
+
+        // Inner join output is expected to preserve both inputs order
+        assert_batches_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn partitioned_join_inner_one(batch_size: usize) -> Result<()> {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 5]), // this has a repetition
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        let (columns, batches) = partitioned_join_collect(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::Inner,
+            false,
+            task_ctx,
+        )
+        .await?;
+
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 3  | 5  | 9  | 20 | 5  | 80 |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_inner_one_no_shared_column_names() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 5]), // this has a repetition
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b2", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let (columns, batches) =
+            join_collect(left, right, on, &JoinType::Inner, false, task_ctx).await?;
+
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b2", "c2"]);
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 3  | 5  | 9  | 20 | 5  | 80 |",
+            "+----+----+----+----+----+----+",
+        ];
+
+        // Inner join output is expected to preserve both inputs order
+        assert_batches_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_inner_one_randomly_ordered() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let left = build_table(
+            ("a1", &vec![0, 3, 2, 1]),
+            ("b1", &vec![4, 5, 5, 4]),
+            ("c1", &vec![6, 9, 8, 7]),
+        );
+        let right = build_table(
+            ("a2", &vec![20, 30, 10]),
+            ("b2", &vec![5, 6, 4]),
+            ("c2", &vec![80, 90, 70]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let (columns, batches) =
+            join_collect(left, right, on, &JoinType::Inner, false, task_ctx).await?;
+
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b2", "c2"]);
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 3  | 5  | 9  | 20 | 5  | 80 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 0  | 4  | 6  | 10 | 4  | 70 |",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "+----+----+----+----+----+----+",
+        ];
+
+        // Inner join output is expected to preserve both inputs order
+        assert_batches_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn join_inner_two(batch_size: usize) -> Result<()> {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_table(
+            ("a1", &vec![1, 2, 2]),
+            ("b2", &vec![1, 2, 2]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b2", &vec![1, 2, 2]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![
+            (
+                Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("a1", &right.schema())?) as _,
+            ),
+            (
+                Arc::new(Column::new_with_schema("b2", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+            ),
+        ];
+
+        let (columns, batches) =
+            join_collect(left, right, on, &JoinType::Inner, false, task_ctx).await?;
+
+        assert_eq!(columns, vec!["a1", "b2", "c1", "a1", "b2", "c2"]);
+
+        // expected joined records = 3
+        // in case batch_size is 1 - additional empty batch for remaining 3-2 row
+        let mut expected_batch_count = div_ceil(3, batch_size);
+        if batch_size == 1 {
+            expected_batch_count += 1;
+        }
+        assert_eq!(batches.len(), expected_batch_count);
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b2 | c1 | a1 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 1  | 7  | 1  | 1  | 70 |",
+            "| 2  | 2  | 8  | 2  | 2  | 80 |",
+            "| 2  | 2  | 9  | 2  | 2  | 80 |",
+            "+----+----+----+----+----+----+",
+        ];
+
+        // Inner join output is expected to preserve both inputs order
+        assert_batches_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_inner_with_probe_threads() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4]),
+            ("b1", &vec![4, 5, 5, 6]),
+            ("c1", &vec![7, 8, 9, 10]),
+        );
+        let right_batches = (0..4)
+            .map(|i| {
+                build_table_i32(
+                    ("a2", &vec![10 * i, 10 * i + 1]),
+                    ("b1", &vec![4 + i, 5 + i]),
+                    ("c2", &vec![70 + i, 80 + i]),
+                )
+            })
+            .collect::<Vec<_>>();
+        let schema = right_batches[0].schema();
+        let right = Arc::new(MemoryExec::try_new(&[right_batches], schema, None)?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        let (_, expected) = join_collect(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::Inner,
+            false,
+            task_ctx.clone(),
+        )
+        .await?;
+
+        let join =
+            join(left, right, on, &JoinType::Inner, false)?.with_probe_threads(3)?;
+        assert_eq!(join.properties().output_partitioning().partition_count(), 3);
+        // The probe side is fanned out by a repartitioning in the plan:
+        let probe_fanout = join.right().as_any().downcast_ref::<RepartitionExec>();
+        assert!(matches!(
+            probe_fanout.map(|fanout| fanout.partitioning()),
+            Some(Partitioning::RoundRobinBatch(3))
+        ));
+
+        let mut batches = vec![];
+        for i in 0..3 {
+            let stream = join.execute(i, task_ctx.clone())?;
+            batches.extend(common::collect(stream).await?);
+        }
+
+        let row_count =
+            |batches: &[RecordBatch]| batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        assert_eq!(row_count(&batches), row_count(&expected));
+        let expected = arrow::util::pretty::pretty_format_batches(&expected)?.to_string();
+        let expected_lines = expected.trim().lines().collect::<Vec<_>>();
+        assert_batches_sorted_eq!(expected_lines, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join_with_build_threads() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let (left_batch, right_batch) = build_sides_record_batches(200, (4, 5))?;
+        let left = Arc::new(MemoryExec::try_new(
+            &[split_record_batches(&left_batch, 10)?],
+            left_batch.schema(),
+            None,
+        )?);
+        let right = Arc::new(MemoryExec::try_new(
+            &[split_record_batches(&right_batch.slice(0, 50), 10)?],
+            right_batch.schema(),
+            None,
+        )?);
+        // Build side keys are heavily duplicated, so that rows are chained:
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("rc1", &right.schema())?) as _,
+        )];
+
+        let mut results = vec![];
+        for build_threads in [1, 4] {
+            let join = join(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                &JoinType::Full,
+                false,
+            )?
+            .with_build_threads(build_threads);
+            assert_eq!(join.build_threads(), build_threads);
+            let batches = common::collect(join.execute(0, task_ctx.clone())?).await?;
+            let build_tasks = join
+                .metrics()
+                .unwrap()
+                .sum_by_name("hash_table_build_tasks")
+                .unwrap()
+                .as_usize();
+            assert_eq!(build_tasks, build_threads);
+            results
+                .push(arrow::util::pretty::pretty_format_batches(&batches)?.to_string());
+        }
+        // The output, including its order, doesn't depend on the build threads:
+        assert_eq!(results[0], results[1]);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join_with_build_prefetch() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let (left_batch, right_batch) = build_sides_record_batches(200, (4, 5))?;
+        // A slow build side source, taking 10ms per batch:
+        let left_batches = split_record_batches(&left_batch, 20)?;
+        let left = Arc::new(
+            MockExec::new(
+                left_batches.into_iter().map(Ok).collect(),
+                left_batch.schema(),
+            )
+            .with_batch_delay(std::time::Duration::from_millis(10)),
+        );
+        let right = Arc::new(MemoryExec::try_new(
+            &[split_record_batches(&right_batch.slice(0, 50), 10)?],
+            right_batch.schema(),
+            None,
+        )?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("rc1", &right.schema())?) as _,
+        )];
+
+        let mut results = vec![];
+        for build_prefetch in [0, 4] {
+            let join = join(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                &JoinType::Full,
+                false,
+            )?
+            .with_build_prefetch(build_prefetch);
+            assert_eq!(join.build_prefetch(), build_prefetch);
+            let batches = common::collect(join.execute(0, task_ctx.clone())?).await?;
+            let build_wall_time = join
+                .metrics()
+                .unwrap()
+                .sum_by_name("hash_table_build_wall_time")
+                .unwrap()
+                .as_usize();
+            // The build takes at least as long as reading the slow source:
+            assert!(build_wall_time >= 10 * 10_000_000);
+            results
+                .push(arrow::util::pretty::pretty_format_batches(&batches)?.to_string());
+        }
+        // The output, including its order, doesn't depend on the prefetching:
+        assert_eq!(results[0], results[1]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_max_chain_length() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        // Chains of 50 rows with the same key, and a single chain of all rows
+        for (left_batch, right_batch) in [
+            build_sides_record_batches(200, (4, 5))?,
+            single_key_record_batches(200)?,
+        ] {
+            let left = Arc::new(MemoryExec::try_new(
+                &[split_record_batches(&left_batch, 20)?],
+                left_batch.schema(),
+                None,
+            )?);
+            let right = Arc::new(MemoryExec::try_new(
+                &[split_record_batches(&right_batch, 20)?],
+                right_batch.schema(),
+                None,
+            )?);
+            let on = vec![(
+                Arc::new(Column::new_with_schema("lc1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("rc1", &right.schema())?) as _,
+            )];
+
+            let mut results = vec![];
+            let mut key_comparisons = vec![];
+            for max_chain_length in [0, 1] {
+                let join = join(
+                    left.clone(),
+                    right.clone(),
+                    on.clone(),
+                    &JoinType::Full,
+                    false,
+                )?
+                .with_max_chain_length(max_chain_length);
+                let batches = common::collect(join.execute(0, task_ctx.clone())?).await?;
+                let metrics = join.metrics().unwrap();
+                let indexed_chains = metrics
+                    .sum_by_name("hash_table_indexed_chains")
+                    .unwrap()
+                    .as_usize();
+                key_comparisons
+                    .push(metrics.sum_by_name("key_comparisons").unwrap().as_usize());
+                let display = displayable(&join).one_line().to_string();
+                if max_chain_length == 0 {
+                    assert_eq!(join.max_chain_length(), None);
+                    assert_eq!(indexed_chains, 0);
+                    assert!(!display.contains("max_chain_length"));
+                } else {
+                    assert_eq!(join.max_chain_length(), Some(1));
+                    assert!(indexed_chains > 0);
+                    assert_contains!(display, "max_chain_length=1");
+                }
+                results.push(
+                    arrow::util::pretty::pretty_format_batches(&batches)?.to_string(),
+                );
+            }
+            // The output, including its order, doesn't depend on the chain
+            // index, while the keys of a probe row are no longer compared to
+            // every row of its chain:
+            assert_eq!(results[0], results[1]);
+            assert!(
+                key_comparisons[1] * 10 < key_comparisons[0],
+                "{key_comparisons:?}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_adaptive_partitioning() -> Result<()> {
+        let left_batch = build_table_i32(
+            ("a1", &(0..1000).collect()),
+            ("b1", &(0..1000).map(|i| i % 100).collect()),
+            ("c1", &(0..1000).collect()),
+        );
+        let right_batch = build_table_i32(
+            ("a2", &(0..400).collect()),
+            ("b2", &(0..400).map(|i| i % 150).collect()),
+            ("c2", &(0..400).collect()),
+        );
+        // Without the trailing empty batch:
+        let mut right_batches = split_record_batches(&right_batch, 50)?;
+        right_batches.pop();
+        let right_partitions = right_batches
+            .chunks(2)
+            .map(|batches| batches.to_vec())
+            .collect::<Vec<_>>();
+        let right: Arc<dyn ExecutionPlan> = Arc::new(MemoryExec::try_new(
+            &right_partitions,
+            right_batch.schema(),
+            None,
+        )?);
+        let single_right: Arc<dyn ExecutionPlan> = Arc::new(MemoryExec::try_new(
+            &[right_batches],
+            right_batch.schema(),
+            None,
+        )?);
+        assert_eq!(right.output_partitioning().partition_count(), 4);
+        // The left side claims to have far fewer rows than it has, unless the
+        // estimate is accurate:
+        let left = |num_rows: usize| -> Result<Arc<dyn ExecutionPlan>> {
+            let schema = left_batch.schema();
+            with_statistics_override(
+                split_record_batches(&left_batch, 100)?,
+                vec![],
+                Statistics {
+                    num_rows: Precision::Inexact(num_rows),
+                    total_byte_size: Precision::Absent,
+                    column_statistics: Statistics::unknown_column(&schema),
+                },
+            )
+        };
+        let on = |left: &Arc<dyn ExecutionPlan>, right: &Arc<dyn ExecutionPlan>| {
+            Ok::<JoinOn, DataFusionError>(vec![(
+                Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+            )])
+        };
+        let task_ctx = Arc::new(TaskContext::default());
+
+        for join_type in [
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Full,
+            JoinType::LeftAnti,
+            JoinType::RightSemi,
+        ] {
+            // The probe side is not split across partitions, so that the
+            // unmatched build rows are emitted once:
+            let expected = join(
+                left(1000)?,
+                single_right.clone(),
+                on(&left(1000)?, &single_right)?,
+                &join_type,
+                false,
+            )?;
+            let expected = crate::collect(Arc::new(expected), task_ctx.clone()).await?;
+
+            // The build side exceeds twice its estimate with its first batch,
+            // so every partition builds a hash table over its share of it:
+            let adaptive = join(
+                left(10)?,
+                right.clone(),
+                on(&left(10)?, &right)?,
+                &join_type,
+                false,
+            )?
+            .with_adaptive_partitioning(2)?;
+            assert_eq!(adaptive.adaptive_partitioning(), Some(2));
+            assert_contains!(
+                displayable(&adaptive).one_line().to_string(),
+                ", adaptive_partitioning=2"
+            );
+            let adaptive = Arc::new(adaptive);
+            let batches = crate::collect(adaptive.clone(), task_ctx.clone()).await?;
+            compare_batches(&batches, &expected);
+            assert_eq!(
+                batches.iter().map(RecordBatch::num_rows).sum::<usize>(),
+                expected.iter().map(RecordBatch::num_rows).sum::<usize>(),
+            );
+            let metrics = adaptive.metrics().unwrap();
+            assert_eq!(
+                metrics
+                    .sum_by_name("adaptive_partitioning_switches")
+                    .unwrap()
+                    .as_usize(),
+                1
+            );
+            // Every partition read its share of the build side:
+            assert_eq!(
+                metrics.sum_by_name("build_input_rows").unwrap().as_usize(),
+                1000
+            );
+        }
+
+        // An accurate estimate keeps the single hash table:
+        let adaptive = join(
+            left(1000)?,
+            right.clone(),
+            on(&left(1000)?, &right)?,
+            &JoinType::Inner,
+            false,
+        )?
+        .with_adaptive_partitioning(2)?;
+        let adaptive = Arc::new(adaptive);
+        let batches = crate::collect(adaptive.clone(), task_ctx.clone()).await?;
+        let expected = join(
+            left(1000)?,
+            right.clone(),
+            on(&left(1000)?, &right)?,
+            &JoinType::Inner,
+            false,
+        )?;
+        let expected = crate::collect(Arc::new(expected), task_ctx.clone()).await?;
+        compare_batches(&batches, &expected);
+        let metrics = adaptive.metrics().unwrap();
+        assert_eq!(
+            metrics
+                .sum_by_name("adaptive_partitioning_switches")
+                .unwrap()
+                .as_usize(),
+            0
+        );
+
+        // The switch needs a CollectLeft join over an unordered probe side:
+        let err = HashJoinExec::try_new(
+            left(10)?,
+            right.clone(),
+            on(&left(10)?, &right)?,
+            None,
+            &JoinType::Inner,
+            None,
+            PartitionMode::Partitioned,
+            false,
+        )?
+        .with_adaptive_partitioning(2)
+        .unwrap_err();
+        assert_contains!(err.to_string(), "only supported in CollectLeft mode");
+        let sort_expr = PhysicalSortExpr {
+            expr: Arc::new(Column::new("a2", 0)),
+            options: SortOptions::default(),
+        };
+        let ordered_right: Arc<dyn ExecutionPlan> = Arc::new(
+            MemoryExec::try_new(
+                &[vec![right_batch.clone()]],
+                right_batch.schema(),
+                None,
+            )?
+            .with_sort_information(vec![vec![sort_expr]]),
+        );
+        assert!(join(
+            left(10)?,
+            ordered_right.clone(),
+            on(&left(10)?, &ordered_right)?,
+            &JoinType::Inner,
+            false
+        )?
+        .with_adaptive_partitioning(2)
+        .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_batch_size() -> Result<()> {
+        let (left_batch, right_batch) = build_sides_record_batches(200, (4, 5))?;
+        let left = Arc::new(MemoryExec::try_new(
+            &[vec![left_batch.clone()]],
+            left_batch.schema(),
+            None,
+        )?);
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![right_batch.clone()]],
+            right_batch.schema(),
+            None,
+        )?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("rc1", &right.schema())?) as _,
+        )];
+
+        let mut results = vec![];
+        // The override takes precedence over the session's batch size, whether
+        // it is smaller or larger
+        for (session_batch_size, batch_size) in [(8192, 100), (10, 1000)] {
+            let task_ctx = Arc::new(TaskContext::default().with_session_config(
+                SessionConfig::new().with_batch_size(session_batch_size),
+            ));
+            let join = join(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                &JoinType::Inner,
+                false,
+            )?
+            .with_batch_size(batch_size);
+            assert_eq!(join.batch_size(), Some(batch_size));
+            assert_contains!(
+                displayable(&join).one_line().to_string(),
+                format!("batch_size={batch_size}")
+            );
+            let join =
+                Arc::new(join).with_new_children(vec![left.clone(), right.clone()])?;
+            let batches = common::collect(join.execute(0, task_ctx)?).await?;
+            let rows = batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>();
+            // 160 probe rows match 50 build rows each
+            assert_eq!(rows.iter().sum::<usize>(), 8000);
+            assert!(rows.iter().all(|rows| *rows <= batch_size), "{rows:?}");
+            assert!(rows.contains(&batch_size), "{rows:?}");
+            results.push(batches);
+        }
+        assert_eq!(
+            concat_batches(&results[0][0].schema(), &results[0])?,
+            concat_batches(&results[1][0].schema(), &results[1])?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_output_order() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let left = build_table(
+            ("la1", &vec![1, 2, 3]),
+            ("lb1", &vec![4, 5, 6]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("ra1", &vec![10, 20, 30]),
+            ("rb1", &vec![4, 5, 7]),
+            ("c1", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lb1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("rb1", &right.schema())?) as _,
+        )];
+        let join = join(left, right, on, &JoinType::Inner, false)?;
+
+        let reordered = join.with_output_order(&["ra1", "la1", "rb1"])?;
+        let field_names = reordered
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(field_names, ["ra1", "la1", "rb1"]);
+        let batches = common::collect(reordered.execute(0, task_ctx)?).await?;
+        let expected = [
+            "+-----+-----+-----+",
+            "| ra1 | la1 | rb1 |",
+            "+-----+-----+-----+",
+            "| 10  | 1   | 4   |",
+            "| 20  | 2   | 5   |",
+            "+-----+-----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // The order is resolved against the current output schema:
+        let field_names = reordered
+            .with_output_order(&["la1", "ra1"])?
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(field_names, ["la1", "ra1"]);
+
+        let err = join.with_output_order(&["ra1", "x"]).unwrap_err();
+        assert_contains!(err.to_string(), "Output column x is not in the join schema");
+        let err = join.with_output_order(&["c1"]).unwrap_err();
+        assert_contains!(err.to_string(), "Output column c1 is ambiguous");
+        let err = join.with_output_order(&["la1", "la1"]).unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "Output column la1 is requested more than once"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_key_cast() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right_batch = RecordBatch::try_from_iter([
+            (
+                "a2",
+                Arc::new(Int32Array::from(vec![10, 20, 30])) as ArrayRef,
+            ),
+            ("b2", Arc::new(Int64Array::from(vec![4, 5, 6])) as ArrayRef),
+            (
+                "c2",
+                Arc::new(Float64Array::from(vec![4.0, 5.0, 6.0])) as ArrayRef,
+            ),
+        ])?;
+        let schema = right_batch.schema();
+        let right: Arc<dyn ExecutionPlan> =
+            Arc::new(MemoryExec::try_new(&[vec![right_batch]], schema, None)?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let cast_join = join(left.clone(), right.clone(), on, &JoinType::Inner, false)?
+            .with_key_cast(true)?;
+        let batches = common::collect(cast_join.execute(0, task_ctx)?).await?;
+        let expected = [
+            "+----+----+----+----+----+-----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2  |",
+            "+----+----+----+----+----+-----+",
+            "| 1  | 4  | 7  | 10 | 4  | 4.0 |",
+            "| 2  | 5  | 8  | 20 | 5  | 5.0 |",
+            "+----+----+----+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // Int32 values are exactly representable as Float64, Int64 values are not
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+            Arc::new(Column::new_with_schema("c2", &right.schema())?) as _,
+        )];
+        let err = join(right.clone(), right.clone(), on, &JoinType::Inner, false)?
+            .with_key_cast(true)
+            .unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "Join keys b2@1 of type Int64 and c2@2 of type Float64 can not be cast"
+        );
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+        let err = HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            None,
+            PartitionMode::Partitioned,
+            false,
+        )?
+        .with_key_cast(true)
+        .unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "Casting join keys is only supported in CollectLeft mode, got Partitioned"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_stall_timeout() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let blocking_left: Arc<dyn ExecutionPlan> =
+            Arc::new(BlockingExec::new(left.schema(), 1));
+        let blocking_right: Arc<dyn ExecutionPlan> =
+            Arc::new(BlockingExec::new(right.schema(), 1));
+
+        // Stalls waiting for the build side, and for the probe side
+        for (left, right) in [(blocking_left, right), (left, blocking_right)] {
+            let on = vec![(
+                Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+            )];
+            let join = HashJoinExec::try_new(
+                left,
+                right,
+                on,
+                None,
+                &JoinType::Inner,
+                None,
+                PartitionMode::Partitioned,
+                false,
+            )?
+            .with_stall_timeout(Some(Duration::from_millis(10)));
+            let mut stream = join.execute(0, task_ctx.clone())?;
+            let output = tokio::time::timeout(Duration::from_millis(200), async {
+                let mut batches = vec![];
+                while let Some(batch) = stream.next().await {
+                    batches.push(batch?);
+                }
+                Ok::<_, DataFusionError>(batches)
+            })
+            .await;
+            assert!(output.is_err());
+
+            let stall_warnings = join
+                .metrics()
+                .unwrap()
+                .sum_by_name("stall_warnings")
+                .unwrap()
+                .as_usize();
+            assert!(stall_warnings > 0);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_build_hash_column() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let left_batch = build_table_i32(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        // The hashes of the join key b1, and of the wrong column a1
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+        let mut key_hashes = vec![0; 3];
+        create_hashes(
+            &[left_batch.column(1).clone()],
+            &random_state,
+            &mut key_hashes,
+        )?;
+        let mut other_hashes = vec![0; 3];
+        create_hashes(
+            &[left_batch.column(0).clone()],
+            &random_state,
+            &mut other_hashes,
+        )?;
+        let mut columns = left_batch.columns().to_vec();
+        columns.push(Arc::new(UInt64Array::from(key_hashes)));
+        columns.push(Arc::new(UInt64Array::from(other_hashes)));
+        let mut fields = left_batch.schema().fields().to_vec();
+        fields.push(Arc::new(Field::new("h", DataType::UInt64, false)));
+        fields.push(Arc::new(Field::new("wrong_h", DataType::UInt64, false)));
+        let schema = Arc::new(Schema::new(fields));
+        let left_batch = RecordBatch::try_new(schema.clone(), columns)?;
+        let left: Arc<dyn ExecutionPlan> =
+            Arc::new(MemoryExec::try_new(&[vec![left_batch]], schema, None)?);
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+        // Without the hash columns in the output
+        let join_without_hashes = || {
+            join(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                &JoinType::Inner,
+                false,
+            )?
+            .with_projection(Some(vec![0, 1, 2, 5, 6, 7]))
+        };
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "+----+----+----+----+----+----+",
+        ];
+        for (build_hash_column, expected_hashed_batches) in
+            [(None, 1), (Some(Column::new("h", 3)), 0)]
+        {
+            let join =
+                join_without_hashes()?.with_build_hash_column(build_hash_column)?;
+            let batches = common::collect(join.execute(0, task_ctx.clone())?).await?;
+            assert_batches_sorted_eq!(expected, &batches);
+            let hashed_batches = join
+                .metrics()
+                .unwrap()
+                .sum_by_name("hash_table_hashed_batches")
+                .unwrap()
+                .as_usize();
+            assert_eq!(hashed_batches, expected_hashed_batches);
+        }
+
+        let join = join_without_hashes()?
+            .with_build_hash_column(Some(Column::new("wrong_h", 4)))?;
+        let err = common::collect(join.execute(0, task_ctx)?)
+            .await
+            .unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "Build hash column wrong_h@4 is inconsistent with the join keys"
+        );
+
+        let err = join_without_hashes()?
+            .with_build_hash_column(Some(Column::new("a1", 0)))
+            .unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "Build hash column a1@0 must be of type UInt64, got Int32"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_output_row_order_by() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let left = build_table(
+            ("la1", &(0..30).rev().collect()),
+            ("lb1", &(0..30).map(|i| i % 5).collect()),
+            ("lc1", &(0..30).collect()),
+        );
+        let right = build_table(
+            ("ra1", &(0..20).collect()),
+            ("rb1", &(0..20).map(|i| i % 5).collect()),
+            ("rc1", &(0..20).collect()),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lb1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("rb1", &right.schema())?) as _,
+        )];
+        let schema = join(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::Inner,
+            false,
+        )?
+        .schema();
+        // Every pair of la1 and ra1 occurs once in the output:
+        let ordering = vec![
+            PhysicalSortExpr {
+                expr: Arc::new(Column::new_with_schema("la1", &schema)?),
+                options: SortOptions::default(),
+            },
+            PhysicalSortExpr {
+                expr: Arc::new(Column::new_with_schema("ra1", &schema)?),
+                options: SortOptions {
+                    descending: true,
+                    nulls_first: false,
+                },
+            },
+        ];
+
+        for fetch in [Some(10), None] {
+            let sorted = join(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                &JoinType::Inner,
+                false,
+            )?
+            .with_output_row_order_by(ordering.clone(), fetch)?;
+            assert_eq!(
+                sorted.properties().output_ordering(),
+                Some(ordering.as_slice())
+            );
+            let batches = common::collect(sorted.execute(0, task_ctx.clone())?).await?;
+
+            let sort = SortExec::new(
+                ordering.clone(),
+                Arc::new(join(
+                    left.clone(),
+                    right.clone(),
+                    on.clone(),
+                    &JoinType::Inner,
+                    false,
+                )?),
+            )
+            .with_fetch(fetch);
+            let expected = common::collect(sort.execute(0, task_ctx.clone())?).await?;
+            assert_eq!(
+                arrow::util::pretty::pretty_format_batches(&batches)?.to_string(),
+                arrow::util::pretty::pretty_format_batches(&expected)?.to_string()
+            );
+            let num_rows = batches.iter().map(|batch| batch.num_rows()).sum::<usize>();
+            assert_eq!(num_rows, fetch.unwrap_or(120));
+        }
+
+        let err = join(left, right, on, &JoinType::Inner, false)?
+            .with_output_row_order_by(vec![], Some(10))
+            .unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "output row order of a HashJoinExec can not be empty"
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_with_group_by_probe_key(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::RightSemi,
+            JoinType::RightAnti
+        )]
+        join_type: JoinType,
+    ) -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        // Every probe batch of 5 rows holds each key of `rc1 = x % 5` once,
+        // which only occur in 4 keys on the build side:
+        let (left_batch, right_batch) = build_sides_record_batches(30, (4, 5))?;
+        let left = Arc::new(MemoryExec::try_new(
+            &[vec![left_batch.clone()]],
+            left_batch.schema(),
+            None,
+        )?);
+        let right = Arc::new(MemoryExec::try_new(
+            &[split_record_batches(&right_batch, 5)?],
+            right_batch.schema(),
+            None,
+        )?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", &left_batch.schema())?) as _,
+            Arc::new(Column::new_with_schema("rc1", &right_batch.schema())?) as _,
+        )];
+        let run = |group_by_probe_key: bool| {
+            let join = join(left.clone(), right.clone(), on.clone(), &join_type, false)
+                .and_then(|join| join.with_group_by_probe_key(group_by_probe_key));
+            let task_ctx = task_ctx.clone();
+            async move { common::collect(join?.execute(0, task_ctx)?).await }
+        };
+        // Whether the rows of each value of `rc1` are contiguous:
+        let contiguous = |batches: &[RecordBatch]| -> Result<bool> {
+            let mut seen = HashSet::new();
+            let mut previous = None;
+            for batch in batches {
+                let keys = as_int32_array(batch.column_by_name("rc1").unwrap())?;
+                for key in keys.iter() {
+                    if previous != Some(key) && !seen.insert(key) {
+                        return Ok(false);
+                    }
+                    previous = Some(key);
+                }
+            }
+            Ok(true)
+        };
+
+        let batches = run(false).await?;
+        let grouped_batches = run(true).await?;
+        if join_type == JoinType::Inner {
+            assert!(!contiguous(&batches)?);
+        }
+        assert!(contiguous(&grouped_batches)?);
+        let num_rows = |batches: &[RecordBatch]| -> usize {
+            batches.iter().map(RecordBatch::num_rows).sum()
+        };
+        assert_eq!(num_rows(&grouped_batches), num_rows(&batches));
+        compare_batches(&grouped_batches, &batches);
+
+        Ok(())
+    }
+
+    #[test]
+    fn group_by_probe_key_errors() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 6]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b2", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let err = join(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::LeftSemi,
+            false,
+        )?
+        .with_group_by_probe_key(true)
+        .unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "Grouping the output by probe key is not supported for LeftSemi joins"
+        );
+
+        let sort_exprs = vec![PhysicalSortExpr {
+            expr: Arc::new(Column::new_with_schema("a2", &right.schema())?),
+            options: SortOptions::default(),
+        }];
+        let sorted_right = Arc::new(SortExec::new(sort_exprs, right));
+        let err = join(left, sorted_right, on, &JoinType::Inner, false)?
+            .with_group_by_probe_key(true)
+            .unwrap_err();
+        assert_contains!(err.to_string(), "ordered or unbounded probe side");
+        Ok(())
+    }
+
+    #[test]
+    fn with_new_children_keeps_options() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 6]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b2", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+        let join = HashJoinExec::try_new(
+            left.clone(),
+            right.clone(),
+            on,
+            None,
+            &JoinType::Left,
+            None,
+            PartitionMode::CollectLeft,
+            false,
+        )?;
+        let ordering = vec![PhysicalSortExpr {
+            expr: Arc::new(Column::new_with_schema("c2", &join.schema())?),
+            options: SortOptions::default(),
+        }];
+        let join = Arc::new(
+            join.with_build_threads(4)
+                .with_build_prefetch(2)
+                .with_row_provenance(true)?
+                .with_unmatched_marker(true)?
+                .with_key_reordering(true)
+                .with_low_latency(true)
+                .with_null_buffer_reuse(false)
+                .with_max_output_batch_bytes(1024)
+                .with_max_serialized_batch_bytes(4096)
+                .with_batch_size(7)
+                .with_max_chain_length(16)
+                .with_adaptive_partitioning(2)?
+                .with_probe_filter_first(true)
+                .with_build_key_unique(true)
+                .with_max_output_rows(100)
+                .with_skip_empty_output_batches(true)
+                .with_collation(Collation::CaseInsensitive)?
+                .with_float_key_mode(FloatKeyMode::Ieee)?
+                .with_semijoin_reduction(true)
+                .with_stall_timeout(Some(Duration::from_secs(1)))
+                .with_runtime_filter_sink(Some(RuntimeFilterRegistry::new()))?
+                .with_trace_matched_keys(10)
+                .with_key_cast(true)?
+                .with_output_row_order_by(ordering, Some(5))?
+                .with_group_by_probe_key(true)?
+                .with_max_concurrency(2)?,
+        );
+
+        let rebuilt = join.clone().with_new_children(vec![left, right])?;
+        let rebuilt = rebuilt.as_any().downcast_ref::<HashJoinExec>().unwrap();
+        assert_eq!(rebuilt.batch_size(), Some(7));
+        assert!(rebuilt.low_latency());
+        assert_eq!(rebuilt.max_output_batch_bytes(), Some(1024));
+        assert_eq!(rebuilt.max_serialized_batch_bytes(), Some(4096));
+        assert!(rebuilt.skip_empty_output_batches());
+        assert!(rebuilt.group_by_probe_key());
+        // The rebuilt join differs from the original one in nothing but the
+        // identity of its (equal) state, so every option is kept:
+        assert_eq!(format!("{rebuilt:?}"), format!("{join:?}"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_semijoin_reduction() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        // Only 10 of the 100 keys of each side occur in the other side:
+        let left = build_table(
+            ("a1", &(0..100).collect()),
+            ("b1", &(0..100).collect()),
+            ("c1", &(0..100).map(|i| i * 10).collect()),
+        );
+        let right = build_table(
+            ("a2", &(0..100).map(|i| i * 10).collect()),
+            ("b1", &(90..190).collect()),
+            ("c2", &(0..100).collect()),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        for join_type in [JoinType::Inner, JoinType::Right] {
+            let mut results = vec![];
+            for semijoin_reduction in [false, true] {
+                let join = HashJoinExec::try_new(
+                    left.clone(),
+                    right.clone(),
+                    on.clone(),
+                    None,
+                    &join_type,
+                    None,
+                    PartitionMode::Partitioned,
+                    false,
+                )?
+                .with_semijoin_reduction(semijoin_reduction);
+                let batches = common::collect(join.execute(0, task_ctx.clone())?).await?;
+                let output = arrow::util::pretty::pretty_format_batches(&batches)?;
+                let mut lines = output
+                    .to_string()
+                    .lines()
+                    .map(String::from)
+                    .collect::<Vec<_>>();
+                lines.sort();
+                results.push(lines);
+
+                let metrics = join.metrics().unwrap();
+                let reduced_build_rows = metrics
+                    .sum_by_name("semijoin_reduced_build_rows")
+                    .unwrap()
+                    .as_usize();
+                let reduced_probe_rows = metrics
+                    .sum_by_name("semijoin_reduced_probe_rows")
+                    .unwrap()
+                    .as_usize();
+                // The Bloom filters may let a few rows without a match through:
+                if semijoin_reduction {
+                    assert!((80..=90).contains(&reduced_build_rows));
+                } else {
+                    assert_eq!(reduced_build_rows, 0);
+                }
+                if semijoin_reduction && join_type == JoinType::Inner {
+                    assert!((80..=90).contains(&reduced_probe_rows));
+                } else {
+                    // Unmatched probe rows are part of the output of right joins
+                    assert_eq!(reduced_probe_rows, 0);
+                }
+            }
+            assert_eq!(results[0], results[1], "{join_type}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn probe_threads_require_collect_left() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 5]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        let err = join(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::Left,
+            false,
+        )?
+        .with_probe_threads(2)
+        .unwrap_err();
+        assert_contains!(err.to_string(), "not supported for Left joins");
+
+        let err = HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            None,
+            PartitionMode::Partitioned,
+            false,
+        )?
+        .with_probe_threads(2)
+        .unwrap_err();
+        assert_contains!(err.to_string(), "only supported in CollectLeft mode");
+
+        Ok(())
+    }
+
+    /// Test where the left has 2 parts, the right with 1 part => 1 part
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn join_inner_one_two_parts_left(batch_size: usize) -> Result<()> {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let batch1 = build_table_i32(
+            ("a1", &vec![1, 2]),
+            ("b2", &vec![1, 2]),
+            ("c1", &vec![7, 8]),
+        );
+        let batch2 =
+            build_table_i32(("a1", &vec![2]), ("b2", &vec![2]), ("c1", &vec![9]));
+        let schema = batch1.schema();
+        let left = Arc::new(
+            MemoryExec::try_new(&[vec![batch1], vec![batch2]], schema, None).unwrap(),
+        );
+
+        let right = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b2", &vec![1, 2, 2]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![
+            (
+                Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("a1", &right.schema())?) as _,
+            ),
+            (
+                Arc::new(Column::new_with_schema("b2", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+            ),
+        ];
+
+        let (columns, batches) =
+            join_collect(left, right, on, &JoinType::Inner, false, task_ctx).await?;
+
+        assert_eq!(columns, vec!["a1", "b2", "c1", "a1", "b2", "c2"]);
+
+        // expected joined records = 3
+        // in case batch_size is 1 - additional empty batch for remaining 3-2 row
+        let mut expected_batch_count = div_ceil(3, batch_size);
+        if batch_size == 1 {
+            expected_batch_count += 1;
+        }
+        assert_eq!(batches.len(), expected_batch_count);
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b2 | c1 | a1 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 1  | 7  | 1  | 1  | 70 |",
+            "| 2  | 2  | 8  | 2  | 2  | 80 |",
+            "| 2  | 2  | 9  | 2  | 2  | 80 |",
+            "+----+----+----+----+----+----+",
+        ];
+
+        // Inner join output is expected to preserve both inputs order
+        assert_batches_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_inner_one_two_parts_left_randomly_ordered() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let batch1 = build_table_i32(
+            ("a1", &vec![0, 3]),
+            ("b1", &vec![4, 5]),
+            ("c1", &vec![6, 9]),
+        );
+        let batch2 = build_table_i32(
+            ("a1", &vec![2, 1]),
+            ("b1", &vec![5, 4]),
+            ("c1", &vec![8, 7]),
+        );
+        let schema = batch1.schema();
+
+        let left = Arc::new(
+            MemoryExec::try_new(&[vec![batch1], vec![batch2]], schema, None).unwrap(),
+        );
+        let right = build_table(
+            ("a2", &vec![20, 30, 10]),
+            ("b2", &vec![5, 6, 4]),
+            ("c2", &vec![80, 90, 70]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let (columns, batches) =
+            join_collect(left, right, on, &JoinType::Inner, false, task_ctx).await?;
+
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b2", "c2"]);
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 3  | 5  | 9  | 20 | 5  | 80 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 0  | 4  | 6  | 10 | 4  | 70 |",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "+----+----+----+----+----+----+",
+        ];
+
+        // Inner join output is expected to preserve both inputs order
+        assert_batches_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    /// Test where the left has 1 part, the right has 2 parts => 2 parts
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn join_inner_one_two_parts_right(batch_size: usize) -> Result<()> {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 5]), // this has a repetition
+            ("c1", &vec![7, 8, 9]),
+        );
+
+        let batch1 = build_table_i32(
+            ("a2", &vec![10, 20]),
+            ("b1", &vec![4, 6]),
+            ("c2", &vec![70, 80]),
+        );
+        let batch2 =
+            build_table_i32(("a2", &vec![30]), ("b1", &vec![5]), ("c2", &vec![90]));
+        let schema = batch1.schema();
+        let right = Arc::new(
+            MemoryExec::try_new(&[vec![batch1], vec![batch2]], schema, None).unwrap(),
+        );
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        let join = join(left, right, on, &JoinType::Inner, false)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
+
+        // first part
+        let stream = join.execute(0, task_ctx.clone())?;
+        let batches = common::collect(stream).await?;
+
+        // expected joined records = 1 (first right batch)
+        // and additional empty batch for non-joined 20-6-80
+        let mut expected_batch_count = div_ceil(1, batch_size);
+        if batch_size == 1 {
+            expected_batch_count += 1;
+        }
+        assert_eq!(batches.len(), expected_batch_count);
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "+----+----+----+----+----+----+",
+        ];
+
+        // Inner join output is expected to preserve both inputs order
+        assert_batches_eq!(expected, &batches);
+
+        // second part
+        let stream = join.execute(1, task_ctx.clone())?;
+        let batches = common::collect(stream).await?;
+
+        // expected joined records = 2 (second right batch)
+        let expected_batch_count = div_ceil(2, batch_size);
+        assert_eq!(batches.len(), expected_batch_count);
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 2  | 5  | 8  | 30 | 5  | 90 |",
+            "| 3  | 5  | 9  | 30 | 5  | 90 |",
+            "+----+----+----+----+----+----+",
+        ];
+
+        // Inner join output is expected to preserve both inputs order
+        assert_batches_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    fn build_table_two_batches(
+        a: (&str, &Vec<i32>),
+        b: (&str, &Vec<i32>),
+        c: (&str, &Vec<i32>),
+    ) -> Arc<dyn ExecutionPlan> {
+        let batch = build_table_i32(a, b, c);
+        let schema = batch.schema();
+        Arc::new(
+            MemoryExec::try_new(&[vec![batch.clone(), batch]], schema, None).unwrap(),
+        )
+    }
+
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn join_left_multi_batch(batch_size: usize) {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table_two_batches(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema()).unwrap()) as _,
+        )];
+
+        let join = join(left, right, on, &JoinType::Left, false).unwrap();
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
+
+        let stream = join.execute(0, task_ctx).unwrap();
+        let batches = common::collect(stream).await.unwrap();
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 3  | 7  | 9  |    |    |    |",
+            "+----+----+----+----+----+----+",
+        ];
+
+        assert_batches_sorted_eq!(expected, &batches);
+    }
+
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn join_full_multi_batch(batch_size: usize) {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
+            ("c1", &vec![7, 8, 9]),
+        );
+        // create two identical batches for the right side
+        let right = build_table_two_batches(
+            ("a2", &vec![10, 20, 30]),
+            ("b2", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema()).unwrap()) as _,
+        )];
+
+        let join = join(left, right, on, &JoinType::Full, false).unwrap();
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b2", "c2"]);
+
+        let stream = join.execute(0, task_ctx).unwrap();
+        let batches = common::collect(stream).await.unwrap();
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "|    |    |    | 30 | 6  | 90 |",
+            "|    |    |    | 30 | 6  | 90 |",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 3  | 7  | 9  |    |    |    |",
+            "+----+----+----+----+----+----+",
+        ];
+
+        assert_batches_sorted_eq!(expected, &batches);
+    }
+
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn join_left_empty_right(batch_size: usize) {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table_i32(("a2", &vec![]), ("b1", &vec![]), ("c2", &vec![]));
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema()).unwrap()) as _,
+        )];
+        let schema = right.schema();
+        let right = Arc::new(MemoryExec::try_new(&[vec![right]], schema, None).unwrap());
+        let join = join(left, right, on, &JoinType::Left, false).unwrap();
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
+
+        let stream = join.execute(0, task_ctx).unwrap();
+        let batches = common::collect(stream).await.unwrap();
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  |    |    |    |",
+            "| 2  | 5  | 8  |    |    |    |",
+            "| 3  | 7  | 9  |    |    |    |",
+            "+----+----+----+----+----+----+",
+        ];
+
+        assert_batches_sorted_eq!(expected, &batches);
+    }
+
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn join_full_empty_right(batch_size: usize) {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table_i32(("a2", &vec![]), ("b2", &vec![]), ("c2", &vec![]));
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema()).unwrap()) as _,
+        )];
+        let schema = right.schema();
+        let right = Arc::new(MemoryExec::try_new(&[vec![right]], schema, None).unwrap());
+        let join = join(left, right, on, &JoinType::Full, false).unwrap();
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b2", "c2"]);
+
+        let stream = join.execute(0, task_ctx).unwrap();
+        let batches = common::collect(stream).await.unwrap();
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  |    |    |    |",
+            "| 2  | 5  | 8  |    |    |    |",
+            "| 3  | 7  | 9  |    |    |    |",
+            "+----+----+----+----+----+----+",
+        ];
+
+        assert_batches_sorted_eq!(expected, &batches);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_with_row_provenance(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightSemi,
+            JoinType::RightAnti
+        )]
+        join_type: JoinType,
+    ) -> Result<()> {
+        let left_batch = build_table_i32(
+            ("a1", &vec![1, 2, 3, 4]),
+            ("b1", &vec![4, 5, 5, 8]),
+            ("c1", &vec![7, 8, 9, 10]),
+        );
+        let right_batch = build_table_i32(
+            ("a2", &vec![10, 20, 30, 40]),
+            ("b2", &vec![5, 4, 6, 5]),
+            ("c2", &vec![70, 80, 90, 100]),
+        );
+        let left = Arc::new(MemoryExec::try_new(
+            &[vec![left_batch.clone()]],
+            left_batch.schema(),
+            None,
+        )?);
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![right_batch.clone()]],
+            right_batch.schema(),
+            None,
+        )?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let join = join(left, right, on, &join_type, false)?.with_row_provenance(true)?;
+        let schema = join.schema();
+        let num_columns = schema.fields().len();
+        assert_eq!(schema.field(num_columns - 2).name(), BUILD_ROW_COLUMN);
+        assert_eq!(schema.field(num_columns - 1).name(), PROBE_ROW_COLUMN);
+
+        let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+        let batches = common::collect(stream).await?;
+        let output = concat_batches(&schema, &batches)?;
+        assert!(output.num_rows() > 0);
+        let build_rows = output.column(num_columns - 2);
+        let probe_rows = output.column(num_columns - 1);
+
+        // Taking the source columns at the provenance indices must reproduce
+        // the output, including the null padding of outer joins:
+        let mut sources = vec![];
+        if !matches!(join_type, JoinType::RightSemi | JoinType::RightAnti) {
+            sources.extend(left_batch.columns().iter().map(|c| (c, build_rows)));
+        } else {
+            assert_eq!(build_rows.null_count(), output.num_rows());
+        }
+        if !matches!(join_type, JoinType::LeftSemi | JoinType::LeftAnti) {
+            sources.extend(right_batch.columns().iter().map(|c| (c, probe_rows)));
+        } else {
+            assert_eq!(probe_rows.null_count(), output.num_rows());
+        }
+        assert_eq!(sources.len(), num_columns - 2);
+        for (i, (source, indices)) in sources.into_iter().enumerate() {
+            let expected = take(source.as_ref(), indices.as_ref(), None)?;
+            assert_eq!(&expected, output.column(i));
+        }
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_with_unmatched_marker(
+        #[values(JoinType::Left, JoinType::Right, JoinType::Full)] join_type: JoinType,
+    ) -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4]),
+            ("b1", &vec![4, 5, 5, 8]),
+            ("c1", &vec![7, 8, 9, 10]),
+        );
+        // The probe side is split across two batches, while the unmatched
+        // build side rows are only known after the last one:
+        let right_batch = build_table_i32(
+            ("a2", &vec![10, 20, 30, 40]),
+            ("b2", &vec![5, 4, 6, 5]),
+            ("c2", &vec![70, 80, 90, 100]),
+        );
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![right_batch.slice(0, 2), right_batch.slice(2, 2)]],
+            right_batch.schema(),
+            None,
+        )?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let marked_join =
+            join(left, right, on, &join_type, false)?.with_unmatched_marker(true)?;
+        assert!(marked_join.unmatched_marker());
+        let schema = marked_join.schema();
+        assert_eq!(
+            schema.field(schema.fields().len() - 1).name(),
+            UNMATCHED_ROW_COLUMN
+        );
+        let task_ctx = Arc::new(TaskContext::default());
+        let batches = common::collect(marked_join.execute(0, task_ctx.clone())?).await?;
+
+        let mut expected = vec![
+            "+----+----+----+----+----+-----+-------------+",
+            "| a1 | b1 | c1 | a2 | b2 | c2  | __unmatched |",
+            "+----+----+----+----+----+-----+-------------+",
+            "| 1  | 4  | 7  | 20 | 4  | 80  | false       |",
+            "| 2  | 5  | 8  | 10 | 5  | 70  | false       |",
+            "| 2  | 5  | 8  | 40 | 5  | 100 | false       |",
+            "| 3  | 5  | 9  | 10 | 5  | 70  | false       |",
+            "| 3  | 5  | 9  | 40 | 5  | 100 | false       |",
+        ];
+        if matches!(join_type, JoinType::Left | JoinType::Full) {
+            expected.push("| 4  | 8  | 10 |    |    |     | true        |");
+        }
+        if matches!(join_type, JoinType::Right | JoinType::Full) {
+            expected.push("|    |    |    | 30 | 6  | 90  | true        |");
+        }
+        expected.push("+----+----+----+----+----+-----+-------------+");
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // Only outer joins emit unmatched rows:
+        let left = build_table(("a1", &vec![1]), ("b1", &vec![4]), ("c1", &vec![7]));
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+        )];
+        assert!(join(left.clone(), left, on, &JoinType::Inner, false)?
+            .with_unmatched_marker(true)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_with_match_flag(
+        #[values(JoinType::RightSemi, JoinType::RightAnti)] join_type: JoinType,
+        #[values(1, 2, 8192)] batch_size: usize,
+    ) -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4]),
+            ("b1", &vec![4, 5, 5, 8]),
+            ("c1", &vec![7, 8, 9, 10]),
+        );
+        let right_batch = RecordBatch::try_from_iter(vec![
+            (
+                "a2",
+                Arc::new(Int32Array::from(vec![10, 20, 30, 40, 50])) as ArrayRef,
+            ),
+            (
+                "b2",
+                Arc::new(Int32Array::from(vec![
+                    Some(5),
+                    None,
+                    Some(6),
+                    Some(4),
+                    Some(5),
+                ])) as ArrayRef,
+            ),
+        ])?;
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![right_batch.clone()]],
+            right_batch.schema(),
+            None,
+        )?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+        let task_ctx = prepare_task_ctx(batch_size);
+
+        let flagged = join(left.clone(), right.clone(), on.clone(), &join_type, false)?
+            .with_match_flag(true)?;
+        assert!(flagged.match_flag());
+        let schema = flagged.schema();
+        assert_eq!(
+            schema.field(schema.fields().len() - 1).name(),
+            MATCHED_ROW_COLUMN
+        );
+        let batches = common::collect(flagged.execute(0, task_ctx.clone())?).await?;
+
+        // Every probe row is emitted once, in probe order, the same for both
+        // join types:
+        let expected = [
+            "+----+----+-----------+",
+            "| a2 | b2 | __matched |",
+            "+----+----+-----------+",
+            "| 10 | 5  | true      |",
+            "| 20 |    | false     |",
+            "| 30 | 6  | false     |",
+            "| 40 | 4  | true      |",
+            "| 50 | 5  | true      |",
+            "+----+----+-----------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        // Filtering on the flag yields the semi and on its negation the anti
+        // join:
+        let flagged = concat_batches(&schema, &batches)?;
+        let flags = flagged.column(2).as_boolean().clone();
+        let unflagged = flagged.project(&[0, 1])?;
+        for (filter_type, mask) in [
+            (JoinType::RightSemi, flags.clone()),
+            (JoinType::RightAnti, arrow::compute::not(&flags)?),
+        ] {
+            let join =
+                join(left.clone(), right.clone(), on.clone(), &filter_type, false)?;
+            let expected = common::collect(join.execute(0, task_ctx.clone())?).await?;
+            assert_eq!(
+                arrow::util::pretty::pretty_format_batches(&[filter_record_batch(
+                    &unflagged, &mask
+                )?])?
+                .to_string(),
+                arrow::util::pretty::pretty_format_batches(&expected)?.to_string(),
+            );
+        }
+
+        // Only the probe rows of right semi and anti joins can be flagged:
+        assert!(join(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::LeftSemi,
+            false
+        )?
+        .with_match_flag(true)
+        .is_err());
+        assert!(join(left, right, on, &join_type, false)?
+            .with_match_flag(true)?
+            .with_projection(Some(vec![0]))
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_trace_matched_keys() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let (left_batch, right_batch) = build_sides_record_batches(100, (10, 11))?;
+        let memory = |batch: &RecordBatch| -> Result<Arc<dyn ExecutionPlan>> {
+            Ok(Arc::new(MemoryExec::try_new(
+                &[split_record_batches(batch, 10)?],
+                batch.schema(),
+                None,
+            )?))
+        };
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", &left_batch.schema())?) as _,
+            Arc::new(Column::new_with_schema("rc1", &right_batch.schema())?) as _,
+        )];
+
+        // Every key matches the product of its occurrences on both sides:
+        let occurrences = |batch: &RecordBatch, name: &str| {
+            let mut counts = std::collections::HashMap::new();
+            let values = as_int32_array(batch.column_by_name(name).unwrap()).unwrap();
+            for value in values.values() {
+                *counts.entry(*value).or_insert(0usize) += 1;
+            }
+            counts
+        };
+        let left_counts = occurrences(&left_batch, "lc1");
+        let right_counts = occurrences(&right_batch, "rc1");
+        let expected = left_counts
+            .iter()
+            .filter_map(|(key, left)| {
+                right_counts
+                    .get(key)
+                    .map(|right| (vec![ScalarValue::Int32(Some(*key))], left * right))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(expected.len(), 10);
+
+        let untraced = join(
+            memory(&left_batch)?,
+            memory(&right_batch)?,
+            on.clone(),
+            &JoinType::Inner,
+            false,
+        )?;
+        assert!(untraced.matched_keys_trace().is_none());
+
+        for max_keys in [100, 3] {
+            let traced = join(
+                memory(&left_batch)?,
+                memory(&right_batch)?,
+                on.clone(),
+                &JoinType::Inner,
+                false,
+            )?
+            .with_probe_threads(2)?
+            .with_trace_matched_keys(max_keys);
+            assert_contains!(
+                displayable(&traced).one_line().to_string(),
+                format!("trace_matched_keys={max_keys}")
+            );
+            // All output partitions trace into the same sample:
+            for partition in 0..2 {
+                common::collect(traced.execute(partition, task_ctx.clone())?).await?;
+            }
+            let sample = traced.matched_keys_trace().unwrap().sample();
+            assert_eq!(sample.len(), max_keys.min(expected.len()));
+            for entry in &sample {
+                assert!(expected.contains(entry), "unexpected {entry:?}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_runtime_filter_sink() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4]),
+            ("b1", &vec![4, 5, 5, 8]),
+            ("c1", &vec![7, 8, 9, 10]),
+        );
+        let values = (0..100).collect::<Vec<_>>();
+        let right = build_table(("a2", &values), ("b2", &values), ("c2", &values));
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let sink = RuntimeFilterRegistry::new();
+        let filtering_join = join(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::Inner,
+            false,
+        )?
+        .with_runtime_filter_sink(Some(sink.clone()))?;
+        assert_contains!(
+            displayable(&filtering_join).one_line().to_string(),
+            "runtime_filter_sink=[b2]"
+        );
+        let [key] = filtering_join.runtime_filter_keys().try_into().unwrap();
+        assert_eq!(key.column(), "b2");
+        // The filter is only declared once the join is executed:
+        assert!(!sink.is_pending(&key));
+
+        // The filter is declared, but not published before the build side is
+        // complete:
+        let task_ctx = Arc::new(TaskContext::default());
+        let join_stream = filtering_join.execute(0, task_ctx.clone())?;
+        assert!(sink.is_pending(&key));
+        assert!(sink.filter(&key).is_none());
+        assert!(!sink.is_pending(&RuntimeFilterKey::new(key.join_id(), "a2")));
+
+        // A scan started before the build side is complete waits for the filter:
+        let waiting_scan = SpawnedTask::spawn(common::collect(sink.filter_stream(
+            right.execute(0, task_ctx.clone())?,
+            &key,
+            true,
+        )?));
+
+        let batches = common::collect(join_stream).await?;
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 4  | 4  | 4  |",
+            "| 2  | 5  | 8  | 5  | 5  | 5  |",
+            "| 3  | 5  | 9  | 5  | 5  | 5  |",
+            "| 4  | 8  | 10 | 8  | 8  | 8  |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let filter = sink.filter(&key).unwrap();
+        assert_eq!(filter.min(), &ScalarValue::Int32(Some(4)));
+        assert_eq!(filter.max(), &ScalarValue::Int32(Some(8)));
+        assert!(!filter.may_contain_range(
+            &ScalarValue::Int32(Some(10)),
+            &ScalarValue::Int32(Some(20))
+        ));
+        assert!(filter.may_contain_range(
+            &ScalarValue::Int32(Some(0)),
+            &ScalarValue::Int32(Some(4))
+        ));
+
+        // Both the waiting scan and a scan started afterwards only read the
+        // rows within the range of the build side keys, which include all
+        // rows that have a match:
+        let skipping_scan = common::collect(sink.filter_stream(
+            right.execute(0, task_ctx.clone())?,
+            &key,
+            false,
+        )?)
+        .await?;
+        for scanned in [waiting_scan.join_unwind().await?, skipping_scan] {
+            let keys = scanned
+                .iter()
+                .flat_map(|batch| {
+                    as_int32_array(batch.column(1)).unwrap().values().to_vec()
+                })
+                .collect::<Vec<_>>();
+            assert!(keys.len() <= 5, "{keys:?}");
+            for key in [4, 5, 8] {
+                assert!(keys.contains(&key), "{keys:?}");
+            }
+        }
+
+        // Another join on a probe side column of the same name gets its own
+        // filter in the shared registry:
+        let other_left = build_table(
+            ("a1", &vec![1, 2]),
+            ("b1", &vec![50, 60]),
+            ("c1", &vec![7, 8]),
+        );
+        let other_join = join(
+            other_left,
+            right.clone(),
+            on.clone(),
+            &JoinType::Inner,
+            false,
+        )?
+        .with_runtime_filter_sink(Some(sink.clone()))?;
+        let [other_key] = other_join.runtime_filter_keys().try_into().unwrap();
+        assert_eq!(other_key.column(), "b2");
+        assert_ne!(other_key, key);
+        common::collect(other_join.execute(0, task_ctx.clone())?).await?;
+        let other_filter = sink.filter(&other_key).unwrap();
+        assert_eq!(other_filter.min(), &ScalarValue::Int32(Some(50)));
+        assert_eq!(
+            sink.filter(&key).unwrap().min(),
+            &ScalarValue::Int32(Some(4))
+        );
+
+        // A new instance of the join, as planned for another execution, keeps
+        // its key but resets the filter published by the previous execution:
+        let rerun_join = Arc::new(filtering_join)
+            .with_new_children(vec![left.clone(), right.clone()])?;
+        let rerun_stream = rerun_join.execute(0, task_ctx.clone())?;
+        assert!(sink.is_pending(&key));
+        common::collect(rerun_stream).await?;
+        assert!(sink.filter(&key).is_some());
+
+        // A failing build side withdraws the filter, releasing the waiting scans:
+        let sink = RuntimeFilterRegistry::new();
+        let failing_left =
+            Arc::new(MockExec::new(vec![exec_err!("bad data")], left.schema()));
+        let failing_join = join(
+            failing_left,
+            right.clone(),
+            on.clone(),
+            &JoinType::Inner,
+            false,
+        )?
+        .with_runtime_filter_sink(Some(sink.clone()))?;
+        assert!(common::collect(failing_join.execute(0, task_ctx.clone())?)
+            .await
+            .is_err());
+        let [key] = failing_join.runtime_filter_keys().try_into().unwrap();
+        assert!(!sink.is_pending(&key));
+        let scanned = common::collect(sink.filter_stream(
+            right.execute(0, task_ctx)?,
+            &key,
+            true,
+        )?)
+        .await?;
+        assert_eq!(scanned.iter().map(|b| b.num_rows()).sum::<usize>(), 100);
+
+        // Only joins in CollectLeft mode which do not emit unmatched probe side
+        // rows can publish runtime filters:
+        assert!(join(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::Right,
+            false
+        )?
+        .with_runtime_filter_sink(Some(RuntimeFilterRegistry::new()))
+        .is_err());
+        assert!(HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            None,
+            PartitionMode::Partitioned,
+            false,
+        )?
+        .with_runtime_filter_sink(Some(RuntimeFilterRegistry::new()))
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_without_on_constraints() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 6]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b2", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+
+        let err = join(left.clone(), right.clone(), vec![], &JoinType::Inner, false)
+            .unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "On constraints in HashJoinExec should be non-empty, \
+             use CrossJoinExec for joins without keys and filter"
+        );
+
+        let column_indices = vec![ColumnIndex {
+            index: 0,
+            side: JoinSide::Left,
+        }];
+        let intermediate_schema =
+            Schema::new(vec![Field::new("a1", DataType::Int32, true)]);
+        let filter = JoinFilter::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("a1", 0)),
+                Operator::Gt,
+                Arc::new(Literal::new(ScalarValue::Int32(Some(1)))),
+            )),
+            column_indices,
+            intermediate_schema,
+        );
+        let err = join_with_filter(left, right, vec![], filter, &JoinType::Inner, false)
+            .unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "On constraints in HashJoinExec should be non-empty, \
+             use NestedLoopJoinExec for joins with only a filter"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_statistics_override() -> Result<()> {
+        let left_batch = build_table_i32(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 6]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right_batch = build_table_i32(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let stats = |num_rows: usize, b_distinct_count: usize| Statistics {
+            num_rows: Precision::Exact(num_rows),
+            total_byte_size: Precision::Absent,
+            column_statistics: (0..3)
+                .map(|i| ColumnStatistics {
+                    min_value: Precision::Exact(ScalarValue::Int32(Some(0))),
+                    max_value: Precision::Exact(ScalarValue::Int32(Some(i32::MAX))),
+                    distinct_count: if i == 1 {
+                        Precision::Exact(b_distinct_count)
+                    } else {
+                        Precision::Absent
+                    },
+                    ..ColumnStatistics::new_unknown()
+                })
+                .collect(),
+        };
+        let left =
+            with_statistics_override(vec![left_batch], vec![], stats(1_000_000, 1000))?;
+        let right =
+            with_statistics_override(vec![right_batch], vec![], stats(2000, 100))?;
+
+        // The overrides are reported unchanged
+        assert_eq!(left.statistics()?, stats(1_000_000, 1000));
+        assert_eq!(right.statistics()?, stats(2000, 100));
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+        let join = join(left, right, on, &JoinType::Inner, false)?;
+
+        // The join estimates its cardinality from the overrides ...
+        assert_eq!(
+            join.statistics()?.num_rows,
+            Precision::Inexact(1_000_000 * 2000 / 1000)
+        );
+
+        // ... while producing the output of the actual data
+        let task_ctx = Arc::new(TaskContext::default());
+        let batches = common::collect(join.execute(0, task_ctx)?).await?;
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 3  | 6  | 9  | 30 | 6  | 90 |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_key_order_by_distinct_count() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+            Field::new("c", DataType::Int32, false),
+        ]);
+        let stats = |distinct_counts: [Precision<usize>; 3]| Statistics {
+            num_rows: Precision::Inexact(1000),
+            total_byte_size: Precision::Absent,
+            column_statistics: distinct_counts
+                .into_iter()
+                .map(|distinct_count| ColumnStatistics {
+                    distinct_count,
+                    ..ColumnStatistics::new_unknown()
+                })
+                .collect(),
+        };
+        let left = Arc::new(StatisticsExec::new(
+            stats([
+                Precision::Exact(2),
+                Precision::Exact(500),
+                Precision::Absent,
+            ]),
+            schema.clone(),
+        ));
+        let right = Arc::new(StatisticsExec::new(
+            stats([
+                Precision::Inexact(3),
+                Precision::Exact(100),
+                Precision::Absent,
+            ]),
+            schema.clone(),
+        ));
+        let on = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| {
+                    Ok((
+                        Arc::new(Column::new_with_schema(name, &schema)?)
+                            as PhysicalExprRef,
+                        Arc::new(Column::new_with_schema(name, &schema)?)
+                            as PhysicalExprRef,
+                    ))
+                })
+                .collect::<Result<JoinOn>>()
+        };
+
+        let two_keys = join(
+            left.clone(),
+            right.clone(),
+            on(&["a", "b"])?,
+            &JoinType::Inner,
+            false,
+        )?;
+        assert_eq!(two_keys.join_key_order(), vec![0, 1]);
+        let two_keys = two_keys.with_key_reordering(true);
+        assert_eq!(two_keys.join_key_order(), vec![1, 0]);
+
+        // Without distinct counts for all the keys, the given order is kept:
+        let three_keys =
+            join(left, right, on(&["a", "b", "c"])?, &JoinType::Inner, false)?
+                .with_key_reordering(true);
+        assert_eq!(three_keys.join_key_order(), vec![0, 1, 2]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn equal_rows_arr_key_comparisons() -> Result<()> {
+        // Candidate pairs as produced by colliding hashes: all of them match on
+        // the first key, but only one matches on the second key.
+        let left: Vec<ArrayRef> = vec![
+            Arc::new(Int32Array::from(vec![1, 1, 1, 1])),
+            Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+        ];
+        let right: Vec<ArrayRef> = vec![
+            Arc::new(Int32Array::from(vec![1])),
+            Arc::new(Int32Array::from(vec![3])),
+        ];
+        let indices_left = UInt64Array::from(vec![0, 1, 2, 3]);
+        let indices_right = UInt32Array::from(vec![0, 0, 0, 0]);
+
+        let comparisons = metrics::Count::new();
+        let (l, r) = equal_rows_arr(
+            &indices_left,
+            &indices_right,
+            &left,
+            &right,
+            false,
+            Some(&comparisons),
+        )?;
+        assert_eq!(l, UInt64Array::from(vec![2]));
+        assert_eq!(r, UInt32Array::from(vec![0]));
+        assert_eq!(comparisons.value(), 8);
+
+        // Comparing the more selective key first gives the same result with
+        // fewer comparisons:
+        let reversed =
+            |arrays: &[ArrayRef]| arrays.iter().rev().cloned().collect::<Vec<_>>();
+        let comparisons = metrics::Count::new();
+        let (reversed_l, reversed_r) = equal_rows_arr(
+            &indices_left,
+            &indices_right,
+            &reversed(&left),
+            &reversed(&right),
+            false,
+            Some(&comparisons),
+        )?;
+        assert_eq!(reversed_l, l);
+        assert_eq!(reversed_r, r);
+        assert_eq!(comparisons.value(), 5);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_duplicate_key_pairs() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 5]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let key_pair = || -> Result<(PhysicalExprRef, PhysicalExprRef)> {
+            Ok((
+                Arc::new(Column::new_with_schema("b1", &left.schema())?),
+                Arc::new(Column::new_with_schema("b1", &right.schema())?),
+            ))
+        };
+
+        let run = |on: JoinOn| {
+            let join = join(left.clone(), right.clone(), on, &JoinType::Inner, false);
+            async move {
+                let join = join?;
+                let stream = join.execute(0, prepare_task_ctx(8))?;
+                let batches = common::collect(stream).await?;
+                let comparisons = join
+                    .metrics()
+                    .unwrap()
+                    .sum_by_name("key_comparisons")
+                    .unwrap()
+                    .as_usize();
+                Result::<_>::Ok((batches, comparisons))
+            }
+        };
+        let (batches, comparisons) = run(vec![key_pair()?]).await?;
+        // The duplicate pairs are removed before building, so they are neither
+        // hashed nor compared:
+        let (duplicate_batches, duplicate_comparisons) =
+            run(vec![key_pair()?, key_pair()?, key_pair()?]).await?;
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 3  | 5  | 9  | 20 | 5  | 80 |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        assert_batches_eq!(expected, &duplicate_batches);
+        assert_eq!(comparisons, 3);
+        assert_eq!(duplicate_comparisons, comparisons);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_output_exprs() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 5]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+        let join = join(left, right, on, &JoinType::Inner, false)?;
+        let join_schema = join.join_schema().clone();
+        let column = |name: &str| -> Result<PhysicalExprRef> {
+            Ok(Arc::new(Column::new_with_schema(name, &join_schema)?))
+        };
+
+        // An expression referencing a column outside of the join schema is
+        // rejected at construction:
+        let invalid = Arc::new(Column::new("d1", 6)) as PhysicalExprRef;
+        let err = join
+            .with_projection(None)?
+            .with_output_exprs(vec![(invalid, "d1".to_string())])
+            .unwrap_err();
+        assert_contains!(err.to_string(), "not in the join schema");
+
+        // la1 + ra1
+        let sum = Arc::new(BinaryExpr::new(
+            column("a1")?,
+            Operator::Plus,
+            column("a2")?,
+        ));
+        let join = join.with_output_exprs(vec![
+            (sum, "sum".to_string()),
+            (column("c2")?, "c2".to_string()),
+        ])?;
+        assert_eq!(columns(&join.schema()), vec!["sum", "c2"]);
+
+        let batches = common::collect(join.execute(0, prepare_task_ctx(8))?).await?;
+        let expected = [
+            "+-----+----+",
+            "| sum | c2 |",
+            "+-----+----+",
+            "| 11  | 70 |",
+            "| 22  | 80 |",
+            "| 23  | 80 |",
+            "+-----+----+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_output_recorded_to_ipc() -> Result<()> {
+        let task_ctx = prepare_task_ctx(2);
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 5]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+        let join = Arc::new(join(left, right, on, &JoinType::Full, false)?);
+
+        let file = task_ctx
+            .runtime_env()
+            .disk_manager
+            .create_tmp_file("HashJoinRecording")?;
+        let batches =
+            collect_and_record(join.clone(), 0, task_ctx.clone(), file.path()).await?;
+        let recorded = read_recorded_batches(file.path())?;
+
+        assert_eq!(batches.len(), recorded.len());
+        assert_eq!(batches, recorded);
+        // Recorded output must match a plain execution of the same plan
+        let expected = common::collect(join.execute(0, task_ctx)?).await?;
+        compare_batches(&expected, &recorded);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ipc_recording_stream_ends_after_error() -> Result<()> {
+        let batch =
+            build_table_i32(("a", &vec![1, 2]), ("b", &vec![3, 4]), ("c", &vec![5, 6]));
+        let schema = batch.schema();
+        // The batch after the error must not be passed through:
+        let input = futures::stream::iter(vec![
+            Ok(batch.clone()),
+            exec_err!("input failed"),
+            Ok(batch.clone()),
+        ]);
+        let input = Box::pin(RecordBatchStreamAdapter::new(schema, input));
+
+        let task_ctx = Arc::new(TaskContext::default());
+        let file = task_ctx
+            .runtime_env()
+            .disk_manager
+            .create_tmp_file("IpcRecordingStream")?;
+        let mut stream = IpcRecordingStream::try_new(input, file.path())?;
+        assert_eq!(stream.next().await.transpose()?, Some(batch.clone()));
+        assert!(stream.next().await.unwrap().is_err());
+        assert!(stream.next().await.is_none());
+        assert!(stream.next().await.is_none());
+
+        assert_eq!(read_recorded_batches(file.path())?, vec![batch]);
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_on_view_keys(
+        #[values(JoinType::Inner, JoinType::Left, JoinType::Full, JoinType::RightAnti)]
+        join_type: JoinType,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(3);
+        // Inlined keys as well as keys longer than 12 bytes, which share their
+        // first four bytes and length
+        let left_keys = [
+            Some("apple"),
+            Some("a key longer than 12 bytes: 1"),
+            None,
+            Some("banana"),
+            Some("a key longer than 12 bytes: 2"),
+            Some("apple"),
+        ];
+        let right_keys = [
+            Some("apple"),
+            Some("a key longer than 12 bytes: 2"),
+            Some("cherry"),
+            None,
+            Some("a key longer than 12 bytes: 3"),
+        ];
+        let table = |name: &str, keys: &[Option<&str>], view: bool| {
+            let keys: ArrayRef = if view {
+                Arc::new(keys.iter().cloned().collect::<StringViewArray>())
+            } else {
+                Arc::new(keys.iter().cloned().collect::<StringArray>())
+            };
+            let values = Arc::new(Int32Array::from_iter_values(0..keys.len() as i32));
+            let batch = RecordBatch::try_from_iter([
+                (format!("{name}_key"), keys),
+                (format!("{name}_value"), values as ArrayRef),
+            ])?;
+            let schema = batch.schema();
+            Ok::<_, DataFusionError>(Arc::new(MemoryExec::try_new(
+                &[split_record_batches(&batch, 2)?],
+                schema,
+                None,
+            )?) as Arc<dyn ExecutionPlan>)
+        };
+
+        let mut outputs = vec![];
+        for view in [false, true] {
+            let left = table("l", &left_keys, view)?;
+            let right = table("r", &right_keys, view)?;
+            let on = vec![(
+                Arc::new(Column::new_with_schema("l_key", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("r_key", &right.schema())?) as _,
+            )];
+            let join = join(left, right, on, &join_type, false)?;
+            let batches = common::collect(join.execute(0, task_ctx.clone())?).await?;
+            outputs.push(batches);
+        }
+
+        // The view encoding is preserved in the output ...
+        let key_types = |batches: &[RecordBatch]| {
+            batches[0]
+                .schema()
+                .fields()
+                .iter()
+                .filter(|field| field.name().ends_with("_key"))
+                .map(|field| field.data_type().clone())
+                .collect::<Vec<_>>()
+        };
+        assert!(key_types(&outputs[0])
+            .iter()
+            .all(|data_type| data_type == &DataType::Utf8));
+        assert!(key_types(&outputs[1])
+            .iter()
+            .all(|data_type| data_type == &DataType::Utf8View));
+
+        // ... while the output rows are the same as for materialized keys
+        let materialized = outputs[1]
+            .iter()
+            .map(|batch| {
+                let columns = batch
+                    .schema()
+                    .fields()
+                    .iter()
+                    .zip(batch.columns())
+                    .map(|(field, column)| {
+                        let column =
+                            match column.as_any().downcast_ref::<StringViewArray>() {
+                                Some(view) => {
+                                    Arc::new(view.iter().collect::<StringArray>())
+                                        as ArrayRef
+                                }
+                                None => column.clone(),
+                            };
+                        (field.name().clone(), column)
+                    })
+                    .collect::<Vec<_>>();
+                RecordBatch::try_from_iter(columns)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let num_rows = |batches: &[RecordBatch]| {
+            batches.iter().map(|batch| batch.num_rows()).sum::<usize>()
+        };
+        assert_eq!(num_rows(&outputs[0]), num_rows(&materialized));
+        compare_batches(&outputs[0], &materialized);
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_with_probe_filter_first(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightSemi,
+            JoinType::RightAnti
+        )]
+        join_type: JoinType,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(2);
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4, 5, 6]),
+            ("b1", &vec![1, 1, 2, 2, 3, 3]),
+            ("c1", &vec![7, 8, 9, 10, 11, 12]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30, 40, 50, 60, 70]),
+            ("b1", &vec![1, 2, 3, 1, 2, 4, 3]),
+            ("c2", &vec![5, 5, 5, 5, 5, 5, 5]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+        // a2 > 25 AND a1 < 5, of which the first conjunct only references the
+        // probe side
+        let intermediate_schema = Schema::new(vec![
+            Field::new("a1", DataType::Int32, true),
+            Field::new("a2", DataType::Int32, true),
+        ]);
+        let filter_expr = Arc::new(BinaryExpr::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("a2", 1)),
+                Operator::Gt,
+                Arc::new(Literal::new(ScalarValue::Int32(Some(25)))),
+            )),
+            Operator::And,
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("a1", 0)),
+                Operator::Lt,
+                Arc::new(Literal::new(ScalarValue::Int32(Some(5)))),
+            )),
+        )) as Arc<dyn PhysicalExpr>;
+        let filter = JoinFilter::new(
+            filter_expr,
+            JoinFilter::build_column_indices(vec![0], vec![0]),
+            intermediate_schema,
+        );
+
+        let mut outputs = vec![];
+        for probe_filter_first in [None, Some(true), Some(false)] {
+            let mut join = join_with_filter(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                filter.clone(),
+                &join_type,
+                false,
+            )?;
+            if let Some(probe_filter_first) = probe_filter_first {
+                join = join.with_probe_filter_first(probe_filter_first);
+            }
+            assert_eq!(join.probe_filter_first(), probe_filter_first);
+            outputs.push(common::collect(join.execute(0, task_ctx.clone())?).await?);
+        }
+
+        // The output doesn't depend on when the probe side conjunct is applied
+        let num_rows = |batches: &[RecordBatch]| {
+            batches.iter().map(|batch| batch.num_rows()).sum::<usize>()
+        };
+        for output in &outputs[1..] {
+            assert_eq!(num_rows(&outputs[0]), num_rows(output));
+            compare_batches(&outputs[0], output);
+        }
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_with_max_output_batch_bytes(
+        #[values(JoinType::Inner, JoinType::Left, JoinType::Right, JoinType::Full)]
+        join_type: JoinType,
+    ) -> Result<()> {
+        const VALUE_BYTES: usize = 2 * 1024 * 1024;
+        const BUDGET: usize = 5 * 1024 * 1024;
+        // A few build rows with multi-megabyte strings, the last one unmatched
+        let left_schema = Arc::new(Schema::new(vec![
+            Field::new("a1", DataType::Int32, false),
+            Field::new("b1", DataType::Int32, false),
+            Field::new("s1", DataType::Utf8, false),
+        ]));
+        let left = RecordBatch::try_new(
+            left_schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![0, 1, 2, 3, 4, 5])),
+                Arc::new(Int32Array::from(vec![0, 0, 0, 1, 1, 9])),
+                Arc::new(StringArray::from_iter_values(
+                    (b'a'..b'g').map(|c| (c as char).to_string().repeat(VALUE_BYTES)),
+                )),
+            ],
+        )?;
+        let left = Arc::new(MemoryExec::try_new(
+            &[vec![left]],
+            left_schema.clone(),
+            None,
+        )?);
+        let right = build_table(
+            ("a2", &vec![10, 11, 12]),
+            ("b2", &vec![0, 1, 5]),
+            ("c2", &vec![20, 21, 22]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left_schema)?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let mut results = vec![];
+        for max_output_batch_bytes in [None, Some(BUDGET)] {
+            let mut join =
+                join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
+            if let Some(bytes) = max_output_batch_bytes {
+                join = join.with_max_output_batch_bytes(bytes);
+            }
+            assert_eq!(join.max_output_batch_bytes(), max_output_batch_bytes);
+            let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+            results.push(common::collect(stream).await?);
+        }
+
+        // Every output row as (a1, a2, length of s1)
+        let rows = |batches: &[RecordBatch]| {
+            let mut rows = vec![];
+            for batch in batches {
+                let a1 = as_int32_array(batch.column(0)).unwrap();
+                let s1 = batch.column(2).as_string::<i32>();
+                let a2 = as_int32_array(batch.column(3)).unwrap();
+                for row in 0..batch.num_rows() {
+                    rows.push((
+                        a1.is_valid(row).then(|| a1.value(row)),
+                        a2.is_valid(row).then(|| a2.value(row)),
+                        s1.is_valid(row).then(|| s1.value_length(row)),
+                    ));
+                }
+            }
+            rows.sort();
+            rows
+        };
+        let (batches, budgeted_batches) = (&results[0], &results[1]);
+        assert_eq!(rows(batches), rows(budgeted_batches));
+        assert!(budgeted_batches.len() > batches.len());
+        for batch in budgeted_batches {
+            let s1 = batch.column(2).as_string::<i32>();
+            let value_bytes = (0..batch.num_rows())
+                .map(|row| s1.value_length(row) as usize)
+                .sum::<usize>();
+            assert!(value_bytes <= BUDGET);
+        }
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_probe_rows_with_max_output_batch_bytes(
+        #[values(JoinType::RightSemi, JoinType::RightAnti)] join_type: JoinType,
+    ) -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 5]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30, 40]),
+            ("b2", &vec![4, 5, 6, 5]),
+            ("c2", &vec![70, 80, 90, 100]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let mut results = vec![];
+        for max_output_batch_bytes in [None, Some(12)] {
+            let mut join =
+                join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
+            if let Some(bytes) = max_output_batch_bytes {
+                join = join.with_max_output_batch_bytes(bytes);
+            }
+            let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+            results.push(common::collect(stream).await?);
+        }
+        // Every probe side row takes up 12 bytes, and forms a batch by itself:
+        let (batches, budgeted_batches) = (&results[0], &results[1]);
+        assert!(budgeted_batches.iter().all(|batch| batch.num_rows() <= 1));
+        compare_batches(batches, budgeted_batches);
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_with_max_serialized_batch_bytes(
+        #[values(JoinType::Inner, JoinType::Left, JoinType::Right, JoinType::Full)]
+        join_type: JoinType,
+    ) -> Result<()> {
+        const BUDGET: usize = 16 * 1024;
+        // Build rows with strings of up to a kilobyte, some of them unmatched
+        let left_schema = Arc::new(Schema::new(vec![
+            Field::new("a1", DataType::Int32, false),
+            Field::new("b1", DataType::Int32, false),
+            Field::new("s1", DataType::Utf8, true),
+        ]));
+        let left = RecordBatch::try_new(
+            left_schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..200)),
+                Arc::new(Int32Array::from_iter_values((0..200).map(|i| i % 25))),
+                Arc::new(StringArray::from_iter(
+                    (0..200).map(|i| (i % 7 != 0).then(|| "s".repeat(i * 5))),
+                )),
+            ],
+        )?;
+        let left = Arc::new(MemoryExec::try_new(
+            &[vec![left]],
+            left_schema.clone(),
+            None,
+        )?);
+        let right = build_table(
+            ("a2", &(0..40).collect()),
+            ("b2", &(0..40).map(|i| i % 30).collect()),
+            ("c2", &(0..40).collect()),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left_schema)?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let mut results = vec![];
+        for max_serialized_batch_bytes in [None, Some(BUDGET)] {
+            let mut join =
+                join(left.clone(), right.clone(), on.clone(), &join_type, false)?;
+            if let Some(bytes) = max_serialized_batch_bytes {
+                join = join.with_max_serialized_batch_bytes(bytes);
+            }
+            assert_eq!(
+                join.max_serialized_batch_bytes(),
+                max_serialized_batch_bytes
+            );
+            let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+            results.push(common::collect(stream).await?);
+        }
+        let (batches, budgeted_batches) = (&results[0], &results[1]);
+        assert!(batches
+            .iter()
+            .any(|batch| estimated_ipc_size(batch) > BUDGET));
+        assert!(budgeted_batches.len() > batches.len());
+        for batch in budgeted_batches {
+            assert!(estimated_ipc_size(batch) <= BUDGET);
+        }
+        compare_batches(batches, budgeted_batches);
+
+        // A single row exceeding the limit fails the join:
+        let join = join(left, right, on, &join_type, false)?
+            .with_max_serialized_batch_bytes(1024);
+        let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+        let err = common::collect(stream).await.unwrap_err();
+        assert!(matches!(err, DataFusionError::ResourcesExhausted(_)));
+        assert_contains!(err.to_string(), "exceeding the maximum of 1024 bytes");
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_with_low_latency(
+        #[values(JoinType::Inner, JoinType::Left, JoinType::Right, JoinType::Full)]
+        join_type: JoinType,
+    ) -> Result<()> {
+        let keys = (0..20).map(|i| i % 5).collect::<Vec<_>>();
+        let left = build_table(
+            ("a1", &(0..20).collect()),
+            ("b1", &keys),
+            ("c1", &(0..20).collect()),
+        );
+        let right = build_table(
+            ("a2", &(0..20).collect()),
+            ("b2", &keys.iter().map(|k| k + 2).collect()),
+            ("c2", &(0..20).collect()),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let mut results = vec![];
+        for low_latency in [false, true] {
+            let join = join(left.clone(), right.clone(), on.clone(), &join_type, false)?
+                .with_low_latency(low_latency);
+            let stream = join.execute(0, prepare_task_ctx(16))?;
+            results.push(common::collect(stream).await?);
+        }
+
+        let (batches, low_latency_batches) = (&results[0], &results[1]);
+        assert!(low_latency_batches.len() > batches.len());
+        // Every output batch is at most one chunk of the batch size:
+        assert!(low_latency_batches
+            .iter()
+            .all(|batch| batch.num_rows() <= 16 / LOW_LATENCY_OUTPUT_CHUNKS));
+        let row_count =
+            |batches: &[RecordBatch]| batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        assert_eq!(row_count(batches), row_count(low_latency_batches));
+        compare_batches(batches, low_latency_batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_right_with_null_buffer_reuse() -> Result<()> {
+        let left = build_table(("a1", &vec![1]), ("b1", &vec![3]), ("c1", &vec![1]));
+        let right_batch = build_table_i32(
+            ("a2", &(0..10).collect()),
+            ("b1", &(10..20).collect()),
+            ("c2", &(0..10).collect()),
+        );
+        let schema = right_batch.schema();
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![right_batch; 3]],
+            schema.clone(),
+            None,
+        )?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &schema)?) as _,
+        )];
+
+        let mut results = vec![];
+        for null_buffer_reuse in [false, true] {
+            let join = join(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                &JoinType::Right,
+                false,
+            )?
+            .with_null_buffer_reuse(null_buffer_reuse);
+            let stream = join.execute(0, prepare_task_ctx(8))?;
+            results.push(common::collect(stream).await?);
+        }
+        compare_batches(&results[0], &results[1]);
+
+        // The left columns of the unmatched probe rows are padded from the
+        // same buffer in every output batch only if null buffers are reused:
+        let padding_buffers = |batches: &[RecordBatch]| {
+            batches
+                .iter()
+                .map(|batch| batch.column(0).to_data().buffers()[0].as_ptr())
+                .collect::<HashSet<_>>()
+                .len()
+        };
+        assert!(results[1].len() > 1);
+        assert_eq!(padding_buffers(&results[0]), results[0].len());
+        assert_eq!(padding_buffers(&results[1]), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_empty_right_skips_build_side() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table_i32(("a2", &vec![]), ("b2", &vec![]), ("c2", &vec![]));
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+        let schema = right.schema();
+        let right = Arc::new(MemoryExec::try_new(&[vec![right]], schema, None)?);
+
+        let cases = vec![
+            (
+                JoinType::Left,
+                None,
+                vec![
+                    "+----+----+----+----+----+----+",
+                    "| a1 | b1 | c1 | a2 | b2 | c2 |",
+                    "+----+----+----+----+----+----+",
+                    "| 1  | 4  | 7  |    |    |    |",
+                    "| 2  | 5  | 8  |    |    |    |",
+                    "| 3  | 7  | 9  |    |    |    |",
+                    "+----+----+----+----+----+----+",
+                ],
+            ),
+            (
+                JoinType::Full,
+                Some(vec![4, 0]),
+                vec![
+                    "+----+----+",
+                    "| b2 | a1 |",
+                    "+----+----+",
+                    "|    | 1  |",
+                    "|    | 2  |",
+                    "|    | 3  |",
+                    "+----+----+",
+                ],
+            ),
+            (
+                JoinType::LeftAnti,
+                None,
+                vec![
+                    "+----+----+----+",
+                    "| a1 | b1 | c1 |",
+                    "+----+----+----+",
+                    "| 1  | 4  | 7  |",
+                    "| 2  | 5  | 8  |",
+                    "| 3  | 7  | 9  |",
+                    "+----+----+----+",
+                ],
+            ),
+        ];
+
+        for (join_type, projection, expected) in cases {
+            let join = join(left.clone(), right.clone(), on.clone(), &join_type, false)?
+                .with_projection(projection)?;
+            let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+            let batches = common::collect(stream).await?;
+            assert_batches_sorted_eq!(expected, &batches);
+
+            // The hash table should not have been built:
+            let build_mem_used = join
+                .metrics()
+                .unwrap()
+                .sum_by_name("build_mem_used")
+                .map(|v| v.as_usize());
+            assert_eq!(build_mem_used, Some(0));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_empty_right_splits_output() -> Result<()> {
+        let left = build_table(
+            ("a1", &(0..20).collect()),
+            ("b1", &(0..20).collect()),
+            ("c1", &(0..20).collect()),
+        );
+        let right = build_table_i32(("a2", &vec![]), ("b2", &vec![]), ("c2", &vec![]));
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+        let schema = right.schema();
+        let right = Arc::new(MemoryExec::try_new(&[vec![right]], schema, None)?);
+
+        // Every output row takes up 12 bytes for the values of the build side,
+        // so both joins emit output batches of 2 rows:
+        let low_latency = join(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::Left,
+            false,
+        )?
+        .with_low_latency(true);
+        let budgeted = join(left, right, on, &JoinType::Left, false)?
+            .with_max_output_batch_bytes(24);
+        for join in [low_latency, budgeted] {
+            let stream = join.execute(0, prepare_task_ctx(16))?;
+            let batches = common::collect(stream).await?;
+            assert_eq!(batches.len(), 10);
+            assert!(batches.iter().all(|batch| batch.num_rows() == 2));
+
+            // The hash table should not have been built:
+            let build_mem_used = join
+                .metrics()
+                .unwrap()
+                .sum_by_name("build_mem_used")
+                .map(|v| v.as_usize());
+            assert_eq!(build_mem_used, Some(0));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_empty_right_with_max_serialized_batch_bytes() -> Result<()> {
+        const BUDGET: usize = 16 * 1024;
+        let left_schema = Arc::new(Schema::new(vec![
+            Field::new("a1", DataType::Int32, false),
+            Field::new("s1", DataType::Utf8, true),
+        ]));
+        let left = RecordBatch::try_new(
+            left_schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..200)),
+                Arc::new(StringArray::from_iter(
+                    (0..200).map(|i| (i % 7 != 0).then(|| "s".repeat(i * 5))),
+                )),
+            ],
+        )?;
+        let left = Arc::new(MemoryExec::try_new(
+            &[vec![left]],
+            left_schema.clone(),
+            None,
+        )?);
+        let right = build_table_i32(("a2", &vec![]), ("b2", &vec![]), ("c2", &vec![]));
+        let on = vec![(
+            Arc::new(Column::new_with_schema("a1", &left_schema)?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+        let schema = right.schema();
+        let right = Arc::new(MemoryExec::try_new(&[vec![right]], schema, None)?);
+
+        let mut results = vec![];
+        for max_serialized_batch_bytes in [None, Some(BUDGET)] {
+            let mut join = join(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                &JoinType::Left,
+                false,
+            )?;
+            if let Some(bytes) = max_serialized_batch_bytes {
+                join = join.with_max_serialized_batch_bytes(bytes);
+            }
+            let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+            results.push(common::collect(stream).await?);
+        }
+        let (batches, budgeted_batches) = (&results[0], &results[1]);
+        assert!(batches
+            .iter()
+            .any(|batch| estimated_ipc_size(batch) > BUDGET));
+        assert!(budgeted_batches.len() > batches.len());
+        for batch in budgeted_batches {
+            assert!(estimated_ipc_size(batch) <= BUDGET);
+        }
+        compare_batches(batches, budgeted_batches);
+
+        // A single row exceeding the limit fails the join:
+        let join = join(left, right, on, &JoinType::Left, false)?
+            .with_max_serialized_batch_bytes(1024);
+        let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+        let err = common::collect(stream).await.unwrap_err();
+        assert!(matches!(err, DataFusionError::ResourcesExhausted(_)));
+
+        Ok(())
+    }
+
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn join_left_one(batch_size: usize) -> Result<()> {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        let (columns, batches) = join_collect(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::Left,
+            false,
+            task_ctx,
+        )
+        .await?;
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 3  | 7  | 9  |    |    |    |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn partitioned_join_left_one(batch_size: usize) -> Result<()> {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        let (columns, batches) = partitioned_join_collect(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::Left,
+            false,
+            task_ctx,
+        )
+        .await?;
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 3  | 7  | 9  |    |    |    |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    fn build_semi_anti_left_table() -> Arc<dyn ExecutionPlan> {
+        // just two line match
+        // b1 = 10
+        build_table(
+            ("a1", &vec![1, 3, 5, 7, 9, 11, 13]),
+            ("b1", &vec![1, 3, 5, 7, 8, 8, 10]),
+            ("c1", &vec![10, 30, 50, 70, 90, 110, 130]),
+        )
+    }
+
+    fn build_semi_anti_right_table() -> Arc<dyn ExecutionPlan> {
+        // just two line match
+        // b2 = 10
+        build_table(
+            ("a2", &vec![8, 12, 6, 2, 10, 4]),
+            ("b2", &vec![8, 10, 6, 2, 10, 4]),
+            ("c2", &vec![20, 40, 60, 80, 100, 120]),
+        )
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn semi_anti_join_on_composite_keys_with_nulls(
+        #[values(
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightSemi,
+            JoinType::RightAnti
+        )]
+        join_type: JoinType,
+        #[values(false, true)] null_equals_null: bool,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(16);
+        let (left_batch, right_batch) = build_sides_record_batches(100, (4, 5))?;
+        let memory = |batch: &RecordBatch| -> Result<Arc<dyn ExecutionPlan>> {
+            Ok(Arc::new(MemoryExec::try_new(
+                &[split_record_batches(batch, 10)?],
+                batch.schema(),
+                None,
+            )?))
+        };
+        // The second key is null for the first 40 rows of both sides, in which
+        // the first key still has matches:
+        let key_names = [("lc1", "rc1"), ("l_asc_null_first", "r_asc_null_first")];
+        let on = key_names
+            .iter()
+            .map(|(l, r)| -> Result<_> {
+                Ok((
+                    Arc::new(Column::new_with_schema(l, &left_batch.schema())?) as _,
+                    Arc::new(Column::new_with_schema(r, &right_batch.schema())?) as _,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Reference semantics: a row matches if all keys compare equal, where
+        // a null is only equal to a null if `null_equals_null` is set
+        let keys = |batch: &RecordBatch, names: [&str; 2]| {
+            let first = as_int32_array(batch.column_by_name(names[0]).unwrap()).unwrap();
+            let second = as_int32_array(batch.column_by_name(names[1]).unwrap()).unwrap();
+            first.iter().zip(second.iter()).collect::<Vec<_>>()
+        };
+        let left_keys = keys(&left_batch, [key_names[0].0, key_names[1].0]);
+        let right_keys = keys(&right_batch, [key_names[0].1, key_names[1].1]);
+        let key_equal = |l: Option<i32>, r: Option<i32>| match (l, r) {
+            (Some(l), Some(r)) => l == r,
+            (None, None) => null_equals_null,
+            _ => false,
+        };
+        let has_match = |row: &(Option<i32>, Option<i32>),
+                         others: &[(Option<i32>, Option<i32>)]| {
+            others
+                .iter()
+                .any(|other| key_equal(row.0, other.0) && key_equal(row.1, other.1))
+        };
+        let (rows, other_rows, row_ids) = match join_type {
+            JoinType::LeftSemi | JoinType::LeftAnti => {
+                (&left_keys, &right_keys, left_batch.column_by_name("la1"))
+            }
+            _ => (&right_keys, &left_keys, right_batch.column_by_name("ra1")),
+        };
+        let semi = matches!(join_type, JoinType::LeftSemi | JoinType::RightSemi);
+        let row_ids = as_int32_array(row_ids.unwrap())?;
+        let expected = rows
+            .iter()
+            .zip(row_ids.values())
+            .filter(|(row, _)| has_match(row, other_rows) == semi)
+            .map(|(_, id)| *id)
+            .collect::<Vec<_>>();
+
+        let join = join(
+            memory(&left_batch)?,
+            memory(&right_batch)?,
+            on,
+            &join_type,
+            null_equals_null,
+        )?;
+        let batches = common::collect(join.execute(0, task_ctx)?).await?;
+        let mut actual = batches
+            .iter()
+            .flat_map(|batch| as_int32_array(batch.column(0)).unwrap().values().to_vec())
+            .collect::<Vec<_>>();
+        actual.sort_unstable();
+        assert!(!expected.is_empty());
+        assert_eq!(actual, expected);
+
+        Ok(())
+    }
+
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn join_left_semi(batch_size: usize) -> Result<()> {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+        // left_table left semi join right_table on left_table.b1 = right_table.b2
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let join = join(left, right, on, &JoinType::LeftSemi, false)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a1", "b1", "c1"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        // ignore the order
+        let expected = [
+            "+----+----+-----+",
+            "| a1 | b1 | c1  |",
+            "+----+----+-----+",
+            "| 11 | 8  | 110 |",
+            "| 13 | 10 | 130 |",
+            "| 9  | 8  | 90  |",
+            "+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn join_left_semi_with_filter(batch_size: usize) -> Result<()> {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+
+        // left_table left semi join right_table on left_table.b1 = right_table.b2 and right_table.a2 != 10
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let column_indices = vec![ColumnIndex {
+            index: 0,
+            side: JoinSide::Right,
+        }];
+        let intermediate_schema =
+            Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::NotEq,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(10)))),
+        )) as Arc<dyn PhysicalExpr>;
+
+        let filter = JoinFilter::new(
+            filter_expression,
+            column_indices.clone(),
+            intermediate_schema.clone(),
+        );
+
+        let join = join_with_filter(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            filter,
+            &JoinType::LeftSemi,
+            false,
+        )?;
+
+        let columns_header = columns(&join.schema());
+        assert_eq!(columns_header.clone(), vec!["a1", "b1", "c1"]);
+
+        let stream = join.execute(0, task_ctx.clone())?;
+        let batches = common::collect(stream).await?;
+
+        let expected = [
+            "+----+----+-----+",
+            "| a1 | b1 | c1  |",
+            "+----+----+-----+",
+            "| 11 | 8  | 110 |",
+            "| 13 | 10 | 130 |",
+            "| 9  | 8  | 90  |",
+            "+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // left_table left semi join right_table on left_table.b1 = right_table.b2 and right_table.a2 > 10
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::Gt,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(10)))),
+        )) as Arc<dyn PhysicalExpr>;
+        let filter =
+            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
+
+        let join = join_with_filter(left, right, on, filter, &JoinType::LeftSemi, false)?;
+
+        let columns_header = columns(&join.schema());
+        assert_eq!(columns_header, vec!["a1", "b1", "c1"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = [
+            "+----+----+-----+",
+            "| a1 | b1 | c1  |",
+            "+----+----+-----+",
+            "| 13 | 10 | 130 |",
+            "+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn join_right_semi(batch_size: usize) -> Result<()> {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+
+        // left_table right semi join right_table on left_table.b1 = right_table.b2
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let join = join(left, right, on, &JoinType::RightSemi, false)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a2", "b2", "c2"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = [
+            "+----+----+-----+",
+            "| a2 | b2 | c2  |",
+            "+----+----+-----+",
+            "| 8  | 8  | 20  |",
+            "| 12 | 10 | 40  |",
+            "| 10 | 10 | 100 |",
+            "+----+----+-----+",
+        ];
 
-        Ok(StatefulStreamResult::Ready(Some(result)))
+        // RightSemi join output is expected to preserve right input order
+        assert_batches_eq!(expected, &batches);
+
+        Ok(())
     }
 
-    /// Processes unmatched build-side rows for certain join types and produces output batch
-    ///
-    /// Updates state to `Completed`
-    fn process_unmatched_build_batch(
-        &mut self,
-    ) -> Result<StatefulStreamResult<Option<RecordBatch>>> {
-        let timer = self.join_metrics.join_time.timer();
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn join_right_semi_with_filter(batch_size: usize) -> Result<()> {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
 
-        if !need_produce_result_in_final(self.join_type) {
-            self.state = HashJoinStreamState::Completed;
+        // left_table right semi join right_table on left_table.b1 = right_table.b2 on left_table.a1!=9
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
 
-            return Ok(StatefulStreamResult::Continue);
-        }
+        let column_indices = vec![ColumnIndex {
+            index: 0,
+            side: JoinSide::Left,
+        }];
+        let intermediate_schema =
+            Schema::new(vec![Field::new("x", DataType::Int32, true)]);
 
-        let build_side = self.build_side.try_as_ready()?;
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::NotEq,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(9)))),
+        )) as Arc<dyn PhysicalExpr>;
 
-        // use the global left bitmap to produce the left indices and right indices
-        let (left_side, right_side) =
-            get_final_indices_from_bit_map(&build_side.visited_left_side, self.join_type);
-        let empty_right_batch = RecordBatch::new_empty(self.right.schema());
-        // use the left and right indices to produce the batch result
-        let result = build_batch_from_indices(
-            &self.schema,
-            build_side.left_data.batch(),
-            &empty_right_batch,
-            &left_side,
-            &right_side,
-            &self.column_indices,
-            JoinSide::Left,
+        let filter = JoinFilter::new(
+            filter_expression,
+            column_indices.clone(),
+            intermediate_schema.clone(),
         );
 
-        if let Ok(ref batch) = result {
-            self.join_metrics.input_batches.add(1);
-            self.join_metrics.input_rows.add(batch.num_rows());
+        let join = join_with_filter(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            filter,
+            &JoinType::RightSemi,
+            false,
+        )?;
 
-            self.join_metrics.output_batches.add(1);
-            self.join_metrics.output_rows.add(batch.num_rows());
-        }
-        timer.done();
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a2", "b2", "c2"]);
 
-        self.state = HashJoinStreamState::Completed;
+        let stream = join.execute(0, task_ctx.clone())?;
+        let batches = common::collect(stream).await?;
 
-        Ok(StatefulStreamResult::Ready(Some(result?)))
-    }
-}
+        let expected = [
+            "+----+----+-----+",
+            "| a2 | b2 | c2  |",
+            "+----+----+-----+",
+            "| 8  | 8  | 20  |",
+            "| 12 | 10 | 40  |",
+            "| 10 | 10 | 100 |",
+            "+----+----+-----+",
+        ];
 
-impl Stream for HashJoinStream {
-    type Item = Result<RecordBatch>;
+        // RightSemi join output is expected to preserve right input order
+        assert_batches_eq!(expected, &batches);
 
-    fn poll_next(
-        mut self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
-    ) -> std::task::Poll<Option<Self::Item>> {
-        self.poll_next_impl(cx)
-    }
-}
+        // left_table right semi join right_table on left_table.b1 = right_table.b2 on left_table.a1!=9
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::Gt,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(11)))),
+        )) as Arc<dyn PhysicalExpr>;
 
-#[cfg(test)]
-mod tests {
-    use std::sync::Arc;
+        let filter =
+            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
 
-    use super::*;
-    use crate::{
-        common, expressions::Column, hash_utils::create_hashes, memory::MemoryExec,
-        repartition::RepartitionExec, test::build_table_i32, test::exec::MockExec,
-    };
+        let join =
+            join_with_filter(left, right, on, filter, &JoinType::RightSemi, false)?;
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
 
-    use arrow::array::{ArrayRef, Date32Array, Int32Array, UInt32Builder, UInt64Builder};
-    use arrow::datatypes::{DataType, Field, Schema};
-    use datafusion_common::{
-        assert_batches_eq, assert_batches_sorted_eq, assert_contains, exec_err,
-        ScalarValue,
-    };
-    use datafusion_execution::config::SessionConfig;
-    use datafusion_execution::runtime_env::{RuntimeConfig, RuntimeEnv};
-    use datafusion_expr::Operator;
-    use datafusion_physical_expr::expressions::{BinaryExpr, Literal};
-    use datafusion_physical_expr::PhysicalExpr;
+        let expected = [
+            "+----+----+-----+",
+            "| a2 | b2 | c2  |",
+            "+----+----+-----+",
+            "| 12 | 10 | 40  |",
+            "| 10 | 10 | 100 |",
+            "+----+----+-----+",
+        ];
 
-    use hashbrown::raw::RawTable;
-    use rstest::*;
-    use rstest_reuse::{self, *};
+        // RightSemi join output is expected to preserve right input order
+        assert_batches_eq!(expected, &batches);
 
-    fn div_ceil(a: usize, b: usize) -> usize {
-        (a + b - 1) / b
+        Ok(())
     }
 
-    #[template]
-    #[rstest]
-    fn batch_sizes(#[values(8192, 10, 5, 2, 1)] batch_size: usize) {}
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn join_left_anti(batch_size: usize) -> Result<()> {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+        // left_table left anti join right_table on left_table.b1 = right_table.b2
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
 
-    fn prepare_task_ctx(batch_size: usize) -> Arc<TaskContext> {
-        let session_config = SessionConfig::default().with_batch_size(batch_size);
-        Arc::new(TaskContext::default().with_session_config(session_config))
-    }
+        let join = join(left, right, on, &JoinType::LeftAnti, false)?;
 
-    fn build_table(
-        a: (&str, &Vec<i32>),
-        b: (&str, &Vec<i32>),
-        c: (&str, &Vec<i32>),
-    ) -> Arc<dyn ExecutionPlan> {
-        let batch = build_table_i32(a, b, c);
-        let schema = batch.schema();
-        Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
-    }
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a1", "b1", "c1"]);
 
-    fn join(
-        left: Arc<dyn ExecutionPlan>,
-        right: Arc<dyn ExecutionPlan>,
-        on: JoinOn,
-        join_type: &JoinType,
-        null_equals_null: bool,
-    ) -> Result<HashJoinExec> {
-        HashJoinExec::try_new(
-            left,
-            right,
-            on,
-            None,
-            join_type,
-            None,
-            PartitionMode::CollectLeft,
-            null_equals_null,
-        )
-    }
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
 
-    fn join_with_filter(
-        left: Arc<dyn ExecutionPlan>,
-        right: Arc<dyn ExecutionPlan>,
-        on: JoinOn,
-        filter: JoinFilter,
-        join_type: &JoinType,
-        null_equals_null: bool,
-    ) -> Result<HashJoinExec> {
-        HashJoinExec::try_new(
-            left,
-            right,
-            on,
-            Some(filter),
-            join_type,
-            None,
-            PartitionMode::CollectLeft,
-            null_equals_null,
-        )
+        let expected = [
+            "+----+----+----+",
+            "| a1 | b1 | c1 |",
+            "+----+----+----+",
+            "| 1  | 1  | 10 |",
+            "| 3  | 3  | 30 |",
+            "| 5  | 5  | 50 |",
+            "| 7  | 7  | 70 |",
+            "+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
     }
 
-    async fn join_collect(
-        left: Arc<dyn ExecutionPlan>,
-        right: Arc<dyn ExecutionPlan>,
-        on: JoinOn,
-        join_type: &JoinType,
-        null_equals_null: bool,
-        context: Arc<TaskContext>,
-    ) -> Result<(Vec<String>, Vec<RecordBatch>)> {
-        let join = join(left, right, on, join_type, null_equals_null)?;
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn join_left_anti_with_filter(batch_size: usize) -> Result<()> {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+        // left_table left anti join right_table on left_table.b1 = right_table.b2 and right_table.a2!=8
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let column_indices = vec![ColumnIndex {
+            index: 0,
+            side: JoinSide::Right,
+        }];
+        let intermediate_schema =
+            Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::NotEq,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(8)))),
+        )) as Arc<dyn PhysicalExpr>;
+
+        let filter = JoinFilter::new(
+            filter_expression,
+            column_indices.clone(),
+            intermediate_schema.clone(),
+        );
+
+        let join = join_with_filter(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            filter,
+            &JoinType::LeftAnti,
+            false,
+        )?;
+
         let columns_header = columns(&join.schema());
+        assert_eq!(columns_header, vec!["a1", "b1", "c1"]);
 
-        let stream = join.execute(0, context)?;
+        let stream = join.execute(0, task_ctx.clone())?;
         let batches = common::collect(stream).await?;
 
-        Ok((columns_header, batches))
-    }
+        let expected = [
+            "+----+----+-----+",
+            "| a1 | b1 | c1  |",
+            "+----+----+-----+",
+            "| 1  | 1  | 10  |",
+            "| 11 | 8  | 110 |",
+            "| 3  | 3  | 30  |",
+            "| 5  | 5  | 50  |",
+            "| 7  | 7  | 70  |",
+            "| 9  | 8  | 90  |",
+            "+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
 
-    async fn partitioned_join_collect(
-        left: Arc<dyn ExecutionPlan>,
-        right: Arc<dyn ExecutionPlan>,
-        on: JoinOn,
-        join_type: &JoinType,
-        null_equals_null: bool,
-        context: Arc<TaskContext>,
-    ) -> Result<(Vec<String>, Vec<RecordBatch>)> {
-        let partition_count = 4;
+        // left_table left anti join right_table on left_table.b1 = right_table.b2 and right_table.a2 != 13
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::NotEq,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(8)))),
+        )) as Arc<dyn PhysicalExpr>;
 
-        let (left_expr, right_expr) =
-            on.iter().map(|(l, r)| (l.clone(), r.clone())).unzip();
+        let filter =
+            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
 
-        let join = HashJoinExec::try_new(
-            Arc::new(RepartitionExec::try_new(
-                left,
-                Partitioning::Hash(left_expr, partition_count),
-            )?),
-            Arc::new(RepartitionExec::try_new(
-                right,
-                Partitioning::Hash(right_expr, partition_count),
-            )?),
-            on,
-            None,
-            join_type,
-            None,
-            PartitionMode::Partitioned,
-            null_equals_null,
-        )?;
+        let join = join_with_filter(left, right, on, filter, &JoinType::LeftAnti, false)?;
 
-        let columns = columns(&join.schema());
+        let columns_header = columns(&join.schema());
+        assert_eq!(columns_header, vec!["a1", "b1", "c1"]);
 
-        let mut batches = vec![];
-        for i in 0..partition_count {
-            let stream = join.execute(i, context.clone())?;
-            let more_batches = common::collect(stream).await?;
-            batches.extend(
-                more_batches
-                    .into_iter()
-                    .filter(|b| b.num_rows() > 0)
-                    .collect::<Vec<_>>(),
-            );
-        }
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
 
-        Ok((columns, batches))
+        let expected = [
+            "+----+----+-----+",
+            "| a1 | b1 | c1  |",
+            "+----+----+-----+",
+            "| 1  | 1  | 10  |",
+            "| 11 | 8  | 110 |",
+            "| 3  | 3  | 30  |",
+            "| 5  | 5  | 50  |",
+            "| 7  | 7  | 70  |",
+            "| 9  | 8  | 90  |",
+            "+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
     }
 
     #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_inner_one(batch_size: usize) -> Result<()> {
+    async fn join_right_anti(batch_size: usize) -> Result<()> {
         let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_table(
-            ("a1", &vec![1, 2, 3]),
-            ("b1", &vec![4, 5, 5]), // this has a repetition
-            ("c1", &vec![7, 8, 9]),
-        );
-        let right = build_table(
-            ("a2", &vec![10, 20, 30]),
-            ("b1", &vec![4, 5, 6]),
-            ("c2", &vec![70, 80, 90]),
-        );
-
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
         let on = vec![(
             Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
-            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
 
-        let (columns, batches) = join_collect(
-            left.clone(),
-            right.clone(),
-            on.clone(),
-            &JoinType::Inner,
-            false,
-            task_ctx,
-        )
-        .await?;
+        let join = join(left, right, on, &JoinType::RightAnti, false)?;
 
-        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a2", "b2", "c2"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
 
         let expected = [
-            "+----+----+----+----+----+----+",
-            "| a1 | b1 | c1 | a2 | b1 | c2 |",
-            "+----+----+----+----+----+----+",
-            "| 1  | 4  | 7  | 10 | 4  | 70 |",
-            "| 2  | 5  | 8  | 20 | 5  | 80 |",
-            "| 3  | 5  | 9  | 20 | 5  | 80 |",
-            "+----+----+----+----+----+----+",
+            "+----+----+-----+",
+            "| a2 | b2 | c2  |",
+            "+----+----+-----+",
+            "| 6  | 6  | 60  |",
+            "| 2  | 2  | 80  |",
+            "| 4  | 4  | 120 |",
+            "+----+----+-----+",
         ];
 
-        // Inner join output is expected to preserve both inputs order
+        // RightAnti join output is expected to preserve right input order
         assert_batches_eq!(expected, &batches);
-
         Ok(())
     }
 
     #[apply(batch_sizes)]
     #[tokio::test]
-    async fn partitioned_join_inner_one(batch_size: usize) -> Result<()> {
+    async fn join_right_anti_with_filter(batch_size: usize) -> Result<()> {
         let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_table(
-            ("a1", &vec![1, 2, 3]),
-            ("b1", &vec![4, 5, 5]), // this has a repetition
-            ("c1", &vec![7, 8, 9]),
-        );
-        let right = build_table(
-            ("a2", &vec![10, 20, 30]),
-            ("b1", &vec![4, 5, 6]),
-            ("c2", &vec![70, 80, 90]),
-        );
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+        // left_table right anti join right_table on left_table.b1 = right_table.b2 and left_table.a1!=13
         let on = vec![(
             Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
-            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
 
-        let (columns, batches) = partitioned_join_collect(
+        let column_indices = vec![ColumnIndex {
+            index: 0,
+            side: JoinSide::Left,
+        }];
+        let intermediate_schema =
+            Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::NotEq,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(13)))),
+        )) as Arc<dyn PhysicalExpr>;
+
+        let filter = JoinFilter::new(
+            filter_expression,
+            column_indices,
+            intermediate_schema.clone(),
+        );
+
+        let join = join_with_filter(
             left.clone(),
             right.clone(),
             on.clone(),
-            &JoinType::Inner,
+            filter,
+            &JoinType::RightAnti,
             false,
-            task_ctx,
-        )
-        .await?;
+        )?;
 
-        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
+        let columns_header = columns(&join.schema());
+        assert_eq!(columns_header, vec!["a2", "b2", "c2"]);
+
+        let stream = join.execute(0, task_ctx.clone())?;
+        let batches = common::collect(stream).await?;
 
         let expected = [
-            "+----+----+----+----+----+----+",
-            "| a1 | b1 | c1 | a2 | b1 | c2 |",
-            "+----+----+----+----+----+----+",
-            "| 1  | 4  | 7  | 10 | 4  | 70 |",
-            "| 2  | 5  | 8  | 20 | 5  | 80 |",
-            "| 3  | 5  | 9  | 20 | 5  | 80 |",
-            "+----+----+----+----+----+----+",
+            "+----+----+-----+",
+            "| a2 | b2 | c2  |",
+            "+----+----+-----+",
+            "| 12 | 10 | 40  |",
+            "| 6  | 6  | 60  |",
+            "| 2  | 2  | 80  |",
+            "| 10 | 10 | 100 |",
+            "| 4  | 4  | 120 |",
+            "+----+----+-----+",
         ];
-        assert_batches_sorted_eq!(expected, &batches);
 
-        Ok(())
-    }
+        // RightAnti join output is expected to preserve right input order
+        assert_batches_eq!(expected, &batches);
 
-    #[tokio::test]
-    async fn join_inner_one_no_shared_column_names() -> Result<()> {
-        let task_ctx = Arc::new(TaskContext::default());
-        let left = build_table(
-            ("a1", &vec![1, 2, 3]),
-            ("b1", &vec![4, 5, 5]), // this has a repetition
-            ("c1", &vec![7, 8, 9]),
-        );
-        let right = build_table(
-            ("a2", &vec![10, 20, 30]),
-            ("b2", &vec![4, 5, 6]),
-            ("c2", &vec![70, 80, 90]),
-        );
-        let on = vec![(
-            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
-            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
-        )];
+        // left_table right anti join right_table on left_table.b1 = right_table.b2 and right_table.b2!=8
+        let column_indices = vec![ColumnIndex {
+            index: 1,
+            side: JoinSide::Right,
+        }];
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::NotEq,
+            Arc::new(Literal::new(ScalarValue::Int32(Some(8)))),
+        )) as Arc<dyn PhysicalExpr>;
 
-        let (columns, batches) =
-            join_collect(left, right, on, &JoinType::Inner, false, task_ctx).await?;
+        let filter =
+            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
 
-        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b2", "c2"]);
+        let join =
+            join_with_filter(left, right, on, filter, &JoinType::RightAnti, false)?;
+
+        let columns_header = columns(&join.schema());
+        assert_eq!(columns_header, vec!["a2", "b2", "c2"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
 
         let expected = [
-            "+----+----+----+----+----+----+",
-            "| a1 | b1 | c1 | a2 | b2 | c2 |",
-            "+----+----+----+----+----+----+",
-            "| 1  | 4  | 7  | 10 | 4  | 70 |",
-            "| 2  | 5  | 8  | 20 | 5  | 80 |",
-            "| 3  | 5  | 9  | 20 | 5  | 80 |",
-            "+----+----+----+----+----+----+",
+            "+----+----+-----+",
+            "| a2 | b2 | c2  |",
+            "+----+----+-----+",
+            "| 8  | 8  | 20  |",
+            "| 6  | 6  | 60  |",
+            "| 2  | 2  | 80  |",
+            "| 4  | 4  | 120 |",
+            "+----+----+-----+",
         ];
 
-        // Inner join output is expected to preserve both inputs order
+        // RightAnti join output is expected to preserve right input order
         assert_batches_eq!(expected, &batches);
 
         Ok(())
     }
 
+    #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_inner_one_randomly_ordered() -> Result<()> {
-        let task_ctx = Arc::new(TaskContext::default());
-        let left = build_table(
-            ("a1", &vec![0, 3, 2, 1]),
-            ("b1", &vec![4, 5, 5, 4]),
-            ("c1", &vec![6, 9, 8, 7]),
-        );
-        let right = build_table(
-            ("a2", &vec![20, 30, 10]),
-            ("b2", &vec![5, 6, 4]),
-            ("c2", &vec![80, 90, 70]),
-        );
+    async fn join_right_mark(batch_size: usize) -> Result<()> {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
         let on = vec![(
             Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
             Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
 
-        let (columns, batches) =
-            join_collect(left, right, on, &JoinType::Inner, false, task_ctx).await?;
+        let join = join(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::RightMark,
+            false,
+        )?;
 
-        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b2", "c2"]);
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a2", "b2", "c2", "mark"]);
+
+        let stream = join.execute(0, task_ctx.clone())?;
+        let batches = common::collect(stream).await?;
 
         let expected = [
-            "+----+----+----+----+----+----+",
-            "| a1 | b1 | c1 | a2 | b2 | c2 |",
-            "+----+----+----+----+----+----+",
-            "| 3  | 5  | 9  | 20 | 5  | 80 |",
-            "| 2  | 5  | 8  | 20 | 5  | 80 |",
-            "| 0  | 4  | 6  | 10 | 4  | 70 |",
-            "| 1  | 4  | 7  | 10 | 4  | 70 |",
-            "+----+----+----+----+----+----+",
+            "+----+----+-----+-------+",
+            "| a2 | b2 | c2  | mark  |",
+            "+----+----+-----+-------+",
+            "| 8  | 8  | 20  | true  |",
+            "| 12 | 10 | 40  | true  |",
+            "| 6  | 6  | 60  | false |",
+            "| 2  | 2  | 80  | false |",
+            "| 10 | 10 | 100 | true  |",
+            "| 4  | 4  | 120 | false |",
+            "+----+----+-----+-------+",
         ];
 
-        // Inner join output is expected to preserve both inputs order
+        // RightMark join output is expected to preserve right input order
         assert_batches_eq!(expected, &batches);
 
+        // The swapped LeftMark join marks the same rows
+        let swapped_on = on
+            .iter()
+            .map(|(l, r)| (r.clone(), l.clone()))
+            .collect::<Vec<_>>();
+        let swapped = join_collect(
+            right,
+            left,
+            swapped_on,
+            &JoinType::LeftMark,
+            false,
+            task_ctx,
+        )
+        .await?;
+        assert_eq!(swapped.0, columns);
+        compare_batches(&batches, &swapped.1);
+
         Ok(())
     }
 
-    #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_inner_two(batch_size: usize) -> Result<()> {
-        let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_table(
-            ("a1", &vec![1, 2, 2]),
-            ("b2", &vec![1, 2, 2]),
-            ("c1", &vec![7, 8, 9]),
-        );
-        let right = build_table(
-            ("a1", &vec![1, 2, 3]),
-            ("b2", &vec![1, 2, 2]),
-            ("c2", &vec![70, 80, 90]),
-        );
-        let on = vec![
-            (
-                Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
-                Arc::new(Column::new_with_schema("a1", &right.schema())?) as _,
-            ),
-            (
-                Arc::new(Column::new_with_schema("b2", &left.schema())?) as _,
-                Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
-            ),
-        ];
+    async fn join_right_mark_with_projection() -> Result<()> {
+        let task_ctx = prepare_task_ctx(8192);
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
 
-        let (columns, batches) =
-            join_collect(left, right, on, &JoinType::Inner, false, task_ctx).await?;
+        let join = join(left, right, on, &JoinType::RightMark, false)?
+            .with_projection(Some(vec![3, 0, 3]))?;
 
-        assert_eq!(columns, vec!["a1", "b2", "c1", "a1", "b2", "c2"]);
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["mark", "a2", "mark"]);
 
-        // expected joined records = 3
-        // in case batch_size is 1 - additional empty batch for remaining 3-2 row
-        let mut expected_batch_count = div_ceil(3, batch_size);
-        if batch_size == 1 {
-            expected_batch_count += 1;
-        }
-        assert_eq!(batches.len(), expected_batch_count);
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
 
         let expected = [
-            "+----+----+----+----+----+----+",
-            "| a1 | b2 | c1 | a1 | b2 | c2 |",
-            "+----+----+----+----+----+----+",
-            "| 1  | 1  | 7  | 1  | 1  | 70 |",
-            "| 2  | 2  | 8  | 2  | 2  | 80 |",
-            "| 2  | 2  | 9  | 2  | 2  | 80 |",
-            "+----+----+----+----+----+----+",
+            "+-------+----+-------+",
+            "| mark  | a2 | mark  |",
+            "+-------+----+-------+",
+            "| true  | 8  | true  |",
+            "| true  | 12 | true  |",
+            "| false | 6  | false |",
+            "| false | 2  | false |",
+            "| true  | 10 | true  |",
+            "| false | 4  | false |",
+            "+-------+----+-------+",
         ];
-
-        // Inner join output is expected to preserve both inputs order
         assert_batches_eq!(expected, &batches);
 
         Ok(())
     }
 
-    /// Test where the left has 2 parts, the right with 1 part => 1 part
-    #[apply(batch_sizes)]
+    #[rstest]
     #[tokio::test]
-    async fn join_inner_one_two_parts_left(batch_size: usize) -> Result<()> {
-        let task_ctx = prepare_task_ctx(batch_size);
-        let batch1 = build_table_i32(
-            ("a1", &vec![1, 2]),
-            ("b2", &vec![1, 2]),
-            ("c1", &vec![7, 8]),
-        );
-        let batch2 =
-            build_table_i32(("a1", &vec![2]), ("b2", &vec![2]), ("c1", &vec![9]));
-        let schema = batch1.schema();
-        let left = Arc::new(
-            MemoryExec::try_new(&[vec![batch1], vec![batch2]], schema, None).unwrap(),
-        );
-
-        let right = build_table(
-            ("a1", &vec![1, 2, 3]),
-            ("b2", &vec![1, 2, 2]),
-            ("c2", &vec![70, 80, 90]),
-        );
-        let on = vec![
+    async fn mark_join_with_null_keys(
+        #[values(JoinType::LeftMark, JoinType::RightMark)] join_type: JoinType,
+    ) -> Result<()> {
+        let task_ctx = prepare_task_ctx(8192);
+        let table =
+            |name: &str, keys: &[Option<i32>]| -> Result<Arc<dyn ExecutionPlan>> {
+                let schema =
+                    Arc::new(Schema::new(vec![Field::new(name, DataType::Int32, true)]));
+                let batch = RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(Int32Array::from(keys.to_vec()))],
+                )?;
+                Ok(Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None)?))
+            };
+        let marked_keys = [Some(1), Some(3), None];
+        // The keys of the other side, whether nulls are equal, and the marks
+        // of `marked_keys`, which follow `IN` unless nulls are equal
+        let cases = [
             (
-                Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
-                Arc::new(Column::new_with_schema("a1", &right.schema())?) as _,
+                vec![Some(1), Some(2), None],
+                false,
+                vec![Some(true), None, None],
             ),
             (
-                Arc::new(Column::new_with_schema("b2", &left.schema())?) as _,
-                Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+                vec![Some(1), Some(2)],
+                false,
+                vec![Some(true), Some(false), None],
+            ),
+            (vec![], false, vec![Some(false), Some(false), Some(false)]),
+            (
+                vec![Some(1), Some(2), None],
+                true,
+                vec![Some(true), Some(false), Some(true)],
             ),
         ];
-
-        let (columns, batches) =
-            join_collect(left, right, on, &JoinType::Inner, false, task_ctx).await?;
-
-        assert_eq!(columns, vec!["a1", "b2", "c1", "a1", "b2", "c2"]);
-
-        // expected joined records = 3
-        // in case batch_size is 1 - additional empty batch for remaining 3-2 row
-        let mut expected_batch_count = div_ceil(3, batch_size);
-        if batch_size == 1 {
-            expected_batch_count += 1;
+        for (other_keys, null_equals_null, expected) in cases {
+            let (left, right) = if join_type == JoinType::LeftMark {
+                (table("l", &marked_keys)?, table("r", &other_keys)?)
+            } else {
+                (table("l", &other_keys)?, table("r", &marked_keys)?)
+            };
+            let on = vec![(
+                Arc::new(Column::new_with_schema("l", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("r", &right.schema())?) as _,
+            )];
+            let join = join(left, right, on, &join_type, null_equals_null)?;
+            let batches = common::collect(join.execute(0, task_ctx.clone())?).await?;
+            let marks = batches
+                .iter()
+                .flat_map(|batch| {
+                    as_boolean_array(batch.column_by_name("mark").unwrap())
+                        .unwrap()
+                        .iter()
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(marks, expected, "{other_keys:?} {null_equals_null}");
         }
-        assert_eq!(batches.len(), expected_batch_count);
-
-        let expected = [
-            "+----+----+----+----+----+----+",
-            "| a1 | b2 | c1 | a1 | b2 | c2 |",
-            "+----+----+----+----+----+----+",
-            "| 1  | 1  | 7  | 1  | 1  | 70 |",
-            "| 2  | 2  | 8  | 2  | 2  | 80 |",
-            "| 2  | 2  | 9  | 2  | 2  | 80 |",
-            "+----+----+----+----+----+----+",
-        ];
-
-        // Inner join output is expected to preserve both inputs order
-        assert_batches_eq!(expected, &batches);
 
         Ok(())
     }
 
-    #[tokio::test]
-    async fn join_inner_one_two_parts_left_randomly_ordered() -> Result<()> {
-        let task_ctx = Arc::new(TaskContext::default());
-        let batch1 = build_table_i32(
-            ("a1", &vec![0, 3]),
-            ("b1", &vec![4, 5]),
-            ("c1", &vec![6, 9]),
-        );
-        let batch2 = build_table_i32(
-            ("a1", &vec![2, 1]),
-            ("b1", &vec![5, 4]),
-            ("c1", &vec![8, 7]),
-        );
-        let schema = batch1.schema();
-
-        let left = Arc::new(
-            MemoryExec::try_new(&[vec![batch1], vec![batch2]], schema, None).unwrap(),
-        );
-        let right = build_table(
-            ("a2", &vec![20, 30, 10]),
-            ("b2", &vec![5, 6, 4]),
-            ("c2", &vec![80, 90, 70]),
-        );
-        let on = vec![(
-            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
-            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
-        )];
-
-        let (columns, batches) =
-            join_collect(left, right, on, &JoinType::Inner, false, task_ctx).await?;
-
-        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b2", "c2"]);
-
-        let expected = [
-            "+----+----+----+----+----+----+",
-            "| a1 | b1 | c1 | a2 | b2 | c2 |",
-            "+----+----+----+----+----+----+",
-            "| 3  | 5  | 9  | 20 | 5  | 80 |",
-            "| 2  | 5  | 8  | 20 | 5  | 80 |",
-            "| 0  | 4  | 6  | 10 | 4  | 70 |",
-            "| 1  | 4  | 7  | 10 | 4  | 70 |",
-            "+----+----+----+----+----+----+",
+    #[test]
+    fn mark_join_rejects_unsupported_null_aware_joins() -> Result<()> {
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+        let on = vec![
+            (
+                Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+            ),
+            (
+                Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("a2", &right.schema())?) as _,
+            ),
         ];
 
-        // Inner join output is expected to preserve both inputs order
-        assert_batches_eq!(expected, &batches);
+        let err = join(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::RightMark,
+            false,
+        )
+        .unwrap_err();
+        assert!(matches!(err, DataFusionError::NotImplemented(_)), "{err}");
+        join(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::RightMark,
+            true,
+        )?;
+
+        let err = HashJoinExec::try_new(
+            left,
+            right,
+            on[..1].to_vec(),
+            None,
+            &JoinType::LeftMark,
+            None,
+            PartitionMode::Partitioned,
+            false,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Partitioned mode"), "{err}");
 
         Ok(())
     }
 
-    /// Test where the left has 1 part, the right has 2 parts => 2 parts
     #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_inner_one_two_parts_right(batch_size: usize) -> Result<()> {
+    async fn join_right_one(batch_size: usize) -> Result<()> {
         let task_ctx = prepare_task_ctx(batch_size);
         let left = build_table(
             ("a1", &vec![1, 2, 3]),
-            ("b1", &vec![4, 5, 5]), // this has a repetition
+            ("b1", &vec![4, 5, 7]),
             ("c1", &vec![7, 8, 9]),
         );
-
-        let batch1 = build_table_i32(
-            ("a2", &vec![10, 20]),
-            ("b1", &vec![4, 6]),
-            ("c2", &vec![70, 80]),
-        );
-        let batch2 =
-            build_table_i32(("a2", &vec![30]), ("b1", &vec![5]), ("c2", &vec![90]));
-        let schema = batch1.schema();
-        let right = Arc::new(
-            MemoryExec::try_new(&[vec![batch1], vec![batch2]], schema, None).unwrap(),
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]), // 6 does not exist on the left
+            ("c2", &vec![70, 80, 90]),
         );
-
         let on = vec![(
             Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
             Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
-        let join = join(left, right, on, &JoinType::Inner, false)?;
+        let (columns, batches) =
+            join_collect(left, right, on, &JoinType::Right, false, task_ctx).await?;
 
-        let columns = columns(&join.schema());
         assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
 
-        // first part
-        let stream = join.execute(0, task_ctx.clone())?;
-        let batches = common::collect(stream).await?;
-
-        // expected joined records = 1 (first right batch)
-        // and additional empty batch for non-joined 20-6-80
-        let mut expected_batch_count = div_ceil(1, batch_size);
-        if batch_size == 1 {
-            expected_batch_count += 1;
-        }
-        assert_eq!(batches.len(), expected_batch_count);
-
         let expected = [
             "+----+----+----+----+----+----+",
             "| a1 | b1 | c1 | a2 | b1 | c2 |",
             "+----+----+----+----+----+----+",
+            "|    |    |    | 30 | 6  | 90 |",
             "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
             "+----+----+----+----+----+----+",
         ];
 
-        // Inner join output is expected to preserve both inputs order
-        assert_batches_eq!(expected, &batches);
-
-        // second part
-        let stream = join.execute(1, task_ctx.clone())?;
-        let batches = common::collect(stream).await?;
-
-        // expected joined records = 2 (second right batch)
-        let expected_batch_count = div_ceil(2, batch_size);
-        assert_eq!(batches.len(), expected_batch_count);
-
-        let expected = [
-            "+----+----+----+----+----+----+",
-            "| a1 | b1 | c1 | a2 | b1 | c2 |",
-            "+----+----+----+----+----+----+",
-            "| 2  | 5  | 8  | 30 | 5  | 90 |",
-            "| 3  | 5  | 9  | 30 | 5  | 90 |",
-            "+----+----+----+----+----+----+",
-        ];
-
-        // Inner join output is expected to preserve both inputs order
-        assert_batches_eq!(expected, &batches);
+        assert_batches_sorted_eq!(expected, &batches);
 
         Ok(())
     }
 
-    fn build_table_two_batches(
-        a: (&str, &Vec<i32>),
-        b: (&str, &Vec<i32>),
-        c: (&str, &Vec<i32>),
-    ) -> Arc<dyn ExecutionPlan> {
-        let batch = build_table_i32(a, b, c);
-        let schema = batch.schema();
-        Arc::new(
-            MemoryExec::try_new(&[vec![batch.clone(), batch]], schema, None).unwrap(),
-        )
-    }
-
     #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_left_multi_batch(batch_size: usize) {
+    async fn partitioned_join_right_one(batch_size: usize) -> Result<()> {
         let task_ctx = prepare_task_ctx(batch_size);
         let left = build_table(
             ("a1", &vec![1, 2, 3]),
-            ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
+            ("b1", &vec![4, 5, 7]),
             ("c1", &vec![7, 8, 9]),
         );
-        let right = build_table_two_batches(
+        let right = build_table(
             ("a2", &vec![10, 20, 30]),
-            ("b1", &vec![4, 5, 6]),
+            ("b1", &vec![4, 5, 6]), // 6 does not exist on the left
             ("c2", &vec![70, 80, 90]),
         );
         let on = vec![(
-            Arc::new(Column::new_with_schema("b1", &left.schema()).unwrap()) as _,
-            Arc::new(Column::new_with_schema("b1", &right.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
-        let join = join(left, right, on, &JoinType::Left, false).unwrap();
+        let (columns, batches) =
+            partitioned_join_collect(left, right, on, &JoinType::Right, false, task_ctx)
+                .await?;
 
-        let columns = columns(&join.schema());
         assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
 
-        let stream = join.execute(0, task_ctx).unwrap();
-        let batches = common::collect(stream).await.unwrap();
-
         let expected = [
             "+----+----+----+----+----+----+",
             "| a1 | b1 | c1 | a2 | b1 | c2 |",
             "+----+----+----+----+----+----+",
+            "|    |    |    | 30 | 6  | 90 |",
             "| 1  | 4  | 7  | 10 | 4  | 70 |",
-            "| 1  | 4  | 7  | 10 | 4  | 70 |",
-            "| 2  | 5  | 8  | 20 | 5  | 80 |",
             "| 2  | 5  | 8  | 20 | 5  | 80 |",
-            "| 3  | 7  | 9  |    |    |    |",
             "+----+----+----+----+----+----+",
         ];
 
         assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
     }
 
     #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_full_multi_batch(batch_size: usize) {
+    async fn join_full_one(batch_size: usize) -> Result<()> {
         let task_ctx = prepare_task_ctx(batch_size);
         let left = build_table(
             ("a1", &vec![1, 2, 3]),
             ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
             ("c1", &vec![7, 8, 9]),
         );
-        // create two identical batches for the right side
-        let right = build_table_two_batches(
+        let right = build_table(
             ("a2", &vec![10, 20, 30]),
             ("b2", &vec![4, 5, 6]),
             ("c2", &vec![70, 80, 90]),
@@ -2175,242 +9965,269 @@ mod tests {
             Arc::new(Column::new_with_schema("b2", &right.schema()).unwrap()) as _,
         )];
 
-        let join = join(left, right, on, &JoinType::Full, false).unwrap();
+        let join = join(left, right, on, &JoinType::Full, false)?;
 
         let columns = columns(&join.schema());
         assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b2", "c2"]);
 
-        let stream = join.execute(0, task_ctx).unwrap();
-        let batches = common::collect(stream).await.unwrap();
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
 
         let expected = [
             "+----+----+----+----+----+----+",
             "| a1 | b1 | c1 | a2 | b2 | c2 |",
             "+----+----+----+----+----+----+",
             "|    |    |    | 30 | 6  | 90 |",
-            "|    |    |    | 30 | 6  | 90 |",
-            "| 1  | 4  | 7  | 10 | 4  | 70 |",
             "| 1  | 4  | 7  | 10 | 4  | 70 |",
             "| 2  | 5  | 8  | 20 | 5  | 80 |",
-            "| 2  | 5  | 8  | 20 | 5  | 80 |",
             "| 3  | 7  | 9  |    |    |    |",
             "+----+----+----+----+----+----+",
         ];
-
         assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
     }
 
-    #[apply(batch_sizes)]
-    #[tokio::test]
-    async fn join_left_empty_right(batch_size: usize) {
-        let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_table(
-            ("a1", &vec![1, 2, 3]),
-            ("b1", &vec![4, 5, 7]),
-            ("c1", &vec![7, 8, 9]),
+    #[test]
+    fn join_with_hash_collision() -> Result<()> {
+        let mut hashmap_left = RawTable::with_capacity(2);
+        let left = build_table_i32(
+            ("a", &vec![10, 20]),
+            ("x", &vec![100, 200]),
+            ("y", &vec![200, 300]),
         );
-        let right = build_table_i32(("a2", &vec![]), ("b1", &vec![]), ("c2", &vec![]));
-        let on = vec![(
-            Arc::new(Column::new_with_schema("b1", &left.schema()).unwrap()) as _,
-            Arc::new(Column::new_with_schema("b1", &right.schema()).unwrap()) as _,
-        )];
-        let schema = right.schema();
-        let right = Arc::new(MemoryExec::try_new(&[vec![right]], schema, None).unwrap());
-        let join = join(left, right, on, &JoinType::Left, false).unwrap();
-
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
 
-        let stream = join.execute(0, task_ctx).unwrap();
-        let batches = common::collect(stream).await.unwrap();
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+        let hashes_buff = &mut vec![0; left.num_rows()];
+        let hashes =
+            create_hashes(&[left.columns()[0].clone()], &random_state, hashes_buff)?;
 
-        let expected = [
-            "+----+----+----+----+----+----+",
-            "| a1 | b1 | c1 | a2 | b1 | c2 |",
-            "+----+----+----+----+----+----+",
-            "| 1  | 4  | 7  |    |    |    |",
-            "| 2  | 5  | 8  |    |    |    |",
-            "| 3  | 7  | 9  |    |    |    |",
-            "+----+----+----+----+----+----+",
-        ];
+        // Create hash collisions (same hashes)
+        hashmap_left.insert(hashes[0], (hashes[0], 1), |(h, _)| *h);
+        hashmap_left.insert(hashes[1], (hashes[1], 1), |(h, _)| *h);
 
-        assert_batches_sorted_eq!(expected, &batches);
-    }
+        let next = vec![2, 0];
 
-    #[apply(batch_sizes)]
-    #[tokio::test]
-    async fn join_full_empty_right(batch_size: usize) {
-        let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_table(
-            ("a1", &vec![1, 2, 3]),
-            ("b1", &vec![4, 5, 7]),
-            ("c1", &vec![7, 8, 9]),
+        let right = build_table_i32(
+            ("a", &vec![10, 20]),
+            ("b", &vec![0, 0]),
+            ("c", &vec![30, 40]),
         );
-        let right = build_table_i32(("a2", &vec![]), ("b2", &vec![]), ("c2", &vec![]));
-        let on = vec![(
-            Arc::new(Column::new_with_schema("b1", &left.schema()).unwrap()) as _,
-            Arc::new(Column::new_with_schema("b2", &right.schema()).unwrap()) as _,
-        )];
-        let schema = right.schema();
-        let right = Arc::new(MemoryExec::try_new(&[vec![right]], schema, None).unwrap());
-        let join = join(left, right, on, &JoinType::Full, false).unwrap();
 
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b2", "c2"]);
+        // Join key column for both join sides
+        let key_column: PhysicalExprRef = Arc::new(Column::new("a", 0)) as _;
 
-        let stream = join.execute(0, task_ctx).unwrap();
-        let batches = common::collect(stream).await.unwrap();
+        let join_hash_map = JoinHashMap::new(hashmap_left, next);
 
-        let expected = [
-            "+----+----+----+----+----+----+",
-            "| a1 | b1 | c1 | a2 | b2 | c2 |",
-            "+----+----+----+----+----+----+",
-            "| 1  | 4  | 7  |    |    |    |",
-            "| 2  | 5  | 8  |    |    |    |",
-            "| 3  | 7  | 9  |    |    |    |",
-            "+----+----+----+----+----+----+",
-        ];
+        let right_keys_values =
+            key_column.evaluate(&right)?.into_array(right.num_rows())?;
+        let mut hashes_buffer = vec![0; right.num_rows()];
+        create_hashes(&[right_keys_values], &random_state, &mut hashes_buffer)?;
 
-        assert_batches_sorted_eq!(expected, &batches);
-    }
+        let (l, r, _) = lookup_join_hashmap(
+            &join_hash_map,
+            &left,
+            &right,
+            &[key_column.clone()],
+            &[key_column],
+            false,
+            &hashes_buffer,
+            8192,
+            (0, None),
+        )?;
 
-    #[apply(batch_sizes)]
-    #[tokio::test]
-    async fn join_left_one(batch_size: usize) -> Result<()> {
-        let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_table(
-            ("a1", &vec![1, 2, 3]),
-            ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
-            ("c1", &vec![7, 8, 9]),
-        );
-        let right = build_table(
-            ("a2", &vec![10, 20, 30]),
-            ("b1", &vec![4, 5, 6]),
-            ("c2", &vec![70, 80, 90]),
-        );
-        let on = vec![(
-            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
-            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
-        )];
+        let mut left_ids = UInt64Builder::with_capacity(0);
+        left_ids.append_value(0);
+        left_ids.append_value(1);
 
-        let (columns, batches) = join_collect(
-            left.clone(),
-            right.clone(),
-            on.clone(),
-            &JoinType::Left,
-            false,
-            task_ctx,
-        )
-        .await?;
-        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
+        let mut right_ids = UInt32Builder::with_capacity(0);
+        right_ids.append_value(0);
+        right_ids.append_value(1);
 
-        let expected = [
-            "+----+----+----+----+----+----+",
-            "| a1 | b1 | c1 | a2 | b1 | c2 |",
-            "+----+----+----+----+----+----+",
-            "| 1  | 4  | 7  | 10 | 4  | 70 |",
-            "| 2  | 5  | 8  | 20 | 5  | 80 |",
-            "| 3  | 7  | 9  |    |    |    |",
-            "+----+----+----+----+----+----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+        assert_eq!(left_ids.finish(), l);
+
+        assert_eq!(right_ids.finish(), r);
+
+        Ok(())
+    }
+
+    #[test]
+    fn lookup_with_indexed_chains() -> Result<()> {
+        // 200 build rows with 20 distinct keys and nulls, all in a single chain
+        let left_keys = (0..200)
+            .map(|i| (i % 25 < 20).then_some(i % 25))
+            .collect::<Int32Array>();
+        let left = RecordBatch::try_from_iter([("a", Arc::new(left_keys) as ArrayRef)])?;
+        let right_keys = (0..40)
+            .map(|i| (i % 8 != 7).then_some(i))
+            .collect::<Int32Array>();
+        let right =
+            RecordBatch::try_from_iter([("a", Arc::new(right_keys) as ArrayRef)])?;
+        let key_column: PhysicalExprRef = Arc::new(Column::new("a", 0)) as _;
+
+        let lookup = |max_chain_length: Option<usize>,
+                      null_equals_null: bool,
+                      limit: usize|
+         -> Result<(Vec<(u64, u32)>, usize)> {
+            let mut hashmap = JoinHashMap::with_capacity(left.num_rows());
+            update_hash_from_values(&mut hashmap, &[42; 200], 0, 0, true);
+            if let Some(max_chain_length) = max_chain_length {
+                let keys = [left.column(0).clone()];
+                assert_eq!(hashmap.index_long_chains(&keys, max_chain_length)?, 1);
+            }
+            let key_comparisons = metrics::Count::new();
+            let mut pairs = vec![];
+            let mut offset = (0, None);
+            loop {
+                let (l, r, next_offset) = lookup_join_hashmap_with_key_comparisons(
+                    &hashmap,
+                    &left,
+                    &right,
+                    &[key_column.clone()],
+                    &[key_column.clone()],
+                    null_equals_null,
+                    &[42; 40],
+                    limit,
+                    offset,
+                    Some(&key_comparisons),
+                )?;
+                pairs.extend(l.values().iter().copied().zip(r.values().iter().copied()));
+                match next_offset {
+                    Some(next_offset) => offset = next_offset,
+                    None => break,
+                }
+            }
+            Ok((pairs, key_comparisons.value()))
+        };
+
+        for null_equals_null in [false, true] {
+            for limit in [8192, 7, 1] {
+                let (expected, linear) = lookup(None, null_equals_null, limit)?;
+                let (pairs, indexed) = lookup(Some(16), null_equals_null, limit)?;
+                // Matches and their order don't change
+                assert_eq!(pairs, expected);
+                // A probe row is compared to the distinct keys of the chain
+                // by a binary search instead of to every row of the chain.
+                // Resuming a probe row repeats its search.
+                if limit == 8192 {
+                    assert!(indexed * 10 < linear, "{indexed} vs {linear}");
+                } else {
+                    assert!(indexed < linear, "{indexed} vs {linear}");
+                }
+            }
+        }
+        let (pairs, _) = lookup(None, false, 8192)?;
+        assert_eq!(pairs.len(), 18 * 8);
+        // Chains up to the limit are not indexed
+        let mut hashmap = JoinHashMap::with_capacity(left.num_rows());
+        update_hash_from_values(&mut hashmap, &[42; 200], 0, 0, true);
+        assert_eq!(
+            hashmap.index_long_chains(&[left.column(0).clone()], 200)?,
+            0
+        );
 
         Ok(())
     }
 
-    #[apply(batch_sizes)]
     #[tokio::test]
-    async fn partitioned_join_left_one(batch_size: usize) -> Result<()> {
-        let task_ctx = prepare_task_ctx(batch_size);
+    async fn join_with_duplicated_column_names() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
         let left = build_table(
-            ("a1", &vec![1, 2, 3]),
-            ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
-            ("c1", &vec![7, 8, 9]),
+            ("a", &vec![1, 2, 3]),
+            ("b", &vec![4, 5, 7]),
+            ("c", &vec![7, 8, 9]),
         );
         let right = build_table(
-            ("a2", &vec![10, 20, 30]),
-            ("b1", &vec![4, 5, 6]),
-            ("c2", &vec![70, 80, 90]),
+            ("a", &vec![10, 20, 30]),
+            ("b", &vec![1, 2, 7]),
+            ("c", &vec![70, 80, 90]),
         );
         let on = vec![(
-            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
-            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+            // join on a=b so there are duplicate column names on unjoined columns
+            Arc::new(Column::new_with_schema("a", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b", &right.schema()).unwrap()) as _,
         )];
 
-        let (columns, batches) = partitioned_join_collect(
-            left.clone(),
-            right.clone(),
-            on.clone(),
-            &JoinType::Left,
-            false,
-            task_ctx,
-        )
-        .await?;
-        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
+        let join = join(left, right, on, &JoinType::Inner, false)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
 
         let expected = [
-            "+----+----+----+----+----+----+",
-            "| a1 | b1 | c1 | a2 | b1 | c2 |",
-            "+----+----+----+----+----+----+",
-            "| 1  | 4  | 7  | 10 | 4  | 70 |",
-            "| 2  | 5  | 8  | 20 | 5  | 80 |",
-            "| 3  | 7  | 9  |    |    |    |",
-            "+----+----+----+----+----+----+",
+            "+---+---+---+----+---+----+",
+            "| a | b | c | a  | b | c  |",
+            "+---+---+---+----+---+----+",
+            "| 1 | 4 | 7 | 10 | 1 | 70 |",
+            "| 2 | 5 | 8 | 20 | 2 | 80 |",
+            "+---+---+---+----+---+----+",
         ];
         assert_batches_sorted_eq!(expected, &batches);
 
         Ok(())
     }
 
-    fn build_semi_anti_left_table() -> Arc<dyn ExecutionPlan> {
-        // just two line match
-        // b1 = 10
-        build_table(
-            ("a1", &vec![1, 3, 5, 7, 9, 11, 13]),
-            ("b1", &vec![1, 3, 5, 7, 8, 8, 10]),
-            ("c1", &vec![10, 30, 50, 70, 90, 110, 130]),
-        )
-    }
+    fn prepare_join_filter() -> JoinFilter {
+        let column_indices = vec![
+            ColumnIndex {
+                index: 2,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 2,
+                side: JoinSide::Right,
+            },
+        ];
+        let intermediate_schema = Schema::new(vec![
+            Field::new("c", DataType::Int32, true),
+            Field::new("c", DataType::Int32, true),
+        ]);
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("c", 0)),
+            Operator::Gt,
+            Arc::new(Column::new("c", 1)),
+        )) as Arc<dyn PhysicalExpr>;
 
-    fn build_semi_anti_right_table() -> Arc<dyn ExecutionPlan> {
-        // just two line match
-        // b2 = 10
-        build_table(
-            ("a2", &vec![8, 12, 6, 2, 10, 4]),
-            ("b2", &vec![8, 10, 6, 2, 10, 4]),
-            ("c2", &vec![20, 40, 60, 80, 100, 120]),
-        )
+        JoinFilter::new(filter_expression, column_indices, intermediate_schema)
     }
 
     #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_left_semi(batch_size: usize) -> Result<()> {
+    async fn join_inner_with_filter(batch_size: usize) -> Result<()> {
         let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_semi_anti_left_table();
-        let right = build_semi_anti_right_table();
-        // left_table left semi join right_table on left_table.b1 = right_table.b2
+        let left = build_table(
+            ("a", &vec![0, 1, 2, 2]),
+            ("b", &vec![4, 5, 7, 8]),
+            ("c", &vec![7, 8, 9, 1]),
+        );
+        let right = build_table(
+            ("a", &vec![10, 20, 30, 40]),
+            ("b", &vec![2, 2, 3, 4]),
+            ("c", &vec![7, 5, 6, 4]),
+        );
         let on = vec![(
-            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
-            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+            Arc::new(Column::new_with_schema("a", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b", &right.schema()).unwrap()) as _,
         )];
+        let filter = prepare_join_filter();
 
-        let join = join(left, right, on, &JoinType::LeftSemi, false)?;
+        let join = join_with_filter(left, right, on, filter, &JoinType::Inner, false)?;
 
         let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a1", "b1", "c1"]);
+        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
 
         let stream = join.execute(0, task_ctx)?;
         let batches = common::collect(stream).await?;
 
-        // ignore the order
         let expected = [
-            "+----+----+-----+",
-            "| a1 | b1 | c1  |",
-            "+----+----+-----+",
-            "| 11 | 8  | 110 |",
-            "| 13 | 10 | 130 |",
-            "| 9  | 8  | 90  |",
-            "+----+----+-----+",
+            "+---+---+---+----+---+---+",
+            "| a | b | c | a  | b | c |",
+            "+---+---+---+----+---+---+",
+            "| 2 | 7 | 9 | 10 | 2 | 7 |",
+            "| 2 | 7 | 9 | 20 | 2 | 5 |",
+            "+---+---+---+----+---+---+",
         ];
         assert_batches_sorted_eq!(expected, &batches);
 
@@ -2419,85 +10236,85 @@ mod tests {
 
     #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_left_semi_with_filter(batch_size: usize) -> Result<()> {
+    async fn join_left_with_filter(batch_size: usize) -> Result<()> {
         let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_semi_anti_left_table();
-        let right = build_semi_anti_right_table();
-
-        // left_table left semi join right_table on left_table.b1 = right_table.b2 and right_table.a2 != 10
+        let left = build_table(
+            ("a", &vec![0, 1, 2, 2]),
+            ("b", &vec![4, 5, 7, 8]),
+            ("c", &vec![7, 8, 9, 1]),
+        );
+        let right = build_table(
+            ("a", &vec![10, 20, 30, 40]),
+            ("b", &vec![2, 2, 3, 4]),
+            ("c", &vec![7, 5, 6, 4]),
+        );
         let on = vec![(
-            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
-            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+            Arc::new(Column::new_with_schema("a", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b", &right.schema()).unwrap()) as _,
         )];
+        let filter = prepare_join_filter();
 
-        let column_indices = vec![ColumnIndex {
-            index: 0,
-            side: JoinSide::Right,
-        }];
-        let intermediate_schema =
-            Schema::new(vec![Field::new("x", DataType::Int32, true)]);
-
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("x", 0)),
-            Operator::NotEq,
-            Arc::new(Literal::new(ScalarValue::Int32(Some(10)))),
-        )) as Arc<dyn PhysicalExpr>;
-
-        let filter = JoinFilter::new(
-            filter_expression,
-            column_indices.clone(),
-            intermediate_schema.clone(),
-        );
-
-        let join = join_with_filter(
-            left.clone(),
-            right.clone(),
-            on.clone(),
-            filter,
-            &JoinType::LeftSemi,
-            false,
-        )?;
+        let join = join_with_filter(left, right, on, filter, &JoinType::Left, false)?;
 
-        let columns_header = columns(&join.schema());
-        assert_eq!(columns_header.clone(), vec!["a1", "b1", "c1"]);
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
 
-        let stream = join.execute(0, task_ctx.clone())?;
+        let stream = join.execute(0, task_ctx)?;
         let batches = common::collect(stream).await?;
 
         let expected = [
-            "+----+----+-----+",
-            "| a1 | b1 | c1  |",
-            "+----+----+-----+",
-            "| 11 | 8  | 110 |",
-            "| 13 | 10 | 130 |",
-            "| 9  | 8  | 90  |",
-            "+----+----+-----+",
+            "+---+---+---+----+---+---+",
+            "| a | b | c | a  | b | c |",
+            "+---+---+---+----+---+---+",
+            "| 0 | 4 | 7 |    |   |   |",
+            "| 1 | 5 | 8 |    |   |   |",
+            "| 2 | 7 | 9 | 10 | 2 | 7 |",
+            "| 2 | 7 | 9 | 20 | 2 | 5 |",
+            "| 2 | 8 | 1 |    |   |   |",
+            "+---+---+---+----+---+---+",
         ];
         assert_batches_sorted_eq!(expected, &batches);
 
-        // left_table left semi join right_table on left_table.b1 = right_table.b2 and right_table.a2 > 10
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("x", 0)),
-            Operator::Gt,
-            Arc::new(Literal::new(ScalarValue::Int32(Some(10)))),
-        )) as Arc<dyn PhysicalExpr>;
-        let filter =
-            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
+        Ok(())
+    }
 
-        let join = join_with_filter(left, right, on, filter, &JoinType::LeftSemi, false)?;
+    #[apply(batch_sizes)]
+    #[tokio::test]
+    async fn join_right_with_filter(batch_size: usize) -> Result<()> {
+        let task_ctx = prepare_task_ctx(batch_size);
+        let left = build_table(
+            ("a", &vec![0, 1, 2, 2]),
+            ("b", &vec![4, 5, 7, 8]),
+            ("c", &vec![7, 8, 9, 1]),
+        );
+        let right = build_table(
+            ("a", &vec![10, 20, 30, 40]),
+            ("b", &vec![2, 2, 3, 4]),
+            ("c", &vec![7, 5, 6, 4]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("a", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b", &right.schema()).unwrap()) as _,
+        )];
+        let filter = prepare_join_filter();
 
-        let columns_header = columns(&join.schema());
-        assert_eq!(columns_header, vec!["a1", "b1", "c1"]);
+        let join = join_with_filter(left, right, on, filter, &JoinType::Right, false)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
 
         let stream = join.execute(0, task_ctx)?;
         let batches = common::collect(stream).await?;
 
         let expected = [
-            "+----+----+-----+",
-            "| a1 | b1 | c1  |",
-            "+----+----+-----+",
-            "| 13 | 10 | 130 |",
-            "+----+----+-----+",
+            "+---+---+---+----+---+---+",
+            "| a | b | c | a  | b | c |",
+            "+---+---+---+----+---+---+",
+            "|   |   |   | 30 | 3 | 6 |",
+            "|   |   |   | 40 | 4 | 4 |",
+            "| 2 | 7 | 9 | 10 | 2 | 7 |",
+            "| 2 | 7 | 9 | 20 | 2 | 5 |",
+            "+---+---+---+----+---+---+",
         ];
         assert_batches_sorted_eq!(expected, &batches);
 
@@ -2506,764 +10323,1434 @@ mod tests {
 
     #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_right_semi(batch_size: usize) -> Result<()> {
+    async fn join_full_with_filter(batch_size: usize) -> Result<()> {
         let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_semi_anti_left_table();
-        let right = build_semi_anti_right_table();
-
-        // left_table right semi join right_table on left_table.b1 = right_table.b2
+        let left = build_table(
+            ("a", &vec![0, 1, 2, 2]),
+            ("b", &vec![4, 5, 7, 8]),
+            ("c", &vec![7, 8, 9, 1]),
+        );
+        let right = build_table(
+            ("a", &vec![10, 20, 30, 40]),
+            ("b", &vec![2, 2, 3, 4]),
+            ("c", &vec![7, 5, 6, 4]),
+        );
         let on = vec![(
-            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
-            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+            Arc::new(Column::new_with_schema("a", &left.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b", &right.schema()).unwrap()) as _,
         )];
+        let filter = prepare_join_filter();
 
-        let join = join(left, right, on, &JoinType::RightSemi, false)?;
+        let join = join_with_filter(left, right, on, filter, &JoinType::Full, false)?;
 
         let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a2", "b2", "c2"]);
+        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
 
         let stream = join.execute(0, task_ctx)?;
         let batches = common::collect(stream).await?;
 
         let expected = [
-            "+----+----+-----+",
-            "| a2 | b2 | c2  |",
-            "+----+----+-----+",
-            "| 8  | 8  | 20  |",
-            "| 12 | 10 | 40  |",
-            "| 10 | 10 | 100 |",
-            "+----+----+-----+",
+            "+---+---+---+----+---+---+",
+            "| a | b | c | a  | b | c |",
+            "+---+---+---+----+---+---+",
+            "|   |   |   | 30 | 3 | 6 |",
+            "|   |   |   | 40 | 4 | 4 |",
+            "| 2 | 7 | 9 | 10 | 2 | 7 |",
+            "| 2 | 7 | 9 | 20 | 2 | 5 |",
+            "| 0 | 4 | 7 |    |   |   |",
+            "| 1 | 5 | 8 |    |   |   |",
+            "| 2 | 8 | 1 |    |   |   |",
+            "+---+---+---+----+---+---+",
         ];
-
-        // RightSemi join output is expected to preserve right input order
-        assert_batches_eq!(expected, &batches);
+        assert_batches_sorted_eq!(expected, &batches);
 
         Ok(())
     }
 
-    #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_right_semi_with_filter(batch_size: usize) -> Result<()> {
-        let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_semi_anti_left_table();
-        let right = build_semi_anti_right_table();
-
-        // left_table right semi join right_table on left_table.b1 = right_table.b2 on left_table.a1!=9
+    async fn join_with_max_output_rows() -> Result<()> {
+        let task_ctx = prepare_task_ctx(2);
+        // Every left row matches every right row
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4]),
+            ("b1", &vec![1, 1, 1, 1]),
+            ("c1", &vec![7, 8, 9, 10]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30, 40]),
+            ("b1", &vec![1, 1, 1, 1]),
+            ("c2", &vec![70, 80, 90, 100]),
+        );
         let on = vec![(
             Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
-            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
-        let column_indices = vec![ColumnIndex {
-            index: 0,
-            side: JoinSide::Left,
-        }];
-        let intermediate_schema =
-            Schema::new(vec![Field::new("x", DataType::Int32, true)]);
-
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("x", 0)),
-            Operator::NotEq,
-            Arc::new(Literal::new(ScalarValue::Int32(Some(9)))),
-        )) as Arc<dyn PhysicalExpr>;
-
-        let filter = JoinFilter::new(
-            filter_expression,
-            column_indices.clone(),
-            intermediate_schema.clone(),
-        );
-
-        let join = join_with_filter(
+        let within_limit = join(
             left.clone(),
             right.clone(),
             on.clone(),
-            filter,
-            &JoinType::RightSemi,
+            &JoinType::Inner,
             false,
-        )?;
-
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a2", "b2", "c2"]);
-
-        let stream = join.execute(0, task_ctx.clone())?;
-        let batches = common::collect(stream).await?;
-
-        let expected = [
-            "+----+----+-----+",
-            "| a2 | b2 | c2  |",
-            "+----+----+-----+",
-            "| 8  | 8  | 20  |",
-            "| 12 | 10 | 40  |",
-            "| 10 | 10 | 100 |",
-            "+----+----+-----+",
-        ];
-
-        // RightSemi join output is expected to preserve right input order
-        assert_batches_eq!(expected, &batches);
-
-        // left_table right semi join right_table on left_table.b1 = right_table.b2 on left_table.a1!=9
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("x", 0)),
-            Operator::Gt,
-            Arc::new(Literal::new(ScalarValue::Int32(Some(11)))),
-        )) as Arc<dyn PhysicalExpr>;
-
-        let filter =
-            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
+        )?
+        .with_max_output_rows(16);
+        assert_eq!(within_limit.max_output_rows(), Some(16));
+        let batches = common::collect(within_limit.execute(0, task_ctx.clone())?).await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 16);
+        // The rows of an earlier execution don't count towards the limit
+        let batches = common::collect(within_limit.execute(0, task_ctx.clone())?).await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 16);
+
+        let over_limit =
+            join(left, right, on, &JoinType::Inner, false)?.with_max_output_rows(3);
+        let err = common::collect(over_limit.execute(0, task_ctx)?)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DataFusionError::ResourcesExhausted(_)));
+        assert_contains!(
+            err.to_string(),
+            "Join produced more than the maximum of 3 output rows"
+        );
 
-        let join =
-            join_with_filter(left, right, on, filter, &JoinType::RightSemi, false)?;
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+        Ok(())
+    }
 
-        let expected = [
-            "+----+----+-----+",
-            "| a2 | b2 | c2  |",
-            "+----+----+-----+",
-            "| 12 | 10 | 40  |",
-            "| 10 | 10 | 100 |",
-            "+----+----+-----+",
-        ];
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn join_with_max_concurrency() -> Result<()> {
+        let (left_batch, right_batch) = build_sides_record_batches(300, (4, 5))?;
+        let hash_partitioned =
+            |batch: &RecordBatch, key: &str| -> Result<Arc<dyn ExecutionPlan>> {
+                let schema = batch.schema();
+                let input = Arc::new(MemoryExec::try_new(
+                    &[split_record_batches(batch, 10)?],
+                    schema.clone(),
+                    None,
+                )?);
+                Ok(Arc::new(RepartitionExec::try_new(
+                    input,
+                    Partitioning::Hash(
+                        vec![Arc::new(Column::new_with_schema(key, &schema)?)],
+                        4,
+                    ),
+                )?))
+            };
+        // A repartition can only be executed once, so every join gets inputs
+        // of its own
+        let join = |max_concurrency: Option<usize>| -> Result<Arc<HashJoinExec>> {
+            let left = hash_partitioned(&left_batch, "lc1")?;
+            let right = hash_partitioned(&right_batch, "rc1")?;
+            let on = vec![(
+                Arc::new(Column::new_with_schema("lc1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("rc1", &right.schema())?) as _,
+            )];
+            let join = HashJoinExec::try_new(
+                left,
+                right,
+                on,
+                None,
+                &JoinType::Full,
+                None,
+                PartitionMode::Partitioned,
+                false,
+            )?;
+            Ok(Arc::new(match max_concurrency {
+                Some(max_concurrency) => join.with_max_concurrency(max_concurrency)?,
+                None => join,
+            }))
+        };
+        // Executes all partitions at once, each in a task of its own
+        let run = |join: Arc<HashJoinExec>| async move {
+            let tasks = (0..4)
+                .map(|partition| {
+                    let join = join.clone();
+                    tokio::spawn(async move {
+                        let context = Arc::new(TaskContext::default());
+                        common::collect(join.execute(partition, context)?).await
+                    })
+                })
+                .collect::<Vec<_>>();
+            let mut batches = vec![];
+            for task in tasks {
+                batches.extend(task.await.unwrap()?);
+            }
+            Result::<_>::Ok(batches)
+        };
 
-        // RightSemi join output is expected to preserve right input order
-        assert_batches_eq!(expected, &batches);
+        let limited = join(Some(2))?;
+        assert_eq!(limited.max_concurrency(), Some(2));
+        assert_eq!(
+            limited.properties().output_partitioning().partition_count(),
+            4
+        );
+        assert_contains!(
+            displayable(limited.as_ref()).one_line().to_string(),
+            ", max_concurrency=2"
+        );
+        let limited_batches = run(limited.clone()).await?;
+        let expected_batches = run(join(None)?).await?;
+        compare_batches(&expected_batches, &limited_batches);
+        let num_rows = |batches: &[RecordBatch]| -> usize {
+            batches.iter().map(|batch| batch.num_rows()).sum()
+        };
+        assert_eq!(num_rows(&expected_batches), num_rows(&limited_batches));
+
+        let metrics = limited.metrics().unwrap();
+        let peak = metrics
+            .sum_by_name("peak_concurrent_partitions")
+            .unwrap()
+            .as_usize();
+        assert!((1..=2).contains(&peak), "{peak}");
+        // A single gauge is registered for the execution
+        let gauges = metrics
+            .iter()
+            .filter(|metric| metric.value().name() == "peak_concurrent_partitions")
+            .count();
+        assert_eq!(gauges, 1);
+
+        let err = join(Some(0)).unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "The maximum concurrency of HashJoinExec must be positive"
+        );
 
         Ok(())
     }
 
-    #[apply(batch_sizes)]
-    #[tokio::test]
-    async fn join_left_anti(batch_size: usize) -> Result<()> {
-        let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_semi_anti_left_table();
-        let right = build_semi_anti_right_table();
-        // left_table left anti join right_table on left_table.b1 = right_table.b2
+    #[test]
+    fn partitioned_join_output_partitioning_on_keys() -> Result<()> {
+        let hash_partitioned = |batch: RecordBatch| -> Result<Arc<dyn ExecutionPlan>> {
+            let schema = batch.schema();
+            let input = Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None)?);
+            Ok(Arc::new(RepartitionExec::try_new(
+                input,
+                Partitioning::Hash(vec![Arc::new(Column::new("b1", 1))], 4),
+            )?))
+        };
+        let left = build_table_i32(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 6]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table_i32(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 7]),
+            ("c2", &vec![70, 80, 90]),
+        );
         let on = vec![(
             Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
-            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
-        let join = join(left, right, on, &JoinType::LeftAnti, false)?;
-
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a1", "b1", "c1"]);
-
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
-
-        let expected = [
-            "+----+----+----+",
-            "| a1 | b1 | c1 |",
-            "+----+----+----+",
-            "| 1  | 1  | 10 |",
-            "| 3  | 3  | 30 |",
-            "| 5  | 5  | 50 |",
-            "| 7  | 7  | 70 |",
-            "+----+----+----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+        // Keys of the side whose rows are all preserved, at their output index:
+        let on_key =
+            |index| Partitioning::Hash(vec![Arc::new(Column::new("b1", index))], 4);
+        for (join_type, expected) in [
+            (JoinType::Inner, on_key(1)),
+            (JoinType::Left, on_key(1)),
+            (JoinType::LeftSemi, on_key(1)),
+            (JoinType::LeftAnti, on_key(1)),
+            (JoinType::Right, on_key(4)),
+            (JoinType::RightSemi, on_key(1)),
+            (JoinType::RightAnti, on_key(1)),
+            (JoinType::Full, Partitioning::UnknownPartitioning(4)),
+        ] {
+            let join = HashJoinExec::try_new(
+                hash_partitioned(left.clone())?,
+                hash_partitioned(right.clone())?,
+                on.clone(),
+                None,
+                &join_type,
+                None,
+                PartitionMode::Partitioned,
+                false,
+            )?;
+            // Unknown partitionings never compare equal, so the displayed
+            // partitionings are compared:
+            assert_eq!(
+                join.properties().output_partitioning().to_string(),
+                expected.to_string(),
+                "Unexpected output partitioning of {join_type} join"
+            );
+        }
         Ok(())
     }
 
-    #[apply(batch_sizes)]
+    #[rstest]
     #[tokio::test]
-    async fn join_left_anti_with_filter(batch_size: usize) -> Result<()> {
-        let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_semi_anti_left_table();
-        let right = build_semi_anti_right_table();
-        // left_table left anti join right_table on left_table.b1 = right_table.b2 and right_table.a2!=8
+    async fn partitioned_join_with_different_partition_counts(
+        #[values(JoinType::Inner, JoinType::Left, JoinType::Right, JoinType::Full)]
+        join_type: JoinType,
+    ) -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let (left_batch, right_batch) = build_sides_record_batches(100, (4, 5))?;
+        let memory = |batch: &RecordBatch| -> Result<Arc<dyn ExecutionPlan>> {
+            Ok(Arc::new(MemoryExec::try_new(
+                &[split_record_batches(batch, 10)?],
+                batch.schema(),
+                None,
+            )?))
+        };
         let on = vec![(
-            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
-            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+            Arc::new(Column::new_with_schema("lc1", &left_batch.schema())?) as _,
+            Arc::new(Column::new_with_schema("rc1", &right_batch.schema())?) as _,
         )];
+        let repartitioned =
+            |batch: &RecordBatch, partitioning| -> Result<Arc<dyn ExecutionPlan>> {
+                Ok(Arc::new(RepartitionExec::try_new(
+                    memory(batch)?,
+                    partitioning,
+                )?))
+            };
+        let on_key =
+            |key: &PhysicalExprRef, count| Partitioning::Hash(vec![key.clone()], count);
 
-        let column_indices = vec![ColumnIndex {
-            index: 0,
-            side: JoinSide::Right,
-        }];
-        let intermediate_schema =
-            Schema::new(vec![Field::new("x", DataType::Int32, true)]);
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("x", 0)),
-            Operator::NotEq,
-            Arc::new(Literal::new(ScalarValue::Int32(Some(8)))),
-        )) as Arc<dyn PhysicalExpr>;
+        let expected = common::collect(
+            join(
+                memory(&left_batch)?,
+                memory(&right_batch)?,
+                on.clone(),
+                &join_type,
+                false,
+            )?
+            .execute(0, task_ctx.clone())?,
+        )
+        .await?;
 
-        let filter = JoinFilter::new(
-            filter_expression,
-            column_indices.clone(),
-            intermediate_schema.clone(),
-        );
+        for (left_partitioning, right_partitioning, rehashed) in [
+            // Only the input with fewer partitions is repartitioned
+            (on_key(&on[0].0, 2), on_key(&on[0].1, 4), (true, false)),
+            (on_key(&on[0].0, 4), on_key(&on[0].1, 2), (false, true)),
+            // An input not partitioned on its keys is repartitioned as well
+            (
+                on_key(&on[0].0, 2),
+                Partitioning::RoundRobinBatch(4),
+                (true, true),
+            ),
+        ] {
+            let left = repartitioned(&left_batch, left_partitioning)?;
+            let right = repartitioned(&right_batch, right_partitioning)?;
+            let join = HashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                None,
+                &join_type,
+                None,
+                PartitionMode::Partitioned,
+                false,
+            )?;
+            // The inputs are only repartitioned when asked for:
+            assert!(Arc::ptr_eq(join.left(), &left));
+            assert!(Arc::ptr_eq(join.right(), &right));
+            let err = join.execute(0, task_ctx.clone()).err().unwrap();
+            assert_contains!(err.to_string(), "partition count mismatch");
+
+            let join = join.with_aligned_input_partitions()?;
+            // The repartitionings replace the inputs as children of the join:
+            assert_eq!(
+                (
+                    !Arc::ptr_eq(join.left(), &left),
+                    !Arc::ptr_eq(join.right(), &right)
+                ),
+                rehashed
+            );
+            let partition_count =
+                join.properties().output_partitioning().partition_count();
+            assert_eq!(partition_count, 4);
+
+            let mut batches = vec![];
+            for partition in 0..partition_count {
+                batches.extend(
+                    common::collect(join.execute(partition, task_ctx.clone())?).await?,
+                );
+            }
+            let num_rows = |batches: &[RecordBatch]| {
+                batches.iter().map(|b| b.num_rows()).sum::<usize>()
+            };
+            assert_eq!(num_rows(&batches), num_rows(&expected));
+            compare_batches(&expected, &batches);
+        }
 
-        let join = join_with_filter(
+        // Inputs with the same partition count are kept as they are
+        let left = repartitioned(&left_batch, on_key(&on[0].0, 4))?;
+        let right = repartitioned(&right_batch, Partitioning::RoundRobinBatch(4))?;
+        let aligned = HashJoinExec::try_new(
             left.clone(),
             right.clone(),
             on.clone(),
-            filter,
-            &JoinType::LeftAnti,
+            None,
+            &join_type,
+            None,
+            PartitionMode::Partitioned,
             false,
-        )?;
-
-        let columns_header = columns(&join.schema());
-        assert_eq!(columns_header, vec!["a1", "b1", "c1"]);
-
-        let stream = join.execute(0, task_ctx.clone())?;
-        let batches = common::collect(stream).await?;
-
-        let expected = [
-            "+----+----+-----+",
-            "| a1 | b1 | c1  |",
-            "+----+----+-----+",
-            "| 1  | 1  | 10  |",
-            "| 11 | 8  | 110 |",
-            "| 3  | 3  | 30  |",
-            "| 5  | 5  | 50  |",
-            "| 7  | 7  | 70  |",
-            "| 9  | 8  | 90  |",
-            "+----+----+-----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
-
-        // left_table left anti join right_table on left_table.b1 = right_table.b2 and right_table.a2 != 13
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("x", 0)),
-            Operator::NotEq,
-            Arc::new(Literal::new(ScalarValue::Int32(Some(8)))),
-        )) as Arc<dyn PhysicalExpr>;
-
-        let filter =
-            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
+        )?
+        .with_aligned_input_partitions()?;
+        assert!(Arc::ptr_eq(aligned.left(), &left));
+        assert!(Arc::ptr_eq(aligned.right(), &right));
+
+        let err = join(
+            memory(&left_batch)?,
+            memory(&right_batch)?,
+            on,
+            &join_type,
+            false,
+        )?
+        .with_aligned_input_partitions()
+        .unwrap_err();
+        assert_contains!(err.to_string(), "only supported in Partitioned mode");
 
-        let join = join_with_filter(left, right, on, filter, &JoinType::LeftAnti, false)?;
+        Ok(())
+    }
 
-        let columns_header = columns(&join.schema());
-        assert_eq!(columns_header, vec!["a1", "b1", "c1"]);
+    #[tokio::test]
+    async fn join_single_key_inputs() -> Result<()> {
+        let task_ctx = prepare_task_ctx(64);
+        // Every left row matches every right row, i.e. 200 * 200 output rows
+        let (left_batch, right_batch) = single_key_record_batches(200)?;
+        let inputs = || -> Result<(Arc<dyn ExecutionPlan>, Arc<dyn ExecutionPlan>)> {
+            let left = MemoryExec::try_new(
+                &[split_record_batches(&left_batch, 32)?],
+                left_batch.schema(),
+                None,
+            )?;
+            let right = MemoryExec::try_new(
+                &[split_record_batches(&right_batch, 32)?],
+                right_batch.schema(),
+                None,
+            )?;
+            Ok((Arc::new(left), Arc::new(right)))
+        };
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", &left_batch.schema())?) as _,
+            Arc::new(Column::new_with_schema("rc1", &right_batch.schema())?) as _,
+        )];
 
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+        // The match group is emitted in chunks of at most the batch size:
+        let (left, right) = inputs()?;
+        let unlimited = join(left, right, on.clone(), &JoinType::Inner, false)?;
+        let batches = common::collect(unlimited.execute(0, task_ctx.clone())?).await?;
+        assert!(batches.iter().all(|batch| batch.num_rows() <= 64));
+        assert_eq!(
+            batches.iter().map(|batch| batch.num_rows()).sum::<usize>(),
+            200 * 200
+        );
 
-        let expected = [
-            "+----+----+-----+",
-            "| a1 | b1 | c1  |",
-            "+----+----+-----+",
-            "| 1  | 1  | 10  |",
-            "| 11 | 8  | 110 |",
-            "| 3  | 3  | 30  |",
-            "| 5  | 5  | 50  |",
-            "| 7  | 7  | 70  |",
-            "| 9  | 8  | 90  |",
-            "+----+----+-----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+        // The output size guard stops the join early:
+        let (left, right) = inputs()?;
+        let limited =
+            join(left, right, on, &JoinType::Inner, false)?.with_max_output_rows(1000);
+        let err = common::collect(limited.execute(0, task_ctx)?)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DataFusionError::ResourcesExhausted(_)));
 
         Ok(())
     }
 
-    #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_right_anti(batch_size: usize) -> Result<()> {
-        let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_semi_anti_left_table();
-        let right = build_semi_anti_right_table();
+    async fn join_hash_table_metrics() -> Result<()> {
+        // 30 build rows over 4 distinct keys, i.e. `lc1 = x % 4`
+        let (left_batch, right_batch) = build_sides_record_batches(30, (4, 5))?;
+        let left = Arc::new(MemoryExec::try_new(
+            &[vec![left_batch.clone()]],
+            left_batch.schema(),
+            None,
+        )?);
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![right_batch.clone()]],
+            right_batch.schema(),
+            None,
+        )?);
         let on = vec![(
-            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
-            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+            Arc::new(Column::new_with_schema("lc1", &left_batch.schema())?) as _,
+            Arc::new(Column::new_with_schema("rc1", &right_batch.schema())?) as _,
         )];
 
-        let join = join(left, right, on, &JoinType::RightAnti, false)?;
+        let join = join(left, right, on, &JoinType::Inner, false)?;
+        common::collect(join.execute(0, Arc::new(TaskContext::default()))?).await?;
+
+        let metrics = join.metrics().unwrap();
+        let metric = |name: &str| metrics.sum_by_name(name).map(|v| v.as_usize());
+        // Rows of a key form a single chain, so all but the first row of each
+        // key collide:
+        assert_eq!(metric("hash_table_collisions"), Some(26));
+        assert_eq!(metric("hash_table_max_chain_length"), Some(8));
+        let buckets = metric("hash_table_buckets").unwrap();
+        assert!(buckets >= 4);
+        assert_eq!(metric("hash_table_load_factor_pct"), Some(400 / buckets));
+        assert_eq!(metric("build_distinct_hashes"), Some(4));
 
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a2", "b2", "c2"]);
+        Ok(())
+    }
 
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+    #[rstest]
+    #[tokio::test]
+    async fn join_build_distinct_hashes(
+        #[values(JoinType::Inner, JoinType::Left, JoinType::Full)] join_type: JoinType,
+    ) -> Result<()> {
+        let (left_batch, right_batch) = build_sides_record_batches(30, (4, 5))?;
+        let left = Arc::new(MemoryExec::try_new(
+            &[vec![left_batch.clone()]],
+            left_batch.schema(),
+            None,
+        )?);
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![right_batch.clone()]],
+            right_batch.schema(),
+            None,
+        )?);
+        let distinct_hashes = |left_key: &str, right_key: &str| {
+            let join = (|| -> Result<HashJoinExec> {
+                let on = vec![(
+                    Arc::new(Column::new_with_schema(left_key, &left_batch.schema())?)
+                        as _,
+                    Arc::new(Column::new_with_schema(right_key, &right_batch.schema())?)
+                        as _,
+                )];
+                join(left.clone(), right.clone(), on, &join_type, false)
+            })();
+            async move {
+                let join = join?;
+                common::collect(join.execute(0, Arc::new(TaskContext::default()))?)
+                    .await?;
+                let metrics = join.metrics().unwrap();
+                Result::<_>::Ok(
+                    metrics
+                        .sum_by_name("build_distinct_hashes")
+                        .unwrap()
+                        .as_usize(),
+                )
+            }
+        };
 
-        let expected = [
-            "+----+----+-----+",
-            "| a2 | b2 | c2  |",
-            "+----+----+-----+",
-            "| 6  | 6  | 60  |",
-            "| 2  | 2  | 80  |",
-            "| 4  | 4  | 120 |",
-            "+----+----+-----+",
-        ];
+        // `lc1 = x % 4`:
+        assert_eq!(distinct_hashes("lc1", "rc1").await?, 4);
+        // The first 12 of the 30 rows have a null key, the remaining keys are
+        // distinct, and the null keys share a hash value:
+        assert_eq!(
+            distinct_hashes("l_asc_null_first", "r_asc_null_first").await?,
+            18 + 1
+        );
 
-        // RightAnti join output is expected to preserve right input order
-        assert_batches_eq!(expected, &batches);
         Ok(())
     }
 
-    #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_right_anti_with_filter(batch_size: usize) -> Result<()> {
-        let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_semi_anti_left_table();
-        let right = build_semi_anti_right_table();
-        // left_table right anti join right_table on left_table.b1 = right_table.b2 and left_table.a1!=13
-        let on = vec![(
-            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
-            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
-        )];
+    async fn join_inner_single_build_row() -> Result<()> {
+        let left = build_table(("a1", &vec![1]), ("b1", &vec![5]), ("c1", &vec![7]));
+        let right = build_table(
+            ("a2", &vec![10, 20, 30, 40]),
+            ("b2", &vec![5, 4, 5, 5]),
+            ("c2", &vec![70, 80, 90, 100]),
+        );
+        let on = || -> Result<JoinOn> {
+            Ok(vec![(
+                Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+            )])
+        };
+
+        let join = join(left.clone(), right.clone(), on()?, &JoinType::Inner, false)?;
+        let batches =
+            common::collect(join.execute(0, Arc::new(TaskContext::default()))?).await?;
+        let expected = [
+            "+----+----+----+----+----+-----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2  |",
+            "+----+----+----+----+----+-----+",
+            "| 1  | 5  | 7  | 10 | 5  | 70  |",
+            "| 1  | 5  | 7  | 30 | 5  | 90  |",
+            "| 1  | 5  | 7  | 40 | 5  | 100 |",
+            "+----+----+----+----+----+-----+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        // Every probe row is compared to the build row once:
+        let metrics = join.metrics().unwrap();
+        assert_eq!(
+            metrics.sum_by_name("key_comparisons").map(|v| v.as_usize()),
+            Some(4)
+        );
 
+        // The filter is applied to the matched rows as usual
         let column_indices = vec![ColumnIndex {
-            index: 0,
-            side: JoinSide::Left,
+            index: 2,
+            side: JoinSide::Right,
         }];
         let intermediate_schema =
-            Schema::new(vec![Field::new("x", DataType::Int32, true)]);
-
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("x", 0)),
-            Operator::NotEq,
-            Arc::new(Literal::new(ScalarValue::Int32(Some(13)))),
-        )) as Arc<dyn PhysicalExpr>;
-
+            Schema::new(vec![Field::new("c2", DataType::Int32, true)]);
         let filter = JoinFilter::new(
-            filter_expression,
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("c2", 0)),
+                Operator::Gt,
+                Arc::new(Literal::new(ScalarValue::Int32(Some(80)))),
+            )),
             column_indices,
-            intermediate_schema.clone(),
+            intermediate_schema,
         );
-
-        let join = join_with_filter(
-            left.clone(),
-            right.clone(),
-            on.clone(),
-            filter,
-            &JoinType::RightAnti,
-            false,
-        )?;
-
-        let columns_header = columns(&join.schema());
-        assert_eq!(columns_header, vec!["a2", "b2", "c2"]);
-
-        let stream = join.execute(0, task_ctx.clone())?;
-        let batches = common::collect(stream).await?;
-
+        let on = on()?;
+        let join = join_with_filter(left, right, on, filter, &JoinType::Inner, false)?;
+        let batches =
+            common::collect(join.execute(0, Arc::new(TaskContext::default()))?).await?;
         let expected = [
-            "+----+----+-----+",
-            "| a2 | b2 | c2  |",
-            "+----+----+-----+",
-            "| 12 | 10 | 40  |",
-            "| 6  | 6  | 60  |",
-            "| 2  | 2  | 80  |",
-            "| 10 | 10 | 100 |",
-            "| 4  | 4  | 120 |",
-            "+----+----+-----+",
+            "+----+----+----+----+----+-----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2  |",
+            "+----+----+----+----+----+-----+",
+            "| 1  | 5  | 7  | 30 | 5  | 90  |",
+            "| 1  | 5  | 7  | 40 | 5  | 100 |",
+            "+----+----+----+----+----+-----+",
         ];
-
-        // RightAnti join output is expected to preserve right input order
         assert_batches_eq!(expected, &batches);
 
-        // left_table right anti join right_table on left_table.b1 = right_table.b2 and right_table.b2!=8
-        let column_indices = vec![ColumnIndex {
-            index: 1,
-            side: JoinSide::Right,
-        }];
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("x", 0)),
-            Operator::NotEq,
-            Arc::new(Literal::new(ScalarValue::Int32(Some(8)))),
-        )) as Arc<dyn PhysicalExpr>;
+        Ok(())
+    }
 
-        let filter =
-            JoinFilter::new(filter_expression, column_indices, intermediate_schema);
+    #[rstest]
+    #[tokio::test]
+    async fn join_on_struct_keys(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::RightAnti
+        )]
+        join_type: JoinType,
+        #[values(false, true)] null_equals_null: bool,
+    ) -> Result<()> {
+        // The first 12 of the 30 rows have a null `*_asc_null_first` field:
+        let (left_batch, right_batch) = build_sides_record_batches(30, (4, 5))?;
+        let with_struct_key =
+            |batch: &RecordBatch, name: &str, fields: [&str; 2]| -> Result<RecordBatch> {
+                let schema = batch.schema();
+                let columns = fields
+                    .iter()
+                    .map(|field| {
+                        let index = schema.index_of(field)?;
+                        Ok((
+                            Arc::new(schema.field(index).clone()),
+                            batch.column(index).clone(),
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let key = Arc::new(StructArray::from(columns)) as ArrayRef;
+                let columns = batch.columns().iter().cloned().chain([key]);
+                let names = schema.fields().iter().map(|f| f.name().clone());
+                Ok(RecordBatch::try_from_iter(
+                    names.chain([name.to_string()]).zip(columns),
+                )?)
+            };
+        let left_batch = with_struct_key(&left_batch, "ls", ["lc1", "l_asc_null_first"])?;
+        let right_batch =
+            with_struct_key(&right_batch, "rs", ["rc1", "r_asc_null_first"])?;
+        let left = Arc::new(MemoryExec::try_new(
+            &[vec![left_batch.clone()]],
+            left_batch.schema(),
+            None,
+        )?);
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![right_batch.clone()]],
+            right_batch.schema(),
+            None,
+        )?);
+        let on = |keys: &[(&str, &str)]| -> Result<JoinOn> {
+            keys.iter()
+                .map(|(left_key, right_key)| {
+                    Ok((
+                        Arc::new(Column::new_with_schema(left_key, &left_batch.schema())?)
+                            as _,
+                        Arc::new(Column::new_with_schema(
+                            right_key,
+                            &right_batch.schema(),
+                        )?) as _,
+                    ))
+                })
+                .collect()
+        };
 
-        let join =
-            join_with_filter(left, right, on, filter, &JoinType::RightAnti, false)?;
+        let (_, struct_batches) = join_collect(
+            left.clone(),
+            right.clone(),
+            on(&[("ls", "rs")])?,
+            &join_type,
+            null_equals_null,
+            Arc::new(TaskContext::default()),
+        )
+        .await?;
+        let (_, flattened_batches) = join_collect(
+            left,
+            right,
+            on(&[("lc1", "rc1"), ("l_asc_null_first", "r_asc_null_first")])?,
+            &join_type,
+            null_equals_null,
+            Arc::new(TaskContext::default()),
+        )
+        .await?;
 
-        let columns_header = columns(&join.schema());
-        assert_eq!(columns_header, vec!["a2", "b2", "c2"]);
+        let num_rows = |batches: &[RecordBatch]| -> usize {
+            batches.iter().map(RecordBatch::num_rows).sum()
+        };
+        assert_eq!(num_rows(&struct_batches), num_rows(&flattened_batches));
+        compare_batches(&struct_batches, &flattened_batches);
 
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+        Ok(())
+    }
 
-        let expected = [
-            "+----+----+-----+",
-            "| a2 | b2 | c2  |",
-            "+----+----+-----+",
-            "| 8  | 8  | 20  |",
-            "| 6  | 6  | 60  |",
-            "| 2  | 2  | 80  |",
-            "| 4  | 4  | 120 |",
-            "+----+----+-----+",
-        ];
+    #[test]
+    fn eq_struct_keys_with_nulls() -> Result<()> {
+        let field = Arc::new(Field::new("a", DataType::Int32, true));
+        let structs = |values: Vec<Option<i32>>, valid: Vec<bool>| {
+            StructArray::new(
+                vec![field.clone()].into(),
+                vec![Arc::new(Int32Array::from(values)) as ArrayRef],
+                Some(valid.into()),
+            )
+        };
+        // Equal, null field on both sides, null struct on both sides and
+        // null struct on one side:
+        let left = structs(
+            vec![Some(1), None, Some(2), Some(3)],
+            vec![true, true, false, false],
+        );
+        let right = structs(
+            vec![Some(1), None, Some(2), Some(3)],
+            vec![true, true, false, true],
+        );
 
-        // RightAnti join output is expected to preserve right input order
-        assert_batches_eq!(expected, &batches);
+        let equal = eq_dyn_null(&left, &right, false)?;
+        assert_eq!(equal, BooleanArray::from(vec![true, false, false, false]));
+        let equal = eq_dyn_null(&left, &right, true)?;
+        assert_eq!(equal, BooleanArray::from(vec![true, true, true, false]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_skips_null_probe_keys() -> Result<()> {
+        // The first 12 of the 30 rows of each side have a null key:
+        let (left_batch, right_batch) = build_sides_record_batches(30, (4, 5))?;
+        let left = Arc::new(MemoryExec::try_new(
+            &[vec![left_batch.clone()]],
+            left_batch.schema(),
+            None,
+        )?);
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![right_batch.clone()]],
+            right_batch.schema(),
+            None,
+        )?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema(
+                "l_asc_null_first",
+                &left_batch.schema(),
+            )?) as _,
+            Arc::new(Column::new_with_schema(
+                "r_asc_null_first",
+                &right_batch.schema(),
+            )?) as _,
+        )];
+
+        let run = |row_provenance: bool| {
+            let join = join(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                &JoinType::Inner,
+                false,
+            )
+            .and_then(|join| join.with_row_provenance(row_provenance));
+            async move {
+                let join = join?;
+                let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+                let batches = common::collect(stream).await?;
+                let metrics = join.metrics().unwrap();
+                let metric = |name: &str| metrics.sum_by_name(name).unwrap().as_usize();
+                Result::<_>::Ok((
+                    batches,
+                    metric("key_comparisons"),
+                    metric("skipped_null_key_rows"),
+                ))
+            }
+        };
+        let (batches, comparisons, skipped) = run(false).await?;
+        assert_eq!(skipped, 12);
+        // The remaining 18 probe rows have exactly one match each:
+        assert_eq!(comparisons, 18);
+
+        // The row provenance refers to the unfiltered probe batches, so null
+        // keys are not skipped; the null build and probe rows share a hash:
+        let (provenance_batches, provenance_comparisons, skipped) = run(true).await?;
+        assert_eq!(skipped, 0);
+        assert_eq!(provenance_comparisons, 18 + 12 * 12);
+        let join_columns = (0..batches[0].num_columns()).collect::<Vec<_>>();
+        let provenance_batches = provenance_batches
+            .iter()
+            .map(|batch| batch.project(&join_columns))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            arrow::util::pretty::pretty_format_batches(&batches)?.to_string(),
+            arrow::util::pretty::pretty_format_batches(&provenance_batches)?.to_string()
+        );
 
         Ok(())
     }
 
-    #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_right_one(batch_size: usize) -> Result<()> {
-        let task_ctx = prepare_task_ctx(batch_size);
+    async fn join_metrics_as_json() -> Result<()> {
         let left = build_table(
             ("a1", &vec![1, 2, 3]),
-            ("b1", &vec![4, 5, 7]),
+            ("b1", &vec![4, 5, 5]),
             ("c1", &vec![7, 8, 9]),
         );
         let right = build_table(
-            ("a2", &vec![10, 20, 30]),
-            ("b1", &vec![4, 5, 6]), // 6 does not exist on the left
-            ("c2", &vec![70, 80, 90]),
+            ("a2", &vec![10, 20, 30, 40]),
+            ("b1", &vec![4, 5, 6, 7]),
+            ("c2", &vec![70, 80, 90, 100]),
         );
         let on = vec![(
             Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
             Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
-        let (columns, batches) =
-            join_collect(left, right, on, &JoinType::Right, false, task_ctx).await?;
-
-        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
-
-        let expected = [
-            "+----+----+----+----+----+----+",
-            "| a1 | b1 | c1 | a2 | b1 | c2 |",
-            "+----+----+----+----+----+----+",
-            "|    |    |    | 30 | 6  | 90 |",
-            "| 1  | 4  | 7  | 10 | 4  | 70 |",
-            "| 2  | 5  | 8  | 20 | 5  | 80 |",
-            "+----+----+----+----+----+----+",
-        ];
-
-        assert_batches_sorted_eq!(expected, &batches);
+        let join = join(left, right, on, &JoinType::Left, false)?;
+        common::collect(join.execute(0, Arc::new(TaskContext::default()))?).await?;
+
+        let json = join.metrics_json();
+        assert_eq!(json["version"], 1);
+        assert_eq!(json["operator"], "HashJoinExec");
+        assert_eq!(json["join_type"], "Left");
+        assert_eq!(json["partition_mode"], "CollectLeft");
+        let metric = |name: &str| &json["metrics"][name];
+        assert_eq!(metric("output_rows")["kind"], "count");
+        assert_eq!(metric("output_rows")["value"], 3);
+        assert_eq!(metric("build_input_rows")["value"], 3);
+        assert_eq!(metric("input_rows")["value"], 4);
+        assert_eq!(metric("build_mem_used")["kind"], "gauge");
+        assert!(metric("build_mem_used")["value"].as_u64().unwrap() > 0);
+        assert_eq!(metric("join_time")["kind"], "time");
 
         Ok(())
     }
 
-    #[apply(batch_sizes)]
     #[tokio::test]
-    async fn partitioned_join_right_one(batch_size: usize) -> Result<()> {
-        let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_table(
-            ("a1", &vec![1, 2, 3]),
-            ("b1", &vec![4, 5, 7]),
-            ("c1", &vec![7, 8, 9]),
-        );
-        let right = build_table(
-            ("a2", &vec![10, 20, 30]),
-            ("b1", &vec![4, 5, 6]), // 6 does not exist on the left
-            ("c2", &vec![70, 80, 90]),
-        );
+    async fn join_phase_times() -> Result<()> {
+        let task_ctx = prepare_task_ctx(8);
+        let (left_batch, right_batch) = build_sides_record_batches(1000, (4, 5))?;
+        let left = Arc::new(MemoryExec::try_new(
+            &[split_record_batches(&left_batch, 100)?],
+            left_batch.schema(),
+            None,
+        )?);
+        let right = Arc::new(MemoryExec::try_new(
+            &[split_record_batches(&right_batch, 100)?],
+            right_batch.schema(),
+            None,
+        )?);
         let on = vec![(
-            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
-            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+            Arc::new(Column::new_with_schema("lc1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("rc1", &right.schema())?) as _,
         )];
 
-        let (columns, batches) =
-            partitioned_join_collect(left, right, on, &JoinType::Right, false, task_ctx)
-                .await?;
-
-        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b1", "c2"]);
-
-        let expected = [
-            "+----+----+----+----+----+----+",
-            "| a1 | b1 | c1 | a2 | b1 | c2 |",
-            "+----+----+----+----+----+----+",
-            "|    |    |    | 30 | 6  | 90 |",
-            "| 1  | 4  | 7  | 10 | 4  | 70 |",
-            "| 2  | 5  | 8  | 20 | 5  | 80 |",
-            "+----+----+----+----+----+----+",
-        ];
-
-        assert_batches_sorted_eq!(expected, &batches);
+        let join = join(left, right, on, &JoinType::Full, false)?;
+        common::collect(join.execute(0, task_ctx)?).await?;
+
+        let metrics = join.metrics().unwrap();
+        let time = |name: &str| metrics.sum_by_name(name).unwrap().as_usize();
+        let (join_time, probe_time, output_time) =
+            (time("join_time"), time("probe_time"), time("output_time"));
+        assert!(time("build_time") > 0);
+        assert!(probe_time > 0);
+        assert!(output_time > 0);
+        // Probing and assembling the output make up the time spent joining:
+        assert!(probe_time + output_time <= join_time);
+        assert!(probe_time + output_time >= join_time / 2);
 
         Ok(())
     }
 
-    #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_full_one(batch_size: usize) -> Result<()> {
-        let task_ctx = prepare_task_ctx(batch_size);
+    async fn join_dedups_existence_only_build_keys() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        // Only 5 distinct keys among 1000 build side rows:
         let left = build_table(
-            ("a1", &vec![1, 2, 3]),
-            ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
-            ("c1", &vec![7, 8, 9]),
+            ("a1", &(0..1000).collect()),
+            ("b1", &(0..1000).map(|i| i % 5).collect()),
+            ("c1", &(0..1000).collect()),
         );
         let right = build_table(
             ("a2", &vec![10, 20, 30]),
-            ("b2", &vec![4, 5, 6]),
+            ("b1", &vec![1, 4, 7]),
             ("c2", &vec![70, 80, 90]),
         );
         let on = vec![(
-            Arc::new(Column::new_with_schema("b1", &left.schema()).unwrap()) as _,
-            Arc::new(Column::new_with_schema("b2", &right.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
 
-        let join = join(left, right, on, &JoinType::Full, false)?;
-
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b2", "c2"]);
+        let run = |join_type| {
+            let join = join(left.clone(), right.clone(), on.clone(), &join_type, false);
+            let task_ctx = task_ctx.clone();
+            async move {
+                let join = join?;
+                let batches = common::collect(join.execute(0, task_ctx)?).await?;
+                let metrics = join.metrics().unwrap();
+                let metric = |name: &str| metrics.sum_by_name(name).unwrap().as_usize();
+                Ok::<_, DataFusionError>((
+                    batches,
+                    metric("hash_table_deduplicated_rows"),
+                    metric("build_mem_used"),
+                ))
+            }
+        };
 
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+        let (batches, deduplicated, semi_mem_used) = run(JoinType::RightSemi).await?;
+        assert_eq!(deduplicated, 995);
+        let expected = [
+            "+----+----+----+",
+            "| a2 | b1 | c2 |",
+            "+----+----+----+",
+            "| 10 | 1  | 70 |",
+            "| 20 | 4  | 80 |",
+            "+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
 
+        let (batches, deduplicated, _) = run(JoinType::RightAnti).await?;
+        assert_eq!(deduplicated, 995);
         let expected = [
-            "+----+----+----+----+----+----+",
-            "| a1 | b1 | c1 | a2 | b2 | c2 |",
-            "+----+----+----+----+----+----+",
-            "|    |    |    | 30 | 6  | 90 |",
-            "| 1  | 4  | 7  | 10 | 4  | 70 |",
-            "| 2  | 5  | 8  | 20 | 5  | 80 |",
-            "| 3  | 7  | 9  |    |    |    |",
-            "+----+----+----+----+----+----+",
+            "+----+----+----+",
+            "| a2 | b1 | c2 |",
+            "+----+----+----+",
+            "| 30 | 7  | 90 |",
+            "+----+----+----+",
         ];
         assert_batches_sorted_eq!(expected, &batches);
 
+        // Left semi joins output every matching build side row:
+        let (batches, deduplicated, left_semi_mem_used) = run(JoinType::LeftSemi).await?;
+        assert_eq!(deduplicated, 0);
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 400);
+        assert!(semi_mem_used < left_semi_mem_used);
+
         Ok(())
     }
 
-    #[test]
-    fn join_with_hash_collision() -> Result<()> {
-        let mut hashmap_left = RawTable::with_capacity(2);
-        let left = build_table_i32(
-            ("a", &vec![10, 20]),
-            ("x", &vec![100, 200]),
-            ("y", &vec![200, 300]),
-        );
-
-        let random_state = RandomState::with_seeds(0, 0, 0, 0);
-        let hashes_buff = &mut vec![0; left.num_rows()];
-        let hashes =
-            create_hashes(&[left.columns()[0].clone()], &random_state, hashes_buff)?;
-
-        // Create hash collisions (same hashes)
-        hashmap_left.insert(hashes[0], (hashes[0], 1), |(h, _)| *h);
-        hashmap_left.insert(hashes[1], (hashes[1], 1), |(h, _)| *h);
+    #[tokio::test]
+    async fn join_with_build_key_unique() -> Result<()> {
+        let task_ctx = prepare_task_ctx(8);
+        let (left_batch, right_batch) = build_sides_record_batches(40, (4, 5))?;
+        let left = Arc::new(MemoryExec::try_new(
+            &[split_record_batches(&left_batch, 16)?],
+            left_batch.schema(),
+            None,
+        )?);
+        let right = Arc::new(MemoryExec::try_new(
+            &[split_record_batches(&right_batch, 16)?],
+            right_batch.schema(),
+            None,
+        )?);
+        let key = |left_key: &str, right_key: &str| -> Result<JoinOn> {
+            Ok(vec![(
+                Arc::new(Column::new_with_schema(left_key, &left_batch.schema())?) as _,
+                Arc::new(Column::new_with_schema(right_key, &right_batch.schema())?) as _,
+            )])
+        };
 
-        let next = vec![2, 0];
+        // `la1` is unique:
+        for join_type in [
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::RightAnti,
+        ] {
+            let on = key("la1", "ra1")?;
+            let expected = common::collect(
+                join(left.clone(), right.clone(), on.clone(), &join_type, false)?
+                    .execute(0, task_ctx.clone())?,
+            )
+            .await?;
+            let unique = join(left.clone(), right.clone(), on, &join_type, false)?
+                .with_build_key_unique(true);
+            assert!(unique.build_key_unique());
+            let batches = common::collect(unique.execute(0, task_ctx.clone())?).await?;
+            compare_batches(&expected, &batches);
+        }
 
-        let right = build_table_i32(
-            ("a", &vec![10, 20]),
-            ("b", &vec![0, 0]),
-            ("c", &vec![30, 40]),
+        // `lc1` has only 4 distinct values:
+        let duplicate = join(left, right, key("lc1", "rc1")?, &JoinType::Inner, false)?
+            .with_build_key_unique(true);
+        let err = common::collect(duplicate.execute(0, task_ctx)?)
+            .await
+            .unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "Build side join keys are hinted to be unique"
         );
 
-        // Join key column for both join sides
-        let key_column: PhysicalExprRef = Arc::new(Column::new("a", 0)) as _;
-
-        let join_hash_map = JoinHashMap::new(hashmap_left, next);
-
-        let right_keys_values =
-            key_column.evaluate(&right)?.into_array(right.num_rows())?;
-        let mut hashes_buffer = vec![0; right.num_rows()];
-        create_hashes(&[right_keys_values], &random_state, &mut hashes_buffer)?;
-
-        let (l, r, _) = lookup_join_hashmap(
-            &join_hash_map,
-            &left,
-            &right,
-            &[key_column.clone()],
-            &[key_column],
-            false,
-            &hashes_buffer,
-            8192,
-            (0, None),
-        )?;
-
-        let mut left_ids = UInt64Builder::with_capacity(0);
-        left_ids.append_value(0);
-        left_ids.append_value(1);
-
-        let mut right_ids = UInt32Builder::with_capacity(0);
-        right_ids.append_value(0);
-        right_ids.append_value(1);
-
-        assert_eq!(left_ids.finish(), l);
-
-        assert_eq!(right_ids.finish(), r);
-
         Ok(())
     }
 
     #[tokio::test]
-    async fn join_with_duplicated_column_names() -> Result<()> {
+    async fn join_skip_empty_output_batches() -> Result<()> {
         let task_ctx = Arc::new(TaskContext::default());
         let left = build_table(
-            ("a", &vec![1, 2, 3]),
-            ("b", &vec![4, 5, 7]),
-            ("c", &vec![7, 8, 9]),
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 6]),
+            ("c1", &vec![7, 8, 9]),
         );
-        let right = build_table(
-            ("a", &vec![10, 20, 30]),
-            ("b", &vec![1, 2, 7]),
-            ("c", &vec![70, 80, 90]),
+        // The second probe batch has no matches:
+        let matching = build_table_i32(
+            ("a2", &vec![10, 20]),
+            ("b1", &vec![4, 5]),
+            ("c2", &vec![70, 80]),
         );
+        let unmatched = build_table_i32(
+            ("a2", &vec![30, 40]),
+            ("b1", &vec![10, 11]),
+            ("c2", &vec![90, 100]),
+        );
+        let schema = matching.schema();
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![matching, unmatched.clone(), unmatched]],
+            schema.clone(),
+            None,
+        )?);
         let on = vec![(
-            // join on a=b so there are duplicate column names on unjoined columns
-            Arc::new(Column::new_with_schema("a", &left.schema()).unwrap()) as _,
-            Arc::new(Column::new_with_schema("b", &right.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &schema)?) as _,
         )];
 
         let join = join(left, right, on, &JoinType::Inner, false)?;
+        let batches = common::collect(join.execute(0, task_ctx.clone())?).await?;
+        assert!(batches.iter().any(|batch| batch.num_rows() == 0));
+
+        let join = join.with_skip_empty_output_batches(true);
+        assert!(join.skip_empty_output_batches());
+        let skipped = common::collect(join.execute(0, task_ctx)?).await?;
+        assert!(skipped.iter().all(|batch| batch.num_rows() > 0));
+        assert_batches_sorted_eq!(
+            [
+                "+----+----+----+----+----+----+",
+                "| a1 | b1 | c1 | a2 | b1 | c2 |",
+                "+----+----+----+----+----+----+",
+                "| 1  | 4  | 7  | 10 | 4  | 70 |",
+                "| 2  | 5  | 8  | 20 | 5  | 80 |",
+                "+----+----+----+----+----+----+",
+            ],
+            &skipped
+        );
 
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
+        Ok(())
+    }
 
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+    #[tokio::test]
+    async fn join_with_case_insensitive_collation() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let left_schema = Arc::new(Schema::new(vec![
+            Field::new("a1", DataType::Int32, false),
+            Field::new("b1", DataType::Utf8, true),
+        ]));
+        let left = RecordBatch::try_new(
+            left_schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(StringArray::from(vec![Some("Apple"), Some("pear"), None])),
+            ],
+        )?;
+        let right_schema = Arc::new(Schema::new(vec![
+            Field::new("a2", DataType::Int32, false),
+            Field::new("b2", DataType::Utf8, true),
+        ]));
+        let right = RecordBatch::try_new(
+            right_schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![10, 20, 30])),
+                Arc::new(StringArray::from(vec![Some("apple"), Some("PEAR"), None])),
+            ],
+        )?;
+        let left = Arc::new(MemoryExec::try_new(
+            &[vec![left]],
+            left_schema.clone(),
+            None,
+        )?);
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![right]],
+            right_schema.clone(),
+            None,
+        )?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left_schema)?) as _,
+            Arc::new(Column::new_with_schema("b2", &right_schema)?) as _,
+        )];
 
-        let expected = [
-            "+---+---+---+----+---+----+",
-            "| a | b | c | a  | b | c  |",
-            "+---+---+---+----+---+----+",
-            "| 1 | 4 | 7 | 10 | 1 | 70 |",
-            "| 2 | 5 | 8 | 20 | 2 | 80 |",
-            "+---+---+---+----+---+----+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+        // Keys differing in case don't match under the binary collation
+        let join = join(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::Inner,
+            false,
+        )?;
+        assert_eq!(join.collation(), Collation::Binary);
+        let batches = common::collect(join.execute(0, task_ctx.clone())?).await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 0);
+
+        let join = join.with_collation(Collation::CaseInsensitive)?;
+        assert_eq!(join.collation(), Collation::CaseInsensitive);
+        // The join keys are not equal in the output
+        assert!(join
+            .properties()
+            .equivalence_properties()
+            .eq_group()
+            .is_empty());
+        let batches = common::collect(join.execute(0, task_ctx)?).await?;
+        assert_batches_sorted_eq!(
+            [
+                "+----+-------+----+-------+",
+                "| a1 | b1    | a2 | b2    |",
+                "+----+-------+----+-------+",
+                "| 1  | Apple | 10 | apple |",
+                "| 2  | pear  | 20 | PEAR  |",
+                "+----+-------+----+-------+",
+            ],
+            &batches
+        );
+
+        let partitioned = HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            None,
+            PartitionMode::Partitioned,
+            false,
+        )?
+        .with_collation(Collation::CaseInsensitive);
+        assert!(partitioned.is_err());
 
         Ok(())
     }
 
-    fn prepare_join_filter() -> JoinFilter {
-        let column_indices = vec![
-            ColumnIndex {
-                index: 2,
-                side: JoinSide::Left,
-            },
-            ColumnIndex {
-                index: 2,
-                side: JoinSide::Right,
-            },
-        ];
-        let intermediate_schema = Schema::new(vec![
-            Field::new("c", DataType::Int32, true),
-            Field::new("c", DataType::Int32, true),
-        ]);
-        let filter_expression = Arc::new(BinaryExpr::new(
-            Arc::new(Column::new("c", 0)),
-            Operator::Gt,
-            Arc::new(Column::new("c", 1)),
-        )) as Arc<dyn PhysicalExpr>;
-
-        JoinFilter::new(filter_expression, column_indices, intermediate_schema)
+    #[tokio::test]
+    async fn join_preserves_field_metadata() -> Result<()> {
+        let with_metadata = |batch: RecordBatch, unit: &str| {
+            let schema = batch.schema();
+            let fields = schema
+                .fields()
+                .iter()
+                .map(|field| {
+                    field
+                        .as_ref()
+                        .clone()
+                        .with_metadata(std::collections::HashMap::from([(
+                            "unit".to_string(),
+                            unit.to_string(),
+                        )]))
+                })
+                .collect::<Vec<_>>();
+            let schema = Arc::new(Schema::new(fields));
+            let batch = RecordBatch::try_new(schema.clone(), batch.columns().to_vec())?;
+            Ok::<_, DataFusionError>(Arc::new(MemoryExec::try_new(
+                &[vec![batch]],
+                schema,
+                None,
+            )?))
+        };
+        let left = with_metadata(
+            build_table_i32(
+                ("a1", &vec![1, 2]),
+                ("b1", &vec![4, 5]),
+                ("c1", &vec![7, 8]),
+            ),
+            "m",
+        )?;
+        let right = with_metadata(
+            build_table_i32(
+                ("a2", &vec![10, 20]),
+                ("b2", &vec![4, 6]),
+                ("c2", &vec![70, 80]),
+            ),
+            "s",
+        )?;
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        let join = join(left, right, on, &JoinType::Full, false)?;
+        let task_ctx = Arc::new(TaskContext::default());
+        let batches = common::collect(join.execute(0, task_ctx)?).await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 3);
+        // Both sides are null padded, and keep their metadata:
+        for schema in
+            std::iter::once(join.schema()).chain(batches.iter().map(|b| b.schema()))
+        {
+            for field in schema.fields() {
+                assert!(field.is_nullable());
+                let unit = if field.name().ends_with('1') {
+                    "m"
+                } else {
+                    "s"
+                };
+                assert_eq!(field.metadata().get("unit").unwrap(), unit);
+            }
+        }
+
+        Ok(())
     }
 
-    #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_inner_with_filter(batch_size: usize) -> Result<()> {
-        let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_table(
-            ("a", &vec![0, 1, 2, 2]),
-            ("b", &vec![4, 5, 7, 8]),
-            ("c", &vec![7, 8, 9, 1]),
-        );
-        let right = build_table(
-            ("a", &vec![10, 20, 30, 40]),
-            ("b", &vec![2, 2, 3, 4]),
-            ("c", &vec![7, 5, 6, 4]),
+    async fn join_with_float_key_mode() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let left =
+            build_table_f64(("a1", &vec![1, 2, 3]), ("b1", &vec![0.0, -0.0, f64::NAN]));
+        let right = build_table_f64(
+            ("a2", &vec![10, 20, 30]),
+            ("b2", &vec![0.0, -0.0, f64::NAN]),
         );
         let on = vec![(
-            Arc::new(Column::new_with_schema("a", &left.schema()).unwrap()) as _,
-            Arc::new(Column::new_with_schema("b", &right.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
         )];
-        let filter = prepare_join_filter();
-
-        let join = join_with_filter(left, right, on, filter, &JoinType::Inner, false)?;
 
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
+        // Keys match if their bits are equal by default
+        let float_join = join(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::Inner,
+            false,
+        )?;
+        assert_eq!(float_join.float_key_mode(), FloatKeyMode::TotalOrder);
+        let batches = common::collect(float_join.execute(0, task_ctx.clone())?).await?;
+        assert_batches_sorted_eq!(
+            [
+                "+----+------+----+------+",
+                "| a1 | b1   | a2 | b2   |",
+                "+----+------+----+------+",
+                "| 1  | 0.0  | 10 | 0.0  |",
+                "| 2  | -0.0 | 20 | -0.0 |",
+                "| 3  | NaN  | 30 | NaN  |",
+                "+----+------+----+------+",
+            ],
+            &batches
+        );
 
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+        // The zeros match each other under IEEE semantics, NaN matches nothing
+        let float_join = float_join.with_float_key_mode(FloatKeyMode::Ieee)?;
+        assert_eq!(float_join.float_key_mode(), FloatKeyMode::Ieee);
+        assert!(float_join
+            .properties()
+            .equivalence_properties()
+            .eq_group()
+            .is_empty());
+        let batches = common::collect(float_join.execute(0, task_ctx)?).await?;
+        assert_batches_sorted_eq!(
+            [
+                "+----+------+----+------+",
+                "| a1 | b1   | a2 | b2   |",
+                "+----+------+----+------+",
+                "| 1  | 0.0  | 10 | 0.0  |",
+                "| 1  | 0.0  | 20 | -0.0 |",
+                "| 2  | -0.0 | 10 | 0.0  |",
+                "| 2  | -0.0 | 20 | -0.0 |",
+                "+----+------+----+------+",
+            ],
+            &batches
+        );
 
-        let expected = [
-            "+---+---+---+----+---+---+",
-            "| a | b | c | a  | b | c |",
-            "+---+---+---+----+---+---+",
-            "| 2 | 7 | 9 | 10 | 2 | 7 |",
-            "| 2 | 7 | 9 | 20 | 2 | 5 |",
-            "+---+---+---+----+---+---+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+        // NaN keys would match as nulls if nulls are compared equal
+        assert!(join(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::Inner,
+            true
+        )?
+        .with_float_key_mode(FloatKeyMode::Ieee)
+        .is_err());
+        let partitioned = HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            None,
+            PartitionMode::Partitioned,
+            false,
+        )?
+        .with_float_key_mode(FloatKeyMode::Ieee);
+        assert!(partitioned.is_err());
 
         Ok(())
     }
 
-    #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_left_with_filter(batch_size: usize) -> Result<()> {
-        let task_ctx = prepare_task_ctx(batch_size);
+    async fn join_skips_filter_on_satisfied_probe_batches() -> Result<()> {
+        let task_ctx = prepare_task_ctx(8);
         let left = build_table(
-            ("a", &vec![0, 1, 2, 2]),
-            ("b", &vec![4, 5, 7, 8]),
-            ("c", &vec![7, 8, 9, 1]),
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![1, 2, 2]),
+            ("c1", &vec![7, 8, 9]),
         );
-        let right = build_table(
-            ("a", &vec![10, 20, 30, 40]),
-            ("b", &vec![2, 2, 3, 4]),
-            ("c", &vec![7, 5, 6, 4]),
+        let right_batch = build_table_i32(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![1, 2, 2]),
+            ("c2", &vec![70, 80, 90]),
         );
         let on = vec![(
-            Arc::new(Column::new_with_schema("a", &left.schema()).unwrap()) as _,
-            Arc::new(Column::new_with_schema("b", &right.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right_batch.schema())?) as _,
         )];
-        let filter = prepare_join_filter();
-
-        let join = join_with_filter(left, right, on, filter, &JoinType::Left, false)?;
-
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
+        // c2 > 0, which every probe row satisfies
+        let probe_filter = JoinFilter::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("c2", 0)),
+                Operator::Gt,
+                Arc::new(Literal::new(ScalarValue::Int32(Some(0)))),
+            )),
+            vec![ColumnIndex {
+                index: 2,
+                side: JoinSide::Right,
+            }],
+            Schema::new(vec![Field::new("c2", DataType::Int32, true)]),
+        );
 
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+        let run = |batch: RecordBatch, filter: JoinFilter| {
+            let schema = batch.schema();
+            let right = Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None)?);
+            let join = join_with_filter(
+                left.clone(),
+                right,
+                on.clone(),
+                filter,
+                &JoinType::Inner,
+                false,
+            )?;
+            let stream = join.execute(0, task_ctx.clone())?;
+            Ok::<_, DataFusionError>((join, stream))
+        };
+        let filter_evaluations = |join: &HashJoinExec| {
+            join.metrics()
+                .unwrap()
+                .sum_by_name("filter_evaluations")
+                .map(|v| v.as_usize())
+        };
 
-        let expected = [
-            "+---+---+---+----+---+---+",
-            "| a | b | c | a  | b | c |",
-            "+---+---+---+----+---+---+",
-            "| 0 | 4 | 7 |    |   |   |",
-            "| 1 | 5 | 8 |    |   |   |",
-            "| 2 | 7 | 9 | 10 | 2 | 7 |",
-            "| 2 | 7 | 9 | 20 | 2 | 5 |",
-            "| 2 | 8 | 1 |    |   |   |",
-            "+---+---+---+----+---+---+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+        let (join, stream) = run(right_batch.clone(), probe_filter.clone())?;
+        let expected_rows = common::collect(stream)
+            .await?
+            .iter()
+            .map(|b| b.num_rows())
+            .sum::<usize>();
+        assert_eq!(expected_rows, 5);
+        assert_eq!(filter_evaluations(&join), Some(1));
+
+        // The annotation skips the evaluation without changing the result:
+        let marked = mark_join_filter_satisfied(
+            right_batch.clone(),
+            &probe_filter,
+            JoinSide::Right,
+        )?;
+        let (join, stream) = run(marked.clone(), probe_filter.clone())?;
+        let rows = common::collect(stream)
+            .await?
+            .iter()
+            .map(|b| b.num_rows())
+            .sum::<usize>();
+        assert_eq!(rows, expected_rows);
+        assert_eq!(filter_evaluations(&join), Some(0));
+
+        // An annotation for a different filter is not honored:
+        let (join, stream) = run(marked.clone(), prepare_join_filter())?;
+        common::collect(stream).await?;
+        assert_eq!(filter_evaluations(&join), Some(1));
+
+        // Neither is one for the same expression over a different probe column:
+        let a2_filter = JoinFilter::new(
+            probe_filter.expression().clone(),
+            vec![ColumnIndex {
+                index: 0,
+                side: JoinSide::Right,
+            }],
+            probe_filter.schema().clone(),
+        );
+        let (join, stream) = run(marked, a2_filter)?;
+        common::collect(stream).await?;
+        assert_eq!(filter_evaluations(&join), Some(1));
+
+        // Filters referencing the build side can not be marked as satisfied:
+        assert!(mark_join_filter_satisfied(
+            right_batch,
+            &prepare_join_filter(),
+            JoinSide::Right
+        )
+        .is_err());
 
         Ok(())
     }
 
     #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_right_with_filter(batch_size: usize) -> Result<()> {
+    async fn join_full_with_filter_rejected_matches(batch_size: usize) -> Result<()> {
         let task_ctx = prepare_task_ctx(batch_size);
         let left = build_table(
-            ("a", &vec![0, 1, 2, 2]),
-            ("b", &vec![4, 5, 7, 8]),
-            ("c", &vec![7, 8, 9, 1]),
+            ("a1", &vec![1, 2, 3, 4]),
+            ("b1", &vec![1, 2, 2, 3]),
+            ("c1", &vec![10, 20, 30, 40]),
         );
         let right = build_table(
-            ("a", &vec![10, 20, 30, 40]),
-            ("b", &vec![2, 2, 3, 4]),
-            ("c", &vec![7, 5, 6, 4]),
+            ("a2", &vec![10, 20, 30, 40]),
+            ("b1", &vec![1, 2, 2, 5]),
+            ("c2", &vec![50, 5, 50, 0]),
         );
         let on = vec![(
-            Arc::new(Column::new_with_schema("a", &left.schema()).unwrap()) as _,
-            Arc::new(Column::new_with_schema("b", &right.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
         )];
-        let filter = prepare_join_filter();
-
-        let join = join_with_filter(left, right, on, filter, &JoinType::Right, false)?;
 
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
+        // a1 + c1 > c2 + 10 AND a1 + c1 < c2 + 100
+        let intermediate_schema = Schema::new(vec![
+            Field::new("0", DataType::Int32, true),
+            Field::new("1", DataType::Int32, true),
+            Field::new("2", DataType::Int32, true),
+        ]);
+        let filter_expr = complicated_filter(&intermediate_schema)?;
+        let column_indices = vec![
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 2,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 2,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
 
+        let join = join_with_filter(left, right, on, filter, &JoinType::Full, false)?;
         let stream = join.execute(0, task_ctx)?;
         let batches = common::collect(stream).await?;
 
+        // (1, 1, 10) and (10, 1, 50) are equi-matched but rejected by the filter,
+        // as are both pairs involving (30, 2, 50), so all of them are unmatched.
         let expected = [
-            "+---+---+---+----+---+---+",
-            "| a | b | c | a  | b | c |",
-            "+---+---+---+----+---+---+",
-            "|   |   |   | 30 | 3 | 6 |",
-            "|   |   |   | 40 | 4 | 4 |",
-            "| 2 | 7 | 9 | 10 | 2 | 7 |",
-            "| 2 | 7 | 9 | 20 | 2 | 5 |",
-            "+---+---+---+----+---+---+",
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "|    |    |    | 10 | 1  | 50 |",
+            "|    |    |    | 30 | 2  | 50 |",
+            "|    |    |    | 40 | 5  | 0  |",
+            "| 1  | 1  | 10 |    |    |    |",
+            "| 2  | 2  | 20 | 20 | 2  | 5  |",
+            "| 3  | 2  | 30 | 20 | 2  | 5  |",
+            "| 4  | 3  | 40 |    |    |    |",
+            "+----+----+----+----+----+----+",
         ];
         assert_batches_sorted_eq!(expected, &batches);
 
@@ -3272,46 +11759,129 @@ mod tests {
 
     #[apply(batch_sizes)]
     #[tokio::test]
-    async fn join_full_with_filter(batch_size: usize) -> Result<()> {
+    async fn join_left_anti_with_filter_rejected_matches(
+        batch_size: usize,
+    ) -> Result<()> {
         let task_ctx = prepare_task_ctx(batch_size);
-        let left = build_table(
-            ("a", &vec![0, 1, 2, 2]),
-            ("b", &vec![4, 5, 7, 8]),
-            ("c", &vec![7, 8, 9, 1]),
+        let (left_batch, right_batch) = build_sides_record_batches(30, (4, 5))?;
+        let left_schema = left_batch.schema();
+        let right_schema = right_batch.schema();
+        let left = Arc::new(MemoryExec::try_new(
+            &[split_record_batches(&left_batch, 7)?],
+            left_schema.clone(),
+            None,
+        )?) as Arc<dyn ExecutionPlan>;
+        let right = Arc::new(MemoryExec::try_new(
+            &[split_record_batches(&right_batch, 7)?],
+            right_schema.clone(),
+            None,
+        )?) as Arc<dyn ExecutionPlan>;
+
+        // Reference: a left row is emitted only if no right row both matches
+        // lc1 = rc1 and passes la1 + la2 > ra1 + 10 AND la1 + la2 < ra1 + 100.
+        let rows = |batch: &RecordBatch, name: &str| -> Result<Vec<i32>> {
+            let column = batch.column(batch.schema().index_of(name)?);
+            Ok(as_int32_array(column)?.values().to_vec())
+        };
+        let (la1, la2, lc1) = (
+            rows(&left_batch, "la1")?,
+            rows(&left_batch, "la2")?,
+            rows(&left_batch, "lc1")?,
         );
-        let right = build_table(
-            ("a", &vec![10, 20, 30, 40]),
-            ("b", &vec![2, 2, 3, 4]),
-            ("c", &vec![7, 5, 6, 4]),
+        let (ra1, rc1) = (rows(&right_batch, "ra1")?, rows(&right_batch, "rc1")?);
+        let (lc1, rc1) = (&lc1, &rc1);
+        let equi_matches = |l: usize| (0..rc1.len()).filter(move |&r| lc1[l] == rc1[r]);
+        let passes = |l: usize, r: usize| {
+            let (sum, c) = ((la1[l] + la2[l]) as i64, ra1[r] as i64);
+            sum > c + 10 && sum < c + 100
+        };
+        let anti_rows = (0..la1.len())
+            .filter(|&l| !equi_matches(l).any(|r| passes(l, r)))
+            .collect::<Vec<_>>();
+        // Some of the expected rows are equi-matched, but rejected by the filter:
+        assert!(anti_rows.iter().any(|&l| equi_matches(l).next().is_some()));
+        let mut expected = anti_rows.into_iter().map(|l| la1[l]).collect::<Vec<_>>();
+        expected.sort_unstable();
+
+        let intermediate_schema = Schema::new(vec![
+            Field::new("0", DataType::Int32, true),
+            Field::new("1", DataType::Int32, true),
+            Field::new("2", DataType::Int32, true),
+            Field::new("3", DataType::Int32, true),
+            Field::new("4", DataType::Int32, true),
+        ]);
+        let column_indices = vec![
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 4,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Right,
+            },
+            ColumnIndex {
+                index: 2,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 2,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter_expr = complicated_filter(&intermediate_schema)?;
+        let hash_filter = JoinFilter::new(
+            filter_expr.clone(),
+            column_indices.clone(),
+            intermediate_schema.clone(),
         );
+        // The nested loop join has no equi-join keys, so the filter covers them:
+        let nested_loop_filter = JoinFilter::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(BinaryExpr::new(
+                    Arc::new(Column::new("3", 3)),
+                    Operator::Eq,
+                    Arc::new(Column::new("4", 4)),
+                )),
+                Operator::And,
+                filter_expr,
+            )),
+            column_indices,
+            intermediate_schema,
+        );
+
         let on = vec![(
-            Arc::new(Column::new_with_schema("a", &left.schema()).unwrap()) as _,
-            Arc::new(Column::new_with_schema("b", &right.schema()).unwrap()) as _,
+            Arc::new(Column::new_with_schema("lc1", &left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", &right_schema)?) as _,
         )];
-        let filter = prepare_join_filter();
-
-        let join = join_with_filter(left, right, on, filter, &JoinType::Full, false)?;
-
-        let columns = columns(&join.schema());
-        assert_eq!(columns, vec!["a", "b", "c", "a", "b", "c"]);
-
-        let stream = join.execute(0, task_ctx)?;
-        let batches = common::collect(stream).await?;
+        let hash_join = Arc::new(join_with_filter(
+            left.clone(),
+            right.clone(),
+            on,
+            hash_filter,
+            &JoinType::LeftAnti,
+            false,
+        )?) as Arc<dyn ExecutionPlan>;
+        let nested_loop_join = Arc::new(crate::joins::NestedLoopJoinExec::try_new(
+            left,
+            right,
+            Some(nested_loop_filter),
+            &JoinType::LeftAnti,
+        )?) as Arc<dyn ExecutionPlan>;
 
-        let expected = [
-            "+---+---+---+----+---+---+",
-            "| a | b | c | a  | b | c |",
-            "+---+---+---+----+---+---+",
-            "|   |   |   | 30 | 3 | 6 |",
-            "|   |   |   | 40 | 4 | 4 |",
-            "| 2 | 7 | 9 | 10 | 2 | 7 |",
-            "| 2 | 7 | 9 | 20 | 2 | 5 |",
-            "| 0 | 4 | 7 |    |   |   |",
-            "| 1 | 5 | 8 |    |   |   |",
-            "| 2 | 8 | 1 |    |   |   |",
-            "+---+---+---+----+---+---+",
-        ];
-        assert_batches_sorted_eq!(expected, &batches);
+        for join in [hash_join, nested_loop_join] {
+            let batches = common::collect(join.execute(0, task_ctx.clone())?).await?;
+            let mut actual = batches
+                .iter()
+                .map(|batch| rows(batch, "la1"))
+                .collect::<Result<Vec<_>>>()?
+                .concat();
+            actual.sort_unstable();
+            assert_eq!(actual, expected, "{}", join.name());
+        }
 
         Ok(())
     }
@@ -3444,6 +12014,7 @@ mod tests {
             JoinType::LeftAnti,
         ];
         let expected_resultset_records = 20;
+        let probe_side_records = 5;
         let common_result = [
             "+----+----+----+----+----+----+",
             "| a1 | b1 | c1 | a2 | b2 | c2 |",
@@ -3520,13 +12091,15 @@ mod tests {
                 // as there is no need to append non-joined build side data.
                 // For other join types it'll be div_ceil + 1 -- for additional batch
                 // containing not visited build side rows (empty in this test case).
+                // Right semi and anti joins keep a single build side row per join
+                // key, so that every probe side row has a single match.
                 let expected_batch_count = match join_type {
-                    JoinType::Inner
-                    | JoinType::Right
-                    | JoinType::RightSemi
-                    | JoinType::RightAnti => {
+                    JoinType::Inner | JoinType::Right => {
                         (expected_resultset_records + batch_size - 1) / batch_size
                     }
+                    JoinType::RightSemi | JoinType::RightAnti => {
+                        (probe_side_records + batch_size - 1) / batch_size
+                    }
                     _ => (expected_resultset_records + batch_size - 1) / batch_size + 1,
                 };
                 assert_eq!(
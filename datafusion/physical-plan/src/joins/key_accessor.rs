@@ -0,0 +1,671 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`KeyAccessor`] for extracting, comparing and hashing the join key values
+//! of individual rows without matching on the data type of the key columns
+
+use std::any::Any;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use arrow::array::{
+    downcast_dictionary_array, downcast_primitive, Array, ArrayRef, ArrowPrimitiveType,
+    AsArray, BooleanArray, DictionaryArray, GenericByteArray, GenericByteViewArray,
+    PrimitiveArray,
+};
+use arrow::compute::SortOptions;
+use arrow::datatypes::{
+    ArrowDictionaryKeyType, ArrowNativeType, ArrowNativeTypeOp, BinaryType,
+    BinaryViewType, ByteArrayType, ByteViewType, DataType, LargeBinaryType,
+    LargeUtf8Type, StringViewType, ToByteSlice, Utf8Type,
+};
+use arrow::record_batch::RecordBatch;
+use datafusion_common::{internal_err, not_impl_err, Result};
+
+/// Accesses the values of a join key column row by row. Implementations exist
+/// per data type, so that the join key handling of a join does not need to
+/// match on the data type of the keys, and supporting a new key type takes a
+/// single implementation, see [`new_key_accessor`].
+pub trait KeyAccessor: Send + Sync {
+    /// Returns whether the key of `row` is null
+    fn is_null(&self, row: usize) -> bool;
+
+    /// Compares the non-null key of `row` to the non-null key of `other_row`
+    /// of `other`. Floats are compared by their total order. Returns an error
+    /// if `other` accesses a column of another data type.
+    fn compare(
+        &self,
+        row: usize,
+        other: &dyn KeyAccessor,
+        other_row: usize,
+    ) -> Result<Ordering>;
+
+    /// Feeds the non-null key of `row` into `state`. Keys comparing equal
+    /// feed the same bytes.
+    fn hash_key(&self, row: usize, state: &mut dyn Hasher);
+
+    /// Returns the accessor as [`Any`] so that it can be downcast to the
+    /// accessor of `other` in [`Self::compare`]
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// Creates the [`KeyAccessor`] for the join key column `array`. Primitive,
+/// boolean, string and binary (including view) columns are supported, as well
+/// as dictionaries of these.
+pub fn new_key_accessor(array: &ArrayRef) -> Result<Box<dyn KeyAccessor>> {
+    macro_rules! primitive_helper {
+        ($t:ty, $array:ident) => {
+            return Ok(Box::new(PrimitiveKeyAccessor::<$t>(
+                $array.as_primitive::<$t>().clone(),
+            )))
+        };
+    }
+    downcast_primitive! {
+        array.data_type() => (primitive_helper, array),
+        _ => {}
+    }
+
+    let accessor: Box<dyn KeyAccessor> = match array.data_type() {
+        DataType::Null => Box::new(NullKeyAccessor),
+        DataType::Boolean => Box::new(BooleanKeyAccessor(array.as_boolean().clone())),
+        DataType::Utf8 => Box::new(byte_accessor::<Utf8Type>(array)),
+        DataType::LargeUtf8 => Box::new(byte_accessor::<LargeUtf8Type>(array)),
+        DataType::Binary => Box::new(byte_accessor::<BinaryType>(array)),
+        DataType::LargeBinary => Box::new(byte_accessor::<LargeBinaryType>(array)),
+        DataType::Utf8View => Box::new(byte_view_accessor::<StringViewType>(array)),
+        DataType::BinaryView => Box::new(byte_view_accessor::<BinaryViewType>(array)),
+        DataType::Dictionary(_, _) => downcast_dictionary_array! {
+            array => dictionary_accessor(array)?,
+            data_type => return not_impl_err!("Unsupported join key type {data_type}"),
+        },
+        data_type => return not_impl_err!("Unsupported join key type {data_type}"),
+    };
+    Ok(accessor)
+}
+
+fn byte_accessor<T: ByteArrayType>(array: &ArrayRef) -> ByteKeyAccessor<T> {
+    ByteKeyAccessor(array.as_bytes::<T>().clone())
+}
+
+fn byte_view_accessor<T: ByteViewType>(array: &ArrayRef) -> ByteViewKeyAccessor<T> {
+    let array = array.as_any().downcast_ref::<GenericByteViewArray<T>>();
+    ByteViewKeyAccessor(array.expect("byte view array").clone())
+}
+
+fn dictionary_accessor<K: ArrowDictionaryKeyType>(
+    array: &DictionaryArray<K>,
+) -> Result<Box<dyn KeyAccessor>> {
+    Ok(Box::new(DictionaryKeyAccessor {
+        keys: array.keys().clone(),
+        values: new_key_accessor(array.values())?,
+    }))
+}
+
+/// Downcasts `other` to the accessor type of `this`
+fn downcast<'a, T: KeyAccessor + 'static>(
+    _this: &T,
+    other: &'a dyn KeyAccessor,
+) -> Result<&'a T> {
+    match other.as_any().downcast_ref::<T>() {
+        Some(other) => Ok(other),
+        None => internal_err!("Join keys can not be compared to keys of another type"),
+    }
+}
+
+struct NullKeyAccessor;
+
+impl KeyAccessor for NullKeyAccessor {
+    fn is_null(&self, _row: usize) -> bool {
+        true
+    }
+
+    fn compare(
+        &self,
+        _row: usize,
+        other: &dyn KeyAccessor,
+        _other_row: usize,
+    ) -> Result<Ordering> {
+        downcast(self, other)?;
+        Ok(Ordering::Equal)
+    }
+
+    fn hash_key(&self, _row: usize, _state: &mut dyn Hasher) {}
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+struct PrimitiveKeyAccessor<T: ArrowPrimitiveType>(PrimitiveArray<T>);
+
+impl<T: ArrowPrimitiveType> KeyAccessor for PrimitiveKeyAccessor<T> {
+    fn is_null(&self, row: usize) -> bool {
+        self.0.is_null(row)
+    }
+
+    fn compare(
+        &self,
+        row: usize,
+        other: &dyn KeyAccessor,
+        other_row: usize,
+    ) -> Result<Ordering> {
+        let other = downcast(self, other)?;
+        Ok(self.0.value(row).compare(other.0.value(other_row)))
+    }
+
+    fn hash_key(&self, row: usize, state: &mut dyn Hasher) {
+        state.write(self.0.value(row).to_byte_slice());
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+struct BooleanKeyAccessor(BooleanArray);
+
+impl KeyAccessor for BooleanKeyAccessor {
+    fn is_null(&self, row: usize) -> bool {
+        self.0.is_null(row)
+    }
+
+    fn compare(
+        &self,
+        row: usize,
+        other: &dyn KeyAccessor,
+        other_row: usize,
+    ) -> Result<Ordering> {
+        let other = downcast(self, other)?;
+        Ok(self.0.value(row).cmp(&other.0.value(other_row)))
+    }
+
+    fn hash_key(&self, row: usize, state: &mut dyn Hasher) {
+        state.write_u8(self.0.value(row) as u8);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+struct ByteKeyAccessor<T: ByteArrayType>(GenericByteArray<T>);
+
+impl<T: ByteArrayType> KeyAccessor for ByteKeyAccessor<T> {
+    fn is_null(&self, row: usize) -> bool {
+        self.0.is_null(row)
+    }
+
+    fn compare(
+        &self,
+        row: usize,
+        other: &dyn KeyAccessor,
+        other_row: usize,
+    ) -> Result<Ordering> {
+        let other = downcast(self, other)?;
+        let value: &[u8] = self.0.value(row).as_ref();
+        let other_value: &[u8] = other.0.value(other_row).as_ref();
+        Ok(value.cmp(other_value))
+    }
+
+    fn hash_key(&self, row: usize, state: &mut dyn Hasher) {
+        let value: &[u8] = self.0.value(row).as_ref();
+        value.hash(&mut HasherRef(state));
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+struct ByteViewKeyAccessor<T: ByteViewType + ?Sized>(GenericByteViewArray<T>);
+
+impl<T: ByteViewType + ?Sized> KeyAccessor for ByteViewKeyAccessor<T> {
+    fn is_null(&self, row: usize) -> bool {
+        self.0.is_null(row)
+    }
+
+    fn compare(
+        &self,
+        row: usize,
+        other: &dyn KeyAccessor,
+        other_row: usize,
+    ) -> Result<Ordering> {
+        let other = downcast(self, other)?;
+        let value: &[u8] = self.0.value(row).as_ref();
+        let other_value: &[u8] = other.0.value(other_row).as_ref();
+        Ok(value.cmp(other_value))
+    }
+
+    fn hash_key(&self, row: usize, state: &mut dyn Hasher) {
+        let value: &[u8] = self.0.value(row).as_ref();
+        value.hash(&mut HasherRef(state));
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Accesses the keys of a dictionary column through the accessor of its values
+struct DictionaryKeyAccessor<K: ArrowDictionaryKeyType> {
+    keys: PrimitiveArray<K>,
+    values: Box<dyn KeyAccessor>,
+}
+
+impl<K: ArrowDictionaryKeyType> DictionaryKeyAccessor<K> {
+    fn value_index(&self, row: usize) -> usize {
+        self.keys.value(row).as_usize()
+    }
+}
+
+impl<K: ArrowDictionaryKeyType> KeyAccessor for DictionaryKeyAccessor<K> {
+    fn is_null(&self, row: usize) -> bool {
+        self.keys.is_null(row) || self.values.is_null(self.value_index(row))
+    }
+
+    fn compare(
+        &self,
+        row: usize,
+        other: &dyn KeyAccessor,
+        other_row: usize,
+    ) -> Result<Ordering> {
+        let other = downcast(self, other)?;
+        self.values.compare(
+            self.value_index(row),
+            other.values.as_ref(),
+            other.value_index(other_row),
+        )
+    }
+
+    fn hash_key(&self, row: usize, state: &mut dyn Hasher) {
+        self.values.hash_key(self.value_index(row), state)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Adapts a `dyn Hasher` to the sized hasher [`Hash::hash`] expects
+struct HasherRef<'a>(&'a mut dyn Hasher);
+
+impl Hasher for HasherRef<'_> {
+    fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.write(bytes)
+    }
+}
+
+/// The join key columns of a batch, accessed through a [`KeyAccessor`] each
+#[derive(Default)]
+pub struct JoinKeys {
+    accessors: Vec<Box<dyn KeyAccessor>>,
+    /// Data types of the key columns
+    data_types: Vec<DataType>,
+}
+
+impl fmt::Debug for JoinKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinKeys")
+            .field("num_keys", &self.num_keys())
+            .finish()
+    }
+}
+
+impl JoinKeys {
+    /// Creates the accessors of the join key columns `arrays`
+    pub fn try_new(arrays: &[ArrayRef]) -> Result<Self> {
+        let accessors = arrays.iter().map(new_key_accessor).collect::<Result<_>>()?;
+        let data_types = arrays
+            .iter()
+            .map(|array| array.data_type().clone())
+            .collect();
+        Ok(Self {
+            accessors,
+            data_types,
+        })
+    }
+
+    /// Creates the accessors of the columns `key_columns` of `batch`
+    pub fn try_from_batch(batch: &RecordBatch, key_columns: &[usize]) -> Result<Self> {
+        let arrays = key_columns
+            .iter()
+            .map(|index| batch.column(*index).clone())
+            .collect::<Vec<_>>();
+        Self::try_new(&arrays)
+    }
+
+    /// Number of join key columns
+    pub fn num_keys(&self) -> usize {
+        self.accessors.len()
+    }
+
+    /// Returns whether any key of `row` is null
+    pub fn has_null(&self, row: usize) -> bool {
+        self.accessors.iter().any(|accessor| accessor.is_null(row))
+    }
+
+    /// Compares the keys of `row` to the keys of `other_row` of `other`, in
+    /// the order of `sort_options`, one per key column. Two null keys compare
+    /// equal if `null_equals_null`, and less otherwise. Returns an error if
+    /// the key columns of `other` are of other data types.
+    pub fn compare(
+        &self,
+        row: usize,
+        other: &JoinKeys,
+        other_row: usize,
+        sort_options: &[SortOptions],
+        null_equals_null: bool,
+    ) -> Result<Ordering> {
+        self.compare_with(
+            row,
+            other,
+            other_row,
+            sort_options.iter().copied(),
+            null_equals_null,
+        )
+    }
+
+    fn compare_with(
+        &self,
+        row: usize,
+        other: &JoinKeys,
+        other_row: usize,
+        sort_options: impl Iterator<Item = SortOptions>,
+        null_equals_null: bool,
+    ) -> Result<Ordering> {
+        for ((accessor, other_accessor), sort_options) in self
+            .accessors
+            .iter()
+            .zip(&other.accessors)
+            .zip(sort_options)
+        {
+            let ordering =
+                match (accessor.is_null(row), other_accessor.is_null(other_row)) {
+                    (false, false) => {
+                        let ordering =
+                            accessor.compare(row, other_accessor.as_ref(), other_row)?;
+                        if sort_options.descending {
+                            ordering.reverse()
+                        } else {
+                            ordering
+                        }
+                    }
+                    (true, false) if sort_options.nulls_first => Ordering::Less,
+                    (true, false) => Ordering::Greater,
+                    (false, true) if sort_options.nulls_first => Ordering::Greater,
+                    (false, true) => Ordering::Less,
+                    (true, true) if null_equals_null => Ordering::Equal,
+                    (true, true) => Ordering::Less,
+                };
+            if !ordering.is_eq() {
+                return Ok(ordering);
+            }
+        }
+        Ok(Ordering::Equal)
+    }
+
+    /// Returns whether the keys of `row` equal the keys of `other_row` of
+    /// `other`, where a null key equals a null key. Returns an error if the
+    /// key columns of `other` are of other data types.
+    pub fn equals(&self, row: usize, other: &JoinKeys, other_row: usize) -> Result<bool> {
+        for (accessor, other_accessor) in self.accessors.iter().zip(&other.accessors) {
+            let equal = match (accessor.is_null(row), other_accessor.is_null(other_row)) {
+                (false, false) => accessor
+                    .compare(row, other_accessor.as_ref(), other_row)?
+                    .is_eq(),
+                (null, other_null) => null && other_null,
+            };
+            if !equal {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Returns a handle to the keys of `row`, which can be hashed and compared
+    /// to the keys of other rows, e.g. to look them up in a hash map
+    pub fn key(&self, row: usize) -> JoinKey<'_> {
+        JoinKey { keys: self, row }
+    }
+}
+
+/// Handle to the join keys of a row, see [`JoinKeys::key`]. Handles are equal
+/// if their keys are, where a null key equals a null key, and ordered by their
+/// keys in ascending order with nulls first. Handles to keys of different data
+/// types are never equal, and ordered by their data types.
+#[derive(Debug, Clone, Copy)]
+pub struct JoinKey<'a> {
+    keys: &'a JoinKeys,
+    row: usize,
+}
+
+impl PartialEq for JoinKey<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for JoinKey<'_> {}
+
+impl PartialOrd for JoinKey<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for JoinKey<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let sort_options = std::iter::repeat(SortOptions::default());
+        self.keys
+            .data_types
+            .cmp(&other.keys.data_types)
+            .then_with(|| {
+                // Accessors of the same data types always compare
+                self.keys
+                    .compare_with(self.row, other.keys, other.row, sort_options, true)
+                    .unwrap_or(Ordering::Equal)
+            })
+    }
+}
+
+impl Hash for JoinKey<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for accessor in &self.keys.accessors {
+            let is_null = accessor.is_null(self.row);
+            state.write_u8(is_null as u8);
+            if !is_null {
+                accessor.hash_key(self.row, state);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::joins::test_utils::build_sides_record_batches;
+
+    use arrow::array::{
+        Float64Array, Int32Array, ListArray, StringArray, StringViewArray,
+    };
+    use arrow::compute::cast;
+    use arrow::datatypes::{Field, Int8Type};
+    use datafusion_common::ScalarValue;
+
+    #[test]
+    fn key_accessors_agree_with_scalar_values() -> Result<()> {
+        let (left, _) = build_sides_record_batches(40, (4, 5))?;
+        let strings = Arc::new(StringArray::from_iter(
+            (0..40).map(|i| (i % 6 != 0).then(|| format!("key{}", i % 4))),
+        )) as ArrayRef;
+        let dictionary = cast(
+            &strings,
+            &DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8)),
+        )?;
+        let booleans = Arc::new(BooleanArray::from_iter(
+            (0..40).map(|i| (i % 7 != 0).then_some(i % 2 == 0)),
+        )) as ArrayRef;
+        let mut arrays = left.columns().to_vec();
+        arrays.extend([strings, dictionary, booleans]);
+
+        for array in &arrays {
+            let keys = JoinKeys::try_new(&[array.clone()])?;
+            let values = (0..array.len())
+                .map(|row| ScalarValue::try_from_array(array, row))
+                .collect::<Result<Vec<_>>>()?;
+            for (row, value) in values.iter().enumerate() {
+                assert_eq!(keys.has_null(row), value.is_null());
+                for (other_row, other) in values.iter().enumerate() {
+                    assert_eq!(keys.key(row) == keys.key(other_row), value == other);
+                    if !value.is_null() && !other.is_null() {
+                        assert_eq!(
+                            keys.key(row).cmp(&keys.key(other_row)),
+                            value.partial_cmp(other).unwrap(),
+                            "{value} vs {other}"
+                        );
+                    }
+                }
+            }
+            let distinct_keys = (0..array.len())
+                .map(|row| keys.key(row))
+                .collect::<HashSet<_>>();
+            let distinct_values = values.iter().collect::<HashSet<_>>();
+            assert_eq!(distinct_keys.len(), distinct_values.len());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn compare_composite_keys() -> Result<()> {
+        let (left, right) = build_sides_record_batches(20, (4, 5))?;
+        // lc1 / rc1 and the nullable l_asc_null_first / r_asc_null_first
+        let left_keys = JoinKeys::try_from_batch(&left, &[2, 6])?;
+        let right_keys = JoinKeys::try_from_batch(&right, &[2, 6])?;
+        assert_eq!(left_keys.num_keys(), 2);
+
+        let options = [SortOptions::default(); 2];
+        for row in 0..left.num_rows() {
+            for other_row in 0..right.num_rows() {
+                let expected = [2, 6]
+                    .iter()
+                    .map(|&column| {
+                        let value =
+                            ScalarValue::try_from_array(left.column(column), row)?;
+                        let other =
+                            ScalarValue::try_from_array(right.column(column), other_row)?;
+                        Ok(value == other)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                assert_eq!(
+                    left_keys.equals(row, &right_keys, other_row)?,
+                    expected.iter().all(|equal| *equal)
+                );
+            }
+            // Both keys null in the second column: only equal if nulls are
+            // compared equal
+            if left_keys.has_null(row) {
+                let null_equals_null =
+                    left_keys.compare(row, &left_keys, row, &options, true)?;
+                let null_not_equals_null =
+                    left_keys.compare(row, &left_keys, row, &options, false)?;
+                assert_eq!(null_equals_null, Ordering::Equal);
+                assert_eq!(null_not_equals_null, Ordering::Less);
+            }
+        }
+
+        // Nulls sort according to the sort options, independent of descending
+        let descending_nulls_last = [SortOptions {
+            descending: true,
+            nulls_first: false,
+        }];
+        let keys = JoinKeys::try_new(&[left.column(6).clone()])?;
+        let (null_row, valid_row) = (0, left.num_rows() - 1);
+        assert!(keys.has_null(null_row) && !keys.has_null(valid_row));
+        let ordering =
+            keys.compare(null_row, &keys, valid_row, &descending_nulls_last, false)?;
+        assert_eq!(ordering, Ordering::Greater);
+        let ordering = keys.compare(
+            valid_row,
+            &keys,
+            valid_row - 1,
+            &descending_nulls_last,
+            false,
+        )?;
+        assert_eq!(ordering, Ordering::Less);
+        Ok(())
+    }
+
+    #[test]
+    fn key_accessor_semantics() -> Result<()> {
+        // Floats compare by their total order, so that NaN equals NaN
+        let floats =
+            Arc::new(Float64Array::from(vec![f64::NAN, f64::NAN, 1.0])) as ArrayRef;
+        let keys = JoinKeys::try_new(&[floats])?;
+        assert!(keys.key(0) == keys.key(1));
+        assert!(keys.key(2) < keys.key(0));
+
+        // Dictionaries compare by value, also across different dictionaries
+        let left = Arc::new(
+            vec![Some("b"), None, Some("a")]
+                .into_iter()
+                .collect::<DictionaryArray<Int8Type>>(),
+        ) as ArrayRef;
+        let right = Arc::new(
+            vec![Some("a"), Some("b")]
+                .into_iter()
+                .collect::<DictionaryArray<Int8Type>>(),
+        ) as ArrayRef;
+        let (left, right) = (JoinKeys::try_new(&[left])?, JoinKeys::try_new(&[right])?);
+        assert!(left.equals(0, &right, 1)?);
+        assert!(left.equals(2, &right, 0)?);
+        assert!(!left.equals(1, &right, 0)?);
+
+        // Views compare by value, whether inlined or not
+        let long = "a string longer than twelve bytes";
+        let views =
+            Arc::new(StringViewArray::from(vec![long, "short", long])) as ArrayRef;
+        let keys = JoinKeys::try_new(&[views])?;
+        assert!(keys.key(0) == keys.key(2));
+        assert!(keys.key(0) < keys.key(1));
+
+        let field = Arc::new(Field::new("item", DataType::Int32, true));
+        let list = Arc::new(ListArray::new_null(field, 1)) as ArrayRef;
+        assert!(JoinKeys::try_new(&[list]).is_err());
+
+        // Keys of different types do not compare, nor are their handles equal
+        let strings = Arc::new(StringArray::from(vec!["1"])) as ArrayRef;
+        let integers = Arc::new(Int32Array::from(vec![1])) as ArrayRef;
+        let (strings, integers) = (
+            JoinKeys::try_new(&[strings])?,
+            JoinKeys::try_new(&[integers])?,
+        );
+        let err = strings.equals(0, &integers, 0).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Join keys can not be compared to keys of another type"));
+        assert!(strings.key(0) != integers.key(0));
+        Ok(())
+    }
+}
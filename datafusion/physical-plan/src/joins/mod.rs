@@ -19,13 +19,18 @@
 
 pub use cross_join::CrossJoinExec;
 pub use hash_join::HashJoinExec;
+pub use key_accessor::{new_key_accessor, JoinKey, JoinKeys, KeyAccessor};
 pub use nested_loop_join::NestedLoopJoinExec;
+pub use runtime_filter::{RuntimeFilter, RuntimeFilterKey, RuntimeFilterRegistry};
 // Note: SortMergeJoin is not used in plans yet
 pub use sort_merge_join::SortMergeJoinExec;
-pub use symmetric_hash_join::SymmetricHashJoinExec;
+pub use symmetric_hash_join::{SymmetricHashJoinExec, WatermarkTrigger};
 mod cross_join;
 mod hash_join;
+mod key_accessor;
 mod nested_loop_join;
+mod output_shaping;
+mod runtime_filter;
 mod sort_merge_join;
 mod stream_join_utils;
 mod symmetric_hash_join;
@@ -46,6 +51,28 @@ pub enum PartitionMode {
     Auto,
 }
 
+/// Collation used to compare string join keys
+#[derive(Hash, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Collation {
+    /// Strings are equal if their bytes are equal
+    #[default]
+    Binary,
+    /// Strings are equal if they are equal after converting them to lowercase
+    CaseInsensitive,
+}
+
+/// Semantics float join keys are hashed and compared under
+#[derive(Hash, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FloatKeyMode {
+    /// Floats are equal if their bits are equal: `-0.0` and `+0.0` are
+    /// distinct keys, and a NaN matches a NaN of the same bits
+    #[default]
+    TotalOrder,
+    /// Floats are equal if they compare equal under IEEE 754: `-0.0` matches
+    /// `+0.0`, and a NaN matches nothing
+    Ieee,
+}
+
 /// Partitioning mode to use for symmetric hash join
 #[derive(Hash, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum StreamJoinPartitionMode {
@@ -26,26 +26,26 @@ use std::task::Poll;
 
 use crate::coalesce_batches::concat_batches;
 use crate::joins::utils::{
-    append_right_indices, apply_join_filter_to_indices, build_batch_from_indices,
-    build_join_schema, check_join_is_valid, estimate_join_statistics, get_anti_indices,
-    get_final_indices_from_bit_map, get_semi_indices,
-    partitioned_join_output_partitioning, BuildProbeJoinMetrics, ColumnIndex, JoinFilter,
-    OnceAsync, OnceFut,
+    adjust_right_output_partitioning, append_right_indices, apply_join_filter_to_indices,
+    build_batch_from_indices, build_join_schema, check_join_is_valid,
+    estimate_join_statistics, get_anti_indices, get_final_indices_from_bit_map,
+    get_semi_indices, join_metrics_json, limit_output_rows,
+    partitioned_join_output_partitioning, skip_empty_batches, BuildProbeJoinMetrics,
+    ColumnIndex, JoinFilter, MatchBitmap, NullPadding, OnceAsync, OnceFut,
+    OutputRowLimit,
 };
 use crate::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use crate::stream::RecordBatchStreamAdapter;
 use crate::{
     execution_mode_from_children, DisplayAs, DisplayFormatType, Distribution,
-    ExecutionMode, ExecutionPlan, ExecutionPlanProperties, PlanProperties,
+    ExecutionMode, ExecutionPlan, ExecutionPlanProperties, Partitioning, PlanProperties,
     RecordBatchStream, SendableRecordBatchStream,
 };
 
-use arrow::array::{
-    BooleanBufferBuilder, UInt32Array, UInt32Builder, UInt64Array, UInt64Builder,
-};
+use arrow::array::{UInt32Array, UInt32Builder, UInt64Array, UInt64Builder};
 use arrow::datatypes::{Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
-use arrow::util::bit_util;
-use datafusion_common::{exec_err, JoinSide, Result, Statistics};
+use datafusion_common::{exec_err, not_impl_err, plan_err, JoinSide, Result, Statistics};
 use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion_execution::TaskContext;
 use datafusion_expr::JoinType;
@@ -75,6 +75,23 @@ type JoinLeftData = (RecordBatch, MemoryReservation);
 /// | Right/RightSemi/RightAnti/Full | (SinglePartition, UnspecifiedDistribution) | left        |
 /// | Full                           | (SinglePartition, SinglePartition)         | left        |
 ///
+/// In the streaming right mode, see [`NestedLoopJoinExec::with_stream_right`],
+/// `Inner` and `Left` joins buffer the left side instead and stream the right:
+///
+/// | JoinType | Distribution (left, right)                 | Inner-table |
+/// |----------|--------------------------------------------|-------------|
+/// | Inner    | (SinglePartition, UnspecifiedDistribution) | left        |
+/// | Left     | (SinglePartition, SinglePartition)         | left        |
+///
+/// In the bucketed mode, see [`NestedLoopJoinExec::with_adjacent_buckets`],
+/// both inputs are [`Partitioning::Bucketed`], and every right partition is
+/// joined with the left partitions of its own and of the adjacent buckets only:
+///
+/// ```text
+/// for right-batch in right-partition-x
+///     check-join(right-batch, left-partitions-of-buckets-next-to-x)
+/// ```
+///
 #[derive(Debug)]
 pub struct NestedLoopJoinExec {
     /// left side
@@ -93,6 +110,24 @@ pub struct NestedLoopJoinExec {
     column_indices: Vec<ColumnIndex>,
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
+    /// Limit on the number of output rows across all partitions, see
+    /// [`Self::with_max_output_rows`]
+    output_row_limit: Option<OutputRowLimit>,
+    /// Whether zero-row batches are dropped from the output, see
+    /// [`Self::with_skip_empty_output_batches`]
+    skip_empty_output_batches: bool,
+    /// Whether to buffer the smaller input according to statistics, see
+    /// [`Self::with_buffer_smaller_side`]
+    buffer_smaller_side: bool,
+    /// Whether the left side is buffered and the right side streamed, see
+    /// [`Self::with_stream_right`]
+    stream_right: bool,
+    /// Number of adjacent buckets on either side whose left partitions each
+    /// right partition is joined with, see [`Self::with_adjacent_buckets`]
+    adjacent_buckets: Option<usize>,
+    /// Data of each left partition in the bucketed mode, shared by the right
+    /// partitions of the adjacent buckets
+    bucket_tables: Vec<OnceAsync<JoinLeftData>>,
     /// Cache holding plan properties like equivalences, output partitioning etc.
     cache: PlanProperties,
 }
@@ -105,13 +140,28 @@ impl NestedLoopJoinExec {
         filter: Option<JoinFilter>,
         join_type: &JoinType,
     ) -> Result<Self> {
+        if matches!(join_type, JoinType::LeftMark | JoinType::RightMark) {
+            return not_impl_err!(
+                "NestedLoopJoinExec does not support JoinType::{join_type}"
+            );
+        }
         let left_schema = left.schema();
         let right_schema = right.schema();
         check_join_is_valid(&left_schema, &right_schema, &[])?;
+        if let Some(filter) = &filter {
+            filter.validate(&left_schema, &right_schema)?;
+        }
         let (schema, column_indices) =
             build_join_schema(&left_schema, &right_schema, join_type);
         let schema = Arc::new(schema);
-        let cache = Self::compute_properties(&left, &right, schema.clone(), *join_type);
+        let cache = Self::compute_properties(
+            &left,
+            &right,
+            schema.clone(),
+            *join_type,
+            false,
+            false,
+        );
         Ok(NestedLoopJoinExec {
             left,
             right,
@@ -121,10 +171,140 @@ impl NestedLoopJoinExec {
             inner_table: Default::default(),
             column_indices,
             metrics: Default::default(),
+            output_row_limit: None,
+            skip_empty_output_batches: false,
+            buffer_smaller_side: false,
+            stream_right: false,
+            adjacent_buckets: None,
+            bucket_tables: vec![],
             cache,
         })
     }
 
+    /// Buffers only the left side and streams the right side, emitting the
+    /// joined rows of every right batch as it arrives, instead of buffering
+    /// the right side. This suits a small, bounded left side joined with a
+    /// large or unbounded right side.
+    ///
+    /// Only `Inner` and `Left` joins can stream the right side. A `Left` join
+    /// emits the unmatched left rows once the right side is exhausted, so its
+    /// right side must be a single partition; an `Inner` join streams every
+    /// right partition. The inputs are never swapped in this mode, see
+    /// [`Self::with_buffer_smaller_side`].
+    pub fn with_stream_right(mut self, stream_right: bool) -> Result<Self> {
+        if stream_right && !matches!(self.join_type, JoinType::Inner | JoinType::Left) {
+            return plan_err!(
+                "NestedLoopJoinExec can only stream the right side of Inner and Left joins, not {}",
+                self.join_type
+            );
+        }
+        if stream_right && self.adjacent_buckets.is_some() {
+            return plan_err!(
+                "NestedLoopJoinExec cannot stream the right side of a bucketed join"
+            );
+        }
+        self.stream_right = stream_right;
+        self.cache = Self::compute_properties(
+            &self.left,
+            &self.right,
+            self.schema.clone(),
+            self.join_type,
+            stream_right,
+            self.adjacent_buckets.is_some(),
+        );
+        Ok(self)
+    }
+
+    /// Joins [`Partitioning::Bucketed`] inputs bucket by bucket: every right
+    /// partition is joined with the left partitions of its own bucket and of
+    /// the `adjacent_buckets` buckets preceding and following it, rather than
+    /// with the whole left side. This suits temporal range joins whose filter
+    /// only matches rows at most `adjacent_buckets` buckets apart, e.g. rows
+    /// within an hour of each other, bucketed by the hour of their timestamps.
+    /// Matches of rows further apart may be missed.
+    ///
+    /// Both inputs must be bucketed into the same number of partitions. Each
+    /// left partition is read once and shared by the right partitions it is
+    /// joined with. Only joins that don't emit unmatched left rows can be
+    /// bucketed, i.e. `Inner`, `Right`, `RightSemi` and `RightAnti` joins, and
+    /// their output keeps the partitioning of the right side.
+    pub fn with_adjacent_buckets(mut self, adjacent_buckets: usize) -> Result<Self> {
+        if !matches!(
+            self.join_type,
+            JoinType::Inner | JoinType::Right | JoinType::RightSemi | JoinType::RightAnti
+        ) {
+            return plan_err!(
+                "NestedLoopJoinExec can only join the buckets of Inner, Right, RightSemi and RightAnti joins, not {}",
+                self.join_type
+            );
+        }
+        if self.stream_right {
+            return plan_err!(
+                "NestedLoopJoinExec cannot stream the right side of a bucketed join"
+            );
+        }
+        let partition_count = match (
+            self.left.output_partitioning(),
+            self.right.output_partitioning(),
+        ) {
+            (Partitioning::Bucketed(_, left), Partitioning::Bucketed(_, right))
+                if left == right =>
+            {
+                *left
+            }
+            (left, right) => {
+                return plan_err!(
+                    "Joining adjacent buckets requires inputs bucketed into the same number of partitions, got {left} and {right}"
+                )
+            }
+        };
+        self.adjacent_buckets = Some(adjacent_buckets);
+        self.bucket_tables = (0..partition_count).map(|_| Default::default()).collect();
+        self.cache = Self::compute_properties(
+            &self.left,
+            &self.right,
+            self.schema.clone(),
+            self.join_type,
+            self.stream_right,
+            true,
+        );
+        Ok(self)
+    }
+
+    /// Buffers whichever input is smaller according to the `statistics()` of
+    /// both inputs at execution time, swapping the inputs (and the filter)
+    /// if that is the side which would be streamed otherwise. The output is
+    /// the same as without swapping.
+    ///
+    /// Only joins whose buffered side is not dictated by the join type can be
+    /// swapped, i.e. `Full` joins and `Inner` joins over single partition
+    /// inputs. If statistics are unavailable, the inputs are never swapped.
+    pub fn with_buffer_smaller_side(mut self, buffer_smaller_side: bool) -> Self {
+        self.buffer_smaller_side = buffer_smaller_side;
+        self
+    }
+
+    /// Fails the execution with `ResourcesExhausted` once the output rows of
+    /// all partitions together exceed `max_output_rows`, see
+    /// [`HashJoinExec::with_max_output_rows`]. By default, the output is
+    /// unlimited.
+    ///
+    /// [`HashJoinExec::with_max_output_rows`]: crate::joins::HashJoinExec::with_max_output_rows
+    pub fn with_max_output_rows(mut self, max_output_rows: usize) -> Self {
+        self.output_row_limit = Some(OutputRowLimit::new(max_output_rows));
+        self
+    }
+
+    /// Drops zero-row batches from the output instead of emitting them, see
+    /// [`HashJoinExec::with_skip_empty_output_batches`]. By default, batches
+    /// are emitted as produced, including empty ones.
+    ///
+    /// [`HashJoinExec::with_skip_empty_output_batches`]: crate::joins::HashJoinExec::with_skip_empty_output_batches
+    pub fn with_skip_empty_output_batches(mut self, skip: bool) -> Self {
+        self.skip_empty_output_batches = skip;
+        self
+    }
+
     /// left side
     pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
         &self.left
@@ -145,12 +325,183 @@ impl NestedLoopJoinExec {
         &self.join_type
     }
 
+    /// The metrics of this join as a JSON object suitable for machine
+    /// ingestion, see [`JOIN_METRICS_JSON_VERSION`] for its stability
+    ///
+    /// [`JOIN_METRICS_JSON_VERSION`]: crate::joins::utils::JOIN_METRICS_JSON_VERSION
+    pub fn metrics_json(&self) -> serde_json::Value {
+        join_metrics_json(self.name(), &self.join_type, None, &self.metrics)
+    }
+
+    /// Maximum number of output rows across all partitions, if limited
+    pub fn max_output_rows(&self) -> Option<usize> {
+        self.output_row_limit.as_ref().map(|limit| limit.max_rows())
+    }
+
+    /// Whether zero-row batches are dropped from the output
+    pub fn skip_empty_output_batches(&self) -> bool {
+        self.skip_empty_output_batches
+    }
+
+    /// Whether the smaller input is buffered, see [`Self::with_buffer_smaller_side`]
+    pub fn buffer_smaller_side(&self) -> bool {
+        self.buffer_smaller_side
+    }
+
+    /// Whether the right side is streamed, see [`Self::with_stream_right`]
+    pub fn stream_right(&self) -> bool {
+        self.stream_right
+    }
+
+    /// Number of adjacent buckets on either side joined with each bucket, if
+    /// the inputs are joined bucket by bucket, see [`Self::with_adjacent_buckets`]
+    pub fn adjacent_buckets(&self) -> Option<usize> {
+        self.adjacent_buckets
+    }
+
+    /// Whether the left side is buffered as the inner table
+    fn left_is_inner_table(&self) -> bool {
+        self.stream_right
+            || self.adjacent_buckets.is_some()
+            || left_is_build_side(self.join_type)
+    }
+
+    /// Calculate order preservation flags for this join. Left semi and anti
+    /// joins emit the qualifying rows of every streamed left batch in their
+    /// input order. Since each output partition streams exactly one left
+    /// partition, rows of different partitions are never interleaved.
+    fn maintains_input_order(join_type: JoinType) -> Vec<bool> {
+        vec![
+            matches!(join_type, JoinType::LeftSemi | JoinType::LeftAnti),
+            false,
+        ]
+    }
+
+    /// Checks whether the inputs should be swapped at execution time so that
+    /// the smaller input is buffered, see [`Self::with_buffer_smaller_side`].
+    fn should_swap_inputs(&self) -> Result<bool> {
+        let swappable = match self.join_type {
+            JoinType::Full => true,
+            JoinType::Inner => self.left.output_partitioning().partition_count() == 1,
+            _ => false,
+        };
+        if !self.buffer_smaller_side
+            || self.stream_right
+            || self.adjacent_buckets.is_some()
+            || !swappable
+        {
+            return Ok(false);
+        }
+        let (buffered, streamed) = if left_is_build_side(self.join_type) {
+            (self.left.statistics()?, self.right.statistics()?)
+        } else {
+            (self.right.statistics()?, self.left.statistics()?)
+        };
+        // Prefer `total_byte_size` and fall back to `num_rows`:
+        match (
+            buffered.total_byte_size.get_value(),
+            streamed.total_byte_size.get_value(),
+        ) {
+            (Some(buffered), Some(streamed)) => Ok(streamed < buffered),
+            _ => match (buffered.num_rows.get_value(), streamed.num_rows.get_value()) {
+                (Some(buffered), Some(streamed)) => Ok(streamed < buffered),
+                _ => Ok(false),
+            },
+        }
+    }
+
+    /// Executes the join with swapped inputs, reverting the column order of
+    /// its output. Swappable joins have a single output partition.
+    fn execute_swapped(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let mut swapped = NestedLoopJoinExec::try_new(
+            self.right.clone(),
+            self.left.clone(),
+            self.filter.as_ref().map(JoinFilter::swap_sides),
+            &self.join_type,
+        )?;
+        // Report the metrics of the swapped join as those of this join:
+        swapped.metrics = self.metrics.clone();
+
+        let left_len = self.left.schema().fields().len();
+        let right_len = self.right.schema().fields().len();
+        let indices = (right_len..right_len + left_len)
+            .chain(0..right_len)
+            .collect::<Vec<_>>();
+        let schema = self.schema.clone();
+        let stream = swapped.execute(partition, context)?.map(move |batch| {
+            let batch = batch?;
+            let columns = indices.iter().map(|i| batch.column(*i).clone()).collect();
+            Ok(RecordBatch::try_new(schema.clone(), columns)?)
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            stream,
+        )))
+    }
+
+    /// Loads the left partitions of the bucket of the right `partition` and of
+    /// its adjacent buckets into a single inner table, see
+    /// [`Self::with_adjacent_buckets`]
+    fn load_adjacent_buckets(
+        &self,
+        partition: usize,
+        adjacent_buckets: usize,
+        context: &Arc<TaskContext>,
+        join_metrics: &BuildProbeJoinMetrics,
+        mut reservation: MemoryReservation,
+    ) -> OnceFut<JoinLeftData> {
+        let partition_count = self.bucket_tables.len();
+        // Each partition is loaded at most once, even if there are fewer
+        // partitions than buckets to join:
+        let bucket_partitions =
+            (0..(2 * adjacent_buckets + 1).min(partition_count)).map(|offset| {
+                Partitioning::bucket_partition(
+                    partition as i64 - adjacent_buckets as i64 + offset as i64,
+                    partition_count,
+                )
+            });
+        let tables = bucket_partitions
+            .map(|bucket_partition| {
+                self.bucket_tables[bucket_partition].once(|| {
+                    load_specified_partition_of_input(
+                        bucket_partition,
+                        self.left.clone(),
+                        context.clone(),
+                        join_metrics.clone(),
+                        MemoryConsumer::new(format!(
+                            "NestedLoopJoinLoad[{bucket_partition}]"
+                        ))
+                        .register(context.memory_pool()),
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        let schema = self.left.schema();
+        OnceFut::new(async move {
+            let mut batches = Vec::with_capacity(tables.len());
+            for mut table in tables {
+                let table = futures::future::poll_fn(|cx| table.get_shared(cx)).await?;
+                batches.push(table.0.clone());
+            }
+            let num_rows = batches.iter().map(RecordBatch::num_rows).sum();
+            let merged_batch = concat_batches(&schema, &batches, num_rows)?;
+            reservation.try_grow(merged_batch.get_array_memory_size())?;
+            Ok((merged_batch, reservation))
+        })
+    }
+
     /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
     fn compute_properties(
         left: &Arc<dyn ExecutionPlan>,
         right: &Arc<dyn ExecutionPlan>,
         schema: SchemaRef,
         join_type: JoinType,
+        stream_right: bool,
+        bucketed: bool,
     ) -> PlanProperties {
         // Calculate equivalence properties:
         let eq_properties = join_equivalence_properties(
@@ -158,7 +509,7 @@ impl NestedLoopJoinExec {
             right.equivalence_properties().clone(),
             &join_type,
             schema,
-            &[false, false],
+            &Self::maintains_input_order(join_type),
             None,
             // No on columns in nested loop join
             &[],
@@ -167,6 +518,18 @@ impl NestedLoopJoinExec {
         // Get output partitioning,
         let output_partitioning = if join_type == JoinType::Full {
             left.output_partitioning().clone()
+        } else if stream_right || (bucketed && join_type == JoinType::Inner) {
+            // The right side is streamed, and its columns are null for the
+            // unmatched left rows of a left join:
+            match join_type {
+                JoinType::Inner => adjust_right_output_partitioning(
+                    right.output_partitioning(),
+                    left.schema().fields.len(),
+                ),
+                _ => Partitioning::UnknownPartitioning(
+                    right.output_partitioning().partition_count(),
+                ),
+            }
         } else {
             partitioned_join_output_partitioning(
                 join_type,
@@ -176,9 +539,14 @@ impl NestedLoopJoinExec {
             )
         };
 
-        // Determine execution mode:
+        // Determine execution mode. Only the streamed right side of an inner
+        // join may be unbounded without breaking the pipeline:
         let mut mode = execution_mode_from_children([left, right]);
-        if mode.is_unbounded() {
+        if mode.is_unbounded()
+            && !(stream_right
+                && join_type == JoinType::Inner
+                && !left.execution_mode().is_unbounded())
+        {
             mode = ExecutionMode::PipelineBreaking;
         }
 
@@ -194,10 +562,21 @@ impl DisplayAs for NestedLoopJoinExec {
                     || "".to_string(),
                     |f| format!(", filter={}", f.expression()),
                 );
+                let display_stream_right = if self.stream_right {
+                    ", stream_right=true"
+                } else {
+                    ""
+                };
+                let display_adjacent_buckets = self
+                    .adjacent_buckets
+                    .map_or_else(String::new, |n| format!(", adjacent_buckets={n}"));
                 write!(
                     f,
-                    "NestedLoopJoinExec: join_type={:?}{}",
-                    self.join_type, display_filter
+                    "NestedLoopJoinExec: join_type={:?}{}{}{}",
+                    self.join_type,
+                    display_filter,
+                    display_stream_right,
+                    display_adjacent_buckets
                 )
             }
         }
@@ -218,9 +597,38 @@ impl ExecutionPlan for NestedLoopJoinExec {
     }
 
     fn required_input_distribution(&self) -> Vec<Distribution> {
+        if self.adjacent_buckets.is_some() {
+            // Both inputs keep their buckets, see `benefits_from_input_partitioning`:
+            return vec![Distribution::UnspecifiedDistribution; 2];
+        }
+        if self.stream_right {
+            // Unmatched left rows are only known once the whole right side
+            // is processed:
+            let right = if self.join_type == JoinType::Left {
+                Distribution::SinglePartition
+            } else {
+                Distribution::UnspecifiedDistribution
+            };
+            return vec![Distribution::SinglePartition, right];
+        }
         distribution_from_join_type(&self.join_type)
     }
 
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        if self.adjacent_buckets.is_some() {
+            // Repartitioning either input would break up its buckets:
+            return vec![false, false];
+        }
+        self.required_input_distribution()
+            .into_iter()
+            .map(|dist| !matches!(dist, Distribution::SinglePartition))
+            .collect()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        Self::maintains_input_order(self.join_type)
+    }
+
     fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
         vec![self.left.clone(), self.right.clone()]
     }
@@ -229,12 +637,23 @@ impl ExecutionPlan for NestedLoopJoinExec {
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(NestedLoopJoinExec::try_new(
+        let mut join = NestedLoopJoinExec::try_new(
             children[0].clone(),
             children[1].clone(),
             self.filter.clone(),
             &self.join_type,
-        )?))
+        )?;
+        join.output_row_limit = self
+            .output_row_limit
+            .as_ref()
+            .map(|limit| OutputRowLimit::new(limit.max_rows()));
+        join.skip_empty_output_batches = self.skip_empty_output_batches;
+        join.buffer_smaller_side = self.buffer_smaller_side;
+        let mut join = join.with_stream_right(self.stream_right)?;
+        if let Some(adjacent_buckets) = self.adjacent_buckets {
+            join = join.with_adjacent_buckets(adjacent_buckets)?;
+        }
+        Ok(Arc::new(join))
     }
 
     fn execute(
@@ -242,6 +661,14 @@ impl ExecutionPlan for NestedLoopJoinExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
+        if self.should_swap_inputs()? {
+            let stream = self.execute_swapped(partition, context)?;
+            return Ok(limit_output_rows(
+                skip_empty_batches(stream, self.skip_empty_output_batches),
+                self.output_row_limit.as_ref(),
+                partition,
+            ));
+        }
         let join_metrics = BuildProbeJoinMetrics::new(partition, &self.metrics);
 
         // Initialization reservation for load of inner table
@@ -253,47 +680,70 @@ impl ExecutionPlan for NestedLoopJoinExec {
         let reservation =
             MemoryConsumer::new(format!("NestedLoopJoinStream[{partition}]"))
                 .register(context.memory_pool());
+        let null_padding = NullPadding::new(
+            MemoryConsumer::new(format!("NestedLoopJoinNullPadding[{partition}]"))
+                .register(context.memory_pool()),
+        );
 
-        let (outer_table, inner_table) = if left_is_build_side(self.join_type) {
-            // left must be single partition
-            let inner_table = self.inner_table.once(|| {
-                load_specified_partition_of_input(
-                    0,
-                    self.left.clone(),
-                    context.clone(),
-                    join_metrics.clone(),
+        let left_is_inner_table = self.left_is_inner_table();
+        let (outer_table, inner_table) =
+            if let Some(adjacent_buckets) = self.adjacent_buckets {
+                let inner_table = self.load_adjacent_buckets(
+                    partition,
+                    adjacent_buckets,
+                    &context,
+                    &join_metrics,
                     load_reservation,
-                )
-            });
-            let outer_table = self.right.execute(partition, context)?;
-            (outer_table, inner_table)
-        } else {
-            // right must be single partition
-            let inner_table = self.inner_table.once(|| {
-                load_specified_partition_of_input(
-                    0,
-                    self.right.clone(),
-                    context.clone(),
-                    join_metrics.clone(),
-                    load_reservation,
-                )
-            });
-            let outer_table = self.left.execute(partition, context)?;
-            (outer_table, inner_table)
-        };
+                );
+                let outer_table = self.right.execute(partition, context)?;
+                (outer_table, inner_table)
+            } else if left_is_inner_table {
+                // left must be single partition
+                let inner_table = self.inner_table.once(|| {
+                    load_specified_partition_of_input(
+                        0,
+                        self.left.clone(),
+                        context.clone(),
+                        join_metrics.clone(),
+                        load_reservation,
+                    )
+                });
+                let outer_table = self.right.execute(partition, context)?;
+                (outer_table, inner_table)
+            } else {
+                // right must be single partition
+                let inner_table = self.inner_table.once(|| {
+                    load_specified_partition_of_input(
+                        0,
+                        self.right.clone(),
+                        context.clone(),
+                        join_metrics.clone(),
+                        load_reservation,
+                    )
+                });
+                let outer_table = self.left.execute(partition, context)?;
+                (outer_table, inner_table)
+            };
 
-        Ok(Box::pin(NestedLoopJoinStream {
+        let stream = Box::pin(NestedLoopJoinStream {
             schema: self.schema.clone(),
             filter: self.filter.clone(),
             join_type: self.join_type,
             outer_table,
             inner_table,
+            left_is_inner_table,
             is_exhausted: false,
             visited_left_side: None,
             column_indices: self.column_indices.clone(),
             join_metrics,
             reservation,
-        }))
+            null_padding,
+        });
+        Ok(limit_output_rows(
+            skip_empty_batches(stream, self.skip_empty_output_batches),
+            self.output_row_limit.as_ref(),
+            partition,
+        ))
     }
 
     fn metrics(&self) -> Option<MetricsSet> {
@@ -315,14 +765,21 @@ impl ExecutionPlan for NestedLoopJoinExec {
 // left and right node.
 fn distribution_from_join_type(join_type: &JoinType) -> Vec<Distribution> {
     match join_type {
-        JoinType::Inner | JoinType::Left | JoinType::LeftSemi | JoinType::LeftAnti => {
+        JoinType::Inner
+        | JoinType::Left
+        | JoinType::LeftSemi
+        | JoinType::LeftAnti
+        | JoinType::LeftMark => {
             // need the left data, and the right should be one partition
             vec![
                 Distribution::UnspecifiedDistribution,
                 Distribution::SinglePartition,
             ]
         }
-        JoinType::Right | JoinType::RightSemi | JoinType::RightAnti => {
+        JoinType::Right
+        | JoinType::RightSemi
+        | JoinType::RightAnti
+        | JoinType::RightMark => {
             // need the right data, and the left should be one partition
             vec![
                 Distribution::SinglePartition,
@@ -392,10 +849,12 @@ struct NestedLoopJoinStream {
     outer_table: SendableRecordBatchStream,
     /// the inner table data of the nested loop join
     inner_table: OnceFut<JoinLeftData>,
+    /// Whether the inner table is the left side
+    left_is_inner_table: bool,
     /// There is nothing to process anymore and left side is processed in case of full join
     is_exhausted: bool,
     /// Keeps track of the left side rows whether they are visited
-    visited_left_side: Option<BooleanBufferBuilder>,
+    visited_left_side: Option<MatchBitmap>,
     /// Information of index and left / right placement of columns
     column_indices: Vec<ColumnIndex>,
     // TODO: support null aware equal
@@ -404,6 +863,8 @@ struct NestedLoopJoinStream {
     join_metrics: BuildProbeJoinMetrics,
     /// Memory reservation for visited_left_side
     reservation: MemoryReservation,
+    /// Null arrays padding the columns of the side without a match
+    null_padding: NullPadding,
 }
 
 fn build_join_indices(
@@ -434,7 +895,8 @@ fn build_join_indices(
 }
 
 impl NestedLoopJoinStream {
-    /// For Right/RightSemi/RightAnti/Full joins, left is the single partition side.
+    /// For Right/RightSemi/RightAnti/Full joins, and Inner/Left joins streaming
+    /// the right side, left is the single partition side.
     fn poll_next_impl_for_build_left(
         &mut self,
         cx: &mut std::task::Context<'_>,
@@ -447,10 +909,10 @@ impl NestedLoopJoinStream {
         };
         build_timer.done();
 
-        if self.visited_left_side.is_none() && self.join_type == JoinType::Full {
-            // TODO: Replace `ceil` wrapper with stable `div_cell` after
-            // https://github.com/rust-lang/rust/issues/88581
-            let visited_bitmap_size = bit_util::ceil(left_data.num_rows(), 8);
+        // Full and left joins produce the unmatched left rows at the end:
+        let track_visited = matches!(self.join_type, JoinType::Full | JoinType::Left);
+        if self.visited_left_side.is_none() && track_visited {
+            let visited_bitmap_size = MatchBitmap::size(left_data.num_rows());
             self.reservation.try_grow(visited_bitmap_size)?;
             self.join_metrics.build_mem_used.add(visited_bitmap_size);
         }
@@ -458,13 +920,10 @@ impl NestedLoopJoinStream {
         // add a bitmap for full join.
         let visited_left_side = self.visited_left_side.get_or_insert_with(|| {
             let left_num_rows = left_data.num_rows();
-            // only full join need bitmap
-            if self.join_type == JoinType::Full {
-                let mut buffer = BooleanBufferBuilder::new(left_num_rows);
-                buffer.append_n(left_num_rows, false);
-                buffer
+            if track_visited {
+                MatchBitmap::new(left_num_rows)
             } else {
-                BooleanBufferBuilder::new(0)
+                MatchBitmap::new(0)
             }
         });
 
@@ -477,14 +936,23 @@ impl NestedLoopJoinStream {
                     self.join_metrics.input_rows.add(right_batch.num_rows());
                     let timer = self.join_metrics.join_time.timer();
 
+                    // Unmatched left rows are not known before the right side
+                    // is exhausted, so a left join only emits the matches:
+                    let batch_join_type = if self.join_type == JoinType::Left {
+                        JoinType::Inner
+                    } else {
+                        self.join_type
+                    };
                     let result = join_left_and_right_batch(
                         left_data,
                         &right_batch,
-                        self.join_type,
+                        batch_join_type,
                         self.filter.as_ref(),
                         &self.column_indices,
                         &self.schema,
                         visited_left_side,
+                        &self.join_metrics,
+                        &mut self.null_padding,
                     );
 
                     // Recording time & updating output metrics
@@ -498,9 +966,10 @@ impl NestedLoopJoinStream {
                 }
                 Some(err) => Some(err),
                 None => {
-                    if self.join_type == JoinType::Full && !self.is_exhausted {
+                    if track_visited && !self.is_exhausted {
                         // Only setting up timer, input is exhausted
                         let timer = self.join_metrics.join_time.timer();
+                        let output_timer = self.join_metrics.output_time.timer();
 
                         // use the global left bitmap to produce the left indices and right indices
                         let (left_side, right_side) = get_final_indices_from_bit_map(
@@ -518,7 +987,9 @@ impl NestedLoopJoinStream {
                             &right_side,
                             &self.column_indices,
                             JoinSide::Left,
+                            &mut self.null_padding,
                         );
+                        output_timer.done();
                         self.is_exhausted = true;
 
                         // Recording time & updating output metrics
@@ -551,7 +1022,7 @@ impl NestedLoopJoinStream {
         build_timer.done();
 
         // for build right, bitmap is not needed.
-        let mut empty_visited_left_side = BooleanBufferBuilder::new(0);
+        let mut empty_visited_left_side = MatchBitmap::new(0);
         self.outer_table
             .poll_next_unpin(cx)
             .map(|maybe_batch| match maybe_batch {
@@ -570,6 +1041,8 @@ impl NestedLoopJoinStream {
                         &self.column_indices,
                         &self.schema,
                         &mut empty_visited_left_side,
+                        &self.join_metrics,
+                        &mut self.null_padding,
                     );
 
                     // Recording time & updating output metrics
@@ -587,6 +1060,7 @@ impl NestedLoopJoinStream {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn join_left_and_right_batch(
     left_batch: &RecordBatch,
     right_batch: &RecordBatch,
@@ -594,8 +1068,11 @@ fn join_left_and_right_batch(
     filter: Option<&JoinFilter>,
     column_indices: &[ColumnIndex],
     schema: &Schema,
-    visited_left_side: &mut BooleanBufferBuilder,
+    visited_left_side: &mut MatchBitmap,
+    join_metrics: &BuildProbeJoinMetrics,
+    null_padding: &mut NullPadding,
 ) -> Result<RecordBatch> {
+    let probe_timer = join_metrics.probe_time.timer();
     let indices_result = (0..left_batch.num_rows())
         .map(|left_row_index| {
             build_join_indices(left_row_index, right_batch, left_batch, filter)
@@ -623,12 +1100,10 @@ fn join_left_and_right_batch(
     };
     match left_right_indices {
         Ok((left_side, right_side)) => {
-            // set the left bitmap
-            // and only full join need the left bitmap
-            if join_type == JoinType::Full {
-                left_side.iter().flatten().for_each(|x| {
-                    visited_left_side.set_bit(x as usize, true);
-                });
+            // set the left bitmap, which is only allocated for the joins
+            // producing the unmatched left rows at the end
+            if !visited_left_side.is_empty() {
+                visited_left_side.mark_matched(&left_side);
             }
             // adjust the two side indices base on the join type
             let (left_side, right_side) = adjust_indices_by_join_type(
@@ -638,7 +1113,9 @@ fn join_left_and_right_batch(
                 right_batch.num_rows(),
                 join_type,
             );
+            probe_timer.done();
 
+            let _output_timer = join_metrics.output_time.timer();
             build_batch_from_indices(
                 schema,
                 left_batch,
@@ -647,6 +1124,7 @@ fn join_left_and_right_batch(
                 &right_side,
                 column_indices,
                 JoinSide::Left,
+                null_padding,
             )
         }
         Err(e) => Err(e),
@@ -705,6 +1183,8 @@ fn adjust_indices_by_join_type(
             // the left_indices will not be used later for the `right anti` join
             (left_indices, right_indices)
         }
+        // mark joins are rejected by `NestedLoopJoinExec::try_new`
+        JoinType::LeftMark | JoinType::RightMark => (left_indices, right_indices),
     }
 }
 
@@ -742,7 +1222,7 @@ impl Stream for NestedLoopJoinStream {
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
-        if left_is_build_side(self.join_type) {
+        if self.left_is_inner_table {
             self.poll_next_impl_for_build_left(cx)
         } else {
             self.poll_next_impl_for_build_right(cx)
@@ -761,17 +1241,23 @@ mod tests {
     use std::sync::Arc;
 
     use super::*;
+    use crate::joins::test_utils::{
+        build_sides_record_batches, compare_batches, create_memory_table,
+        split_record_batches,
+    };
     use crate::{
         common, expressions::Column, memory::MemoryExec, repartition::RepartitionExec,
         test::build_table_i32,
     };
 
+    use arrow::compute::SortOptions;
     use arrow::datatypes::{DataType, Field};
+    use datafusion_common::cast::as_int32_array;
     use datafusion_common::{assert_batches_sorted_eq, assert_contains, ScalarValue};
     use datafusion_execution::runtime_env::{RuntimeConfig, RuntimeEnv};
     use datafusion_expr::Operator;
     use datafusion_physical_expr::expressions::{BinaryExpr, Literal};
-    use datafusion_physical_expr::{Partitioning, PhysicalExpr};
+    use datafusion_physical_expr::{Partitioning, PhysicalExpr, PhysicalSortExpr};
 
     fn build_table(
         a: (&str, &Vec<i32>),
@@ -1133,6 +1619,368 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_buffer_smaller_side() -> Result<()> {
+        let large = build_table(
+            ("a1", &vec![1, 2, 3, 4, 5, 6]),
+            ("b1", &vec![2, 8, 5, 10, 8, 2]),
+            ("c1", &vec![10, 20, 30, 40, 50, 60]),
+        );
+        let small = build_table(
+            ("a2", &vec![7, 8]),
+            ("b2", &vec![2, 10]),
+            ("c2", &vec![70, 80]),
+        );
+        let filter = prepare_join_filter();
+        // Inner joins buffer the right side and full joins the left side by
+        // default, so the larger input is buffered unless swapped:
+        let cases = [
+            (JoinType::Inner, small.clone(), large.clone(), "a2"),
+            (JoinType::Full, large, small, "a1"),
+        ];
+
+        for (join_type, left, right, first_column) in cases {
+            let task_ctx = Arc::new(TaskContext::default());
+            let mut results = vec![];
+            for buffer_smaller_side in [false, true] {
+                let join = NestedLoopJoinExec::try_new(
+                    left.clone(),
+                    right.clone(),
+                    Some(filter.clone()),
+                    &join_type,
+                )?
+                .with_buffer_smaller_side(buffer_smaller_side);
+                assert_eq!(join.buffer_smaller_side(), buffer_smaller_side);
+                let batches = common::collect(join.execute(0, task_ctx.clone())?).await?;
+                let build_input_rows = join
+                    .metrics()
+                    .unwrap()
+                    .sum_by_name("build_input_rows")
+                    .map(|v| v.as_usize());
+                let expected_rows = if buffer_smaller_side { 2 } else { 6 };
+                assert_eq!(build_input_rows, Some(expected_rows));
+                // The column order is unaffected by swapping:
+                assert_eq!(batches[0].schema().field(0).name(), first_column);
+                results.push(batches);
+            }
+            assert_eq!(
+                results[0].iter().map(|b| b.num_rows()).sum::<usize>(),
+                results[1].iter().map(|b| b.num_rows()).sum::<usize>()
+            );
+            compare_batches(&results[0], &results[1]);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stream_right() -> Result<()> {
+        let (left_batch, right_batch) = build_sides_record_batches(1000, (5, 13))?;
+        // A small left side, some rows of which have no match:
+        let (left, right) = create_memory_table(
+            vec![left_batch.slice(0, 16)],
+            split_record_batches(&right_batch, 50)?,
+            vec![],
+            vec![],
+        )?;
+        // left.la1 = right.rc1
+        let filter = JoinFilter::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("x", 0)),
+                Operator::Eq,
+                Arc::new(Column::new("x", 1)),
+            )),
+            vec![
+                ColumnIndex {
+                    index: 0,
+                    side: JoinSide::Left,
+                },
+                ColumnIndex {
+                    index: 2,
+                    side: JoinSide::Right,
+                },
+            ],
+            Schema::new(vec![
+                Field::new("x", DataType::Int32, true),
+                Field::new("x", DataType::Int32, true),
+            ]),
+        );
+
+        for join_type in [JoinType::Inner, JoinType::Left] {
+            let task_ctx = Arc::new(TaskContext::default());
+            let buffered = NestedLoopJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                Some(filter.clone()),
+                &join_type,
+            )?;
+            let expected =
+                common::collect(buffered.execute(0, task_ctx.clone())?).await?;
+
+            // Inner joins stream every partition of the right side:
+            let streamed_right = if join_type == JoinType::Inner {
+                Arc::new(RepartitionExec::try_new(
+                    right.clone(),
+                    Partitioning::RoundRobinBatch(4),
+                )?) as Arc<dyn ExecutionPlan>
+            } else {
+                right.clone()
+            };
+            let streaming = NestedLoopJoinExec::try_new(
+                left.clone(),
+                streamed_right,
+                Some(filter.clone()),
+                &join_type,
+            )?
+            .with_stream_right(true)?;
+            assert!(streaming.stream_right());
+            assert!(matches!(
+                streaming.required_input_distribution()[0],
+                Distribution::SinglePartition
+            ));
+            let partition_count = streaming
+                .properties()
+                .output_partitioning()
+                .partition_count();
+            let mut batches = vec![];
+            for partition in 0..partition_count {
+                let stream = streaming.execute(partition, task_ctx.clone())?;
+                batches.extend(common::collect(stream).await?);
+            }
+            // Only the left side is buffered:
+            let build_input_rows = streaming
+                .metrics()
+                .unwrap()
+                .sum_by_name("build_input_rows")
+                .map(|v| v.as_usize());
+            assert_eq!(build_input_rows, Some(16));
+            assert_eq!(
+                batches.iter().map(|b| b.num_rows()).sum::<usize>(),
+                expected.iter().map(|b| b.num_rows()).sum::<usize>()
+            );
+            compare_batches(&batches, &expected);
+        }
+
+        // Other join types buffer the side dictated by the join type:
+        let err = NestedLoopJoinExec::try_new(left, right, None, &JoinType::Full)?
+            .with_stream_right(true)
+            .unwrap_err();
+        assert_contains!(err.to_string(), "can only stream the right side");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_adjacent_buckets() -> Result<()> {
+        let (left_batch, right_batch) = build_sides_record_batches(100, (10, 11))?;
+        let (left, right) = create_memory_table(
+            split_record_batches(&left_batch, 10)?,
+            split_record_batches(&right_batch, 10)?,
+            vec![],
+            vec![],
+        )?;
+        // Buckets of 10 consecutive rows, spread over `partition_count` partitions:
+        let bucketed = |input: &Arc<dyn ExecutionPlan>,
+                        name: &str,
+                        partition_count: usize|
+         -> Result<Arc<dyn ExecutionPlan>> {
+            let bucket = Arc::new(BinaryExpr::new(
+                Arc::new(Column::new_with_schema(name, &input.schema())?),
+                Operator::Divide,
+                Arc::new(Literal::new(ScalarValue::Int32(Some(10)))),
+            ));
+            Ok(Arc::new(RepartitionExec::try_new(
+                input.clone(),
+                Partitioning::Bucketed(bucket, partition_count),
+            )?))
+        };
+        // Left rows at most 5 after the right rows, i.e. in the same or in the
+        // next bucket: left.la1 - right.ra1 <= 5 AND right.ra1 < left.la1
+        let filter = JoinFilter::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(BinaryExpr::new(
+                    Arc::new(BinaryExpr::new(
+                        Arc::new(Column::new("x", 0)),
+                        Operator::Minus,
+                        Arc::new(Column::new("x", 1)),
+                    )),
+                    Operator::LtEq,
+                    Arc::new(Literal::new(ScalarValue::Int32(Some(5)))),
+                )),
+                Operator::And,
+                Arc::new(BinaryExpr::new(
+                    Arc::new(Column::new("x", 1)),
+                    Operator::Lt,
+                    Arc::new(Column::new("x", 0)),
+                )),
+            )),
+            vec![
+                ColumnIndex {
+                    index: 0,
+                    side: JoinSide::Left,
+                },
+                ColumnIndex {
+                    index: 0,
+                    side: JoinSide::Right,
+                },
+            ],
+            Schema::new(vec![
+                Field::new("x", DataType::Int32, true),
+                Field::new("x", DataType::Int32, true),
+            ]),
+        );
+
+        for join_type in [
+            JoinType::Inner,
+            JoinType::Right,
+            JoinType::RightSemi,
+            JoinType::RightAnti,
+        ] {
+            let task_ctx = Arc::new(TaskContext::default());
+            let single = NestedLoopJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                Some(filter.clone()),
+                &join_type,
+            )?;
+            let expected = common::collect(single.execute(0, task_ctx.clone())?).await?;
+
+            for adjacent_buckets in [0, 1] {
+                let join = NestedLoopJoinExec::try_new(
+                    bucketed(&left, "la1", 4)?,
+                    bucketed(&right, "ra1", 4)?,
+                    Some(filter.clone()),
+                    &join_type,
+                )?
+                .with_adjacent_buckets(adjacent_buckets)?;
+                assert_eq!(join.adjacent_buckets(), Some(adjacent_buckets));
+                assert!(matches!(
+                    join.properties().output_partitioning(),
+                    Partitioning::Bucketed(_, 4)
+                ));
+                let mut batches = vec![];
+                for partition in 0..4 {
+                    let stream = join.execute(partition, task_ctx.clone())?;
+                    batches.extend(common::collect(stream).await?);
+                }
+                // Every left partition is read once:
+                let build_input_rows = join
+                    .metrics()
+                    .unwrap()
+                    .sum_by_name("build_input_rows")
+                    .map(|v| v.as_usize());
+                assert_eq!(build_input_rows, Some(100));
+
+                let num_rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+                let expected_rows = expected.iter().map(|b| b.num_rows()).sum::<usize>();
+                if adjacent_buckets == 1 {
+                    assert_eq!(num_rows, expected_rows);
+                    compare_batches(&batches, &expected);
+                } else if join_type == JoinType::RightAnti {
+                    // Right rows matching only rows of the next bucket are
+                    // taken for unmatched:
+                    assert!(num_rows > expected_rows);
+                } else {
+                    // Matches across adjacent buckets are missed:
+                    assert!(num_rows < expected_rows);
+                }
+            }
+        }
+
+        let err = NestedLoopJoinExec::try_new(
+            bucketed(&left, "la1", 4)?,
+            bucketed(&right, "ra1", 4)?,
+            Some(filter.clone()),
+            &JoinType::Left,
+        )?
+        .with_adjacent_buckets(1)
+        .unwrap_err();
+        assert_contains!(err.to_string(), "can only join the buckets");
+
+        let err = NestedLoopJoinExec::try_new(
+            bucketed(&left, "la1", 4)?,
+            bucketed(&right, "ra1", 2)?,
+            Some(filter),
+            &JoinType::Inner,
+        )?
+        .with_adjacent_buckets(1)
+        .unwrap_err();
+        assert_contains!(err.to_string(), "same number of partitions");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_semi_anti_join_output_ordering() -> Result<()> {
+        let (left_batch, right_batch) = build_sides_record_batches(30, (4, 5))?;
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: Arc::new(Column::new_with_schema("la1", &left_batch.schema())?),
+            options: SortOptions::default(),
+        }];
+        let (left, right) = create_memory_table(
+            split_record_batches(&left_batch, 7)?,
+            vec![right_batch],
+            vec![left_sorted.clone()],
+            vec![],
+        )?;
+        // left.lb1 < right.rb1
+        let filter = JoinFilter::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("x", 0)),
+                Operator::Lt,
+                Arc::new(Column::new("x", 1)),
+            )),
+            vec![
+                ColumnIndex {
+                    index: 1,
+                    side: JoinSide::Left,
+                },
+                ColumnIndex {
+                    index: 1,
+                    side: JoinSide::Right,
+                },
+            ],
+            Schema::new(vec![
+                Field::new("x", DataType::Int32, true),
+                Field::new("x", DataType::Int32, true),
+            ]),
+        );
+
+        for join_type in [JoinType::LeftSemi, JoinType::LeftAnti] {
+            let join = NestedLoopJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                Some(filter.clone()),
+                &join_type,
+            )?;
+            assert_eq!(join.maintains_input_order(), vec![true, false]);
+            assert_eq!(
+                join.properties().output_ordering(),
+                Some(left_sorted.as_slice())
+            );
+
+            // The output is indeed ordered by `la1`:
+            let batches =
+                common::collect(join.execute(0, Arc::new(TaskContext::default()))?)
+                    .await?;
+            let la1 = batches
+                .iter()
+                .flat_map(|batch| {
+                    as_int32_array(batch.column(0)).unwrap().values().to_vec()
+                })
+                .collect::<Vec<_>>();
+            assert!(!la1.is_empty());
+            assert!(la1.windows(2).all(|w| w[0] <= w[1]));
+        }
+
+        // Other join types do not report an ordering:
+        let join =
+            NestedLoopJoinExec::try_new(left, right, Some(filter), &JoinType::Left)?;
+        assert_eq!(join.properties().output_ordering(), None);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_overallocation() -> Result<()> {
         let left = build_table(
@@ -0,0 +1,259 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Options shaping the output batches of a [`HashJoinExec`], i.e. how its
+//! output rows are split into batches and in which order they are emitted,
+//! independently of which rows are produced
+//!
+//! [`HashJoinExec`]: crate::joins::HashJoinExec
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use super::utils::take_join_column;
+
+use arrow::array::{UInt32Array, UInt64Array};
+use arrow::compute::concat_batches;
+use arrow::datatypes::Schema;
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use arrow::row::{OwnedRow, RowConverter, Rows, SortField};
+use datafusion_common::{internal_err, DataFusionError, Result};
+use datafusion_execution::memory_pool::MemoryReservation;
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::PhysicalExprRef;
+
+/// Number of smaller batches a full output batch is split into by
+/// [`HashJoinExec::with_low_latency`]
+///
+/// [`HashJoinExec::with_low_latency`]: crate::joins::HashJoinExec::with_low_latency
+pub(super) const LOW_LATENCY_OUTPUT_CHUNKS: usize = 8;
+
+/// The options of a [`HashJoinExec`] shaping its output batches, which are
+/// kept together so that they are carried over as a whole when the join is
+/// rebuilt, e.g. by [`HashJoinExec::with_projection`]
+///
+/// [`HashJoinExec`]: crate::joins::HashJoinExec
+/// [`HashJoinExec::with_projection`]: crate::joins::HashJoinExec::with_projection
+#[derive(Debug, Clone, Default)]
+pub(super) struct OutputShaping {
+    /// Number of rows of an output batch overriding the batch size of the
+    /// session, see `HashJoinExec::with_batch_size`
+    pub(super) batch_size: Option<usize>,
+    /// Whether output is emitted in smaller, more frequent batches, see
+    /// `HashJoinExec::with_low_latency`
+    pub(super) low_latency: bool,
+    /// Upper bound of the bytes taken by the values of an output batch, see
+    /// `HashJoinExec::with_max_output_batch_bytes`
+    pub(super) max_output_batch_bytes: Option<usize>,
+    /// Upper bound of the estimated serialized size of an output batch, see
+    /// `HashJoinExec::with_max_serialized_batch_bytes`
+    pub(super) max_serialized_batch_bytes: Option<usize>,
+    /// Whether zero-row batches are dropped from the output, see
+    /// `HashJoinExec::with_skip_empty_output_batches`
+    pub(super) skip_empty_batches: bool,
+    /// Whether the output rows are emitted grouped by their probe side join
+    /// keys, see `HashJoinExec::with_group_by_probe_key`
+    pub(super) group_by_probe_key: bool,
+}
+
+impl OutputShaping {
+    /// Number of rows of an output batch when executed in `context`
+    pub(super) fn batch_size(&self, context: &TaskContext) -> usize {
+        self.batch_size
+            .unwrap_or_else(|| context.session_config().batch_size())
+    }
+
+    /// Number of rows joined into an output batch at a time when executed in
+    /// `context`, see `HashJoinExec::with_low_latency`
+    pub(super) fn chunk_size(&self, context: &TaskContext) -> usize {
+        let batch_size = self.batch_size(context);
+        if self.low_latency {
+            (batch_size / LOW_LATENCY_OUTPUT_CHUNKS).max(1)
+        } else {
+            batch_size
+        }
+    }
+
+    /// Upper bound of the bytes taken by the values of an output batch, if
+    /// any. The values of a batch never take up more than its serialized
+    /// size, so the output is split by the tighter of both limits before the
+    /// serialized size is checked.
+    pub(super) fn byte_budget(&self) -> Option<usize> {
+        match (self.max_output_batch_bytes, self.max_serialized_batch_bytes) {
+            (Some(values), Some(serialized)) => Some(values.min(serialized)),
+            (values, serialized) => values.or(serialized),
+        }
+    }
+}
+
+/// Splits the `num_rows` rows of an output batch whose estimated serialized
+/// size of `batch_bytes` exceeds `max_bytes` into ranges of rows, see
+/// [`HashJoinExec::with_max_serialized_batch_bytes`]. Fails if the batch
+/// can not be split, i.e. if a single row exceeds `max_bytes`.
+///
+/// [`HashJoinExec::with_max_serialized_batch_bytes`]: crate::joins::HashJoinExec::with_max_serialized_batch_bytes
+pub(super) fn split_serialized_rows(
+    num_rows: usize,
+    batch_bytes: usize,
+    max_bytes: usize,
+) -> Result<Vec<Range<usize>>> {
+    if num_rows <= 1 {
+        return Err(DataFusionError::ResourcesExhausted(format!(
+            "Join output batch of {num_rows} row(s) takes up {batch_bytes} bytes \
+             serialized, exceeding the maximum of {max_bytes} bytes"
+        )));
+    }
+    // Split proportionally to the excess, so that the parts fit in most cases:
+    let num_parts = batch_bytes.div_ceil(max_bytes).clamp(2, num_rows);
+    let part_rows = num_rows.div_ceil(num_parts);
+    Ok((0..num_rows)
+        .step_by(part_rows)
+        .map(|start| start..(start + part_rows).min(num_rows))
+        .collect())
+}
+
+/// Output rows of a [`HashJoinExec`] stream, identified by their build and probe
+/// side indices into the build side and `probe_batch`
+///
+/// [`HashJoinExec`]: crate::joins::HashJoinExec
+pub(super) struct OutputChunk {
+    pub(super) probe_batch: RecordBatch,
+    pub(super) build_indices: UInt64Array,
+    pub(super) probe_indices: UInt32Array,
+}
+
+/// Buffers the output rows of a [`HashJoinExec`] stream to emit them grouped by
+/// their probe side join keys, see [`HashJoinExec::with_group_by_probe_key`]
+///
+/// [`HashJoinExec`]: crate::joins::HashJoinExec
+/// [`HashJoinExec::with_group_by_probe_key`]: crate::joins::HashJoinExec::with_group_by_probe_key
+pub(super) struct ProbeKeyGroups {
+    /// Probe side join key expressions
+    on_right: Vec<PhysicalExprRef>,
+    /// Converts the probe side join key values into comparable rows
+    converter: RowConverter,
+    /// Probe side join keys of the rows of the output batch built last, taken
+    /// once the batch is buffered
+    built_keys: Option<Rows>,
+    /// Buffered output batches
+    batches: Vec<RecordBatch>,
+    /// Number of rows of `batches`
+    num_rows: usize,
+    /// Index into `groups` of every distinct buffered key
+    group_indices: HashMap<OwnedRow, usize>,
+    /// Buffered rows of every group, as indices into the rows of `batches`,
+    /// in the order the keys first occurred
+    groups: Vec<Vec<u64>>,
+    /// Memory reservation of the buffered rows
+    reservation: MemoryReservation,
+}
+
+impl ProbeKeyGroups {
+    pub(super) fn try_new(
+        on_right: &[PhysicalExprRef],
+        schema: &Schema,
+        reservation: MemoryReservation,
+    ) -> Result<Self> {
+        let fields = on_right
+            .iter()
+            .map(|expr| Ok(SortField::new(expr.data_type(schema)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            on_right: on_right.to_vec(),
+            converter: RowConverter::new(fields)?,
+            built_keys: None,
+            batches: vec![],
+            num_rows: 0,
+            group_indices: HashMap::new(),
+            groups: vec![],
+            reservation,
+        })
+    }
+
+    /// Records the probe side join keys of the rows of `chunk`, whose output
+    /// batch was just built
+    pub(super) fn record_keys(&mut self, chunk: &OutputChunk) -> Result<()> {
+        let probe_batch = &chunk.probe_batch;
+        let keys = self
+            .on_right
+            .iter()
+            .map(|expr| {
+                let keys = expr
+                    .evaluate(probe_batch)?
+                    .into_array(probe_batch.num_rows())?;
+                take_join_column(keys.as_ref(), &chunk.probe_indices)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.built_keys = Some(self.converter.convert_columns(&keys)?);
+        Ok(())
+    }
+
+    /// Buffers the output batch built last. If the reservation can not grow
+    /// to hold it, the rows buffered before are returned grouped by key.
+    pub(super) fn push(&mut self, batch: RecordBatch) -> Result<Option<RecordBatch>> {
+        let Some(keys) = self.built_keys.take() else {
+            return internal_err!(
+                "The probe keys of a buffered output batch are unknown"
+            );
+        };
+        let size = batch.get_array_memory_size()
+            + keys.size()
+            + batch.num_rows() * std::mem::size_of::<u64>();
+        let flushed = match self.reservation.try_grow(size) {
+            Ok(()) => None,
+            Err(_) => {
+                let flushed = self.flush()?;
+                self.reservation.try_grow(size)?;
+                flushed
+            }
+        };
+        for (row, key) in keys.iter().enumerate() {
+            let next_group = self.groups.len();
+            let group = *self.group_indices.entry(key.owned()).or_insert(next_group);
+            if group == next_group {
+                self.groups.push(vec![]);
+            }
+            self.groups[group].push((self.num_rows + row) as u64);
+        }
+        self.num_rows += batch.num_rows();
+        self.batches.push(batch);
+        Ok(flushed)
+    }
+
+    /// Returns the buffered rows grouped by key, if any, and releases them
+    pub(super) fn flush(&mut self) -> Result<Option<RecordBatch>> {
+        let Some(schema) = self.batches.first().map(|batch| batch.schema()) else {
+            return Ok(None);
+        };
+        let batch = concat_batches(&schema, &self.batches)?;
+        let indices =
+            UInt64Array::from_iter_values(self.groups.iter().flatten().copied());
+        let columns = batch
+            .columns()
+            .iter()
+            .map(|column| take_join_column(column.as_ref(), &indices))
+            .collect::<Result<Vec<_>>>()?;
+        let options = RecordBatchOptions::new().with_row_count(Some(indices.len()));
+        let grouped = RecordBatch::try_new_with_options(schema, columns, &options)?;
+        self.batches.clear();
+        self.num_rows = 0;
+        self.group_indices.clear();
+        self.groups.clear();
+        self.reservation.free();
+        Ok(Some(grouped))
+    }
+}
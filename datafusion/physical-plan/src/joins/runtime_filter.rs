@@ -0,0 +1,352 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Runtime filters published by a [`HashJoinExec`] over its build side join
+//! keys, which the scans of its probe side consult to skip rows and row
+//! groups that can not have a match, see
+//! [`HashJoinExec::with_runtime_filter_sink`].
+//!
+//! [`HashJoinExec`]: crate::joins::HashJoinExec
+//! [`HashJoinExec::with_runtime_filter_sink`]: crate::joins::HashJoinExec::with_runtime_filter_sink
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+
+use crate::stream::RecordBatchStreamAdapter;
+use crate::SendableRecordBatchStream;
+
+use super::hash_join::JoinKeyFilter;
+
+use arrow::array::{Array, ArrayRef, BooleanArray};
+use arrow::compute::kernels::cmp::{gt_eq, lt_eq};
+use arrow::compute::{and, filter_record_batch, is_null, sort_to_indices};
+use arrow::datatypes::DataType;
+use arrow_schema::SortOptions;
+use datafusion_common::hash_utils::create_hashes;
+use datafusion_common::{Result, ScalarValue};
+
+use ahash::RandomState;
+use futures::future::poll_fn;
+use futures::{stream, StreamExt};
+use parking_lot::Mutex;
+
+/// Filter over the values of one build side join key of a hash join: the rows
+/// of the probe side whose key value lies outside the range of the build side
+/// values, or is not contained in a Bloom filter over them, can not have a
+/// match.
+pub struct RuntimeFilter {
+    /// Smallest non-null build side value, null if there is none
+    min: ScalarValue,
+    /// Largest non-null build side value, null if there is none
+    max: ScalarValue,
+    /// Bloom filter over the hashes of the build side values
+    key_filter: JoinKeyFilter,
+    /// Random state the values are hashed with
+    random_state: RandomState,
+    /// Whether null probe side values may have a match, i.e. nulls are
+    /// compared equal and the build side holds a null value
+    matches_nulls: bool,
+}
+
+impl RuntimeFilter {
+    /// Creates a filter over the build side join key `values`
+    pub(crate) fn try_new(values: &ArrayRef, matches_nulls: bool) -> Result<Self> {
+        let bound = |descending| -> Result<ScalarValue> {
+            let options = SortOptions {
+                descending,
+                nulls_first: false,
+            };
+            let indices = sort_to_indices(values, Some(options), Some(1))?;
+            match indices.iter().flatten().next() {
+                Some(index) if values.is_valid(index as usize) => {
+                    ScalarValue::try_from_array(values, index as usize)
+                }
+                _ => ScalarValue::try_from(values.data_type()),
+            }
+        };
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+        let mut hashes = vec![0; values.len()];
+        create_hashes(&[values.clone()], &random_state, &mut hashes)?;
+        Ok(Self {
+            min: bound(false)?,
+            max: bound(true)?,
+            key_filter: JoinKeyFilter::from_hashes([hashes.as_slice()]),
+            random_state,
+            matches_nulls: matches_nulls && values.null_count() > 0,
+        })
+    }
+
+    /// Smallest non-null build side value, null if there is none
+    pub fn min(&self) -> &ScalarValue {
+        &self.min
+    }
+
+    /// Largest non-null build side value, null if there is none
+    pub fn max(&self) -> &ScalarValue {
+        &self.max
+    }
+
+    /// Whether null probe side values may have a match
+    pub fn matches_nulls(&self) -> bool {
+        self.matches_nulls
+    }
+
+    /// Returns false if no non-null value between `min` and `max` can have a
+    /// match, e.g. to skip a row group based on its statistics. Null bounds
+    /// are treated as unknown.
+    pub fn may_contain_range(&self, min: &ScalarValue, max: &ScalarValue) -> bool {
+        if self.min.is_null() || self.max.is_null() {
+            return false;
+        }
+        let below =
+            !max.is_null() && max.partial_cmp(&self.min).is_some_and(|o| o.is_lt());
+        let above =
+            !min.is_null() && min.partial_cmp(&self.max).is_some_and(|o| o.is_gt());
+        !(below || above)
+    }
+
+    /// Returns a mask selecting the probe side `values` that may have a match.
+    /// `values` must be of the type of the filtered probe side column.
+    pub fn evaluate(&self, values: &ArrayRef) -> Result<BooleanArray> {
+        if self.min.is_null() || self.max.is_null() {
+            let mask = if self.matches_nulls {
+                is_null(values)?
+            } else {
+                BooleanArray::from(vec![false; values.len()])
+            };
+            return Ok(mask);
+        }
+        let in_range = and(
+            &gt_eq(values, &self.min.to_scalar()?)?,
+            &lt_eq(values, &self.max.to_scalar()?)?,
+        )?;
+        let mut hashes = vec![0; values.len()];
+        create_hashes(&[values.clone()], &self.random_state, &mut hashes)?;
+        let (contained, _) = self.key_filter.mask(&hashes);
+        // Only the comparisons of null values are null:
+        Ok(and(&in_range, &contained)?
+            .iter()
+            .map(|matched| Some(matched.unwrap_or(self.matches_nulls)))
+            .collect())
+    }
+}
+
+impl fmt::Debug for RuntimeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RuntimeFilter")
+            .field("min", &self.min)
+            .field("max", &self.max)
+            .field("matches_nulls", &self.matches_nulls)
+            .finish()
+    }
+}
+
+/// Key of a runtime filter in a [`RuntimeFilterRegistry`]: the join publishing
+/// it, identified by the id it got when the registry was attached to it, and
+/// the name of the probe side column it filters, see
+/// [`HashJoinExec::runtime_filter_keys`](crate::joins::HashJoinExec::runtime_filter_keys).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RuntimeFilterKey {
+    join_id: usize,
+    column: String,
+}
+
+impl RuntimeFilterKey {
+    /// Creates the key of the filter of `column` published by join `join_id`
+    pub fn new(join_id: usize, column: impl Into<String>) -> Self {
+        Self {
+            join_id,
+            column: column.into(),
+        }
+    }
+
+    /// Id of the join publishing the filter, unique within its registry
+    pub fn join_id(&self) -> usize {
+        self.join_id
+    }
+
+    /// Name of the probe side column the filter applies to
+    pub fn column(&self) -> &str {
+        &self.column
+    }
+}
+
+/// Registry the runtime filters of hash joins are published to, keyed by a
+/// [`RuntimeFilterKey`], and which scans of the filtered columns subscribe
+/// to, see
+/// [`HashJoinExec::with_runtime_filter_sink`](crate::joins::HashJoinExec::with_runtime_filter_sink).
+///
+/// A join declares the filters it will publish when it starts building its
+/// build side, so a scan starting before the build side is complete can tell
+/// a filter that is still pending from one that never comes: it may either
+/// skip the filter until it is published, see [`Self::filter`], or wait for
+/// it, see [`Self::wait_for`]. A join that fails to build a declared filter
+/// withdraws it, releasing the waiting scans unfiltered. Declaring a filter
+/// again resets it to pending, so a filter published by a previous execution
+/// of the join is not consulted by the scans of the next one.
+///
+/// Clones of the handle share the same filters. Every join the registry is
+/// attached to gets its own id, so that joins on probe side columns of the
+/// same name do not overwrite each other's filters.
+#[derive(Debug, Clone, Default)]
+pub struct RuntimeFilterRegistry {
+    state: Arc<Mutex<RegistryState>>,
+}
+
+#[derive(Debug, Default)]
+struct RegistryState {
+    /// Id given to the next join the registry is attached to
+    next_join_id: usize,
+    filters: HashMap<RuntimeFilterKey, FilterSlot>,
+    /// Wakers of the scans waiting for a pending filter
+    wakers: Vec<Waker>,
+}
+
+#[derive(Debug)]
+enum FilterSlot {
+    /// The filter will be published once the build side is complete
+    Pending,
+    Published(Arc<RuntimeFilter>),
+}
+
+impl RuntimeFilterRegistry {
+    /// Creates a registry without filters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a new id for a join publishing filters to this registry
+    pub(crate) fn register_join(&self) -> usize {
+        let mut state = self.state.lock();
+        let join_id = state.next_join_id;
+        state.next_join_id += 1;
+        join_id
+    }
+
+    /// Declares that a filter for `key` will be published, discarding the one
+    /// published before, if any
+    pub(crate) fn declare(&self, key: &RuntimeFilterKey) {
+        let mut state = self.state.lock();
+        state.filters.insert(key.clone(), FilterSlot::Pending);
+    }
+
+    /// Publishes `filter` for `key`, waking the scans waiting for it
+    pub(crate) fn publish(&self, key: &RuntimeFilterKey, filter: RuntimeFilter) {
+        let mut state = self.state.lock();
+        state
+            .filters
+            .insert(key.clone(), FilterSlot::Published(Arc::new(filter)));
+        state.wakers.drain(..).for_each(Waker::wake);
+    }
+
+    /// Withdraws the declared filter for `key` if it is still pending, waking
+    /// the scans waiting for it
+    pub(crate) fn withdraw(&self, key: &RuntimeFilterKey) {
+        let mut state = self.state.lock();
+        if matches!(state.filters.get(key), Some(FilterSlot::Pending)) {
+            state.filters.remove(key);
+        }
+        state.wakers.drain(..).for_each(Waker::wake);
+    }
+
+    /// Returns the filter published for `key`, if any
+    pub fn filter(&self, key: &RuntimeFilterKey) -> Option<Arc<RuntimeFilter>> {
+        match self.state.lock().filters.get(key) {
+            Some(FilterSlot::Published(filter)) => Some(filter.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns whether a filter for `key` is declared but not published yet
+    pub fn is_pending(&self, key: &RuntimeFilterKey) -> bool {
+        matches!(
+            self.state.lock().filters.get(key),
+            Some(FilterSlot::Pending)
+        )
+    }
+
+    /// Polls for the filter of `key`: pending while it is declared but not
+    /// published, and ready with the published filter, or none if no filter
+    /// is declared for `key`
+    pub fn poll_filter(
+        &self,
+        key: &RuntimeFilterKey,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Arc<RuntimeFilter>>> {
+        let mut state = self.state.lock();
+        match state.filters.get(key) {
+            Some(FilterSlot::Published(filter)) => Poll::Ready(Some(filter.clone())),
+            Some(FilterSlot::Pending) => {
+                if !state.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                    state.wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+            None => Poll::Ready(None),
+        }
+    }
+
+    /// Waits for the filter of `key`, see [`Self::poll_filter`]
+    pub async fn wait_for(&self, key: &RuntimeFilterKey) -> Option<Arc<RuntimeFilter>> {
+        poll_fn(|cx| self.poll_filter(key, cx)).await
+    }
+
+    /// Drops the rows of the `input` scan whose value of the column of `key`
+    /// can not have a match, once the filter of `key` is published. If `wait`
+    /// is set, no batch is pulled from `input` before the filter is published
+    /// or withdrawn; otherwise the batches scanned before are passed
+    /// unfiltered.
+    ///
+    /// Scans that can skip whole row groups should rather consult
+    /// [`RuntimeFilter::may_contain_range`] with their statistics.
+    pub fn filter_stream(
+        &self,
+        input: SendableRecordBatchStream,
+        key: &RuntimeFilterKey,
+        wait: bool,
+    ) -> Result<SendableRecordBatchStream> {
+        let schema = input.schema();
+        let index = schema.index_of(key.column())?;
+        let registry = self.clone();
+        let key = key.clone();
+        let stream = stream::once(async move {
+            if wait {
+                registry.wait_for(&key).await;
+            }
+            input.map(move |batch| {
+                let batch = batch?;
+                match registry.filter(&key) {
+                    Some(filter) => {
+                        let mask = filter.evaluate(batch.column(index))?;
+                        Ok(filter_record_batch(&batch, &mask)?)
+                    }
+                    None => Ok(batch),
+                }
+            })
+        })
+        .flatten();
+        Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+    }
+}
+
+/// Returns whether a runtime filter can be built for probe side values of
+/// `data_type`, i.e. they can be compared to a scalar and hashed
+pub(crate) fn supports_runtime_filter(data_type: &DataType) -> bool {
+    !data_type.is_nested() && !matches!(data_type, DataType::Dictionary(_, _))
+}
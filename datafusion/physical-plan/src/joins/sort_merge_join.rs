@@ -31,9 +31,11 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use crate::expressions::PhysicalSortExpr;
+use crate::joins::key_accessor::JoinKeys;
 use crate::joins::utils::{
-    build_join_schema, check_join_is_valid, estimate_join_statistics,
-    partitioned_join_output_partitioning, JoinFilter, JoinOn, JoinOnRef,
+    build_join_schema, check_join_is_valid, estimate_join_statistics, join_metrics_json,
+    limit_output_rows, partitioned_join_output_partitioning, skip_empty_batches,
+    JoinFilter, JoinOn, JoinOnRef, NullPadding, OutputRowLimit,
 };
 use crate::metrics::{ExecutionPlanMetricsSet, MetricBuilder, MetricsSet};
 use crate::{
@@ -44,7 +46,7 @@ use crate::{
 
 use arrow::array::*;
 use arrow::compute::{self, concat_batches, take, SortOptions};
-use arrow::datatypes::{DataType, SchemaRef, TimeUnit};
+use arrow::datatypes::SchemaRef;
 use arrow::error::ArrowError;
 use arrow::record_batch::RecordBatch;
 use datafusion_common::{
@@ -83,6 +85,15 @@ pub struct SortMergeJoinExec {
     pub sort_options: Vec<SortOptions>,
     /// If null_equals_null is true, null == null else null != null
     pub null_equals_null: bool,
+    /// Limit on the number of output rows across all partitions, see
+    /// [`Self::with_max_output_rows`]
+    output_row_limit: Option<OutputRowLimit>,
+    /// Whether zero-row batches are dropped from the output, see
+    /// [`Self::with_skip_empty_output_batches`]
+    skip_empty_output_batches: bool,
+    /// Number of rows of an output batch overriding the batch size of the
+    /// session, see [`Self::with_batch_size`]
+    batch_size: Option<usize>,
     /// Cache holding plan properties like equivalences, output partitioning etc.
     cache: PlanProperties,
 }
@@ -109,8 +120,16 @@ impl SortMergeJoinExec {
                 "SortMergeJoinExec does not support JoinType::RightSemi"
             );
         }
+        if matches!(join_type, JoinType::LeftMark | JoinType::RightMark) {
+            return not_impl_err!(
+                "SortMergeJoinExec does not support JoinType::{join_type}"
+            );
+        }
 
         check_join_is_valid(&left_schema, &right_schema, &on)?;
+        if let Some(filter) = &filter {
+            filter.validate(&left_schema, &right_schema)?;
+        }
         if sort_options.len() != on.len() {
             return plan_err!(
                 "Expected number of sort options: {}, actual: {}",
@@ -151,24 +170,60 @@ impl SortMergeJoinExec {
             right_sort_exprs,
             sort_options,
             null_equals_null,
+            output_row_limit: None,
+            skip_empty_output_batches: false,
+            batch_size: None,
             cache,
         })
     }
 
+    /// Fails the execution with `ResourcesExhausted` once the output rows of
+    /// all partitions together exceed `max_output_rows`, see
+    /// [`HashJoinExec::with_max_output_rows`]. By default, the output is
+    /// unlimited.
+    ///
+    /// [`HashJoinExec::with_max_output_rows`]: crate::joins::HashJoinExec::with_max_output_rows
+    pub fn with_max_output_rows(mut self, max_output_rows: usize) -> Self {
+        self.output_row_limit = Some(OutputRowLimit::new(max_output_rows));
+        self
+    }
+
+    /// Drops zero-row batches from the output instead of emitting them, see
+    /// [`HashJoinExec::with_skip_empty_output_batches`]. By default, batches
+    /// are emitted as produced, including empty ones.
+    ///
+    /// [`HashJoinExec::with_skip_empty_output_batches`]: crate::joins::HashJoinExec::with_skip_empty_output_batches
+    pub fn with_skip_empty_output_batches(mut self, skip: bool) -> Self {
+        self.skip_empty_output_batches = skip;
+        self
+    }
+
+    /// Emits output batches of up to `batch_size` rows instead of the batch
+    /// size configured for the session, see
+    /// [`HashJoinExec::with_batch_size`].
+    ///
+    /// [`HashJoinExec::with_batch_size`]: crate::joins::HashJoinExec::with_batch_size
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = Some(batch_size.max(1));
+        self
+    }
+
     /// Get probe side (e.g streaming side) information for this sort merge join.
     /// In current implementation, probe side is determined according to join type.
     pub fn probe_side(join_type: &JoinType) -> JoinSide {
         // When output schema contains only the right side, probe side is right.
         // Otherwise probe side is the left side.
         match join_type {
-            JoinType::Right | JoinType::RightSemi | JoinType::RightAnti => {
-                JoinSide::Right
-            }
+            JoinType::Right
+            | JoinType::RightSemi
+            | JoinType::RightAnti
+            | JoinType::RightMark => JoinSide::Right,
             JoinType::Inner
             | JoinType::Left
             | JoinType::Full
             | JoinType::LeftAnti
-            | JoinType::LeftSemi => JoinSide::Left,
+            | JoinType::LeftSemi
+            | JoinType::LeftMark => JoinSide::Left,
         }
     }
 
@@ -197,10 +252,34 @@ impl SortMergeJoinExec {
         self.join_type
     }
 
+    /// The metrics of this join as a JSON object suitable for machine
+    /// ingestion, see [`JOIN_METRICS_JSON_VERSION`] for its stability
+    ///
+    /// [`JOIN_METRICS_JSON_VERSION`]: crate::joins::utils::JOIN_METRICS_JSON_VERSION
+    pub fn metrics_json(&self) -> serde_json::Value {
+        join_metrics_json(self.name(), &self.join_type, None, &self.metrics)
+    }
+
     pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
         &self.left
     }
 
+    /// Maximum number of output rows across all partitions, if limited
+    pub fn max_output_rows(&self) -> Option<usize> {
+        self.output_row_limit.as_ref().map(|limit| limit.max_rows())
+    }
+
+    /// Whether zero-row batches are dropped from the output
+    pub fn skip_empty_output_batches(&self) -> bool {
+        self.skip_empty_output_batches
+    }
+
+    /// Number of rows of an output batch, if overriding the batch size of the
+    /// session
+    pub fn batch_size(&self) -> Option<usize> {
+        self.batch_size
+    }
+
     /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
     fn compute_properties(
         left: &Arc<dyn ExecutionPlan>,
@@ -307,15 +386,24 @@ impl ExecutionPlan for SortMergeJoinExec {
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
         match &children[..] {
-            [left, right] => Ok(Arc::new(SortMergeJoinExec::try_new(
-                left.clone(),
-                right.clone(),
-                self.on.clone(),
-                self.filter.clone(),
-                self.join_type,
-                self.sort_options.clone(),
-                self.null_equals_null,
-            )?)),
+            [left, right] => {
+                let mut join = SortMergeJoinExec::try_new(
+                    left.clone(),
+                    right.clone(),
+                    self.on.clone(),
+                    self.filter.clone(),
+                    self.join_type,
+                    self.sort_options.clone(),
+                    self.null_equals_null,
+                )?;
+                join.output_row_limit = self
+                    .output_row_limit
+                    .as_ref()
+                    .map(|limit| OutputRowLimit::new(limit.max_rows()));
+                join.skip_empty_output_batches = self.skip_empty_output_batches;
+                join.batch_size = self.batch_size;
+                Ok(Arc::new(join))
+            }
             _ => internal_err!("SortMergeJoin wrong number of children"),
         }
     }
@@ -346,14 +434,16 @@ impl ExecutionPlan for SortMergeJoinExec {
         let buffered = buffered.execute(partition, context.clone())?;
 
         // create output buffer
-        let batch_size = context.session_config().batch_size();
+        let batch_size = self
+            .batch_size
+            .unwrap_or_else(|| context.session_config().batch_size());
 
         // create memory reservation
         let reservation = MemoryConsumer::new(format!("SMJStream[{partition}]"))
             .register(context.memory_pool());
 
         // create join stream
-        Ok(Box::pin(SMJStream::try_new(
+        let stream = Box::pin(SMJStream::try_new(
             self.schema.clone(),
             self.sort_options.clone(),
             self.null_equals_null,
@@ -366,7 +456,16 @@ impl ExecutionPlan for SortMergeJoinExec {
             batch_size,
             SortMergeJoinMetrics::new(partition, &self.metrics),
             reservation,
-        )?))
+            NullPadding::new(
+                MemoryConsumer::new(format!("SMJNullPadding[{partition}]"))
+                    .register(context.memory_pool()),
+            ),
+        )?);
+        Ok(limit_output_rows(
+            skip_empty_batches(stream, self.skip_empty_output_batches),
+            self.output_row_limit.as_ref(),
+            partition,
+        ))
     }
 
     fn metrics(&self) -> Option<MetricsSet> {
@@ -484,9 +583,9 @@ struct StreamedBatch {
     pub batch: RecordBatch,
     /// The index of row in the streamed batch to compare with buffered batches
     pub idx: usize,
-    /// The join key arrays of streamed batch which are used to compare with buffered batches
-    /// and to produce output. They are produced by evaluating `on` expressions.
-    pub join_arrays: Vec<ArrayRef>,
+    /// The join keys of streamed batch which are used to compare with buffered batches.
+    /// They are produced by evaluating `on` expressions.
+    pub join_keys: JoinKeys,
 
     /// Chunks of indices from buffered side (may be nulls) joined to streamed
     pub output_indices: Vec<StreamedJoinedChunk>,
@@ -495,22 +594,22 @@ struct StreamedBatch {
 }
 
 impl StreamedBatch {
-    fn new(batch: RecordBatch, on_column: &[Arc<dyn PhysicalExpr>]) -> Self {
-        let join_arrays = join_arrays(&batch, on_column);
-        StreamedBatch {
+    fn new(batch: RecordBatch, on_column: &[Arc<dyn PhysicalExpr>]) -> Result<Self> {
+        let join_keys = JoinKeys::try_new(&join_arrays(&batch, on_column))?;
+        Ok(StreamedBatch {
             batch,
             idx: 0,
-            join_arrays,
+            join_keys,
             output_indices: vec![],
             buffered_batch_idx: None,
-        }
+        })
     }
 
     fn new_empty(schema: SchemaRef) -> Self {
         StreamedBatch {
             batch: RecordBatch::new_empty(schema),
             idx: 0,
-            join_arrays: vec![],
+            join_keys: JoinKeys::default(),
             output_indices: vec![],
             buffered_batch_idx: None,
         }
@@ -553,8 +652,8 @@ struct BufferedBatch {
     pub batch: RecordBatch,
     /// The range in which the rows share the same join key
     pub range: Range<usize>,
-    /// Array refs of the join key
-    pub join_arrays: Vec<ArrayRef>,
+    /// Join keys of the batch
+    pub join_keys: JoinKeys,
     /// Buffered joined index (null joining buffered)
     pub null_joined: Vec<usize>,
     /// Size estimation used for reserving / releasing memory
@@ -566,8 +665,9 @@ impl BufferedBatch {
         batch: RecordBatch,
         range: Range<usize>,
         on_column: &[PhysicalExprRef],
-    ) -> Self {
+    ) -> Result<Self> {
         let join_arrays = join_arrays(&batch, on_column);
+        let join_keys = JoinKeys::try_new(&join_arrays)?;
 
         // Estimation is calculated as
         //   inner batch size
@@ -584,13 +684,13 @@ impl BufferedBatch {
             + mem::size_of::<Range<usize>>()
             + mem::size_of::<usize>();
 
-        BufferedBatch {
+        Ok(BufferedBatch {
             batch,
             range,
-            join_arrays,
+            join_keys,
             null_joined: vec![],
             size_estimation,
-        }
+        })
     }
 }
 
@@ -647,6 +747,8 @@ struct SMJStream {
     pub join_metrics: SortMergeJoinMetrics,
     /// Memory reservation
     pub reservation: MemoryReservation,
+    /// Null arrays padding the columns of the side without a match
+    pub null_padding: NullPadding,
 }
 
 impl RecordBatchStream for SMJStream {
@@ -766,6 +868,7 @@ impl SMJStream {
         batch_size: usize,
         join_metrics: SortMergeJoinMetrics,
         reservation: MemoryReservation,
+        null_padding: NullPadding,
     ) -> Result<Self> {
         let streamed_schema = streamed.schema();
         let buffered_schema = buffered.schema();
@@ -794,6 +897,7 @@ impl SMJStream {
             join_type,
             join_metrics,
             reservation,
+            null_padding,
         })
     }
 
@@ -824,7 +928,7 @@ impl SMJStream {
                             self.join_metrics.input_batches.add(1);
                             self.join_metrics.input_rows.add(batch.num_rows());
                             self.streamed_batch =
-                                StreamedBatch::new(batch, &self.on_streamed);
+                                StreamedBatch::new(batch, &self.on_streamed)?;
                             self.streamed_state = StreamedState::Ready;
                         }
                     }
@@ -880,7 +984,7 @@ impl SMJStream {
                         self.join_metrics.input_rows.add(batch.num_rows());
                         if batch.num_rows() > 0 {
                             let buffered_batch =
-                                BufferedBatch::new(batch, 0..1, &self.on_buffered);
+                                BufferedBatch::new(batch, 0..1, &self.on_buffered)?;
                             self.reservation.try_grow(buffered_batch.size_estimation)?;
                             self.join_metrics
                                 .peak_mem_used
@@ -898,11 +1002,12 @@ impl SMJStream {
                         while self.buffered_data.tail_batch().range.end
                             < self.buffered_data.tail_batch().batch.num_rows()
                         {
-                            if is_join_arrays_equal(
-                                &self.buffered_data.head_batch().join_arrays,
-                                self.buffered_data.head_batch().range.start,
-                                &self.buffered_data.tail_batch().join_arrays,
-                                self.buffered_data.tail_batch().range.end,
+                            let head_batch = self.buffered_data.head_batch();
+                            let tail_batch = self.buffered_data.tail_batch();
+                            if head_batch.join_keys.equals(
+                                head_batch.range.start,
+                                &tail_batch.join_keys,
+                                tail_batch.range.end,
                             )? {
                                 self.buffered_data.tail_batch_mut().range.end += 1;
                             } else {
@@ -926,7 +1031,7 @@ impl SMJStream {
                                         batch,
                                         0..0,
                                         &self.on_buffered,
-                                    );
+                                    )?;
                                     self.reservation
                                         .try_grow(buffered_batch.size_estimation)?;
                                     self.join_metrics
@@ -957,14 +1062,14 @@ impl SMJStream {
             return Ok(Ordering::Less);
         }
 
-        return compare_join_arrays(
-            &self.streamed_batch.join_arrays,
+        let head_batch = self.buffered_data.head_batch();
+        self.streamed_batch.join_keys.compare(
             self.streamed_batch.idx,
-            &self.buffered_data.head_batch().join_arrays,
-            self.buffered_data.head_batch().range.start,
+            &head_batch.join_keys,
+            head_batch.range.start,
             &self.sort_options,
             self.null_equals_null,
-        );
+        )
     }
 
     /// Produce join and fill output buffer until reaching target batch size
@@ -1104,7 +1209,7 @@ impl SMJStream {
                 .streamed_schema
                 .fields()
                 .iter()
-                .map(|f| new_null_array(f.data_type(), buffered_indices.len()))
+                .map(|f| self.null_padding.get(f.data_type(), buffered_indices.len()))
                 .collect::<Vec<_>>();
 
             streamed_columns.extend(buffered_columns);
@@ -1150,7 +1255,9 @@ impl SMJStream {
                     self.buffered_schema
                         .fields()
                         .iter()
-                        .map(|f| new_null_array(f.data_type(), buffered_indices.len()))
+                        .map(|f| {
+                            self.null_padding.get(f.data_type(), buffered_indices.len())
+                        })
                         .collect::<Vec<_>>()
                 };
 
@@ -1227,10 +1334,8 @@ impl SMJStream {
                             .fields()
                             .iter()
                             .map(|f| {
-                                new_null_array(
-                                    f.data_type(),
-                                    null_joined_batch.num_rows(),
-                                )
+                                self.null_padding
+                                    .get(f.data_type(), null_joined_batch.num_rows())
                             })
                             .collect::<Vec<_>>();
 
@@ -1281,10 +1386,8 @@ impl SMJStream {
                                 .fields()
                                 .iter()
                                 .map(|f| {
-                                    new_null_array(
-                                        f.data_type(),
-                                        null_joined_batch.num_rows(),
-                                    )
+                                    self.null_padding
+                                        .get(f.data_type(), null_joined_batch.num_rows())
                                 })
                                 .collect::<Vec<_>>();
 
@@ -1443,177 +1546,25 @@ fn join_arrays(batch: &RecordBatch, on_column: &[PhysicalExprRef]) -> Vec<ArrayR
         .collect()
 }
 
-/// Get comparison result of two rows of join arrays
-fn compare_join_arrays(
-    left_arrays: &[ArrayRef],
-    left: usize,
-    right_arrays: &[ArrayRef],
-    right: usize,
-    sort_options: &[SortOptions],
-    null_equals_null: bool,
-) -> Result<Ordering> {
-    let mut res = Ordering::Equal;
-    for ((left_array, right_array), sort_options) in
-        left_arrays.iter().zip(right_arrays).zip(sort_options)
-    {
-        macro_rules! compare_value {
-            ($T:ty) => {{
-                let left_array = left_array.as_any().downcast_ref::<$T>().unwrap();
-                let right_array = right_array.as_any().downcast_ref::<$T>().unwrap();
-                match (left_array.is_null(left), right_array.is_null(right)) {
-                    (false, false) => {
-                        let left_value = &left_array.value(left);
-                        let right_value = &right_array.value(right);
-                        res = left_value.partial_cmp(right_value).unwrap();
-                        if sort_options.descending {
-                            res = res.reverse();
-                        }
-                    }
-                    (true, false) => {
-                        res = if sort_options.nulls_first {
-                            Ordering::Less
-                        } else {
-                            Ordering::Greater
-                        };
-                    }
-                    (false, true) => {
-                        res = if sort_options.nulls_first {
-                            Ordering::Greater
-                        } else {
-                            Ordering::Less
-                        };
-                    }
-                    _ => {
-                        res = if null_equals_null {
-                            Ordering::Equal
-                        } else {
-                            Ordering::Less
-                        };
-                    }
-                }
-            }};
-        }
-
-        match left_array.data_type() {
-            DataType::Null => {}
-            DataType::Boolean => compare_value!(BooleanArray),
-            DataType::Int8 => compare_value!(Int8Array),
-            DataType::Int16 => compare_value!(Int16Array),
-            DataType::Int32 => compare_value!(Int32Array),
-            DataType::Int64 => compare_value!(Int64Array),
-            DataType::UInt8 => compare_value!(UInt8Array),
-            DataType::UInt16 => compare_value!(UInt16Array),
-            DataType::UInt32 => compare_value!(UInt32Array),
-            DataType::UInt64 => compare_value!(UInt64Array),
-            DataType::Float32 => compare_value!(Float32Array),
-            DataType::Float64 => compare_value!(Float64Array),
-            DataType::Utf8 => compare_value!(StringArray),
-            DataType::LargeUtf8 => compare_value!(LargeStringArray),
-            DataType::Decimal128(..) => compare_value!(Decimal128Array),
-            DataType::Timestamp(time_unit, None) => match time_unit {
-                TimeUnit::Second => compare_value!(TimestampSecondArray),
-                TimeUnit::Millisecond => compare_value!(TimestampMillisecondArray),
-                TimeUnit::Microsecond => compare_value!(TimestampMicrosecondArray),
-                TimeUnit::Nanosecond => compare_value!(TimestampNanosecondArray),
-            },
-            DataType::Date32 => compare_value!(Date32Array),
-            DataType::Date64 => compare_value!(Date64Array),
-            _ => {
-                return not_impl_err!(
-                    "Unsupported data type in sort merge join comparator"
-                );
-            }
-        }
-        if !res.is_eq() {
-            break;
-        }
-    }
-    Ok(res)
-}
-
-/// A faster version of compare_join_arrays() that only output whether
-/// the given two rows are equal
-fn is_join_arrays_equal(
-    left_arrays: &[ArrayRef],
-    left: usize,
-    right_arrays: &[ArrayRef],
-    right: usize,
-) -> Result<bool> {
-    let mut is_equal = true;
-    for (left_array, right_array) in left_arrays.iter().zip(right_arrays) {
-        macro_rules! compare_value {
-            ($T:ty) => {{
-                match (left_array.is_null(left), right_array.is_null(right)) {
-                    (false, false) => {
-                        let left_array =
-                            left_array.as_any().downcast_ref::<$T>().unwrap();
-                        let right_array =
-                            right_array.as_any().downcast_ref::<$T>().unwrap();
-                        if left_array.value(left) != right_array.value(right) {
-                            is_equal = false;
-                        }
-                    }
-                    (true, false) => is_equal = false,
-                    (false, true) => is_equal = false,
-                    _ => {}
-                }
-            }};
-        }
-
-        match left_array.data_type() {
-            DataType::Null => {}
-            DataType::Boolean => compare_value!(BooleanArray),
-            DataType::Int8 => compare_value!(Int8Array),
-            DataType::Int16 => compare_value!(Int16Array),
-            DataType::Int32 => compare_value!(Int32Array),
-            DataType::Int64 => compare_value!(Int64Array),
-            DataType::UInt8 => compare_value!(UInt8Array),
-            DataType::UInt16 => compare_value!(UInt16Array),
-            DataType::UInt32 => compare_value!(UInt32Array),
-            DataType::UInt64 => compare_value!(UInt64Array),
-            DataType::Float32 => compare_value!(Float32Array),
-            DataType::Float64 => compare_value!(Float64Array),
-            DataType::Utf8 => compare_value!(StringArray),
-            DataType::LargeUtf8 => compare_value!(LargeStringArray),
-            DataType::Decimal128(..) => compare_value!(Decimal128Array),
-            DataType::Timestamp(time_unit, None) => match time_unit {
-                TimeUnit::Second => compare_value!(TimestampSecondArray),
-                TimeUnit::Millisecond => compare_value!(TimestampMillisecondArray),
-                TimeUnit::Microsecond => compare_value!(TimestampMicrosecondArray),
-                TimeUnit::Nanosecond => compare_value!(TimestampNanosecondArray),
-            },
-            DataType::Date32 => compare_value!(Date32Array),
-            DataType::Date64 => compare_value!(Date64Array),
-            _ => {
-                return not_impl_err!(
-                    "Unsupported data type in sort merge join comparator"
-                );
-            }
-        }
-        if !is_equal {
-            return Ok(false);
-        }
-    }
-    Ok(true)
-}
-
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
     use crate::expressions::Column;
     use crate::joins::utils::JoinOn;
-    use crate::joins::SortMergeJoinExec;
+    use crate::joins::{HashJoinExec, PartitionMode, SortMergeJoinExec};
     use crate::memory::MemoryExec;
     use crate::test::build_table_i32;
     use crate::{common, ExecutionPlan};
 
-    use arrow::array::{Date32Array, Date64Array, Int32Array};
-    use arrow::compute::SortOptions;
+    use arrow::array::{ArrayRef, Date32Array, Date64Array, Int32Array, StringArray};
+    use arrow::compute::{cast, SortOptions};
     use arrow::datatypes::{DataType, Field, Schema};
     use arrow::record_batch::RecordBatch;
+    use arrow::util::pretty::pretty_format_batches;
     use datafusion_common::{
-        assert_batches_eq, assert_batches_sorted_eq, assert_contains, JoinType, Result,
+        assert_batches_eq, assert_batches_sorted_eq, assert_contains, DataFusionError,
+        JoinType, Result,
     };
     use datafusion_execution::config::SessionConfig;
     use datafusion_execution::runtime_env::{RuntimeConfig, RuntimeEnv};
@@ -2253,6 +2204,139 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn join_dictionary_keys() -> Result<()> {
+        // Dictionary encoded keys join like the values they encode
+        let build_string_table = |a: (&str, Vec<i32>), b: (&str, Vec<Option<&str>>)| {
+            let keys = Arc::new(StringArray::from(b.1)) as ArrayRef;
+            let dictionary = cast(
+                &keys,
+                &DataType::Dictionary(
+                    Box::new(DataType::Int32),
+                    Box::new(DataType::Utf8),
+                ),
+            )
+            .unwrap();
+            let values = Arc::new(Int32Array::from(a.1)) as ArrayRef;
+            let batch =
+                RecordBatch::try_from_iter(vec![(a.0, values), (b.0, dictionary)])
+                    .unwrap();
+            let schema = batch.schema();
+            Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+                as Arc<dyn ExecutionPlan>
+        };
+        let left = build_string_table(
+            ("a1", vec![1, 2, 3, 4]),
+            ("b1", vec![None, Some("x"), Some("y"), Some("y")]),
+        );
+        let right = build_string_table(
+            ("a2", vec![10, 20, 30]),
+            ("b1", vec![Some("w"), Some("y"), Some("z")]),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b1", &right.schema())?) as _,
+        )];
+
+        let (_, batches) = join_collect(left, right, on, JoinType::Left).await?;
+
+        let expected = [
+            "+----+----+----+----+",
+            "| a1 | b1 | a2 | b1 |",
+            "+----+----+----+----+",
+            "| 1  |    |    |    |",
+            "| 2  | x  |    |    |",
+            "| 3  | y  | 20 | y  |",
+            "| 4  | y  | 20 | y  |",
+            "+----+----+----+----+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_keys_of_all_types_match_hash_join() -> Result<()> {
+        // Sorted keys, whose string representation sorts the same way
+        let left_keys = vec![None, Some(1), Some(1), Some(2), Some(4), Some(5), Some(7)];
+        let right_keys = vec![None, Some(1), Some(3), Some(4), Some(5), Some(5), Some(8)];
+        let build_table = |name: &str, keys: &Vec<Option<i32>>, data_type: &DataType| {
+            let keys = Arc::new(Int32Array::from(keys.clone())) as ArrayRef;
+            let keys = match data_type {
+                DataType::Binary => cast(&cast(&keys, &DataType::Utf8)?, data_type)?,
+                _ => cast(&keys, data_type)?,
+            };
+            let values = Arc::new(Int32Array::from_iter_values(0..keys.len() as i32));
+            let batch = RecordBatch::try_from_iter(vec![
+                (format!("{name}_key"), keys),
+                (format!("{name}_value"), values as ArrayRef),
+            ])?;
+            let schema = batch.schema();
+            Ok::<_, DataFusionError>(Arc::new(MemoryExec::try_new(
+                &[vec![batch]],
+                schema,
+                None,
+            )?) as Arc<dyn ExecutionPlan>)
+        };
+        let sorted_rows = |batches: &[RecordBatch]| -> Result<Vec<String>> {
+            let mut rows = pretty_format_batches(batches)?
+                .to_string()
+                .lines()
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            rows.sort_unstable();
+            Ok(rows)
+        };
+
+        let data_types = [
+            DataType::Int32,
+            DataType::Int64,
+            DataType::Float64,
+            DataType::Utf8,
+            DataType::LargeUtf8,
+            DataType::Binary,
+            DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8)),
+        ];
+        let join_types = [
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+        ];
+        for data_type in &data_types {
+            let left = build_table("l", &left_keys, data_type)?;
+            let right = build_table("r", &right_keys, data_type)?;
+            let on: JoinOn = vec![(
+                Arc::new(Column::new_with_schema("l_key", &left.schema())?) as _,
+                Arc::new(Column::new_with_schema("r_key", &right.schema())?) as _,
+            )];
+            for join_type in join_types {
+                let (_, batches) =
+                    join_collect(left.clone(), right.clone(), on.clone(), join_type)
+                        .await?;
+                let hash_join = HashJoinExec::try_new(
+                    left.clone(),
+                    right.clone(),
+                    on.clone(),
+                    None,
+                    &join_type,
+                    None,
+                    PartitionMode::CollectLeft,
+                    false,
+                )?;
+                let stream = hash_join.execute(0, Arc::new(TaskContext::default()))?;
+                let expected = common::collect(stream).await?;
+                assert_eq!(
+                    sorted_rows(&batches)?,
+                    sorted_rows(&expected)?,
+                    "{join_type} join on {data_type} keys"
+                );
+            }
+        }
+        Ok(())
+    }
+
     #[tokio::test]
     async fn join_date64() -> Result<()> {
         let left = build_date64_table(
@@ -2638,6 +2722,45 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn join_with_batch_size() -> Result<()> {
+        let left = build_table(
+            ("a1", &(0..10).collect::<Vec<_>>()),
+            ("b1", &vec![1; 10]),
+            ("c1", &(0..10).collect::<Vec<_>>()),
+        );
+        let right = build_table(
+            ("a2", &(0..6).collect::<Vec<_>>()),
+            ("b2", &vec![1; 6]),
+            ("c2", &(0..6).collect::<Vec<_>>()),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+
+        // The override takes precedence over the session's batch size, whether
+        // it is smaller or larger
+        for (session_batch_size, batch_size) in [(2, 16), (8192, 7)] {
+            let task_ctx = TaskContext::default().with_session_config(
+                SessionConfig::new().with_batch_size(session_batch_size),
+            );
+            let join = join(left.clone(), right.clone(), on.clone(), JoinType::Inner)?
+                .with_batch_size(batch_size);
+            assert_eq!(join.batch_size(), Some(batch_size));
+            let join =
+                Arc::new(join).with_new_children(vec![left.clone(), right.clone()])?;
+            let batches = common::collect(join.execute(0, Arc::new(task_ctx))?).await?;
+            let rows = batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>();
+            assert_eq!(rows.iter().sum::<usize>(), 60);
+            assert!(rows.iter().all(|rows| *rows <= batch_size), "{rows:?}");
+            assert!(rows.iter().any(|rows| *rows > 2), "{rows:?}");
+        }
+
+        Ok(())
+    }
+
     /// Returns the column names on the schema
     fn columns(schema: &Schema) -> Vec<String> {
         schema.fields().iter().map(|f| f.name().clone()).collect()
@@ -25,12 +25,14 @@ use std::usize;
 
 use crate::joins::utils::{JoinFilter, JoinHashMapType, StatefulStreamResult};
 use crate::metrics::{ExecutionPlanMetricsSet, MetricBuilder};
-use crate::{handle_async_state, handle_state, metrics, ExecutionPlan};
+use crate::{
+    handle_async_state, handle_state, metrics, ExecutionPlan, ExecutionPlanProperties,
+};
 
 use arrow::compute::concat_batches;
 use arrow_array::{ArrowPrimitiveType, NativeAdapter, PrimitiveArray, RecordBatch};
 use arrow_buffer::{ArrowNativeType, BooleanBufferBuilder};
-use arrow_schema::{Schema, SchemaRef};
+use arrow_schema::{DataType, IntervalUnit, Schema, SchemaRef};
 use datafusion_common::tree_node::{Transformed, TransformedResult, TreeNode};
 use datafusion_common::{
     arrow_datafusion_err, plan_datafusion_err, DataFusionError, JoinSide, Result,
@@ -39,9 +41,12 @@ use datafusion_common::{
 use datafusion_execution::SendableRecordBatchStream;
 use datafusion_expr::interval_arithmetic::Interval;
 use datafusion_physical_expr::expressions::Column;
-use datafusion_physical_expr::intervals::cp_solver::ExprIntervalGraph;
+use datafusion_physical_expr::intervals::cp_solver::{
+    ExprIntervalGraph, PropagationResult,
+};
+use datafusion_physical_expr::sort_properties::SortProperties;
 use datafusion_physical_expr::utils::collect_columns;
-use datafusion_physical_expr::{PhysicalExpr, PhysicalSortExpr};
+use datafusion_physical_expr::{EquivalenceProperties, PhysicalExpr, PhysicalSortExpr};
 
 use async_trait::async_trait;
 use futures::{ready, FutureExt, StreamExt};
@@ -315,6 +320,11 @@ pub fn convert_sort_expr_with_filter_schema(
 ///
 /// The `SortedFilterExpr` instance contains information about the sort order of columns that can
 /// be used in the filter expression, which can be used to optimize the query execution process.
+///
+/// Returns `None` as well if the sort order can be used in the filter
+/// expression, but its values are not totally ordered under interval
+/// arithmetic, see [`is_range_filter_dimension_type`], so that the buffers are
+/// not pruned based on it.
 pub fn build_filter_input_order(
     side: JoinSide,
     filter: &JoinFilter,
@@ -322,6 +332,11 @@ pub fn build_filter_input_order(
     order: &PhysicalSortExpr,
 ) -> Result<Option<SortedFilterExpr>> {
     let opt_expr = convert_sort_expr_with_filter_schema(&side, filter, schema, order)?;
+    if let Some(filter_expr) = &opt_expr {
+        if !is_range_filter_dimension_type(&filter_expr.data_type(filter.schema())?) {
+            return Ok(None);
+        }
+    }
     opt_expr
         .map(|filter_expr| {
             SortedFilterExpr::try_new(order.clone(), filter_expr, filter.schema())
@@ -329,6 +344,114 @@ pub fn build_filter_input_order(
         .transpose()
 }
 
+/// Like [`build_filter_input_order`], but prefers an arithmetic expression of
+/// the filter that combines the sort expression `order` with other columns of
+/// the `input`, and that is sorted as well given the orderings of the `input`.
+/// E.g. the filter `a + b > c + 10` bounds the buffered rows of an input
+/// sorted on `a` if the input is sorted on `b` too, as the interval of `a + b`
+/// then follows from the intervals of `a` and `b`, while the interval of `a`
+/// alone is unbounded.
+pub fn build_filter_input_order_with_equivalences(
+    side: JoinSide,
+    filter: &JoinFilter,
+    input: &Arc<dyn ExecutionPlan>,
+    order: &PhysicalSortExpr,
+) -> Result<Option<SortedFilterExpr>> {
+    let schema = input.schema();
+    // Map the filter columns of this side back to the input columns:
+    let filter_schema = filter.schema();
+    let column_map = filter
+        .column_indices()
+        .iter()
+        .enumerate()
+        .filter(|(_, index)| index.side == side)
+        .map(|(filter_schema_index, index)| {
+            (
+                Column::new(
+                    filter_schema.field(filter_schema_index).name(),
+                    filter_schema_index,
+                ),
+                Column::new(schema.field(index.index).name(), index.index),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    let Some((filter_expr, sort_expr)) = find_sorted_filter_expr(
+        filter.expression(),
+        &column_map,
+        input.equivalence_properties(),
+        &order.expr,
+    )?
+    else {
+        return build_filter_input_order(side, filter, &schema, order);
+    };
+    if !is_range_filter_dimension_type(&filter_expr.data_type(filter_schema)?) {
+        return Ok(None);
+    }
+    SortedFilterExpr::try_new(sort_expr, filter_expr, filter_schema).map(Some)
+}
+
+/// Returns the outermost expression of `expr` that only refers to the filter
+/// columns in `column_map`, at least two of them, contains `sort_expr` once
+/// mapped to the input columns, and is sorted given the `eq_properties` of the
+/// input, along with its sort expression over the input columns.
+fn find_sorted_filter_expr(
+    expr: &Arc<dyn PhysicalExpr>,
+    column_map: &HashMap<Column, Column>,
+    eq_properties: &EquivalenceProperties,
+    sort_expr: &Arc<dyn PhysicalExpr>,
+) -> Result<Option<(Arc<dyn PhysicalExpr>, PhysicalSortExpr)>> {
+    let columns = collect_columns(expr);
+    if columns.len() > 1 && columns.iter().all(|col| column_map.contains_key(col)) {
+        let input_expr = expr
+            .clone()
+            .transform_up(&|p| {
+                convert_filter_columns(p.as_ref(), column_map).map(|transformed| {
+                    match transformed {
+                        Some(transformed) => Transformed::yes(transformed),
+                        None => Transformed::no(p),
+                    }
+                })
+            })
+            .data()?;
+        if check_filter_expr_contains_sort_information(&input_expr, sort_expr) {
+            if let SortProperties::Ordered(options) =
+                eq_properties.get_expr_ordering(input_expr.clone()).data
+            {
+                let sort_expr = PhysicalSortExpr {
+                    expr: input_expr,
+                    options,
+                };
+                return Ok(Some((expr.clone(), sort_expr)));
+            }
+        }
+    }
+    for child in expr.children() {
+        if let Some(found) =
+            find_sorted_filter_expr(&child, column_map, eq_properties, sort_expr)?
+        {
+            return Ok(Some(found));
+        }
+    }
+    Ok(None)
+}
+
+/// Returns whether the values of `data_type` can serve as a range filter
+/// dimension, whose bounds are derived with interval arithmetic.
+///
+/// Day-time and month-day-nano intervals consist of several fields, which are
+/// compared one after another, but are not normalized, so their order does not
+/// follow the time they span: e.g. `(0 days, 25 hours in ms)` compares less
+/// than `(1 day, 0 ms)`. Bounds derived with interval arithmetic therefore do
+/// not hold for them, and the buffers would be pruned too early. Year-month
+/// intervals consist of a single field and are totally ordered, like
+/// timestamps and durations.
+pub fn is_range_filter_dimension_type(data_type: &DataType) -> bool {
+    !matches!(
+        data_type,
+        DataType::Interval(IntervalUnit::DayTime | IntervalUnit::MonthDayNano)
+    )
+}
+
 /// Convert a physical expression into a filter expression using the given
 /// column mapping information.
 fn convert_filter_columns(
@@ -365,6 +488,9 @@ pub struct SortedFilterExpr {
     interval: Interval,
     /// Node index in the expression DAG
     node_index: usize,
+    /// Range all values of the expression are known to lie in, see
+    /// [`Self::set_bounds`]
+    bounds: Option<Interval>,
 }
 
 impl SortedFilterExpr {
@@ -380,6 +506,7 @@ impl SortedFilterExpr {
             filter_expr,
             interval: Interval::make_unbounded(dt)?,
             node_index: 0,
+            bounds: None,
         })
     }
     /// Get origin expr information
@@ -394,9 +521,25 @@ impl SortedFilterExpr {
     pub fn interval(&self) -> &Interval {
         &self.interval
     }
-    /// Sets interval
+    /// Sets interval, narrowed to the bounds of the expression if any
     pub fn set_interval(&mut self, interval: Interval) {
-        self.interval = interval;
+        // Values outside of the bounds should not occur, in which case the
+        // interval is kept as it is:
+        self.interval = match &self.bounds {
+            Some(bounds) => interval
+                .intersect(bounds)
+                .ok()
+                .flatten()
+                .unwrap_or(interval),
+            None => interval,
+        };
+    }
+    /// Sets the range all values of the expression are known to lie in, e.g.
+    /// due to a filter pushed down into the input, which narrows every
+    /// interval set from then on
+    pub fn set_bounds(&mut self, bounds: Interval) {
+        self.bounds = Some(bounds);
+        self.set_interval(self.interval.clone());
     }
     /// Node index in ExprIntervalGraph
     pub fn node_index(&self) -> usize {
@@ -505,6 +648,15 @@ pub fn update_filter_expr_interval(
         .into_array(1)?;
     // Convert the array to a ScalarValue:
     let value = ScalarValue::try_from_array(&array, 0)?;
+    update_filter_expr_interval_with_value(value, sorted_expr)
+}
+
+/// Updates the interval of the filter expression (i.e. `sorted_expr`) to the
+/// values at or after `value` in the sort order of the expression.
+pub fn update_filter_expr_interval_with_value(
+    value: ScalarValue,
+    sorted_expr: &mut SortedFilterExpr,
+) -> Result<()> {
     // Create a ScalarValue representing positive or negative infinity for the same data type:
     let inf = ScalarValue::try_from(value.data_type())?;
     // Update the interval with lower and upper bounds based on the sort option:
@@ -518,6 +670,80 @@ pub fn update_filter_expr_interval(
     Ok(())
 }
 
+/// Determines whether rows of the build side buffer can ever be pruned, taking
+/// the (independent) ordering directions of both sides into account.
+///
+/// Future probe side values are only bounded in the direction of the probe
+/// side ordering, i.e. `[pivot, ∞)` for ascending and `(-∞, pivot]` for
+/// descending inputs. Propagating this through the filter constrains the
+/// build side expression; the buffer is prunable only if the resulting bound
+/// faces the build side ordering (a lower bound when ascending, an upper bound
+/// when descending). Otherwise, pruning the buffer would silently drop rows
+/// that may still match future probe rows, so pruning is never possible.
+pub fn is_build_side_prunable(
+    graph: &mut ExprIntervalGraph,
+    build_sorted_filter_expr: &SortedFilterExpr,
+    probe_sorted_filter_expr: &SortedFilterExpr,
+    filter_schema: &Schema,
+) -> Result<bool> {
+    let build_type = build_sorted_filter_expr
+        .filter_expr()
+        .data_type(filter_schema)?;
+    let probe_type = probe_sorted_filter_expr
+        .filter_expr()
+        .data_type(filter_schema)?;
+    let probe_descending = probe_sorted_filter_expr
+        .origin_sorted_expr()
+        .options
+        .descending;
+    // Zero alone is not a representative pivot for sign-sensitive filters
+    // (e.g. `a * b > 100`), so also try a pivot one step behind it in the
+    // probe ordering direction whenever the type can represent it:
+    let mut pivots = vec![ScalarValue::new_zero(&probe_type)?];
+    let behind_zero = if probe_descending {
+        ScalarValue::new_one(&probe_type)
+    } else {
+        ScalarValue::new_negative_one(&probe_type)
+    };
+    pivots.extend(behind_zero.ok());
+    let inf = ScalarValue::try_from(&probe_type)?;
+    for pivot in pivots {
+        let probe_interval = if probe_descending {
+            Interval::try_new(inf.clone(), pivot)?
+        } else {
+            Interval::try_new(pivot, inf.clone())?
+        };
+        let mut filter_intervals = vec![
+            (
+                build_sorted_filter_expr.node_index(),
+                Interval::make_unbounded(&build_type)?,
+            ),
+            (probe_sorted_filter_expr.node_index(), probe_interval),
+        ];
+        match graph.update_ranges(&mut filter_intervals, Interval::CERTAINLY_TRUE)? {
+            // The filter can never be satisfied, so every buffered row is prunable:
+            PropagationResult::Infeasible => {}
+            PropagationResult::CannotPropagate => return Ok(false),
+            PropagationResult::Success => {
+                let build_interval = &filter_intervals[0].1;
+                let bound = if build_sorted_filter_expr
+                    .origin_sorted_expr()
+                    .options
+                    .descending
+                {
+                    build_interval.upper()
+                } else {
+                    build_interval.lower()
+                };
+                if bound.is_null() {
+                    return Ok(false);
+                }
+            }
+        }
+    }
+    Ok(true)
+}
+
 /// Get the anti join indices from the visited hash set.
 ///
 /// This method returns the indices from the original input that were not present in the visited hash set.
@@ -754,6 +980,9 @@ pub trait EagerJoinStream {
     /// This default implementation checks for the next value in the right stream.
     /// If a batch is found, the state is switched to `PullLeft`, and the batch handling
     /// is delegated to `process_batch_from_right`. If the stream ends, the state is set to `RightExhausted`.
+    /// An empty batch, e.g. a keepalive of a live input without new data, is
+    /// not processed, but still hands the turn over to the left stream, so
+    /// that a right input emitting only empty batches does not stall the left one.
     ///
     /// # Returns
     ///
@@ -763,10 +992,10 @@ pub trait EagerJoinStream {
     ) -> Result<StatefulStreamResult<Option<RecordBatch>>> {
         match self.right_stream().next().await {
             Some(Ok(batch)) => {
+                self.set_state(EagerJoinStreamState::PullLeft);
                 if batch.num_rows() == 0 {
                     return Ok(StatefulStreamResult::Continue);
                 }
-                self.set_state(EagerJoinStreamState::PullLeft);
                 self.process_batch_from_right(batch)
             }
             Some(Err(e)) => Err(e),
@@ -782,6 +1011,8 @@ pub trait EagerJoinStream {
     /// This default implementation checks for the next value in the left stream.
     /// If a batch is found, the state is switched to `PullRight`, and the batch handling
     /// is delegated to `process_batch_from_left`. If the stream ends, the state is set to `LeftExhausted`.
+    /// Like in [`Self::fetch_next_from_right_stream`], an empty batch is not
+    /// processed, but still hands the turn over to the right stream.
     ///
     /// # Returns
     ///
@@ -791,10 +1022,10 @@ pub trait EagerJoinStream {
     ) -> Result<StatefulStreamResult<Option<RecordBatch>>> {
         match self.left_stream().next().await {
             Some(Ok(batch)) => {
+                self.set_state(EagerJoinStreamState::PullRight);
                 if batch.num_rows() == 0 {
                     return Ok(StatefulStreamResult::Continue);
                 }
-                self.set_state(EagerJoinStreamState::PullRight);
                 self.process_batch_from_left(batch)
             }
             Some(Err(e)) => Err(e),
@@ -981,6 +1212,69 @@ pub struct StreamJoinSideMetrics {
     pub(crate) input_batches: metrics::Count,
     /// Number of rows consumed by this operator
     pub(crate) input_rows: metrics::Count,
+    /// Number of rows in the buffer of this side
+    pub(crate) buffered_rows: metrics::Gauge,
+    /// Memory used by the buffer and hash table of this side in bytes
+    pub(crate) buffered_bytes: metrics::Gauge,
+    /// Buffered rows retained by every pruning dimension, starting with the
+    /// leading one. Empty unless the join prunes on several dimensions.
+    pub(crate) dimensions: Vec<PruningDimensionMetrics>,
+}
+
+impl StreamJoinSideMetrics {
+    fn new(
+        side: JoinSide,
+        num_pruning_dimensions: usize,
+        partition: usize,
+        metrics: &ExecutionPlanMetricsSet,
+    ) -> Self {
+        let input_batches =
+            MetricBuilder::new(metrics).counter("input_batches", partition);
+        let input_rows = MetricBuilder::new(metrics).counter("input_rows", partition);
+
+        let buffered_rows = MetricBuilder::new(metrics)
+            .with_new_label("side", side.to_string())
+            .gauge("buffered_rows", partition);
+
+        let buffered_bytes = MetricBuilder::new(metrics)
+            .with_new_label("side", side.to_string())
+            .gauge("buffered_bytes", partition);
+
+        let dimensions = (0..num_pruning_dimensions)
+            .map(|dimension| {
+                let gauge = |name| {
+                    MetricBuilder::new(metrics)
+                        .with_new_label("side", side.to_string())
+                        .with_new_label("dimension", dimension.to_string())
+                        .gauge(name, partition)
+                };
+                PruningDimensionMetrics {
+                    retained_rows: gauge("dimension_retained_rows"),
+                    retained_bytes: gauge("dimension_retained_bytes"),
+                }
+            })
+            .collect();
+
+        Self {
+            input_batches,
+            input_rows,
+            buffered_rows,
+            buffered_bytes,
+            dimensions,
+        }
+    }
+}
+
+/// Metrics of one pruning dimension of a side: the buffered rows outside of
+/// the window of the dimension, which pruning on it alone could not remove.
+/// The dimension retaining the most rows holds back the pruning of the side.
+#[derive(Debug)]
+pub struct PruningDimensionMetrics {
+    /// Number of buffered rows retained by the dimension
+    pub(crate) retained_rows: metrics::Gauge,
+    /// Memory used by the retained rows in bytes, estimated as their share of
+    /// the memory used by the side
+    pub(crate) retained_bytes: metrics::Gauge,
 }
 
 /// Metrics for HashJoinExec
@@ -996,25 +1290,43 @@ pub struct StreamJoinMetrics {
     pub(crate) output_batches: metrics::Count,
     /// Number of rows produced by this operator
     pub(crate) output_rows: metrics::Count,
+    /// Number of pruning passes run on the side buffers
+    pub(crate) prune_passes: metrics::Count,
+    /// Number of pruning passes that had to recompute interval bounds
+    pub(crate) prune_bound_computations: metrics::Count,
+    /// Number of buffered rows removed by pruning passes
+    pub(crate) pruned_rows: metrics::Count,
+    /// Number of duplicate input rows dropped before joining
+    pub(crate) duplicate_rows: metrics::Count,
+    /// Number of externally injected watermarks applied to the side buffers
+    pub(crate) injected_watermarks: metrics::Count,
 }
 
 impl StreamJoinMetrics {
-    pub fn new(partition: usize, metrics: &ExecutionPlanMetricsSet) -> Self {
-        let input_batches =
-            MetricBuilder::new(metrics).counter("input_batches", partition);
-        let input_rows = MetricBuilder::new(metrics).counter("input_rows", partition);
-        let left = StreamJoinSideMetrics {
-            input_batches,
-            input_rows,
-        };
-
-        let input_batches =
-            MetricBuilder::new(metrics).counter("input_batches", partition);
-        let input_rows = MetricBuilder::new(metrics).counter("input_rows", partition);
-        let right = StreamJoinSideMetrics {
-            input_batches,
-            input_rows,
+    /// Creates the metrics of a join, reporting the rows retained by each of
+    /// its `num_pruning_dimensions` if it prunes on more than one
+    pub fn new(
+        partition: usize,
+        num_pruning_dimensions: usize,
+        metrics: &ExecutionPlanMetricsSet,
+    ) -> Self {
+        let num_pruning_dimensions = if num_pruning_dimensions > 1 {
+            num_pruning_dimensions
+        } else {
+            0
         };
+        let left = StreamJoinSideMetrics::new(
+            JoinSide::Left,
+            num_pruning_dimensions,
+            partition,
+            metrics,
+        );
+        let right = StreamJoinSideMetrics::new(
+            JoinSide::Right,
+            num_pruning_dimensions,
+            partition,
+            metrics,
+        );
 
         let stream_memory_usage =
             MetricBuilder::new(metrics).gauge("stream_memory_usage", partition);
@@ -1024,12 +1336,30 @@ impl StreamJoinMetrics {
 
         let output_rows = MetricBuilder::new(metrics).output_rows(partition);
 
+        let prune_passes = MetricBuilder::new(metrics).counter("prune_passes", partition);
+
+        let prune_bound_computations =
+            MetricBuilder::new(metrics).counter("prune_bound_computations", partition);
+
+        let pruned_rows = MetricBuilder::new(metrics).counter("pruned_rows", partition);
+
+        let duplicate_rows =
+            MetricBuilder::new(metrics).counter("duplicate_rows", partition);
+
+        let injected_watermarks =
+            MetricBuilder::new(metrics).counter("injected_watermarks", partition);
+
         Self {
             left,
             right,
             output_batches,
             stream_memory_usage,
             output_rows,
+            prune_passes,
+            prune_bound_computations,
+            pruned_rows,
+            duplicate_rows,
+            injected_watermarks,
         }
     }
 }
@@ -1041,7 +1371,7 @@ impl StreamJoinMetrics {
 /// gathers the corresponding node indices from the expression interval graph,
 /// and then updates the sorted expressions with these indices. It ensures
 /// that these sorted expressions are aligned with the structure of the graph.
-fn update_sorted_exprs_with_node_indices(
+pub(crate) fn update_sorted_exprs_with_node_indices(
     graph: &mut ExprIntervalGraph,
     sorted_exprs: &mut [SortedFilterExpr],
 ) {
@@ -1081,29 +1411,68 @@ pub fn prepare_sorted_exprs(
     left_sort_exprs: &[PhysicalSortExpr],
     right_sort_exprs: &[PhysicalSortExpr],
 ) -> Result<(SortedFilterExpr, SortedFilterExpr, ExprIntervalGraph)> {
-    // Build the filter order for the left side
-    let err = || plan_datafusion_err!("Filter does not include the child order");
+    let (left_sorted_filter_expr, right_sorted_filter_expr, _, graph) =
+        prepare_sorted_exprs_with_dimensions(
+            filter,
+            left,
+            right,
+            left_sort_exprs,
+            right_sort_exprs,
+            &[],
+        )?;
+    Ok((left_sorted_filter_expr, right_sorted_filter_expr, graph))
+}
 
-    let left_temp_sorted_filter_expr = build_filter_input_order(
-        JoinSide::Left,
-        filter,
-        &left.schema(),
-        &left_sort_exprs[0],
-    )?
-    .ok_or_else(err)?;
-
-    // Build the filter order for the right side
-    let right_temp_sorted_filter_expr = build_filter_input_order(
-        JoinSide::Right,
-        filter,
-        &right.schema(),
-        &right_sort_exprs[0],
-    )?
-    .ok_or_else(err)?;
+/// Like [`prepare_sorted_exprs`], additionally preparing the sorted filter
+/// expressions of the pruning `dimensions`, pairs of left and right side sort
+/// expressions besides the leading ones, within the same expression graph.
+///
+/// # Returns
+///
+/// * A tuple consisting of the sorted filter expression for the left and right
+///   sides, the left and right sorted filter expressions of every dimension, and
+///   an expression interval graph.
+pub fn prepare_sorted_exprs_with_dimensions(
+    filter: &JoinFilter,
+    left: &Arc<dyn ExecutionPlan>,
+    right: &Arc<dyn ExecutionPlan>,
+    left_sort_exprs: &[PhysicalSortExpr],
+    right_sort_exprs: &[PhysicalSortExpr],
+    dimensions: &[(PhysicalSortExpr, PhysicalSortExpr)],
+) -> Result<(
+    SortedFilterExpr,
+    SortedFilterExpr,
+    Vec<(SortedFilterExpr, SortedFilterExpr)>,
+    ExprIntervalGraph,
+)> {
+    let err = || plan_datafusion_err!("Filter does not include the child order");
 
-    // Collect the sorted expressions
-    let mut sorted_exprs =
-        vec![left_temp_sorted_filter_expr, right_temp_sorted_filter_expr];
+    // Build the filter order for the left and right side of every dimension,
+    // starting with the leading sort expressions
+    let mut sorted_exprs = vec![];
+    for (left_sort_expr, right_sort_expr) in
+        std::iter::once((&left_sort_exprs[0], &right_sort_exprs[0]))
+            .chain(dimensions.iter().map(|(left, right)| (left, right)))
+    {
+        sorted_exprs.push(
+            build_filter_input_order_with_equivalences(
+                JoinSide::Left,
+                filter,
+                left,
+                left_sort_expr,
+            )?
+            .ok_or_else(err)?,
+        );
+        sorted_exprs.push(
+            build_filter_input_order_with_equivalences(
+                JoinSide::Right,
+                filter,
+                right,
+                right_sort_expr,
+            )?
+            .ok_or_else(err)?,
+        );
+    }
 
     // Build the expression interval graph
     let mut graph =
@@ -1112,11 +1481,20 @@ pub fn prepare_sorted_exprs(
     // Update sorted expressions with node indices
     update_sorted_exprs_with_node_indices(&mut graph, &mut sorted_exprs);
 
-    // Swap and remove to get the final sorted filter expressions
-    let right_sorted_filter_expr = sorted_exprs.swap_remove(1);
-    let left_sorted_filter_expr = sorted_exprs.swap_remove(0);
-
-    Ok((left_sorted_filter_expr, right_sorted_filter_expr, graph))
+    // Pair up the left and right sorted filter expressions again
+    let mut sorted_exprs = sorted_exprs.into_iter();
+    let mut pairs = vec![];
+    while let (Some(left), Some(right)) = (sorted_exprs.next(), sorted_exprs.next()) {
+        pairs.push((left, right));
+    }
+    let (left_sorted_filter_expr, right_sorted_filter_expr) = pairs.remove(0);
+
+    Ok((
+        left_sorted_filter_expr,
+        right_sorted_filter_expr,
+        pairs,
+        graph,
+    ))
 }
 
 #[cfg(test)]
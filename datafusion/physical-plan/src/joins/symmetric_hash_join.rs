@@ -28,26 +28,30 @@
 use std::any::Any;
 use std::fmt::{self, Debug};
 use std::sync::Arc;
-use std::task::Poll;
+use std::task::{Poll, Waker};
 use std::{usize, vec};
 
 use crate::common::SharedMemoryReservation;
 use crate::joins::hash_join::{equal_rows_arr, update_hash};
 use crate::joins::stream_join_utils::{
-    calculate_filter_expr_intervals, combine_two_batches,
-    convert_sort_expr_with_filter_schema, get_pruning_anti_indices,
-    get_pruning_semi_indices, prepare_sorted_exprs, record_visited_indices,
-    EagerJoinStream, EagerJoinStreamState, PruningJoinHashMap, SortedFilterExpr,
+    build_filter_input_order, build_filter_input_order_with_equivalences,
+    calculate_filter_expr_intervals, combine_two_batches, get_pruning_anti_indices,
+    get_pruning_semi_indices, is_build_side_prunable, is_range_filter_dimension_type,
+    prepare_sorted_exprs, prepare_sorted_exprs_with_dimensions, record_visited_indices,
+    update_filter_expr_interval, update_filter_expr_interval_with_value, EagerJoinStream,
+    EagerJoinStreamState, PruningDimensionMetrics, PruningJoinHashMap, SortedFilterExpr,
     StreamJoinMetrics,
 };
 use crate::joins::utils::{
     apply_join_filter_to_indices, build_batch_from_indices, build_join_schema,
-    check_join_is_valid, partitioned_join_output_partitioning, ColumnIndex, JoinFilter,
-    JoinHashMapType, JoinOn, JoinOnRef, StatefulStreamResult,
+    check_join_is_valid, get_anti_indices, get_semi_indices, join_metrics_json,
+    limit_output_rows, partitioned_join_output_partitioning, punctuation_batch,
+    skip_empty_batches, ColumnIndex, JoinFilter, JoinHashMapType, JoinOn, JoinOnRef,
+    NullPadding, OutputRowLimit, SetOperation, StatefulStreamResult,
 };
 use crate::{
     execution_mode_from_children,
-    expressions::PhysicalSortExpr,
+    expressions::{Column, PhysicalSortExpr},
     joins::StreamJoinPartitionMode,
     metrics::{ExecutionPlanMetricsSet, MetricsSet},
     DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, ExecutionPlanProperties,
@@ -55,25 +59,32 @@ use crate::{
 };
 
 use arrow::array::{
-    ArrowPrimitiveType, NativeAdapter, PrimitiveArray, PrimitiveBuilder, UInt32Array,
-    UInt64Array,
+    downcast_array, ArrowPrimitiveType, BooleanArray, NativeAdapter, PrimitiveArray,
+    PrimitiveBuilder, Scalar, UInt32Array, UInt64Array,
 };
-use arrow::compute::concat_batches;
+use arrow::compute::kernels::cmp::not_distinct;
+use arrow::compute::{concat_batches, filter_record_batch, take_record_batch};
 use arrow::datatypes::{Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
+use arrow::row::{OwnedRow, RowConverter, Rows, SortField};
 use datafusion_common::hash_utils::create_hashes;
 use datafusion_common::utils::bisect;
-use datafusion_common::{internal_err, plan_err, JoinSide, JoinType, Result};
+use datafusion_common::{
+    exec_err, internal_err, not_impl_err, plan_err, JoinSide, JoinType, Result,
+    ScalarValue,
+};
 use datafusion_execution::memory_pool::MemoryConsumer;
 use datafusion_execution::TaskContext;
 use datafusion_expr::interval_arithmetic::Interval;
 use datafusion_physical_expr::equivalence::join_equivalence_properties;
 use datafusion_physical_expr::intervals::cp_solver::ExprIntervalGraph;
-use datafusion_physical_expr::{PhysicalExprRef, PhysicalSortRequirement};
+use datafusion_physical_expr::{
+    physical_exprs_contains, PhysicalExprRef, PhysicalSortRequirement,
+};
 
 use ahash::RandomState;
 use futures::Stream;
-use hashbrown::HashSet;
+use hashbrown::{HashMap, HashSet};
 use parking_lot::Mutex;
 
 const HASHMAP_SHRINK_SCALE_FACTOR: usize = 4;
@@ -189,6 +200,36 @@ pub struct SymmetricHashJoinExec {
     pub(crate) right_sort_exprs: Option<Vec<PhysicalSortExpr>>,
     /// Partition Mode
     mode: StreamJoinPartitionMode,
+    /// Number of probe batches after which a pruning pass runs on a side
+    prune_every: usize,
+    /// Left/right key pairs used to drop duplicate rows from each input
+    dedup_keys: JoinOn,
+    /// Tolerance applied to the floating point comparisons of the filter
+    float_tolerance: Option<f64>,
+    /// Limit on the number of output rows across all partitions, see
+    /// [`Self::with_max_output_rows`]
+    output_row_limit: Option<OutputRowLimit>,
+    /// Whether zero-row batches are dropped from the output, see
+    /// [`Self::with_skip_empty_output_batches`]
+    skip_empty_output_batches: bool,
+    /// Externally advanced watermarks of the inputs, see
+    /// [`Self::with_watermark_trigger`]
+    watermark_trigger: Option<WatermarkTrigger>,
+    /// Whether punctuation batches are emitted while no rows are produced, see
+    /// [`Self::with_forward_watermarks`]
+    forward_watermarks: bool,
+    /// Pairs of left and right sort expressions the buffers are pruned on
+    /// besides the leading sort expressions, see [`Self::with_pruning_dimensions`]
+    pruning_dimensions: Vec<(PhysicalSortExpr, PhysicalSortExpr)>,
+    /// Maximum number of matches produced per join key value, see
+    /// [`Self::with_max_matches_per_key`]
+    max_matches_per_key: Option<usize>,
+    /// Ranges the values of the leading left and right sort expressions are
+    /// known to lie in, see [`Self::with_sort_key_bounds`]
+    sort_key_bounds: (Option<Interval>, Option<Interval>),
+    /// Whether the order of the input batches is validated at runtime, see
+    /// [`Self::with_input_ordering_assertion`]
+    input_ordering_assertion: bool,
     /// Cache holding plan properties like equivalences, output partitioning etc.
     cache: PlanProperties,
 }
@@ -215,6 +256,12 @@ impl SymmetricHashJoinExec {
         let left_schema = left.schema();
         let right_schema = right.schema();
 
+        if matches!(join_type, JoinType::LeftMark | JoinType::RightMark) {
+            return not_impl_err!(
+                "SymmetricHashJoinExec does not support JoinType::{join_type}"
+            );
+        }
+
         // Error out if no "on" contraints are given:
         if on.is_empty() {
             return plan_err!(
@@ -224,6 +271,9 @@ impl SymmetricHashJoinExec {
 
         // Check if the join is valid with the given on constraints:
         check_join_is_valid(&left_schema, &right_schema, &on)?;
+        if let Some(filter) = &filter {
+            filter.validate(&left_schema, &right_schema)?;
+        }
 
         // Build the join schema from the left and right schemas:
         let (schema, column_indices) =
@@ -247,10 +297,355 @@ impl SymmetricHashJoinExec {
             left_sort_exprs,
             right_sort_exprs,
             mode,
+            prune_every: 1,
+            dedup_keys: vec![],
+            float_tolerance: None,
+            output_row_limit: None,
+            skip_empty_output_batches: false,
+            watermark_trigger: None,
+            forward_watermarks: false,
+            pruning_dimensions: vec![],
+            max_matches_per_key: None,
+            sort_key_bounds: (None, None),
+            input_ordering_assertion: false,
             cache,
         })
     }
 
+    /// Tries to create a new [SymmetricHashJoinExec] that evaluates a set
+    /// `operation` of `left` and `right` as a left semi join (for
+    /// [`SetOperation::Intersect`]) or a left anti join (for
+    /// [`SetOperation::Except`]) over two streams.
+    ///
+    /// The join keys are all the columns of both inputs, paired up by
+    /// position, and nulls compare equal, as required by set operation
+    /// semantics. The output has the schema of `left`, and contains every row
+    /// of `left` that has (for `Intersect`) or does not have (for `Except`) an
+    /// equal row in `right`, in no particular order:
+    ///
+    /// - Duplicate rows of `left` are emitted as often as they occur in `left`.
+    /// - Duplicate rows of `right` do not multiply the output rows, they only
+    ///   decide whether a row of `left` is emitted at all.
+    ///
+    /// The output thus matches SQL `INTERSECT` and `EXCEPT` only once its
+    /// duplicates are removed, e.g. by a downstream aggregation, and matches
+    /// neither `INTERSECT ALL` (which keeps as many copies of a row as appear
+    /// in both inputs) nor `EXCEPT ALL` (which subtracts the copies of a row in
+    /// `right` from those in `left`).
+    ///
+    /// # Error
+    /// Besides the errors of [`Self::try_new`], this function errors when the
+    /// inputs differ in their number of columns or in the type of a column.
+    pub fn try_new_set_operation(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        operation: SetOperation,
+        mode: StreamJoinPartitionMode,
+    ) -> Result<Self> {
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        if left_schema.fields().len() != right_schema.fields().len() {
+            return plan_err!(
+                "{operation:?} requires inputs with the same number of columns, got {} and {}",
+                left_schema.fields().len(),
+                right_schema.fields().len()
+            );
+        }
+        let on = left_schema
+            .fields()
+            .iter()
+            .zip(right_schema.fields().iter())
+            .enumerate()
+            .map(|(index, (left_field, right_field))| {
+                if left_field.data_type() != right_field.data_type() {
+                    return plan_err!(
+                        "{operation:?} requires matching column types, got {} and {} at position {index}",
+                        left_field.data_type(),
+                        right_field.data_type()
+                    );
+                }
+                Ok((
+                    Arc::new(Column::new(left_field.name(), index)) as PhysicalExprRef,
+                    Arc::new(Column::new(right_field.name(), index)) as PhysicalExprRef,
+                ))
+            })
+            .collect::<Result<JoinOn>>()?;
+        let join_type = match operation {
+            SetOperation::Intersect => JoinType::LeftSemi,
+            SetOperation::Except => JoinType::LeftAnti,
+        };
+        Self::try_new(left, right, on, None, &join_type, true, None, None, mode)
+    }
+
+    /// Sets how often a pruning pass runs on the buffer of each side: a pass
+    /// runs once every `prune_every` batches arriving from the opposite side.
+    ///
+    /// Pruning less often saves CPU time at the cost of buffering more rows.
+    /// Regardless of this setting, a pruning pass runs whenever the memory
+    /// reservation can not grow any further. Defaults to 1, i.e. pruning
+    /// after every batch. Values smaller than 1 are treated as 1.
+    pub fn with_prune_every(mut self, prune_every: usize) -> Self {
+        self.prune_every = prune_every.max(1);
+        self
+    }
+
+    /// Drops duplicate rows from both inputs before they enter the join
+    /// buffers. Two rows of a side are duplicates when they have the same
+    /// values for the side's expressions in `dedup_keys`.
+    ///
+    /// Both inputs must be sorted, and the keys of each side must include the
+    /// leading sort expression of that side. Since duplicates share their sort
+    /// value, a duplicate can only arrive while the watermark (the latest sort
+    /// value seen) still equals its sort value, so only the keys of the rows at
+    /// the watermark are remembered. An empty `dedup_keys` disables
+    /// deduplication.
+    pub fn with_dedup_keys(mut self, dedup_keys: JoinOn) -> Result<Self> {
+        if !dedup_keys.is_empty() {
+            check_join_is_valid(&self.left.schema(), &self.right.schema(), &dedup_keys)?;
+            let (left_keys, right_keys): (Vec<_>, Vec<_>) =
+                dedup_keys.iter().cloned().unzip();
+            for (keys, sort_exprs) in [
+                (left_keys, &self.left_sort_exprs),
+                (right_keys, &self.right_sort_exprs),
+            ] {
+                let includes_sort_expr = sort_exprs
+                    .as_ref()
+                    .and_then(|exprs| exprs.first())
+                    .map_or(false, |sort_expr| {
+                        physical_exprs_contains(&keys, &sort_expr.expr)
+                    });
+                if !includes_sort_expr {
+                    return plan_err!(
+                        "Deduplication keys of SymmetricHashJoinExec must include the sort expression of each side"
+                    );
+                }
+            }
+        }
+        self.dedup_keys = dedup_keys;
+        Ok(self)
+    }
+
+    /// Relaxes the floating point comparisons of the join filter by
+    /// `tolerance`, so that matches at the boundary of a `<`/`<=`/`>`/`>=`
+    /// comparison are kept despite rounding errors. See
+    /// [`JoinFilter::with_float_tolerance`] for how the filter is rewritten;
+    /// interval pruning uses the relaxed filter as well, so it never prunes
+    /// rows the relaxed filter would keep.
+    pub fn with_float_tolerance(mut self, tolerance: f64) -> Result<Self> {
+        if let Some(filter) = &self.filter {
+            // Validate the tolerance against the filter early:
+            filter.with_float_tolerance(tolerance)?;
+        }
+        self.float_tolerance = Some(tolerance);
+        Ok(self)
+    }
+
+    /// Fails the execution with `ResourcesExhausted` once the output rows of
+    /// all partitions together exceed `max_output_rows`, see
+    /// [`HashJoinExec::with_max_output_rows`]. By default, the output is
+    /// unlimited.
+    ///
+    /// [`HashJoinExec::with_max_output_rows`]: crate::joins::HashJoinExec::with_max_output_rows
+    pub fn with_max_output_rows(mut self, max_output_rows: usize) -> Self {
+        self.output_row_limit = Some(OutputRowLimit::new(max_output_rows));
+        self
+    }
+
+    /// Drops zero-row batches from the output instead of emitting them, see
+    /// [`HashJoinExec::with_skip_empty_output_batches`]. By default, batches
+    /// are emitted as produced, including empty ones.
+    ///
+    /// [`HashJoinExec::with_skip_empty_output_batches`]: crate::joins::HashJoinExec::with_skip_empty_output_batches
+    pub fn with_skip_empty_output_batches(mut self, skip: bool) -> Self {
+        self.skip_empty_output_batches = skip;
+        self
+    }
+
+    /// Lets `trigger` advance the watermarks of the inputs from outside the
+    /// plan, e.g. on punctuations or heartbeats of an event-time stream. An
+    /// advanced watermark prunes the buffer of the opposite side right away,
+    /// emitting the results of the pruned rows (such as unmatched outer rows),
+    /// even while no new input rows arrive.
+    ///
+    /// All partitions of the join observe the watermarks of `trigger`.
+    pub fn with_watermark_trigger(mut self, trigger: WatermarkTrigger) -> Self {
+        self.watermark_trigger = Some(trigger);
+        self
+    }
+
+    /// Forwards the progress of the inputs downstream, so that time-based
+    /// operators like windowed aggregations can advance while the join
+    /// produces no output. If set, every input batch and every watermark of
+    /// the [`WatermarkTrigger`] that results in no output rows emits a
+    /// punctuation batch instead, see [`punctuation_batch`]. It carries the
+    /// watermark of each sorted input, i.e. the value of its leading sort
+    /// expression in the latest row or the latest triggered watermark,
+    /// whichever came last. Punctuation batches are emitted even if
+    /// [`Self::with_skip_empty_output_batches`] is set. Defaults to false.
+    ///
+    /// [`punctuation_batch`]: crate::joins::utils::punctuation_batch
+    pub fn with_forward_watermarks(mut self, forward: bool) -> Self {
+        self.forward_watermarks = forward;
+        self
+    }
+
+    /// Prunes the buffers on the additional `dimensions`, pairs of left and
+    /// right side sort expressions, besides the leading sort expressions of the
+    /// inputs. This lets a filter bounding several independent column pairs at
+    /// once, e.g. both a time range and a numeric range, prune on all of them:
+    /// the intervals of all dimensions are propagated through the filter
+    /// together, and a buffered row is only pruned once the windows of all
+    /// dimensions have closed on it.
+    ///
+    /// Each input must be sorted on its expression of every dimension, e.g. by
+    /// an ordering equivalent to its leading one, and the filter must refer to
+    /// them. Watermarks of the [`WatermarkTrigger`] only advance the leading
+    /// dimension. An empty `dimensions` prunes on the leading sort expressions
+    /// only, the default.
+    pub fn with_pruning_dimensions(
+        mut self,
+        dimensions: Vec<(PhysicalSortExpr, PhysicalSortExpr)>,
+    ) -> Result<Self> {
+        if !dimensions.is_empty() {
+            let (Some(filter), Some(_), Some(_)) =
+                (&self.filter, &self.left_sort_exprs, &self.right_sort_exprs)
+            else {
+                return plan_err!(
+                    "Pruning dimensions of SymmetricHashJoinExec require a filter and sorted inputs"
+                );
+            };
+            for (left_sort_expr, right_sort_expr) in &dimensions {
+                for (side, input, sort_expr) in [
+                    (JoinSide::Left, &self.left, left_sort_expr),
+                    (JoinSide::Right, &self.right, right_sort_expr),
+                ] {
+                    if !input
+                        .equivalence_properties()
+                        .ordering_satisfy(std::slice::from_ref(sort_expr))
+                    {
+                        return plan_err!(
+                            "The {side} input of SymmetricHashJoinExec is not sorted on the pruning dimension {sort_expr}"
+                        );
+                    }
+                    let data_type = sort_expr.expr.data_type(&input.schema())?;
+                    if !is_range_filter_dimension_type(&data_type) {
+                        return plan_err!(
+                            "The pruning dimension {sort_expr} of SymmetricHashJoinExec is of type {data_type}, whose ordering is not preserved by interval arithmetic"
+                        );
+                    }
+                    if build_filter_input_order(side, filter, &input.schema(), sort_expr)?
+                        .is_none()
+                    {
+                        return plan_err!(
+                            "The filter of SymmetricHashJoinExec does not refer to the pruning dimension {sort_expr}"
+                        );
+                    }
+                }
+            }
+        }
+        self.pruning_dimensions = dimensions;
+        Ok(self)
+    }
+
+    /// Declares the ranges the values of the leading `left` and `right` sort
+    /// expressions are known to lie in, e.g. because a filter such as
+    /// `left.ts > '2023-06-01'` was pushed down into the input. The bounds are
+    /// intersected into the intervals the buffers are pruned by, which tightens
+    /// the bounds propagated through the filter: with a lower bound on the
+    /// probe side, buffered rows that can only match probe rows below it are
+    /// pruned before the probe side has advanced that far.
+    ///
+    /// The bounds must have the types of the sort expressions and hold for
+    /// every input row, otherwise rows may be pruned while they still have
+    /// matches. `None` leaves a side unbounded, the default.
+    pub fn with_sort_key_bounds(
+        mut self,
+        left: Option<Interval>,
+        right: Option<Interval>,
+    ) -> Result<Self> {
+        for (side, input, sort_exprs, bounds) in [
+            (JoinSide::Left, &self.left, &self.left_sort_exprs, &left),
+            (JoinSide::Right, &self.right, &self.right_sort_exprs, &right),
+        ] {
+            let Some(bounds) = bounds else {
+                continue;
+            };
+            let (Some(_), Some(sort_expr)) = (
+                &self.filter,
+                sort_exprs.as_ref().and_then(|exprs| exprs.first()),
+            ) else {
+                return plan_err!(
+                    "Sort key bounds of SymmetricHashJoinExec require a filter and sorted inputs"
+                );
+            };
+            let data_type = sort_expr.expr.data_type(&input.schema())?;
+            if bounds.data_type() != data_type {
+                return plan_err!(
+                    "The {side} sort key bounds of SymmetricHashJoinExec have type {}, expected {data_type}",
+                    bounds.data_type()
+                );
+            }
+        }
+        self.sort_key_bounds = (left, right);
+        Ok(self)
+    }
+
+    /// Caps the number of matches produced for each join key value at
+    /// `max_matches_per_key`, e.g. to match only the first 3 rows arriving for
+    /// every key. The join maintains a running match counter per key, and a
+    /// matching pair passing the filter is treated as if it failed the filter
+    /// once its key has reached the cap. Unmatched rows of outer and anti joins
+    /// are emitted accordingly.
+    ///
+    /// To keep the counters bounded, the counter of a key is dropped once no
+    /// row with that key is buffered on either side anymore, i.e. once pruning
+    /// (or watermarks) removed all of them. The cap therefore applies among the
+    /// rows of a key that are joinable at the same time. The join keys of both
+    /// sides must have the same types. `None` disables the cap, the default.
+    pub fn with_max_matches_per_key(
+        mut self,
+        max_matches_per_key: Option<usize>,
+    ) -> Result<Self> {
+        if max_matches_per_key == Some(0) {
+            return plan_err!(
+                "The maximum number of matches per key of SymmetricHashJoinExec must be positive"
+            );
+        }
+        if max_matches_per_key.is_some() {
+            let (left_schema, right_schema) = (self.left.schema(), self.right.schema());
+            for (left_key, right_key) in &self.on {
+                let left_type = left_key.data_type(&left_schema)?;
+                let right_type = right_key.data_type(&right_schema)?;
+                if left_type != right_type {
+                    return plan_err!(
+                        "Capping matches per key requires join keys of the same type, got {left_type} and {right_type}"
+                    );
+                }
+            }
+        }
+        self.max_matches_per_key = max_matches_per_key;
+        Ok(self)
+    }
+
+    /// Validates at runtime that the batches of each sorted input arrive in the
+    /// declared order, i.e. that every batch is sorted on the sort expressions
+    /// of its side and does not start before the previous batch ended. The
+    /// join prunes its buffers relying on this order, so a violation silently
+    /// produces wrong results otherwise; with the assertion, the execution
+    /// fails with an error naming the offending input instead.
+    ///
+    /// Checking converts the sort keys of every input batch into rows, so it
+    /// is meant for debugging and defaults to false.
+    pub fn with_input_ordering_assertion(mut self, assert: bool) -> Result<Self> {
+        if assert && self.left_sort_exprs.is_none() && self.right_sort_exprs.is_none() {
+            return plan_err!(
+                "Asserting the input ordering of SymmetricHashJoinExec requires a sorted input"
+            );
+        }
+        self.input_ordering_assertion = assert;
+        Ok(self)
+    }
+
     /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
     fn compute_properties(
         left: &Arc<dyn ExecutionPlan>,
@@ -321,6 +716,90 @@ impl SymmetricHashJoinExec {
         self.mode
     }
 
+    /// The metrics of this join as a JSON object suitable for machine
+    /// ingestion, see [`JOIN_METRICS_JSON_VERSION`] for its stability
+    ///
+    /// [`JOIN_METRICS_JSON_VERSION`]: crate::joins::utils::JOIN_METRICS_JSON_VERSION
+    pub fn metrics_json(&self) -> serde_json::Value {
+        join_metrics_json(
+            self.name(),
+            &self.join_type,
+            Some(format!("{:?}", self.mode)),
+            &self.metrics,
+        )
+    }
+
+    /// Get the pruning cadence, see [`Self::with_prune_every`]
+    pub fn prune_every(&self) -> usize {
+        self.prune_every
+    }
+
+    /// Key pairs used to drop duplicate rows from the inputs
+    pub fn dedup_keys(&self) -> JoinOnRef {
+        &self.dedup_keys
+    }
+
+    /// Tolerance applied to the floating point comparisons of the filter
+    pub fn float_tolerance(&self) -> Option<f64> {
+        self.float_tolerance
+    }
+
+    /// Maximum number of output rows across all partitions, if limited
+    pub fn max_output_rows(&self) -> Option<usize> {
+        self.output_row_limit.as_ref().map(|limit| limit.max_rows())
+    }
+
+    /// Whether zero-row batches are dropped from the output
+    pub fn skip_empty_output_batches(&self) -> bool {
+        self.skip_empty_output_batches
+    }
+
+    /// Handle advancing the watermarks of the inputs, if any
+    pub fn watermark_trigger(&self) -> Option<&WatermarkTrigger> {
+        self.watermark_trigger.as_ref()
+    }
+
+    /// Whether the progress of the inputs is forwarded in punctuation batches
+    pub fn forward_watermarks(&self) -> bool {
+        self.forward_watermarks
+    }
+
+    /// Pairs of left and right sort expressions the buffers are pruned on
+    /// besides the leading sort expressions
+    pub fn pruning_dimensions(&self) -> &[(PhysicalSortExpr, PhysicalSortExpr)] {
+        &self.pruning_dimensions
+    }
+
+    /// Maximum number of matches produced per join key value, if capped
+    pub fn max_matches_per_key(&self) -> Option<usize> {
+        self.max_matches_per_key
+    }
+
+    /// Ranges the values of the leading left and right sort expressions are
+    /// known to lie in
+    pub fn sort_key_bounds(&self) -> (Option<&Interval>, Option<&Interval>) {
+        (
+            self.sort_key_bounds.0.as_ref(),
+            self.sort_key_bounds.1.as_ref(),
+        )
+    }
+
+    /// Whether the order of the input batches is validated at runtime
+    pub fn input_ordering_assertion(&self) -> bool {
+        self.input_ordering_assertion
+    }
+
+    /// Returns the filter evaluated at execution time, i.e. the join filter
+    /// relaxed by the float tolerance, if any.
+    fn effective_filter(&self) -> Result<Option<JoinFilter>> {
+        match (&self.filter, self.float_tolerance) {
+            (Some(filter), Some(tolerance)) => {
+                filter.with_float_tolerance(tolerance).map(Some)
+            }
+            (filter, _) => Ok(filter.clone()),
+        }
+    }
+
     /// Get left_sort_exprs
     pub fn left_sort_exprs(&self) -> Option<&[PhysicalSortExpr]> {
         self.left_sort_exprs.as_deref()
@@ -331,6 +810,28 @@ impl SymmetricHashJoinExec {
         self.right_sort_exprs.as_deref()
     }
 
+    /// Whether the buffers can be bounded by the leading sort expressions,
+    /// which is not the case if their values are not totally ordered under
+    /// interval arithmetic, see [`is_range_filter_dimension_type`]. The join
+    /// then runs without pruning the buffers.
+    fn sort_exprs_bound_buffers(&self) -> Result<bool> {
+        let (Some(left_sort_expr), Some(right_sort_expr)) = (
+            self.left_sort_exprs
+                .as_ref()
+                .and_then(|exprs| exprs.first()),
+            self.right_sort_exprs
+                .as_ref()
+                .and_then(|exprs| exprs.first()),
+        ) else {
+            return Ok(false);
+        };
+        Ok(is_range_filter_dimension_type(
+            &left_sort_expr.expr.data_type(&self.left.schema())?,
+        ) && is_range_filter_dimension_type(
+            &right_sort_expr.expr.data_type(&self.right.schema())?,
+        ))
+    }
+
     /// Check if order information covers every column in the filter expression.
     pub fn check_if_order_information_available(&self) -> Result<bool> {
         if let Some(filter) = self.filter() {
@@ -338,17 +839,17 @@ impl SymmetricHashJoinExec {
             if let Some(left_ordering) = left.output_ordering() {
                 let right = self.right();
                 if let Some(right_ordering) = right.output_ordering() {
-                    let left_convertible = convert_sort_expr_with_filter_schema(
-                        &JoinSide::Left,
+                    let left_convertible = build_filter_input_order_with_equivalences(
+                        JoinSide::Left,
                         filter,
-                        &left.schema(),
+                        left,
                         &left_ordering[0],
                     )?
                     .is_some();
-                    let right_convertible = convert_sort_expr_with_filter_schema(
-                        &JoinSide::Right,
+                    let right_convertible = build_filter_input_order_with_equivalences(
+                        JoinSide::Right,
                         filter,
-                        &right.schema(),
+                        right,
                         &right_ordering[0],
                     )?
                     .is_some();
@@ -358,6 +859,42 @@ impl SymmetricHashJoinExec {
         }
         Ok(false)
     }
+
+    /// Check whether the ordering directions of the children allow pruning the
+    /// buffer of at least one side. Each side may be ordered ascending or
+    /// descending independently; combinations under which neither buffer can
+    /// ever be pruned make the join state grow without bounds.
+    pub fn check_if_order_directions_allow_pruning(&self) -> Result<bool> {
+        if !self.check_if_order_information_available()? {
+            return Ok(false);
+        }
+        let (Some(filter), Some(left_ordering), Some(right_ordering)) = (
+            self.effective_filter()?,
+            self.left.output_ordering(),
+            self.right.output_ordering(),
+        ) else {
+            return Ok(false);
+        };
+        let (left_sorted_filter_expr, right_sorted_filter_expr, mut graph) =
+            prepare_sorted_exprs(
+                &filter,
+                &self.left,
+                &self.right,
+                left_ordering,
+                right_ordering,
+            )?;
+        Ok(is_build_side_prunable(
+            &mut graph,
+            &left_sorted_filter_expr,
+            &right_sorted_filter_expr,
+            filter.schema(),
+        )? || is_build_side_prunable(
+            &mut graph,
+            &right_sorted_filter_expr,
+            &left_sorted_filter_expr,
+            filter.schema(),
+        )?)
+    }
 }
 
 impl DisplayAs for SymmetricHashJoinExec {
@@ -374,10 +911,63 @@ impl DisplayAs for SymmetricHashJoinExec {
                     .map(|(c1, c2)| format!("({}, {})", c1, c2))
                     .collect::<Vec<String>>()
                     .join(", ");
+                let display_dedup_keys = if self.dedup_keys.is_empty() {
+                    "".to_string()
+                } else {
+                    let dedup_keys = self
+                        .dedup_keys
+                        .iter()
+                        .map(|(c1, c2)| format!("({}, {})", c1, c2))
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    format!(", dedup_keys=[{dedup_keys}]")
+                };
+                let display_float_tolerance = self
+                    .float_tolerance
+                    .map_or_else(String::new, |t| format!(", float_tolerance={t}"));
+                let display_pruning_dimensions = if self.pruning_dimensions.is_empty() {
+                    "".to_string()
+                } else {
+                    let pruning_dimensions = self
+                        .pruning_dimensions
+                        .iter()
+                        .map(|(c1, c2)| format!("({}, {})", c1, c2))
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    format!(", pruning_dimensions=[{pruning_dimensions}]")
+                };
+                let display_sort_key_bounds = match &self.sort_key_bounds {
+                    (None, None) => "".to_string(),
+                    (left, right) => {
+                        let bounds = |bounds: &Option<Interval>| {
+                            bounds
+                                .as_ref()
+                                .map_or_else(|| "None".to_string(), |b| b.to_string())
+                        };
+                        format!(", sort_key_bounds=({}, {})", bounds(left), bounds(right))
+                    }
+                };
+                let display_max_matches_per_key = self
+                    .max_matches_per_key
+                    .map_or_else(String::new, |n| format!(", max_matches_per_key={n}"));
+                let display_input_ordering_assertion = if self.input_ordering_assertion {
+                    ", input_ordering_assertion=true"
+                } else {
+                    ""
+                };
                 write!(
                     f,
-                    "SymmetricHashJoinExec: mode={:?}, join_type={:?}, on=[{}]{}",
-                    self.mode, self.join_type, on, display_filter
+                    "SymmetricHashJoinExec: mode={:?}, join_type={:?}, on=[{}]{}{}{}{}{}{}{}",
+                    self.mode,
+                    self.join_type,
+                    on,
+                    display_filter,
+                    display_dedup_keys,
+                    display_float_tolerance,
+                    display_pruning_dimensions,
+                    display_sort_key_bounds,
+                    display_max_matches_per_key,
+                    display_input_ordering_assertion
                 )
             }
         }
@@ -435,7 +1025,7 @@ impl ExecutionPlan for SymmetricHashJoinExec {
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(SymmetricHashJoinExec::try_new(
+        let mut join = SymmetricHashJoinExec::try_new(
             children[0].clone(),
             children[1].clone(),
             self.on.clone(),
@@ -445,7 +1035,27 @@ impl ExecutionPlan for SymmetricHashJoinExec {
             self.left_sort_exprs.clone(),
             self.right_sort_exprs.clone(),
             self.mode,
-        )?))
+        )?
+        .with_prune_every(self.prune_every)
+        .with_dedup_keys(self.dedup_keys.clone())?
+        .with_pruning_dimensions(self.pruning_dimensions.clone())?
+        .with_max_matches_per_key(self.max_matches_per_key)?
+        .with_sort_key_bounds(
+            self.sort_key_bounds.0.clone(),
+            self.sort_key_bounds.1.clone(),
+        )?
+        .with_input_ordering_assertion(self.input_ordering_assertion)?;
+        if let Some(tolerance) = self.float_tolerance {
+            join = join.with_float_tolerance(tolerance)?;
+        }
+        join.output_row_limit = self
+            .output_row_limit
+            .as_ref()
+            .map(|limit| OutputRowLimit::new(limit.max_rows()));
+        join.skip_empty_output_batches = self.skip_empty_output_batches;
+        join.watermark_trigger = self.watermark_trigger.clone();
+        join.forward_watermarks = self.forward_watermarks;
+        Ok(Arc::new(join))
     }
 
     fn metrics(&self) -> Option<MetricsSet> {
@@ -470,30 +1080,94 @@ impl ExecutionPlan for SymmetricHashJoinExec {
                  consider using RepartitionExec"
             );
         }
+        // Relax the filter by the float tolerance, if any, before building the
+        // expression graph so that pruning agrees with filter evaluation.
+        let filter = self.effective_filter()?;
         // If `filter_state` and `filter` are both present, then calculate sorted filter expressions
         // for both sides, and build an expression graph.
-        let (left_sorted_filter_expr, right_sorted_filter_expr, graph) =
-            match (&self.left_sort_exprs, &self.right_sort_exprs, &self.filter) {
-                (Some(left_sort_exprs), Some(right_sort_exprs), Some(filter)) => {
-                    let (left, right, graph) = prepare_sorted_exprs(
+        let (
+            left_sorted_filter_expr,
+            right_sorted_filter_expr,
+            pruning_dimensions,
+            graph,
+        ) = match (&self.left_sort_exprs, &self.right_sort_exprs, &filter) {
+            (Some(left_sort_exprs), Some(right_sort_exprs), Some(filter))
+                if self.sort_exprs_bound_buffers()? =>
+            {
+                let (mut left, mut right, pruning_dimensions, graph) =
+                    prepare_sorted_exprs_with_dimensions(
                         filter,
                         &self.left,
                         &self.right,
                         left_sort_exprs,
                         right_sort_exprs,
+                        &self.pruning_dimensions,
                     )?;
-                    (Some(left), Some(right), Some(graph))
+                if let Some(bounds) = &self.sort_key_bounds.0 {
+                    left.set_bounds(bounds.clone());
                 }
-                // If `filter_state` or `filter` is not present, then return None for all three values:
-                _ => (None, None, None),
-            };
+                if let Some(bounds) = &self.sort_key_bounds.1 {
+                    right.set_bounds(bounds.clone());
+                }
+                (Some(left), Some(right), pruning_dimensions, Some(graph))
+            }
+            // If `filter_state` or `filter` is not present, or the buffers can
+            // not be pruned, then return None for all values:
+            _ => (None, None, vec![], None),
+        };
 
-        let (on_left, on_right) = self.on.iter().cloned().unzip();
+        let (on_left, on_right): (Vec<_>, Vec<_>) = self.on.iter().cloned().unzip();
+        let match_counter = self
+            .max_matches_per_key
+            .map(|max_matches| {
+                KeyMatchCounter::try_new(&on_left, &self.left.schema(), max_matches)
+            })
+            .transpose()?;
 
-        let left_side_joiner =
+        let mut left_side_joiner =
             OneSideHashJoiner::new(JoinSide::Left, on_left, self.left.schema());
-        let right_side_joiner =
+        let mut right_side_joiner =
             OneSideHashJoiner::new(JoinSide::Right, on_right, self.right.schema());
+        if let (false, Some(left_sort_exprs), Some(right_sort_exprs)) = (
+            self.dedup_keys.is_empty(),
+            &self.left_sort_exprs,
+            &self.right_sort_exprs,
+        ) {
+            let (dedup_left, dedup_right) = self.dedup_keys.iter().cloned().unzip();
+            left_side_joiner.deduplicator = Some(RowDeduplicator::try_new(
+                dedup_left,
+                left_sort_exprs[0].expr.clone(),
+                &self.left.schema(),
+            )?);
+            right_side_joiner.deduplicator = Some(RowDeduplicator::try_new(
+                dedup_right,
+                right_sort_exprs[0].expr.clone(),
+                &self.right.schema(),
+            )?);
+        }
+
+        if self.input_ordering_assertion {
+            for (joiner, sort_exprs, schema) in [
+                (
+                    &mut left_side_joiner,
+                    &self.left_sort_exprs,
+                    self.left.schema(),
+                ),
+                (
+                    &mut right_side_joiner,
+                    &self.right_sort_exprs,
+                    self.right.schema(),
+                ),
+            ] {
+                if let Some(sort_exprs) = sort_exprs {
+                    joiner.order_checker = Some(InputOrderChecker::try_new(
+                        joiner.build_side,
+                        sort_exprs.clone(),
+                        &schema,
+                    )?);
+                }
+            }
+        }
 
         let left_stream = self.left.execute(partition, context.clone())?;
 
@@ -506,25 +1180,110 @@ impl ExecutionPlan for SymmetricHashJoinExec {
         if let Some(g) = graph.as_ref() {
             reservation.lock().try_grow(g.size())?;
         }
+        let null_padding = NullPadding::new(
+            MemoryConsumer::new(format!("SymmetricHashJoinNullPadding[{partition}]"))
+                .register(context.memory_pool()),
+        );
 
-        Ok(Box::pin(SymmetricHashJoinStream {
+        let stream = Box::pin(SymmetricHashJoinStream {
             left_stream,
             right_stream,
             schema: self.schema(),
-            filter: self.filter.clone(),
+            filter,
             join_type: self.join_type,
             random_state: self.random_state.clone(),
             left: left_side_joiner,
             right: right_side_joiner,
             column_indices: self.column_indices.clone(),
-            metrics: StreamJoinMetrics::new(partition, &self.metrics),
+            metrics: StreamJoinMetrics::new(
+                partition,
+                pruning_dimensions.len() + 1,
+                &self.metrics,
+            ),
             graph,
             left_sorted_filter_expr,
             right_sorted_filter_expr,
+            pruning_dimensions,
             null_equals_null: self.null_equals_null,
+            prune_every: self.prune_every,
+            match_counter,
             state: EagerJoinStreamState::PullRight,
             reservation,
-        }))
+            watermark_trigger: self.watermark_trigger.clone(),
+            applied_watermarks: (None, None),
+            watermark_forwarder: self.forward_watermarks.then(|| {
+                let leading_expr = |sort_exprs: &Option<Vec<PhysicalSortExpr>>| {
+                    sort_exprs
+                        .as_ref()
+                        .and_then(|exprs| exprs.first())
+                        .map(|sort_expr| sort_expr.expr.clone())
+                };
+                WatermarkForwarder::new(
+                    leading_expr(&self.left_sort_exprs),
+                    leading_expr(&self.right_sort_exprs),
+                )
+            }),
+            null_padding,
+        });
+        Ok(limit_output_rows(
+            skip_empty_batches(stream, self.skip_empty_output_batches),
+            self.output_row_limit.as_ref(),
+            partition,
+        ))
+    }
+}
+
+/// Handle advancing the watermarks of the inputs of a [`SymmetricHashJoinExec`]
+/// from outside the plan, see [`SymmetricHashJoinExec::with_watermark_trigger`].
+///
+/// Clones of the handle share the same watermarks.
+#[derive(Debug, Clone, Default)]
+pub struct WatermarkTrigger {
+    state: Arc<Mutex<WatermarkState>>,
+}
+
+#[derive(Debug, Default)]
+struct WatermarkState {
+    left: Option<ScalarValue>,
+    right: Option<ScalarValue>,
+    /// Wakers of the join streams waiting for input
+    wakers: Vec<Waker>,
+}
+
+impl WatermarkTrigger {
+    /// Creates a handle without watermarks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares that no future row of the `side` input has a value of the
+    /// leading sort expression of that side before `watermark` in the sort
+    /// order. Rows arriving later must honor the declaration, otherwise their
+    /// matches with already pruned rows are lost.
+    pub fn advance(&self, side: JoinSide, watermark: ScalarValue) {
+        let mut state = self.state.lock();
+        match side {
+            JoinSide::Left => state.left = Some(watermark),
+            JoinSide::Right => state.right = Some(watermark),
+        }
+        state.wakers.drain(..).for_each(Waker::wake);
+    }
+
+    /// Returns the latest watermark of the `side` input, if any
+    pub fn watermark(&self, side: JoinSide) -> Option<ScalarValue> {
+        let state = self.state.lock();
+        match side {
+            JoinSide::Left => state.left.clone(),
+            JoinSide::Right => state.right.clone(),
+        }
+    }
+
+    /// Registers `waker` to be woken when a watermark advances
+    fn register(&self, waker: &Waker) {
+        let mut state = self.state.lock();
+        if !state.wakers.iter().any(|w| w.will_wake(waker)) {
+            state.wakers.push(waker.clone());
+        }
     }
 }
 
@@ -551,16 +1310,87 @@ struct SymmetricHashJoinStream {
     left_sorted_filter_expr: Option<SortedFilterExpr>,
     // Right globally sorted filter expr
     right_sorted_filter_expr: Option<SortedFilterExpr>,
+    /// Left and right sorted filter exprs of the additional pruning dimensions
+    pruning_dimensions: Vec<(SortedFilterExpr, SortedFilterExpr)>,
     /// Random state used for hashing initialization
     random_state: RandomState,
     /// If null_equals_null is true, null == null else null != null
     null_equals_null: bool,
+    /// Number of probe batches after which a pruning pass runs on a side
+    prune_every: usize,
+    /// Running match counters of the join keys, if matches per key are capped
+    match_counter: Option<KeyMatchCounter>,
     /// Metrics
     metrics: StreamJoinMetrics,
     /// Memory reservation
     reservation: SharedMemoryReservation,
     /// State machine for input execution
     state: EagerJoinStreamState,
+    /// Externally advanced watermarks of the inputs
+    watermark_trigger: Option<WatermarkTrigger>,
+    /// Watermarks of the left and right inputs applied so far
+    applied_watermarks: (Option<ScalarValue>, Option<ScalarValue>),
+    /// Progress of the inputs forwarded in punctuation batches, if enabled
+    watermark_forwarder: Option<WatermarkForwarder>,
+    /// Null arrays padding the columns of the side without a match
+    null_padding: NullPadding,
+}
+
+/// Tracks the watermarks of the inputs of a [`SymmetricHashJoinStream`] that
+/// are forwarded downstream, see [`SymmetricHashJoinExec::with_forward_watermarks`]
+struct WatermarkForwarder {
+    /// Leading sort expressions of the left and right inputs, if sorted
+    sort_exprs: (Option<PhysicalExprRef>, Option<PhysicalExprRef>),
+    /// Latest watermarks of the left and right inputs
+    watermarks: (Option<ScalarValue>, Option<ScalarValue>),
+}
+
+impl WatermarkForwarder {
+    fn new(
+        left_sort_expr: Option<PhysicalExprRef>,
+        right_sort_expr: Option<PhysicalExprRef>,
+    ) -> Self {
+        Self {
+            sort_exprs: (left_sort_expr, right_sort_expr),
+            watermarks: (None, None),
+        }
+    }
+
+    /// Advances the watermark of the `side` input to the sort value of the
+    /// last row of `batch`, if the input is sorted
+    fn observe(&mut self, batch: &RecordBatch, side: JoinSide) -> Result<()> {
+        let sort_expr = match side {
+            JoinSide::Left => &self.sort_exprs.0,
+            JoinSide::Right => &self.sort_exprs.1,
+        };
+        let (Some(sort_expr), Some(last_row)) =
+            (sort_expr, batch.num_rows().checked_sub(1))
+        else {
+            return Ok(());
+        };
+        let values = sort_expr
+            .evaluate(&batch.slice(last_row, 1))?
+            .into_array(1)?;
+        self.advance(side, ScalarValue::try_from_array(&values, 0)?);
+        Ok(())
+    }
+
+    /// Advances the watermark of the `side` input to `watermark`
+    fn advance(&mut self, side: JoinSide, watermark: ScalarValue) {
+        match side {
+            JoinSide::Left => self.watermarks.0 = Some(watermark),
+            JoinSide::Right => self.watermarks.1 = Some(watermark),
+        }
+    }
+
+    /// Creates a punctuation batch carrying the current watermarks
+    fn punctuation(&self, schema: &SchemaRef) -> Result<RecordBatch> {
+        punctuation_batch(
+            schema,
+            self.watermarks.0.as_ref(),
+            self.watermarks.1.as_ref(),
+        )
+    }
 }
 
 impl RecordBatchStream for SymmetricHashJoinStream {
@@ -576,7 +1406,11 @@ impl Stream for SymmetricHashJoinStream {
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
-        self.poll_next_impl(cx)
+        match self.apply_watermarks(cx) {
+            Ok(Some(batch)) => Poll::Ready(Some(Ok(batch))),
+            Ok(None) => self.poll_next_impl(cx),
+            Err(e) => Poll::Ready(Some(Err(e))),
+        }
     }
 }
 
@@ -621,6 +1455,20 @@ fn determine_prune_length(
     bisect::<true>(&[batch_arr], &[target], &[origin_sorted_expr.options])
 }
 
+/// Returns the build and probe side sorted filter expressions of a pruning
+/// `dimension`, holding the left and right ones, given the `probe_side`
+fn build_and_probe_side(
+    dimension: &mut (SortedFilterExpr, SortedFilterExpr),
+    probe_side: JoinSide,
+) -> (&mut SortedFilterExpr, &mut SortedFilterExpr) {
+    let (left, right) = dimension;
+    if probe_side.eq(&JoinSide::Left) {
+        (right, left)
+    } else {
+        (left, right)
+    }
+}
+
 /// This method determines if the result of the join should be produced in the final step or not.
 ///
 /// # Arguments
@@ -719,6 +1567,7 @@ where
 /// * `probe_schema` - The schema of the probe [RecordBatch].
 /// * `join_type` - The type of join to be performed.
 /// * `column_indices` - Indices of columns that are being joined.
+/// * `null_padding` - Null arrays padding the columns of the side without a match.
 ///
 /// # Returns
 ///
@@ -730,6 +1579,7 @@ pub(crate) fn build_side_determined_results(
     probe_schema: SchemaRef,
     join_type: JoinType,
     column_indices: &[ColumnIndex],
+    null_padding: &mut NullPadding,
 ) -> Result<Option<RecordBatch>> {
     // Check if we need to produce a result in the final output:
     if prune_length > 0
@@ -755,6 +1605,7 @@ pub(crate) fn build_side_determined_results(
             &probe_indices,
             column_indices,
             build_hash_joiner.build_side,
+            null_padding,
         )
         .map(|batch| (batch.num_rows() > 0).then_some(batch))
     } else {
@@ -777,6 +1628,8 @@ pub(crate) fn build_side_determined_results(
 /// * `column_indices` - An array of columns to be selected for the result of the join.
 /// * `random_state` - The random state for the join.
 /// * `null_equals_null` - A boolean indicating whether NULL values should be treated as equal when joining.
+/// * `match_counter` - Running match counters of the join keys, if matches per key are capped.
+/// * `null_padding` - Null arrays padding the columns of the side without a match.
 ///
 /// # Returns
 ///
@@ -793,6 +1646,8 @@ pub(crate) fn join_with_probe_batch(
     column_indices: &[ColumnIndex],
     random_state: &RandomState,
     null_equals_null: bool,
+    match_counter: Option<&mut KeyMatchCounter>,
+    null_padding: &mut NullPadding,
 ) -> Result<Option<RecordBatch>> {
     if build_hash_joiner.input_buffer.num_rows() == 0 || probe_batch.num_rows() == 0 {
         return Ok(None);
@@ -821,6 +1676,16 @@ pub(crate) fn join_with_probe_batch(
     } else {
         (build_indices, probe_indices)
     };
+    let (build_indices, probe_indices) = if let Some(match_counter) = match_counter {
+        match_counter.cap_matches(
+            probe_batch,
+            &probe_hash_joiner.on,
+            build_indices,
+            probe_indices,
+        )?
+    } else {
+        (build_indices, probe_indices)
+    };
 
     if need_to_produce_result_in_final(build_hash_joiner.build_side, join_type) {
         record_visited_indices(
@@ -853,59 +1718,202 @@ pub(crate) fn join_with_probe_batch(
             &probe_indices,
             column_indices,
             build_hash_joiner.build_side,
+            null_padding,
         )
         .map(|batch| (batch.num_rows() > 0).then_some(batch))
     }
 }
 
-/// This method performs lookups against JoinHashMap by hash values of join-key columns, and handles potential
-/// hash collisions.
+/// This method joins a probe side batch with the build side input buffer after
+/// the build side input has been exhausted.
+///
+/// Since no more build side rows will arrive, the probe batch does not need to
+/// be inserted into the probe side hash table: the rows of the batch are either
+/// matched now or never. Results determined by the probe side rows (unmatched
+/// rows of outer and anti joins, matched rows of semi joins) are therefore
+/// produced immediately, together with the regular join output.
 ///
 /// # Arguments
 ///
-/// * `build_hashmap` - hashmap collected from build side data.
-/// * `build_batch` - Build side record batch.
-/// * `probe_batch` - Probe side record batch.
-/// * `build_on` - An array of columns on which the join will be performed. The columns are from the build side of the join.
-/// * `probe_on` - An array of columns on which the join will be performed. The columns are from the probe side of the join.
+/// * `build_hash_joiner` - Build side hash joiner, whose input is exhausted
+/// * `probe_on` - Join key expressions of the probe side
+/// * `schema` - A reference to the schema of the output record batch.
+/// * `join_type` - The type of join to be performed.
+/// * `filter` - An optional filter on the join condition.
+/// * `probe_batch` - The probe side record batch.
+/// * `column_indices` - An array of columns to be selected for the result of the join.
 /// * `random_state` - The random state for the join.
 /// * `null_equals_null` - A boolean indicating whether NULL values should be treated as equal when joining.
-/// * `hashes_buffer` - Buffer used for probe side keys hash calculation.
-/// * `deleted_offset` - deleted offset for build side data.
+/// * `match_counter` - Running match counters of the join keys, if matches per key are capped.
+/// * `null_padding` - Null arrays padding the columns of the side without a match.
 ///
 /// # Returns
 ///
-/// A [Result] containing a tuple with two equal length arrays, representing indices of rows from build and probe side,
-/// matched by join key columns.
+/// A [Result] containing an optional record batch with all the results determined by `probe_batch`.
 #[allow(clippy::too_many_arguments)]
-fn lookup_join_hashmap(
-    build_hashmap: &PruningJoinHashMap,
-    build_batch: &RecordBatch,
-    probe_batch: &RecordBatch,
-    build_on: &[PhysicalExprRef],
+pub(crate) fn join_with_exhausted_build_side(
+    build_hash_joiner: &mut OneSideHashJoiner,
     probe_on: &[PhysicalExprRef],
+    schema: &SchemaRef,
+    join_type: JoinType,
+    filter: Option<&JoinFilter>,
+    probe_batch: &RecordBatch,
+    column_indices: &[ColumnIndex],
     random_state: &RandomState,
     null_equals_null: bool,
-    hashes_buffer: &mut Vec<u64>,
-    deleted_offset: Option<usize>,
-) -> Result<(UInt64Array, UInt32Array)> {
-    let keys_values = probe_on
-        .iter()
-        .map(|c| c.evaluate(probe_batch)?.into_array(probe_batch.num_rows()))
-        .collect::<Result<Vec<_>>>()?;
-    let build_join_values = build_on
-        .iter()
-        .map(|c| c.evaluate(build_batch)?.into_array(build_batch.num_rows()))
-        .collect::<Result<Vec<_>>>()?;
-
-    hashes_buffer.clear();
-    hashes_buffer.resize(probe_batch.num_rows(), 0);
-    let hash_values = create_hashes(&keys_values, random_state, hashes_buffer)?;
-
-    // As SymmetricHashJoin uses LIFO JoinHashMap, the chained list algorithm
-    // will return build indices for each probe row in a reverse order as such:
-    // Build Indices: [5, 4, 3]
-    // Probe Indices: [1, 1, 1]
+    match_counter: Option<&mut KeyMatchCounter>,
+    null_padding: &mut NullPadding,
+) -> Result<Option<RecordBatch>> {
+    let build_side = build_hash_joiner.build_side;
+    let (build_indices, probe_indices) = if build_hash_joiner.input_buffer.num_rows() == 0
+    {
+        (
+            UInt64Array::from_iter_values(vec![]),
+            UInt32Array::from_iter_values(vec![]),
+        )
+    } else {
+        let (build_indices, probe_indices) = lookup_join_hashmap(
+            &build_hash_joiner.hashmap,
+            &build_hash_joiner.input_buffer,
+            probe_batch,
+            &build_hash_joiner.on,
+            probe_on,
+            random_state,
+            null_equals_null,
+            &mut build_hash_joiner.hashes_buffer,
+            Some(build_hash_joiner.deleted_offset),
+        )?;
+        let (build_indices, probe_indices) = if let Some(filter) = filter {
+            apply_join_filter_to_indices(
+                &build_hash_joiner.input_buffer,
+                probe_batch,
+                build_indices,
+                probe_indices,
+                filter,
+                build_side,
+            )?
+        } else {
+            (build_indices, probe_indices)
+        };
+        if let Some(match_counter) = match_counter {
+            match_counter.cap_matches(
+                probe_batch,
+                probe_on,
+                build_indices,
+                probe_indices,
+            )?
+        } else {
+            (build_indices, probe_indices)
+        }
+    };
+
+    if need_to_produce_result_in_final(build_side, join_type) {
+        record_visited_indices(
+            &mut build_hash_joiner.visited_rows,
+            build_hash_joiner.deleted_offset,
+            &build_indices,
+        );
+    }
+    let equal_result = if build_indices.is_empty()
+        || matches!(
+            join_type,
+            JoinType::LeftAnti
+                | JoinType::RightAnti
+                | JoinType::LeftSemi
+                | JoinType::RightSemi
+        ) {
+        None
+    } else {
+        build_batch_from_indices(
+            schema,
+            &build_hash_joiner.input_buffer,
+            probe_batch,
+            &build_indices,
+            &probe_indices,
+            column_indices,
+            build_side,
+            null_padding,
+        )
+        .map(|batch| (batch.num_rows() > 0).then_some(batch))?
+    };
+
+    let probe_result = if need_to_produce_result_in_final(build_side.negate(), join_type)
+    {
+        let probe_range = 0..probe_batch.num_rows();
+        let probe_indices =
+            if matches!(join_type, JoinType::LeftSemi | JoinType::RightSemi) {
+                get_semi_indices(probe_range, &probe_indices)
+            } else {
+                get_anti_indices(probe_range, &probe_indices)
+            };
+        let mut builder = UInt64Array::builder(probe_indices.len());
+        builder.append_nulls(probe_indices.len());
+        build_batch_from_indices(
+            schema,
+            &build_hash_joiner.input_buffer,
+            probe_batch,
+            &builder.finish(),
+            &probe_indices,
+            column_indices,
+            build_side,
+            null_padding,
+        )
+        .map(|batch| (batch.num_rows() > 0).then_some(batch))?
+    } else {
+        None
+    };
+
+    combine_two_batches(schema, equal_result, probe_result)
+}
+
+/// This method performs lookups against JoinHashMap by hash values of join-key columns, and handles potential
+/// hash collisions.
+///
+/// # Arguments
+///
+/// * `build_hashmap` - hashmap collected from build side data.
+/// * `build_batch` - Build side record batch.
+/// * `probe_batch` - Probe side record batch.
+/// * `build_on` - An array of columns on which the join will be performed. The columns are from the build side of the join.
+/// * `probe_on` - An array of columns on which the join will be performed. The columns are from the probe side of the join.
+/// * `random_state` - The random state for the join.
+/// * `null_equals_null` - A boolean indicating whether NULL values should be treated as equal when joining.
+/// * `hashes_buffer` - Buffer used for probe side keys hash calculation.
+/// * `deleted_offset` - deleted offset for build side data.
+///
+/// # Returns
+///
+/// A [Result] containing a tuple with two equal length arrays, representing indices of rows from build and probe side,
+/// matched by join key columns.
+#[allow(clippy::too_many_arguments)]
+fn lookup_join_hashmap(
+    build_hashmap: &PruningJoinHashMap,
+    build_batch: &RecordBatch,
+    probe_batch: &RecordBatch,
+    build_on: &[PhysicalExprRef],
+    probe_on: &[PhysicalExprRef],
+    random_state: &RandomState,
+    null_equals_null: bool,
+    hashes_buffer: &mut Vec<u64>,
+    deleted_offset: Option<usize>,
+) -> Result<(UInt64Array, UInt32Array)> {
+    let keys_values = probe_on
+        .iter()
+        .map(|c| c.evaluate(probe_batch)?.into_array(probe_batch.num_rows()))
+        .collect::<Result<Vec<_>>>()?;
+    let build_join_values = build_on
+        .iter()
+        .map(|c| c.evaluate(build_batch)?.into_array(build_batch.num_rows()))
+        .collect::<Result<Vec<_>>>()?;
+
+    hashes_buffer.clear();
+    hashes_buffer.resize(probe_batch.num_rows(), 0);
+    let hash_values = create_hashes(&keys_values, random_state, hashes_buffer)?;
+
+    // As SymmetricHashJoin uses LIFO JoinHashMap, the chained list algorithm
+    // will return build indices for each probe row in a reverse order as such:
+    // Build Indices: [5, 4, 3]
+    // Probe Indices: [1, 1, 1]
     //
     // This affects the output sequence. Hypothetically, it's possible to preserve the lexicographic order on the build side.
     // Let's consider probe rows [0,1] as an example:
@@ -949,6 +1957,7 @@ fn lookup_join_hashmap(
         &build_join_values,
         &keys_values,
         null_equals_null,
+        None,
     )?;
 
     Ok((build_indices, probe_indices))
@@ -971,6 +1980,16 @@ pub struct OneSideHashJoiner {
     pub(crate) offset: usize,
     /// Deleted offset
     pub(crate) deleted_offset: usize,
+    /// Number of probe batches received since the last pruning pass
+    pub(crate) batches_since_prune: usize,
+    /// Drops duplicate incoming rows, if deduplication is enabled
+    pub(crate) deduplicator: Option<RowDeduplicator>,
+    /// Validates the order of incoming rows, if the input ordering is asserted
+    pub(crate) order_checker: Option<InputOrderChecker>,
+    /// Build and probe side filter intervals (i.e. watermarks) of the last
+    /// pruning pass that computed bounds without pruning anything. While the
+    /// watermarks stay the same, the pruning length stays zero.
+    pub(crate) prune_bounds_cache: Option<(Interval, Interval)>,
 }
 
 impl OneSideHashJoiner {
@@ -985,6 +2004,10 @@ impl OneSideHashJoiner {
         size += self.visited_rows.capacity() * std::mem::size_of::<usize>();
         size += std::mem::size_of_val(&self.offset);
         size += std::mem::size_of_val(&self.deleted_offset);
+        size += std::mem::size_of_val(&self.batches_since_prune);
+        size += self.deduplicator.as_ref().map_or(0, |d| d.size());
+        size += self.order_checker.as_ref().map_or(0, |c| c.size());
+        size += std::mem::size_of_val(&self.prune_bounds_cache);
         size
     }
     pub fn new(
@@ -1001,6 +2024,28 @@ impl OneSideHashJoiner {
             visited_rows: HashSet::new(),
             offset: 0,
             deleted_offset: 0,
+            batches_since_prune: 0,
+            deduplicator: None,
+            order_checker: None,
+            prune_bounds_cache: None,
+        }
+    }
+
+    /// Removes duplicate rows from the incoming `batch` if deduplication is
+    /// enabled, see [`RowDeduplicator`].
+    pub(crate) fn deduplicate(&mut self, batch: RecordBatch) -> Result<RecordBatch> {
+        match self.deduplicator.as_mut() {
+            Some(deduplicator) => deduplicator.deduplicate(batch),
+            None => Ok(batch),
+        }
+    }
+
+    /// Checks that the incoming `batch` continues the declared order of the
+    /// input, if the input ordering is asserted, see [`InputOrderChecker`].
+    pub(crate) fn check_order(&mut self, batch: &RecordBatch) -> Result<()> {
+        match self.order_checker.as_mut() {
+            Some(order_checker) => order_checker.check(batch),
+            None => Ok(()),
         }
     }
 
@@ -1042,44 +2087,54 @@ impl OneSideHashJoiner {
     ///
     /// # Arguments
     ///
-    /// * `build_side_sorted_filter_expr` - Build side mutable sorted filter expression..
-    /// * `probe_side_sorted_filter_expr` - Probe side mutable sorted filter expression.
+    /// * `dimensions` - Build and probe side mutable sorted filter expressions
+    ///   of every pruning dimension, starting with the leading one.
     /// * `graph` - A mutable reference to the physical expression graph.
     ///
     /// # Returns
     ///
-    /// A Result object that contains the pruning length.
+    /// A Result object that contains the pruning length, i.e. the number of
+    /// leading buffered rows for which the windows of all dimensions closed.
     pub(crate) fn calculate_prune_length_with_probe_batch(
         &mut self,
-        build_side_sorted_filter_expr: &mut SortedFilterExpr,
-        probe_side_sorted_filter_expr: &mut SortedFilterExpr,
+        dimensions: Vec<(&mut SortedFilterExpr, &mut SortedFilterExpr)>,
         graph: &mut ExprIntervalGraph,
     ) -> Result<usize> {
         // Return early if the input buffer is empty:
         if self.input_buffer.num_rows() == 0 {
             return Ok(0);
         }
-        // Process the build and probe side sorted filter expressions if both are present:
-        // Collect the sorted filter expressions into a vector of (node_index, interval) tuples:
+        // Collect the build and probe side sorted filter expressions of all
+        // dimensions into a vector of (node_index, interval) tuples:
         let mut filter_intervals = vec![];
-        for expr in [
-            &build_side_sorted_filter_expr,
-            &probe_side_sorted_filter_expr,
-        ] {
-            filter_intervals.push((expr.node_index(), expr.interval().clone()))
+        for (build_side_sorted_filter_expr, probe_side_sorted_filter_expr) in &dimensions
+        {
+            for expr in [build_side_sorted_filter_expr, probe_side_sorted_filter_expr] {
+                filter_intervals.push((expr.node_index(), expr.interval().clone()))
+            }
         }
         // Update the physical expression graph using the join filter intervals:
         graph.update_ranges(&mut filter_intervals, Interval::CERTAINLY_TRUE)?;
-        // Extract the new join filter interval for the build side:
-        let calculated_build_side_interval = filter_intervals.remove(0).1;
-        // If the intervals have not changed, return early without pruning:
-        if calculated_build_side_interval.eq(build_side_sorted_filter_expr.interval()) {
-            return Ok(0);
+        let mut prune_length = self.input_buffer.num_rows();
+        for ((build_side_sorted_filter_expr, _), (_, calculated_build_side_interval)) in
+            dimensions
+                .into_iter()
+                .zip(filter_intervals.into_iter().step_by(2))
+        {
+            // If the intervals have not changed, return early without pruning:
+            if calculated_build_side_interval.eq(build_side_sorted_filter_expr.interval())
+            {
+                return Ok(0);
+            }
+            // Update the build side interval and determine the pruning length,
+            // which is bounded by the dimension pruning the fewest rows:
+            build_side_sorted_filter_expr.set_interval(calculated_build_side_interval);
+            prune_length = prune_length.min(determine_prune_length(
+                &self.input_buffer,
+                build_side_sorted_filter_expr,
+            )?);
         }
-        // Update the build side interval and determine the pruning length:
-        build_side_sorted_filter_expr.set_interval(calculated_build_side_interval);
-
-        determine_prune_length(&self.input_buffer, build_side_sorted_filter_expr)
+        Ok(prune_length)
     }
 
     pub(crate) fn prune_internal_state(&mut self, prune_length: usize) -> Result<()> {
@@ -1093,14 +2148,376 @@ impl OneSideHashJoiner {
         for row in self.deleted_offset..(self.deleted_offset + prune_length) {
             self.visited_rows.remove(&row);
         }
-        // Update the input buffer after pruning:
-        self.input_buffer = self
-            .input_buffer
-            .slice(prune_length, self.input_buffer.num_rows() - prune_length);
+        // Update the input buffer after pruning. The remaining rows are copied,
+        // as a slice would hold on to the memory of the pruned rows, which the
+        // memory reservation would then keep accounting for:
+        if prune_length > 0 {
+            let num_rows = self.input_buffer.num_rows() as u32;
+            let remaining = UInt32Array::from_iter_values(prune_length as u32..num_rows);
+            self.input_buffer = take_record_batch(&self.input_buffer, &remaining)?;
+        }
         // Increment the deleted offset:
         self.deleted_offset += prune_length;
         Ok(())
     }
+
+    /// Records the number of buffered rows retained by each of the pruning
+    /// `dimensions`, i.e. the rows not yet outside of its window, and their
+    /// estimated memory usage.
+    pub(crate) fn record_retained_rows<'a>(
+        &self,
+        dimensions: impl Iterator<Item = &'a SortedFilterExpr>,
+        metrics: &[PruningDimensionMetrics],
+    ) -> Result<()> {
+        let num_rows = self.input_buffer.num_rows();
+        let size = self.size();
+        for (build_side_sorted_filter_expr, metrics) in dimensions.zip(metrics) {
+            let interval = build_side_sorted_filter_expr.interval();
+            let bound = if build_side_sorted_filter_expr
+                .origin_sorted_expr()
+                .options
+                .descending
+            {
+                interval.upper()
+            } else {
+                interval.lower()
+            };
+            // A dimension without a bound on the pruned end of its window has
+            // not been calculated yet, and retains all rows:
+            let retained_rows = if num_rows == 0 || bound.is_null() {
+                num_rows
+            } else {
+                num_rows
+                    - determine_prune_length(
+                        &self.input_buffer,
+                        build_side_sorted_filter_expr,
+                    )?
+            };
+            metrics.retained_rows.set(retained_rows);
+            metrics
+                .retained_bytes
+                .set(size * retained_rows / num_rows.max(1));
+        }
+        Ok(())
+    }
+}
+
+/// Checks that the batches of a sorted input arrive in its declared order, see
+/// [`SymmetricHashJoinExec::with_input_ordering_assertion`]. The sort keys are
+/// converted into rows, which compare in the order of the sort expressions, so
+/// only the key of the last row of the previous batch needs to be kept.
+pub(crate) struct InputOrderChecker {
+    /// Side of the checked input
+    side: JoinSide,
+    /// Sort expressions the input is declared to be sorted on
+    sort_exprs: Vec<PhysicalSortExpr>,
+    /// Converts sort key values into comparable rows
+    converter: RowConverter,
+    /// Sort key of the last row of the previous non-empty batch
+    last_row: Option<OwnedRow>,
+    /// Number of batches checked so far
+    num_batches: usize,
+}
+
+impl InputOrderChecker {
+    pub(crate) fn try_new(
+        side: JoinSide,
+        sort_exprs: Vec<PhysicalSortExpr>,
+        schema: &Schema,
+    ) -> Result<Self> {
+        let fields = sort_exprs
+            .iter()
+            .map(|sort_expr| {
+                Ok(SortField::new_with_options(
+                    sort_expr.expr.data_type(schema)?,
+                    sort_expr.options,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            side,
+            sort_exprs,
+            converter: RowConverter::new(fields)?,
+            last_row: None,
+            num_batches: 0,
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        let mut size = 0;
+        size += std::mem::size_of_val(self);
+        size += self.converter.size();
+        size += self.last_row.as_ref().map_or(0, |row| row.as_ref().len());
+        size
+    }
+
+    /// Returns an error if a row of `batch` sorts before the row preceding it,
+    /// either in `batch` or at the end of the previous batch.
+    pub(crate) fn check(&mut self, batch: &RecordBatch) -> Result<()> {
+        let batch_index = self.num_batches;
+        self.num_batches += 1;
+        let num_rows = batch.num_rows();
+        if num_rows == 0 {
+            return Ok(());
+        }
+        let sort_values = self
+            .sort_exprs
+            .iter()
+            .map(|sort_expr| sort_expr.expr.evaluate(batch)?.into_array(num_rows))
+            .collect::<Result<Vec<_>>>()?;
+        let rows = self.converter.convert_columns(&sort_values)?;
+
+        let mut previous = self.last_row.as_ref().map(|row| row.row());
+        for (row_index, row) in rows.iter().enumerate() {
+            if previous.is_some_and(|previous| previous > row) {
+                let sort_exprs = self
+                    .sort_exprs
+                    .iter()
+                    .map(|sort_expr| sort_expr.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return exec_err!(
+                    "The {} input of SymmetricHashJoinExec is not sorted on [{sort_exprs}]: row {row_index} of batch {batch_index} sorts before the row preceding it",
+                    self.side
+                );
+            }
+            previous = Some(row);
+        }
+        self.last_row = Some(rows.row(num_rows - 1).owned());
+        Ok(())
+    }
+}
+
+/// Drops rows of a sorted input whose deduplication key was seen before.
+///
+/// The deduplication key includes the expression the input is sorted on, so
+/// all duplicates of a row share its sort value. Once the watermark, i.e. the
+/// sort value of the latest row, moves past that value, the row can not be
+/// duplicated anymore. Hence only the keys of the rows at the watermark are
+/// kept, which bounds the memory used for deduplication.
+pub(crate) struct RowDeduplicator {
+    /// Expressions forming the deduplication key
+    keys: Vec<PhysicalExprRef>,
+    /// Expression the input is sorted on
+    sort_expr: PhysicalExprRef,
+    /// Converts key values into comparable rows
+    converter: RowConverter,
+    /// Sort value of the latest row
+    watermark: Option<ScalarValue>,
+    /// Keys of the rows at the watermark
+    seen: HashSet<OwnedRow>,
+    /// Total size of the keys in `seen` in bytes
+    seen_size: usize,
+}
+
+impl RowDeduplicator {
+    pub(crate) fn try_new(
+        keys: Vec<PhysicalExprRef>,
+        sort_expr: PhysicalExprRef,
+        schema: &Schema,
+    ) -> Result<Self> {
+        let fields = keys
+            .iter()
+            .map(|key| Ok(SortField::new(key.data_type(schema)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            keys,
+            sort_expr,
+            converter: RowConverter::new(fields)?,
+            watermark: None,
+            seen: HashSet::new(),
+            seen_size: 0,
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        let mut size = 0;
+        size += std::mem::size_of_val(self);
+        size += self.converter.size();
+        size += self.watermark.as_ref().map_or(0, |w| w.size());
+        size += self.seen.capacity() * std::mem::size_of::<OwnedRow>();
+        size += self.seen_size;
+        size
+    }
+
+    /// Removes the rows of `batch` whose key was seen before, either in an
+    /// earlier batch or earlier in `batch` itself, and advances the watermark.
+    pub(crate) fn deduplicate(&mut self, batch: RecordBatch) -> Result<RecordBatch> {
+        let num_rows = batch.num_rows();
+        if num_rows == 0 {
+            return Ok(batch);
+        }
+        let key_values = self
+            .keys
+            .iter()
+            .map(|key| key.evaluate(&batch)?.into_array(num_rows))
+            .collect::<Result<Vec<_>>>()?;
+        let rows = self.converter.convert_columns(&key_values)?;
+
+        // Keep the rows whose key is inserted for the first time:
+        let mask = rows
+            .iter()
+            .map(|row| {
+                let is_new = self.seen.insert(row.owned());
+                if is_new {
+                    self.seen_size += row.as_ref().len();
+                }
+                Some(is_new)
+            })
+            .collect::<BooleanArray>();
+
+        // Advance the watermark to the sort value of the last row, forgetting
+        // the keys of the rows left behind:
+        let sort_values = self.sort_expr.evaluate(&batch)?.into_array(num_rows)?;
+        let watermark = ScalarValue::try_from_array(&sort_values, num_rows - 1)?;
+        if self.watermark.as_ref() != Some(&watermark) {
+            let at_watermark = not_distinct(
+                &sort_values,
+                &Scalar::new(sort_values.slice(num_rows - 1, 1)),
+            )?;
+            let watermark_start = (0..num_rows)
+                .rev()
+                .take_while(|idx| at_watermark.value(*idx))
+                .last()
+                .unwrap_or(num_rows - 1);
+            self.seen.clear();
+            self.seen_size = 0;
+            for row in (watermark_start..num_rows).map(|idx| rows.row(idx)) {
+                if self.seen.insert(row.owned()) {
+                    self.seen_size += row.as_ref().len();
+                }
+            }
+            self.watermark = Some(watermark);
+        }
+
+        if mask.true_count() == num_rows {
+            Ok(batch)
+        } else {
+            Ok(filter_record_batch(&batch, &mask)?)
+        }
+    }
+}
+
+/// Running match counters of the join key values of a [`SymmetricHashJoinStream`],
+/// see [`SymmetricHashJoinExec::with_max_matches_per_key`].
+///
+/// Every matching pair joins rows of both sides sharing their key, so the
+/// counter of a key is only needed while one of its rows is buffered. Once
+/// pruning removes the last of them, the counter is dropped, which bounds the
+/// counters by the buffered rows.
+pub(crate) struct KeyMatchCounter {
+    /// Maximum number of matches per key
+    max_matches: usize,
+    /// Converts key values into comparable rows
+    converter: RowConverter,
+    /// Number of matches produced so far per key
+    counts: HashMap<OwnedRow, usize>,
+    /// Total size of the keys in `counts` in bytes
+    counts_size: usize,
+}
+
+impl KeyMatchCounter {
+    pub(crate) fn try_new(
+        keys: &[PhysicalExprRef],
+        schema: &Schema,
+        max_matches: usize,
+    ) -> Result<Self> {
+        let fields = keys
+            .iter()
+            .map(|key| Ok(SortField::new(key.data_type(schema)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            max_matches,
+            converter: RowConverter::new(fields)?,
+            counts: HashMap::new(),
+            counts_size: 0,
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        let mut size = 0;
+        size += std::mem::size_of_val(self);
+        size += self.converter.size();
+        size += self.counts.capacity() * std::mem::size_of::<(OwnedRow, usize)>();
+        size += self.counts_size;
+        size
+    }
+
+    /// Converts the values of the key expressions `on` in `batch` into rows
+    fn key_rows(&self, batch: &RecordBatch, on: &[PhysicalExprRef]) -> Result<Rows> {
+        let key_values = on
+            .iter()
+            .map(|key| key.evaluate(batch)?.into_array(batch.num_rows()))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self.converter.convert_columns(&key_values)?)
+    }
+
+    /// Keeps the matching pairs of `build_indices` and `probe_indices` whose
+    /// key has not reached the cap yet, in order, and counts them. The key of a
+    /// pair is taken from the values of `probe_on` in `probe_batch`.
+    pub(crate) fn cap_matches(
+        &mut self,
+        probe_batch: &RecordBatch,
+        probe_on: &[PhysicalExprRef],
+        build_indices: UInt64Array,
+        probe_indices: UInt32Array,
+    ) -> Result<(UInt64Array, UInt32Array)> {
+        if probe_indices.is_empty() {
+            return Ok((build_indices, probe_indices));
+        }
+        let rows = self.key_rows(probe_batch, probe_on)?;
+        let mask = probe_indices
+            .values()
+            .iter()
+            .map(|probe_index| {
+                let row = rows.row(*probe_index as usize);
+                let count = self.counts.entry(row.owned()).or_insert_with(|| {
+                    self.counts_size += row.as_ref().len();
+                    0
+                });
+                let is_kept = *count < self.max_matches;
+                if is_kept {
+                    *count += 1;
+                }
+                Some(is_kept)
+            })
+            .collect::<BooleanArray>();
+        if mask.true_count() == probe_indices.len() {
+            return Ok((build_indices, probe_indices));
+        }
+        let build_indices = arrow::compute::filter(&build_indices, &mask)?;
+        let probe_indices = arrow::compute::filter(&probe_indices, &mask)?;
+        Ok((
+            downcast_array(build_indices.as_ref()),
+            downcast_array(probe_indices.as_ref()),
+        ))
+    }
+
+    /// Drops the counters of the keys without buffered rows in `left` or
+    /// `right`.
+    pub(crate) fn retain_buffered(
+        &mut self,
+        left: &OneSideHashJoiner,
+        right: &OneSideHashJoiner,
+    ) -> Result<()> {
+        if self.counts.is_empty() {
+            return Ok(());
+        }
+        let mut buffered = HashSet::new();
+        for joiner in [left, right] {
+            let rows = self.key_rows(&joiner.input_buffer, &joiner.on)?;
+            buffered.extend(rows.iter().map(|row| row.owned()));
+        }
+        let counts_size = &mut self.counts_size;
+        self.counts.retain(|key, _| {
+            let is_buffered = buffered.contains(key);
+            if !is_buffered {
+                *counts_size -= key.row().as_ref().len();
+            }
+            is_buffered
+        });
+        Ok(())
+    }
 }
 
 impl EagerJoinStream for SymmetricHashJoinStream {
@@ -1108,44 +2525,42 @@ impl EagerJoinStream for SymmetricHashJoinStream {
         &mut self,
         batch: RecordBatch,
     ) -> Result<StatefulStreamResult<Option<RecordBatch>>> {
-        self.perform_join_for_given_side(batch, JoinSide::Right)
-            .map(|maybe_batch| {
-                if maybe_batch.is_some() {
-                    StatefulStreamResult::Ready(maybe_batch)
-                } else {
-                    StatefulStreamResult::Continue
-                }
-            })
+        let maybe_batch = self.perform_join_for_given_side(batch, JoinSide::Right)?;
+        self.output_or_punctuation(maybe_batch)
     }
 
     fn process_batch_from_left(
         &mut self,
         batch: RecordBatch,
     ) -> Result<StatefulStreamResult<Option<RecordBatch>>> {
-        self.perform_join_for_given_side(batch, JoinSide::Left)
-            .map(|maybe_batch| {
-                if maybe_batch.is_some() {
-                    StatefulStreamResult::Ready(maybe_batch)
-                } else {
-                    StatefulStreamResult::Continue
-                }
-            })
+        let maybe_batch = self.perform_join_for_given_side(batch, JoinSide::Left)?;
+        self.output_or_punctuation(maybe_batch)
     }
 
     fn process_batch_after_left_end(
         &mut self,
         right_batch: RecordBatch,
     ) -> Result<StatefulStreamResult<Option<RecordBatch>>> {
-        self.process_batch_from_right(right_batch)
+        let maybe_batch =
+            self.perform_join_after_build_side_end(right_batch, JoinSide::Right)?;
+        self.output_or_punctuation(maybe_batch)
     }
 
     fn process_batch_after_right_end(
         &mut self,
         left_batch: RecordBatch,
     ) -> Result<StatefulStreamResult<Option<RecordBatch>>> {
-        self.process_batch_from_left(left_batch)
+        let maybe_batch =
+            self.perform_join_after_build_side_end(left_batch, JoinSide::Left)?;
+        self.output_or_punctuation(maybe_batch)
     }
 
+    /// Emits all rows still buffered that the join type requires in the
+    /// output, e.g. the unmatched rows of outer joins, as a single batch. The
+    /// order of this batch only depends on the buffered rows: left side rows
+    /// come first, then right side rows, each in the order they were received.
+    /// As the inputs arrive sorted, the rows of each side are thus ordered by
+    /// their sort keys, with ties in insertion order.
     fn process_batches_before_finalization(
         &mut self,
     ) -> Result<StatefulStreamResult<Option<RecordBatch>>> {
@@ -1157,6 +2572,7 @@ impl EagerJoinStream for SymmetricHashJoinStream {
             self.right.input_buffer.schema(),
             self.join_type,
             &self.column_indices,
+            &mut self.null_padding,
         )?;
         // Get the right side results:
         let right_result = build_side_determined_results(
@@ -1166,6 +2582,7 @@ impl EagerJoinStream for SymmetricHashJoinStream {
             self.left.input_buffer.schema(),
             self.join_type,
             &self.column_indices,
+            &mut self.null_padding,
         )?;
 
         // Combine the left and right results:
@@ -1199,6 +2616,29 @@ impl EagerJoinStream for SymmetricHashJoinStream {
 }
 
 impl SymmetricHashJoinStream {
+    /// Turns the result of processing an input batch into the state result:
+    /// output rows are emitted as they are, and if there are none, either a
+    /// punctuation batch is emitted or the stream continues polling.
+    fn output_or_punctuation(
+        &self,
+        maybe_batch: Option<RecordBatch>,
+    ) -> Result<StatefulStreamResult<Option<RecordBatch>>> {
+        if let Some(forwarder) = &self.watermark_forwarder {
+            if maybe_batch
+                .as_ref()
+                .map_or(true, |batch| batch.num_rows() == 0)
+            {
+                let punctuation = forwarder.punctuation(&self.schema)?;
+                return Ok(StatefulStreamResult::Ready(Some(punctuation)));
+            }
+        }
+        if maybe_batch.is_some() {
+            Ok(StatefulStreamResult::Ready(maybe_batch))
+        } else {
+            Ok(StatefulStreamResult::Continue)
+        }
+    }
+
     fn size(&self) -> usize {
         let mut size = 0;
         size += std::mem::size_of_val(&self.schema);
@@ -1210,12 +2650,29 @@ impl SymmetricHashJoinStream {
         size += self.graph.as_ref().map(|g| g.size()).unwrap_or(0);
         size += std::mem::size_of_val(&self.left_sorted_filter_expr);
         size += std::mem::size_of_val(&self.right_sorted_filter_expr);
+        size += self.pruning_dimensions.capacity()
+            * std::mem::size_of::<(SortedFilterExpr, SortedFilterExpr)>();
         size += std::mem::size_of_val(&self.random_state);
         size += std::mem::size_of_val(&self.null_equals_null);
+        size += std::mem::size_of_val(&self.prune_every);
+        size += self.match_counter.as_ref().map_or(0, |c| c.size());
         size += std::mem::size_of_val(&self.metrics);
         size
     }
 
+    /// Updates the memory usage metrics of the stream and its sides to the
+    /// current state of the buffers, `capacity` being the size of the stream
+    fn update_memory_metrics(&self, capacity: usize) {
+        self.metrics.stream_memory_usage.set(capacity);
+        for (joiner, metrics) in [
+            (&self.left, &self.metrics.left),
+            (&self.right, &self.metrics.right),
+        ] {
+            metrics.buffered_rows.set(joiner.input_buffer.num_rows());
+            metrics.buffered_bytes.set(joiner.size());
+        }
+    }
+
     /// Performs a join operation for the specified `probe_side` (either left or right).
     /// This function:
     /// 1. Determines which side is the probe and which is the build side.
@@ -1228,32 +2685,25 @@ impl SymmetricHashJoinStream {
         probe_batch: RecordBatch,
         probe_side: JoinSide,
     ) -> Result<Option<RecordBatch>> {
-        let (
-            probe_hash_joiner,
-            build_hash_joiner,
-            probe_side_sorted_filter_expr,
-            build_side_sorted_filter_expr,
-            probe_side_metrics,
-        ) = if probe_side.eq(&JoinSide::Left) {
-            (
-                &mut self.left,
-                &mut self.right,
-                &mut self.left_sorted_filter_expr,
-                &mut self.right_sorted_filter_expr,
-                &mut self.metrics.left,
-            )
-        } else {
-            (
-                &mut self.right,
-                &mut self.left,
-                &mut self.right_sorted_filter_expr,
-                &mut self.left_sorted_filter_expr,
-                &mut self.metrics.right,
-            )
-        };
+        let (probe_hash_joiner, build_hash_joiner, probe_side_metrics) =
+            if probe_side.eq(&JoinSide::Left) {
+                (&mut self.left, &mut self.right, &mut self.metrics.left)
+            } else {
+                (&mut self.right, &mut self.left, &mut self.metrics.right)
+            };
         // Update the metrics for the stream that was polled:
         probe_side_metrics.input_batches.add(1);
         probe_side_metrics.input_rows.add(probe_batch.num_rows());
+        if let Some(forwarder) = &mut self.watermark_forwarder {
+            forwarder.observe(&probe_batch, probe_side)?;
+        }
+        probe_hash_joiner.check_order(&probe_batch)?;
+        // Drop duplicate rows before they enter the buffer:
+        let input_rows = probe_batch.num_rows();
+        let probe_batch = probe_hash_joiner.deduplicate(probe_batch)?;
+        self.metrics
+            .duplicate_rows
+            .add(input_rows - probe_batch.num_rows());
         // Update the internal state of the hash joiner for the build side:
         probe_hash_joiner.update_internal_state(&probe_batch, &self.random_state)?;
         // Join the two sides:
@@ -1267,57 +2717,434 @@ impl SymmetricHashJoinStream {
             &self.column_indices,
             &self.random_state,
             self.null_equals_null,
+            self.match_counter.as_mut(),
+            &mut self.null_padding,
         )?;
         // Increment the offset for the probe hash joiner:
         probe_hash_joiner.offset += probe_batch.num_rows();
 
-        let anti_result = if let (
-            Some(build_side_sorted_filter_expr),
-            Some(probe_side_sorted_filter_expr),
-            Some(graph),
-        ) = (
-            build_side_sorted_filter_expr.as_mut(),
-            probe_side_sorted_filter_expr.as_mut(),
-            self.graph.as_mut(),
-        ) {
-            // Calculate filter intervals:
-            calculate_filter_expr_intervals(
-                &build_hash_joiner.input_buffer,
-                build_side_sorted_filter_expr,
-                &probe_batch,
-                probe_side_sorted_filter_expr,
+        // Check whether the build side is due for a pruning pass:
+        build_hash_joiner.batches_since_prune += 1;
+        let prune_now = build_hash_joiner.batches_since_prune >= self.prune_every;
+        let anti_result = if prune_now {
+            self.prune_build_side(&probe_batch, probe_side)?
+        } else {
+            None
+        };
+
+        // Combine results:
+        let mut result = combine_two_batches(&self.schema, equal_result, anti_result)?;
+        let mut capacity = self.size();
+        let resized = self.reservation.lock().try_resize(capacity);
+        if let Err(e) = resized {
+            if prune_now {
+                return Err(e);
+            }
+            // The memory limit is reached, prune regardless of the cadence:
+            let anti_result = self.prune_build_side(&probe_batch, probe_side)?;
+            result = combine_two_batches(&self.schema, result, anti_result)?;
+            capacity = self.size();
+            self.reservation.lock().try_resize(capacity)?;
+        }
+        self.update_memory_metrics(capacity);
+        // Update the metrics if we have a batch; otherwise, continue the loop.
+        if let Some(batch) = &result {
+            self.metrics.output_batches.add(1);
+            self.metrics.output_rows.add(batch.num_rows());
+        }
+        Ok(result)
+    }
+
+    /// Performs a join operation for a batch of the `probe_side` once the input
+    /// of the other (build) side is exhausted. The symmetric join degenerates
+    /// into a one-sided hash join at this point:
+    /// 1. The rows buffered on the probe side so far can not be matched anymore,
+    ///    so their results are determined right away and the probe side buffer
+    ///    and hash table are released.
+    /// 2. Incoming probe batches are joined with the build side without being
+    ///    buffered themselves, see [`join_with_exhausted_build_side`].
+    /// 3. The build side is pruned as usual, since its buffer is still needed to
+    ///    match the remaining probe batches.
+    fn perform_join_after_build_side_end(
+        &mut self,
+        probe_batch: RecordBatch,
+        probe_side: JoinSide,
+    ) -> Result<Option<RecordBatch>> {
+        let (probe_hash_joiner, build_hash_joiner, probe_side_metrics) =
+            if probe_side.eq(&JoinSide::Left) {
+                (&mut self.left, &mut self.right, &mut self.metrics.left)
+            } else {
+                (&mut self.right, &mut self.left, &mut self.metrics.right)
+            };
+        // Update the metrics for the stream that was polled:
+        probe_side_metrics.input_batches.add(1);
+        probe_side_metrics.input_rows.add(probe_batch.num_rows());
+        if let Some(forwarder) = &mut self.watermark_forwarder {
+            forwarder.observe(&probe_batch, probe_side)?;
+        }
+        probe_hash_joiner.check_order(&probe_batch)?;
+        // Drop duplicate rows before joining:
+        let input_rows = probe_batch.num_rows();
+        let probe_batch = probe_hash_joiner.deduplicate(probe_batch)?;
+        self.metrics
+            .duplicate_rows
+            .add(input_rows - probe_batch.num_rows());
+        // Release the probe side buffer, producing the results it determines:
+        let buffered_rows = probe_hash_joiner.input_buffer.num_rows();
+        let buffered_result = if buffered_rows > 0 {
+            let result = build_side_determined_results(
+                probe_hash_joiner,
+                &self.schema,
+                buffered_rows,
+                build_hash_joiner.input_buffer.schema(),
+                self.join_type,
+                &self.column_indices,
+                &mut self.null_padding,
             )?;
-            let prune_length = build_hash_joiner
-                .calculate_prune_length_with_probe_batch(
+            probe_hash_joiner.prune_internal_state(buffered_rows)?;
+            result
+        } else {
+            None
+        };
+        // Join the probe batch with the build side:
+        let probe_result = join_with_exhausted_build_side(
+            build_hash_joiner,
+            &probe_hash_joiner.on,
+            &self.schema,
+            self.join_type,
+            self.filter.as_ref(),
+            &probe_batch,
+            &self.column_indices,
+            &self.random_state,
+            self.null_equals_null,
+            self.match_counter.as_mut(),
+            &mut self.null_padding,
+        )?;
+
+        // Check whether the build side is due for a pruning pass:
+        build_hash_joiner.batches_since_prune += 1;
+        let prune_now = build_hash_joiner.batches_since_prune >= self.prune_every;
+        let anti_result = if prune_now {
+            self.prune_build_side(&probe_batch, probe_side)?
+        } else {
+            None
+        };
+
+        // Forget the match counters of the keys of the released probe side rows:
+        if buffered_rows > 0 {
+            self.forget_unbuffered_keys()?;
+        }
+
+        // Combine results:
+        let result = combine_two_batches(&self.schema, buffered_result, probe_result)?;
+        let mut result = combine_two_batches(&self.schema, result, anti_result)?;
+        let mut capacity = self.size();
+        let resized = self.reservation.lock().try_resize(capacity);
+        if let Err(e) = resized {
+            if prune_now {
+                return Err(e);
+            }
+            // The memory limit is reached, prune regardless of the cadence:
+            let anti_result = self.prune_build_side(&probe_batch, probe_side)?;
+            result = combine_two_batches(&self.schema, result, anti_result)?;
+            capacity = self.size();
+            self.reservation.lock().try_resize(capacity)?;
+        }
+        self.update_memory_metrics(capacity);
+        // Update the metrics if we have a batch; otherwise, continue the loop.
+        if let Some(batch) = &result {
+            self.metrics.output_batches.add(1);
+            self.metrics.output_rows.add(batch.num_rows());
+        }
+        Ok(result)
+    }
+
+    /// Runs a pruning pass on the build side (i.e. the side opposite to
+    /// `probe_side`) using the interval bounds implied by `probe_batch`.
+    /// Returns the results determined by the pruned build side rows, if any.
+    fn prune_build_side(
+        &mut self,
+        probe_batch: &RecordBatch,
+        probe_side: JoinSide,
+    ) -> Result<Option<RecordBatch>> {
+        let (
+            build_hash_joiner,
+            build_side_sorted_filter_expr,
+            probe_side_sorted_filter_expr,
+            pruning_dimensions,
+        ) = self.pruning_sides(probe_side);
+        if let (
+            Some(build_side_sorted_filter_expr),
+            Some(probe_side_sorted_filter_expr),
+        ) = (
+            build_side_sorted_filter_expr.as_mut(),
+            probe_side_sorted_filter_expr.as_mut(),
+        ) {
+            // Calculate filter intervals of all dimensions:
+            let dimensions = std::iter::once((
+                build_side_sorted_filter_expr,
+                probe_side_sorted_filter_expr,
+            ))
+            .chain(
+                pruning_dimensions
+                    .iter_mut()
+                    .map(|dimension| build_and_probe_side(dimension, probe_side)),
+            );
+            for (build_side_sorted_filter_expr, probe_side_sorted_filter_expr) in
+                dimensions
+            {
+                calculate_filter_expr_intervals(
+                    &build_hash_joiner.input_buffer,
                     build_side_sorted_filter_expr,
+                    probe_batch,
                     probe_side_sorted_filter_expr,
-                    graph,
                 )?;
+            }
+        }
+        self.prune_build_side_with_intervals(probe_side)
+    }
+
+    /// Runs a pruning pass on the build side (i.e. the side opposite to
+    /// `probe_side`) using the interval bounds implied by the probe side
+    /// `watermark`, see [`WatermarkTrigger::advance`]. Returns the results
+    /// determined by the pruned build side rows, if any.
+    fn prune_build_side_at_watermark(
+        &mut self,
+        watermark: ScalarValue,
+        probe_side: JoinSide,
+    ) -> Result<Option<RecordBatch>> {
+        let (
+            build_hash_joiner,
+            build_side_sorted_filter_expr,
+            probe_side_sorted_filter_expr,
+            _,
+        ) = self.pruning_sides(probe_side);
+        if let (
+            Some(build_side_sorted_filter_expr),
+            Some(probe_side_sorted_filter_expr),
+        ) = (
+            build_side_sorted_filter_expr.as_mut(),
+            probe_side_sorted_filter_expr.as_mut(),
+        ) {
+            let data_type = probe_side_sorted_filter_expr.interval().data_type();
+            if watermark.data_type() != data_type {
+                return exec_err!(
+                    "Watermark of the {probe_side} side has type {}, expected {data_type}",
+                    watermark.data_type()
+                );
+            }
+            if build_hash_joiner.input_buffer.num_rows() == 0 {
+                return Ok(None);
+            }
+            // Calculate filter intervals:
+            update_filter_expr_interval(
+                &build_hash_joiner.input_buffer.slice(0, 1),
+                build_side_sorted_filter_expr,
+            )?;
+            update_filter_expr_interval_with_value(
+                watermark,
+                probe_side_sorted_filter_expr,
+            )?;
+        }
+        self.prune_build_side_with_intervals(probe_side)
+    }
+
+    /// Returns the hash joiner and the sorted filter expression of the build
+    /// side, the sorted filter expression of the probe side, and the left and
+    /// right sorted filter expressions of the additional pruning dimensions.
+    #[allow(clippy::type_complexity)]
+    fn pruning_sides(
+        &mut self,
+        probe_side: JoinSide,
+    ) -> (
+        &mut OneSideHashJoiner,
+        &mut Option<SortedFilterExpr>,
+        &mut Option<SortedFilterExpr>,
+        &mut Vec<(SortedFilterExpr, SortedFilterExpr)>,
+    ) {
+        if probe_side.eq(&JoinSide::Left) {
+            (
+                &mut self.right,
+                &mut self.right_sorted_filter_expr,
+                &mut self.left_sorted_filter_expr,
+                &mut self.pruning_dimensions,
+            )
+        } else {
+            (
+                &mut self.left,
+                &mut self.left_sorted_filter_expr,
+                &mut self.right_sorted_filter_expr,
+                &mut self.pruning_dimensions,
+            )
+        }
+    }
+
+    /// Prunes the build side (i.e. the side opposite to `probe_side`) using
+    /// the current intervals of the sorted filter expressions.
+    fn prune_build_side_with_intervals(
+        &mut self,
+        probe_side: JoinSide,
+    ) -> Result<Option<RecordBatch>> {
+        let (
+            probe_schema,
+            build_hash_joiner,
+            build_side_sorted_filter_expr,
+            probe_side_sorted_filter_expr,
+        ) = if probe_side.eq(&JoinSide::Left) {
+            (
+                self.left.input_buffer.schema(),
+                &mut self.right,
+                &mut self.right_sorted_filter_expr,
+                &mut self.left_sorted_filter_expr,
+            )
+        } else {
+            (
+                self.right.input_buffer.schema(),
+                &mut self.left,
+                &mut self.left_sorted_filter_expr,
+                &mut self.right_sorted_filter_expr,
+            )
+        };
+        if let (
+            Some(build_side_sorted_filter_expr),
+            Some(probe_side_sorted_filter_expr),
+            Some(graph),
+        ) = (
+            build_side_sorted_filter_expr.as_mut(),
+            probe_side_sorted_filter_expr.as_mut(),
+            self.graph.as_mut(),
+        ) {
+            // Skip recomputing bounds if neither watermark has advanced since
+            // the last pass, as it would yield the same (zero) pruning length:
+            let watermarks = (
+                build_side_sorted_filter_expr.interval().clone(),
+                probe_side_sorted_filter_expr.interval().clone(),
+            );
+            let prune_length =
+                if build_hash_joiner.prune_bounds_cache.as_ref() == Some(&watermarks) {
+                    0
+                } else {
+                    self.metrics.prune_bound_computations.add(1);
+                    let dimensions = std::iter::once((
+                        &mut *build_side_sorted_filter_expr,
+                        &mut *probe_side_sorted_filter_expr,
+                    ))
+                    .chain(
+                        self.pruning_dimensions
+                            .iter_mut()
+                            .map(|dimension| build_and_probe_side(dimension, probe_side)),
+                    )
+                    .collect();
+                    let prune_length = build_hash_joiner
+                        .calculate_prune_length_with_probe_batch(dimensions, graph)?;
+                    // Pruning moves the build side watermark, so only cache
+                    // passes that left the buffer intact:
+                    build_hash_joiner.prune_bounds_cache =
+                        (prune_length == 0).then_some(watermarks);
+                    prune_length
+                };
             let result = build_side_determined_results(
                 build_hash_joiner,
                 &self.schema,
                 prune_length,
-                probe_batch.schema(),
+                probe_schema,
                 self.join_type,
                 &self.column_indices,
+                &mut self.null_padding,
             )?;
             build_hash_joiner.prune_internal_state(prune_length)?;
-            result
+            build_hash_joiner.batches_since_prune = 0;
+            self.metrics.prune_passes.add(1);
+            self.metrics.pruned_rows.add(prune_length);
+            let build_side_metrics = if probe_side.eq(&JoinSide::Left) {
+                &self.metrics.right
+            } else {
+                &self.metrics.left
+            };
+            if !build_side_metrics.dimensions.is_empty() {
+                let dimensions = std::iter::once(&*build_side_sorted_filter_expr).chain(
+                    self.pruning_dimensions.iter().map(|(left, right)| {
+                        if probe_side.eq(&JoinSide::Left) {
+                            right
+                        } else {
+                            left
+                        }
+                    }),
+                );
+                build_hash_joiner
+                    .record_retained_rows(dimensions, &build_side_metrics.dimensions)?;
+            }
+            if prune_length > 0 {
+                self.forget_unbuffered_keys()?;
+            }
+            Ok(result)
         } else {
-            None
-        };
+            Ok(None)
+        }
+    }
 
-        // Combine results:
-        let result = combine_two_batches(&self.schema, equal_result, anti_result)?;
+    /// Drops the match counters of the keys no longer buffered on either side,
+    /// if matches per key are capped.
+    fn forget_unbuffered_keys(&mut self) -> Result<()> {
+        match &mut self.match_counter {
+            Some(match_counter) => match_counter.retain_buffered(&self.left, &self.right),
+            None => Ok(()),
+        }
+    }
+
+    /// Prunes the buffers with the watermarks advanced by the watermark
+    /// trigger since the last call, if any. Registers the waker of `cx` to be
+    /// woken on the next advance while the join is running. If watermarks are
+    /// forwarded and the pruning produces no rows, a punctuation batch is
+    /// returned instead.
+    fn apply_watermarks(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> Result<Option<RecordBatch>> {
+        let Some(trigger) = self.watermark_trigger.clone() else {
+            return Ok(None);
+        };
+        if matches!(self.state, EagerJoinStreamState::BothExhausted { .. }) {
+            return Ok(None);
+        }
+        trigger.register(cx.waker());
+        let mut result = None;
+        let mut advanced = false;
+        for probe_side in [JoinSide::Left, JoinSide::Right] {
+            let Some(watermark) = trigger.watermark(probe_side) else {
+                continue;
+            };
+            let applied = match probe_side {
+                JoinSide::Left => &mut self.applied_watermarks.0,
+                JoinSide::Right => &mut self.applied_watermarks.1,
+            };
+            if applied.as_ref() == Some(&watermark) {
+                continue;
+            }
+            *applied = Some(watermark.clone());
+            self.metrics.injected_watermarks.add(1);
+            if let Some(forwarder) = &mut self.watermark_forwarder {
+                forwarder.advance(probe_side, watermark.clone());
+            }
+            advanced = true;
+            let anti_result =
+                self.prune_build_side_at_watermark(watermark, probe_side)?;
+            result = combine_two_batches(&self.schema, result, anti_result)?;
+        }
         let capacity = self.size();
-        self.metrics.stream_memory_usage.set(capacity);
+        self.update_memory_metrics(capacity);
         self.reservation.lock().try_resize(capacity)?;
-        // Update the metrics if we have a batch; otherwise, continue the loop.
         if let Some(batch) = &result {
             self.metrics.output_batches.add(1);
             self.metrics.output_rows.add(batch.num_rows());
         }
-        Ok(result)
+        if !advanced {
+            return Ok(result);
+        }
+        match self.output_or_punctuation(result)? {
+            StatefulStreamResult::Ready(result) => Ok(result),
+            StatefulStreamResult::Continue => Ok(None),
+        }
     }
 }
 
@@ -1328,22 +3155,129 @@ mod tests {
 
     use super::*;
     use crate::joins::test_utils::{
-        build_sides_record_batches, compare_batches, complicated_filter,
-        create_memory_table, join_expr_tests_fixture_f64, join_expr_tests_fixture_i32,
-        join_expr_tests_fixture_temporal, partitioned_hash_join_with_filter,
+        build_sides_record_batches, compare_batches, complicated_4_column_exprs,
+        complicated_filter, create_memory_table, join_expr_tests_fixture_f64,
+        join_expr_tests_fixture_i32, join_expr_tests_fixture_i64,
+        join_expr_tests_fixture_non_prunable, join_expr_tests_fixture_temporal,
+        join_expr_tests_fixture_u64, partitioned_hash_join_with_filter,
         partitioned_sym_join_with_filter, split_record_batches,
     };
-
-    use arrow::compute::SortOptions;
-    use arrow::datatypes::{DataType, Field, IntervalUnit, Schema, TimeUnit};
-    use datafusion_common::ScalarValue;
+    use crate::joins::utils::{is_punctuation, is_streamable, punctuation_watermark};
+    use crate::memory::MemoryExec;
+    use crate::stream::RecordBatchStreamAdapter;
+    use crate::streaming::{PartitionStream, StreamingTableExec};
+    use crate::test::exec::BlockingExec;
+    use crate::{common, displayable};
+
+    use arrow::array::{ArrayRef, Float64Array, Int32Array, IntervalDayTimeArray};
+    use arrow::compute::{take, SortOptions};
+    use arrow::datatypes::{
+        DataType, Field, IntervalDayTimeType, IntervalUnit, Schema, TimeUnit,
+    };
+    use datafusion_common::{assert_batches_eq, assert_batches_sorted_eq};
     use datafusion_execution::config::SessionConfig;
     use datafusion_expr::Operator;
     use datafusion_physical_expr::expressions::{binary, col, lit, Column};
+    use datafusion_physical_expr::intervals::test_utils::gen_between_expr;
+    use datafusion_physical_expr::PhysicalExpr;
 
+    use futures::StreamExt;
     use once_cell::sync::Lazy;
     use rstest::*;
 
+    fn set_operation_reference(
+        left: &RecordBatch,
+        right: &RecordBatch,
+        operation: SetOperation,
+    ) -> Result<RecordBatch> {
+        let converter = RowConverter::new(
+            left.schema()
+                .fields()
+                .iter()
+                .map(|f| SortField::new(f.data_type().clone()))
+                .collect(),
+        )?;
+        let right_rows = converter.convert_columns(right.columns())?;
+        let right_set = right_rows.iter().collect::<HashSet<_>>();
+        let left_rows = converter.convert_columns(left.columns())?;
+        let mask = left_rows
+            .iter()
+            .map(|row| {
+                Some(right_set.contains(&row) == (operation == SetOperation::Intersect))
+            })
+            .collect::<BooleanArray>();
+        Ok(filter_record_batch(left, &mask)?)
+    }
+
+    #[tokio::test]
+    async fn test_set_operation_join() -> Result<()> {
+        let (left_batch, right_batch) = build_sides_record_batches(30, (4, 5))?;
+        // Keep a few columns, including one with nulls, and let the right side
+        // overlap with only a part of the left side:
+        let left_batch = left_batch.project(&[0, 1, 6])?;
+        let right_batch = right_batch.project(&[0, 1, 6])?.slice(5, 20);
+
+        for operation in [SetOperation::Intersect, SetOperation::Except] {
+            let (left, right) = create_memory_table(
+                split_record_batches(&left_batch, 8)?,
+                split_record_batches(&right_batch, 8)?,
+                vec![],
+                vec![],
+            )?;
+            let join = SymmetricHashJoinExec::try_new_set_operation(
+                left,
+                right,
+                operation,
+                StreamJoinPartitionMode::SinglePartition,
+            )?;
+            let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+            let batches = common::collect(stream).await?;
+
+            let expected = set_operation_reference(&left_batch, &right_batch, operation)?;
+            let row_count = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+            assert_eq!(row_count, expected.num_rows());
+            compare_batches(&batches, &[expected]);
+        }
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn set_operation_with_duplicates_and_nulls(
+        #[values(SetOperation::Intersect, SetOperation::Except)] operation: SetOperation,
+    ) -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let batch = |values: Vec<Option<i32>>| {
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(values))])
+        };
+        let left = batch(vec![Some(1), Some(1), Some(2), None, None, Some(3)])?;
+        let right = batch(vec![Some(1), Some(1), Some(1), None, Some(4)])?;
+        let (left, right) = create_memory_table(vec![left], vec![right], vec![], vec![])?;
+        let join = SymmetricHashJoinExec::try_new_set_operation(
+            left,
+            right,
+            operation,
+            StreamJoinPartitionMode::SinglePartition,
+        )?;
+        let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+        let batches = common::collect(stream).await?;
+
+        // Duplicates of the left side are kept, duplicates of the right side do
+        // not multiply the output, and nulls are equal:
+        let expected = match operation {
+            SetOperation::Intersect => vec![
+                "+---+", "| a |", "+---+", "|   |", "|   |", "| 1 |", "| 1 |", "+---+",
+            ],
+            SetOperation::Except => {
+                vec!["+---+", "| a |", "+---+", "| 2 |", "| 3 |", "+---+"]
+            }
+        };
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
     const TABLE_SIZE: i32 = 30;
 
     type TableKey = (i32, i32, usize); // (cardinality.0, cardinality.1, batch_size)
@@ -1497,7 +3431,7 @@ mod tests {
 
     #[rstest]
     #[tokio::test(flavor = "multi_thread")]
-    async fn join_all_one_ascending_numeric(
+    async fn complex_join_on_sum_of_sorted_columns(
         #[values(
             JoinType::Inner,
             JoinType::Left,
@@ -1509,27 +3443,30 @@ mod tests {
             JoinType::Full
         )]
         join_type: JoinType,
-        #[values(0, 1, 2, 3, 4, 5)] case_expr: usize,
     ) -> Result<()> {
+        // a + b > c + 10 AND a + b < c + 100, where the left input is sorted
+        // on both `a` and `b`, but not declared to be sorted on `a + b`
         let task_ctx = Arc::new(TaskContext::default());
+
         let (left_partition, right_partition) = get_or_create_table((4, 5), 8)?;
 
         let left_schema = &left_partition[0].schema();
         let right_schema = &right_partition[0].schema();
 
-        let left_sorted = vec![PhysicalSortExpr {
-            expr: col("la1", left_schema)?,
-            options: SortOptions::default(),
-        }];
-        let right_sorted = vec![PhysicalSortExpr {
-            expr: col("ra1", right_schema)?,
-            options: SortOptions::default(),
-        }];
+        let sorted_on = |name: &str, schema: &SchemaRef| -> Result<_> {
+            Ok(vec![PhysicalSortExpr {
+                expr: col(name, schema)?,
+                options: SortOptions::default(),
+            }])
+        };
         let (left, right) = create_memory_table(
             left_partition,
             right_partition,
-            vec![left_sorted],
-            vec![right_sorted],
+            vec![
+                sorted_on("la1", left_schema)?,
+                sorted_on("la2", left_schema)?,
+            ],
+            vec![sorted_on("ra1", right_schema)?],
         )?;
 
         let on = vec![(
@@ -1538,19 +3475,20 @@ mod tests {
         )];
 
         let intermediate_schema = Schema::new(vec![
-            Field::new("left", DataType::Int32, true),
-            Field::new("right", DataType::Int32, true),
+            Field::new("0", DataType::Int32, true),
+            Field::new("1", DataType::Int32, true),
+            Field::new("2", DataType::Int32, true),
         ]);
-        let filter_expr = join_expr_tests_fixture_i32(
-            case_expr,
-            col("left", &intermediate_schema)?,
-            col("right", &intermediate_schema)?,
-        );
+        let filter_expr = complicated_filter(&intermediate_schema)?;
         let column_indices = vec![
             ColumnIndex {
                 index: 0,
                 side: JoinSide::Left,
             },
+            ColumnIndex {
+                index: 4,
+                side: JoinSide::Left,
+            },
             ColumnIndex {
                 index: 0,
                 side: JoinSide::Right,
@@ -1558,13 +3496,37 @@ mod tests {
         ];
         let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
 
+        // The interval of `a + b` is derived from the orderings of `a` and `b`,
+        // so both buffers are pruned:
+        let join = SymmetricHashJoinExec::try_new(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            Some(filter.clone()),
+            &join_type,
+            false,
+            Some(sorted_on("la1", left_schema)?),
+            Some(sorted_on("ra1", right_schema)?),
+            StreamJoinPartitionMode::SinglePartition,
+        )?;
+        assert!(join.check_if_order_information_available()?);
+        assert!(join.check_if_order_directions_allow_pruning()?);
+        common::collect(join.execute(0, task_ctx.clone())?).await?;
+        let pruned_rows = join
+            .metrics()
+            .unwrap()
+            .sum_by_name("pruned_rows")
+            .unwrap()
+            .as_usize();
+        assert!(pruned_rows > 0);
+
         experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
         Ok(())
     }
 
     #[rstest]
     #[tokio::test(flavor = "multi_thread")]
-    async fn join_without_sort_information(
+    async fn join_with_repeated_filter_column(
         #[values(
             JoinType::Inner,
             JoinType::Left,
@@ -1576,37 +3538,63 @@ mod tests {
             JoinType::Full
         )]
         join_type: JoinType,
-        #[values(0, 1, 2, 3, 4, 5)] case_expr: usize,
+        #[values(
+        (4, 5),
+        (12, 17),
+        )]
+        cardinality: (i32, i32),
     ) -> Result<()> {
+        // a - b > a - c - 10 AND a < b + 10
         let task_ctx = Arc::new(TaskContext::default());
-        let (left_partition, right_partition) = get_or_create_table((4, 5), 8)?;
+
+        let (left_partition, right_partition) = get_or_create_table(cardinality, 8)?;
 
         let left_schema = &left_partition[0].schema();
         let right_schema = &right_partition[0].schema();
-        let (left, right) =
-            create_memory_table(left_partition, right_partition, vec![], vec![])?;
 
-        let on = vec![(
-            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("la1", left_schema)?,
+            options: SortOptions::default(),
+        }];
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("ra1", right_schema)?,
+            options: SortOptions::default(),
+        }];
+        let (left, right) = create_memory_table(
+            left_partition,
+            right_partition,
+            vec![left_sorted],
+            vec![right_sorted],
+        )?;
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
             Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
         )];
 
         let intermediate_schema = Schema::new(vec![
-            Field::new("left", DataType::Int32, true),
-            Field::new("right", DataType::Int32, true),
+            Field::new("0", DataType::Int32, true),
+            Field::new("1", DataType::Int32, true),
+            Field::new("2", DataType::Int32, true),
+            Field::new("3", DataType::Int32, true),
         ]);
-        let filter_expr = join_expr_tests_fixture_i32(
-            case_expr,
-            col("left", &intermediate_schema)?,
-            col("right", &intermediate_schema)?,
-        );
+        let filter_expr = complicated_4_column_exprs(&intermediate_schema)?;
+        // "la1" is referenced twice by the filter:
         let column_indices = vec![
             ColumnIndex {
-                index: 5,
+                index: 0,
                 side: JoinSide::Left,
             },
             ColumnIndex {
-                index: 5,
+                index: 0,
+                side: JoinSide::Right,
+            },
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 1,
                 side: JoinSide::Right,
             },
         ];
@@ -1618,37 +3606,7 @@ mod tests {
 
     #[rstest]
     #[tokio::test(flavor = "multi_thread")]
-    async fn join_without_filter(
-        #[values(
-            JoinType::Inner,
-            JoinType::Left,
-            JoinType::Right,
-            JoinType::RightSemi,
-            JoinType::LeftSemi,
-            JoinType::LeftAnti,
-            JoinType::RightAnti,
-            JoinType::Full
-        )]
-        join_type: JoinType,
-    ) -> Result<()> {
-        let task_ctx = Arc::new(TaskContext::default());
-        let (left_partition, right_partition) = get_or_create_table((11, 21), 8)?;
-        let left_schema = &left_partition[0].schema();
-        let right_schema = &right_partition[0].schema();
-        let (left, right) =
-            create_memory_table(left_partition, right_partition, vec![], vec![])?;
-
-        let on = vec![(
-            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
-            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
-        )];
-        experiment(left, right, None, join_type, on, task_ctx).await?;
-        Ok(())
-    }
-
-    #[rstest]
-    #[tokio::test(flavor = "multi_thread")]
-    async fn join_all_one_descending_numeric_particular(
+    async fn join_all_one_ascending_numeric(
         #[values(
             JoinType::Inner,
             JoinType::Left,
@@ -1663,22 +3621,18 @@ mod tests {
         #[values(0, 1, 2, 3, 4, 5)] case_expr: usize,
     ) -> Result<()> {
         let task_ctx = Arc::new(TaskContext::default());
-        let (left_partition, right_partition) = get_or_create_table((11, 21), 8)?;
+        let (left_partition, right_partition) = get_or_create_table((4, 5), 8)?;
+
         let left_schema = &left_partition[0].schema();
         let right_schema = &right_partition[0].schema();
+
         let left_sorted = vec![PhysicalSortExpr {
-            expr: col("la1_des", left_schema)?,
-            options: SortOptions {
-                descending: true,
-                nulls_first: true,
-            },
+            expr: col("la1", left_schema)?,
+            options: SortOptions::default(),
         }];
         let right_sorted = vec![PhysicalSortExpr {
-            expr: col("ra1_des", right_schema)?,
-            options: SortOptions {
-                descending: true,
-                nulls_first: true,
-            },
+            expr: col("ra1", right_schema)?,
+            options: SortOptions::default(),
         }];
         let (left, right) = create_memory_table(
             left_partition,
@@ -1703,11 +3657,11 @@ mod tests {
         );
         let column_indices = vec![
             ColumnIndex {
-                index: 5,
+                index: 0,
                 side: JoinSide::Left,
             },
             ColumnIndex {
-                index: 5,
+                index: 0,
                 side: JoinSide::Right,
             },
         ];
@@ -1717,36 +3671,33 @@ mod tests {
         Ok(())
     }
 
+    #[rstest]
     #[tokio::test(flavor = "multi_thread")]
-    async fn build_null_columns_first() -> Result<()> {
-        let join_type = JoinType::Full;
-        let case_expr = 1;
-        let session_config = SessionConfig::new().with_repartition_joins(false);
-        let task_ctx = TaskContext::default().with_session_config(session_config);
-        let task_ctx = Arc::new(task_ctx);
-        let (left_partition, right_partition) = get_or_create_table((10, 11), 8)?;
+    async fn join_with_non_prunable_filter(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::LeftSemi,
+            JoinType::Full
+        )]
+        join_type: JoinType,
+        #[values(0, 1)] case_expr: usize,
+    ) -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let (left_partition, right_partition) = get_or_create_table((4, 5), 8)?;
+
         let left_schema = &left_partition[0].schema();
         let right_schema = &right_partition[0].schema();
+
         let left_sorted = vec![PhysicalSortExpr {
-            expr: col("l_asc_null_first", left_schema)?,
-            options: SortOptions {
-                descending: false,
-                nulls_first: true,
-            },
+            expr: col("la1", left_schema)?,
+            options: SortOptions::default(),
         }];
         let right_sorted = vec![PhysicalSortExpr {
-            expr: col("r_asc_null_first", right_schema)?,
-            options: SortOptions {
-                descending: false,
-                nulls_first: true,
-            },
+            expr: col("ra1", right_schema)?,
+            options: SortOptions::default(),
         }];
-        let (left, right) = create_memory_table(
-            left_partition,
-            right_partition,
-            vec![left_sorted],
-            vec![right_sorted],
-        )?;
 
         let on = vec![(
             Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
@@ -1757,50 +3708,80 @@ mod tests {
             Field::new("left", DataType::Int32, true),
             Field::new("right", DataType::Int32, true),
         ]);
-        let filter_expr = join_expr_tests_fixture_i32(
+        let filter_expr = join_expr_tests_fixture_non_prunable(
             case_expr,
             col("left", &intermediate_schema)?,
             col("right", &intermediate_schema)?,
-        );
+            &intermediate_schema,
+        )?;
         let column_indices = vec![
             ColumnIndex {
-                index: 6,
+                index: 0,
                 side: JoinSide::Left,
             },
             ColumnIndex {
-                index: 6,
+                index: 0,
                 side: JoinSide::Right,
             },
         ];
         let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+
+        // The join can not run on unbounded inputs with bounded memory:
+        assert!(!is_streamable(
+            &join_type,
+            Some(&filter),
+            left_schema,
+            right_schema,
+            Some(&left_sorted),
+            Some(&right_sorted),
+        )?);
+
+        // It falls back to keeping all rows on bounded inputs, with the same
+        // results as a hash join:
+        let (left, right) = create_memory_table(
+            left_partition,
+            right_partition,
+            vec![left_sorted],
+            vec![right_sorted],
+        )?;
         experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
         Ok(())
     }
 
+    #[rstest]
     #[tokio::test(flavor = "multi_thread")]
-    async fn build_null_columns_last() -> Result<()> {
-        let join_type = JoinType::Full;
-        let case_expr = 1;
-        let session_config = SessionConfig::new().with_repartition_joins(false);
-        let task_ctx = TaskContext::default().with_session_config(session_config);
-        let task_ctx = Arc::new(task_ctx);
-        let (left_partition, right_partition) = get_or_create_table((10, 11), 8)?;
+    async fn join_all_one_ascending_64_bit_integers(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::RightSemi,
+            JoinType::LeftAnti,
+            JoinType::Full
+        )]
+        join_type: JoinType,
+        #[values(0, 1, 2, 3, 4, 5, 6, 7)] case_expr: usize,
+        #[values(DataType::Int64, DataType::UInt64)] data_type: DataType,
+    ) -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let (left_partition, right_partition) = get_or_create_table((4, 5), 8)?;
 
         let left_schema = &left_partition[0].schema();
         let right_schema = &right_partition[0].schema();
+        // The unsigned columns start at zero, so that e.g. `left_col - 10`
+        // wraps around for the first rows:
+        let (left_col, right_col, fixture): (_, _, fn(_, _, _) -> _) = match data_type {
+            DataType::Int64 => ("l_i64", "r_i64", join_expr_tests_fixture_i64),
+            _ => ("l_u64", "r_u64", join_expr_tests_fixture_u64),
+        };
+
         let left_sorted = vec![PhysicalSortExpr {
-            expr: col("l_asc_null_last", left_schema)?,
-            options: SortOptions {
-                descending: false,
-                nulls_first: false,
-            },
+            expr: col(left_col, left_schema)?,
+            options: SortOptions::default(),
         }];
         let right_sorted = vec![PhysicalSortExpr {
-            expr: col("r_asc_null_last", right_schema)?,
-            options: SortOptions {
-                descending: false,
-                nulls_first: false,
-            },
+            expr: col(right_col, right_schema)?,
+            options: SortOptions::default(),
         }];
         let (left, right) = create_memory_table(
             left_partition,
@@ -1815,21 +3796,21 @@ mod tests {
         )];
 
         let intermediate_schema = Schema::new(vec![
-            Field::new("left", DataType::Int32, true),
-            Field::new("right", DataType::Int32, true),
+            Field::new("left", data_type.clone(), true),
+            Field::new("right", data_type, true),
         ]);
-        let filter_expr = join_expr_tests_fixture_i32(
+        let filter_expr = fixture(
             case_expr,
             col("left", &intermediate_schema)?,
             col("right", &intermediate_schema)?,
         );
         let column_indices = vec![
             ColumnIndex {
-                index: 7,
+                index: left_schema.index_of(left_col)?,
                 side: JoinSide::Left,
             },
             ColumnIndex {
-                index: 7,
+                index: right_schema.index_of(right_col)?,
                 side: JoinSide::Right,
             },
         ];
@@ -1840,36 +3821,26 @@ mod tests {
     }
 
     #[tokio::test(flavor = "multi_thread")]
-    async fn build_null_columns_first_descending() -> Result<()> {
+    async fn join_with_prune_cadence() -> Result<()> {
         let join_type = JoinType::Full;
-        let cardinality = (10, 11);
-        let case_expr = 1;
-        let session_config = SessionConfig::new().with_repartition_joins(false);
-        let task_ctx = TaskContext::default().with_session_config(session_config);
-        let task_ctx = Arc::new(task_ctx);
-        let (left_partition, right_partition) = get_or_create_table(cardinality, 8)?;
+        let (left_partition, right_partition) = get_or_create_table((4, 5), 2)?;
 
         let left_schema = &left_partition[0].schema();
         let right_schema = &right_partition[0].schema();
+
         let left_sorted = vec![PhysicalSortExpr {
-            expr: col("l_desc_null_first", left_schema)?,
-            options: SortOptions {
-                descending: true,
-                nulls_first: true,
-            },
+            expr: col("la1", left_schema)?,
+            options: SortOptions::default(),
         }];
         let right_sorted = vec![PhysicalSortExpr {
-            expr: col("r_desc_null_first", right_schema)?,
-            options: SortOptions {
-                descending: true,
-                nulls_first: true,
-            },
+            expr: col("ra1", right_schema)?,
+            options: SortOptions::default(),
         }];
         let (left, right) = create_memory_table(
             left_partition,
             right_partition,
-            vec![left_sorted],
-            vec![right_sorted],
+            vec![left_sorted.clone()],
+            vec![right_sorted.clone()],
         )?;
 
         let on = vec![(
@@ -1882,44 +3853,88 @@ mod tests {
             Field::new("right", DataType::Int32, true),
         ]);
         let filter_expr = join_expr_tests_fixture_i32(
-            case_expr,
+            0,
             col("left", &intermediate_schema)?,
             col("right", &intermediate_schema)?,
         );
         let column_indices = vec![
             ColumnIndex {
-                index: 8,
+                index: 0,
                 side: JoinSide::Left,
             },
             ColumnIndex {
-                index: 8,
+                index: 0,
                 side: JoinSide::Right,
             },
         ];
         let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
 
-        experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
+        let mut results = vec![];
+        for prune_every in [1, 4] {
+            let join = SymmetricHashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                Some(filter.clone()),
+                &join_type,
+                false,
+                Some(left_sorted.clone()),
+                Some(right_sorted.clone()),
+                StreamJoinPartitionMode::SinglePartition,
+            )?
+            .with_prune_every(prune_every);
+            let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+            let batches = common::collect(stream).await?;
+            let prune_passes = join
+                .metrics()
+                .unwrap()
+                .sum_by_name("prune_passes")
+                .unwrap()
+                .as_usize();
+            results.push((batches, prune_passes));
+        }
+
+        let (eager_batches, eager_passes) = &results[0];
+        let (lazy_batches, lazy_passes) = &results[1];
+        assert!(*lazy_passes > 0);
+        assert!(lazy_passes < eager_passes);
+        let row_count =
+            |batches: &[RecordBatch]| batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        assert_eq!(row_count(eager_batches), row_count(lazy_batches));
+        compare_batches(eager_batches, lazy_batches);
         Ok(())
     }
 
-    #[tokio::test(flavor = "multi_thread")]
-    async fn complex_join_all_one_ascending_numeric_missing_stat() -> Result<()> {
-        let cardinality = (3, 4);
-        let join_type = JoinType::Full;
-
-        // a + b > c + 10 AND a + b < c + 100
-        let session_config = SessionConfig::new().with_repartition_joins(false);
-        let task_ctx = TaskContext::default().with_session_config(session_config);
-        let task_ctx = Arc::new(task_ctx);
-        let (left_partition, right_partition) = get_or_create_table(cardinality, 8)?;
+    #[test]
+    fn pruning_releases_buffer_memory() -> Result<()> {
+        let (left_batch, _) = build_sides_record_batches(1000, (4, 5))?;
+        let on = vec![col("lc1", &left_batch.schema())?];
+        let mut joiner = OneSideHashJoiner::new(JoinSide::Left, on, left_batch.schema());
+        joiner
+            .update_internal_state(&left_batch, &RandomState::with_seeds(0, 0, 0, 0))?;
+        let buffered_size = joiner.size();
+
+        joiner.prune_internal_state(left_batch.num_rows() * 3 / 4)?;
+        assert_eq!(joiner.input_buffer.num_rows(), left_batch.num_rows() / 4);
+        assert!(
+            joiner.input_buffer.get_array_memory_size()
+                < left_batch.get_array_memory_size() / 2
+        );
+        assert!(joiner.size() < buffered_size);
+        Ok(())
+    }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join_memory_reservation_follows_buffers() -> Result<()> {
+        let (left_batch, right_batch) = build_sides_record_batches(300, (4, 5))?;
+        let left_partition = split_record_batches(&left_batch, 8)?;
+        let right_partition = split_record_batches(&right_batch, 8)?;
         let left_schema = &left_partition[0].schema();
         let right_schema = &right_partition[0].schema();
         let left_sorted = vec![PhysicalSortExpr {
             expr: col("la1", left_schema)?,
             options: SortOptions::default(),
         }];
-
         let right_sorted = vec![PhysicalSortExpr {
             expr: col("ra1", right_schema)?,
             options: SortOptions::default(),
@@ -1927,111 +3942,2107 @@ mod tests {
         let (left, right) = create_memory_table(
             left_partition,
             right_partition,
-            vec![left_sorted],
-            vec![right_sorted],
+            vec![left_sorted.clone()],
+            vec![right_sorted.clone()],
         )?;
-
         let on = vec![(
             Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
             Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
         )];
-
         let intermediate_schema = Schema::new(vec![
-            Field::new("0", DataType::Int32, true),
-            Field::new("1", DataType::Int32, true),
-            Field::new("2", DataType::Int32, true),
+            Field::new("left", DataType::Int32, true),
+            Field::new("right", DataType::Int32, true),
         ]);
-        let filter_expr = complicated_filter(&intermediate_schema)?;
+        let filter_expr = join_expr_tests_fixture_i32(
+            0,
+            col("left", &intermediate_schema)?,
+            col("right", &intermediate_schema)?,
+        );
         let column_indices = vec![
             ColumnIndex {
                 index: 0,
                 side: JoinSide::Left,
             },
-            ColumnIndex {
-                index: 4,
-                side: JoinSide::Left,
-            },
             ColumnIndex {
                 index: 0,
                 side: JoinSide::Right,
             },
         ];
         let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+        let join = SymmetricHashJoinExec::try_new(
+            left,
+            right,
+            on,
+            Some(filter),
+            &JoinType::Inner,
+            false,
+            Some(left_sorted),
+            Some(right_sorted),
+            StreamJoinPartitionMode::SinglePartition,
+        )?;
+
+        let task_ctx = Arc::new(TaskContext::default());
+        let pool = task_ctx.memory_pool().clone();
+        let mut stream = join.execute(0, task_ctx)?;
+        let mut reserved = vec![];
+        while let Some(batch) = stream.next().await {
+            batch?;
+            reserved.push(pool.reserved());
+        }
+
+        // The reservation grows with the buffered rows and shrinks as they
+        // are pruned, and is released with the stream:
+        assert!(reserved.iter().all(|bytes| *bytes > 0));
+        assert!(reserved.windows(2).any(|pair| pair[1] < pair[0]));
+        drop(stream);
+        assert_eq!(pool.reserved(), 0);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join_with_pruning_dimensions() -> Result<()> {
+        let (left_batch, right_batch) = build_sides_record_batches(300, (4, 5))?;
+        let left_schema = &left_batch.schema();
+        let right_schema = &right_batch.schema();
+        let sort_expr = |name: &str, schema: &SchemaRef| -> Result<_> {
+            Ok(PhysicalSortExpr {
+                expr: col(name, schema)?,
+                options: SortOptions::default(),
+            })
+        };
+        // Both inputs are sorted on their numeric and on their time column:
+        let (left, right) = create_memory_table(
+            split_record_batches(&left_batch, 10)?,
+            split_record_batches(&right_batch, 10)?,
+            vec![
+                vec![sort_expr("la1", left_schema)?],
+                vec![sort_expr("lt1", left_schema)?],
+            ],
+            vec![
+                vec![sort_expr("ra1", right_schema)?],
+                vec![sort_expr("rt1", right_schema)?],
+            ],
+        )?;
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+        let intermediate_schema = Schema::new(vec![
+            Field::new("left", DataType::Int32, true),
+            Field::new("right", DataType::Int32, true),
+            Field::new(
+                "left_time",
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+            Field::new(
+                "right_time",
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+        ]);
+        // left + 1 > right + 5 AND left + 3 < right + 10, i.e. a window of a
+        // few rows, combined with a window of 250 rows on the time columns:
+        let numeric_filter = join_expr_tests_fixture_i32(
+            0,
+            col("left", &intermediate_schema)?,
+            col("right", &intermediate_schema)?,
+        );
+        let temporal_filter = join_expr_tests_fixture_temporal(
+            0,
+            col("left_time", &intermediate_schema)?,
+            col("right_time", &intermediate_schema)?,
+            &intermediate_schema,
+        )?;
+        let filter_expr = binary(
+            numeric_filter,
+            Operator::And,
+            temporal_filter,
+            &intermediate_schema,
+        )?;
+        let column_indices = vec![
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Right,
+            },
+            ColumnIndex {
+                index: 3,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 3,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+        let join = || {
+            SymmetricHashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                Some(filter.clone()),
+                &JoinType::Full,
+                false,
+                Some(vec![sort_expr("la1", left_schema)?]),
+                Some(vec![sort_expr("ra1", right_schema)?]),
+                StreamJoinPartitionMode::SinglePartition,
+            )
+        };
+
+        let err = join()?
+            .with_pruning_dimensions(vec![(
+                sort_expr("lb1", left_schema)?,
+                sort_expr("rb1", right_schema)?,
+            )])
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("is not sorted on the pruning dimension"));
+
+        let mut results = vec![];
+        for dimensions in [
+            vec![],
+            vec![(
+                sort_expr("lt1", left_schema)?,
+                sort_expr("rt1", right_schema)?,
+            )],
+        ] {
+            let join = join()?.with_pruning_dimensions(dimensions)?;
+            let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+            let batches = common::collect(stream).await?;
+            let pruned_rows = join
+                .metrics()
+                .unwrap()
+                .sum_by_name("pruned_rows")
+                .unwrap()
+                .as_usize();
+            results.push((batches, pruned_rows));
+        }
+
+        let (numeric_batches, numeric_pruned) = &results[0];
+        let (combined_batches, combined_pruned) = &results[1];
+        // Rows are only pruned once the time window closed as well, i.e. a
+        // lot later than once the numeric window closed, but still pruned:
+        assert!(*combined_pruned > 0);
+        assert!(combined_pruned < numeric_pruned);
+        compare_batches(numeric_batches, combined_batches);
+
+        let gauge = |metrics: &MetricsSet, name: &str, labels: &[(&str, &str)]| {
+            metrics
+                .iter()
+                .filter(|metric| {
+                    metric.value().name() == name
+                        && labels.iter().all(|(key, value)| {
+                            metric.labels().iter().any(|label| {
+                                label.name() == *key && label.value() == *value
+                            })
+                        })
+                })
+                .map(|metric| metric.value().as_usize())
+                .sum::<usize>()
+        };
+        let join = join()?.with_pruning_dimensions(vec![(
+            sort_expr("lt1", left_schema)?,
+            sort_expr("rt1", right_schema)?,
+        )])?;
+        let mut stream = join.execute(0, Arc::new(TaskContext::default()))?;
+        let mut max_buffered = HashMap::new();
+        while let Some(batch) = stream.next().await {
+            batch?;
+            let metrics = join.metrics().unwrap();
+            for side in ["left", "right"] {
+                let rows = gauge(&metrics, "buffered_rows", &[("side", side)]);
+                let bytes = gauge(&metrics, "buffered_bytes", &[("side", side)]);
+                assert!(bytes > 0);
+                let max_rows = max_buffered.entry(side).or_insert(0);
+                *max_rows = rows.max(*max_rows);
+            }
+        }
+        assert!(max_buffered.values().all(|rows| *rows > 0));
+        // The time window is a lot wider than the numeric one, so the time
+        // dimension is the one retaining the buffered rows of either side:
+        let metrics = join.metrics().unwrap();
+        for side in ["left", "right"] {
+            for name in ["dimension_retained_rows", "dimension_retained_bytes"] {
+                let numeric =
+                    gauge(&metrics, name, &[("side", side), ("dimension", "0")]);
+                let temporal =
+                    gauge(&metrics, name, &[("side", side), ("dimension", "1")]);
+                assert!(temporal > numeric, "{side} {name}: {temporal} <= {numeric}");
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join_with_sort_key_bounds() -> Result<()> {
+        let (left_batch, right_batch) = build_sides_record_batches(300, (4, 5))?;
+        // The right side only holds the rows with `ra1_des >= 150`, e.g. due
+        // to a pushed down filter:
+        let right_batch = right_batch.slice(0, 150);
+        let left_schema = &left_batch.schema();
+        let right_schema = &right_batch.schema();
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("la1", left_schema)?,
+            options: SortOptions::default(),
+        }];
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("ra1_des", right_schema)?,
+            options: SortOptions {
+                descending: true,
+                nulls_first: true,
+            },
+        }];
+        let (left, right) = create_memory_table(
+            split_record_batches(&left_batch, 10)?,
+            split_record_batches(&right_batch, 10)?,
+            vec![left_sorted.clone()],
+            vec![right_sorted.clone()],
+        )?;
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+        let intermediate_schema = Schema::new(vec![
+            Field::new("left", DataType::Int32, true),
+            Field::new("right", DataType::Int32, true),
+        ]);
+        // left > right - 3 AND left < right + 2
+        let filter_expr = gen_between_expr(
+            col("left", &intermediate_schema)?,
+            col("right", &intermediate_schema)?,
+            ScalarValue::Int32(Some(3)),
+            ScalarValue::Int32(Some(2)),
+            (Operator::Gt, Operator::Lt),
+        );
+        let column_indices = vec![
+            ColumnIndex {
+                index: left_schema.index_of("la1")?,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: right_schema.index_of("ra1_des")?,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+        let join = || {
+            SymmetricHashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                Some(filter.clone()),
+                &JoinType::Full,
+                false,
+                Some(left_sorted.clone()),
+                Some(right_sorted.clone()),
+                StreamJoinPartitionMode::SinglePartition,
+            )
+        };
+        let lower_bound =
+            Interval::try_new(ScalarValue::Int32(Some(150)), ScalarValue::Int32(None))?;
+
+        let err = join()?
+            .with_sort_key_bounds(None, Some(Interval::make_unbounded(&DataType::Int64)?))
+            .unwrap_err();
+        assert!(err.to_string().contains("have type Int64, expected Int32"));
+
+        let mut results = vec![];
+        for bounds in [None, Some(lower_bound)] {
+            let join = join()?.with_sort_key_bounds(None, bounds.clone())?;
+            assert_eq!(join.sort_key_bounds(), (None, bounds.as_ref()));
+            let join =
+                Arc::new(join).with_new_children(vec![left.clone(), right.clone()])?;
+            let display = displayable(join.as_ref()).one_line().to_string();
+            assert_eq!(
+                display.contains("sort_key_bounds=(None, [150, NULL])"),
+                bounds.is_some(),
+                "{display}"
+            );
+            let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+            let batches = common::collect(stream).await?;
+            let pruned_rows = join
+                .metrics()
+                .unwrap()
+                .sum_by_name("pruned_rows")
+                .unwrap()
+                .as_usize();
+            results.push((batches, pruned_rows));
+        }
+
+        let (unbounded_batches, unbounded_pruned) = &results[0];
+        let (bounded_batches, bounded_pruned) = &results[1];
+        // The descending right side alone never bounds the future right values
+        // from below, so the left side is only pruned given the lower bound:
+        assert_eq!(*unbounded_pruned, 0);
+        assert!(*bounded_pruned > 0);
+        compare_batches(unbounded_batches, bounded_batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn full_join_final_results_are_deterministic() -> Result<()> {
+        let left_schema = Arc::new(Schema::new(vec![
+            Field::new("la1", DataType::Int32, false),
+            Field::new("lc1", DataType::Int32, false),
+        ]));
+        let right_schema = Arc::new(Schema::new(vec![
+            Field::new("ra1", DataType::Int32, false),
+            Field::new("rc1", DataType::Int32, false),
+        ]));
+        let left_batch = RecordBatch::try_new(
+            left_schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 2, 3, 4, 5])),
+                Arc::new(Int32Array::from(vec![10, 30, 20, 40, 50, 60])),
+            ],
+        )?;
+        let right_batch = RecordBatch::try_new(
+            right_schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 1, 2, 3, 4, 5])),
+                Arc::new(Int32Array::from(vec![15, 40, 25, 60, 5, 35])),
+            ],
+        )?;
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("la1", &left_schema)?,
+            options: SortOptions::default(),
+        }];
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("ra1", &right_schema)?,
+            options: SortOptions::default(),
+        }];
+        let (left, right) = create_memory_table(
+            split_record_batches(&left_batch, 2)?,
+            split_record_batches(&right_batch, 2)?,
+            vec![left_sorted.clone()],
+            vec![right_sorted.clone()],
+        )?;
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", &left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", &right_schema)?) as _,
+        )];
+        let join = SymmetricHashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Full,
+            false,
+            Some(left_sorted),
+            Some(right_sorted),
+            StreamJoinPartitionMode::SinglePartition,
+        )?;
+        let join: Arc<dyn ExecutionPlan> = Arc::new(join);
+
+        let task_ctx = Arc::new(TaskContext::default());
+        let first = common::collect(join.execute(0, task_ctx.clone())?).await?;
+        let second = common::collect(join.execute(0, task_ctx)?).await?;
+        assert_eq!(first, second);
+
+        // The unmatched rows are emitted last, ordered by side and sort key:
+        let expected = [
+            "+-----+-----+-----+-----+",
+            "| la1 | lc1 | ra1 | rc1 |",
+            "+-----+-----+-----+-----+",
+            "| 1   | 10  |     |     |",
+            "| 2   | 30  |     |     |",
+            "| 2   | 20  |     |     |",
+            "| 4   | 50  |     |     |",
+            "|     |     | 1   | 15  |",
+            "|     |     | 2   | 25  |",
+            "|     |     | 4   | 5   |",
+            "|     |     | 5   | 35  |",
+            "+-----+-----+-----+-----+",
+        ];
+        assert_batches_eq!(expected, &first[first.len() - 1..]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_injected_watermark() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let left_schema = Arc::new(Schema::new(vec![
+            Field::new("la1", DataType::Int32, false),
+            Field::new("lc1", DataType::Int32, false),
+        ]));
+        let right_schema = Arc::new(Schema::new(vec![
+            Field::new("ra1", DataType::Int32, false),
+            Field::new("rc1", DataType::Int32, false),
+        ]));
+        let right_batch = RecordBatch::try_new(
+            right_schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![0, 1, 2, 3, 4])),
+                Arc::new(Int32Array::from(vec![0, 1, 2, 3, 4])),
+            ],
+        )?;
+        // The left input never produces any rows:
+        let left = Arc::new(BlockingExec::new(left_schema.clone(), 1));
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![right_batch]],
+            right_schema.clone(),
+            None,
+        )?);
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("la1", &left_schema)?,
+            options: SortOptions::default(),
+        }];
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("ra1", &right_schema)?,
+            options: SortOptions::default(),
+        }];
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", &left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", &right_schema)?) as _,
+        )];
+
+        // left > right - 2 AND left < right + 2
+        let intermediate_schema = Schema::new(vec![
+            Field::new("left", DataType::Int32, false),
+            Field::new("right", DataType::Int32, false),
+        ]);
+        let left_col = col("left", &intermediate_schema)?;
+        let right_col = col("right", &intermediate_schema)?;
+        let lower = binary(
+            left_col.clone(),
+            Operator::Gt,
+            binary(
+                right_col.clone(),
+                Operator::Minus,
+                lit(2),
+                &intermediate_schema,
+            )?,
+            &intermediate_schema,
+        )?;
+        let upper = binary(
+            left_col,
+            Operator::Lt,
+            binary(right_col, Operator::Plus, lit(2), &intermediate_schema)?,
+            &intermediate_schema,
+        )?;
+        let filter_expr = binary(lower, Operator::And, upper, &intermediate_schema)?;
+        let column_indices = vec![
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+
+        let trigger = WatermarkTrigger::new();
+        let join = SymmetricHashJoinExec::try_new(
+            left,
+            right,
+            on,
+            Some(filter),
+            &JoinType::Right,
+            false,
+            Some(left_sorted),
+            Some(right_sorted),
+            StreamJoinPartitionMode::SinglePartition,
+        )?
+        .with_skip_empty_output_batches(true)
+        .with_watermark_trigger(trigger.clone());
+        let mut stream = join.execute(0, task_ctx)?;
+
+        // The buffered right rows may still match future left rows:
+        assert!(futures::poll!(stream.next()).is_pending());
+
+        // No future left row matches any of them once the left side advances:
+        trigger.advance(JoinSide::Left, ScalarValue::Int32(Some(10)));
+        let batch = stream.next().await.unwrap()?;
+        assert_batches_sorted_eq!(
+            [
+                "+-----+-----+-----+-----+",
+                "| la1 | lc1 | ra1 | rc1 |",
+                "+-----+-----+-----+-----+",
+                "|     |     | 0   | 0   |",
+                "|     |     | 1   | 1   |",
+                "|     |     | 2   | 2   |",
+                "|     |     | 3   | 3   |",
+                "|     |     | 4   | 4   |",
+                "+-----+-----+-----+-----+",
+            ],
+            &[batch]
+        );
+        assert!(futures::poll!(stream.next()).is_pending());
+        let injected_watermarks = join
+            .metrics()
+            .unwrap()
+            .sum_by_name("injected_watermarks")
+            .unwrap()
+            .as_usize();
+        assert_eq!(injected_watermarks, 1);
+
+        // Watermarks of an unexpected type are rejected:
+        trigger.advance(JoinSide::Left, ScalarValue::Int64(Some(20)));
+        assert!(stream.next().await.unwrap().is_err());
+        Ok(())
+    }
+
+    /// A partition emitting a single empty batch, as a keepalive of a live
+    /// input, and nothing else afterwards
+    struct KeepaliveOnlyPartition(SchemaRef);
+
+    impl PartitionStream for KeepaliveOnlyPartition {
+        fn schema(&self) -> &SchemaRef {
+            &self.0
+        }
+
+        fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+            let keepalive = RecordBatch::new_empty(self.0.clone());
+            Box::pin(RecordBatchStreamAdapter::new(
+                self.0.clone(),
+                futures::stream::once(async { Ok(keepalive) })
+                    .chain(futures::stream::pending()),
+            ))
+        }
+    }
+
+    #[rstest]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join_with_empty_input_batches(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftAnti
+        )]
+        join_type: JoinType,
+    ) -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        // 40 rows split into batches of 8 end with an empty batch, and an
+        // empty batch precedes every other batch:
+        let (left_batch, right_batch) = build_sides_record_batches(40, (4, 5))?;
+        let with_empty_batches = |batch: &RecordBatch| -> Result<Vec<RecordBatch>> {
+            Ok(split_record_batches(batch, 8)?
+                .into_iter()
+                .flat_map(|batch| [batch.slice(0, 0), batch])
+                .collect())
+        };
+        let left_schema = &left_batch.schema();
+        let right_schema = &right_batch.schema();
+        let (left, right) = create_memory_table(
+            with_empty_batches(&left_batch)?,
+            with_empty_batches(&right_batch)?,
+            vec![vec![PhysicalSortExpr {
+                expr: col("la1", left_schema)?,
+                options: SortOptions::default(),
+            }]],
+            vec![vec![PhysicalSortExpr {
+                expr: col("ra1", right_schema)?,
+                options: SortOptions::default(),
+            }]],
+        )?;
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+        let intermediate_schema = Schema::new(vec![
+            Field::new("left", DataType::Int32, true),
+            Field::new("right", DataType::Int32, true),
+        ]);
+        let filter_expr = join_expr_tests_fixture_i32(
+            0,
+            col("left", &intermediate_schema)?,
+            col("right", &intermediate_schema)?,
+        );
+        let column_indices = vec![
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+        experiment(left, right, Some(filter), join_type, on, task_ctx.clone()).await?;
+
+        // A right input emitting only a keepalive does not keep the left input
+        // from being consumed:
+        let left = Arc::new(MemoryExec::try_new(
+            &[split_record_batches(&left_batch, 8)?],
+            left_schema.clone(),
+            None,
+        )?);
+        let right = Arc::new(StreamingTableExec::try_new(
+            right_schema.clone(),
+            vec![Arc::new(KeepaliveOnlyPartition(right_schema.clone()))],
+            None,
+            vec![],
+            true,
+        )?);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+        let join = SymmetricHashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &join_type,
+            false,
+            None,
+            None,
+            StreamJoinPartitionMode::SinglePartition,
+        )?;
+        let mut stream = join.execute(0, task_ctx)?;
+        let next =
+            tokio::time::timeout(std::time::Duration::from_millis(100), stream.next())
+                .await;
+        // The right input is live, so the join neither ends nor fails:
+        assert!(next.is_err());
+        let input_batches = join
+            .metrics()
+            .unwrap()
+            .sum_by_name("input_batches")
+            .unwrap()
+            .as_usize();
+        assert!(input_batches > 0);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join_with_unchanged_watermarks() -> Result<()> {
+        // Sorted columns are constant, so watermarks never advance:
+        let left_batch = RecordBatch::try_from_iter(vec![
+            (
+                "lc1",
+                Arc::new(Int32Array::from_iter_values((0..TABLE_SIZE).map(|i| i % 4)))
+                    as ArrayRef,
+            ),
+            (
+                "la1",
+                Arc::new(Int32Array::from_iter_values((0..TABLE_SIZE).map(|_| 10))),
+            ),
+        ])?;
+        let right_batch = RecordBatch::try_from_iter(vec![
+            (
+                "rc1",
+                Arc::new(Int32Array::from_iter_values((0..TABLE_SIZE).map(|i| i % 4)))
+                    as ArrayRef,
+            ),
+            (
+                "ra1",
+                Arc::new(Int32Array::from_iter_values((0..TABLE_SIZE).map(|_| 5))),
+            ),
+        ])?;
+        let left_schema = &left_batch.schema();
+        let right_schema = &right_batch.schema();
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("la1", left_schema)?,
+            options: SortOptions::default(),
+        }];
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("ra1", right_schema)?,
+            options: SortOptions::default(),
+        }];
+        let (left, right) = create_memory_table(
+            split_record_batches(&left_batch, 4)?,
+            split_record_batches(&right_batch, 4)?,
+            vec![left_sorted.clone()],
+            vec![right_sorted.clone()],
+        )?;
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+        let intermediate_schema = Schema::new(vec![
+            Field::new("left", DataType::Int32, true),
+            Field::new("right", DataType::Int32, true),
+        ]);
+        // left + 1 > right + 5 AND left + 3 < right + 10
+        let filter_expr = join_expr_tests_fixture_i32(
+            0,
+            col("left", &intermediate_schema)?,
+            col("right", &intermediate_schema)?,
+        );
+        let column_indices = vec![
+            ColumnIndex {
+                index: 1,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 1,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+
+        let join = SymmetricHashJoinExec::try_new(
+            left,
+            right,
+            on,
+            Some(filter),
+            &JoinType::Inner,
+            false,
+            Some(left_sorted),
+            Some(right_sorted),
+            StreamJoinPartitionMode::SinglePartition,
+        )?;
+        let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+        let batches = common::collect(stream).await?;
+
+        // Every row satisfies the filter, so all equal keys match:
+        let expected_rows = (0..4)
+            .map(|key| (0..TABLE_SIZE).filter(|i| i % 4 == key).count().pow(2))
+            .sum::<usize>();
+        let row_count = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        assert_eq!(row_count, expected_rows);
+
+        let metrics = join.metrics().unwrap();
+        let prune_passes = metrics.sum_by_name("prune_passes").unwrap().as_usize();
+        let bound_computations = metrics
+            .sum_by_name("prune_bound_computations")
+            .unwrap()
+            .as_usize();
+        // Bounds are only computed when each side sees its first watermarks:
+        assert!(bound_computations <= 4);
+        assert!(bound_computations < prune_passes);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join_forwards_watermarks() -> Result<()> {
+        // Sorted inputs whose keys never match, so no data rows are produced:
+        let left_batch = RecordBatch::try_from_iter(vec![
+            (
+                "la1",
+                Arc::new(Int32Array::from_iter_values(0..12)) as ArrayRef,
+            ),
+            ("lc1", Arc::new(Int32Array::from_iter_values(0..12))),
+        ])?;
+        let right_batch = RecordBatch::try_from_iter(vec![
+            (
+                "ra1",
+                Arc::new(Int32Array::from_iter_values(0..12)) as ArrayRef,
+            ),
+            ("rc1", Arc::new(Int32Array::from_iter_values(100..112))),
+        ])?;
+        let left_schema = &left_batch.schema();
+        let right_schema = &right_batch.schema();
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("la1", left_schema)?,
+            options: SortOptions::default(),
+        }];
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("ra1", right_schema)?,
+            options: SortOptions::default(),
+        }];
+        let (left, right) = create_memory_table(
+            split_record_batches(&left_batch, 4)?,
+            split_record_batches(&right_batch, 4)?,
+            vec![left_sorted.clone()],
+            vec![right_sorted.clone()],
+        )?;
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+        let join = SymmetricHashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            false,
+            Some(left_sorted),
+            Some(right_sorted),
+            StreamJoinPartitionMode::SinglePartition,
+        )?
+        .with_skip_empty_output_batches(true);
+
+        // Without forwarding, the downstream sees nothing:
+        let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+        assert!(common::collect(stream).await?.is_empty());
+
+        let join = Arc::new(join.with_forward_watermarks(true));
+        let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+        let batches = common::collect(stream).await?;
+        // One punctuation batch for each of the three batches of both inputs,
+        // carrying the sort value of the latest row of each side:
+        assert_eq!(batches.len(), 6);
+        assert!(batches.iter().all(is_punctuation));
+        let watermarks = batches
+            .iter()
+            .map(|batch| {
+                (
+                    punctuation_watermark(batch, JoinSide::Left),
+                    punctuation_watermark(batch, JoinSide::Right),
+                )
+            })
+            .collect::<Vec<_>>();
+        let watermark = |value: &str| Some(value.to_string());
+        assert_eq!(watermarks[0], (None, watermark("3")));
+        assert_eq!(watermarks[1], (watermark("3"), watermark("3")));
+        assert_eq!(watermarks[5], (watermark("11"), watermark("11")));
+        // Punctuation batches keep the fields of the output schema:
+        assert_eq!(batches[0].schema().fields(), join.schema().fields());
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join_after_one_side_exhausted(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::RightSemi,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightAnti,
+            JoinType::Full
+        )]
+        join_type: JoinType,
+        #[values(JoinSide::Left, JoinSide::Right)] exhausted_side: JoinSide,
+        #[values(true, false)] with_filter: bool,
+    ) -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let (mut left_partition, mut right_partition) = get_or_create_table((4, 5), 4)?;
+        // Let one side end long before the other one:
+        match exhausted_side {
+            JoinSide::Left => left_partition.truncate(2),
+            JoinSide::Right => right_partition.truncate(2),
+        }
+
+        let left_schema = &left_partition[0].schema();
+        let right_schema = &right_partition[0].schema();
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("la1", left_schema)?,
+            options: SortOptions::default(),
+        }];
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("ra1", right_schema)?,
+            options: SortOptions::default(),
+        }];
+        let (left, right) = create_memory_table(
+            left_partition,
+            right_partition,
+            vec![left_sorted],
+            vec![right_sorted],
+        )?;
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+
+        let filter = if with_filter {
+            let intermediate_schema = Schema::new(vec![
+                Field::new("left", DataType::Int32, true),
+                Field::new("right", DataType::Int32, true),
+            ]);
+            let filter_expr = join_expr_tests_fixture_i32(
+                0,
+                col("left", &intermediate_schema)?,
+                col("right", &intermediate_schema)?,
+            );
+            let column_indices = vec![
+                ColumnIndex {
+                    index: 0,
+                    side: JoinSide::Left,
+                },
+                ColumnIndex {
+                    index: 0,
+                    side: JoinSide::Right,
+                },
+            ];
+            Some(JoinFilter::new(
+                filter_expr,
+                column_indices,
+                intermediate_schema,
+            ))
+        } else {
+            None
+        };
+
+        experiment(left, right, filter, join_type, on, task_ctx).await?;
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join_without_sort_information(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::RightSemi,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightAnti,
+            JoinType::Full
+        )]
+        join_type: JoinType,
+        #[values(0, 1, 2, 3, 4, 5)] case_expr: usize,
+    ) -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let (left_partition, right_partition) = get_or_create_table((4, 5), 8)?;
+
+        let left_schema = &left_partition[0].schema();
+        let right_schema = &right_partition[0].schema();
+        let (left, right) =
+            create_memory_table(left_partition, right_partition, vec![], vec![])?;
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+
+        let intermediate_schema = Schema::new(vec![
+            Field::new("left", DataType::Int32, true),
+            Field::new("right", DataType::Int32, true),
+        ]);
+        let filter_expr = join_expr_tests_fixture_i32(
+            case_expr,
+            col("left", &intermediate_schema)?,
+            col("right", &intermediate_schema)?,
+        );
+        let column_indices = vec![
+            ColumnIndex {
+                index: 5,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 5,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+
+        experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join_without_filter(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::RightSemi,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightAnti,
+            JoinType::Full
+        )]
+        join_type: JoinType,
+    ) -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let (left_partition, right_partition) = get_or_create_table((11, 21), 8)?;
+        let left_schema = &left_partition[0].schema();
+        let right_schema = &right_partition[0].schema();
+        let (left, right) =
+            create_memory_table(left_partition, right_partition, vec![], vec![])?;
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+        experiment(left, right, None, join_type, on, task_ctx).await?;
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join_all_one_descending_numeric_particular(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::RightSemi,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightAnti,
+            JoinType::Full
+        )]
+        join_type: JoinType,
+        #[values(0, 1, 2, 3, 4, 5)] case_expr: usize,
+    ) -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let (left_partition, right_partition) = get_or_create_table((11, 21), 8)?;
+        let left_schema = &left_partition[0].schema();
+        let right_schema = &right_partition[0].schema();
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("la1_des", left_schema)?,
+            options: SortOptions {
+                descending: true,
+                nulls_first: true,
+            },
+        }];
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("ra1_des", right_schema)?,
+            options: SortOptions {
+                descending: true,
+                nulls_first: true,
+            },
+        }];
+        let (left, right) = create_memory_table(
+            left_partition,
+            right_partition,
+            vec![left_sorted],
+            vec![right_sorted],
+        )?;
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+
+        let intermediate_schema = Schema::new(vec![
+            Field::new("left", DataType::Int32, true),
+            Field::new("right", DataType::Int32, true),
+        ]);
+        let filter_expr = join_expr_tests_fixture_i32(
+            case_expr,
+            col("left", &intermediate_schema)?,
+            col("right", &intermediate_schema)?,
+        );
+        let column_indices = vec![
+            ColumnIndex {
+                index: 5,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 5,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+
+        experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn build_null_columns_first() -> Result<()> {
+        let join_type = JoinType::Full;
+        let case_expr = 1;
+        let session_config = SessionConfig::new().with_repartition_joins(false);
+        let task_ctx = TaskContext::default().with_session_config(session_config);
+        let task_ctx = Arc::new(task_ctx);
+        let (left_partition, right_partition) = get_or_create_table((10, 11), 8)?;
+        let left_schema = &left_partition[0].schema();
+        let right_schema = &right_partition[0].schema();
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("l_asc_null_first", left_schema)?,
+            options: SortOptions {
+                descending: false,
+                nulls_first: true,
+            },
+        }];
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("r_asc_null_first", right_schema)?,
+            options: SortOptions {
+                descending: false,
+                nulls_first: true,
+            },
+        }];
+        let (left, right) = create_memory_table(
+            left_partition,
+            right_partition,
+            vec![left_sorted],
+            vec![right_sorted],
+        )?;
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+
+        let intermediate_schema = Schema::new(vec![
+            Field::new("left", DataType::Int32, true),
+            Field::new("right", DataType::Int32, true),
+        ]);
+        let filter_expr = join_expr_tests_fixture_i32(
+            case_expr,
+            col("left", &intermediate_schema)?,
+            col("right", &intermediate_schema)?,
+        );
+        let column_indices = vec![
+            ColumnIndex {
+                index: 6,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 6,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+        experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn build_null_columns_last() -> Result<()> {
+        let join_type = JoinType::Full;
+        let case_expr = 1;
+        let session_config = SessionConfig::new().with_repartition_joins(false);
+        let task_ctx = TaskContext::default().with_session_config(session_config);
+        let task_ctx = Arc::new(task_ctx);
+        let (left_partition, right_partition) = get_or_create_table((10, 11), 8)?;
+
+        let left_schema = &left_partition[0].schema();
+        let right_schema = &right_partition[0].schema();
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("l_asc_null_last", left_schema)?,
+            options: SortOptions {
+                descending: false,
+                nulls_first: false,
+            },
+        }];
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("r_asc_null_last", right_schema)?,
+            options: SortOptions {
+                descending: false,
+                nulls_first: false,
+            },
+        }];
+        let (left, right) = create_memory_table(
+            left_partition,
+            right_partition,
+            vec![left_sorted],
+            vec![right_sorted],
+        )?;
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+
+        let intermediate_schema = Schema::new(vec![
+            Field::new("left", DataType::Int32, true),
+            Field::new("right", DataType::Int32, true),
+        ]);
+        let filter_expr = join_expr_tests_fixture_i32(
+            case_expr,
+            col("left", &intermediate_schema)?,
+            col("right", &intermediate_schema)?,
+        );
+        let column_indices = vec![
+            ColumnIndex {
+                index: 7,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 7,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+
+        experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn build_null_columns_first_descending() -> Result<()> {
+        let join_type = JoinType::Full;
+        let cardinality = (10, 11);
+        let case_expr = 1;
+        let session_config = SessionConfig::new().with_repartition_joins(false);
+        let task_ctx = TaskContext::default().with_session_config(session_config);
+        let task_ctx = Arc::new(task_ctx);
+        let (left_partition, right_partition) = get_or_create_table(cardinality, 8)?;
+
+        let left_schema = &left_partition[0].schema();
+        let right_schema = &right_partition[0].schema();
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("l_desc_null_first", left_schema)?,
+            options: SortOptions {
+                descending: true,
+                nulls_first: true,
+            },
+        }];
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("r_desc_null_first", right_schema)?,
+            options: SortOptions {
+                descending: true,
+                nulls_first: true,
+            },
+        }];
+        let (left, right) = create_memory_table(
+            left_partition,
+            right_partition,
+            vec![left_sorted],
+            vec![right_sorted],
+        )?;
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+
+        let intermediate_schema = Schema::new(vec![
+            Field::new("left", DataType::Int32, true),
+            Field::new("right", DataType::Int32, true),
+        ]);
+        let filter_expr = join_expr_tests_fixture_i32(
+            case_expr,
+            col("left", &intermediate_schema)?,
+            col("right", &intermediate_schema)?,
+        );
+        let column_indices = vec![
+            ColumnIndex {
+                index: 8,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 8,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+
+        experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn complex_join_all_one_ascending_numeric_missing_stat() -> Result<()> {
+        let cardinality = (3, 4);
+        let join_type = JoinType::Full;
+
+        // a + b > c + 10 AND a + b < c + 100
+        let session_config = SessionConfig::new().with_repartition_joins(false);
+        let task_ctx = TaskContext::default().with_session_config(session_config);
+        let task_ctx = Arc::new(task_ctx);
+        let (left_partition, right_partition) = get_or_create_table(cardinality, 8)?;
+
+        let left_schema = &left_partition[0].schema();
+        let right_schema = &right_partition[0].schema();
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("la1", left_schema)?,
+            options: SortOptions::default(),
+        }];
+
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("ra1", right_schema)?,
+            options: SortOptions::default(),
+        }];
+        let (left, right) = create_memory_table(
+            left_partition,
+            right_partition,
+            vec![left_sorted],
+            vec![right_sorted],
+        )?;
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+
+        let intermediate_schema = Schema::new(vec![
+            Field::new("0", DataType::Int32, true),
+            Field::new("1", DataType::Int32, true),
+            Field::new("2", DataType::Int32, true),
+        ]);
+        let filter_expr = complicated_filter(&intermediate_schema)?;
+        let column_indices = vec![
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 4,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+
+        experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn complex_join_all_one_ascending_equivalence() -> Result<()> {
+        let cardinality = (3, 4);
+        let join_type = JoinType::Full;
+
+        // a + b > c + 10 AND a + b < c + 100
+        let config = SessionConfig::new().with_repartition_joins(false);
+        // let session_ctx = SessionContext::with_config(config);
+        // let task_ctx = session_ctx.task_ctx();
+        let task_ctx = Arc::new(TaskContext::default().with_session_config(config));
+        let (left_partition, right_partition) = get_or_create_table(cardinality, 8)?;
+        let left_schema = &left_partition[0].schema();
+        let right_schema = &right_partition[0].schema();
+        let left_sorted = vec![
+            vec![PhysicalSortExpr {
+                expr: col("la1", left_schema)?,
+                options: SortOptions::default(),
+            }],
+            vec![PhysicalSortExpr {
+                expr: col("la2", left_schema)?,
+                options: SortOptions::default(),
+            }],
+        ];
+
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("ra1", right_schema)?,
+            options: SortOptions::default(),
+        }];
+
+        let (left, right) = create_memory_table(
+            left_partition,
+            right_partition,
+            left_sorted,
+            vec![right_sorted],
+        )?;
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+
+        let intermediate_schema = Schema::new(vec![
+            Field::new("0", DataType::Int32, true),
+            Field::new("1", DataType::Int32, true),
+            Field::new("2", DataType::Int32, true),
+        ]);
+        let filter_expr = complicated_filter(&intermediate_schema)?;
+        let column_indices = vec![
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 4,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+
+        experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
+        Ok(())
+    }
 
+    #[rstest]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn testing_with_temporal_columns(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::RightSemi,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightAnti,
+            JoinType::Full
+        )]
+        join_type: JoinType,
+        #[values(
+        (4, 5),
+        (12, 17),
+        )]
+        cardinality: (i32, i32),
+        #[values(0, 1, 2)] case_expr: usize,
+    ) -> Result<()> {
+        let session_config = SessionConfig::new().with_repartition_joins(false);
+        let task_ctx = TaskContext::default().with_session_config(session_config);
+        let task_ctx = Arc::new(task_ctx);
+        let (left_partition, right_partition) = get_or_create_table(cardinality, 8)?;
+
+        let left_schema = &left_partition[0].schema();
+        let right_schema = &right_partition[0].schema();
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("lt1", left_schema)?,
+            options: SortOptions {
+                descending: false,
+                nulls_first: true,
+            },
+        }];
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("rt1", right_schema)?,
+            options: SortOptions {
+                descending: false,
+                nulls_first: true,
+            },
+        }];
+        let (left, right) = create_memory_table(
+            left_partition,
+            right_partition,
+            vec![left_sorted],
+            vec![right_sorted],
+        )?;
+        let intermediate_schema = Schema::new(vec![
+            Field::new(
+                "left",
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+            Field::new(
+                "right",
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+        ]);
+        let filter_expr = join_expr_tests_fixture_temporal(
+            case_expr,
+            col("left", &intermediate_schema)?,
+            col("right", &intermediate_schema)?,
+            &intermediate_schema,
+        )?;
+        let column_indices = vec![
+            ColumnIndex {
+                index: 3,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 3,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+        experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join_with_max_matches_per_key() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let left_schema = Arc::new(Schema::new(vec![
+            Field::new("la", DataType::Int32, false),
+            Field::new("lb", DataType::Int32, false),
+        ]));
+        let right_schema = Arc::new(Schema::new(vec![
+            Field::new("ra", DataType::Int32, false),
+            Field::new("rb", DataType::Int32, false),
+        ]));
+        let left_batch = RecordBatch::try_new(
+            left_schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 1, 1, 1, 1, 2, 3])),
+                Arc::new(Int32Array::from(vec![0, 1, 2, 3, 4, 5, 6])),
+            ],
+        )?;
+        let right_batch = RecordBatch::try_new(
+            right_schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 1, 3, 3])),
+                Arc::new(Int32Array::from(vec![10, 11, 12, 13, 14])),
+            ],
+        )?;
+        let join = |max_matches_per_key| -> Result<SymmetricHashJoinExec> {
+            let left = Arc::new(MemoryExec::try_new(
+                &[split_record_batches(&left_batch, 3)?],
+                left_schema.clone(),
+                None,
+            )?);
+            let right = Arc::new(MemoryExec::try_new(
+                &[split_record_batches(&right_batch, 2)?],
+                right_schema.clone(),
+                None,
+            )?);
+            let on = vec![(
+                Arc::new(Column::new_with_schema("la", &left_schema)?) as _,
+                Arc::new(Column::new_with_schema("ra", &right_schema)?) as _,
+            )];
+            SymmetricHashJoinExec::try_new(
+                left,
+                right,
+                on,
+                None,
+                &JoinType::Inner,
+                false,
+                None,
+                None,
+                StreamJoinPartitionMode::SinglePartition,
+            )?
+            .with_max_matches_per_key(max_matches_per_key)
+        };
+
+        // The fan-out of every key is capped, keys below the cap are unaffected:
+        let capped = join(Some(3))?;
+        assert_eq!(capped.max_matches_per_key(), Some(3));
+        let batches = common::collect(capped.execute(0, task_ctx.clone())?).await?;
+        let mut matches_per_key = HashMap::new();
+        for batch in &batches {
+            let keys = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            for key in keys.values() {
+                *matches_per_key.entry(*key).or_insert(0) += 1;
+            }
+        }
+        assert_eq!(matches_per_key, HashMap::from([(1, 3), (2, 1), (3, 2)]));
+
+        // Without a cap, every key matches all rows of the opposite side:
+        let uncapped = join(None)?;
+        let batches = common::collect(uncapped.execute(0, task_ctx)?).await?;
+        let num_rows = batches.iter().map(|batch| batch.num_rows()).sum::<usize>();
+        assert_eq!(num_rows, 10 + 1 + 2);
+
+        assert!(join(Some(0)).is_err());
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn testing_with_date_columns(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::RightSemi,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightAnti,
+            JoinType::Full
+        )]
+        join_type: JoinType,
+        #[values(
+        (4, 5),
+        (12, 17),
+        )]
+        cardinality: (i32, i32),
+    ) -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let (left_partition, right_partition) = get_or_create_table(cardinality, 8)?;
+
+        let left_schema = &left_partition[0].schema();
+        let right_schema = &right_partition[0].schema();
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+        // The date columns start before the epoch, i.e. with negative values:
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("ld1", left_schema)?,
+            options: SortOptions::default(),
+        }];
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("rd1", right_schema)?,
+            options: SortOptions::default(),
+        }];
+        let (left, right) = create_memory_table(
+            left_partition,
+            right_partition,
+            vec![left_sorted],
+            vec![right_sorted],
+        )?;
+        let intermediate_schema = Schema::new(vec![
+            Field::new("left", DataType::Date32, false),
+            Field::new("right", DataType::Date32, false),
+        ]);
+        let filter_expr = join_expr_tests_fixture_temporal(
+            3,
+            col("left", &intermediate_schema)?,
+            col("right", &intermediate_schema)?,
+            &intermediate_schema,
+        )?;
+        let column_indices = vec![
+            ColumnIndex {
+                index: 11,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 11,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
         experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
         Ok(())
     }
 
+    #[rstest]
     #[tokio::test(flavor = "multi_thread")]
-    async fn complex_join_all_one_ascending_equivalence() -> Result<()> {
-        let cardinality = (3, 4);
-        let join_type = JoinType::Full;
+    async fn join_with_dedup_keys(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::RightSemi,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightAnti,
+            JoinType::Full
+        )]
+        join_type: JoinType,
+        #[values(0, 1, 2)] case_expr: usize,
+    ) -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let (left_batch, right_batch) = build_sides_record_batches(TABLE_SIZE, (4, 5))?;
+        // Deliver every row twice, with some duplicates straddling batch boundaries:
+        let duplicate = |batch: &RecordBatch| -> Result<RecordBatch> {
+            let indices = UInt32Array::from_iter_values(
+                (0..batch.num_rows() as u32).flat_map(|idx| [idx, idx]),
+            );
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|column| take(column, &indices, None))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(RecordBatch::try_new(batch.schema(), columns)?)
+        };
 
-        // a + b > c + 10 AND a + b < c + 100
-        let config = SessionConfig::new().with_repartition_joins(false);
-        // let session_ctx = SessionContext::with_config(config);
-        // let task_ctx = session_ctx.task_ctx();
-        let task_ctx = Arc::new(TaskContext::default().with_session_config(config));
+        let left_schema = &left_batch.schema();
+        let right_schema = &right_batch.schema();
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("lt1", left_schema)?,
+            options: SortOptions::default(),
+        }];
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("rt1", right_schema)?,
+            options: SortOptions::default(),
+        }];
+        let (left, right) = create_memory_table(
+            split_record_batches(&left_batch, 8)?,
+            split_record_batches(&right_batch, 8)?,
+            vec![left_sorted.clone()],
+            vec![right_sorted.clone()],
+        )?;
+        let (duplicated_left, duplicated_right) = create_memory_table(
+            split_record_batches(&duplicate(&left_batch)?, 7)?,
+            split_record_batches(&duplicate(&right_batch)?, 7)?,
+            vec![left_sorted.clone()],
+            vec![right_sorted.clone()],
+        )?;
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+        let intermediate_schema = Schema::new(vec![
+            Field::new(
+                "left",
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+            Field::new(
+                "right",
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+        ]);
+        let filter_expr = join_expr_tests_fixture_temporal(
+            case_expr,
+            col("left", &intermediate_schema)?,
+            col("right", &intermediate_schema)?,
+            &intermediate_schema,
+        )?;
+        let column_indices = vec![
+            ColumnIndex {
+                index: 3,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 3,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+
+        let dedup_keys = vec![
+            (col("lt1", left_schema)?, col("rt1", right_schema)?),
+            (col("la1", left_schema)?, col("ra1", right_schema)?),
+        ];
+        let join = SymmetricHashJoinExec::try_new(
+            duplicated_left,
+            duplicated_right,
+            on.clone(),
+            Some(filter.clone()),
+            &join_type,
+            false,
+            Some(left_sorted),
+            Some(right_sorted),
+            StreamJoinPartitionMode::SinglePartition,
+        )?
+        .with_dedup_keys(dedup_keys)?;
+        let batches = common::collect(join.execute(0, task_ctx.clone())?).await?;
+        let expected = partitioned_hash_join_with_filter(
+            left,
+            right,
+            on,
+            Some(filter),
+            &join_type,
+            false,
+            task_ctx,
+        )
+        .await?;
+
+        // Every row must be emitted exactly once:
+        let output_rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        let expected_rows = expected.iter().map(|b| b.num_rows()).sum::<usize>();
+        assert_eq!(output_rows, expected_rows);
+        compare_batches(&batches, &expected);
+
+        let duplicate_rows = join
+            .metrics()
+            .unwrap()
+            .sum_by_name("duplicate_rows")
+            .map(|v| v.as_usize());
+        assert_eq!(duplicate_rows, Some(2 * TABLE_SIZE as usize));
+        Ok(())
+    }
+
+    #[test]
+    fn dedup_keys_must_include_sort_expr() -> Result<()> {
+        let (left_batch, right_batch) = build_sides_record_batches(TABLE_SIZE, (4, 5))?;
+        let left_schema = &left_batch.schema();
+        let right_schema = &right_batch.schema();
+        let (left, right) = create_memory_table(
+            vec![left_batch.clone()],
+            vec![right_batch.clone()],
+            vec![],
+            vec![],
+        )?;
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+        let join = || {
+            SymmetricHashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                None,
+                &JoinType::Inner,
+                false,
+                Some(vec![PhysicalSortExpr {
+                    expr: col("lt1", left_schema)?,
+                    options: SortOptions::default(),
+                }]),
+                Some(vec![PhysicalSortExpr {
+                    expr: col("rt1", right_schema)?,
+                    options: SortOptions::default(),
+                }]),
+                StreamJoinPartitionMode::SinglePartition,
+            )
+        };
+
+        let err = join()?
+            .with_dedup_keys(vec![(col("la1", left_schema)?, col("ra1", right_schema)?)])
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("must include the sort expression of each side"));
+
+        let join = join()?.with_dedup_keys(vec![
+            (col("lt1", left_schema)?, col("rt1", right_schema)?),
+            (col("la1", left_schema)?, col("ra1", right_schema)?),
+        ])?;
+        assert_eq!(join.dedup_keys().len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join_with_input_ordering_assertion() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let (left_batch, right_batch) = build_sides_record_batches(TABLE_SIZE, (4, 5))?;
+        let left_schema = &left_batch.schema();
+        let right_schema = &right_batch.schema();
+        let sorted_on =
+            |name: &str, schema: &SchemaRef| -> Result<Vec<PhysicalSortExpr>> {
+                Ok(vec![PhysicalSortExpr {
+                    expr: col(name, schema)?,
+                    options: SortOptions::default(),
+                }])
+            };
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+        let run = |left_batches: Vec<RecordBatch>,
+                   right_batches: Vec<RecordBatch>,
+                   right_sort_column: &str,
+                   assert: bool| {
+            let join = create_memory_table(
+                left_batches,
+                right_batches,
+                vec![sorted_on("la1", left_schema)?],
+                vec![sorted_on(right_sort_column, right_schema)?],
+            )
+            .and_then(|(left, right)| {
+                SymmetricHashJoinExec::try_new(
+                    left,
+                    right,
+                    on.clone(),
+                    None,
+                    &JoinType::Inner,
+                    false,
+                    Some(sorted_on("la1", left_schema)?),
+                    Some(sorted_on(right_sort_column, right_schema)?),
+                    StreamJoinPartitionMode::SinglePartition,
+                )?
+                .with_input_ordering_assertion(assert)
+            });
+            let task_ctx = task_ctx.clone();
+            Result::<_>::Ok(
+                async move { common::collect(join?.execute(0, task_ctx)?).await },
+            )
+        };
+        let left_batches = split_record_batches(&left_batch, 8)?;
+        let right_batches = split_record_batches(&right_batch, 8)?;
+        let reversed_left_batches =
+            left_batches.iter().rev().cloned().collect::<Vec<_>>();
+
+        // Sorted inputs pass the assertion:
+        let expected =
+            run(left_batches.clone(), right_batches.clone(), "ra1", false)?.await?;
+        let batches =
+            run(left_batches.clone(), right_batches.clone(), "ra1", true)?.await?;
+        compare_batches(&batches, &expected);
+
+        // Batches arriving out of order are only detected with the assertion:
+        run(
+            reversed_left_batches.clone(),
+            right_batches.clone(),
+            "ra1",
+            false,
+        )?
+        .await?;
+        let err = run(reversed_left_batches, right_batches.clone(), "ra1", true)?
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("The left input of SymmetricHashJoinExec is not sorted on [la1@0 ASC]: row 0 of batch 1"));
+
+        // As are rows out of order within a batch:
+        let err = run(left_batches, right_batches, "ra1_des", true)?
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("The right input of SymmetricHashJoinExec is not sorted on [ra1_des@5 ASC]: row 1 of batch 0"));
+
+        // The assertion requires a sorted input:
+        let (left, right) = create_memory_table(
+            vec![left_batch.clone()],
+            vec![right_batch.clone()],
+            vec![],
+            vec![],
+        )?;
+        let err = SymmetricHashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            false,
+            None,
+            None,
+            StreamJoinPartitionMode::SinglePartition,
+        )?
+        .with_input_ordering_assertion(true)
+        .unwrap_err();
+        assert!(err.to_string().contains("requires a sorted input"));
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_with_interval_columns(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::RightSemi,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightAnti,
+            JoinType::Full
+        )]
+        join_type: JoinType,
+        #[values(
+        (4, 5),
+        (12, 17),
+        )]
+        cardinality: (i32, i32),
+    ) -> Result<()> {
+        let session_config = SessionConfig::new().with_repartition_joins(false);
+        let task_ctx = TaskContext::default().with_session_config(session_config);
+        let task_ctx = Arc::new(task_ctx);
         let (left_partition, right_partition) = get_or_create_table(cardinality, 8)?;
+
         let left_schema = &left_partition[0].schema();
         let right_schema = &right_partition[0].schema();
-        let left_sorted = vec![
-            vec![PhysicalSortExpr {
-                expr: col("la1", left_schema)?,
-                options: SortOptions::default(),
-            }],
-            vec![PhysicalSortExpr {
-                expr: col("la2", left_schema)?,
-                options: SortOptions::default(),
-            }],
-        ];
-
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("li1", left_schema)?,
+            options: SortOptions {
+                descending: false,
+                nulls_first: true,
+            },
+        }];
         let right_sorted = vec![PhysicalSortExpr {
-            expr: col("ra1", right_schema)?,
-            options: SortOptions::default(),
+            expr: col("ri1", right_schema)?,
+            options: SortOptions {
+                descending: false,
+                nulls_first: true,
+            },
         }];
-
         let (left, right) = create_memory_table(
             left_partition,
             right_partition,
-            left_sorted,
+            vec![left_sorted],
             vec![right_sorted],
         )?;
+        let intermediate_schema = Schema::new(vec![
+            Field::new("left", DataType::Interval(IntervalUnit::DayTime), false),
+            Field::new("right", DataType::Interval(IntervalUnit::DayTime), false),
+        ]);
+        let filter_expr = join_expr_tests_fixture_temporal(
+            0,
+            col("left", &intermediate_schema)?,
+            col("right", &intermediate_schema)?,
+            &intermediate_schema,
+        )?;
+        let column_indices = vec![
+            ColumnIndex {
+                index: 9,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 9,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+        experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
+
+        Ok(())
+    }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_with_unnormalized_interval_columns() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let schema = |side| {
+            Arc::new(Schema::new(vec![
+                Field::new(format!("{side}k"), DataType::Int32, false),
+                Field::new(
+                    format!("{side}i"),
+                    DataType::Interval(IntervalUnit::DayTime),
+                    false,
+                ),
+            ]))
+        };
+        let batch = |schema: &SchemaRef, days, millis| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from(vec![1])),
+                    Arc::new(IntervalDayTimeArray::from(vec![
+                        IntervalDayTimeType::make_value(days, millis),
+                    ])),
+                ],
+            )
+        };
+        let (left_schema, right_schema) = (schema("l"), schema("r"));
+        let sorted = |schema: &SchemaRef, name| -> Result<_> {
+            Ok(vec![vec![PhysicalSortExpr {
+                expr: col(name, schema)?,
+                options: SortOptions::default(),
+            }]])
+        };
+        // The right side values are sorted, but subtracting 1 ms from the first
+        // one gives `(1 day, -1 ms)`, which compares greater than the last one
+        // minus 1 ms. Bounds derived with interval arithmetic would prune the
+        // left row once the first value is repeated, although the last one
+        // matches it:
+        let (left, right) = create_memory_table(
+            vec![batch(&left_schema, 1, 5)?],
+            vec![
+                batch(&right_schema, 1, 0)?,
+                batch(&right_schema, 1, 0)?,
+                batch(&right_schema, 1, 3)?,
+            ],
+            sorted(&left_schema, "li")?,
+            sorted(&right_schema, "ri")?,
+        )?;
         let on = vec![(
-            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
-            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+            Arc::new(Column::new_with_schema("lk", &left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rk", &right_schema)?) as _,
         )];
-
         let intermediate_schema = Schema::new(vec![
-            Field::new("0", DataType::Int32, true),
-            Field::new("1", DataType::Int32, true),
-            Field::new("2", DataType::Int32, true),
+            Field::new("left", DataType::Interval(IntervalUnit::DayTime), false),
+            Field::new("right", DataType::Interval(IntervalUnit::DayTime), false),
         ]);
-        let filter_expr = complicated_filter(&intermediate_schema)?;
+        let filter_expr = binary(
+            col("left", &intermediate_schema)?,
+            Operator::Gt,
+            binary(
+                col("right", &intermediate_schema)?,
+                Operator::Minus,
+                lit(ScalarValue::new_interval_dt(0, 1)),
+                &intermediate_schema,
+            )?,
+            &intermediate_schema,
+        )?;
         let column_indices = vec![
             ColumnIndex {
-                index: 0,
-                side: JoinSide::Left,
-            },
-            ColumnIndex {
-                index: 4,
+                index: 1,
                 side: JoinSide::Left,
             },
             ColumnIndex {
-                index: 0,
+                index: 1,
                 side: JoinSide::Right,
             },
         ];
         let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
 
-        experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
+        let left_sort_exprs = left.output_ordering().map(|p| p.to_vec());
+        let right_sort_exprs = right.output_ordering().map(|p| p.to_vec());
+        let join = SymmetricHashJoinExec::try_new(
+            left,
+            right,
+            on,
+            Some(filter),
+            &JoinType::Inner,
+            false,
+            left_sort_exprs,
+            right_sort_exprs,
+            StreamJoinPartitionMode::SinglePartition,
+        )?;
+        let batches = common::collect(join.execute(0, task_ctx)?).await?;
+        let expected = [
+            "+----+-------------------------------------------------+----+-------------------------------------------------+",
+            "| lk | li                                              | rk | ri                                              |",
+            "+----+-------------------------------------------------+----+-------------------------------------------------+",
+            "| 1  | 0 years 0 mons 1 days 0 hours 0 mins 0.005 secs | 1  | 0 years 0 mons 1 days 0 hours 0 mins 0.003 secs |",
+            "+----+-------------------------------------------------+----+-------------------------------------------------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
         Ok(())
     }
 
     #[rstest]
     #[tokio::test(flavor = "multi_thread")]
-    async fn testing_with_temporal_columns(
+    async fn testing_ascending_float_pruning(
         #[values(
             JoinType::Inner,
             JoinType::Left,
@@ -2048,7 +6059,7 @@ mod tests {
         (12, 17),
         )]
         cardinality: (i32, i32),
-        #[values(0, 1, 2)] case_expr: usize,
+        #[values(0, 1, 2, 3, 4, 5)] case_expr: usize,
     ) -> Result<()> {
         let session_config = SessionConfig::new().with_repartition_joins(false);
         let task_ctx = TaskContext::default().with_session_config(session_config);
@@ -2057,23 +6068,13 @@ mod tests {
 
         let left_schema = &left_partition[0].schema();
         let right_schema = &right_partition[0].schema();
-        let on = vec![(
-            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
-            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
-        )];
         let left_sorted = vec![PhysicalSortExpr {
-            expr: col("lt1", left_schema)?,
-            options: SortOptions {
-                descending: false,
-                nulls_first: true,
-            },
+            expr: col("l_float", left_schema)?,
+            options: SortOptions::default(),
         }];
         let right_sorted = vec![PhysicalSortExpr {
-            expr: col("rt1", right_schema)?,
-            options: SortOptions {
-                descending: false,
-                nulls_first: true,
-            },
+            expr: col("r_float", right_schema)?,
+            options: SortOptions::default(),
         }];
         let (left, right) = create_memory_table(
             left_partition,
@@ -2081,42 +6082,40 @@ mod tests {
             vec![left_sorted],
             vec![right_sorted],
         )?;
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+
         let intermediate_schema = Schema::new(vec![
-            Field::new(
-                "left",
-                DataType::Timestamp(TimeUnit::Millisecond, None),
-                false,
-            ),
-            Field::new(
-                "right",
-                DataType::Timestamp(TimeUnit::Millisecond, None),
-                false,
-            ),
+            Field::new("left", DataType::Float64, true),
+            Field::new("right", DataType::Float64, true),
         ]);
-        let filter_expr = join_expr_tests_fixture_temporal(
+        let filter_expr = join_expr_tests_fixture_f64(
             case_expr,
             col("left", &intermediate_schema)?,
             col("right", &intermediate_schema)?,
-            &intermediate_schema,
-        )?;
+        );
         let column_indices = vec![
             ColumnIndex {
-                index: 3,
+                index: 10, // l_float
                 side: JoinSide::Left,
             },
             ColumnIndex {
-                index: 3,
+                index: 10, // r_float
                 side: JoinSide::Right,
             },
         ];
         let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+
         experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
         Ok(())
     }
 
     #[rstest]
     #[tokio::test(flavor = "multi_thread")]
-    async fn test_with_interval_columns(
+    async fn join_with_float_tolerance(
         #[values(
             JoinType::Inner,
             JoinType::Left,
@@ -2128,72 +6127,142 @@ mod tests {
             JoinType::Full
         )]
         join_type: JoinType,
-        #[values(
-        (4, 5),
-        (12, 17),
-        )]
-        cardinality: (i32, i32),
     ) -> Result<()> {
-        let session_config = SessionConfig::new().with_repartition_joins(false);
-        let task_ctx = TaskContext::default().with_session_config(session_config);
-        let task_ctx = Arc::new(task_ctx);
-        let (left_partition, right_partition) = get_or_create_table(cardinality, 8)?;
-
-        let left_schema = &left_partition[0].schema();
-        let right_schema = &right_partition[0].schema();
-        let on = vec![(
-            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
-            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
-        )];
+        let task_ctx = Arc::new(TaskContext::default());
+        // Right values differ from the left ones by a jitter around the tolerance:
+        let tolerance = 1e-9;
+        let jitter = [-5e-10, 5e-10, -3e-9];
+        let left_batch = RecordBatch::try_from_iter(vec![
+            (
+                "lc1",
+                Arc::new(Int32Array::from_iter_values((0..TABLE_SIZE).map(|i| i % 4)))
+                    as ArrayRef,
+            ),
+            (
+                "l_float",
+                Arc::new(Float64Array::from_iter_values(
+                    (0..TABLE_SIZE).map(|i| i as f64 * 0.25),
+                )),
+            ),
+        ])?;
+        let right_batch = RecordBatch::try_from_iter(vec![
+            (
+                "rc1",
+                Arc::new(Int32Array::from_iter_values((0..TABLE_SIZE).map(|i| i % 4)))
+                    as ArrayRef,
+            ),
+            (
+                "r_float",
+                Arc::new(Float64Array::from_iter_values(
+                    (0..TABLE_SIZE).map(|i| i as f64 * 0.25 + jitter[i as usize % 3]),
+                )),
+            ),
+        ])?;
+        let left_schema = &left_batch.schema();
+        let right_schema = &right_batch.schema();
         let left_sorted = vec![PhysicalSortExpr {
-            expr: col("li1", left_schema)?,
-            options: SortOptions {
-                descending: false,
-                nulls_first: true,
-            },
+            expr: col("l_float", left_schema)?,
+            options: SortOptions::default(),
         }];
         let right_sorted = vec![PhysicalSortExpr {
-            expr: col("ri1", right_schema)?,
-            options: SortOptions {
-                descending: false,
-                nulls_first: true,
-            },
+            expr: col("r_float", right_schema)?,
+            options: SortOptions::default(),
         }];
         let (left, right) = create_memory_table(
-            left_partition,
-            right_partition,
-            vec![left_sorted],
-            vec![right_sorted],
+            split_record_batches(&left_batch, 4)?,
+            split_record_batches(&right_batch, 4)?,
+            vec![left_sorted.clone()],
+            vec![right_sorted.clone()],
         )?;
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
         let intermediate_schema = Schema::new(vec![
-            Field::new("left", DataType::Interval(IntervalUnit::DayTime), false),
-            Field::new("right", DataType::Interval(IntervalUnit::DayTime), false),
+            Field::new("left", DataType::Float64, false),
+            Field::new("right", DataType::Float64, false),
         ]);
-        let filter_expr = join_expr_tests_fixture_temporal(
-            0,
-            col("left", &intermediate_schema)?,
-            col("right", &intermediate_schema)?,
+        // left <= right AND left > right - 2
+        let filter_expr = binary(
+            binary(
+                col("left", &intermediate_schema)?,
+                Operator::LtEq,
+                col("right", &intermediate_schema)?,
+                &intermediate_schema,
+            )?,
+            Operator::And,
+            binary(
+                col("left", &intermediate_schema)?,
+                Operator::Gt,
+                binary(
+                    col("right", &intermediate_schema)?,
+                    Operator::Minus,
+                    lit(2.0),
+                    &intermediate_schema,
+                )?,
+                &intermediate_schema,
+            )?,
             &intermediate_schema,
         )?;
         let column_indices = vec![
             ColumnIndex {
-                index: 9,
+                index: 1,
                 side: JoinSide::Left,
             },
             ColumnIndex {
-                index: 9,
+                index: 1,
                 side: JoinSide::Right,
             },
         ];
         let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
-        experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
 
+        let join = |tolerance: Option<f64>| -> Result<SymmetricHashJoinExec> {
+            let join = SymmetricHashJoinExec::try_new(
+                left.clone(),
+                right.clone(),
+                on.clone(),
+                Some(filter.clone()),
+                &join_type,
+                false,
+                Some(left_sorted.clone()),
+                Some(right_sorted.clone()),
+                StreamJoinPartitionMode::SinglePartition,
+            )?;
+            match tolerance {
+                Some(tolerance) => join.with_float_tolerance(tolerance),
+                None => Ok(join),
+            }
+        };
+        let strict = common::collect(join(None)?.execute(0, task_ctx.clone())?).await?;
+        let tolerant =
+            common::collect(join(Some(tolerance))?.execute(0, task_ctx.clone())?).await?;
+        // Pruning must keep every row the relaxed filter matches:
+        let expected = partitioned_hash_join_with_filter(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            Some(filter.with_float_tolerance(tolerance)?),
+            &join_type,
+            false,
+            task_ctx,
+        )
+        .await?;
+        let row_count = |batches: &[RecordBatch]| -> usize {
+            batches.iter().map(|b| b.num_rows()).sum()
+        };
+        assert_eq!(row_count(&tolerant), row_count(&expected));
+        compare_batches(&tolerant, &expected);
+        if join_type == JoinType::Inner {
+            // Boundary matches within the tolerance are only kept when relaxed:
+            assert!(row_count(&tolerant) > row_count(&strict));
+        }
         Ok(())
     }
 
     #[rstest]
     #[tokio::test(flavor = "multi_thread")]
-    async fn testing_ascending_float_pruning(
+    async fn join_with_between_filter(
         #[values(
             JoinType::Inner,
             JoinType::Left,
@@ -2205,26 +6274,20 @@ mod tests {
             JoinType::Full
         )]
         join_type: JoinType,
-        #[values(
-        (4, 5),
-        (12, 17),
-        )]
-        cardinality: (i32, i32),
-        #[values(0, 1, 2, 3, 4, 5)] case_expr: usize,
+        #[values(Operator::Gt, Operator::GtEq)] greater_op: Operator,
+        #[values(Operator::Lt, Operator::LtEq)] less_op: Operator,
     ) -> Result<()> {
-        let session_config = SessionConfig::new().with_repartition_joins(false);
-        let task_ctx = TaskContext::default().with_session_config(session_config);
-        let task_ctx = Arc::new(task_ctx);
-        let (left_partition, right_partition) = get_or_create_table(cardinality, 8)?;
+        let task_ctx = Arc::new(TaskContext::default());
+        let (left_partition, right_partition) = get_or_create_table((4, 5), 4)?;
 
         let left_schema = &left_partition[0].schema();
         let right_schema = &right_partition[0].schema();
         let left_sorted = vec![PhysicalSortExpr {
-            expr: col("l_float", left_schema)?,
+            expr: col("la1", left_schema)?,
             options: SortOptions::default(),
         }];
         let right_sorted = vec![PhysicalSortExpr {
-            expr: col("r_float", right_schema)?,
+            expr: col("ra1", right_schema)?,
             options: SortOptions::default(),
         }];
         let (left, right) = create_memory_table(
@@ -2240,27 +6303,182 @@ mod tests {
         )];
 
         let intermediate_schema = Schema::new(vec![
-            Field::new("left", DataType::Float64, true),
-            Field::new("right", DataType::Float64, true),
+            Field::new("left", DataType::Int32, true),
+            Field::new("right", DataType::Int32, true),
         ]);
-        let filter_expr = join_expr_tests_fixture_f64(
-            case_expr,
-            col("left", &intermediate_schema)?,
+        // right >/>= left - 3 AND right </<= left + 2
+        let filter_expr = gen_between_expr(
             col("right", &intermediate_schema)?,
+            col("left", &intermediate_schema)?,
+            ScalarValue::Int32(Some(3)),
+            ScalarValue::Int32(Some(2)),
+            (greater_op, less_op),
         );
         let column_indices = vec![
             ColumnIndex {
-                index: 10, // l_float
+                index: 0,
                 side: JoinSide::Left,
             },
             ColumnIndex {
-                index: 10, // r_float
+                index: 0,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+
+        experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
+        Ok(())
+    }
+
+    /// Builds memory tables where the left side is ordered ascending on `la1`
+    /// and the right side is ordered descending on `ra1_des`, along with a
+    /// filter over these two columns.
+    fn mixed_order_direction_inputs(
+        filter_fn: impl Fn(
+            Arc<dyn PhysicalExpr>,
+            Arc<dyn PhysicalExpr>,
+            &Schema,
+        ) -> Result<Arc<dyn PhysicalExpr>>,
+    ) -> Result<(
+        Arc<dyn ExecutionPlan>,
+        Arc<dyn ExecutionPlan>,
+        JoinOn,
+        JoinFilter,
+    )> {
+        let (left_partition, right_partition) = get_or_create_table((4, 5), 4)?;
+        let left_schema = &left_partition[0].schema();
+        let right_schema = &right_partition[0].schema();
+        let left_sorted = vec![PhysicalSortExpr {
+            expr: col("la1", left_schema)?,
+            options: SortOptions::default(),
+        }];
+        let right_sorted = vec![PhysicalSortExpr {
+            expr: col("ra1_des", right_schema)?,
+            options: SortOptions {
+                descending: true,
+                nulls_first: true,
+            },
+        }];
+        let (left, right) = create_memory_table(
+            left_partition,
+            right_partition,
+            vec![left_sorted],
+            vec![right_sorted],
+        )?;
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", left_schema)?) as _,
+            Arc::new(Column::new_with_schema("rc1", right_schema)?) as _,
+        )];
+
+        let intermediate_schema = Schema::new(vec![
+            Field::new("left", DataType::Int32, true),
+            Field::new("right", DataType::Int32, true),
+        ]);
+        let filter_expr = filter_fn(
+            col("left", &intermediate_schema)?,
+            col("right", &intermediate_schema)?,
+            &intermediate_schema,
+        )?;
+        let column_indices = vec![
+            ColumnIndex {
+                index: left_schema.index_of("la1")?,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: right_schema.index_of("ra1_des")?,
                 side: JoinSide::Right,
             },
         ];
         let filter = JoinFilter::new(filter_expr, column_indices, intermediate_schema);
+        Ok((left, right, on, filter))
+    }
+
+    // left + right > 25 AND left + right < 35
+    fn sum_band_filter(
+        left: Arc<dyn PhysicalExpr>,
+        right: Arc<dyn PhysicalExpr>,
+        schema: &Schema,
+    ) -> Result<Arc<dyn PhysicalExpr>> {
+        let sum = binary(left, Operator::Plus, right, schema)?;
+        binary(
+            binary(sum.clone(), Operator::Gt, lit(25), schema)?,
+            Operator::And,
+            binary(sum, Operator::Lt, lit(35), schema)?,
+            schema,
+        )
+    }
+
+    // left > right - 3 AND left < right + 2
+    fn difference_band_filter(
+        left: Arc<dyn PhysicalExpr>,
+        right: Arc<dyn PhysicalExpr>,
+        _schema: &Schema,
+    ) -> Result<Arc<dyn PhysicalExpr>> {
+        Ok(gen_between_expr(
+            left,
+            right,
+            ScalarValue::Int32(Some(3)),
+            ScalarValue::Int32(Some(2)),
+            (Operator::Gt, Operator::Lt),
+        ))
+    }
 
+    #[rstest]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join_with_mixed_order_directions(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::RightSemi,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightAnti,
+            JoinType::Full
+        )]
+        join_type: JoinType,
+    ) -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let (left, right, on, filter) = mixed_order_direction_inputs(sum_band_filter)?;
         experiment(left, right, Some(filter), join_type, on, task_ctx).await?;
         Ok(())
     }
+
+    #[test]
+    fn order_directions_allow_pruning() -> Result<()> {
+        // An ascending left side and a descending right side can both be
+        // pruned when the filter bounds their sum:
+        let (left, right, on, filter) = mixed_order_direction_inputs(sum_band_filter)?;
+        let join = SymmetricHashJoinExec::try_new(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            Some(filter),
+            &JoinType::Inner,
+            false,
+            left.output_ordering().map(|p| p.to_vec()),
+            right.output_ordering().map(|p| p.to_vec()),
+            StreamJoinPartitionMode::SinglePartition,
+        )?;
+        assert!(join.check_if_order_directions_allow_pruning()?);
+
+        // Bounding their difference instead means that future rows of either
+        // side may match any buffered row, so neither side can be pruned:
+        let (left, right, on, filter) =
+            mixed_order_direction_inputs(difference_band_filter)?;
+        let join = SymmetricHashJoinExec::try_new(
+            left.clone(),
+            right.clone(),
+            on,
+            Some(filter),
+            &JoinType::Inner,
+            false,
+            left.output_ordering().map(|p| p.to_vec()),
+            right.output_ordering().map(|p| p.to_vec()),
+            StreamJoinPartitionMode::SinglePartition,
+        )?;
+        assert!(!join.check_if_order_directions_allow_pruning()?);
+        Ok(())
+    }
 }
@@ -17,24 +17,36 @@
 
 //! This file has test utils for hash joins
 
+use std::any::Any;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
 use std::usize;
 
+use crate::common::IPCWriter;
 use crate::joins::utils::{JoinFilter, JoinOn};
 use crate::joins::{
     HashJoinExec, PartitionMode, StreamJoinPartitionMode, SymmetricHashJoinExec,
 };
 use crate::memory::MemoryExec;
 use crate::repartition::RepartitionExec;
-use crate::{common, ExecutionPlan, ExecutionPlanProperties, Partitioning};
+use crate::{
+    common, DisplayAs, DisplayFormatType, ExecutionPlan, ExecutionPlanProperties,
+    Partitioning, PlanProperties, RecordBatchStream, SendableRecordBatchStream,
+    Statistics,
+};
 
+use arrow::ipc::reader::FileReader;
 use arrow::util::pretty::pretty_format_batches;
 use arrow_array::{
-    ArrayRef, Float64Array, Int32Array, IntervalDayTimeArray, RecordBatch,
-    TimestampMillisecondArray,
+    ArrayRef, Date32Array, Float64Array, Int32Array, Int64Array, IntervalDayTimeArray,
+    RecordBatch, TimestampMillisecondArray, UInt64Array,
 };
-use arrow_schema::{DataType, Schema};
-use datafusion_common::{Result, ScalarValue};
+use arrow_schema::{DataType, Schema, SchemaRef};
+use datafusion_common::{internal_err, plan_err, Result, ScalarValue};
 use datafusion_execution::TaskContext;
 use datafusion_expr::{JoinType, Operator};
 use datafusion_physical_expr::expressions::{binary, cast, col, lit};
@@ -43,6 +55,7 @@ use datafusion_physical_expr::intervals::test_utils::{
 };
 use datafusion_physical_expr::{LexOrdering, PhysicalExpr};
 
+use futures::{Stream, StreamExt};
 use rand::prelude::StdRng;
 use rand::{Rng, SeedableRng};
 
@@ -67,6 +80,105 @@ pub fn compare_batches(collected_1: &[RecordBatch], collected_2: &[RecordBatch])
     }
 }
 
+/// Stream wrapper that passes batches of the wrapped stream through unchanged
+/// while also writing each of them to an Arrow IPC file. Useful for capturing
+/// the exact output of a misbehaving join for offline comparison.
+///
+/// The inner stream is only polled when the consumer polls this stream, so
+/// backpressure is preserved. The IPC file is finished (flushed) once the inner
+/// stream completes or yields an error, after which the stream is exhausted and
+/// the inner stream is no longer polled.
+pub struct IpcRecordingStream {
+    /// The wrapped stream
+    input: SendableRecordBatchStream,
+    /// Writer for the recorded batches, `None` once finished
+    writer: Option<IPCWriter>,
+    /// Whether the stream is exhausted
+    done: bool,
+}
+
+impl IpcRecordingStream {
+    /// Wrap `input` so that its batches are recorded to the file at `path`.
+    pub fn try_new(input: SendableRecordBatchStream, path: &Path) -> Result<Self> {
+        let writer = IPCWriter::new(path, &input.schema())?;
+        Ok(Self {
+            input,
+            writer: Some(writer),
+            done: false,
+        })
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        match self.writer.take() {
+            Some(mut writer) => writer.finish(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Stream for IpcRecordingStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        let poll = self.input.poll_next_unpin(cx);
+        match &poll {
+            Poll::Ready(Some(Ok(batch))) => {
+                if let Some(writer) = self.writer.as_mut() {
+                    if let Err(e) = writer.write(batch) {
+                        self.writer = None;
+                        self.done = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                }
+            }
+            Poll::Ready(Some(Err(_))) | Poll::Ready(None) => {
+                self.done = true;
+                if let Err(e) = self.finish() {
+                    return Poll::Ready(Some(Err(e)));
+                }
+            }
+            Poll::Pending => {}
+        }
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl RecordBatchStream for IpcRecordingStream {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+}
+
+/// Executes `plan` on the given partition and collects its output, recording
+/// every batch to the Arrow IPC file at `path` along the way.
+pub async fn collect_and_record(
+    plan: Arc<dyn ExecutionPlan>,
+    partition: usize,
+    context: Arc<TaskContext>,
+    path: &Path,
+) -> Result<Vec<RecordBatch>> {
+    let stream = plan.execute(partition, context)?;
+    let stream = IpcRecordingStream::try_new(stream, path)?;
+    common::collect(Box::pin(stream)).await
+}
+
+/// Reads back all batches recorded by an [`IpcRecordingStream`].
+pub fn read_recorded_batches(path: &Path) -> Result<Vec<RecordBatch>> {
+    let file = BufReader::new(File::open(path)?);
+    let reader = FileReader::try_new(file, None)?;
+    reader.map(|batch| batch.map_err(Into::into)).collect()
+}
+
 pub async fn partitioned_sym_join_with_filter(
     left: Arc<dyn ExecutionPlan>,
     right: Arc<dyn ExecutionPlan>,
@@ -98,18 +210,13 @@ pub async fn partitioned_sym_join_with_filter(
         left.output_ordering().map(|p| p.to_vec()),
         right.output_ordering().map(|p| p.to_vec()),
         StreamJoinPartitionMode::Partitioned,
-    )?;
+    )?
+    .with_skip_empty_output_batches(true);
 
     let mut batches = vec![];
     for i in 0..partition_count {
         let stream = join.execute(i, context.clone())?;
-        let more_batches = common::collect(stream).await?;
-        batches.extend(
-            more_batches
-                .into_iter()
-                .filter(|b| b.num_rows() > 0)
-                .collect::<Vec<_>>(),
-        );
+        batches.extend(common::collect(stream).await?);
     }
 
     Ok(batches)
@@ -130,33 +237,30 @@ pub async fn partitioned_hash_join_with_filter(
         .map(|(l, r)| (l.clone() as _, r.clone() as _))
         .unzip();
 
-    let join = Arc::new(HashJoinExec::try_new(
-        Arc::new(RepartitionExec::try_new(
-            left,
-            Partitioning::Hash(left_expr, partition_count),
-        )?),
-        Arc::new(RepartitionExec::try_new(
-            right,
-            Partitioning::Hash(right_expr, partition_count),
-        )?),
-        on,
-        filter,
-        join_type,
-        None,
-        PartitionMode::Partitioned,
-        null_equals_null,
-    )?);
+    let join = Arc::new(
+        HashJoinExec::try_new(
+            Arc::new(RepartitionExec::try_new(
+                left,
+                Partitioning::Hash(left_expr, partition_count),
+            )?),
+            Arc::new(RepartitionExec::try_new(
+                right,
+                Partitioning::Hash(right_expr, partition_count),
+            )?),
+            on,
+            filter,
+            join_type,
+            None,
+            PartitionMode::Partitioned,
+            null_equals_null,
+        )?
+        .with_skip_empty_output_batches(true),
+    );
 
     let mut batches = vec![];
     for i in 0..partition_count {
         let stream = join.execute(i, context.clone())?;
-        let more_batches = common::collect(stream).await?;
-        batches.extend(
-            more_batches
-                .into_iter()
-                .filter(|b| b.num_rows() > 0)
-                .collect::<Vec<_>>(),
-        );
+        batches.extend(common::collect(stream).await?);
     }
 
     Ok(batches)
@@ -254,6 +358,57 @@ pub fn join_expr_tests_fixture_temporal(
             ScalarValue::DurationMillisecond(Some(4000)), // 4 secs
             schema,
         ),
+        // constructs ((left_col - INTERVAL '1 day')  > (right_col - INTERVAL '3 days')) AND ((left_col - INTERVAL '5 days') < (right_col - INTERVAL '2 days'))
+        3 => gen_conjunctive_temporal_expr(
+            left_col,
+            right_col,
+            Operator::Minus,
+            Operator::Minus,
+            Operator::Minus,
+            Operator::Minus,
+            ScalarValue::new_interval_dt(1, 0), // 1 day
+            ScalarValue::new_interval_dt(3, 0), // 3 days
+            ScalarValue::new_interval_dt(5, 0), // 5 days
+            ScalarValue::new_interval_dt(2, 0), // 2 days
+            schema,
+        ),
+        _ => unreachable!(),
+    }
+}
+
+/// Creates join filters which are deliberately not prunable, i.e. which do not
+/// bound the buffered rows of both sides as the inputs advance, for testing
+/// that joins detect them, see [`is_streamable`](crate::joins::utils::is_streamable).
+/// The columns are multiplied with each other, so the filters are supported by
+/// interval arithmetic, but never yield bounds on a single side. The columns
+/// must be of type `Int32`.
+pub fn join_expr_tests_fixture_non_prunable(
+    expr_id: usize,
+    left_col: Arc<dyn PhysicalExpr>,
+    right_col: Arc<dyn PhysicalExpr>,
+    schema: &Schema,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    let product = binary(
+        left_col.clone(),
+        Operator::Multiply,
+        right_col.clone(),
+        schema,
+    )?;
+    match expr_id {
+        // left_col * right_col > 100
+        0 => binary(product, Operator::Gt, lit(100), schema),
+        // left_col * right_col > 100 AND left_col + 1 > right_col + 5
+        1 => binary(
+            binary(product, Operator::Gt, lit(100), schema)?,
+            Operator::And,
+            binary(
+                binary(left_col, Operator::Plus, lit(1), schema)?,
+                Operator::Gt,
+                binary(right_col, Operator::Plus, lit(5), schema)?,
+                schema,
+            )?,
+            schema,
+        ),
         _ => unreachable!(),
     }
 }
@@ -402,6 +557,8 @@ macro_rules! join_expr_tests {
 }
 
 join_expr_tests!(join_expr_tests_fixture_i32, i32, Int32);
+join_expr_tests!(join_expr_tests_fixture_i64, i64, Int64);
+join_expr_tests!(join_expr_tests_fixture_u64, u64, UInt64);
 join_expr_tests!(join_expr_tests_fixture_f64, f64, Float64);
 
 pub fn build_sides_record_batches(
@@ -466,11 +623,24 @@ pub fn build_sides_record_batches(
             .collect::<Vec<i64>>(),
     ));
 
+    // Days around the epoch, i.e. starting with negative values:
+    let date = Arc::new(Date32Array::from_iter_values(
+        (0..table_size).map(|x| x - table_size / 2),
+    ));
+
     let float_asc = Arc::new(Float64Array::from_iter_values(
         AscendingRandomFloatIterator::new(0., table_size as f64)
             .take(table_size as usize),
     ));
 
+    let ordered_i64 = Arc::new(Int64Array::from_iter_values(
+        (0..table_size).map(|x| x as i64),
+    ));
+    // Starts at zero, so that subtractions wrap around for the first rows:
+    let ordered_u64 = Arc::new(UInt64Array::from_iter_values(
+        (0..table_size).map(|x| x as u64),
+    ));
+
     let left = RecordBatch::try_from_iter(vec![
         ("la1", ordered.clone()),
         ("lb1", cardinality.clone()),
@@ -483,6 +653,9 @@ pub fn build_sides_record_batches(
         ("l_desc_null_first", ordered_desc_null_first.clone()),
         ("li1", interval_time.clone()),
         ("l_float", float_asc.clone()),
+        ("ld1", date.clone()),
+        ("l_i64", ordered_i64.clone()),
+        ("l_u64", ordered_u64.clone()),
     ])?;
     let right = RecordBatch::try_from_iter(vec![
         ("ra1", ordered.clone()),
@@ -496,10 +669,23 @@ pub fn build_sides_record_batches(
         ("r_desc_null_first", ordered_desc_null_first),
         ("ri1", interval_time),
         ("r_float", float_asc),
+        ("rd1", date),
+        ("r_i64", ordered_i64),
+        ("r_u64", ordered_u64),
     ])?;
     Ok((left, right))
 }
 
+/// Builds the same inputs as [`build_sides_record_batches`], except that every
+/// row of both sides has the same join key, i.e. `lc1` and `rc1` are zero for
+/// all `table_size` rows. This is the worst case for a hash join: the build
+/// side forms a single match group (one long probe chain), and an equi-join on
+/// the key produces `table_size * table_size` rows, which stresses the output
+/// size guards and the chunking of large match groups.
+pub fn single_key_record_batches(table_size: i32) -> Result<(RecordBatch, RecordBatch)> {
+    build_sides_record_batches(table_size, (1, 1))
+}
+
 pub fn create_memory_table(
     left_partition: Vec<RecordBatch>,
     right_partition: Vec<RecordBatch>,
@@ -515,6 +701,115 @@ pub fn create_memory_table(
     Ok((Arc::new(left), Arc::new(right)))
 }
 
+/// [`MemoryExec`] wrapper that reports the given [`Statistics`] instead of the
+/// ones computed from its data. Lets tests control the cardinalities seen by
+/// the optimizer (e.g. to choose the join partition mode or build side)
+/// independently of the data actually produced.
+#[derive(Debug)]
+pub struct StatisticsOverrideExec {
+    /// The wrapped plan producing the data
+    input: MemoryExec,
+    /// Statistics reported by [`ExecutionPlan::statistics`]
+    statistics: Statistics,
+}
+
+impl StatisticsOverrideExec {
+    /// Wraps `input` so that it reports `statistics`, which must have one
+    /// column statistics entry per output column of `input`.
+    pub fn try_new(input: MemoryExec, statistics: Statistics) -> Result<Self> {
+        let num_fields = input.schema().fields().len();
+        if statistics.column_statistics.len() != num_fields {
+            return plan_err!(
+                "Expected {num_fields} column statistics, got {}",
+                statistics.column_statistics.len()
+            );
+        }
+        Ok(Self { input, statistics })
+    }
+
+    /// The wrapped plan
+    pub fn input(&self) -> &MemoryExec {
+        &self.input
+    }
+}
+
+impl DisplayAs for StatisticsOverrideExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "StatisticsOverrideExec: row_count={:?}, ",
+                    self.statistics.num_rows
+                )?;
+                self.input.fmt_as(t, f)
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for StatisticsOverrideExec {
+    fn name(&self) -> &'static str {
+        "StatisticsOverrideExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        self.input.properties()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        // Like the wrapped MemoryExec, this is a leaf node
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if children.is_empty() {
+            Ok(self)
+        } else {
+            internal_err!("Children cannot be replaced in {self:?}")
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        self.input.execute(partition, context)
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        Ok(self.statistics.clone())
+    }
+}
+
+/// Wraps a single partition of `batches` in a [`MemoryExec`] reporting
+/// `statistics` rather than the statistics of `batches`, see
+/// [`StatisticsOverrideExec`].
+pub fn with_statistics_override(
+    batches: Vec<RecordBatch>,
+    sorted: Vec<LexOrdering>,
+    statistics: Statistics,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let schema = batches[0].schema();
+    let input =
+        MemoryExec::try_new(&[batches], schema, None)?.with_sort_information(sorted);
+    Ok(Arc::new(StatisticsOverrideExec::try_new(
+        input, statistics,
+    )?))
+}
+
 /// Filter expr for a + b > c + 10 AND a + b < c + 100
 pub(crate) fn complicated_filter(
     filter_schema: &Schema,
@@ -562,3 +857,44 @@ pub(crate) fn complicated_filter(
     )?;
     binary(left_expr, Operator::And, right_expr, filter_schema)
 }
+
+/// Filter expr for a - b > a - c - 10 AND a < b + 10, where `a` is referenced
+/// twice through columns "0" and "2" of the intermediate schema
+pub(crate) fn complicated_4_column_exprs(
+    filter_schema: &Schema,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    let left_expr = binary(
+        binary(
+            col("0", filter_schema)?,
+            Operator::Minus,
+            col("1", filter_schema)?,
+            filter_schema,
+        )?,
+        Operator::Gt,
+        binary(
+            binary(
+                col("2", filter_schema)?,
+                Operator::Minus,
+                col("3", filter_schema)?,
+                filter_schema,
+            )?,
+            Operator::Minus,
+            lit(ScalarValue::Int32(Some(10))),
+            filter_schema,
+        )?,
+        filter_schema,
+    )?;
+
+    let right_expr = binary(
+        col("2", filter_schema)?,
+        Operator::Lt,
+        binary(
+            col("1", filter_schema)?,
+            Operator::Plus,
+            lit(ScalarValue::Int32(Some(10))),
+            filter_schema,
+        )?,
+        filter_schema,
+    )?;
+    binary(left_expr, Operator::And, right_expr, filter_schema)
+}
@@ -17,44 +17,88 @@
 
 //! Join related functionality used both on logical and physical plans
 
-use std::collections::HashSet;
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Debug};
 use std::future::Future;
+use std::hash::{Hash, Hasher};
 use std::ops::{IndexMut, Range};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::usize;
 
-use crate::metrics::{self, ExecutionPlanMetricsSet, MetricBuilder};
-use crate::{ColumnStatistics, ExecutionPlan, Partitioning, Statistics};
+use crate::filter::FilterExec;
+use crate::joins::stream_join_utils::{
+    build_filter_input_order, is_build_side_prunable,
+    update_sorted_exprs_with_node_indices,
+};
+use crate::joins::{Collation, FloatKeyMode, PartitionMode};
+use crate::memory::MemoryExec;
+use crate::metrics::{self, ExecutionPlanMetricsSet, MetricBuilder, MetricValue};
+use crate::sorts::sort::sort_batch;
+use crate::stream::RecordBatchStreamAdapter;
+use crate::topk::TopK;
+use crate::{
+    collect_partitioned, ColumnStatistics, ExecutionPlan, ExecutionPlanProperties,
+    Partitioning, RecordBatchStream, SendableRecordBatchStream, Statistics,
+};
 
 use arrow::array::{
-    downcast_array, new_null_array, Array, BooleanBufferBuilder, UInt32Array,
-    UInt32BufferBuilder, UInt32Builder, UInt64Array, UInt64BufferBuilder,
+    downcast_array, new_null_array, Array, ArrayData, ArrayRef, AsArray, BinaryViewArray,
+    BooleanArray, BooleanBufferBuilder, GenericByteViewArray, LargeStringArray,
+    StringArray, StringViewArray, UInt32Array, UInt32BufferBuilder, UInt32Builder,
+    UInt64Array, UInt64BufferBuilder,
 };
 use arrow::compute;
-use arrow::datatypes::{Field, Schema, SchemaBuilder};
+use arrow::datatypes::{
+    ByteViewType, DataType, Field, Float16Type, Float32Type, Float64Type, Schema,
+    SchemaBuilder, SchemaRef,
+};
 use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use arrow::row::{OwnedRow, Row, RowConverter, Rows, SortField};
+use arrow::util::bit_util;
 use arrow_array::{ArrowPrimitiveType, NativeAdapter, PrimitiveArray};
-use arrow_buffer::ArrowNativeType;
-use datafusion_common::cast::as_boolean_array;
+use arrow_buffer::bit_chunk_iterator::BitChunks;
+use arrow_buffer::{ArrowNativeType, NullBuffer, ScalarBuffer};
+use datafusion_common::cast::{
+    as_binary_view_array, as_boolean_array, as_generic_string_array, as_string_array,
+    as_string_view_array,
+};
 use datafusion_common::stats::Precision;
-use datafusion_common::tree_node::{Transformed, TransformedResult, TreeNode};
+use datafusion_common::tree_node::{
+    Transformed, TransformedResult, TreeNode, TreeNodeRecursion,
+};
 use datafusion_common::{
-    plan_err, DataFusionError, JoinSide, JoinType, Result, SharedResult,
+    internal_err, plan_err, DataFusionError, JoinSide, JoinType, Result, ScalarValue,
+    SharedResult,
 };
+use datafusion_execution::memory_pool::MemoryReservation;
+use datafusion_execution::TaskContext;
 use datafusion_expr::interval_arithmetic::Interval;
+use datafusion_expr::{ColumnarValue, Operator};
 use datafusion_physical_expr::equivalence::add_offset_to_expr;
-use datafusion_physical_expr::expressions::Column;
-use datafusion_physical_expr::utils::{collect_columns, merge_vectors};
+use datafusion_physical_expr::expressions::{
+    is_not_null, is_null, not, BinaryExpr, CastExpr, Column, InListExpr, LikeExpr,
+    Literal, NegativeExpr, TryCastExpr, UnKnownColumn,
+};
+use datafusion_physical_expr::intervals::cp_solver::ExprIntervalGraph;
+use datafusion_physical_expr::utils::{
+    collect_columns, merge_vectors, split_conjunction,
+};
 use datafusion_physical_expr::{
-    LexOrdering, LexOrderingRef, PhysicalExpr, PhysicalExprRef, PhysicalSortExpr,
+    EquivalenceProperties, LexOrdering, LexOrderingRef, PhysicalExpr, PhysicalExprRef,
+    PhysicalSortExpr, ScalarFunctionExpr,
 };
+use datafusion_physical_expr_common::physical_expr::down_cast_any_ref;
 
 use futures::future::{BoxFuture, Shared};
-use futures::{ready, FutureExt};
+use futures::stream::BoxStream;
+use futures::{ready, FutureExt, Stream, StreamExt, TryStreamExt};
 use hashbrown::raw::RawTable;
 use parking_lot::Mutex;
+use serde_json::json;
+use tokio::sync::Semaphore;
 
 /// Maps a `u64` hash value based on the build side ["on" values] to a list of indices with this key's value.
 ///
@@ -123,18 +167,366 @@ pub struct JoinHashMap {
     map: RawTable<(u64, u64)>,
     // Stores indices in chained list data structure
     next: Vec<u64>,
+    // Indexes of the chains longer than the chain length limit by hash value,
+    // see `index_long_chains`
+    chain_indices: HashMap<u64, ChainIndex>,
+    // Converts the join keys to the row format the chain indexes are sorted by
+    chain_key_converter: Option<RowConverter>,
 }
 
 impl JoinHashMap {
     #[cfg(test)]
     pub(crate) fn new(map: RawTable<(u64, u64)>, next: Vec<u64>) -> Self {
-        Self { map, next }
+        Self {
+            map,
+            next,
+            chain_indices: HashMap::new(),
+            chain_key_converter: None,
+        }
     }
 
     pub(crate) fn with_capacity(capacity: usize) -> Self {
         JoinHashMap {
             map: RawTable::with_capacity(capacity),
             next: vec![0; capacity],
+            chain_indices: HashMap::new(),
+            chain_key_converter: None,
+        }
+    }
+
+    /// Returns the hash value and row indices of every chain of rows sharing
+    /// a hash value.
+    fn chains_by_hash(&self) -> Vec<(u64, Vec<usize>)> {
+        // Each entry of the map is the head of a chain of rows sharing its hash value:
+        let heads = unsafe {
+            self.map
+                .iter()
+                .map(|bucket| *bucket.as_ref())
+                .collect::<Vec<_>>()
+        };
+        heads
+            .into_iter()
+            .map(|(hash, head)| {
+                let mut chain = vec![];
+                let mut index = head;
+                while index != 0 {
+                    chain.push((index - 1) as usize);
+                    // Without chains, every hash value maps to a single row:
+                    index = self.next.get(index as usize - 1).copied().unwrap_or(0);
+                }
+                (hash, chain)
+            })
+            .collect()
+    }
+
+    /// Returns the row indices of every chain of rows sharing a hash value.
+    fn chains(&self) -> Vec<Vec<usize>> {
+        self.chains_by_hash()
+            .into_iter()
+            .map(|(_, chain)| chain)
+            .collect()
+    }
+
+    /// Indexes the rows of every chain longer than `max_chain_length` by their
+    /// join `keys`, the key values of the rows the hash table was built from,
+    /// so that a probe row hitting such a chain finds the rows with equal keys
+    /// by a binary search instead of comparing its keys to every row of the
+    /// chain, see [`ChainIndex`]. The chains themselves are kept. Returns the
+    /// number of indexed chains.
+    pub(crate) fn index_long_chains(
+        &mut self,
+        keys: &[ArrayRef],
+        max_chain_length: usize,
+    ) -> Result<usize> {
+        let long_chains = self
+            .chains_by_hash()
+            .into_iter()
+            .filter(|(_, chain)| chain.len() > max_chain_length)
+            .collect::<Vec<_>>();
+        if long_chains.is_empty() {
+            return Ok(0);
+        }
+        let converter = RowConverter::new(
+            keys.iter()
+                .map(|key| SortField::new(key.data_type().clone()))
+                .collect(),
+        )?;
+        let rows = converter.convert_columns(keys)?;
+        for (hash, mut chain) in long_chains {
+            // The sort is stable, so the rows with equal keys stay in chain
+            // order, which is the order the rows are matched in:
+            chain.sort_by(|a, b| rows.row(*a).cmp(&rows.row(*b)));
+            let mut index = ChainIndex {
+                keys: vec![],
+                offsets: vec![],
+                rows: Vec::with_capacity(chain.len()),
+            };
+            for (i, row) in chain.iter().enumerate() {
+                if i == 0 || rows.row(chain[i - 1]) != rows.row(*row) {
+                    index.keys.push(rows.row(*row).owned());
+                    index.offsets.push(i);
+                }
+                index.rows.push(*row as u64);
+            }
+            index.offsets.push(chain.len());
+            self.chain_indices.insert(hash, index);
+        }
+        self.chain_key_converter = Some(converter);
+        Ok(self.chain_indices.len())
+    }
+
+    /// Returns the converter of the join keys to the row format the chain
+    /// indexes are sorted by, if any chains are indexed
+    pub(crate) fn chain_key_converter(&self) -> Option<&RowConverter> {
+        self.chain_key_converter.as_ref()
+    }
+
+    /// Estimated memory used by the chain indexes
+    pub(crate) fn chain_indices_size(&self) -> usize {
+        self.chain_indices
+            .values()
+            .map(ChainIndex::size)
+            .sum::<usize>()
+            + self.chain_key_converter.as_ref().map_or(0, |c| c.size())
+    }
+
+    /// Like [`JoinHashMapType::get_matched_indices_with_limit_offset`] without
+    /// a deleted offset, except that the candidates of a probe row hitting an
+    /// indexed chain are looked up in its index by the row's `probe_keys`, see
+    /// [`Self::index_long_chains`]. These candidates are exactly the rows with
+    /// equal keys, so they are flagged as verified. Probe rows that are null
+    /// in `probe_nulls` have no candidates in an indexed chain.
+    pub(crate) fn get_matched_indices_with_chain_index(
+        &self,
+        hash_values: &[u64],
+        probe_keys: &Rows,
+        probe_nulls: Option<&NullBuffer>,
+        limit: usize,
+        offset: JoinHashMapOffset,
+    ) -> IndexedMatches {
+        let mut matches = IndexedMatches {
+            probe_indices: UInt32BufferBuilder::new(0),
+            build_indices: UInt64BufferBuilder::new(0),
+            verified: BooleanBufferBuilder::new(0),
+            next_offset: None,
+            key_comparisons: 0,
+        };
+        let mut remaining_output = limit;
+        let last_row = hash_values.len().saturating_sub(1);
+        // A pending chain index of an offset is the next chain link for plain
+        // chains, and the number of rows matched so far for indexed chains:
+        let (mut row_idx, mut resume) = match offset {
+            (initial_idx, None) => (initial_idx, None),
+            (initial_idx, Some(0)) => (initial_idx + 1, None),
+            (initial_idx, Some(next)) => (initial_idx, Some(next)),
+        };
+        while row_idx < hash_values.len() {
+            let hash_value = hash_values[row_idx];
+            if let Some(index) = self.chain_indices.get(&hash_value) {
+                let rows: &[u64] =
+                    if probe_nulls.is_some_and(|nulls| nulls.is_null(row_idx)) {
+                        &[]
+                    } else {
+                        let (rows, comparisons) = index.lookup(probe_keys.row(row_idx));
+                        matches.key_comparisons += comparisons;
+                        rows
+                    };
+                let start = resume.unwrap_or(0) as usize;
+                for (position, build_row) in rows.iter().enumerate().skip(start) {
+                    matches.push(row_idx, *build_row, true);
+                    remaining_output -= 1;
+                    if remaining_output == 0 {
+                        matches.next_offset = match position + 1 {
+                            matched if matched < rows.len() => {
+                                Some((row_idx, Some(matched as u64)))
+                            }
+                            _ if row_idx == last_row => None,
+                            _ => Some((row_idx, Some(0))),
+                        };
+                        return matches;
+                    }
+                }
+            } else if let Some((_, head)) =
+                self.map.get(hash_value, |(hash, _)| hash_value == *hash)
+            {
+                let mut index = resume.unwrap_or(*head);
+                loop {
+                    let build_row = index - 1;
+                    matches.push(row_idx, build_row, false);
+                    remaining_output -= 1;
+                    let next = self.next.get(build_row as usize).copied().unwrap_or(0);
+                    if remaining_output == 0 {
+                        matches.next_offset = if row_idx == last_row && next == 0 {
+                            None
+                        } else {
+                            Some((row_idx, Some(next)))
+                        };
+                        return matches;
+                    }
+                    if next == 0 {
+                        break;
+                    }
+                    index = next;
+                }
+            }
+            resume = None;
+            row_idx += 1;
+        }
+        matches
+    }
+
+    /// Computes fill statistics of the hash table, see [`JoinHashMapStats`].
+    pub(crate) fn fill_stats(&self) -> JoinHashMapStats {
+        let mut max_chain_length = 0;
+        let mut chained_rows = 0;
+        for chain in self.chains() {
+            max_chain_length = max_chain_length.max(chain.len());
+            chained_rows += chain.len();
+        }
+        JoinHashMapStats {
+            buckets: self.map.buckets(),
+            entries: self.map.len(),
+            max_chain_length,
+            collisions: chained_rows - self.map.len(),
+        }
+    }
+
+    /// Returns a pair of rows whose `keys` are equal, if any, where `keys` are
+    /// the join key values of the rows the hash table was built from. Only
+    /// rows sharing a hash value are compared, so this is cheap unless the
+    /// keys contain many duplicates. Null keys are only equal to each other
+    /// if `null_equals_null` is set.
+    pub(crate) fn find_duplicate_keys(
+        &self,
+        keys: &[ArrayRef],
+        null_equals_null: bool,
+    ) -> Result<Option<(usize, usize)>> {
+        let chains = self
+            .chains()
+            .into_iter()
+            .filter(|chain| chain.len() > 1)
+            .collect::<Vec<_>>();
+        if chains.is_empty() {
+            return Ok(None);
+        }
+        let converter = RowConverter::new(
+            keys.iter()
+                .map(|key| SortField::new(key.data_type().clone()))
+                .collect(),
+        )?;
+        let rows = converter.convert_columns(keys)?;
+        for chain in chains {
+            for (i, row) in chain.iter().enumerate() {
+                if !null_equals_null && keys.iter().any(|key| key.is_null(*row)) {
+                    continue;
+                }
+                if let Some(other) = chain[..i]
+                    .iter()
+                    .find(|other| rows.row(**other) == rows.row(*row))
+                {
+                    return Ok(Some((*other, *row)));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Releases the chains of rows sharing a hash value if every hash value
+    /// maps to a single row, e.g. because the join keys are unique, so that
+    /// lookups don't need to traverse them. Returns whether the chains were
+    /// released.
+    pub(crate) fn release_chains(&mut self) -> bool {
+        if self.next.iter().any(|next| *next != 0) {
+            return false;
+        }
+        self.next = vec![];
+        true
+    }
+}
+
+/// Index over the rows of a long chain of a [`JoinHashMap`], i.e. of many rows
+/// sharing a hash value, see [`JoinHashMap::index_long_chains`]. The rows are
+/// grouped by their join key values, sorted in row format.
+pub(crate) struct ChainIndex {
+    /// The distinct join key values of the rows of the chain, sorted
+    keys: Vec<OwnedRow>,
+    /// The start of the group of rows of every key in `rows`, followed by the
+    /// number of rows
+    offsets: Vec<usize>,
+    /// The rows of the chain grouped by key, in chain order within a group
+    rows: Vec<u64>,
+}
+
+impl ChainIndex {
+    /// Returns the rows with the join key values `key`, and the number of key
+    /// comparisons made to find them
+    fn lookup(&self, key: Row<'_>) -> (&[u64], usize) {
+        let mut comparisons = 0;
+        let found = self.keys.binary_search_by(|probe| {
+            comparisons += 1;
+            probe.row().cmp(&key)
+        });
+        match found {
+            Ok(group) => (
+                &self.rows[self.offsets[group]..self.offsets[group + 1]],
+                comparisons,
+            ),
+            Err(_) => (&[], comparisons),
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.keys
+            .iter()
+            .map(|key| std::mem::size_of::<OwnedRow>() + key.row().as_ref().len())
+            .sum::<usize>()
+            + self.offsets.capacity() * std::mem::size_of::<usize>()
+            + self.rows.capacity() * std::mem::size_of::<u64>()
+    }
+}
+
+/// Candidate pairs of probe and build side rows found by
+/// [`JoinHashMap::get_matched_indices_with_chain_index`]
+pub(crate) struct IndexedMatches {
+    pub probe_indices: UInt32BufferBuilder,
+    pub build_indices: UInt64BufferBuilder,
+    /// Whether the join keys of a pair are known to be equal
+    pub verified: BooleanBufferBuilder,
+    /// Offset to continue from, if the limit was reached
+    pub next_offset: Option<JoinHashMapOffset>,
+    /// Number of key comparisons made by the chain index lookups
+    pub key_comparisons: usize,
+}
+
+impl IndexedMatches {
+    fn push(&mut self, probe_row: usize, build_row: u64, verified: bool) {
+        self.probe_indices.append(probe_row as u32);
+        self.build_indices.append(build_row);
+        self.verified.append(verified);
+    }
+}
+
+/// Fill statistics of a [`JoinHashMap`] after it has been built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct JoinHashMapStats {
+    /// Number of buckets allocated by the hash table
+    pub buckets: usize,
+    /// Number of distinct hash values stored in the hash table
+    pub entries: usize,
+    /// Length of the longest chain of rows sharing a hash value, i.e. the
+    /// maximum number of candidates a single probe row is checked against
+    pub max_chain_length: usize,
+    /// Number of rows chained behind another row with the same hash value
+    pub collisions: usize,
+}
+
+impl JoinHashMapStats {
+    /// Ratio of occupied buckets, as a percentage
+    pub fn load_factor_pct(&self) -> usize {
+        if self.buckets == 0 {
+            0
+        } else {
+            self.entries * 100 / self.buckets
         }
     }
 }
@@ -198,6 +590,11 @@ pub trait JoinHashMapType {
     fn get_map(&self) -> &RawTable<(u64, u64)>;
     /// Returns a reference to the next.
     fn get_list(&self) -> &Self::NextType;
+    /// Returns whether rows may share a hash value, i.e. whether the next list
+    /// has to be followed to find all rows matching a hash value.
+    fn has_chains(&self) -> bool {
+        true
+    }
 
     /// Updates hashmap from iterator of row indices & row hashes pairs.
     fn update_from_iter<'a>(
@@ -300,6 +697,35 @@ pub trait JoinHashMapType {
         let hash_map: &RawTable<(u64, u64)> = self.get_map();
         let next_chain = self.get_list();
 
+        if !self.has_chains() {
+            // Every hash value maps to a single row, so an offset with a pending
+            // chain index never occurs:
+            let to_skip = match offset {
+                (initial_idx, None) => initial_idx,
+                (initial_idx, Some(_)) => initial_idx + 1,
+            };
+            for (row_idx, hash_value) in hash_values.iter().enumerate().skip(to_skip) {
+                let Some((_, index)) =
+                    hash_map.get(*hash_value, |(hash, _)| *hash_value == *hash)
+                else {
+                    continue;
+                };
+                match deleted_offset {
+                    Some(offset) if *index - 1 < offset as u64 => continue,
+                    Some(offset) => match_indices.append(*index - 1 - offset as u64),
+                    None => match_indices.append(*index - 1),
+                }
+                input_indices.append(row_idx as u32);
+                remaining_output -= 1;
+                if remaining_output == 0 {
+                    let next_offset =
+                        (row_idx != hash_values.len() - 1).then_some((row_idx, Some(0)));
+                    return (input_indices, match_indices, next_offset);
+                }
+            }
+            return (input_indices, match_indices, None);
+        }
+
         // Calculate initial `hash_values` index before iterating
         let to_skip = match offset {
             // None `initial_next_idx` indicates that `initial_idx` processing has'n been started
@@ -369,6 +795,12 @@ impl JoinHashMapType for JoinHashMap {
     fn get_list(&self) -> &Self::NextType {
         &self.next
     }
+
+    /// The next list is empty once the chains have been released, see
+    /// [`JoinHashMap::release_chains`].
+    fn has_chains(&self) -> bool {
+        !self.next.is_empty()
+    }
 }
 
 impl fmt::Debug for JoinHashMap {
@@ -382,6 +814,70 @@ pub type JoinOn = Vec<(PhysicalExprRef, PhysicalExprRef)>;
 /// Reference for JoinOn.
 pub type JoinOnRef<'a> = &'a [(PhysicalExprRef, PhysicalExprRef)];
 
+/// Version of the layout of the JSON objects describing join metrics, see
+/// [`HashJoinExec::metrics_json`](crate::joins::HashJoinExec::metrics_json).
+/// It is increased whenever keys are renamed or removed or the types of their
+/// values change, but not when new keys are added.
+pub const JOIN_METRICS_JSON_VERSION: u64 = 1;
+
+/// Describes the metrics of a join operator as a JSON object of the form
+///
+/// ```text
+/// {
+///   "version": 1,
+///   "operator": "HashJoinExec",
+///   "join_type": "Inner",
+///   "partition_mode": "CollectLeft",
+///   "metrics": {
+///     "output_rows": { "kind": "count", "value": 3 },
+///     ...
+///   }
+/// }
+/// ```
+///
+/// Metrics are summed up across partitions, times are in nanoseconds and
+/// timestamps in nanoseconds since the epoch. `partition_mode` is `null` for
+/// operators without partition modes.
+pub(crate) fn join_metrics_json(
+    operator: &str,
+    join_type: &JoinType,
+    partition_mode: Option<String>,
+    metrics: &ExecutionPlanMetricsSet,
+) -> serde_json::Value {
+    let metrics = metrics
+        .clone_inner()
+        .aggregate_by_name()
+        .sorted_for_display()
+        .iter()
+        .map(|metric| {
+            let value = metric.value();
+            let kind = match value {
+                MetricValue::OutputRows(_)
+                | MetricValue::SpillCount(_)
+                | MetricValue::SpilledBytes(_)
+                | MetricValue::SpilledRows(_)
+                | MetricValue::Count { .. } => "count",
+                MetricValue::CurrentMemoryUsage(_) | MetricValue::Gauge { .. } => "gauge",
+                MetricValue::ElapsedCompute(_) | MetricValue::Time { .. } => "time",
+                MetricValue::StartTimestamp(_) | MetricValue::EndTimestamp(_) => {
+                    "timestamp"
+                }
+            };
+            (
+                value.name().to_string(),
+                json!({ "kind": kind, "value": value.as_usize() }),
+            )
+        })
+        .collect::<serde_json::Map<_, _>>();
+    json!({
+        "version": JOIN_METRICS_JSON_VERSION,
+        "operator": operator,
+        "join_type": join_type.to_string(),
+        "partition_mode": partition_mode,
+        "metrics": metrics,
+    })
+}
+
 /// Checks whether the schemas "left" and "right" and columns "on" represent a valid join.
 /// They are valid whenever their columns' intersection equals the set `on`
 pub fn check_join_is_valid(left: &Schema, right: &Schema, on: JoinOnRef) -> Result<()> {
@@ -429,7 +925,45 @@ fn check_join_set_is_valid(
     Ok(())
 }
 
+/// Removes the pairs of `on` that are implied by an earlier pair: a pair is
+/// redundant if both of its keys are equal to the keys of a kept pair, either
+/// syntactically or through the equivalences known to hold on the respective
+/// inputs (`left_eq` and `right_eq`). Comparing a redundant pair can never
+/// reject a row that the earlier pair accepted, so dropping it does not change
+/// the join result. The order of the kept pairs is preserved, and the first
+/// pair is always kept.
+pub fn canonicalize_join_on(
+    on: JoinOn,
+    left_eq: &EquivalenceProperties,
+    right_eq: &EquivalenceProperties,
+) -> JoinOn {
+    let mut normalized_keys: Vec<(PhysicalExprRef, PhysicalExprRef)> = vec![];
+    on.into_iter()
+        .filter(|(left_key, right_key)| {
+            let left_key = left_eq.eq_group().normalize_expr(left_key.clone());
+            let right_key = right_eq.eq_group().normalize_expr(right_key.clone());
+            let redundant = normalized_keys
+                .iter()
+                .any(|(left, right)| left.eq(&left_key) && right.eq(&right_key));
+            if !redundant {
+                normalized_keys.push((left_key, right_key));
+            }
+            !redundant
+        })
+        .collect()
+}
+
 /// Calculate the OutputPartitioning for Partitioned Join
+///
+/// Both inputs of a partitioned equi-join are hash partitioned on their join
+/// keys, so every output row stays in the partition of its key. The output is
+/// reported as partitioned like the input whose rows are all preserved, i.e.
+/// on the key columns of that side: rows padded with nulls for the other side
+/// only have null keys on the other side, while the keys of the preserved side
+/// (null or not) still hash to the partition they came from. A `Full` join
+/// preserves the rows of neither side, e.g. an unmatched right row has a null
+/// left key but lives in the partition of its right key, so its partitioning
+/// is unknown.
 pub fn partitioned_join_output_partitioning(
     join_type: JoinType,
     left_partitioning: &Partitioning,
@@ -437,10 +971,14 @@ pub fn partitioned_join_output_partitioning(
     left_columns_len: usize,
 ) -> Partitioning {
     match join_type {
-        JoinType::Inner | JoinType::Left | JoinType::LeftSemi | JoinType::LeftAnti => {
-            left_partitioning.clone()
+        JoinType::Inner
+        | JoinType::Left
+        | JoinType::LeftSemi
+        | JoinType::LeftAnti
+        | JoinType::LeftMark => left_partitioning.clone(),
+        JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => {
+            right_partitioning.clone()
         }
-        JoinType::RightSemi | JoinType::RightAnti => right_partitioning.clone(),
         JoinType::Right => {
             adjust_right_output_partitioning(right_partitioning, left_columns_len)
         }
@@ -463,6 +1001,10 @@ pub fn adjust_right_output_partitioning(
                 .collect();
             Partitioning::Hash(new_exprs, *size)
         }
+        Partitioning::Bucketed(expr, size) => Partitioning::Bucketed(
+            add_offset_to_expr(expr.clone(), left_columns_len),
+            *size,
+        ),
         result => result.clone(),
     }
 }
@@ -571,6 +1113,210 @@ pub struct ColumnIndex {
     pub side: JoinSide,
 }
 
+/// Join key expression evaluating to the normalized form of the string values
+/// of `expr` under `collation`, so that keys which are equal under the
+/// collation are hashed and compared as equal. Values of other types are
+/// passed through unchanged.
+#[derive(Debug, Hash)]
+pub(crate) struct CollatedKeyExpr {
+    expr: PhysicalExprRef,
+    collation: Collation,
+}
+
+impl CollatedKeyExpr {
+    /// Wraps the join key `expr`, unless `collation` is [`Collation::Binary`]
+    pub(crate) fn wrap(expr: PhysicalExprRef, collation: Collation) -> PhysicalExprRef {
+        match collation {
+            Collation::Binary => expr,
+            Collation::CaseInsensitive => Arc::new(Self { expr, collation }),
+        }
+    }
+
+    fn normalize(&self, array: &ArrayRef) -> Result<ArrayRef> {
+        if self.collation == Collation::Binary {
+            return Ok(array.clone());
+        }
+        Ok(match array.data_type() {
+            DataType::Utf8 => Arc::new(
+                as_string_array(array)?
+                    .iter()
+                    .map(|value| value.map(str::to_lowercase))
+                    .collect::<StringArray>(),
+            ),
+            DataType::LargeUtf8 => Arc::new(
+                as_generic_string_array::<i64>(array)?
+                    .iter()
+                    .map(|value| value.map(str::to_lowercase))
+                    .collect::<LargeStringArray>(),
+            ),
+            _ => array.clone(),
+        })
+    }
+}
+
+impl fmt::Display for CollatedKeyExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} COLLATE {:?}", self.expr, self.collation)
+    }
+}
+
+impl PhysicalExpr for CollatedKeyExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
+        self.expr.data_type(input_schema)
+    }
+
+    fn nullable(&self, input_schema: &Schema) -> Result<bool> {
+        self.expr.nullable(input_schema)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        match self.expr.evaluate(batch)? {
+            ColumnarValue::Array(array) => {
+                Ok(ColumnarValue::Array(self.normalize(&array)?))
+            }
+            ColumnarValue::Scalar(scalar) => {
+                let array = self.normalize(&scalar.to_array()?)?;
+                Ok(ColumnarValue::Scalar(ScalarValue::try_from_array(
+                    &array, 0,
+                )?))
+            }
+        }
+    }
+
+    fn children(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn PhysicalExpr>>,
+    ) -> Result<Arc<dyn PhysicalExpr>> {
+        Ok(Arc::new(Self {
+            expr: children[0].clone(),
+            collation: self.collation,
+        }))
+    }
+
+    fn dyn_hash(&self, state: &mut dyn Hasher) {
+        let mut s = state;
+        self.hash(&mut s);
+    }
+}
+
+impl PartialEq<dyn Any> for CollatedKeyExpr {
+    fn eq(&self, other: &dyn Any) -> bool {
+        down_cast_any_ref(other)
+            .downcast_ref::<Self>()
+            .map(|x| self.expr.eq(&x.expr) && self.collation == x.collation)
+            .unwrap_or(false)
+    }
+}
+
+/// Join key expression evaluating to the canonical form of the float values of
+/// `expr` under [`FloatKeyMode::Ieee`]: `-0.0` is replaced by `+0.0`, and NaN
+/// by null, so that keys which are equal under IEEE 754 are hashed and compared
+/// as equal, and NaN matches nothing as long as nulls are not compared equal.
+/// Values of other types are passed through unchanged.
+#[derive(Debug, Hash)]
+pub(crate) struct FloatKeyExpr {
+    expr: PhysicalExprRef,
+}
+
+/// Canonicalizes the values of a float array, see [`FloatKeyExpr`]
+macro_rules! ieee_float_keys {
+    ($array:expr, $ty:ty, $zero:expr) => {
+        Arc::new($array.as_primitive::<$ty>().unary_opt::<_, $ty>(|value| {
+            (!value.is_nan()).then_some(if value == $zero { $zero } else { value })
+        })) as ArrayRef
+    };
+}
+
+impl FloatKeyExpr {
+    /// Wraps the join key `expr`, unless `mode` is [`FloatKeyMode::TotalOrder`],
+    /// which the hash table implements on the raw values
+    pub(crate) fn wrap(expr: PhysicalExprRef, mode: FloatKeyMode) -> PhysicalExprRef {
+        match mode {
+            FloatKeyMode::TotalOrder => expr,
+            FloatKeyMode::Ieee => Arc::new(Self { expr }),
+        }
+    }
+
+    fn normalize(array: &ArrayRef) -> ArrayRef {
+        match array.data_type() {
+            DataType::Float16 => ieee_float_keys!(array, Float16Type, half::f16::ZERO),
+            DataType::Float32 => ieee_float_keys!(array, Float32Type, 0.0),
+            DataType::Float64 => ieee_float_keys!(array, Float64Type, 0.0),
+            _ => array.clone(),
+        }
+    }
+}
+
+impl fmt::Display for FloatKeyExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} FLOAT_KEYS {:?}", self.expr, FloatKeyMode::Ieee)
+    }
+}
+
+impl PhysicalExpr for FloatKeyExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
+        self.expr.data_type(input_schema)
+    }
+
+    fn nullable(&self, input_schema: &Schema) -> Result<bool> {
+        let data_type = self.expr.data_type(input_schema)?;
+        Ok(data_type.is_floating() || self.expr.nullable(input_schema)?)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        match self.expr.evaluate(batch)? {
+            ColumnarValue::Array(array) => {
+                Ok(ColumnarValue::Array(Self::normalize(&array)))
+            }
+            ColumnarValue::Scalar(scalar) => {
+                let array = Self::normalize(&scalar.to_array()?);
+                Ok(ColumnarValue::Scalar(ScalarValue::try_from_array(
+                    &array, 0,
+                )?))
+            }
+        }
+    }
+
+    fn children(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn PhysicalExpr>>,
+    ) -> Result<Arc<dyn PhysicalExpr>> {
+        Ok(Arc::new(Self {
+            expr: children[0].clone(),
+        }))
+    }
+
+    fn dyn_hash(&self, state: &mut dyn Hasher) {
+        let mut s = state;
+        self.hash(&mut s);
+    }
+}
+
+impl PartialEq<dyn Any> for FloatKeyExpr {
+    fn eq(&self, other: &dyn Any) -> bool {
+        down_cast_any_ref(other)
+            .downcast_ref::<Self>()
+            .map(|x| self.expr.eq(&x.expr))
+            .unwrap_or(false)
+    }
+}
+
 /// Filter applied before join output
 #[derive(Debug, Clone)]
 pub struct JoinFilter {
@@ -628,53 +1374,677 @@ impl JoinFilter {
     pub fn schema(&self) -> &Schema {
         &self.schema
     }
-}
 
-/// Returns the output field given the input field. Outer joins may
-/// insert nulls even if the input was not null
-///
-fn output_join_field(old_field: &Field, join_type: &JoinType, is_left: bool) -> Field {
-    let force_nullable = match join_type {
-        JoinType::Inner => false,
-        JoinType::Left => !is_left, // right input is padded with nulls
-        JoinType::Right => is_left, // left input is padded with nulls
-        JoinType::Full => true,     // both inputs can be padded with nulls
-        JoinType::LeftSemi => false, // doesn't introduce nulls
-        JoinType::RightSemi => false, // doesn't introduce nulls
-        JoinType::LeftAnti => false, // doesn't introduce nulls (or can it??)
-        JoinType::RightAnti => false, // doesn't introduce nulls (or can it??)
-    };
+    /// Checks that this filter is consistent with the join inputs, i.e. that
+    /// every column referenced by the expression is backed by a column index,
+    /// and that each column index points to an existing column of the given
+    /// side whose type matches the intermediate schema.
+    pub fn validate(&self, left_schema: &Schema, right_schema: &Schema) -> Result<()> {
+        let fields = self.schema.fields();
+        if self.column_indices.len() != fields.len() {
+            return plan_err!(
+                "Join filter has {} column indices, but its intermediate schema has {} fields",
+                self.column_indices.len(),
+                fields.len()
+            );
+        }
+        for column in collect_columns(&self.expression) {
+            if column.index() >= fields.len() {
+                return plan_err!(
+                    "Join filter references column {column}, which is out of bounds for its intermediate schema with {} fields",
+                    fields.len()
+                );
+            }
+        }
+        for (field, column_index) in fields.iter().zip(&self.column_indices) {
+            let (side_schema, side) = match column_index.side {
+                JoinSide::Left => (left_schema, "left"),
+                JoinSide::Right => (right_schema, "right"),
+            };
+            let Some(side_field) = side_schema.fields().get(column_index.index) else {
+                return plan_err!(
+                    "Join filter column {} refers to index {} of the {side} side, which has only {} columns",
+                    field.name(),
+                    column_index.index,
+                    side_schema.fields().len()
+                );
+            };
+            if side_field.data_type() != field.data_type() {
+                return plan_err!(
+                    "Join filter column {} has type {}, but column {} of the {side} side has type {}",
+                    field.name(),
+                    field.data_type(),
+                    side_field.name(),
+                    side_field.data_type()
+                );
+            }
+        }
+        Ok(())
+    }
 
-    if force_nullable {
-        old_field.clone().with_nullable(true)
-    } else {
-        old_field.clone()
+    /// Returns the filter of the same join with its inputs swapped, i.e. with
+    /// the sides of all column indices negated.
+    pub fn swap_sides(&self) -> JoinFilter {
+        let column_indices = self
+            .column_indices
+            .iter()
+            .map(|idx| ColumnIndex {
+                index: idx.index,
+                side: idx.side.negate(),
+            })
+            .collect();
+        JoinFilter::new(self.expression.clone(), column_indices, self.schema.clone())
     }
-}
 
-/// Creates a schema for a join operation.
-/// The fields from the left side are first
-pub fn build_join_schema(
-    left: &Schema,
-    right: &Schema,
-    join_type: &JoinType,
-) -> (Schema, Vec<ColumnIndex>) {
-    let (fields, column_indices): (SchemaBuilder, Vec<ColumnIndex>) = match join_type {
-        JoinType::Inner | JoinType::Left | JoinType::Full | JoinType::Right => {
-            let left_fields = left
-                .fields()
-                .iter()
-                .map(|f| output_join_field(f, join_type, true))
-                .enumerate()
-                .map(|(index, f)| {
-                    (
-                        f,
-                        ColumnIndex {
-                            index,
-                            side: JoinSide::Left,
-                        },
-                    )
-                });
+    /// Returns a copy of this filter whose floating point comparisons are
+    /// relaxed by `tolerance`: `a < b` becomes `a < b + tolerance` and `a > b`
+    /// becomes `a > b - tolerance` (likewise for `<=` and `>=`). Comparisons
+    /// between other types are left unchanged.
+    ///
+    /// Since interval analysis works on the filter expression itself, the
+    /// relaxed bounds apply to both filter evaluation and pruning.
+    pub fn with_float_tolerance(&self, tolerance: f64) -> Result<JoinFilter> {
+        if !tolerance.is_finite() || tolerance < 0.0 {
+            return plan_err!(
+                "Float tolerance of a join filter must be a non-negative finite number, got {tolerance}"
+            );
+        }
+        let schema = &self.schema;
+        let expression = self
+            .expression
+            .clone()
+            .transform_up(&|expr| {
+                let Some(binary) = expr.as_any().downcast_ref::<BinaryExpr>() else {
+                    return Ok(Transformed::no(expr));
+                };
+                let shift_op = match binary.op() {
+                    Operator::Lt | Operator::LtEq => Operator::Plus,
+                    Operator::Gt | Operator::GtEq => Operator::Minus,
+                    _ => return Ok(Transformed::no(expr)),
+                };
+                let delta = match (
+                    binary.left().data_type(schema)?,
+                    binary.right().data_type(schema)?,
+                ) {
+                    (DataType::Float32, DataType::Float32) => {
+                        ScalarValue::Float32(Some(tolerance as f32))
+                    }
+                    (DataType::Float64, DataType::Float64) => {
+                        ScalarValue::Float64(Some(tolerance))
+                    }
+                    _ => return Ok(Transformed::no(expr)),
+                };
+                let shifted_right = Arc::new(BinaryExpr::new(
+                    binary.right().clone(),
+                    shift_op,
+                    Arc::new(Literal::new(delta)),
+                ));
+                Ok(Transformed::yes(Arc::new(BinaryExpr::new(
+                    binary.left().clone(),
+                    *binary.op(),
+                    shifted_right,
+                )) as _))
+            })
+            .data()?;
+        Ok(JoinFilter::new(
+            expression,
+            self.column_indices.clone(),
+            self.schema.clone(),
+        ))
+    }
+
+    /// Renders the filter expression as SQL-like text for debugging, with
+    /// the columns of the intermediate batch resolved through the column
+    /// indices: a column backed by column `N` of the left input is rendered
+    /// as `left.colN`, one backed by column `M` of the right input as
+    /// `right.colM`.
+    pub fn to_debug_sql(&self) -> Result<String> {
+        let column_indices = &self.column_indices;
+        let expression = self
+            .expression
+            .clone()
+            .transform_up(&|expr| {
+                let Some(column) = expr.as_any().downcast_ref::<Column>() else {
+                    return Ok(Transformed::no(expr));
+                };
+                let Some(column_index) = column_indices.get(column.index()) else {
+                    return plan_err!(
+                        "Join filter references column {column}, which has no column index"
+                    );
+                };
+                let side = match column_index.side {
+                    JoinSide::Left => "left",
+                    JoinSide::Right => "right",
+                };
+                let name = format!("{side}.col{}", column_index.index);
+                Ok(Transformed::yes(Arc::new(UnKnownColumn::new(&name)) as _))
+            })
+            .data()?;
+        Ok(expression.to_string())
+    }
+
+    /// Parses a filter back from the text rendered by [`Self::to_debug_sql`],
+    /// e.g. to reproduce a reported join filter, resolving the `left.colN` and
+    /// `right.colM` references against the `left_schema` and `right_schema`
+    /// of the join inputs. The intermediate batch holds the referenced left
+    /// columns, then the right ones, in the order of their indices.
+    ///
+    /// Column references, numeric and boolean literals, `NULL`, casts to
+    /// primitive types, `NOT`, `IS [NOT] NULL` and the arithmetic, comparison
+    /// and logical operators are supported. As literals are rendered without
+    /// their type, a literal takes the type of the operand it is combined
+    /// with, and string literals, rendered unquoted, can not be parsed.
+    pub fn from_debug_sql(
+        sql: &str,
+        left_schema: &Schema,
+        right_schema: &Schema,
+    ) -> Result<JoinFilter> {
+        let mut parser = DebugSqlParser {
+            tokens: debug_sql_tokens(sql)?,
+            position: 0,
+        };
+        let parsed = parser.parse_expr(0)?;
+        if let Some(token) = parser.peek() {
+            return plan_err!("Unexpected token {token} in join filter {sql}");
+        }
+
+        let mut column_indices = vec![];
+        parsed.collect_columns(&mut column_indices);
+        column_indices
+            .sort_by_key(|column| (column.side == JoinSide::Right, column.index));
+        column_indices.dedup();
+        let fields = column_indices
+            .iter()
+            .map(|column| {
+                let input_schema = match column.side {
+                    JoinSide::Left => left_schema,
+                    JoinSide::Right => right_schema,
+                };
+                match input_schema.fields().get(column.index) {
+                    Some(field) => Ok(field.as_ref().clone()),
+                    None => plan_err!(
+                        "Join filter references column {} of the {:?} input, which has {} columns",
+                        column.index,
+                        column.side,
+                        input_schema.fields().len()
+                    ),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let schema = Schema::new(fields);
+
+        let expression = parsed.into_physical(&column_indices, &schema, None)?;
+        let data_type = expression.data_type(&schema)?;
+        if data_type != DataType::Boolean {
+            return plan_err!("Join filter {sql} is of type {data_type}, not Boolean");
+        }
+        Ok(JoinFilter::new(expression, column_indices, schema))
+    }
+
+    /// Splits this filter into the conjuncts referencing only columns of
+    /// `side` and a filter made of the remaining conjuncts. The former are
+    /// rewritten to be evaluated directly on the input batches of `side`.
+    /// Either part is `None` if it has no conjuncts.
+    pub fn split_side_conjuncts(
+        &self,
+        side: JoinSide,
+    ) -> Result<(Option<PhysicalExprRef>, Option<JoinFilter>)> {
+        let (side_conjuncts, other_conjuncts): (Vec<_>, Vec<_>) =
+            split_conjunction(&self.expression)
+                .into_iter()
+                .partition(|conjunct| {
+                    let columns = collect_columns(conjunct);
+                    !columns.is_empty()
+                        && columns.iter().all(|column| {
+                            self.column_indices[column.index()].side == side
+                        })
+                });
+        let side_expr = side_conjuncts
+            .into_iter()
+            .map(|conjunct| {
+                conjunct
+                    .clone()
+                    .transform_up(&|expr| {
+                        let Some(column) = expr.as_any().downcast_ref::<Column>() else {
+                            return Ok(Transformed::no(expr));
+                        };
+                        let index = self.column_indices[column.index()].index;
+                        Ok(Transformed::yes(
+                            Arc::new(Column::new(column.name(), index)) as _,
+                        ))
+                    })
+                    .data()
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .reduce(|acc, expr| Arc::new(BinaryExpr::new(acc, Operator::And, expr)));
+        let other_filter = other_conjuncts
+            .into_iter()
+            .cloned()
+            .reduce(|acc, expr| Arc::new(BinaryExpr::new(acc, Operator::And, expr)))
+            .map(|expression| {
+                JoinFilter::new(
+                    expression,
+                    self.column_indices.clone(),
+                    self.schema.clone(),
+                )
+            });
+        Ok((side_expr, other_filter))
+    }
+
+    /// Returns a rough estimate of the per-row cost of evaluating this filter,
+    /// derived from the complexity of its expression tree. Column references
+    /// and literals are free, most operations cost one unit, while casts,
+    /// pattern matching and function calls are weighted higher. The estimate
+    /// is deterministic for a given expression and is only meaningful relative
+    /// to other estimates, e.g. to weigh filter cost against match reduction
+    /// when choosing a join method.
+    pub fn estimated_eval_cost(&self) -> usize {
+        let mut cost = 0;
+        self.expression
+            .apply(&mut |expr| {
+                let any = expr.as_any();
+                cost += if any.is::<Column>() || any.is::<Literal>() {
+                    0
+                } else if let Some(binary) = any.downcast_ref::<BinaryExpr>() {
+                    match binary.op() {
+                        Operator::RegexMatch
+                        | Operator::RegexIMatch
+                        | Operator::RegexNotMatch
+                        | Operator::RegexNotIMatch
+                        | Operator::LikeMatch
+                        | Operator::ILikeMatch
+                        | Operator::NotLikeMatch
+                        | Operator::NotILikeMatch => 4,
+                        _ => 1,
+                    }
+                } else if any.is::<CastExpr>() || any.is::<TryCastExpr>() {
+                    2
+                } else if any.is::<LikeExpr>() || any.is::<ScalarFunctionExpr>() {
+                    4
+                } else if let Some(in_list) = any.downcast_ref::<InListExpr>() {
+                    in_list.list().len().max(1)
+                } else {
+                    1
+                };
+                Ok(TreeNodeRecursion::Continue)
+            })
+            // The visitor never returns an error:
+            .expect("no way to return error during recursion");
+        cost
+    }
+}
+
+/// Expression parsed by [`JoinFilter::from_debug_sql`], before its columns are
+/// resolved and its literals are typed
+#[derive(Debug)]
+enum DebugSqlExpr {
+    Column(ColumnIndex),
+    /// Numeric literal, typed after the operand it is combined with
+    Number(String),
+    Boolean(bool),
+    Null,
+    Cast(Box<DebugSqlExpr>, DataType),
+    Not(Box<DebugSqlExpr>),
+    Negative(Box<DebugSqlExpr>),
+    IsNull(Box<DebugSqlExpr>, bool),
+    Binary(Box<DebugSqlExpr>, Operator, Box<DebugSqlExpr>),
+}
+
+impl DebugSqlExpr {
+    /// Whether the type of the expression depends on its operands
+    fn is_untyped(&self) -> bool {
+        match self {
+            DebugSqlExpr::Number(_) | DebugSqlExpr::Null => true,
+            DebugSqlExpr::Negative(expr) => expr.is_untyped(),
+            _ => false,
+        }
+    }
+
+    /// Appends the columns referenced by the expression to `columns`
+    fn collect_columns(&self, columns: &mut Vec<ColumnIndex>) {
+        match self {
+            DebugSqlExpr::Column(column) => columns.push(column.clone()),
+            DebugSqlExpr::Number(_) | DebugSqlExpr::Boolean(_) | DebugSqlExpr::Null => {}
+            DebugSqlExpr::Cast(expr, _)
+            | DebugSqlExpr::Not(expr)
+            | DebugSqlExpr::Negative(expr)
+            | DebugSqlExpr::IsNull(expr, _) => expr.collect_columns(columns),
+            DebugSqlExpr::Binary(left, _, right) => {
+                left.collect_columns(columns);
+                right.collect_columns(columns);
+            }
+        }
+    }
+
+    /// Converts the expression into a physical expression over the
+    /// intermediate batch of `schema`, made of the `column_indices`. Untyped
+    /// literals take the type `hint`, if any.
+    fn into_physical(
+        self,
+        column_indices: &[ColumnIndex],
+        schema: &Schema,
+        hint: Option<&DataType>,
+    ) -> Result<Arc<dyn PhysicalExpr>> {
+        Ok(match self {
+            DebugSqlExpr::Column(column) => {
+                let index = column_indices
+                    .iter()
+                    .position(|c| *c == column)
+                    .expect("referenced columns are collected");
+                Arc::new(Column::new(schema.field(index).name(), index))
+            }
+            DebugSqlExpr::Number(number) => {
+                let value = match hint {
+                    Some(data_type) => ScalarValue::try_from_string(number, data_type)?,
+                    None => {
+                        ScalarValue::try_from_string(number.clone(), &DataType::Int64)
+                            .or_else(|_| {
+                                ScalarValue::try_from_string(number, &DataType::Float64)
+                            })?
+                    }
+                };
+                Arc::new(Literal::new(value))
+            }
+            DebugSqlExpr::Boolean(value) => {
+                Arc::new(Literal::new(ScalarValue::Boolean(Some(value))))
+            }
+            DebugSqlExpr::Null => {
+                let value = match hint {
+                    Some(data_type) => ScalarValue::try_from(data_type)?,
+                    None => ScalarValue::Null,
+                };
+                Arc::new(Literal::new(value))
+            }
+            DebugSqlExpr::Cast(expr, data_type) => Arc::new(CastExpr::new(
+                expr.into_physical(column_indices, schema, None)?,
+                data_type,
+                None,
+            )),
+            DebugSqlExpr::Not(expr) => not(expr.into_physical(
+                column_indices,
+                schema,
+                Some(&DataType::Boolean),
+            )?)?,
+            DebugSqlExpr::Negative(expr) => Arc::new(NegativeExpr::new(
+                expr.into_physical(column_indices, schema, hint)?,
+            )),
+            DebugSqlExpr::IsNull(expr, negated) => {
+                let expr = expr.into_physical(column_indices, schema, None)?;
+                if negated {
+                    is_not_null(expr)?
+                } else {
+                    is_null(expr)?
+                }
+            }
+            DebugSqlExpr::Binary(left, op, right) => {
+                // The typed operand is converted first, to type the other one:
+                let hint = hint.filter(|_| op.is_numerical_operators());
+                let swapped = left.is_untyped() && !right.is_untyped();
+                let (first, second) = if swapped {
+                    (right, left)
+                } else {
+                    (left, right)
+                };
+                let first = first.into_physical(column_indices, schema, hint)?;
+                let first_type = first.data_type(schema)?;
+                let second =
+                    second.into_physical(column_indices, schema, Some(&first_type))?;
+                let (left, right) = if swapped {
+                    (second, first)
+                } else {
+                    (first, second)
+                };
+                Arc::new(BinaryExpr::new(left, op, right))
+            }
+        })
+    }
+}
+
+/// Splits the text rendered by [`JoinFilter::to_debug_sql`] into tokens. A
+/// minus sign directly followed by a digit starts a negative number, as the
+/// binary minus is rendered surrounded by spaces.
+fn debug_sql_tokens(sql: &str) -> Result<Vec<String>> {
+    let chars = sql.chars().collect::<Vec<_>>();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '.';
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let len = if c.is_whitespace() {
+            i += 1;
+            continue;
+        } else if is_word(c)
+            || (c == '-' && chars.get(i + 1).is_some_and(char::is_ascii_digit))
+        {
+            1 + chars[i + 1..].iter().take_while(|c| is_word(**c)).count()
+        } else if matches!(
+            (c, chars.get(i + 1)),
+            ('<', Some('=')) | ('>', Some('=')) | ('!', Some('='))
+        ) {
+            2
+        } else if "()=<>+-*/%".contains(c) {
+            1
+        } else {
+            return plan_err!("Unexpected character {c:?} in join filter {sql}");
+        };
+        tokens.push(chars[i..i + len].iter().collect());
+        i += len;
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser of the tokens of a join filter rendered by
+/// [`JoinFilter::to_debug_sql`]
+struct DebugSqlParser {
+    tokens: Vec<String>,
+    position: usize,
+}
+
+impl DebugSqlParser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.position).map(String::as_str)
+    }
+
+    fn next_token(&mut self) -> Result<String> {
+        let Some(token) = self.tokens.get(self.position) else {
+            return plan_err!("Unexpected end of join filter");
+        };
+        self.position += 1;
+        Ok(token.clone())
+    }
+
+    /// Consumes the next token if it is `keyword`, ignoring the case
+    fn consume(&mut self, keyword: &str) -> bool {
+        let matched = self
+            .peek()
+            .is_some_and(|token| token.eq_ignore_ascii_case(keyword));
+        if matched {
+            self.position += 1;
+        }
+        matched
+    }
+
+    fn expect(&mut self, keyword: &str) -> Result<()> {
+        let token = self.next_token()?;
+        if !token.eq_ignore_ascii_case(keyword) {
+            return plan_err!("Expected {keyword} in join filter, got {token}");
+        }
+        Ok(())
+    }
+
+    /// Parses the binary operations whose operators have a precedence of at
+    /// least `min_precedence`, associating operators of the same precedence
+    /// to the left
+    fn parse_expr(&mut self, min_precedence: u8) -> Result<DebugSqlExpr> {
+        let mut expr = self.parse_unary()?;
+        loop {
+            // IS [NOT] NULL binds like a comparison:
+            if Operator::Eq.precedence() >= min_precedence && self.consume("IS") {
+                let negated = self.consume("NOT");
+                self.expect("NULL")?;
+                expr = DebugSqlExpr::IsNull(Box::new(expr), negated);
+                continue;
+            }
+            let Some(op) = self.peek().and_then(debug_sql_operator) else {
+                break;
+            };
+            if op.precedence() < min_precedence {
+                break;
+            }
+            self.position += 1;
+            let right = self.parse_expr(op.precedence() + 1)?;
+            expr = DebugSqlExpr::Binary(Box::new(expr), op, Box::new(right));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<DebugSqlExpr> {
+        if self.consume("NOT") {
+            let expr = self.parse_expr(Operator::Eq.precedence())?;
+            return Ok(DebugSqlExpr::Not(Box::new(expr)));
+        }
+        let token = self.next_token()?;
+        if token == "(" {
+            let expr = if self.consume("-") {
+                DebugSqlExpr::Negative(Box::new(self.parse_expr(0)?))
+            } else {
+                self.parse_expr(0)?
+            };
+            self.expect(")")?;
+            return Ok(expr);
+        }
+        if token.eq_ignore_ascii_case("CAST") {
+            self.expect("(")?;
+            let expr = self.parse_expr(0)?;
+            self.expect("AS")?;
+            let data_type = debug_sql_data_type(&self.next_token()?)?;
+            self.expect(")")?;
+            return Ok(DebugSqlExpr::Cast(Box::new(expr), data_type));
+        }
+        if token.eq_ignore_ascii_case("true") || token.eq_ignore_ascii_case("false") {
+            return Ok(DebugSqlExpr::Boolean(token.eq_ignore_ascii_case("true")));
+        }
+        if token.eq_ignore_ascii_case("NULL") {
+            return Ok(DebugSqlExpr::Null);
+        }
+        if token.starts_with(|c: char| c == '-' || c.is_ascii_digit()) {
+            return Ok(DebugSqlExpr::Number(token));
+        }
+        let column = [("left.col", JoinSide::Left), ("right.col", JoinSide::Right)]
+            .into_iter()
+            .find_map(|(prefix, side)| {
+                let index = token.strip_prefix(prefix)?.parse().ok()?;
+                Some(ColumnIndex { index, side })
+            });
+        match column {
+            Some(column) => Ok(DebugSqlExpr::Column(column)),
+            None => plan_err!("Unexpected token {token} in join filter"),
+        }
+    }
+}
+
+/// Returns the binary operator rendered as `token`, if any
+fn debug_sql_operator(token: &str) -> Option<Operator> {
+    Some(match token.to_ascii_uppercase().as_str() {
+        "=" => Operator::Eq,
+        "!=" => Operator::NotEq,
+        "<" => Operator::Lt,
+        "<=" => Operator::LtEq,
+        ">" => Operator::Gt,
+        ">=" => Operator::GtEq,
+        "+" => Operator::Plus,
+        "-" => Operator::Minus,
+        "*" => Operator::Multiply,
+        "/" => Operator::Divide,
+        "%" => Operator::Modulo,
+        "AND" => Operator::And,
+        "OR" => Operator::Or,
+        _ => return None,
+    })
+}
+
+/// Returns the primitive type rendered as `name` in a cast
+fn debug_sql_data_type(name: &str) -> Result<DataType> {
+    Ok(match name {
+        "Boolean" => DataType::Boolean,
+        "Int8" => DataType::Int8,
+        "Int16" => DataType::Int16,
+        "Int32" => DataType::Int32,
+        "Int64" => DataType::Int64,
+        "UInt8" => DataType::UInt8,
+        "UInt16" => DataType::UInt16,
+        "UInt32" => DataType::UInt32,
+        "UInt64" => DataType::UInt64,
+        "Float32" => DataType::Float32,
+        "Float64" => DataType::Float64,
+        "Utf8" => DataType::Utf8,
+        "LargeUtf8" => DataType::LargeUtf8,
+        "Date32" => DataType::Date32,
+        "Date64" => DataType::Date64,
+        _ => return plan_err!("Unsupported cast to {name} in join filter"),
+    })
+}
+
+/// Returns the output field given the input field. Outer joins may
+/// insert nulls even if the input was not null. The field keeps its metadata,
+/// also if it is made nullable.
+///
+fn output_join_field(old_field: &Field, join_type: &JoinType, is_left: bool) -> Field {
+    let force_nullable = match join_type {
+        JoinType::Inner => false,
+        JoinType::Left => !is_left, // right input is padded with nulls
+        JoinType::Right => is_left, // left input is padded with nulls
+        JoinType::Full => true,     // both inputs can be padded with nulls
+        JoinType::LeftSemi => false, // doesn't introduce nulls
+        JoinType::RightSemi => false, // doesn't introduce nulls
+        JoinType::LeftAnti => false, // doesn't introduce nulls (or can it??)
+        JoinType::RightAnti => false, // doesn't introduce nulls (or can it??)
+        JoinType::LeftMark => false, // doesn't introduce nulls
+        JoinType::RightMark => false, // doesn't introduce nulls
+    };
+
+    if force_nullable {
+        old_field.clone().with_nullable(true)
+    } else {
+        old_field.clone()
+    }
+}
+
+/// Name of the column of a [`JoinType::LeftMark`] or [`JoinType::RightMark`]
+/// join telling whether a row has a match in the other side of the join
+pub const MARK_COLUMN: &str = "mark";
+
+/// Creates a schema for a join operation.
+/// The fields from the left side are first, every output field carrying the
+/// metadata of the input field it is taken from.
+///
+/// The [`MARK_COLUMN`] of mark joins follows the fields of the marked side. It
+/// is computed from the matches of a row rather than taken from an input, so
+/// it has no [`ColumnIndex`], i.e. the returned column indices only cover the
+/// fields before it.
+pub fn build_join_schema(
+    left: &Schema,
+    right: &Schema,
+    join_type: &JoinType,
+) -> (Schema, Vec<ColumnIndex>) {
+    let (mut fields, column_indices): (SchemaBuilder, Vec<ColumnIndex>) = match join_type
+    {
+        JoinType::Inner | JoinType::Left | JoinType::Full | JoinType::Right => {
+            let left_fields = left
+                .fields()
+                .iter()
+                .map(|f| output_join_field(f, join_type, true))
+                .enumerate()
+                .map(|(index, f)| {
+                    (
+                        f,
+                        ColumnIndex {
+                            index,
+                            side: JoinSide::Left,
+                        },
+                    )
+                });
             let right_fields = right
                 .fields()
                 .iter()
@@ -693,7 +2063,7 @@ pub fn build_join_schema(
             // left then right
             left_fields.chain(right_fields).unzip()
         }
-        JoinType::LeftSemi | JoinType::LeftAnti => left
+        JoinType::LeftSemi | JoinType::LeftAnti | JoinType::LeftMark => left
             .fields()
             .iter()
             .cloned()
@@ -708,24 +2078,982 @@ pub fn build_join_schema(
                 )
             })
             .unzip(),
-        JoinType::RightSemi | JoinType::RightAnti => right
+        JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => right
+            .fields()
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(index, f)| {
+                (
+                    f,
+                    ColumnIndex {
+                        index,
+                        side: JoinSide::Right,
+                    },
+                )
+            })
+            .unzip(),
+    };
+
+    if matches!(join_type, JoinType::LeftMark | JoinType::RightMark) {
+        fields.push(Field::new(MARK_COLUMN, DataType::Boolean, true));
+    }
+
+    (fields.finish(), column_indices)
+}
+
+/// Set operations that can be evaluated as a join over all the columns of
+/// both inputs, see [`SymmetricHashJoinExec::try_new_set_operation`].
+///
+/// [`SymmetricHashJoinExec::try_new_set_operation`]: crate::joins::SymmetricHashJoinExec::try_new_set_operation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SetOperation {
+    /// Rows of the left input that also appear in the right input
+    Intersect,
+    /// Rows of the left input that do not appear in the right input
+    Except,
+}
+
+/// Returns whether a [`SymmetricHashJoinExec`] of type `join_type`, with the
+/// join `filter`, on inputs with the given schemas and orderings, can execute
+/// with bounded memory, i.e. whether the buffers of both inputs are pruned as
+/// the inputs advance.
+///
+/// This requires a range filter that refers to the leading sort expression of
+/// each input, and ordering directions under which the filter bounds the
+/// buffered rows of both sides from below (or above, for descending inputs).
+/// Both buffers are kept for every join type, so `join_type` does not change
+/// the outcome at the moment; planners pass it so that the whole configuration
+/// is checked.
+///
+/// [`SymmetricHashJoinExec`]: crate::joins::SymmetricHashJoinExec
+pub fn is_streamable(
+    _join_type: &JoinType,
+    filter: Option<&JoinFilter>,
+    left_schema: &SchemaRef,
+    right_schema: &SchemaRef,
+    left_order: Option<&[PhysicalSortExpr]>,
+    right_order: Option<&[PhysicalSortExpr]>,
+) -> Result<bool> {
+    let (Some(filter), Some(left_sort_expr), Some(right_sort_expr)) = (
+        filter,
+        left_order.and_then(|order| order.first()),
+        right_order.and_then(|order| order.first()),
+    ) else {
+        return Ok(false);
+    };
+    let (Some(left_sorted_filter_expr), Some(right_sorted_filter_expr)) = (
+        build_filter_input_order(JoinSide::Left, filter, left_schema, left_sort_expr)?,
+        build_filter_input_order(JoinSide::Right, filter, right_schema, right_sort_expr)?,
+    ) else {
+        return Ok(false);
+    };
+    let mut sorted_exprs = [left_sorted_filter_expr, right_sorted_filter_expr];
+    for (build, probe) in [(0, 1), (1, 0)] {
+        // Use a fresh graph for every side, as propagation narrows its nodes:
+        let mut graph =
+            ExprIntervalGraph::try_new(filter.expression().clone(), filter.schema())?;
+        update_sorted_exprs_with_node_indices(&mut graph, &mut sorted_exprs);
+        if !is_build_side_prunable(
+            &mut graph,
+            &sorted_exprs[build],
+            &sorted_exprs[probe],
+            filter.schema(),
+        )? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Executes `join` and materializes its output into a [`MemoryExec`], so that
+/// the result can be cached and planned against again without recomputing it.
+///
+/// Every output partition of `join` becomes a partition of the [`MemoryExec`],
+/// which keeps the output ordering of `join`. The partitioning scheme itself,
+/// e.g. hash partitioning on the join keys, is not preserved, as a
+/// [`MemoryExec`] can only report an unknown partitioning.
+pub async fn materialize_join_output(
+    join: Arc<dyn ExecutionPlan>,
+    context: Arc<TaskContext>,
+) -> Result<MemoryExec> {
+    let schema = join.schema();
+    let sort_information = join
+        .output_ordering()
+        .map(|ordering| vec![ordering.to_vec()])
+        .unwrap_or_default();
+    let partitions = collect_partitioned(join, context).await?;
+    Ok(MemoryExec::try_new(&partitions, schema, None)?
+        .with_sort_information(sort_information))
+}
+
+/// A join operator, see [`support_matrix`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JoinOperator {
+    /// [`HashJoinExec`](crate::joins::HashJoinExec)
+    Hash,
+    /// [`SymmetricHashJoinExec`](crate::joins::SymmetricHashJoinExec)
+    SymmetricHash,
+    /// [`SortMergeJoinExec`](crate::joins::SortMergeJoinExec)
+    SortMerge,
+    /// [`NestedLoopJoinExec`](crate::joins::NestedLoopJoinExec)
+    NestedLoop,
+    /// [`CrossJoinExec`](crate::joins::CrossJoinExec)
+    Cross,
+}
+
+/// A combination of join settings supported by a join operator, see
+/// [`support_matrix`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JoinSupport {
+    /// The join operator
+    pub operator: JoinOperator,
+    /// The type of the join
+    pub join_type: JoinType,
+    /// The partition mode of a hash join, `None` for the other operators,
+    /// which do not take a [`PartitionMode`]
+    pub partition_mode: Option<PartitionMode>,
+    /// Whether the join has a [`JoinFilter`]
+    pub has_filter: bool,
+    /// Whether the join keeps streaming its output if an input is unbounded,
+    /// rather than breaking the pipeline
+    pub is_streaming: bool,
+}
+
+/// All join types, in declaration order
+const JOIN_TYPES: [JoinType; 8] = [
+    JoinType::Inner,
+    JoinType::Left,
+    JoinType::Right,
+    JoinType::Full,
+    JoinType::LeftSemi,
+    JoinType::RightSemi,
+    JoinType::LeftAnti,
+    JoinType::RightAnti,
+];
+
+/// Returns the combinations of join type, partition mode, join filter and
+/// unbounded inputs each join operator supports, so that plans can be
+/// validated before the operators are constructed.
+///
+/// A combination is listed if the operator accepts it on construction and can
+/// execute it; e.g. [`PartitionMode::Auto`] is accepted by
+/// [`HashJoinExec::try_new`](crate::joins::HashJoinExec::try_new) but has to
+/// be resolved by the optimizer before execution, so it is not listed. A
+/// streaming combination is listed along with the same non-streaming one, as
+/// operators supporting unbounded inputs also support bounded ones. A hash
+/// join streams if only its probe side is unbounded, and it does not emit
+/// unmatched build rows at the end.
+pub fn support_matrix() -> Vec<JoinSupport> {
+    let mut matrix = vec![];
+    let mut add = |operator, join_type, partition_mode, has_filter, is_streaming| {
+        matrix.push(JoinSupport {
+            operator,
+            join_type,
+            partition_mode,
+            has_filter,
+            is_streaming,
+        })
+    };
+    for join_type in JOIN_TYPES {
+        let emits_unmatched_build_rows = matches!(
+            join_type,
+            JoinType::Left | JoinType::Full | JoinType::LeftAnti | JoinType::LeftSemi
+        );
+        for has_filter in [false, true] {
+            for partition_mode in [PartitionMode::Partitioned, PartitionMode::CollectLeft]
+            {
+                add(
+                    JoinOperator::Hash,
+                    join_type,
+                    Some(partition_mode),
+                    has_filter,
+                    false,
+                );
+                if !emits_unmatched_build_rows {
+                    add(
+                        JoinOperator::Hash,
+                        join_type,
+                        Some(partition_mode),
+                        has_filter,
+                        true,
+                    );
+                }
+            }
+            for is_streaming in [false, true] {
+                add(
+                    JoinOperator::SymmetricHash,
+                    join_type,
+                    None,
+                    has_filter,
+                    is_streaming,
+                );
+                if join_type != JoinType::RightSemi {
+                    add(
+                        JoinOperator::SortMerge,
+                        join_type,
+                        None,
+                        has_filter,
+                        is_streaming,
+                    );
+                }
+            }
+            add(JoinOperator::NestedLoop, join_type, None, has_filter, false);
+        }
+    }
+    add(JoinOperator::Cross, JoinType::Inner, None, false, false);
+    matrix
+}
+
+/// Wraps the inputs of an equi-join in [`FilterExec`]s that discard rows
+/// whose join keys are null, for every side on which such rows can never
+/// contribute to the join output:
+///
+/// - both sides of inner and semi joins,
+/// - the non-preserved side of left, right and anti joins.
+///
+/// Inputs are returned unchanged when `null_equals_null` is true (null keys
+/// do match then), for full joins, and when none of the keys on a side are
+/// nullable. Since the equality condition has to hold before any
+/// [`JoinFilter`] is evaluated, this is valid regardless of the join filter.
+pub fn filter_null_join_keys(
+    left: Arc<dyn ExecutionPlan>,
+    right: Arc<dyn ExecutionPlan>,
+    on: JoinOnRef,
+    join_type: &JoinType,
+    null_equals_null: bool,
+) -> Result<(Arc<dyn ExecutionPlan>, Arc<dyn ExecutionPlan>)> {
+    if null_equals_null {
+        return Ok((left, right));
+    }
+    let (filter_left, filter_right) = match join_type {
+        JoinType::Inner | JoinType::LeftSemi | JoinType::RightSemi => (true, true),
+        JoinType::Left | JoinType::LeftAnti => (false, true),
+        JoinType::Right | JoinType::RightAnti => (true, false),
+        // The null keys of the other side make the marks of the unmatched
+        // rows unknown:
+        JoinType::Full | JoinType::LeftMark | JoinType::RightMark => (false, false),
+    };
+    let left = if filter_left {
+        filter_null_keys(left, on.iter().map(|(l, _)| l))?
+    } else {
+        left
+    };
+    let right = if filter_right {
+        filter_null_keys(right, on.iter().map(|(_, r)| r))?
+    } else {
+        right
+    };
+    Ok((left, right))
+}
+
+/// Adds an `IS NOT NULL` filter for the nullable `keys` on top of `input`.
+fn filter_null_keys<'a>(
+    input: Arc<dyn ExecutionPlan>,
+    keys: impl Iterator<Item = &'a PhysicalExprRef>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let schema = input.schema();
+    let mut predicate: Option<Arc<dyn PhysicalExpr>> = None;
+    for key in keys {
+        if !key.nullable(&schema)? {
+            continue;
+        }
+        let not_null = is_not_null(key.clone())?;
+        predicate = Some(match predicate {
+            Some(predicate) => {
+                Arc::new(BinaryExpr::new(predicate, Operator::And, not_null))
+            }
+            None => not_null,
+        });
+    }
+    match predicate {
+        Some(predicate) => Ok(Arc::new(FilterExec::try_new(predicate, input)?)),
+        None => Ok(input),
+    }
+}
+
+/// Chains the output batches of several partitions into a single stream,
+/// partition by partition and preserving the order within each partition.
+///
+/// Batches are re-emitted as they are (their columns are reference counted),
+/// so no batch data is copied. Batches are yielded lazily; a batch whose
+/// schema differs from `schema` results in an error when it is reached.
+pub fn merge_partition_outputs(
+    schema: SchemaRef,
+    batches_per_partition: Vec<Vec<RecordBatch>>,
+) -> SendableRecordBatchStream {
+    let stream_schema = schema.clone();
+    let batches = batches_per_partition
+        .into_iter()
+        .flatten()
+        .map(move |batch| {
+            if batch.schema() == schema {
+                Ok(batch)
+            } else {
+                internal_err!(
+                    "Partition outputs to merge must share the schema {schema:?}, got {:?}",
+                    batch.schema()
+                )
+            }
+        });
+    Box::pin(RecordBatchStreamAdapter::new(
+        stream_schema,
+        futures::stream::iter(batches),
+    ))
+}
+
+/// Wraps a stream of batches, e.g. the output of a join, so that its next
+/// batch can be inspected with [`Self::peek`] without consuming it, for
+/// example to choose a buffering strategy based on its row count. At most one
+/// batch is buffered.
+///
+/// Errors are peeked like batches and returned by the next poll of the
+/// stream. Once the input has ended it is not polled again.
+pub struct PeekableBatchStream {
+    /// The wrapped stream
+    input: SendableRecordBatchStream,
+    /// Schema of the batches of `input`
+    schema: SchemaRef,
+    /// The batch, or error, polled from `input` but not yet returned
+    peeked: Option<Result<RecordBatch>>,
+    /// Whether `input` has ended
+    done: bool,
+}
+
+impl PeekableBatchStream {
+    /// Wraps `input`
+    pub fn new(input: SendableRecordBatchStream) -> Self {
+        Self {
+            schema: input.schema(),
+            input,
+            peeked: None,
+            done: false,
+        }
+    }
+
+    /// Polls for the next batch without consuming it: ready with the batch, or
+    /// error, the stream yields next, or with none once it has ended
+    pub fn poll_peek(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<&Result<RecordBatch>>> {
+        if self.peeked.is_none() && !self.done {
+            match ready!(self.input.poll_next_unpin(cx)) {
+                Some(item) => self.peeked = Some(item),
+                None => self.done = true,
+            }
+        }
+        Poll::Ready(self.peeked.as_ref())
+    }
+
+    /// Returns the batch, or error, the stream yields next without consuming
+    /// it, or none once the stream has ended, see [`Self::poll_peek`]
+    pub async fn peek(&mut self) -> Option<&Result<RecordBatch>> {
+        futures::future::poll_fn(|cx| self.poll_peek(cx).map(|_| ())).await;
+        self.peeked.as_ref()
+    }
+}
+
+impl Stream for PeekableBatchStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        if let Some(item) = self.peeked.take() {
+            return Poll::Ready(Some(item));
+        }
+        if self.done {
+            return Poll::Ready(None);
+        }
+        let item = ready!(self.input.poll_next_unpin(cx));
+        self.done = item.is_none();
+        Poll::Ready(item)
+    }
+}
+
+impl RecordBatchStream for PeekableBatchStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// State shared by the output partitions of one execution of a join. It is
+/// created by the first partition executed, and replaced by a new state once a
+/// partition is executed again, which starts the next execution.
+#[derive(Debug)]
+pub(crate) struct ExecutionState<T> {
+    /// The partitions executed so far and the state of the current execution
+    current: Mutex<Option<(HashSet<usize>, Arc<T>)>>,
+}
+
+impl<T> Default for ExecutionState<T> {
+    fn default() -> Self {
+        Self {
+            current: Mutex::new(None),
+        }
+    }
+}
+
+impl<T> ExecutionState<T> {
+    /// Returns the state of the current execution for `partition`, or the
+    /// state created by `f` for a new execution if `partition` was already
+    /// executed in the current one.
+    pub(crate) fn get_or_init(&self, partition: usize, f: impl FnOnce() -> T) -> Arc<T> {
+        let mut current = self.current.lock();
+        if let Some((partitions, state)) = current.as_mut() {
+            if partitions.insert(partition) {
+                return state.clone();
+            }
+        }
+        let state = Arc::new(f());
+        *current = Some((HashSet::from([partition]), state.clone()));
+        state
+    }
+}
+
+/// Limit on the number of rows a join may produce, shared by all of its output
+/// partitions, see for example [`HashJoinExec::with_max_output_rows`]. The
+/// rows are counted per execution of the join, see [`ExecutionState`].
+///
+/// [`HashJoinExec::with_max_output_rows`]: crate::joins::HashJoinExec::with_max_output_rows
+#[derive(Debug)]
+pub(crate) struct OutputRowLimit {
+    /// Maximum number of output rows
+    max_rows: usize,
+    /// Number of rows produced so far by all output partitions
+    produced_rows: ExecutionState<AtomicUsize>,
+}
+
+impl OutputRowLimit {
+    pub(crate) fn new(max_rows: usize) -> Self {
+        Self {
+            max_rows,
+            produced_rows: ExecutionState::default(),
+        }
+    }
+
+    /// Maximum number of output rows
+    pub(crate) fn max_rows(&self) -> usize {
+        self.max_rows
+    }
+
+    /// Wraps the output `stream` of `partition` so that it fails with
+    /// [`DataFusionError::ResourcesExhausted`] once the rows produced by all
+    /// streams of the same execution exceed the limit.
+    pub(crate) fn limit_stream(
+        &self,
+        stream: SendableRecordBatchStream,
+        partition: usize,
+    ) -> SendableRecordBatchStream {
+        let schema = stream.schema();
+        let max_rows = self.max_rows;
+        let produced_rows = self
+            .produced_rows
+            .get_or_init(partition, || AtomicUsize::new(0));
+        let stream = stream.map(move |batch| {
+            let batch = batch?;
+            let num_rows = batch.num_rows();
+            let produced =
+                produced_rows.fetch_add(num_rows, Ordering::Relaxed) + num_rows;
+            if produced > max_rows {
+                return Err(DataFusionError::ResourcesExhausted(format!(
+                    "Join produced more than the maximum of {max_rows} output rows"
+                )));
+            }
+            Ok(batch)
+        });
+        Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+    }
+}
+
+/// Limit on the number of output partitions of a join that produce a batch at
+/// the same time, see for example [`HashJoinExec::with_max_concurrency`]. A
+/// partition takes a slot while it produces its next batch and returns it
+/// once the batch is produced, so consumers that wait for a batch of every
+/// partition, like merges, make progress even with fewer slots than
+/// partitions. The slots are shared by the partitions of an execution of the
+/// join, see [`ExecutionState`].
+///
+/// [`HashJoinExec::with_max_concurrency`]: crate::joins::HashJoinExec::with_max_concurrency
+#[derive(Debug)]
+pub(crate) struct ConcurrencyLimit {
+    /// Maximum number of partitions producing a batch at once
+    max_concurrency: usize,
+    /// Slots of the partitions producing a batch in the current execution
+    slots: ExecutionState<ConcurrencySlots>,
+}
+
+/// Slots of a [`ConcurrencyLimit`] for an execution of a join
+#[derive(Debug)]
+struct ConcurrencySlots {
+    /// Slots of the partitions producing a batch
+    semaphore: Arc<Semaphore>,
+    /// Highest number of partitions that produced a batch at once
+    peak_concurrency: metrics::Gauge,
+}
+
+impl ConcurrencyLimit {
+    pub(crate) fn new(max_concurrency: usize) -> Self {
+        Self {
+            max_concurrency,
+            slots: ExecutionState::default(),
+        }
+    }
+
+    /// Maximum number of partitions producing a batch at once
+    pub(crate) fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    /// Wraps the output `stream` of `partition` so that it waits for a free
+    /// slot before producing each of its batches. The first partition of an
+    /// execution registers the `peak_concurrent_partitions` metric in
+    /// `metrics`.
+    pub(crate) fn limit_stream(
+        &self,
+        stream: SendableRecordBatchStream,
+        partition: usize,
+        metrics: &ExecutionPlanMetricsSet,
+    ) -> SendableRecordBatchStream {
+        let schema = stream.schema();
+        let max_concurrency = self.max_concurrency;
+        let slots = self.slots.get_or_init(partition, || ConcurrencySlots {
+            semaphore: Arc::new(Semaphore::new(max_concurrency)),
+            peak_concurrency: MetricBuilder::new(metrics)
+                .global_gauge("peak_concurrent_partitions"),
+        });
+        let stream = futures::stream::unfold(
+            (stream, slots),
+            move |(mut stream, slots)| async move {
+                // The semaphore is never closed:
+                let slot = slots.semaphore.clone().acquire_owned().await.ok()?;
+                slots
+                    .peak_concurrency
+                    .set_max(max_concurrency - slots.semaphore.available_permits());
+                let batch = stream.next().await;
+                drop(slot);
+                batch.map(|batch| (batch, (stream, slots)))
+            },
+        );
+        Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+    }
+}
+
+/// Applies the concurrency `limit` of a join with `metrics` to the output
+/// `stream` of `partition`, if any.
+pub(crate) fn limit_concurrency(
+    stream: SendableRecordBatchStream,
+    limit: Option<&ConcurrencyLimit>,
+    partition: usize,
+    metrics: &ExecutionPlanMetricsSet,
+) -> SendableRecordBatchStream {
+    match limit {
+        Some(limit) => limit.limit_stream(stream, partition, metrics),
+        None => stream,
+    }
+}
+
+/// Bounded sample of the matched join key values of a join and how often each
+/// of them matched, shared by all of its output partitions, see
+/// [`HashJoinExec::with_trace_matched_keys`]. The first `max_keys` distinct
+/// key values that match are traced; matches of other values are not
+/// counted, so the sample never grows beyond `max_keys` entries.
+///
+/// [`HashJoinExec::with_trace_matched_keys`]: crate::joins::HashJoinExec::with_trace_matched_keys
+#[derive(Debug, Clone)]
+pub struct MatchedKeysTrace {
+    /// Maximum number of distinct key values traced
+    max_keys: usize,
+    /// Number of matches of every traced key value
+    matches: Arc<Mutex<HashMap<Vec<ScalarValue>, usize>>>,
+}
+
+impl MatchedKeysTrace {
+    pub(crate) fn new(max_keys: usize) -> Self {
+        Self {
+            max_keys,
+            matches: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Maximum number of distinct key values traced
+    pub fn max_keys(&self) -> usize {
+        self.max_keys
+    }
+
+    /// Counts a match for the values of the join `keys` at every index of
+    /// `indices`, unless the sample is full and they are not traced yet
+    pub(crate) fn record(&self, keys: &[ArrayRef], indices: &UInt32Array) -> Result<()> {
+        let mut matches = self.matches.lock();
+        for index in indices.values() {
+            let key = keys
+                .iter()
+                .map(|values| ScalarValue::try_from_array(values, *index as usize))
+                .collect::<Result<Vec<_>>>()?;
+            if let Some(count) = matches.get_mut(&key) {
+                *count += 1;
+            } else if matches.len() < self.max_keys {
+                matches.insert(key, 1);
+            }
+        }
+        Ok(())
+    }
+
+    /// The traced key values and their number of matches, ordered by the key
+    /// values
+    pub fn sample(&self) -> Vec<(Vec<ScalarValue>, usize)> {
+        let mut sample = self
+            .matches
+            .lock()
+            .iter()
+            .map(|(key, count)| (key.clone(), *count))
+            .collect::<Vec<_>>();
+        sample.sort_by(|(a, _), (b, _)| {
+            a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        sample
+    }
+}
+
+/// Applies the output row `limit` of a join to the output `stream` of
+/// `partition`, if any.
+pub(crate) fn limit_output_rows(
+    stream: SendableRecordBatchStream,
+    limit: Option<&OutputRowLimit>,
+    partition: usize,
+) -> SendableRecordBatchStream {
+    match limit {
+        Some(limit) => limit.limit_stream(stream, partition),
+        None => stream,
+    }
+}
+
+/// Sorts the output `stream` of `partition` of a join by the ordering of
+/// `output_row_order`, keeping only as many rows as its fetch if given, see for
+/// example [`HashJoinExec::with_output_row_order_by`]. With a fetch, the rows
+/// are kept in a bounded heap as they arrive; otherwise all of them are
+/// buffered and sorted once `stream` ends. The heap emits batches of
+/// `batch_size` rows.
+///
+/// [`HashJoinExec::with_output_row_order_by`]: crate::joins::HashJoinExec::with_output_row_order_by
+pub(crate) fn sort_output_rows(
+    mut stream: SendableRecordBatchStream,
+    output_row_order: Option<&(LexOrdering, Option<usize>)>,
+    partition: usize,
+    batch_size: usize,
+    context: &TaskContext,
+) -> Result<SendableRecordBatchStream> {
+    let Some((ordering, fetch)) = output_row_order else {
+        return Ok(stream);
+    };
+    let schema = stream.schema();
+    let sorted = match fetch {
+        Some(fetch) => {
+            // The join reports its output rows itself, so the metrics of the
+            // heap are not kept
+            let mut topk = TopK::try_new(
+                partition,
+                schema.clone(),
+                ordering.clone(),
+                *fetch,
+                batch_size,
+                context.runtime_env(),
+                &ExecutionPlanMetricsSet::new(),
+                partition,
+            )?;
+            futures::stream::once(async move {
+                while let Some(batch) = stream.next().await {
+                    topk.insert_batch(batch?)?;
+                }
+                topk.emit()
+            })
+            .try_flatten()
+            .boxed()
+        }
+        None => {
+            let ordering = ordering.clone();
+            let output_schema = schema.clone();
+            futures::stream::once(async move {
+                let batches = stream.try_collect::<Vec<_>>().await?;
+                let batch = compute::concat_batches(&output_schema, &batches)?;
+                sort_batch(&batch, &ordering, None)
+            })
+            .boxed()
+        }
+    };
+    Ok(Box::pin(RecordBatchStreamAdapter::new(schema, sorted)))
+}
+
+/// Drops the zero-row batches from the output `stream` of a join if `skip` is
+/// set, see for example [`HashJoinExec::with_skip_empty_output_batches`].
+/// Empty batches are skipped by polling the next batch right away, so that
+/// no empty batch is ever returned. Punctuation batches (see
+/// [`is_punctuation`]) are kept, as they carry progress rather than data.
+///
+/// [`HashJoinExec::with_skip_empty_output_batches`]: crate::joins::HashJoinExec::with_skip_empty_output_batches
+pub(crate) fn skip_empty_batches(
+    stream: SendableRecordBatchStream,
+    skip: bool,
+) -> SendableRecordBatchStream {
+    if !skip {
+        return stream;
+    }
+    let schema = stream.schema();
+    let stream = stream.try_filter(|batch| {
+        futures::future::ready(batch.num_rows() > 0 || is_punctuation(batch))
+    });
+    Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+}
+
+/// Evaluates `output_exprs`, if any, on the batches of join columns produced by
+/// `stream`, emitting batches of `schema` instead, see for example
+/// [`HashJoinExec::with_output_exprs`].
+///
+/// [`HashJoinExec::with_output_exprs`]: crate::joins::HashJoinExec::with_output_exprs
+pub(crate) fn evaluate_output_exprs(
+    stream: SendableRecordBatchStream,
+    output_exprs: Option<&[(PhysicalExprRef, String)]>,
+    schema: SchemaRef,
+) -> SendableRecordBatchStream {
+    let Some(output_exprs) = output_exprs else {
+        return stream;
+    };
+    let exprs = output_exprs
+        .iter()
+        .map(|(expr, _)| expr.clone())
+        .collect::<Vec<_>>();
+    let output_schema = schema.clone();
+    let stream = stream.map(move |batch| {
+        let batch = batch?;
+        let num_rows = batch.num_rows();
+        let columns = exprs
+            .iter()
+            .map(|expr| expr.evaluate(&batch)?.into_array(num_rows))
+            .collect::<Result<Vec<_>>>()?;
+        let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+        Ok(RecordBatch::try_new_with_options(
+            output_schema.clone(),
+            columns,
+            &options,
+        )?)
+    });
+    Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+}
+
+/// Returns the indices of the fields of `schema` named `names`, in the order
+/// of `names`, to emit the output columns of a join in a caller-specified
+/// order. Fails if a name does not occur in `schema` exactly once, or if it is
+/// requested more than once.
+pub fn output_order_indices(schema: &Schema, names: &[&str]) -> Result<Vec<usize>> {
+    let mut indices = Vec::with_capacity(names.len());
+    for name in names {
+        let mut matches = schema
             .fields()
             .iter()
-            .cloned()
             .enumerate()
-            .map(|(index, f)| {
-                (
-                    f,
-                    ColumnIndex {
-                        index,
-                        side: JoinSide::Right,
-                    },
-                )
+            .filter(|(_, field)| field.name() == name)
+            .map(|(index, _)| index);
+        let index = match (matches.next(), matches.next()) {
+            (Some(index), None) => index,
+            (None, _) => {
+                return plan_err!("Output column {name} is not in the join schema")
+            }
+            (Some(_), Some(_)) => {
+                return plan_err!("Output column {name} is ambiguous in the join schema")
+            }
+        };
+        if indices.contains(&index) {
+            return plan_err!("Output column {name} is requested more than once");
+        }
+        indices.push(index);
+    }
+    Ok(indices)
+}
+
+/// Builds the schema of the columns computed by `output_exprs` on batches of
+/// `input_schema`. Fails if an expression references a column that is not in
+/// `input_schema`. Columns passed through as they are keep the metadata of
+/// their input field.
+pub(crate) fn output_exprs_schema(
+    output_exprs: &[(PhysicalExprRef, String)],
+    input_schema: &Schema,
+) -> Result<Schema> {
+    let fields = output_exprs
+        .iter()
+        .map(|(expr, name)| {
+            for column in collect_columns(expr) {
+                let is_valid = input_schema
+                    .fields()
+                    .get(column.index())
+                    .is_some_and(|field| field.name() == column.name());
+                if !is_valid {
+                    return plan_err!(
+                        "Output expression {expr} references column {column}, which is not in the join schema"
+                    );
+                }
+            }
+            let field = Field::new(
+                name,
+                expr.data_type(input_schema)?,
+                expr.nullable(input_schema)?,
+            );
+            Ok(match expr.as_any().downcast_ref::<Column>() {
+                Some(column) => field
+                    .with_metadata(input_schema.field(column.index()).metadata().clone()),
+                None => field,
             })
-            .unzip(),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Schema::new_with_metadata(
+        fields,
+        input_schema.metadata().clone(),
+    ))
+}
+
+/// Schema metadata key marking a punctuation batch, see [`punctuation_batch`]
+pub const PUNCTUATION_METADATA_KEY: &str = "datafusion.join.punctuation";
+
+/// Schema metadata key of the watermark of the left input in a punctuation
+/// batch
+pub const LEFT_WATERMARK_METADATA_KEY: &str = "datafusion.join.watermark.left";
+
+/// Schema metadata key of the watermark of the right input in a punctuation
+/// batch
+pub const RIGHT_WATERMARK_METADATA_KEY: &str = "datafusion.join.watermark.right";
+
+/// Creates a punctuation batch: a zero-row batch emitted by a streaming join
+/// to signal progress downstream while it produces no data rows, see
+/// [`SymmetricHashJoinExec::with_forward_watermarks`].
+///
+/// The batch has the fields of `schema`, and its schema metadata additionally
+/// marks it as punctuation and carries the watermarks of the left and right
+/// inputs known so far, formatted as strings. Operators unaware of
+/// punctuation treat it as an ordinary empty batch.
+///
+/// [`SymmetricHashJoinExec::with_forward_watermarks`]: crate::joins::SymmetricHashJoinExec::with_forward_watermarks
+pub fn punctuation_batch(
+    schema: &SchemaRef,
+    left_watermark: Option<&ScalarValue>,
+    right_watermark: Option<&ScalarValue>,
+) -> Result<RecordBatch> {
+    let mut metadata = schema.metadata().clone();
+    metadata.insert(PUNCTUATION_METADATA_KEY.to_string(), "true".to_string());
+    for (key, watermark) in [
+        (LEFT_WATERMARK_METADATA_KEY, left_watermark),
+        (RIGHT_WATERMARK_METADATA_KEY, right_watermark),
+    ] {
+        if let Some(watermark) = watermark {
+            metadata.insert(key.to_string(), watermark.to_string());
+        }
+    }
+    let schema = Arc::new(Schema::new_with_metadata(schema.fields().clone(), metadata));
+    Ok(RecordBatch::new_empty(schema))
+}
+
+/// Whether `batch` is a punctuation batch, see [`punctuation_batch`]
+pub fn is_punctuation(batch: &RecordBatch) -> bool {
+    batch.num_rows() == 0
+        && batch
+            .schema()
+            .metadata()
+            .contains_key(PUNCTUATION_METADATA_KEY)
+}
+
+/// The watermark of the `side` input carried by the punctuation `batch`, if
+/// any, see [`punctuation_batch`]
+pub fn punctuation_watermark(batch: &RecordBatch, side: JoinSide) -> Option<String> {
+    if !is_punctuation(batch) {
+        return None;
+    }
+    let key = match side {
+        JoinSide::Left => LEFT_WATERMARK_METADATA_KEY,
+        JoinSide::Right => RIGHT_WATERMARK_METADATA_KEY,
     };
+    batch.schema().metadata().get(key).cloned()
+}
 
-    (fields.finish(), column_indices)
+/// A single row of a join output batch, passed to the closure of [`typed_rows`]
+/// to extract the fields of a row struct. Values are accessed by column index
+/// and null values are returned as `None`.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchRow<'a> {
+    batch: &'a RecordBatch,
+    row: usize,
+}
+
+impl<'a> BatchRow<'a> {
+    /// The batch the row belongs to
+    pub fn batch(&self) -> &'a RecordBatch {
+        self.batch
+    }
+
+    /// Index of the row within its batch
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// Returns the value of a primitive `column`, e.g. `get::<Int32Type>(0)`,
+    /// or `None` if the value is null.
+    pub fn get<T: ArrowPrimitiveType>(&self, column: usize) -> Result<Option<T::Native>> {
+        let array = self.column(column)?;
+        let Some(array) = array.as_any().downcast_ref::<PrimitiveArray<T>>() else {
+            return plan_err!(
+                "Column {column} has type {}, not {}",
+                array.data_type(),
+                T::DATA_TYPE
+            );
+        };
+        Ok(array.is_valid(self.row).then(|| array.value(self.row)))
+    }
+
+    /// Returns the value of a boolean `column`, or `None` if the value is null.
+    pub fn get_bool(&self, column: usize) -> Result<Option<bool>> {
+        let array = as_boolean_array(self.column(column)?)?;
+        Ok(array.is_valid(self.row).then(|| array.value(self.row)))
+    }
+
+    /// Returns the value of a string `column`, or `None` if the value is null.
+    pub fn get_str(&self, column: usize) -> Result<Option<&'a str>> {
+        let array = as_string_array(self.column(column)?)?;
+        Ok(array.is_valid(self.row).then(|| array.value(self.row)))
+    }
+
+    fn column(&self, column: usize) -> Result<&'a ArrayRef> {
+        match self.batch.columns().get(column) {
+            Some(array) => Ok(array),
+            None => plan_err!(
+                "Column {column} is out of bounds for a batch of {} columns",
+                self.batch.num_columns()
+            ),
+        }
+    }
+}
+
+/// Converts the output `stream` of a join into a stream of row structs, for
+/// Rust code embedding DataFusion that would rather not extract the values
+/// from the record batches itself. Each row is converted by `f`, which reads
+/// the values of the row through a [`BatchRow`].
+///
+/// Errors of the input stream and of `f` are passed on in place of the rows.
+pub fn typed_rows<T, F>(
+    stream: SendableRecordBatchStream,
+    mut f: F,
+) -> BoxStream<'static, Result<T>>
+where
+    T: Send + 'static,
+    F: FnMut(BatchRow) -> Result<T> + Send + 'static,
+{
+    stream
+        .map(move |batch| match batch {
+            Ok(batch) => (0..batch.num_rows())
+                .map(|row| f(BatchRow { batch: &batch, row }))
+                .collect::<Vec<_>>(),
+            Err(e) => vec![Err(e)],
+        })
+        .flat_map(futures::stream::iter)
+        .boxed()
 }
 
 /// A [`OnceAsync`] can be used to run an async closure once, with subsequent calls
@@ -920,6 +3248,24 @@ fn estimate_join_cardinality(
                 column_statistics: outer_stats.column_statistics,
             })
         }
+
+        // Mark joins emit every row of the marked side once, followed by the
+        // mark column
+        JoinType::LeftMark | JoinType::RightMark => {
+            let outer_stats = match join_type {
+                JoinType::LeftMark => left_stats,
+                _ => right_stats,
+            };
+
+            Some(PartialJoinStatistics {
+                num_rows: *outer_stats.num_rows.get_value()?,
+                column_statistics: outer_stats
+                    .column_statistics
+                    .into_iter()
+                    .chain(std::iter::once(ColumnStatistics::new_unknown()))
+                    .collect(),
+            })
+        }
     }
 }
 
@@ -1171,10 +3517,110 @@ impl<T: 'static> OnceFut<T> {
 pub(crate) fn need_produce_result_in_final(join_type: JoinType) -> bool {
     matches!(
         join_type,
-        JoinType::Left | JoinType::LeftAnti | JoinType::LeftSemi | JoinType::Full
+        JoinType::Left
+            | JoinType::LeftAnti
+            | JoinType::LeftSemi
+            | JoinType::LeftMark
+            | JoinType::Full
     )
 }
 
+/// Tracks which rows of the build side have been matched by any probe row, so
+/// that outer, semi and anti joins can produce the matched or unmatched build
+/// side rows once the probe side is exhausted. Every build side row takes up a
+/// single bit, and the (un)matched indices are extracted by iterating over the
+/// bits a word at a time.
+#[derive(Debug)]
+pub struct MatchBitmap {
+    bits: BooleanBufferBuilder,
+}
+
+impl MatchBitmap {
+    /// Creates a bitmap for `num_rows` build side rows, none of which are matched
+    pub fn new(num_rows: usize) -> Self {
+        let mut bits = BooleanBufferBuilder::new(num_rows);
+        bits.append_n(num_rows, false);
+        Self { bits }
+    }
+
+    /// Size of a bitmap for `num_rows` build side rows in bytes, to account
+    /// for it before creating it
+    pub fn size(num_rows: usize) -> usize {
+        bit_util::ceil(num_rows, 8)
+    }
+
+    /// Number of build side rows tracked by the bitmap
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Returns `true` if the bitmap tracks no rows
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Marks the given build side rows as matched, null indices are ignored
+    pub fn mark_matched(&mut self, indices: &UInt64Array) {
+        indices
+            .iter()
+            .flatten()
+            .for_each(|index| self.bits.set_bit(index as usize, true));
+    }
+
+    /// Returns whether the given build side row has been matched
+    pub fn is_matched(&self, index: usize) -> bool {
+        self.bits.get_bit(index)
+    }
+
+    /// Merges the matches tracked by `other` over the same build side, e.g.
+    /// for other probe batches or partitions, into this bitmap
+    pub fn merge(&mut self, other: &MatchBitmap) -> Result<()> {
+        if self.len() != other.len() {
+            return internal_err!(
+                "Cannot merge match bitmaps of {} and {} rows",
+                self.len(),
+                other.len()
+            );
+        }
+        self.bits
+            .as_slice_mut()
+            .iter_mut()
+            .zip(other.bits.as_slice())
+            .for_each(|(byte, other)| *byte |= other);
+        Ok(())
+    }
+
+    /// Indices of the matched build side rows, in ascending order
+    pub fn matched_indices(&self) -> UInt64Array {
+        self.indices(true)
+    }
+
+    /// Indices of the unmatched build side rows, in ascending order
+    pub fn unmatched_indices(&self) -> UInt64Array {
+        self.indices(false)
+    }
+
+    fn indices(&self, matched: bool) -> UInt64Array {
+        let chunks = BitChunks::new(self.bits.as_slice(), 0, self.len());
+        let remainder_mask = (1u64 << chunks.remainder_len()) - 1;
+        let words = chunks
+            .iter()
+            .map(|word| (word, u64::MAX))
+            .chain(std::iter::once((chunks.remainder_bits(), remainder_mask)));
+        let mut indices = vec![];
+        for (chunk_index, (word, mask)) in words.enumerate() {
+            let mut word = (if matched { word } else { !word }) & mask;
+            while word != 0 {
+                let bit = word.trailing_zeros() as usize;
+                indices.push((chunk_index * 64 + bit) as u64);
+                // Clear the lowest set bit:
+                word &= word - 1;
+            }
+        }
+        UInt64Array::from(indices)
+    }
+}
+
 /// In the end of join execution, need to use bit map of the matched
 /// indices to generate the final left and right indices.
 ///
@@ -1184,21 +3630,27 @@ pub(crate) fn need_produce_result_in_final(join_type: JoinType) -> bool {
 /// 2. join_type: `Left`
 ///
 /// The result is: `([1,4], [null, null])`
+///
+/// A `LeftMark` join produces all left rows, the matched ones paired with a
+/// (meaningless) right index of 0, i.e. `([0,1,2,3,4], [0, null, 0, 0, null])`
+/// in the example.
 pub(crate) fn get_final_indices_from_bit_map(
-    left_bit_map: &BooleanBufferBuilder,
+    left_bit_map: &MatchBitmap,
     join_type: JoinType,
 ) -> (UInt64Array, UInt32Array) {
-    let left_size = left_bit_map.len();
+    if join_type == JoinType::LeftMark {
+        let left_indices = UInt64Array::from_iter_values(0..left_bit_map.len() as u64);
+        let right_indices = (0..left_bit_map.len())
+            .map(|index| left_bit_map.is_matched(index).then_some(0))
+            .collect::<UInt32Array>();
+        return (left_indices, right_indices);
+    }
     let left_indices = if join_type == JoinType::LeftSemi {
-        (0..left_size)
-            .filter_map(|idx| (left_bit_map.get_bit(idx)).then_some(idx as u64))
-            .collect::<UInt64Array>()
+        left_bit_map.matched_indices()
     } else {
         // just for `Left`, `LeftAnti` and `Full` join
         // `LeftAnti`, `Left` and `Full` will produce the unmatched left row finally
-        (0..left_size)
-            .filter_map(|idx| (!left_bit_map.get_bit(idx)).then_some(idx as u64))
-            .collect::<UInt64Array>()
+        left_bit_map.unmatched_indices()
     };
     // right_indices
     // all the element in the right side is None
@@ -1228,6 +3680,7 @@ pub(crate) fn apply_join_filter_to_indices(
         &probe_indices,
         filter.column_indices(),
         build_side,
+        &mut NullPadding::default(),
     )?;
     let filter_result = filter
         .expression()
@@ -1243,8 +3696,172 @@ pub(crate) fn apply_join_filter_to_indices(
     ))
 }
 
+/// Schema metadata key with which an upstream operator can declare that every
+/// row of a batch satisfies a join filter. The value identifies the filter in
+/// terms of the columns of the batch; see [`mark_join_filter_satisfied`].
+pub const SATISFIED_JOIN_FILTER_KEY: &str = "datafusion.join.satisfied_filter";
+
+/// Annotates `batch` as already satisfying `filter`, so that a join applying
+/// the same filter to it as its `probe_side` input can skip evaluating the
+/// filter. This is only valid if the filter holds for every row of `batch`,
+/// and fails if the filter references columns of the other side.
+pub fn mark_join_filter_satisfied(
+    batch: RecordBatch,
+    filter: &JoinFilter,
+    probe_side: JoinSide,
+) -> Result<RecordBatch> {
+    let schema = batch.schema();
+    let Some(key) = satisfied_join_filter_key(filter, &schema, probe_side)? else {
+        return plan_err!(
+            "Only join filters referencing the {probe_side:?} side alone can be marked as satisfied"
+        );
+    };
+    let mut metadata = schema.metadata().clone();
+    metadata.insert(SATISFIED_JOIN_FILTER_KEY.to_string(), key);
+    let schema = Schema::new_with_metadata(schema.fields().clone(), metadata);
+    Ok(batch.with_schema(Arc::new(schema))?)
+}
+
+/// Checks whether `probe_batch` is annotated as satisfying `filter`, in which
+/// case evaluating the filter against it is redundant. The annotation is
+/// honored conservatively: the filter must only reference columns of the
+/// probe side, as the annotation cannot vouch for build side rows, and the
+/// annotated filter must match it exactly once both are resolved against the
+/// columns of `probe_batch`.
+pub(crate) fn is_join_filter_satisfied(
+    probe_batch: &RecordBatch,
+    filter: &JoinFilter,
+    probe_side: JoinSide,
+) -> bool {
+    let schema = probe_batch.schema();
+    let Some(satisfied) = schema.metadata().get(SATISFIED_JOIN_FILTER_KEY) else {
+        return false;
+    };
+    satisfied_join_filter_key(filter, &schema, probe_side)
+        .ok()
+        .flatten()
+        .is_some_and(|key| *satisfied == key)
+}
+
+/// Identifies `filter` by its expression with the columns of its intermediate
+/// schema replaced by the `side` input columns they are taken from, as the
+/// same intermediate expression can refer to different input columns in
+/// different joins. Returns `None` if the filter references columns of the
+/// other side or columns outside of `schema`.
+fn satisfied_join_filter_key(
+    filter: &JoinFilter,
+    schema: &Schema,
+    side: JoinSide,
+) -> Result<Option<String>> {
+    let mut resolvable = true;
+    let expr = filter
+        .expression()
+        .clone()
+        .transform_up_mut(&mut |expr| {
+            let Some(column) = expr.as_any().downcast_ref::<Column>() else {
+                return Ok(Transformed::no(expr));
+            };
+            let field = filter
+                .column_indices()
+                .get(column.index())
+                .filter(|c| c.side == side)
+                .and_then(|c| Some((c.index, schema.fields().get(c.index)?)));
+            match field {
+                Some((index, field)) => Ok(Transformed::yes(Arc::new(Column::new(
+                    field.name(),
+                    index,
+                )) as _)),
+                None => {
+                    resolvable = false;
+                    Ok(Transformed::no(expr))
+                }
+            }
+        })
+        .data()?;
+    Ok(resolvable.then(|| expr.to_string()))
+}
+
+/// Upper bound of the memory held by the array [`NullPadding`] keeps for a
+/// single data type
+const NULL_PADDING_CACHE_BYTES: usize = 1024 * 1024;
+
+/// All-null arrays padding the columns of the side without a match in outer
+/// join output, kept by a join stream across its output batches.
+///
+/// The padding is sliced from an all-null array kept per data type whenever
+/// it fits, so that blocks of unmatched rows don't materialize new null and
+/// value buffers for every output batch. The slices are logically identical
+/// to a newly allocated null array. An array is only kept if it takes at
+/// most [`NULL_PADDING_CACHE_BYTES`] and fits into the memory reservation of
+/// the stream, which is released with the stream. Without a reservation, see
+/// [`NullPadding::default`], every padding is newly allocated.
+#[derive(Debug, Default)]
+pub(crate) struct NullPadding {
+    /// The kept all-null arrays, by data type
+    arrays: HashMap<DataType, ArrayRef>,
+    /// Reservation accounting for the kept arrays
+    reservation: Option<MemoryReservation>,
+}
+
+impl NullPadding {
+    /// Creates a [`NullPadding`] keeping arrays within `reservation`
+    pub(crate) fn new(reservation: MemoryReservation) -> Self {
+        Self {
+            arrays: HashMap::new(),
+            reservation: Some(reservation),
+        }
+    }
+
+    /// Returns an all-null array of `data_type` with `len` rows
+    pub(crate) fn get(&mut self, data_type: &DataType, len: usize) -> ArrayRef {
+        let kept = self.arrays.get(data_type);
+        if let Some(array) = kept.filter(|array| len <= array.len()) {
+            return array.slice(0, len);
+        }
+        let array = new_null_join_array(data_type, len);
+        let Some(reservation) = self.reservation.as_mut() else {
+            return array;
+        };
+        let size = array.get_array_memory_size();
+        let replaced = kept.map_or(0, |kept| kept.get_array_memory_size());
+        if size <= NULL_PADDING_CACHE_BYTES
+            && reservation
+                .try_resize(reservation.size() - replaced + size)
+                .is_ok()
+        {
+            self.arrays.insert(data_type.clone(), array.clone());
+        }
+        array
+    }
+}
+
+/// Returns an all-null array of `data_type` with `len` rows like
+/// [`new_null_array`], which doesn't support view arrays (`Utf8View` and
+/// `BinaryView`) yet.
+pub(crate) fn new_null_join_array(data_type: &DataType, len: usize) -> ArrayRef {
+    match data_type {
+        DataType::Utf8View => Arc::new(StringViewArray::new_null(len)),
+        DataType::BinaryView => Arc::new(BinaryViewArray::new_null(len)),
+        _ => new_null_array(data_type, len),
+    }
+}
+
+/// Returns an empty [RecordBatch] of `schema` like [`RecordBatch::new_empty`],
+/// supporting view arrays, see [`new_null_join_array`]
+pub(crate) fn new_empty_join_batch(schema: SchemaRef) -> Result<RecordBatch> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| new_null_join_array(field.data_type(), 0))
+        .collect();
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
 /// Returns a new [RecordBatch] by combining the `left` and `right` according to `indices`.
 /// The resulting batch has [Schema] `schema`.
+///
+/// The columns of a side without a match are padded from `null_padding`.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn build_batch_from_indices(
     schema: &Schema,
     build_input_buffer: &RecordBatch,
@@ -1253,6 +3870,7 @@ pub(crate) fn build_batch_from_indices(
     probe_indices: &UInt32Array,
     column_indices: &[ColumnIndex],
     build_side: JoinSide,
+    null_padding: &mut NullPadding,
 ) -> Result<RecordBatch> {
     if schema.fields().is_empty() {
         let options = RecordBatchOptions::new()
@@ -1271,7 +3889,17 @@ pub(crate) fn build_batch_from_indices(
     // 2. based on the pick, `take` items from the different RecordBatches
     let mut columns: Vec<Arc<dyn Array>> = Vec::with_capacity(schema.fields().len());
 
-    for column_index in column_indices {
+    for (position, column_index) in column_indices.iter().enumerate() {
+        // A filter may reference the same input column more than once (e.g.
+        // `left.a - right.b > left.a - right.c`), in which case we reuse the
+        // array we have already gathered for it.
+        if let Some(seen) = column_indices[..position]
+            .iter()
+            .position(|c| c == column_index)
+        {
+            columns.push(columns[seen].clone());
+            continue;
+        }
         let array = if column_index.side == build_side {
             let array = build_input_buffer.column(column_index.index);
             if array.is_empty() || build_indices.null_count() == build_indices.len() {
@@ -1279,17 +3907,17 @@ pub(crate) fn build_batch_from_indices(
                 // Therefore, it's possible we are empty but need to populate an n-length null array,
                 // where n is the length of the index array.
                 assert_eq!(build_indices.null_count(), build_indices.len());
-                new_null_array(array.data_type(), build_indices.len())
+                null_padding.get(array.data_type(), build_indices.len())
             } else {
-                compute::take(array.as_ref(), build_indices, None)?
+                take_join_column(array.as_ref(), build_indices)?
             }
         } else {
             let array = probe_batch.column(column_index.index);
             if array.is_empty() || probe_indices.null_count() == probe_indices.len() {
                 assert_eq!(probe_indices.null_count(), probe_indices.len());
-                new_null_array(array.data_type(), probe_indices.len())
+                null_padding.get(array.data_type(), probe_indices.len())
             } else {
-                compute::take(array.as_ref(), probe_indices, None)?
+                take_join_column(array.as_ref(), probe_indices)?
             }
         };
         columns.push(array);
@@ -1297,6 +3925,333 @@ pub(crate) fn build_batch_from_indices(
     Ok(RecordBatch::try_new(Arc::new(schema.clone()), columns)?)
 }
 
+/// Gathers the values of `array` at `indices` like [`compute::take`]. View
+/// arrays (`Utf8View` and `BinaryView`) stay view arrays: only their views are
+/// gathered, while the data buffers holding the values are shared.
+pub(crate) fn take_join_column<I: ArrowPrimitiveType>(
+    array: &dyn Array,
+    indices: &PrimitiveArray<I>,
+) -> Result<ArrayRef> {
+    Ok(match array.data_type() {
+        DataType::Utf8View => Arc::new(take_views(as_string_view_array(array)?, indices)),
+        DataType::BinaryView => {
+            Arc::new(take_views(as_binary_view_array(array)?, indices))
+        }
+        _ => compute::take(array, indices, None)?,
+    })
+}
+
+fn take_views<T: ByteViewType + ?Sized, I: ArrowPrimitiveType>(
+    array: &GenericByteViewArray<T>,
+    indices: &PrimitiveArray<I>,
+) -> GenericByteViewArray<T> {
+    let views = array.views();
+    let mut validity = BooleanBufferBuilder::new(indices.len());
+    let taken = indices
+        .iter()
+        .map(|index| match index.map(|index| index.as_usize()) {
+            Some(index) if array.is_valid(index) => {
+                validity.append(true);
+                views[index]
+            }
+            // An empty inlined value
+            _ => {
+                validity.append(false);
+                0
+            }
+        })
+        .collect::<Vec<u128>>();
+    let nulls = NullBuffer::new(validity.finish());
+    let nulls = (nulls.null_count() > 0).then_some(nulls);
+    // SAFETY: The views are copied from `array` and refer to its data buffers
+    unsafe {
+        GenericByteViewArray::new_unchecked(
+            ScalarBuffer::from(taken),
+            array.data_buffers().to_vec(),
+            nulls,
+        )
+    }
+}
+
+/// Returns the number of bytes the value of `array` at `row` takes up: the
+/// length of variable-length values (summed up over the elements of lists),
+/// or the width of fixed-width values.
+fn value_byte_size(array: &dyn Array, row: usize) -> usize {
+    if array.is_null(row) {
+        return 0;
+    }
+    match array.data_type() {
+        DataType::Utf8 => array.as_string::<i32>().value_length(row).as_usize(),
+        DataType::LargeUtf8 => array.as_string::<i64>().value_length(row).as_usize(),
+        DataType::Binary => array.as_binary::<i32>().value_length(row).as_usize(),
+        DataType::LargeBinary => array.as_binary::<i64>().value_length(row).as_usize(),
+        DataType::Utf8View => {
+            as_string_view_array(array).map_or(0, |array| array.value(row).len())
+        }
+        DataType::BinaryView => {
+            as_binary_view_array(array).map_or(0, |array| array.value(row).len())
+        }
+        DataType::List(_) => {
+            let list = array.as_list::<i32>();
+            let offsets = list.value_offsets();
+            (offsets[row].as_usize()..offsets[row + 1].as_usize())
+                .map(|idx| value_byte_size(list.values().as_ref(), idx))
+                .sum()
+        }
+        DataType::LargeList(_) => {
+            let list = array.as_list::<i64>();
+            let offsets = list.value_offsets();
+            (offsets[row].as_usize()..offsets[row + 1].as_usize())
+                .map(|idx| value_byte_size(list.values().as_ref(), idx))
+                .sum()
+        }
+        data_type => data_type.primitive_width().unwrap_or(0),
+    }
+}
+
+/// Splits the output rows of the joined `build_indices` and `probe_indices`
+/// into consecutive ranges of rows whose values take up at most
+/// `byte_budget` bytes in the output. A row whose values exceed the budget on
+/// its own forms a range by itself. Returns a single empty range if there are
+/// no rows.
+pub(crate) fn split_indices_by_byte_budget(
+    build_input_buffer: &RecordBatch,
+    probe_batch: &RecordBatch,
+    build_indices: &UInt64Array,
+    probe_indices: &UInt32Array,
+    column_indices: &[ColumnIndex],
+    build_side: JoinSide,
+    byte_budget: usize,
+) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let (mut start, mut bytes) = (0, 0);
+    for row in 0..build_indices.len() {
+        let row_bytes = column_indices
+            .iter()
+            .map(|column_index| {
+                let (array, index) = if column_index.side == build_side {
+                    (
+                        build_input_buffer.column(column_index.index),
+                        build_indices
+                            .is_valid(row)
+                            .then(|| build_indices.value(row) as usize),
+                    )
+                } else {
+                    (
+                        probe_batch.column(column_index.index),
+                        probe_indices
+                            .is_valid(row)
+                            .then(|| probe_indices.value(row) as usize),
+                    )
+                };
+                index.map_or(0, |index| value_byte_size(array.as_ref(), index))
+            })
+            .sum::<usize>();
+        if row > start && bytes + row_bytes > byte_budget {
+            ranges.push(start..row);
+            (start, bytes) = (row, 0);
+        }
+        bytes += row_bytes;
+    }
+    ranges.push(start..build_indices.len());
+    ranges
+}
+
+/// Upper bound of the bytes taken by the header of an Arrow IPC record batch
+/// message besides its field nodes and buffer entries, including the
+/// continuation marker and length prefix
+const IPC_HEADER_BYTES: usize = 256;
+/// Upper bound of the bytes taken by a field node or a buffer entry in the
+/// header of an Arrow IPC record batch message
+const IPC_HEADER_ENTRY_BYTES: usize = 16;
+
+/// Estimates the bytes `batch` takes up when serialized as an Arrow IPC
+/// record batch message, e.g. in an Arrow Flight frame, see
+/// [`HashJoinExec::with_max_serialized_batch_bytes`]. The estimate is an
+/// upper bound: every buffer of every column, including the validity buffers
+/// and the buffers of nested and dictionary values, is accounted for in full
+/// and padded to 64 bytes, the largest alignment of the IPC format. Buffers
+/// shared with other arrays, like the data buffers of view arrays, are thus
+/// accounted for as well.
+///
+/// [`HashJoinExec::with_max_serialized_batch_bytes`]: crate::joins::HashJoinExec::with_max_serialized_batch_bytes
+pub fn estimated_ipc_size(batch: &RecordBatch) -> usize {
+    fn body_size(data: &ArrayData, header_entries: &mut usize) -> usize {
+        // A field node, and an entry for the validity buffer and every other
+        // buffer
+        *header_entries += 2 + data.buffers().len();
+        let validity = data.nulls().map_or(0, |nulls| nulls.buffer().len());
+        bit_util::round_upto_multiple_of_64(validity)
+            + data
+                .buffers()
+                .iter()
+                .map(|buffer| bit_util::round_upto_multiple_of_64(buffer.len()))
+                .sum::<usize>()
+            + data
+                .child_data()
+                .iter()
+                .map(|child| body_size(child, header_entries))
+                .sum::<usize>()
+    }
+
+    let mut header_entries = 0;
+    let body = batch
+        .columns()
+        .iter()
+        .map(|column| body_size(&column.to_data(), &mut header_entries))
+        .sum::<usize>();
+    IPC_HEADER_BYTES + header_entries * IPC_HEADER_ENTRY_BYTES + body
+}
+
+/// Name of the hidden column holding the build side row index of every join
+/// output row, see [`with_row_provenance_fields`]
+pub const BUILD_ROW_COLUMN: &str = "__build_row";
+/// Name of the hidden column holding the probe side row index of every join
+/// output row, see [`with_row_provenance_fields`]
+pub const PROBE_ROW_COLUMN: &str = "__probe_row";
+
+/// Appends the [`BUILD_ROW_COLUMN`] and [`PROBE_ROW_COLUMN`] fields to the
+/// output schema of a join.
+///
+/// The build side index refers to the row within the collected build side,
+/// i.e. within the single batch concatenating all build side batches in the
+/// order they were received, rather than within the original input batch.
+/// In [`PartitionMode::CollectLeft`] that order spans all build side
+/// partitions and is only deterministic for a single build side partition.
+/// The probe side index refers to the row within the probe batch that
+/// produced the output row. An index is null when the output row has no
+/// counterpart on that side, i.e. for unmatched rows of outer joins and for
+/// the side that semi and anti joins do not output.
+///
+/// These columns are only emitted by [`HashJoinExec`], see
+/// [`HashJoinExec::with_row_provenance`].
+///
+/// [`HashJoinExec`]: crate::joins::HashJoinExec
+/// [`HashJoinExec::with_row_provenance`]: crate::joins::HashJoinExec::with_row_provenance
+pub fn with_row_provenance_fields(schema: &Schema) -> Schema {
+    let mut builder = SchemaBuilder::from(schema.fields());
+    builder.push(Field::new(BUILD_ROW_COLUMN, DataType::UInt64, true));
+    builder.push(Field::new(PROBE_ROW_COLUMN, DataType::UInt32, true));
+    builder.finish().with_metadata(schema.metadata().clone())
+}
+
+/// Name of the hidden column flagging the outer join output rows without a
+/// counterpart on the other side, see [`with_unmatched_marker_field`]
+pub const UNMATCHED_ROW_COLUMN: &str = "__unmatched";
+
+/// Appends the [`UNMATCHED_ROW_COLUMN`] field to the output schema of a join.
+///
+/// The column is true for the output rows of an outer join that have no
+/// matching row on the other side, i.e. the rows padded with nulls for the
+/// other side, and false for matched rows. Unlike checking the padded columns
+/// for nulls, it tells unmatched rows apart from matches with null values.
+pub fn with_unmatched_marker_field(schema: &Schema) -> Schema {
+    let mut builder = SchemaBuilder::from(schema.fields());
+    builder.push(Field::new(UNMATCHED_ROW_COLUMN, DataType::Boolean, false));
+    builder.finish().with_metadata(schema.metadata().clone())
+}
+
+/// Name of the hidden column flagging the probe side rows of a right semi or
+/// anti join that have a match, see [`with_match_flag_field`]
+pub const MATCHED_ROW_COLUMN: &str = "__matched";
+
+/// Appends the [`MATCHED_ROW_COLUMN`] field to the output schema of a join.
+///
+/// The column is true for the probe side rows that have a matching build side
+/// row, and false for all others, including the rows with null join keys, so
+/// that filtering on it yields the semi join and filtering on its negation the
+/// anti join of the probe side.
+pub fn with_match_flag_field(schema: &Schema) -> Schema {
+    let mut builder = SchemaBuilder::from(schema.fields());
+    builder.push(Field::new(MATCHED_ROW_COLUMN, DataType::Boolean, false));
+    builder.finish().with_metadata(schema.metadata().clone())
+}
+
+/// Appends the hidden columns for `build_indices` and `probe_indices` to a
+/// `batch` created by [`build_batch_from_indices`]: the row provenance
+/// columns if `row_provenance` is set, followed by the unmatched row marker
+/// if `unmatched_marker` is set and the match flag if `match_flag` is set.
+/// The resulting batch has `schema`, which must be the schema of `batch`
+/// extended by [`with_row_provenance_fields`], [`with_unmatched_marker_field`]
+/// and [`with_match_flag_field`] accordingly.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn append_hidden_columns(
+    batch: RecordBatch,
+    schema: &SchemaRef,
+    join_type: JoinType,
+    build_indices: &UInt64Array,
+    probe_indices: &UInt32Array,
+    row_provenance: bool,
+    unmatched_marker: bool,
+    match_flag: bool,
+) -> Result<RecordBatch> {
+    let num_rows = batch.num_rows();
+    let mut columns = batch.columns().to_vec();
+    if row_provenance {
+        // Semi and anti joins only output the rows of one side:
+        let build_rows: ArrayRef =
+            if matches!(join_type, JoinType::RightSemi | JoinType::RightAnti) {
+                new_null_array(&DataType::UInt64, num_rows)
+            } else {
+                Arc::new(build_indices.clone())
+            };
+        let probe_rows: ArrayRef =
+            if matches!(join_type, JoinType::LeftSemi | JoinType::LeftAnti) {
+                new_null_array(&DataType::UInt32, num_rows)
+            } else {
+                Arc::new(probe_indices.clone())
+            };
+        columns.push(build_rows);
+        columns.push(probe_rows);
+    }
+    if unmatched_marker {
+        // Outer joins pad the unmatched rows of a side with a null index for
+        // the other side:
+        let unmatched = (0..num_rows)
+            .map(|row| Some(build_indices.is_null(row) || probe_indices.is_null(row)))
+            .collect::<BooleanArray>();
+        columns.push(Arc::new(unmatched));
+    }
+    if match_flag {
+        // Unmatched probe rows are paired with a null build side index, see
+        // `match_flag_indices`:
+        let matched = (0..num_rows)
+            .map(|row| Some(build_indices.is_valid(row)))
+            .collect::<BooleanArray>();
+        columns.push(Arc::new(matched));
+    }
+    let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+    Ok(RecordBatch::try_new_with_options(
+        schema.clone(),
+        columns,
+        &options,
+    )?)
+}
+
+/// Pairs every probe side row in `adjust_range` with the first build side row
+/// it is joined with in `left_indices` and `right_indices`, or a null index if
+/// it has none. This is how [`adjust_indices_by_join_type`] aligns the rows of
+/// right mark joins, and replaces it for right semi and anti joins emitting
+/// all probe rows with the match flag, see [`with_match_flag_field`].
+pub(crate) fn match_flag_indices(
+    left_indices: &UInt64Array,
+    right_indices: &UInt32Array,
+    adjust_range: Range<usize>,
+) -> (UInt64Array, UInt32Array) {
+    let mut first_matches = vec![None; adjust_range.len()];
+    for (left, right) in left_indices.values().iter().zip(right_indices.values()) {
+        let right = *right as usize;
+        if adjust_range.contains(&right) {
+            first_matches[right - adjust_range.start].get_or_insert(*left);
+        }
+    }
+    let right_indices = adjust_range.map(|row| row as u32).collect::<Vec<_>>();
+    (
+        UInt64Array::from(first_matches),
+        UInt32Array::from(right_indices),
+    )
+}
+
 /// The input is the matched indices for left and right and
 /// adjust the indices according to the join type
 pub(crate) fn adjust_indices_by_join_type(
@@ -1335,7 +4290,12 @@ pub(crate) fn adjust_indices_by_join_type(
             // the left_indices will not be used later for the `right anti` join
             (left_indices, right_indices)
         }
-        JoinType::LeftSemi | JoinType::LeftAnti => {
+        JoinType::RightMark => {
+            // every right row, with a (null) left index marking whether it is
+            // matched
+            match_flag_indices(&left_indices, &right_indices, adjust_range)
+        }
+        JoinType::LeftSemi | JoinType::LeftAnti | JoinType::LeftMark => {
             // matched or unmatched left row will be produced in the end of loop
             // When visit the right batch, we can output the matched left row and don't need to wait the end of loop
             (
@@ -1444,6 +4404,12 @@ pub(crate) struct BuildProbeJoinMetrics {
     pub(crate) build_mem_used: metrics::Gauge,
     /// Total time for joining probe-side batches to the build-side batches
     pub(crate) join_time: metrics::Time,
+    /// Part of `join_time` spent finding the matching rows of the probe-side
+    /// batches
+    pub(crate) probe_time: metrics::Time,
+    /// Part of `join_time` spent assembling output batches from the matching
+    /// rows
+    pub(crate) output_time: metrics::Time,
     /// Number of batches consumed by probe-side of this operator
     pub(crate) input_batches: metrics::Count,
     /// Number of rows consumed by probe-side this operator
@@ -1460,6 +4426,11 @@ impl BuildProbeJoinMetrics {
 
         let build_time = MetricBuilder::new(metrics).subset_time("build_time", partition);
 
+        let probe_time = MetricBuilder::new(metrics).subset_time("probe_time", partition);
+
+        let output_time =
+            MetricBuilder::new(metrics).subset_time("output_time", partition);
+
         let build_input_batches =
             MetricBuilder::new(metrics).counter("build_input_batches", partition);
 
@@ -1485,6 +4456,8 @@ impl BuildProbeJoinMetrics {
             build_input_rows,
             build_mem_used,
             join_time,
+            probe_time,
+            output_time,
             input_batches,
             input_rows,
             output_batches,
@@ -1566,12 +4539,25 @@ mod tests {
 
     use super::*;
 
-    use arrow::datatypes::{DataType, Fields};
+    use crate::joins::test_utils::{
+        build_sides_record_batches, complicated_filter, create_memory_table,
+        join_expr_tests_fixture_i32, join_expr_tests_fixture_non_prunable,
+        split_record_batches,
+    };
+    use crate::joins::{StreamJoinPartitionMode, SymmetricHashJoinExec};
+
+    use arrow::array::{BooleanArray, Float64Array, Int32Array};
+    use arrow::datatypes::{Fields, Int32Type, Int64Type, TimestampMillisecondType};
     use arrow::error::{ArrowError, Result as ArrowResult};
     use arrow_schema::SortOptions;
 
     use datafusion_common::stats::Precision::{Absent, Exact, Inexact};
-    use datafusion_common::{arrow_datafusion_err, arrow_err, ScalarValue};
+    use datafusion_common::{arrow_datafusion_err, arrow_err, assert_contains, exec_err};
+    use datafusion_execution::memory_pool::{
+        MemoryConsumer, MemoryPool, UnboundedMemoryPool,
+    };
+    use datafusion_execution::TaskContext;
+    use datafusion_physical_expr::expressions::{binary, col, lit};
 
     fn check(
         left: &[Column],
@@ -1739,6 +4725,62 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_join_schema_field_metadata() -> Result<()> {
+        let metadata =
+            |value: &str| HashMap::from([("unit".to_string(), value.to_string())]);
+        let left = Schema::new(vec![
+            Field::new("a", DataType::Int32, false).with_metadata(metadata("m")),
+            Field::new("b", DataType::Int32, false),
+        ]);
+        let right = Schema::new(vec![
+            Field::new("c", DataType::Int32, false).with_metadata(metadata("s"))
+        ]);
+
+        for join_type in [
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightSemi,
+            JoinType::RightAnti,
+        ] {
+            let (schema, _) = build_join_schema(&left, &right, &join_type);
+            // The fields of the null padded side of outer joins keep their
+            // metadata as well
+            for field in schema.fields() {
+                let input_field = match field.name().as_str() {
+                    "c" => right.field(0),
+                    name => left.field_with_name(name)?,
+                };
+                assert_eq!(
+                    field.metadata(),
+                    input_field.metadata(),
+                    "{} of {join_type} join",
+                    field.name()
+                );
+            }
+        }
+
+        // Output expressions passing a column through keep its metadata
+        let (schema, _) = build_join_schema(&left, &right, &JoinType::Full);
+        let output_exprs = vec![
+            (col("c", &schema)?, "c_out".to_string()),
+            (
+                binary(col("a", &schema)?, Operator::Plus, lit(1), &schema)?,
+                "a_plus_1".to_string(),
+            ),
+        ];
+        let output_schema = output_exprs_schema(&output_exprs, &schema)?;
+        assert_eq!(output_schema.field(0).metadata(), &metadata("s"));
+        assert!(output_schema.field(0).is_nullable());
+        assert!(output_schema.field(1).metadata().is_empty());
+
+        Ok(())
+    }
+
     fn create_stats(
         num_rows: Option<usize>,
         column_stats: Vec<ColumnStatistics>,
@@ -2481,4 +5523,1182 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_build_batch_from_indices_repeated_columns() -> Result<()> {
+        let build_batch = RecordBatch::try_from_iter(vec![
+            ("a", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+            (
+                "b",
+                Arc::new(Int32Array::from(vec![10, 20, 30])) as ArrayRef,
+            ),
+        ])?;
+        let probe_batch = RecordBatch::try_from_iter(vec![
+            ("c", Arc::new(Int32Array::from(vec![4, 5])) as ArrayRef),
+            ("d", Arc::new(Int32Array::from(vec![40, 50])) as ArrayRef),
+        ])?;
+        // Intermediate schema for `a - c > a - d`, which references `a` twice:
+        let schema = Schema::new(vec![
+            Field::new("0", DataType::Int32, true),
+            Field::new("1", DataType::Int32, true),
+            Field::new("2", DataType::Int32, true),
+            Field::new("3", DataType::Int32, true),
+        ]);
+        let column_indices = vec![
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Right,
+            },
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 1,
+                side: JoinSide::Right,
+            },
+        ];
+        let build_indices = UInt64Array::from(vec![2, 0, 1]);
+        let probe_indices = UInt32Array::from(vec![0, 1, 1]);
+
+        let batch = build_batch_from_indices(
+            &schema,
+            &build_batch,
+            &probe_batch,
+            &build_indices,
+            &probe_indices,
+            &column_indices,
+            JoinSide::Left,
+            &mut NullPadding::default(),
+        )?;
+
+        let expected = [
+            vec![3, 1, 2],
+            vec![4, 5, 5],
+            vec![3, 1, 2],
+            vec![40, 50, 50],
+        ];
+        for (column, expected) in batch.columns().iter().zip(expected) {
+            assert_eq!(column.as_ref(), &Int32Array::from(expected) as &dyn Array);
+        }
+
+        // Repeated references must point to the same gathered array:
+        assert!(Arc::ptr_eq(batch.column(0), batch.column(2)));
+
+        Ok(())
+    }
+
+    /// Evaluates `operation` with a hash set of the right rows
+    #[tokio::test]
+    async fn test_materialize_join_output() -> Result<()> {
+        let (left_batch, right_batch) = build_sides_record_batches(40, (4, 5))?;
+        let right_ordering = vec![PhysicalSortExpr {
+            expr: col("ra1", &right_batch.schema())?,
+            options: SortOptions::default(),
+        }];
+        let (left, right) = create_memory_table(
+            split_record_batches(&left_batch, 8)?,
+            split_record_batches(&right_batch, 8)?,
+            vec![],
+            vec![right_ordering],
+        )?;
+        let on = vec![(col("lc1", &left.schema())?, col("rc1", &right.schema())?)];
+        let join = Arc::new(crate::joins::HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Inner,
+            None,
+            crate::joins::PartitionMode::CollectLeft,
+            false,
+        )?) as Arc<dyn ExecutionPlan>;
+        let context = Arc::new(TaskContext::default());
+        let expected = crate::common::collect(join.execute(0, context.clone())?).await?;
+
+        let cached = materialize_join_output(join.clone(), context.clone()).await?;
+        assert_eq!(cached.schema(), join.schema());
+        assert_eq!(
+            cached.properties().output_partitioning().partition_count(),
+            1
+        );
+        // The join keeps the order of its probe side, and so does the cache:
+        assert!(join.output_ordering().is_some());
+        assert_eq!(
+            cached.properties().output_ordering(),
+            join.output_ordering()
+        );
+        let batches = crate::common::collect(cached.execute(0, context)?).await?;
+        assert_eq!(
+            arrow::util::pretty::pretty_format_batches(&batches)?.to_string(),
+            arrow::util::pretty::pretty_format_batches(&expected)?.to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_support_matrix() -> Result<()> {
+        use crate::joins::{HashJoinExec, NestedLoopJoinExec, SortMergeJoinExec};
+
+        let (left_batch, right_batch) = build_sides_record_batches(10, (4, 5))?;
+        let (left, right) =
+            create_memory_table(vec![left_batch], vec![right_batch], vec![], vec![])?;
+        let on = vec![(col("la1", &left.schema())?, col("ra1", &right.schema())?)];
+        let intermediate_schema = Schema::new(vec![
+            Field::new("left", DataType::Int32, true),
+            Field::new("right", DataType::Int32, true),
+        ]);
+        // left < right
+        let filter = JoinFilter::new(
+            binary(
+                col("left", &intermediate_schema)?,
+                Operator::Lt,
+                col("right", &intermediate_schema)?,
+                &intermediate_schema,
+            )?,
+            vec![
+                ColumnIndex {
+                    index: left.schema().index_of("la1")?,
+                    side: JoinSide::Left,
+                },
+                ColumnIndex {
+                    index: right.schema().index_of("ra1")?,
+                    side: JoinSide::Right,
+                },
+            ],
+            intermediate_schema.clone(),
+        );
+
+        let matrix = support_matrix();
+        let supported = |operator, join_type, partition_mode, has_filter| {
+            matrix.contains(&JoinSupport {
+                operator,
+                join_type,
+                partition_mode,
+                has_filter,
+                is_streaming: false,
+            })
+        };
+        for join_type in JOIN_TYPES {
+            for filter in [None, Some(filter.clone())] {
+                let has_filter = filter.is_some();
+                for partition_mode in
+                    [PartitionMode::Partitioned, PartitionMode::CollectLeft]
+                {
+                    let accepted = HashJoinExec::try_new(
+                        left.clone(),
+                        right.clone(),
+                        on.clone(),
+                        filter.clone(),
+                        &join_type,
+                        None,
+                        partition_mode,
+                        false,
+                    )
+                    .is_ok();
+                    assert_eq!(
+                        accepted,
+                        supported(
+                            JoinOperator::Hash,
+                            join_type,
+                            Some(partition_mode),
+                            has_filter
+                        )
+                    );
+                }
+                let accepted = SymmetricHashJoinExec::try_new(
+                    left.clone(),
+                    right.clone(),
+                    on.clone(),
+                    filter.clone(),
+                    &join_type,
+                    false,
+                    None,
+                    None,
+                    StreamJoinPartitionMode::SinglePartition,
+                )
+                .is_ok();
+                assert_eq!(
+                    accepted,
+                    supported(JoinOperator::SymmetricHash, join_type, None, has_filter)
+                );
+                let accepted = SortMergeJoinExec::try_new(
+                    left.clone(),
+                    right.clone(),
+                    on.clone(),
+                    filter.clone(),
+                    join_type,
+                    vec![SortOptions::default()],
+                    false,
+                )
+                .is_ok();
+                assert_eq!(
+                    accepted,
+                    supported(JoinOperator::SortMerge, join_type, None, has_filter)
+                );
+                let accepted = NestedLoopJoinExec::try_new(
+                    left.clone(),
+                    right.clone(),
+                    filter.clone(),
+                    &join_type,
+                )
+                .is_ok();
+                assert_eq!(
+                    accepted,
+                    supported(JoinOperator::NestedLoop, join_type, None, has_filter)
+                );
+            }
+        }
+        // The cross join takes neither a join type nor a filter
+        let cross_joins = matrix
+            .iter()
+            .filter(|support| support.operator == JoinOperator::Cross)
+            .collect::<Vec<_>>();
+        assert_eq!(cross_joins.len(), 1);
+        assert!(supported(JoinOperator::Cross, JoinType::Inner, None, false));
+        // A hash join emitting unmatched build rows at the end does not stream:
+        assert!(!matrix.iter().any(|support| support.is_streaming
+            && support.operator == JoinOperator::Hash
+            && support.join_type == JoinType::Left));
+        assert!(supported(
+            JoinOperator::Hash,
+            JoinType::Left,
+            Some(PartitionMode::CollectLeft),
+            true
+        ));
+
+        Ok(())
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct JoinedRow {
+        la1: i32,
+        l_asc_null_first: Option<i32>,
+        lt1: i64,
+        ra1: Option<i32>,
+    }
+
+    #[tokio::test]
+    async fn test_typed_rows() -> Result<()> {
+        let (left_batch, right_batch) = build_sides_record_batches(30, (4, 5))?;
+        // Only the first 20 left rows have a match:
+        let (left, right) = create_memory_table(
+            split_record_batches(&left_batch, 8)?,
+            split_record_batches(&right_batch.slice(0, 20), 8)?,
+            vec![],
+            vec![],
+        )?;
+        let on = vec![(
+            Arc::new(Column::new_with_schema("la1", &left.schema())?) as PhysicalExprRef,
+            Arc::new(Column::new_with_schema("ra1", &right.schema())?) as PhysicalExprRef,
+        )];
+        let join = crate::joins::HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &JoinType::Left,
+            None,
+            crate::joins::PartitionMode::CollectLeft,
+            false,
+        )?;
+        let schema = join.schema();
+        let [la1, l_asc_null_first, lt1, ra1] =
+            ["la1", "l_asc_null_first", "lt1", "ra1"].map(|name| schema.index_of(name));
+        let (la1, l_asc_null_first, lt1, ra1) = (la1?, l_asc_null_first?, lt1?, ra1?);
+
+        let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+        let mut rows = typed_rows(stream, move |row| {
+            Ok(JoinedRow {
+                la1: row.get::<Int32Type>(la1)?.unwrap(),
+                l_asc_null_first: row.get::<Int32Type>(l_asc_null_first)?,
+                lt1: row.get::<TimestampMillisecondType>(lt1)?.unwrap(),
+                ra1: row.get::<Int32Type>(ra1)?,
+            })
+        })
+        .try_collect::<Vec<_>>()
+        .await?;
+        rows.sort_by_key(|row| row.la1);
+
+        assert_eq!(rows.len(), 30);
+        for (i, row) in rows.iter().enumerate() {
+            let i = i as i32;
+            assert_eq!(
+                row,
+                &JoinedRow {
+                    la1: i,
+                    // The first 12 values are null:
+                    l_asc_null_first: (i >= 12).then_some(i),
+                    lt1: i as i64 + 1672531200000,
+                    ra1: (i < 20).then_some(i),
+                }
+            );
+        }
+
+        // Reading a column as the wrong type fails the stream:
+        let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+        let err = typed_rows(stream, move |row| row.get::<Int64Type>(la1))
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("has type Int32, not Int64"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_filter_null_join_keys() -> Result<()> {
+        let (left_batch, right_batch) = build_sides_record_batches(30, (4, 5))?;
+        let left_nulls = left_batch.column(6).null_count();
+        assert!(left_nulls > 0);
+        let (left, right) = create_memory_table(
+            split_record_batches(&left_batch, 8)?,
+            split_record_batches(&right_batch, 8)?,
+            vec![],
+            vec![],
+        )?;
+        let on = vec![(
+            Arc::new(Column::new_with_schema("l_asc_null_first", &left.schema())?)
+                as PhysicalExprRef,
+            Arc::new(Column::new_with_schema(
+                "r_asc_null_first",
+                &right.schema(),
+            )?) as PhysicalExprRef,
+        )];
+        let context = Arc::new(TaskContext::default());
+
+        // Null key rows are dropped before the join for inner joins:
+        let (filtered_left, filtered_right) = filter_null_join_keys(
+            left.clone(),
+            right.clone(),
+            &on,
+            &JoinType::Inner,
+            false,
+        )?;
+        for (input, batch) in [
+            (&filtered_left, &left_batch),
+            (&filtered_right, &right_batch),
+        ] {
+            assert!(input.as_any().is::<FilterExec>());
+            let batches =
+                crate::common::collect(input.execute(0, context.clone())?).await?;
+            let row_count = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+            assert_eq!(row_count, batch.num_rows() - left_nulls);
+            assert!(batches.iter().all(|b| b.column(6).null_count() == 0));
+        }
+
+        // Only the non-preserved side is filtered for outer and anti joins:
+        for (join_type, left_filtered, right_filtered) in [
+            (JoinType::Left, false, true),
+            (JoinType::Right, true, false),
+            (JoinType::Full, false, false),
+            (JoinType::LeftSemi, true, true),
+            (JoinType::RightSemi, true, true),
+            (JoinType::LeftAnti, false, true),
+            (JoinType::RightAnti, true, false),
+        ] {
+            let (filtered_left, filtered_right) = filter_null_join_keys(
+                left.clone(),
+                right.clone(),
+                &on,
+                &join_type,
+                false,
+            )?;
+            assert_eq!(filtered_left.as_any().is::<FilterExec>(), left_filtered);
+            assert_eq!(filtered_right.as_any().is::<FilterExec>(), right_filtered);
+        }
+
+        // Null keys match each other when null_equals_null is set:
+        let (unfiltered_left, unfiltered_right) = filter_null_join_keys(
+            left.clone(),
+            right.clone(),
+            &on,
+            &JoinType::Inner,
+            true,
+        )?;
+        assert!(Arc::ptr_eq(&unfiltered_left, &left));
+        assert!(Arc::ptr_eq(&unfiltered_right, &right));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_filter_with_float_tolerance() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("left", DataType::Float64, false),
+            Field::new("right", DataType::Float64, false),
+            Field::new("left_int", DataType::Int32, false),
+            Field::new("right_int", DataType::Int32, false),
+        ]);
+        let column_indices = JoinFilter::build_column_indices(vec![0, 1], vec![0, 1]);
+        // left <= right AND left_int < right_int
+        let expression = Arc::new(BinaryExpr::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("left", 0)),
+                Operator::LtEq,
+                Arc::new(Column::new("right", 1)),
+            )),
+            Operator::And,
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("left_int", 2)),
+                Operator::Lt,
+                Arc::new(Column::new("right_int", 3)),
+            )),
+        ));
+        let filter = JoinFilter::new(expression, column_indices, schema.clone());
+
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Float64Array::from(vec![1.0, 1.0 + 5e-10, 1.0 + 2e-9])),
+                Arc::new(Float64Array::from(vec![1.0, 1.0, 1.0])),
+                Arc::new(Int32Array::from(vec![0, 0, 0])),
+                Arc::new(Int32Array::from(vec![1, 1, 1])),
+            ],
+        )?;
+        let evaluate = |filter: &JoinFilter| -> Result<BooleanArray> {
+            let result = filter
+                .expression()
+                .evaluate(&batch)?
+                .into_array(batch.num_rows())?;
+            Ok(as_boolean_array(&result)?.clone())
+        };
+
+        assert_eq!(
+            evaluate(&filter)?,
+            BooleanArray::from(vec![true, false, false])
+        );
+        let relaxed = filter.with_float_tolerance(1e-9)?;
+        assert_eq!(
+            evaluate(&relaxed)?,
+            BooleanArray::from(vec![true, true, false])
+        );
+        // Only the float comparison is rewritten:
+        assert_eq!(
+            relaxed.expression().to_string(),
+            "left@0 <= right@1 + 0.000000001 AND left_int@2 < right_int@3"
+        );
+
+        for tolerance in [-1.0, f64::NAN, f64::INFINITY] {
+            assert!(filter.with_float_tolerance(tolerance).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_filter_to_debug_sql() -> Result<()> {
+        // la1 + la2 and ra1, the columns referenced by the fixture filter
+        let schema = Schema::new(vec![
+            Field::new("0", DataType::Int32, true),
+            Field::new("1", DataType::Int32, true),
+            Field::new("2", DataType::Int32, true),
+        ]);
+        let column_indices = JoinFilter::build_column_indices(vec![0, 4], vec![0]);
+        let filter =
+            JoinFilter::new(complicated_filter(&schema)?, column_indices, schema);
+        assert_eq!(
+            filter.to_debug_sql()?,
+            "CAST(left.col0 + left.col4 AS Int64) > CAST(right.col0 AS Int64) + 10 \
+             AND CAST(left.col0 + left.col4 AS Int64) < CAST(right.col0 AS Int64) + 100"
+        );
+        assert_eq!(
+            filter.swap_sides().to_debug_sql()?,
+            "CAST(right.col0 + right.col4 AS Int64) > CAST(left.col0 AS Int64) + 10 \
+             AND CAST(right.col0 + right.col4 AS Int64) < CAST(left.col0 AS Int64) + 100"
+        );
+
+        // A column without a column index can not be resolved
+        let filter = JoinFilter::new(
+            filter.expression().clone(),
+            JoinFilter::build_column_indices(vec![0, 4], vec![]),
+            filter.schema().clone(),
+        );
+        assert!(filter.to_debug_sql().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_filter_from_debug_sql() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("0", DataType::Int32, true),
+            Field::new("1", DataType::Int32, true),
+            Field::new("2", DataType::Int32, true),
+        ]);
+        let column_indices = JoinFilter::build_column_indices(vec![0, 4], vec![0]);
+        let filter =
+            JoinFilter::new(complicated_filter(&schema)?, column_indices, schema);
+        let left_schema = Schema::new(
+            (0..5)
+                .map(|i| Field::new(format!("la{i}"), DataType::Int32, true))
+                .collect::<Vec<_>>(),
+        );
+        let right_schema = Schema::new(vec![
+            Field::new("ra0", DataType::Int32, true),
+            Field::new("ra1", DataType::Float64, true),
+        ]);
+
+        // The fixture filter round trips, and filters the same rows:
+        let sql = filter.to_debug_sql()?;
+        let parsed = JoinFilter::from_debug_sql(&sql, &left_schema, &right_schema)?;
+        assert_eq!(parsed.to_debug_sql()?, sql);
+        assert_eq!(parsed.column_indices(), filter.column_indices());
+        let batch = RecordBatch::try_new(
+            Arc::new(parsed.schema().clone()),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 50, 100])),
+                Arc::new(Int32Array::from(vec![2, 50, 100])),
+                Arc::new(Int32Array::from(vec![0, 0, 0])),
+            ],
+        )?;
+        let evaluate = |filter: &JoinFilter| -> Result<ArrayRef> {
+            filter
+                .expression()
+                .evaluate(&batch)?
+                .into_array(batch.num_rows())
+        };
+        assert_eq!(&evaluate(&parsed)?, &evaluate(&filter)?);
+
+        // Literals take the type of the operand they are combined with:
+        for sql in [
+            "NOT right.col1 IS NULL AND right.col1 >= -1.5",
+            "left.col2 IS NOT NULL OR (- left.col1) * 2 < right.col0 % 3",
+            "(left.col0 = 1 OR left.col0 = right.col0) AND 10 > right.col1",
+        ] {
+            let parsed = JoinFilter::from_debug_sql(sql, &left_schema, &right_schema)?;
+            assert_eq!(parsed.to_debug_sql()?, sql);
+        }
+        let parsed = JoinFilter::from_debug_sql(
+            "10 > right.col1 AND right.col0 != 4",
+            &left_schema,
+            &right_schema,
+        )?;
+        assert_eq!(
+            parsed
+                .schema()
+                .fields()
+                .iter()
+                .map(|f| f.name().as_str())
+                .collect::<Vec<_>>(),
+            ["ra0", "ra1"]
+        );
+        let binary = parsed
+            .expression()
+            .as_any()
+            .downcast_ref::<BinaryExpr>()
+            .unwrap();
+        assert_eq!(binary.left().data_type(parsed.schema())?, DataType::Boolean);
+        let comparison = binary.left().as_any().downcast_ref::<BinaryExpr>().unwrap();
+        assert_eq!(
+            comparison.left().data_type(parsed.schema())?,
+            DataType::Float64
+        );
+
+        for sql in [
+            // Not a predicate
+            "left.col0 + 1",
+            // Out of range column
+            "left.col5 > 1",
+            // String literals are not quoted
+            "right.col0 = abc",
+            // Trailing tokens
+            "left.col0 > 1 1",
+            "CAST(left.col0 AS Decimal128(10, 2)) > 1",
+        ] {
+            assert!(
+                JoinFilter::from_debug_sql(sql, &left_schema, &right_schema).is_err(),
+                "{sql}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_null_padding_shares_buffers() -> Result<()> {
+        let data_type = DataType::Decimal128(38, 7);
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", data_type.clone(), true),
+        ]);
+        let build_batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)])),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3, 4]))],
+        )?;
+        let probe_batch =
+            RecordBatch::new_empty(Arc::new(Schema::new(vec![Field::new(
+                "b",
+                data_type.clone(),
+                true,
+            )])));
+        let column_indices = vec![
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Right,
+            },
+        ];
+        // All build rows are unmatched:
+        let padded_batch = |build_indices: Vec<u64>, null_padding: &mut NullPadding| {
+            let probe_indices = UInt32Array::from(vec![None; build_indices.len()]);
+            build_batch_from_indices(
+                &schema,
+                &build_batch,
+                &probe_batch,
+                &UInt64Array::from(build_indices),
+                &probe_indices,
+                &column_indices,
+                JoinSide::Left,
+                null_padding,
+            )
+        };
+        let padding_buffer =
+            |batch: &RecordBatch| batch.column(1).to_data().buffers()[0].as_ptr();
+
+        let pool: Arc<dyn MemoryPool> = Arc::new(UnboundedMemoryPool::default());
+        let mut null_padding =
+            NullPadding::new(MemoryConsumer::new("NullPadding").register(&pool));
+        let first = padded_batch(vec![0, 1, 2, 3], &mut null_padding)?;
+        let second = padded_batch(vec![1, 3], &mut null_padding)?;
+        for batch in [&first, &second] {
+            let padding = batch.column(1);
+            assert_eq!(padding.data_type(), &data_type);
+            assert_eq!(padding.null_count(), batch.num_rows());
+            assert_eq!(
+                padding.to_data(),
+                new_null_array(&data_type, batch.num_rows()).to_data()
+            );
+        }
+        assert_eq!(padding_buffer(&first), padding_buffer(&second));
+        // The kept array is accounted for until the padding is dropped:
+        assert_eq!(pool.reserved(), first.column(1).get_array_memory_size());
+        drop(null_padding);
+        assert_eq!(pool.reserved(), 0);
+
+        // Nothing is kept without a reservation:
+        let mut null_padding = NullPadding::default();
+        let first = padded_batch(vec![0, 1, 2, 3], &mut null_padding)?;
+        let second = padded_batch(vec![1, 3], &mut null_padding)?;
+        assert_ne!(padding_buffer(&first), padding_buffer(&second));
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_filter_validate() -> Result<()> {
+        let (left_batch, right_batch) = build_sides_record_batches(30, (4, 5))?;
+        let left_schema = left_batch.schema();
+        let right_schema = right_batch.schema();
+        let schema = Schema::new(vec![
+            Field::new("left", DataType::Int32, true),
+            Field::new("right", DataType::Int32, true),
+        ]);
+        let expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("left", 0)),
+            Operator::Lt,
+            Arc::new(Column::new("right", 1)),
+        ));
+        let filter_with = |column_indices, schema| {
+            JoinFilter::new(expression.clone(), column_indices, schema)
+        };
+
+        // la1 < ra1
+        let valid = filter_with(
+            JoinFilter::build_column_indices(vec![0], vec![0]),
+            schema.clone(),
+        );
+        valid.validate(&left_schema, &right_schema)?;
+        // The swapped filter is only valid for swapped inputs:
+        valid.swap_sides().validate(&right_schema, &left_schema)?;
+
+        // lt1 is a timestamp column:
+        let wrong_type = filter_with(
+            JoinFilter::build_column_indices(vec![3], vec![0]),
+            schema.clone(),
+        );
+        let err = wrong_type
+            .validate(&left_schema, &right_schema)
+            .unwrap_err();
+        assert!(err.to_string().contains(
+            "Join filter column left has type Int32, but column lt1 of the left side has type Timestamp(Millisecond, None)"
+        ));
+
+        let out_of_bounds = filter_with(
+            JoinFilter::build_column_indices(vec![0], vec![42]),
+            schema.clone(),
+        );
+        assert!(out_of_bounds.validate(&left_schema, &right_schema).is_err());
+
+        // The expression references an intermediate column that has no index:
+        let missing_index =
+            filter_with(JoinFilter::build_column_indices(vec![0], vec![]), schema);
+        assert!(missing_index.validate(&left_schema, &right_schema).is_err());
+        let narrow_schema = Schema::new(vec![Field::new("left", DataType::Int32, true)]);
+        let missing_column = filter_with(
+            JoinFilter::build_column_indices(vec![0], vec![]),
+            narrow_schema,
+        );
+        assert!(missing_column
+            .validate(&left_schema, &right_schema)
+            .is_err());
+
+        // Join constructors reject inconsistent filters:
+        let (left, right) = create_memory_table(
+            vec![left_batch.clone()],
+            vec![right_batch.clone()],
+            vec![],
+            vec![],
+        )?;
+        let on = vec![(
+            Arc::new(Column::new_with_schema("lc1", &left_schema)?) as PhysicalExprRef,
+            Arc::new(Column::new_with_schema("rc1", &right_schema)?) as PhysicalExprRef,
+        )];
+        assert!(crate::joins::HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            Some(wrong_type),
+            &JoinType::Inner,
+            None,
+            crate::joins::PartitionMode::CollectLeft,
+            false,
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_filter_estimated_eval_cost() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("0", DataType::Int32, true),
+            Field::new("1", DataType::Int32, true),
+            Field::new("2", DataType::Int32, true),
+        ]);
+        let column_indices = JoinFilter::build_column_indices(vec![0, 1], vec![0]);
+        let complicated = JoinFilter::new(
+            complicated_filter(&schema)?,
+            column_indices.clone(),
+            schema.clone(),
+        );
+        // 0 < 2
+        let single = JoinFilter::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("0", 0)),
+                Operator::Lt,
+                Arc::new(Column::new("2", 2)),
+            )),
+            column_indices,
+            schema,
+        );
+
+        assert_eq!(single.estimated_eval_cost(), 1);
+        // Two comparisons, four additions and four casts joined by AND:
+        assert_eq!(complicated.estimated_eval_cost(), 15);
+        // The estimate only depends on the expression:
+        assert_eq!(
+            complicated.estimated_eval_cost(),
+            complicated.clone().estimated_eval_cost()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_filter_split_side_conjuncts() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("0", DataType::Int32, true),
+            Field::new("1", DataType::Int32, true),
+            Field::new("2", DataType::Int32, true),
+        ]);
+        let column_indices = JoinFilter::build_column_indices(vec![0, 1], vec![3]);
+        let binary = |left: usize, op, right: Arc<dyn PhysicalExpr>| {
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new(&left.to_string(), left)),
+                op,
+                right,
+            )) as Arc<dyn PhysicalExpr>
+        };
+        let literal = |value| {
+            Arc::new(Literal::new(ScalarValue::Int32(Some(value))))
+                as Arc<dyn PhysicalExpr>
+        };
+        // 0 < 2 AND 2 > 10 AND 1 > 5
+        let expression = Arc::new(BinaryExpr::new(
+            Arc::new(BinaryExpr::new(
+                binary(0, Operator::Lt, Arc::new(Column::new("2", 2))),
+                Operator::And,
+                binary(2, Operator::Gt, literal(10)),
+            )),
+            Operator::And,
+            binary(1, Operator::Gt, literal(5)),
+        ));
+        let filter = JoinFilter::new(expression, column_indices.clone(), schema);
+
+        let (right_expr, other) = filter.split_side_conjuncts(JoinSide::Right)?;
+        // Right side columns are rewritten to their input indices:
+        assert_eq!(right_expr.unwrap().to_string(), "2@3 > 10");
+        let other = other.unwrap();
+        assert_eq!(other.expression().to_string(), "0@0 < 2@2 AND 1@1 > 5");
+        assert_eq!(other.column_indices(), column_indices.as_slice());
+
+        let (left_expr, other) = filter.split_side_conjuncts(JoinSide::Left)?;
+        assert_eq!(left_expr.unwrap().to_string(), "1@1 > 5");
+        assert_eq!(
+            other.unwrap().expression().to_string(),
+            "0@0 < 2@2 AND 2@2 > 10"
+        );
+
+        // Conjuncts referencing both sides are never split off:
+        let mixed = JoinFilter::new(
+            binary(0, Operator::Lt, Arc::new(Column::new("2", 2))),
+            filter.column_indices().to_vec(),
+            filter.schema().clone(),
+        );
+        let (side_expr, other) = mixed.split_side_conjuncts(JoinSide::Right)?;
+        assert!(side_expr.is_none());
+        assert_eq!(other.unwrap().expression().to_string(), "0@0 < 2@2");
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_streamable() -> Result<()> {
+        let (left_batch, right_batch) = build_sides_record_batches(10, (4, 5))?;
+        let (left_schema, right_schema) = (left_batch.schema(), right_batch.schema());
+        let sort_expr = |name: &str, schema: &SchemaRef, descending: bool| {
+            Ok::<_, DataFusionError>(vec![PhysicalSortExpr {
+                expr: col(name, schema)?,
+                options: SortOptions {
+                    descending,
+                    nulls_first: true,
+                },
+            }])
+        };
+        let intermediate_schema = Schema::new(vec![
+            Field::new("left", DataType::Int32, true),
+            Field::new("right", DataType::Int32, true),
+        ]);
+        let join_filter = |expr, left_column, right_column| {
+            JoinFilter::new(
+                expr,
+                vec![
+                    ColumnIndex {
+                        index: left_schema.index_of(left_column).unwrap(),
+                        side: JoinSide::Left,
+                    },
+                    ColumnIndex {
+                        index: right_schema.index_of(right_column).unwrap(),
+                        side: JoinSide::Right,
+                    },
+                ],
+                intermediate_schema.clone(),
+            )
+        };
+        let left_col = Arc::new(Column::new("left", 0)) as PhysicalExprRef;
+        let right_col = Arc::new(Column::new("right", 1)) as PhysicalExprRef;
+        // left + 1 > right + 5 AND left + 3 < right + 10
+        let range_filter =
+            join_expr_tests_fixture_i32(0, left_col.clone(), right_col.clone());
+        // left + 1 > right + 5, which never bounds the right side buffer
+        let lower_bound_filter = binary(
+            binary(left_col, Operator::Plus, lit(1), &intermediate_schema)?,
+            Operator::Gt,
+            binary(right_col, Operator::Plus, lit(5), &intermediate_schema)?,
+            &intermediate_schema,
+        )?;
+
+        let cases = [
+            // Prunable range filter on ascending inputs
+            (
+                Some(join_filter(range_filter.clone(), "la1", "ra1")),
+                "la1",
+                "ra1",
+                false,
+                true,
+            ),
+            // Prunable range filter on descending inputs
+            (
+                Some(join_filter(range_filter.clone(), "la1_des", "ra1_des")),
+                "la1_des",
+                "ra1_des",
+                true,
+                true,
+            ),
+            // Filter bounding one side only
+            (
+                Some(join_filter(lower_bound_filter, "la1", "ra1")),
+                "la1",
+                "ra1",
+                false,
+                false,
+            ),
+            // Filter not referring to the orderings
+            (
+                Some(join_filter(range_filter, "la1", "ra1")),
+                "lb1",
+                "rb1",
+                false,
+                false,
+            ),
+            // No filter
+            (None, "la1", "ra1", false, false),
+        ];
+        for (filter, left_column, right_column, descending, expected) in cases {
+            let left_order = sort_expr(left_column, &left_schema, descending)?;
+            let right_order = sort_expr(right_column, &right_schema, descending)?;
+            assert_eq!(
+                is_streamable(
+                    &JoinType::Inner,
+                    filter.as_ref(),
+                    &left_schema,
+                    &right_schema,
+                    Some(&left_order),
+                    Some(&right_order),
+                )?,
+                expected,
+                "{left_column} and {right_column}"
+            );
+        }
+        // Filters not bounding either side, on any ordering
+        for expr_id in 0..2 {
+            let expr = join_expr_tests_fixture_non_prunable(
+                expr_id,
+                Arc::new(Column::new("left", 0)),
+                Arc::new(Column::new("right", 1)),
+                &intermediate_schema,
+            )?;
+            for (left_column, right_column, descending) in
+                [("la1", "ra1", false), ("la1_des", "ra1_des", true)]
+            {
+                let left_order = sort_expr(left_column, &left_schema, descending)?;
+                let right_order = sort_expr(right_column, &right_schema, descending)?;
+                assert!(
+                    !is_streamable(
+                        &JoinType::Inner,
+                        Some(&join_filter(expr.clone(), left_column, right_column)),
+                        &left_schema,
+                        &right_schema,
+                        Some(&left_order),
+                        Some(&right_order),
+                    )?,
+                    "{expr}"
+                );
+            }
+        }
+        // Unsorted inputs
+        let filter = join_filter(
+            join_expr_tests_fixture_i32(
+                0,
+                Arc::new(Column::new("left", 0)),
+                Arc::new(Column::new("right", 1)),
+            ),
+            "la1",
+            "ra1",
+        );
+        assert!(!is_streamable(
+            &JoinType::Full,
+            Some(&filter),
+            &left_schema,
+            &right_schema,
+            None,
+            None,
+        )?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonicalize_join_on() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+            Field::new("c", DataType::Int32, false),
+        ]));
+        let col =
+            |name: &str, index| Arc::new(Column::new(name, index)) as PhysicalExprRef;
+        let on: JoinOn = vec![
+            (col("a", 0), col("a", 0)),
+            (col("b", 1), col("b", 1)),
+            (col("a", 0), col("a", 0)),
+            (col("b", 1), col("c", 2)),
+            (col("c", 2), col("c", 2)),
+        ];
+        let to_string = |on: &JoinOn| {
+            on.iter()
+                .map(|(left, right)| format!("{left}={right}"))
+                .collect::<Vec<_>>()
+        };
+
+        // Without known equivalences, only the exact duplicate is removed:
+        let left_eq = EquivalenceProperties::new(schema.clone());
+        let right_eq = EquivalenceProperties::new(schema.clone());
+        let canonical = canonicalize_join_on(on.clone(), &left_eq, &right_eq);
+        assert_eq!(
+            to_string(&canonical),
+            ["a@0=a@0", "b@1=b@1", "b@1=c@2", "c@2=c@2"]
+        );
+
+        // With b = c on the right side, b=c is implied by b=b. The pair c=c is
+        // kept, as c and b are not known to be equal on the left side:
+        let mut right_eq = EquivalenceProperties::new(schema.clone());
+        right_eq.add_equal_conditions(&col("b", 1), &col("c", 2));
+        let canonical = canonicalize_join_on(on.clone(), &left_eq, &right_eq);
+        assert_eq!(to_string(&canonical), ["a@0=a@0", "b@1=b@1", "c@2=c@2"]);
+
+        // With b = c on both sides, c=c is implied by b=b as well:
+        let mut left_eq = EquivalenceProperties::new(schema);
+        left_eq.add_equal_conditions(&col("b", 1), &col("c", 2));
+        let canonical = canonicalize_join_on(on, &left_eq, &right_eq);
+        assert_eq!(to_string(&canonical), ["a@0=a@0", "b@1=b@1"]);
+    }
+
+    #[test]
+    fn test_match_bitmap() -> Result<()> {
+        // Spans three full 64-bit words and a remainder:
+        let mut bitmap = MatchBitmap::new(200);
+        assert_eq!(bitmap.len(), 200);
+        assert_eq!(MatchBitmap::size(200), 25);
+        assert_eq!(bitmap.matched_indices().len(), 0);
+        assert_eq!(bitmap.unmatched_indices().len(), 200);
+
+        bitmap.mark_matched(&UInt64Array::from(vec![Some(3), None, Some(64), Some(3)]));
+        let mut other = MatchBitmap::new(200);
+        other.mark_matched(&UInt64Array::from(vec![0, 130, 199]));
+        bitmap.merge(&other)?;
+
+        assert!(bitmap.is_matched(64));
+        assert!(!bitmap.is_matched(65));
+        assert_eq!(
+            bitmap.matched_indices(),
+            UInt64Array::from(vec![0, 3, 64, 130, 199])
+        );
+        let unmatched = bitmap.unmatched_indices();
+        assert_eq!(unmatched.len(), 195);
+        assert!(unmatched
+            .values()
+            .iter()
+            .all(|idx| !bitmap.is_matched(*idx as usize)));
+
+        assert!(bitmap.merge(&MatchBitmap::new(10)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_estimated_ipc_size() -> Result<()> {
+        use arrow::array::ListArray;
+        use arrow::ipc::writer::{DictionaryTracker, IpcDataGenerator, IpcWriteOptions};
+
+        let (batch, _) = build_sides_record_batches(100, (4, 5))?;
+        let strings = Arc::new(StringArray::from_iter(
+            (0..100).map(|i| (i % 3 != 0).then(|| "x".repeat(i))),
+        )) as ArrayRef;
+        let lists = Arc::new(ListArray::from_iter_primitive::<Int32Type, _, _>(
+            (0..100).map(|i| (i % 5 != 0).then(|| (0..i).map(Some).collect::<Vec<_>>())),
+        )) as ArrayRef;
+        let mut columns = batch.columns().to_vec();
+        columns.extend([strings, lists]);
+        let mut fields = batch.schema().fields().to_vec();
+        fields.push(Arc::new(Field::new("s", DataType::Utf8, true)));
+        fields.push(Arc::new(Field::new(
+            "l",
+            DataType::List(Arc::new(Field::new("item", DataType::Int32, true))),
+            true,
+        )));
+        let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?;
+
+        // The estimate bounds the actual size of the IPC message, including
+        // its continuation marker and length prefix:
+        let ipc_size = |batch: &RecordBatch| -> Result<usize> {
+            let (_, encoded) = IpcDataGenerator::default().encoded_batch(
+                batch,
+                &mut DictionaryTracker::new(false),
+                &IpcWriteOptions::default(),
+            )?;
+            Ok(8 + encoded.ipc_message.len() + encoded.arrow_data.len())
+        };
+        for batch in [batch.clone(), batch.slice(10, 50), batch.slice(0, 0)] {
+            let estimate = estimated_ipc_size(&batch);
+            assert!(ipc_size(&batch)? <= estimate);
+        }
+        // The estimate is not overly pessimistic for freshly built batches:
+        assert!(estimated_ipc_size(&batch) < 2 * ipc_size(&batch)?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_merge_partition_outputs() -> Result<()> {
+        let (left, right) = build_sides_record_batches(30, (4, 5))?;
+        let batches = split_record_batches(&left, 8)?;
+        let partitions = vec![batches[..2].to_vec(), vec![], batches[2..].to_vec()];
+
+        let stream = merge_partition_outputs(left.schema(), partitions);
+        assert_eq!(stream.schema(), left.schema());
+        let merged = crate::common::collect(stream).await?;
+        assert_eq!(merged, batches);
+        // Batches are passed through without copying their data:
+        for (merged, batch) in merged.iter().zip(&batches) {
+            assert!(merged
+                .columns()
+                .iter()
+                .zip(batch.columns())
+                .all(|(a, b)| Arc::ptr_eq(a, b)));
+        }
+
+        let stream = merge_partition_outputs(left.schema(), vec![batches, vec![right]]);
+        let err = crate::common::collect(stream).await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Partition outputs to merge must share the schema"));
+        Ok(())
+    }
+    #[tokio::test]
+    async fn test_peekable_batch_stream() -> Result<()> {
+        let (left, _) = build_sides_record_batches(30, (4, 5))?;
+        let batches = split_record_batches(&left, 8)?;
+
+        let stream = merge_partition_outputs(left.schema(), vec![batches.clone()]);
+        let mut stream = PeekableBatchStream::new(stream);
+        assert_eq!(stream.schema(), left.schema());
+        // Peeking again returns the same batch, which is not consumed:
+        for _ in 0..2 {
+            let peeked = stream.peek().await.unwrap().as_ref().unwrap();
+            assert_eq!(peeked, &batches[0]);
+        }
+        assert_eq!(stream.next().await.unwrap()?, batches[0]);
+        assert_eq!(stream.peek().await.unwrap().as_ref().unwrap(), &batches[1]);
+        // No batch is lost or duplicated:
+        let mut collected = vec![];
+        while let Some(batch) = stream.next().await {
+            collected.push(batch?);
+        }
+        assert_eq!(collected, batches[1..]);
+        // The end of the stream is peeked, and returned, repeatedly:
+        assert!(stream.peek().await.is_none());
+        assert!(stream.next().await.is_none());
+        assert!(stream.peek().await.is_none());
+
+        // Errors are peeked and returned in place:
+        let items = vec![
+            Ok(batches[0].clone()),
+            exec_err!("boom"),
+            Ok(batches[1].clone()),
+        ];
+        let stream = Box::pin(RecordBatchStreamAdapter::new(
+            left.schema(),
+            futures::stream::iter(items),
+        ));
+        let mut stream = PeekableBatchStream::new(stream);
+        assert_eq!(stream.next().await.unwrap()?, batches[0]);
+        let peeked = stream.peek().await.unwrap().as_ref().unwrap_err();
+        assert_contains!(peeked.to_string(), "boom");
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_contains!(err.to_string(), "boom");
+        assert_eq!(stream.next().await.unwrap()?, batches[1]);
+        assert!(stream.next().await.is_none());
+        Ok(())
+    }
 }
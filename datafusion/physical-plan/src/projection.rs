@@ -127,21 +127,19 @@ impl ProjectionExec {
 
         // Calculate output partitioning, which needs to respect aliases:
         let input_partition = input.output_partitioning();
-        let output_partitioning = if let Partitioning::Hash(exprs, part) = input_partition
-        {
-            let normalized_exprs = exprs
-                .iter()
-                .map(|expr| {
-                    input_eq_properties
-                        .project_expr(expr, projection_mapping)
-                        .unwrap_or_else(|| {
-                            Arc::new(UnKnownColumn::new(&expr.to_string()))
-                        })
-                })
-                .collect();
-            Partitioning::Hash(normalized_exprs, *part)
-        } else {
-            input_partition.clone()
+        let project_expr = |expr: &Arc<dyn PhysicalExpr>| {
+            input_eq_properties
+                .project_expr(expr, projection_mapping)
+                .unwrap_or_else(|| Arc::new(UnKnownColumn::new(&expr.to_string())))
+        };
+        let output_partitioning = match input_partition {
+            Partitioning::Hash(exprs, part) => {
+                Partitioning::Hash(exprs.iter().map(project_expr).collect(), *part)
+            }
+            Partitioning::Bucketed(expr, part) => {
+                Partitioning::Bucketed(project_expr(expr), *part)
+            }
+            _ => input_partition.clone(),
         };
 
         Ok(PlanProperties::new(
@@ -31,7 +31,7 @@ use super::{
 };
 use crate::common::transpose;
 use crate::hash_utils::create_hashes;
-use crate::metrics::BaselineMetrics;
+use crate::metrics::{BaselineMetrics, ScopedTimerGuard};
 use crate::repartition::distributor_channels::{
     channels, partition_aware_channels, DistributionReceiver, DistributionSender,
 };
@@ -39,10 +39,19 @@ use crate::sorts::streaming_merge;
 use crate::stream::RecordBatchStreamAdapter;
 use crate::{DisplayFormatType, ExecutionPlan, Partitioning, PlanProperties, Statistics};
 
-use arrow::array::{ArrayRef, UInt64Builder};
-use arrow::datatypes::SchemaRef;
+use arrow::array::{Array, ArrayAccessor, ArrayRef, FixedSizeBinaryArray, UInt64Builder};
+use arrow::datatypes::{
+    ArrowNativeType, DataType, Decimal128Type, Decimal256Type, SchemaRef, ToByteSlice,
+};
+use arrow::downcast_primitive_array;
 use arrow::record_batch::RecordBatch;
-use datafusion_common::{arrow_datafusion_err, not_impl_err, DataFusionError, Result};
+use datafusion_common::cast::{
+    as_boolean_array, as_generic_binary_array, as_generic_string_array, as_int64_array,
+    as_primitive_array, as_string_array,
+};
+use datafusion_common::{
+    arrow_datafusion_err, not_impl_err, plan_err, DataFusionError, Result,
+};
 use datafusion_common_runtime::SpawnedTask;
 use datafusion_execution::memory_pool::MemoryConsumer;
 use datafusion_execution::TaskContext;
@@ -82,6 +91,7 @@ impl RepartitionExecState {
     fn new(
         input: Arc<dyn ExecutionPlan>,
         partitioning: Partitioning,
+        partition_hash: PartitionHash,
         metrics: ExecutionPlanMetricsSet,
         preserve_order: bool,
         name: String,
@@ -137,6 +147,7 @@ impl RepartitionExecState {
                 i,
                 txs.clone(),
                 partitioning.clone(),
+                partition_hash,
                 r_metrics,
                 context.clone(),
             ));
@@ -173,6 +184,102 @@ impl RepartitionExecState {
 ///  (e.g. removing channels on completion) where the overhead of `await` is not warranted.
 type LazyState = Arc<tokio::sync::OnceCell<Mutex<RepartitionExecState>>>;
 
+/// Hash function assigning the rows of a [`Partitioning::Hash`] scheme to
+/// partitions, see [`RepartitionExec::with_partition_hash`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PartitionHash {
+    /// The hash used by joins and aggregations. It is fast, but the partition
+    /// of a key may differ between builds and platforms.
+    #[default]
+    Default,
+    /// 64-bit FNV-1a over the key values of every row, starting from the FNV
+    /// offset basis xor-ed with `seed`.
+    ///
+    /// For every key column, in order, a null contributes the byte `0`, and any
+    /// other value the byte `1` followed by its bytes: the little-endian Arrow
+    /// representation of fixed-width values, or the little-endian `u64` length
+    /// followed by the contents of strings and binaries. Dictionaries are hashed
+    /// like their values. The row is assigned to partition `hash % partitions`,
+    /// so the same key lands in the same partition across runs and machines.
+    Fnv1a {
+        /// Seed mixed into the initial hash of every row
+        seed: u64,
+    },
+}
+
+impl std::fmt::Display for PartitionHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PartitionHash::Default => write!(f, "default"),
+            PartitionHash::Fnv1a { seed } => write!(f, "fnv1a(seed={seed})"),
+        }
+    }
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Feeds the values of `array` into the FNV-1a `hashes` of its rows, using
+/// `hash_value` to hash the bytes of the valid ones
+fn fnv1a_hash_values<A: ArrayAccessor>(
+    array: A,
+    hashes: &mut [u64],
+    hash_value: impl Fn(u64, A::Item) -> u64,
+) {
+    for (index, hash) in hashes.iter_mut().enumerate() {
+        *hash = if array.is_null(index) {
+            fnv1a(*hash, &[0])
+        } else {
+            hash_value(fnv1a(*hash, &[1]), array.value(index))
+        };
+    }
+}
+
+fn fnv1a_hash_native<T: ArrowNativeType>(hash: u64, value: T) -> u64 {
+    fnv1a(hash, [value].to_byte_slice())
+}
+
+fn fnv1a_hash_bytes(hash: u64, bytes: &[u8]) -> u64 {
+    fnv1a(fnv1a(hash, &(bytes.len() as u64).to_le_bytes()), bytes)
+}
+
+/// Feeds the key values of `array` into the FNV-1a `hashes` of its rows, see
+/// [`PartitionHash::Fnv1a`]
+fn fnv1a_hash_array(array: &dyn Array, hashes: &mut [u64]) -> Result<()> {
+    downcast_primitive_array! {
+        array => fnv1a_hash_values(array, hashes, fnv1a_hash_native),
+        DataType::Boolean => fnv1a_hash_values(as_boolean_array(array)?, hashes, |hash, value| fnv1a(hash, &[value as u8])),
+        DataType::Utf8 => fnv1a_hash_values(as_string_array(array)?, hashes, |hash, value| fnv1a_hash_bytes(hash, value.as_bytes())),
+        DataType::LargeUtf8 => fnv1a_hash_values(as_generic_string_array::<i64>(array)?, hashes, |hash, value| fnv1a_hash_bytes(hash, value.as_bytes())),
+        DataType::Binary => fnv1a_hash_values(as_generic_binary_array::<i32>(array)?, hashes, fnv1a_hash_bytes),
+        DataType::LargeBinary => fnv1a_hash_values(as_generic_binary_array::<i64>(array)?, hashes, fnv1a_hash_bytes),
+        DataType::FixedSizeBinary(_) => {
+            let array: &FixedSizeBinaryArray = array.as_any().downcast_ref().unwrap();
+            fnv1a_hash_values(array, hashes, fnv1a_hash_bytes)
+        }
+        DataType::Decimal128(_, _) => {
+            let array = as_primitive_array::<Decimal128Type>(array)?;
+            fnv1a_hash_values(array, hashes, fnv1a_hash_native)
+        }
+        DataType::Decimal256(_, _) => {
+            let array = as_primitive_array::<Decimal256Type>(array)?;
+            fnv1a_hash_values(array, hashes, fnv1a_hash_native)
+        }
+        DataType::Dictionary(_, value_type) => {
+            let values = arrow::compute::cast(array, value_type)?;
+            fnv1a_hash_array(values.as_ref(), hashes)?
+        }
+        other => return not_impl_err!("Unsupported data type in FNV-1a partition hash: {other}"),
+    }
+    Ok(())
+}
+
 /// A utility that can be used to partition batches based on [`Partitioning`]
 pub struct BatchPartitioner {
     state: BatchPartitionerState,
@@ -182,6 +289,7 @@ pub struct BatchPartitioner {
 enum BatchPartitionerState {
     Hash {
         random_state: ahash::RandomState,
+        partition_hash: PartitionHash,
         exprs: Vec<Arc<dyn PhysicalExpr>>,
         num_partitions: usize,
         hash_buffer: Vec<u64>,
@@ -190,6 +298,10 @@ enum BatchPartitionerState {
         num_partitions: usize,
         next_idx: usize,
     },
+    Bucketed {
+        expr: Arc<dyn PhysicalExpr>,
+        num_partitions: usize,
+    },
 }
 
 impl BatchPartitioner {
@@ -209,14 +321,34 @@ impl BatchPartitioner {
                 num_partitions,
                 // Use fixed random hash
                 random_state: ahash::RandomState::with_seeds(0, 0, 0, 0),
+                partition_hash: PartitionHash::Default,
                 hash_buffer: vec![],
             },
+            Partitioning::Bucketed(expr, num_partitions) => {
+                BatchPartitionerState::Bucketed {
+                    expr,
+                    num_partitions,
+                }
+            }
             other => return not_impl_err!("Unsupported repartitioning scheme {other:?}"),
         };
 
         Ok(Self { state, timer })
     }
 
+    /// Assigns the rows of a [`Partitioning::Hash`] scheme to partitions using
+    /// `partition_hash`. Has no effect on other partitioning schemes.
+    pub fn with_partition_hash(mut self, partition_hash: PartitionHash) -> Self {
+        if let BatchPartitionerState::Hash {
+            partition_hash: current,
+            ..
+        } = &mut self.state
+        {
+            *current = partition_hash;
+        }
+        self
+    }
+
     /// Partition the provided [`RecordBatch`] into one or more partitioned [`RecordBatch`]
     /// based on the [`Partitioning`] specified on construction
     ///
@@ -257,6 +389,7 @@ impl BatchPartitioner {
                 }
                 BatchPartitionerState::Hash {
                     random_state,
+                    partition_hash,
                     exprs,
                     num_partitions: partitions,
                     hash_buffer,
@@ -271,7 +404,17 @@ impl BatchPartitioner {
                     hash_buffer.clear();
                     hash_buffer.resize(batch.num_rows(), 0);
 
-                    create_hashes(&arrays, random_state, hash_buffer)?;
+                    match partition_hash {
+                        PartitionHash::Default => {
+                            create_hashes(&arrays, random_state, hash_buffer)?;
+                        }
+                        PartitionHash::Fnv1a { seed } => {
+                            hash_buffer.fill(FNV_OFFSET_BASIS ^ *seed);
+                            for array in &arrays {
+                                fnv1a_hash_array(array.as_ref(), hash_buffer)?;
+                            }
+                        }
+                    }
 
                     let mut indices: Vec<_> = (0..*partitions)
                         .map(|_| UInt64Builder::with_capacity(batch.num_rows()))
@@ -282,45 +425,77 @@ impl BatchPartitioner {
                             .append_value(index as u64);
                     }
 
-                    let it = indices
-                        .into_iter()
-                        .enumerate()
-                        .filter_map(|(partition, mut indices)| {
-                            let indices = indices.finish();
-                            (!indices.is_empty()).then_some((partition, indices))
-                        })
-                        .map(move |(partition, indices)| {
-                            // Produce batches based on indices
-                            let columns = batch
-                                .columns()
-                                .iter()
-                                .map(|c| {
-                                    arrow::compute::take(c.as_ref(), &indices, None)
-                                        .map_err(|e| arrow_datafusion_err!(e))
-                                })
-                                .collect::<Result<Vec<ArrayRef>>>()?;
-
-                            let batch =
-                                RecordBatch::try_new(batch.schema(), columns).unwrap();
-
-                            // bind timer so it drops w/ this iterator
-                            let _ = &timer;
-
-                            Ok((partition, batch))
+                    Box::new(Self::take_partitions(batch, indices, timer))
+                }
+                BatchPartitionerState::Bucketed {
+                    expr,
+                    num_partitions: partitions,
+                } => {
+                    let timer = self.timer.timer();
+
+                    let buckets = expr.evaluate(&batch)?.into_array(batch.num_rows())?;
+                    let buckets = arrow::compute::cast(&buckets, &DataType::Int64)?;
+                    let buckets = as_int64_array(&buckets)?;
+
+                    let mut indices: Vec<_> = (0..*partitions)
+                        .map(|_| UInt64Builder::with_capacity(batch.num_rows()))
+                        .collect();
+
+                    // Rows without a bucket go to the first partition
+                    for (index, bucket) in buckets.iter().enumerate() {
+                        let partition = bucket.map_or(0, |bucket| {
+                            Partitioning::bucket_partition(bucket, *partitions)
                         });
+                        indices[partition].append_value(index as u64);
+                    }
 
-                    Box::new(it)
+                    Box::new(Self::take_partitions(batch, indices, timer))
                 }
             };
 
         Ok(it)
     }
 
+    /// Produces the non-empty batches of each partition from the row `indices`
+    /// collected for every partition
+    fn take_partitions<'a>(
+        batch: RecordBatch,
+        indices: Vec<UInt64Builder>,
+        timer: ScopedTimerGuard<'a>,
+    ) -> impl Iterator<Item = Result<(usize, RecordBatch)>> + Send + 'a {
+        indices
+            .into_iter()
+            .enumerate()
+            .filter_map(|(partition, mut indices)| {
+                let indices = indices.finish();
+                (!indices.is_empty()).then_some((partition, indices))
+            })
+            .map(move |(partition, indices)| {
+                // Produce batches based on indices
+                let columns = batch
+                    .columns()
+                    .iter()
+                    .map(|c| {
+                        arrow::compute::take(c.as_ref(), &indices, None)
+                            .map_err(|e| arrow_datafusion_err!(e))
+                    })
+                    .collect::<Result<Vec<ArrayRef>>>()?;
+
+                let batch = RecordBatch::try_new(batch.schema(), columns).unwrap();
+
+                // bind timer so it drops w/ this iterator
+                let _ = &timer;
+
+                Ok((partition, batch))
+            })
+    }
+
     // return the number of output partitions
     fn num_partitions(&self) -> usize {
         match self.state {
             BatchPartitionerState::RoundRobin { num_partitions, .. } => num_partitions,
             BatchPartitionerState::Hash { num_partitions, .. } => num_partitions,
+            BatchPartitionerState::Bucketed { num_partitions, .. } => num_partitions,
         }
     }
 }
@@ -400,6 +575,9 @@ pub struct RepartitionExec {
     /// Boolean flag to decide whether to preserve ordering. If true means
     /// `SortPreservingRepartitionExec`, false means `RepartitionExec`.
     preserve_order: bool,
+    /// Hash function assigning the rows of a [`Partitioning::Hash`] scheme to
+    /// partitions, see [`Self::with_partition_hash`].
+    partition_hash: PartitionHash,
     /// Cache holding plan properties like equivalences, output partitioning etc.
     cache: PlanProperties,
 }
@@ -466,6 +644,12 @@ impl RepartitionExec {
         self.preserve_order
     }
 
+    /// Hash function assigning the rows of a [`Partitioning::Hash`] scheme to
+    /// partitions
+    pub fn partition_hash(&self) -> PartitionHash {
+        self.partition_hash
+    }
+
     /// Get name used to display this Exec
     pub fn name(&self) -> &str {
         "RepartitionExec"
@@ -492,6 +676,10 @@ impl DisplayAs for RepartitionExec {
                     write!(f, ", preserve_order=true")?;
                 }
 
+                if self.partition_hash != PartitionHash::Default {
+                    write!(f, ", partition_hash={}", self.partition_hash)?;
+                }
+
                 if let Some(sort_exprs) = self.sort_exprs() {
                     write!(
                         f,
@@ -532,11 +720,17 @@ impl ExecutionPlan for RepartitionExec {
         if self.preserve_order {
             repartition = repartition.with_preserve_order();
         }
+        if self.partition_hash != PartitionHash::Default {
+            repartition = repartition.with_partition_hash(self.partition_hash)?;
+        }
         Ok(Arc::new(repartition))
     }
 
     fn benefits_from_input_partitioning(&self) -> Vec<bool> {
-        vec![matches!(self.partitioning, Partitioning::Hash(_, _))]
+        vec![matches!(
+            self.partitioning,
+            Partitioning::Hash(_, _) | Partitioning::Bucketed(_, _)
+        )]
     }
 
     fn maintains_input_order(&self) -> Vec<bool> {
@@ -559,6 +753,7 @@ impl ExecutionPlan for RepartitionExec {
         let partitioning = self.partitioning.clone();
         let metrics = self.metrics.clone();
         let preserve_order = self.preserve_order;
+        let partition_hash = self.partition_hash;
         let name = self.name().to_owned();
         let schema = self.schema();
         let schema_captured = Arc::clone(&schema);
@@ -578,6 +773,7 @@ impl ExecutionPlan for RepartitionExec {
                     Mutex::new(RepartitionExecState::new(
                         input_captured,
                         partitioning,
+                        partition_hash,
                         metrics_captured,
                         preserve_order,
                         name_captured,
@@ -679,6 +875,7 @@ impl RepartitionExec {
             state: Default::default(),
             metrics: ExecutionPlanMetricsSet::new(),
             preserve_order,
+            partition_hash: PartitionHash::Default,
             cache,
         })
     }
@@ -739,6 +936,35 @@ impl RepartitionExec {
         self
     }
 
+    /// Assigns the rows of a [`Partitioning::Hash`] scheme to partitions using
+    /// `partition_hash`.
+    ///
+    /// With [`PartitionHash::Fnv1a`] the same key lands in the same partition
+    /// across runs and machines, so that independently repartitioned inputs of
+    /// a distributed join are co-partitioned. All inputs that are expected to
+    /// be co-partitioned must use the same hash function and seed.
+    ///
+    /// As [`Partitioning::Hash`] does not record the hash function, and plans
+    /// compare it assuming the default one, the output partitioning is
+    /// reported as [`Partitioning::UnknownPartitioning`] unless the hash is
+    /// [`PartitionHash::Default`].
+    pub fn with_partition_hash(mut self, partition_hash: PartitionHash) -> Result<Self> {
+        let Partitioning::Hash(_, partition_count) = self.partitioning else {
+            return plan_err!(
+                "Partition hash requires hash partitioning, got {}",
+                self.partitioning
+            );
+        };
+        self.partition_hash = partition_hash;
+        let output_partitioning = if partition_hash == PartitionHash::Default {
+            self.partitioning.clone()
+        } else {
+            Partitioning::UnknownPartitioning(partition_count)
+        };
+        self.cache = self.cache.with_partitioning(output_partitioning);
+        Ok(self)
+    }
+
     /// Return the sort expressions that are used to merge
     fn sort_exprs(&self) -> Option<&[PhysicalSortExpr]> {
         if self.preserve_order {
@@ -760,11 +986,13 @@ impl RepartitionExec {
             (DistributionSender<MaybeBatch>, SharedMemoryReservation),
         >,
         partitioning: Partitioning,
+        partition_hash: PartitionHash,
         metrics: RepartitionMetrics,
         context: Arc<TaskContext>,
     ) -> Result<()> {
         let mut partitioner =
-            BatchPartitioner::try_new(partitioning, metrics.repartition_time.clone())?;
+            BatchPartitioner::try_new(partitioning, metrics.repartition_time.clone())?
+                .with_partition_hash(partition_hash);
 
         // execute the child operator
         let timer = metrics.fetch_time.timer();
@@ -999,6 +1227,8 @@ mod tests {
     use std::collections::HashSet;
 
     use super::*;
+    use crate::expressions::{lit, BinaryExpr, CastExpr};
+    use crate::joins::test_utils::{build_sides_record_batches, split_record_batches};
     use crate::{
         test::{
             assert_is_pending,
@@ -1016,6 +1246,7 @@ mod tests {
     use datafusion_common::cast::as_string_array;
     use datafusion_common::{assert_batches_sorted_eq, exec_err};
     use datafusion_execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+    use datafusion_expr::Operator;
 
     use futures::FutureExt;
     use tokio::task::JoinSet;
@@ -1103,6 +1334,123 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn hash_partition_with_fixed_seed() -> Result<()> {
+        let schema = test_schema();
+        let input = Arc::new(MemoryExec::try_new(
+            &[create_vec_batches(2), create_vec_batches(3)],
+            schema.clone(),
+            None,
+        )?);
+        let partitioning = Partitioning::Hash(vec![col("c0", &schema)?], 3);
+        let task_ctx = Arc::new(TaskContext::default());
+
+        let err =
+            RepartitionExec::try_new(input.clone(), Partitioning::RoundRobinBatch(3))?
+                .with_partition_hash(PartitionHash::Fnv1a { seed: 42 })
+                .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Partition hash requires hash partitioning"));
+
+        for _ in 0..3 {
+            let exec = RepartitionExec::try_new(input.clone(), partitioning.clone())?
+                .with_partition_hash(PartitionHash::Fnv1a { seed: 42 })?;
+            // Not to be taken for co-partitioned with a default hashed input
+            assert!(matches!(
+                exec.properties().output_partitioning(),
+                Partitioning::UnknownPartitioning(3)
+            ));
+            let mut assignment = vec![];
+            for partition in 0..3 {
+                let stream = exec.execute(partition, task_ctx.clone())?;
+                for batch in crate::common::collect(stream).await? {
+                    let keys = batch.column(0).as_any().downcast_ref::<UInt32Array>();
+                    for key in keys.unwrap().values() {
+                        assignment.push((*key, partition));
+                    }
+                }
+            }
+            assignment.sort_unstable();
+            assignment.dedup();
+            // Every key lands in the same, documented, partition in every run
+            assert_eq!(
+                assignment,
+                vec![
+                    (1, 2),
+                    (2, 1),
+                    (3, 2),
+                    (4, 0),
+                    (5, 2),
+                    (6, 0),
+                    (7, 2),
+                    (8, 2)
+                ]
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Returns the 10ms bucket of the `lt1` timestamps of the left side of
+    /// [`build_sides_record_batches`], along with a batch of 100 rows
+    fn time_buckets() -> Result<(RecordBatch, Arc<dyn PhysicalExpr>)> {
+        let (left, _) = build_sides_record_batches(100, (10, 11))?;
+        let bucket = Arc::new(BinaryExpr::new(
+            Arc::new(CastExpr::new(
+                col("lt1", &left.schema())?,
+                DataType::Int64,
+                None,
+            )),
+            Operator::Divide,
+            lit(10i64),
+        ));
+        Ok((left, bucket))
+    }
+
+    fn partition_buckets(
+        batches: &[RecordBatch],
+        bucket: &Arc<dyn PhysicalExpr>,
+    ) -> Result<Vec<i64>> {
+        let mut buckets = vec![];
+        for batch in batches {
+            let values = bucket.evaluate(batch)?.into_array(batch.num_rows())?;
+            buckets.extend(as_int64_array(&values)?.values());
+        }
+        Ok(buckets)
+    }
+
+    #[tokio::test]
+    async fn many_to_many_bucketed_partition() -> Result<()> {
+        let (batch, bucket) = time_buckets()?;
+        let partitions = vec![
+            split_record_batches(&batch.slice(0, 50), 20)?,
+            split_record_batches(&batch.slice(50, 50), 20)?,
+        ];
+
+        let output_partitions = repartition(
+            &batch.schema(),
+            partitions,
+            Partitioning::Bucketed(bucket.clone(), 4),
+        )
+        .await?;
+
+        assert_eq!(4, output_partitions.len());
+        let mut total_rows = 0;
+        for (partition, batches) in output_partitions.iter().enumerate() {
+            let buckets = partition_buckets(batches, &bucket)?;
+            // Every 10ms bucket of the 100 rows ends up in a single partition:
+            assert_eq!(buckets.len(), 30 - 10 * (partition / 2));
+            assert!(buckets
+                .iter()
+                .all(|b| Partitioning::bucket_partition(*b, 4) == partition));
+            total_rows += buckets.len();
+        }
+        assert_eq!(total_rows, 100);
+
+        Ok(())
+    }
+
     fn test_schema() -> Arc<Schema> {
         Arc::new(Schema::new(vec![Field::new("c0", DataType::UInt32, false)]))
     }
@@ -22,6 +22,7 @@ use std::{
     pin::Pin,
     sync::{Arc, Weak},
     task::{Context, Poll},
+    time::Duration,
 };
 
 use crate::stream::{RecordBatchReceiverStream, RecordBatchStreamAdapter};
@@ -121,6 +122,8 @@ pub struct MockExec {
     /// if true (the default), sends data using a separate task to ensure the
     /// batches are not available without this stream yielding first
     use_task: bool,
+    /// delay before sending each batch, see [`Self::with_batch_delay`]
+    batch_delay: Option<Duration>,
     cache: PlanProperties,
 }
 
@@ -138,6 +141,7 @@ impl MockExec {
             data,
             schema,
             use_task: true,
+            batch_delay: None,
             cache,
         }
     }
@@ -150,6 +154,13 @@ impl MockExec {
         self
     }
 
+    /// Waits for `batch_delay` before sending each batch, to simulate a
+    /// slow source. Only applies if `use_task` is true
+    pub fn with_batch_delay(mut self, batch_delay: Duration) -> Self {
+        self.batch_delay = Some(batch_delay);
+        self
+    }
+
     /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
     fn compute_properties(schema: SchemaRef) -> PlanProperties {
         let eq_properties = EquivalenceProperties::new(schema);
@@ -220,8 +231,12 @@ impl ExecutionPlan for MockExec {
             // the batches are not available without the stream
             // yielding).
             let tx = builder.tx();
+            let batch_delay = self.batch_delay;
             builder.spawn(async move {
                 for batch in data {
+                    if let Some(batch_delay) = batch_delay {
+                        tokio::time::sleep(batch_delay).await;
+                    }
                     println!("Sending batch via delayed stream");
                     if let Err(e) = tx.send(batch).await {
                         println!("ERROR batch via delayed stream: {e}");
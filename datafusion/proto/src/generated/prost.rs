@@ -2664,10 +2664,18 @@ pub struct PhysicalHashRepartition {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhysicalBucketedRepartition {
+    #[prost(message, optional, tag = "1")]
+    pub bucket_expr: ::core::option::Option<PhysicalExprNode>,
+    #[prost(uint64, tag = "2")]
+    pub partition_count: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RepartitionExecNode {
     #[prost(message, optional, boxed, tag = "1")]
     pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
-    #[prost(oneof = "repartition_exec_node::PartitionMethod", tags = "2, 3, 4")]
+    #[prost(oneof = "repartition_exec_node::PartitionMethod", tags = "2, 3, 4, 5")]
     pub partition_method: ::core::option::Option<repartition_exec_node::PartitionMethod>,
 }
 /// Nested message and enum types in `RepartitionExecNode`.
@@ -2681,6 +2689,8 @@ pub mod repartition_exec_node {
         Hash(super::PhysicalHashRepartition),
         #[prost(uint64, tag = "4")]
         Unknown(u64),
+        #[prost(message, tag = "5")]
+        Bucketed(super::PhysicalBucketedRepartition),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2776,6 +2786,8 @@ pub enum JoinType {
     Leftanti = 5,
     Rightsemi = 6,
     Rightanti = 7,
+    Leftmark = 8,
+    Rightmark = 9,
 }
 impl JoinType {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -2792,6 +2804,8 @@ impl JoinType {
             JoinType::Leftanti => "LEFTANTI",
             JoinType::Rightsemi => "RIGHTSEMI",
             JoinType::Rightanti => "RIGHTANTI",
+            JoinType::Leftmark => "LEFTMARK",
+            JoinType::Rightmark => "RIGHTMARK",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -2805,6 +2819,8 @@ impl JoinType {
             "LEFTANTI" => Some(Self::Leftanti),
             "RIGHTSEMI" => Some(Self::Rightsemi),
             "RIGHTANTI" => Some(Self::Rightanti),
+            "LEFTMARK" => Some(Self::Leftmark),
+            "RIGHTMARK" => Some(Self::Rightmark),
             _ => None,
         }
     }
@@ -299,6 +299,34 @@ impl AsExecutionPlan for PhysicalPlanNode {
                             ),
                         )?))
                     }
+                    Some(PartitionMethod::Bucketed(ref bucketed_part)) => {
+                        let expr = bucketed_part
+                            .bucket_expr
+                            .as_ref()
+                            .map(|expr| {
+                                parse_physical_expr(
+                                    expr,
+                                    registry,
+                                    input.schema().as_ref(),
+                                    extension_codec,
+                                )
+                            })
+                            .transpose()?
+                            .ok_or_else(|| {
+                                DataFusionError::Internal(
+                                    "bucket_expr in PhysicalBucketedRepartition is missing."
+                                        .to_owned(),
+                                )
+                            })?;
+
+                        Ok(Arc::new(RepartitionExec::try_new(
+                            input,
+                            Partitioning::Bucketed(
+                                expr,
+                                bucketed_part.partition_count.try_into().unwrap(),
+                            ),
+                        )?))
+                    }
                     _ => internal_err!("Invalid partitioning scheme"),
                 }
             }
@@ -1633,6 +1661,15 @@ impl AsExecutionPlan for PhysicalPlanNode {
                 Partitioning::RoundRobinBatch(partition_count) => {
                     PartitionMethod::RoundRobin(*partition_count as u64)
                 }
+                Partitioning::Bucketed(expr, partition_count) => {
+                    PartitionMethod::Bucketed(protobuf::PhysicalBucketedRepartition {
+                        bucket_expr: Some(serialize_physical_expr(
+                            expr.clone(),
+                            extension_codec,
+                        )?),
+                        partition_count: *partition_count as u64,
+                    })
+                }
                 Partitioning::UnknownPartitioning(partition_count) => {
                     PartitionMethod::Unknown(*partition_count as u64)
                 }
@@ -225,6 +225,19 @@ fn roundtrip_hash_join() -> Result<()> {
             )?))?;
         }
     }
+    // Unless nulls are equal, mark joins need the whole build side
+    for join_type in &[JoinType::LeftMark, JoinType::RightMark] {
+        roundtrip_test(Arc::new(HashJoinExec::try_new(
+            Arc::new(EmptyExec::new(schema_left.clone())),
+            Arc::new(EmptyExec::new(schema_right.clone())),
+            on.clone(),
+            None,
+            join_type,
+            None,
+            PartitionMode::CollectLeft,
+            false,
+        )?))?;
+    }
     Ok(())
 }
 
@@ -1028,6 +1041,18 @@ fn roundtrip_union() -> Result<()> {
     roundtrip_test(Arc::new(union))
 }
 
+#[test]
+fn roundtrip_bucketed_repartition() -> Result<()> {
+    let field_a = Field::new("a", DataType::Int64, false);
+    let schema = Arc::new(Schema::new(vec![field_a]));
+    let bucket_expr = binary(col("a", &schema)?, Operator::Divide, lit(10i64), &schema)?;
+    let repartition = RepartitionExec::try_new(
+        Arc::new(EmptyExec::new(schema)),
+        Partitioning::Bucketed(bucket_expr, 4),
+    )?;
+    roundtrip_test(Arc::new(repartition))
+}
+
 #[test]
 fn roundtrip_interleave() -> Result<()> {
     let field_a = Field::new("col", DataType::Int64, false);
@@ -292,7 +292,7 @@ impl Unparser<'_> {
                 let ast_join = ast::Join {
                     relation: right_relation.build()?,
                     join_operator: self
-                        .join_operator_to_sql(join.join_type, join_constraint),
+                        .join_operator_to_sql(join.join_type, join_constraint)?,
                 };
                 let mut from = select.pop_from().unwrap();
                 from.push_join(ast_join);
@@ -365,8 +365,8 @@ impl Unparser<'_> {
         &self,
         join_type: JoinType,
         constraint: ast::JoinConstraint,
-    ) -> ast::JoinOperator {
-        match join_type {
+    ) -> Result<ast::JoinOperator> {
+        Ok(match join_type {
             JoinType::Inner => ast::JoinOperator::Inner(constraint),
             JoinType::Left => ast::JoinOperator::LeftOuter(constraint),
             JoinType::Right => ast::JoinOperator::RightOuter(constraint),
@@ -375,7 +375,10 @@ impl Unparser<'_> {
             JoinType::LeftSemi => ast::JoinOperator::LeftSemi(constraint),
             JoinType::RightAnti => ast::JoinOperator::RightAnti(constraint),
             JoinType::RightSemi => ast::JoinOperator::RightSemi(constraint),
-        }
+            JoinType::LeftMark | JoinType::RightMark => {
+                return not_impl_err!("Unsupported join type: {join_type}")
+            }
+        })
     }
 
     fn join_conditions_to_sql(
@@ -552,7 +552,10 @@ fn to_substrait_jointype(join_type: JoinType) -> join_rel::JoinType {
         JoinType::Full => join_rel::JoinType::Outer,
         JoinType::LeftAnti => join_rel::JoinType::Anti,
         JoinType::LeftSemi => join_rel::JoinType::Semi,
-        JoinType::RightAnti | JoinType::RightSemi => unimplemented!(),
+        JoinType::RightAnti
+        | JoinType::RightSemi
+        | JoinType::LeftMark
+        | JoinType::RightMark => unimplemented!(),
     }
 }
 